@@ -0,0 +1,73 @@
+//! Compares the memory and time of serializing a configuration with thousands of devices
+//! to a `String` and streaming it to a writer. Run with `cargo bench --bench to_writer`.
+
+use netplan_types::NetplanConfig;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fmt::Write;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+/// Counts the bytes allocated on the heap, and the peak since it was last reset.
+struct Counting;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let allocated = ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+        PEAK.fetch_max(allocated, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+const ITERATIONS: u32 = 20;
+
+/// The peak heap usage of `f` above what was allocated before, and its mean duration.
+fn measure(f: impl Fn()) -> (usize, f64) {
+    let base = ALLOCATED.load(Ordering::Relaxed);
+    PEAK.store(base, Ordering::Relaxed);
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    let elapsed = start.elapsed().as_secs_f64() / f64::from(ITERATIONS);
+    (PEAK.load(Ordering::Relaxed) - base, elapsed * 1000.0)
+}
+
+fn main() {
+    let mut yaml = String::from("network:\n  version: 2\n  ethernets:\n");
+    for id in 0..1000 {
+        writeln!(yaml, "    eth{id}: {{dhcp4: true, mtu: 9000}}").unwrap();
+    }
+    yaml.push_str("  vlans:\n");
+    for id in 1..=1000 {
+        writeln!(
+            yaml,
+            "    vlan{id}: {{id: {id}, link: eth0, addresses: [10.{}.{}.1/24]}}",
+            id / 256,
+            id % 256
+        )
+        .unwrap();
+    }
+    let config = NetplanConfig::from_yaml(&yaml).unwrap();
+
+    let (string_peak, string_time) = measure(|| {
+        std::hint::black_box(config.to_yaml());
+    });
+    let (writer_peak, writer_time) = measure(|| {
+        config.to_writer(std::io::sink()).unwrap();
+    });
+
+    println!("2000 devices, mean of {ITERATIONS} iterations");
+    println!("to_yaml:   peak {string_peak:>9} bytes, {string_time:.2} ms");
+    println!("to_writer: peak {writer_peak:>9} bytes, {writer_time:.2} ms");
+}
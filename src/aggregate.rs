@@ -0,0 +1,338 @@
+//! Turning existing interfaces into the members of a bond or bridge.
+
+use crate::merge::Merge;
+use crate::{
+    BondConfig, BondParameters, BridgeConfig, CommonProperties, CommonPropertiesAllDevices,
+    CommonPropertiesPhysicalDeviceType, DeviceKind, EthernetConfig, NetworkConfig,
+};
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+/// The error returned when creating a bond or bridge from existing interfaces fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AggregateError {
+    /// A device with the ID of the bond or bridge already exists.
+    AlreadyExists(String),
+    /// The member is defined as a kind of device which cannot be a member.
+    InvalidMember(String, DeviceKind),
+}
+
+impl Display for AggregateError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::AlreadyExists(name) => write!(f, "A device with ID '{name}' already exists"),
+            Self::InvalidMember(name, kind) => {
+                write!(f, "'{name}' is defined in {kind} and cannot be a member")
+            }
+        }
+    }
+}
+
+impl std::error::Error for AggregateError {}
+
+/// Move the IP configuration out of the common properties.
+fn take_addressing(common: &mut CommonPropertiesAllDevices) -> CommonPropertiesAllDevices {
+    CommonPropertiesAllDevices {
+        dhcp4: common.dhcp4.take(),
+        dhcp6: common.dhcp6.take(),
+        ipv6_privacy: common.ipv6_privacy.take(),
+        link_local: common.link_local.take(),
+        dhcp_identifier: common.dhcp_identifier.take(),
+        dhcp4_overrides: common.dhcp4_overrides.take(),
+        dhcp6_overrides: common.dhcp6_overrides.take(),
+        accept_ra: common.accept_ra.take(),
+        addresses: common.addresses.take(),
+        ipv6_address_generation: common.ipv6_address_generation.take(),
+        ipv6_address_token: common.ipv6_address_token.take(),
+        gateway4: common.gateway4.take(),
+        gateway6: common.gateway6.take(),
+        nameservers: common.nameservers.take(),
+        optional_addresses: common.optional_addresses.take(),
+        routes: common.routes.take(),
+        routing_policy: common.routing_policy.take(),
+        ..Default::default()
+    }
+}
+
+impl NetworkConfig {
+    /// Bond the ethernet devices `members` together, the way a machine is migrated to bonding
+    /// by hand: the bond is created with the parameters, the common properties of the
+    /// members, like their addresses, DHCP settings and routes, are moved onto the bond, and
+    /// the members are left with only their match rules and `set-name`. VLANs on top of the
+    /// members are moved onto the bond.
+    ///
+    /// If several members have the same property set, the last member's value is used.
+    /// Members which are not defined yet are added as ethernet devices.
+    ///
+    /// ```
+    /// use netplan_types::{BondMode, BondParameters, NetplanConfig};
+    ///
+    /// let mut config = NetplanConfig::build(|net| {
+    ///     net.ethernet("eth0", |e| e.address("10.0.0.2/24").route("default", "10.0.0.1"));
+    ///     net.ethernet("eth1", |e| e.dhcp4(false));
+    /// });
+    ///
+    /// config.network.bondify("bond0", ["eth0", "eth1"], BondParameters {
+    ///     mode: Some(BondMode::ActiveBackup),
+    ///     ..Default::default()
+    /// }).unwrap();
+    ///
+    /// assert_eq!(config.summary().to_string(), "eth0: ethernet\neth1: ethernet\n\
+    ///     bond0: bond of eth0 eth1, 10.0.0.2/24, default via 10.0.0.1");
+    /// ```
+    pub fn bondify<S: Into<String>>(
+        &mut self,
+        name: impl Into<String>,
+        members: impl IntoIterator<Item = S>,
+        parameters: BondParameters,
+    ) -> Result<(), AggregateError> {
+        let name = name.into();
+        let members = members.into_iter().map(Into::into).collect::<Vec<String>>();
+        self.check_aggregate(&name, &members, |kind| kind == DeviceKind::Ethernet)?;
+
+        let mut bond = BondConfig {
+            interfaces: Some(members.clone()),
+            parameters: Some(Box::new(parameters)),
+            ..Default::default()
+        };
+
+        let ethernets = self.ethernets.get_or_insert_with(HashMap::new);
+        for member in &members {
+            let ethernet = ethernets.remove(member).unwrap_or_default();
+            bond.common_all.merge(ethernet.common_all);
+
+            let physical = ethernet.common_physical.map(|physical| {
+                Box::new(CommonPropertiesPhysicalDeviceType {
+                    r#match: physical.r#match,
+                    set_name: physical.set_name,
+                    ..Default::default()
+                })
+            });
+            ethernets.insert(
+                member.clone(),
+                EthernetConfig {
+                    common_physical: physical,
+                    ..Default::default()
+                },
+            );
+        }
+
+        for vlan in self.vlans.iter_mut().flat_map(HashMap::values_mut) {
+            if vlan
+                .link
+                .as_ref()
+                .is_some_and(|link| members.contains(link))
+            {
+                vlan.link = Some(name.clone());
+            }
+        }
+
+        self.bonds
+            .get_or_insert_with(HashMap::new)
+            .insert(name, bond);
+        Ok(())
+    }
+
+    /// Bridge the interfaces `members`, e.g. to attach virtual machines or containers to
+    /// the network: the bridge is created and the IP configuration of the members, i.e. their
+    /// addresses, DHCP settings, routes and nameservers, is removed. With `migrate`, the
+    /// IP configuration of the first member is moved onto the bridge instead, so the host
+    /// keeps its connectivity.
+    ///
+    /// Members may be ethernet devices, bonds, VLANs, tunnels or dummy devices. Members
+    /// which are not defined yet are added as ethernet devices.
+    ///
+    /// ```
+    /// use netplan_types::NetplanConfig;
+    ///
+    /// let mut config = NetplanConfig::build(|net| {
+    ///     net.ethernet("eth0", |e| e.address("10.0.0.2/24").route("default", "10.0.0.1").mtu(9000));
+    /// });
+    ///
+    /// config.network.create_bridge("br0", ["eth0"], true).unwrap();
+    ///
+    /// assert_eq!(config.summary().to_string(), "eth0: ethernet, MTU 9000\n\
+    ///     br0: bridge of eth0, 10.0.0.2/24, default via 10.0.0.1");
+    /// ```
+    pub fn create_bridge<S: Into<String>>(
+        &mut self,
+        name: impl Into<String>,
+        members: impl IntoIterator<Item = S>,
+        migrate: bool,
+    ) -> Result<(), AggregateError> {
+        let name = name.into();
+        let members = members.into_iter().map(Into::into).collect::<Vec<String>>();
+        self.check_aggregate(&name, &members, |kind| {
+            matches!(
+                kind,
+                DeviceKind::Ethernet
+                    | DeviceKind::Bond
+                    | DeviceKind::Vlan
+                    | DeviceKind::Tunnel
+                    | DeviceKind::DummyDevice
+            )
+        })?;
+
+        let mut bridge = BridgeConfig {
+            interfaces: Some(members.clone()),
+            ..Default::default()
+        };
+
+        for (i, member) in members.iter().enumerate() {
+            let Some(mut device) = self.device_mut(member) else {
+                self.add_ethernet(member.clone(), EthernetConfig::default());
+                continue;
+            };
+
+            if device.common_all().is_none() {
+                continue;
+            }
+
+            let addressing = take_addressing(device.common_all_mut());
+            if migrate && i == 0 && addressing != CommonPropertiesAllDevices::default() {
+                bridge.common_all = Some(Box::new(addressing));
+            }
+        }
+
+        self.bridges
+            .get_or_insert_with(HashMap::new)
+            .insert(name, bridge);
+        Ok(())
+    }
+
+    /// Check that no device is defined as `name` yet, and that the members which are
+    /// defined already are of a kind which can be a member.
+    fn check_aggregate(
+        &self,
+        name: &str,
+        members: &[String],
+        allowed: impl Fn(DeviceKind) -> bool,
+    ) -> Result<(), AggregateError> {
+        if self.device(name).is_some() {
+            return Err(AggregateError::AlreadyExists(name.to_string()));
+        }
+
+        for member in members {
+            if let Some(device) = self.device(member) {
+                if !allowed(device.kind()) {
+                    return Err(AggregateError::InvalidMember(member.clone(), device.kind()));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "yaml"))]
+mod test {
+    use crate::{AggregateError, BondParameters, DeviceKind, NetplanConfig};
+
+    #[test]
+    fn bondify() {
+        let input = r#"
+            network:
+              version: 2
+              ethernets:
+                eno1:
+                  match:
+                    macaddress: "00:11:22:33:44:55"
+                  set-name: eno1
+                  dhcp4: true
+                  mtu: 9000
+                  emit-lldp: true
+              vlans:
+                vlan10:
+                  id: 10
+                  link: eno1
+            "#;
+        let expected = r#"
+            network:
+              version: 2
+              ethernets:
+                eno1:
+                  match:
+                    macaddress: "00:11:22:33:44:55"
+                  set-name: eno1
+                eno2: {}
+              bonds:
+                bond0:
+                  interfaces: [eno1, eno2]
+                  parameters: {}
+                  dhcp4: true
+                  mtu: 9000
+              vlans:
+                vlan10:
+                  id: 10
+                  link: bond0
+            "#;
+
+        let mut config: NetplanConfig = serde_yaml::from_str(input).unwrap();
+        assert_eq!(
+            config
+                .network
+                .bondify("bond1", ["eno1", "vlan10"], BondParameters::default()),
+            Err(AggregateError::InvalidMember(
+                "vlan10".to_string(),
+                DeviceKind::Vlan
+            ))
+        );
+
+        config
+            .network
+            .bondify("bond0", ["eno1", "eno2"], BondParameters::default())
+            .unwrap();
+
+        let expected: NetplanConfig = serde_yaml::from_str(expected).unwrap();
+        assert_eq!(
+            serde_yaml::to_value(&config).unwrap(),
+            serde_yaml::to_value(&expected).unwrap()
+        );
+    }
+
+    #[test]
+    fn create_bridge() {
+        let input = r#"
+            network:
+              version: 2
+              ethernets:
+                eth0:
+                  dhcp4: true
+                  mtu: 9000
+              bonds:
+                bond0:
+                  interfaces: [eth1, eth2]
+                  addresses: [10.0.0.2/24]
+            "#;
+        let expected = r#"
+            network:
+              version: 2
+              ethernets:
+                eth0:
+                  mtu: 9000
+              bonds:
+                bond0:
+                  interfaces: [eth1, eth2]
+              bridges:
+                br0:
+                  interfaces: [eth0, bond0]
+                  dhcp4: true
+            "#;
+
+        let mut config: NetplanConfig = serde_yaml::from_str(input).unwrap();
+        config
+            .network
+            .create_bridge("br0", ["eth0", "bond0"], true)
+            .unwrap();
+        assert_eq!(
+            config.network.create_bridge("br0", ["eth0"], false),
+            Err(AggregateError::AlreadyExists("br0".to_string()))
+        );
+
+        let expected: NetplanConfig = serde_yaml::from_str(expected).unwrap();
+        assert_eq!(
+            serde_yaml::to_value(&config).unwrap(),
+            serde_yaml::to_value(&expected).unwrap()
+        );
+    }
+}
@@ -0,0 +1,95 @@
+//! Asynchronous loading and writing of configurations, using tokio.
+
+use crate::write::write_atomic;
+use crate::{LoadError, LoadedConfig, NetplanConfig, WriteOptions};
+use std::path::{Path, PathBuf};
+
+impl NetplanConfig {
+    /// Load the configuration from a single file, see [NetplanConfig::load].
+    pub async fn load_async(path: impl AsRef<Path>) -> Result<Self, LoadError> {
+        let yaml = tokio::fs::read_to_string(path).await?;
+        Ok(Self::from_yaml(&yaml)?)
+    }
+
+    /// Load and merge all `*.yaml` files in a directory, see [NetplanConfig::load_dir].
+    pub async fn load_dir_async(path: impl AsRef<Path>) -> std::io::Result<LoadedConfig> {
+        let mut files = Vec::new();
+        for path in yaml_files(path.as_ref()).await? {
+            let result = Self::load_async(&path).await;
+            files.push((path, result));
+        }
+
+        Ok(LoadedConfig::merge(files))
+    }
+
+    /// Write the configuration to a file, see [NetplanConfig::write_to].
+    pub async fn write_to_async(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        self.write_to_with_async(path, &WriteOptions::default())
+            .await
+    }
+
+    /// Write the configuration to a file, replacing it atomically, see [NetplanConfig::write_to_with].
+    ///
+    /// ```no_run
+    /// use netplan_types::NetplanConfig;
+    ///
+    /// # async fn provision() -> std::io::Result<()> {
+    /// let loaded = NetplanConfig::load_dir_async("/etc/netplan").await?;
+    /// let mut config = loaded.config;
+    /// config.network.renderer = None;
+    /// config.write_to_async("/etc/netplan/90-provisioning.yaml").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn write_to_with_async(
+        &self,
+        path: impl AsRef<Path>,
+        options: &WriteOptions,
+    ) -> std::io::Result<()> {
+        let path = path.as_ref().to_path_buf();
+        let contents = self.to_file_contents(options);
+        tokio::task::spawn_blocking(move || write_atomic(&path, contents.as_bytes()))
+            .await
+            .map_err(std::io::Error::other)?
+    }
+}
+
+/// The paths of the `*.yaml` files in a directory, sorted by file name.
+async fn yaml_files(dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut entries = tokio::fs::read_dir(dir).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        let path = entry.path();
+        if path.extension().is_some_and(|ext| ext == "yaml")
+            && tokio::fs::metadata(&path)
+                .await
+                .is_ok_and(|metadata| metadata.is_file())
+        {
+            files.push(path);
+        }
+    }
+
+    files.sort_by(|a, b| a.file_name().cmp(&b.file_name()));
+    Ok(files)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::NetplanConfig;
+
+    #[tokio::test]
+    async fn write_and_load_async() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = NetplanConfig::single_nic_dhcp("eth0");
+        config
+            .write_to_async(dir.path().join("50-test.yaml"))
+            .await
+            .unwrap();
+        std::fs::write(dir.path().join("90-broken.yaml"), "network: [").unwrap();
+
+        let loaded = NetplanConfig::load_dir_async(dir.path()).await.unwrap();
+        assert_eq!(loaded.files.len(), 2);
+        assert_eq!(loaded.errors().count(), 1);
+        assert!(loaded.config.semantically_eq(&config));
+    }
+}
@@ -0,0 +1,251 @@
+//! A command line tool to validate, format, compare, convert and redact netplan configurations.
+
+use netplan_types::{NetplanConfig, NetplanVersion, Severity, ValidationOptions};
+use std::io::Read;
+use std::path::Path;
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: netplan-types <command> [options]
+
+Commands:
+  validate [--audit-secrets] [--target VERSION] PATH...
+      Validate configuration files, or the merged configuration of directories like
+      /etc/netplan. Fails if netplan would reject a configuration.
+  fmt [--check | --write] [FILE]
+      Print a configuration in a consistent layout, or rewrite the file with --write.
+      With --check, fail if the file is not formatted. Comments are not preserved.
+  diff OLD NEW
+      List the fields which differ between two configurations. Fails if any do.
+  convert [--to yaml|json] [FILE]
+      Convert a configuration between YAML and JSON. By default JSON is converted to
+      YAML and YAML to JSON.
+  redact [FILE]
+      Print a configuration with its passwords and keys replaced.
+
+A FILE of '-', or no FILE, reads the configuration from standard input.";
+
+/// The outcome of a command which ran: whether its check passed.
+enum Outcome {
+    Success,
+    Failure,
+}
+
+fn main() -> ExitCode {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let Some((command, args)) = args.split_first() else {
+        eprintln!("{USAGE}");
+        return ExitCode::from(2);
+    };
+
+    let result = match command.as_str() {
+        "validate" => validate(args),
+        "fmt" => fmt(args),
+        "diff" => diff(args),
+        "convert" => convert(args),
+        "redact" => redact(args),
+        "help" | "-h" | "--help" => {
+            println!("{USAGE}");
+            Ok(Outcome::Success)
+        }
+        _ => Err(format!("Unknown command '{command}'\n\n{USAGE}")),
+    };
+
+    match result {
+        Ok(Outcome::Success) => ExitCode::SUCCESS,
+        Ok(Outcome::Failure) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("netplan-types: {e}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+fn validate(args: &[String]) -> Result<Outcome, String> {
+    let mut options = ValidationOptions::default();
+    let mut paths = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--audit-secrets" => options.audit_secrets = true,
+            "--target" => {
+                let version = args.next().ok_or("--target requires a version")?;
+                let version = version
+                    .parse::<NetplanVersion>()
+                    .map_err(|e| e.to_string())?;
+                options.target = Some(version);
+            }
+            _ if arg.starts_with("--") => return Err(format!("Unknown option '{arg}'")),
+            _ => paths.push(arg.as_str()),
+        }
+    }
+    if paths.is_empty() {
+        return Err("validate requires a file or directory".to_string());
+    }
+
+    let mut outcome = Outcome::Success;
+    for path in paths {
+        let config = if Path::new(path).is_dir() {
+            let loaded = NetplanConfig::load_dir(path).map_err(|e| format!("{path}: {e}"))?;
+            for (file, result) in &loaded.files {
+                if let Err(e) = result {
+                    println!("{}: error: {e}", file.display());
+                    outcome = Outcome::Failure;
+                }
+            }
+            loaded.config
+        } else {
+            read_config(path)?
+        };
+
+        let report = config.validate_with(&options);
+        for issue in report.iter() {
+            let severity = match issue.severity {
+                Severity::Error => "error",
+                Severity::Warning => "warning",
+            };
+            println!(
+                "{path}: {severity}: {}: {} [{}]",
+                issue.path, issue.message, issue.rule
+            );
+            if let Some(suggestion) = &issue.suggestion {
+                println!("  {suggestion}");
+            }
+        }
+        if report.has_errors() {
+            outcome = Outcome::Failure;
+        }
+    }
+
+    Ok(outcome)
+}
+
+fn fmt(args: &[String]) -> Result<Outcome, String> {
+    let (mut check, mut write) = (false, false);
+    let mut files = Vec::new();
+    for arg in args {
+        match arg.as_str() {
+            "--check" => check = true,
+            "--write" => write = true,
+            _ if arg.starts_with("--") => return Err(format!("Unknown option '{arg}'")),
+            _ => files.push(arg.as_str()),
+        }
+    }
+    let path = single_file(&files)?;
+    if write && path == "-" {
+        return Err("--write requires a file".to_string());
+    }
+
+    let input = read_input(path)?;
+    let config = parse(path, &input)?;
+    let formatted = config.to_yaml();
+    if check {
+        if input == formatted {
+            return Ok(Outcome::Success);
+        }
+        eprintln!("{path} is not formatted");
+        return Ok(Outcome::Failure);
+    }
+
+    if write {
+        if input != formatted {
+            config.write_to(path).map_err(|e| format!("{path}: {e}"))?;
+        }
+    } else {
+        print!("{formatted}");
+    }
+
+    Ok(Outcome::Success)
+}
+
+fn diff(args: &[String]) -> Result<Outcome, String> {
+    let [old, new] = args else {
+        return Err("diff requires two files".to_string());
+    };
+
+    let changes = read_config(old)?.diff(&read_config(new)?);
+    for change in &changes {
+        println!("{change}");
+    }
+
+    Ok(if changes.is_empty() {
+        Outcome::Success
+    } else {
+        Outcome::Failure
+    })
+}
+
+fn convert(args: &[String]) -> Result<Outcome, String> {
+    let mut to = None;
+    let mut files = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--to" => to = Some(args.next().ok_or("--to requires a format")?.as_str()),
+            _ if arg.starts_with("--") => return Err(format!("Unknown option '{arg}'")),
+            _ => files.push(arg.as_str()),
+        }
+    }
+    let path = single_file(&files)?;
+
+    let input = read_input(path)?;
+    // JSON is valid YAML, so both formats are read by the YAML parser
+    let config = parse(path, &input)?;
+    let to = to.unwrap_or(if input.trim_start().starts_with('{') {
+        "yaml"
+    } else {
+        "json"
+    });
+    match to {
+        "yaml" => print!("{}", config.to_yaml()),
+        "json" => println!("{}", to_json(&config)?),
+        _ => return Err(format!("Unknown format '{to}', expected yaml or json")),
+    }
+
+    Ok(Outcome::Success)
+}
+
+fn redact(args: &[String]) -> Result<Outcome, String> {
+    let mut files = Vec::new();
+    for arg in args {
+        match arg.as_str() {
+            _ if arg.starts_with("--") => return Err(format!("Unknown option '{arg}'")),
+            _ => files.push(arg.as_str()),
+        }
+    }
+    let config = read_config(single_file(&files)?)?;
+    print!("{}", config.redacted().to_yaml());
+    Ok(Outcome::Success)
+}
+
+/// The single file a command reads, standard input if none is given.
+fn single_file<'a>(files: &[&'a str]) -> Result<&'a str, String> {
+    match files {
+        [] => Ok("-"),
+        [file] => Ok(file),
+        _ => Err("Only a single file can be given".to_string()),
+    }
+}
+
+fn read_input(path: &str) -> Result<String, String> {
+    let mut input = String::new();
+    let result = if path == "-" {
+        std::io::stdin().read_to_string(&mut input).map(|_| ())
+    } else {
+        std::fs::read_to_string(path).map(|contents| input = contents)
+    };
+    result.map_err(|e| format!("{path}: {e}"))?;
+    Ok(input)
+}
+
+fn parse(path: &str, input: &str) -> Result<NetplanConfig, String> {
+    NetplanConfig::from_yaml(input).map_err(|e| format!("{path}: Invalid configuration: {e}"))
+}
+
+fn read_config(path: &str) -> Result<NetplanConfig, String> {
+    parse(path, &read_input(path)?)
+}
+
+fn to_json(config: &NetplanConfig) -> Result<String, String> {
+    serde_json::to_string_pretty(config).map_err(|e| e.to_string())
+}
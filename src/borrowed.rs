@@ -0,0 +1,416 @@
+//! Read-only views of configurations, which borrow their strings from the YAML they were
+//! parsed from.
+
+use crate::{DeviceKind, NetplanConfig, Renderer};
+use serde::de::{IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fmt::Formatter;
+
+/// A read-only view of a netplan configuration, for inspecting large configurations
+/// without copying them. Strings borrow from the YAML the view was parsed from, unless
+/// they contain escape sequences.
+///
+/// The view holds the settings which are commonly inspected: the addressing, routes,
+/// nameservers, MTU and MAC address of every device, its match rules, and how it is
+/// linked to other devices. [NetplanConfigRef::to_config] parses the YAML again into a
+/// [NetplanConfig] with all settings, when it needs to be edited.
+///
+/// ```
+/// use netplan_types::NetplanConfigRef;
+///
+/// let yaml = "
+/// network:
+///   version: 2
+///   ethernets:
+///     eth0:
+///       addresses: [192.0.2.10/24]
+///   vlans:
+///     vlan10: {id: 10, link: eth0, dhcp4: true}
+/// ";
+///
+/// let view = NetplanConfigRef::from_yaml(yaml).unwrap();
+/// let eth0 = view.device("eth0").unwrap();
+/// assert_eq!(eth0.addresses, ["192.0.2.10/24"]);
+/// assert_eq!(view.device("vlan10").unwrap().link.as_deref(), Some("eth0"));
+///
+/// let mut config = view.to_config().unwrap();
+/// config.network.vlans = None;
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetplanConfigRef<'a> {
+    source: &'a str,
+    pub network: NetworkConfigRef<'a>,
+}
+
+/// The `network` mapping of a [NetplanConfigRef]. Devices are ordered by their ID.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct NetworkConfigRef<'a> {
+    pub version: u8,
+    pub renderer: Option<Renderer>,
+    #[serde(borrow, default, deserialize_with = "devices")]
+    pub ethernets: BTreeMap<Cow<'a, str>, DeviceRef<'a>>,
+    #[serde(borrow, default, deserialize_with = "devices")]
+    pub wifis: BTreeMap<Cow<'a, str>, DeviceRef<'a>>,
+    #[serde(borrow, default, deserialize_with = "devices")]
+    pub modems: BTreeMap<Cow<'a, str>, DeviceRef<'a>>,
+    #[serde(borrow, default, deserialize_with = "devices")]
+    pub bonds: BTreeMap<Cow<'a, str>, DeviceRef<'a>>,
+    #[serde(borrow, default, deserialize_with = "devices")]
+    pub bridges: BTreeMap<Cow<'a, str>, DeviceRef<'a>>,
+    #[serde(borrow, default, deserialize_with = "devices")]
+    pub vlans: BTreeMap<Cow<'a, str>, DeviceRef<'a>>,
+    #[serde(borrow, default, deserialize_with = "devices")]
+    pub tunnels: BTreeMap<Cow<'a, str>, DeviceRef<'a>>,
+    #[serde(borrow, default, deserialize_with = "devices")]
+    pub vrfs: BTreeMap<Cow<'a, str>, DeviceRef<'a>>,
+    #[serde(borrow, default, deserialize_with = "devices")]
+    pub dummy_devices: BTreeMap<Cow<'a, str>, DeviceRef<'a>>,
+    #[serde(borrow, default, deserialize_with = "devices")]
+    pub nm_devices: BTreeMap<Cow<'a, str>, DeviceRef<'a>>,
+}
+
+/// A read-only view of a device definition of any kind. Settings which do not apply to
+/// the kind of the device, like `link` for an ethernet, are empty.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct DeviceRef<'a> {
+    pub renderer: Option<Renderer>,
+    #[serde(default, deserialize_with = "crate::bool::string_or_bool_option")]
+    pub dhcp4: Option<bool>,
+    #[serde(default, deserialize_with = "crate::bool::string_or_bool_option")]
+    pub dhcp6: Option<bool>,
+    #[serde(default, deserialize_with = "crate::bool::string_or_bool_option")]
+    pub optional: Option<bool>,
+    /// The static addresses, in CIDR notation, without their properties.
+    #[serde(borrow, default, deserialize_with = "addresses")]
+    pub addresses: Vec<Cow<'a, str>>,
+    #[serde(borrow, default, deserialize_with = "string")]
+    pub gateway4: Option<Cow<'a, str>>,
+    #[serde(borrow, default, deserialize_with = "string")]
+    pub gateway6: Option<Cow<'a, str>>,
+    #[serde(borrow, default)]
+    pub nameservers: Option<NameserversRef<'a>>,
+    #[serde(borrow, default)]
+    pub routes: Vec<RouteRef<'a>>,
+    pub mtu: Option<u16>,
+    #[serde(borrow, default, deserialize_with = "string")]
+    pub macaddress: Option<Cow<'a, str>>,
+    #[serde(rename = "match", borrow, default)]
+    pub match_config: Option<MatchRef<'a>>,
+    #[serde(borrow, default, deserialize_with = "string")]
+    pub set_name: Option<Cow<'a, str>>,
+    /// The member interfaces of a bond, bridge or VRF.
+    #[serde(borrow, default, deserialize_with = "strings")]
+    pub interfaces: Vec<Cow<'a, str>>,
+    /// The parent of a VLAN.
+    #[serde(borrow, default, deserialize_with = "string")]
+    pub link: Option<Cow<'a, str>>,
+    /// The ID of a VLAN.
+    pub id: Option<u16>,
+    /// The mode of a tunnel, e.g. `wireguard`.
+    #[serde(borrow, default, deserialize_with = "string")]
+    pub mode: Option<Cow<'a, str>>,
+}
+
+/// The `nameservers` of a [DeviceRef].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct NameserversRef<'a> {
+    #[serde(borrow, default, deserialize_with = "strings")]
+    pub addresses: Vec<Cow<'a, str>>,
+    #[serde(borrow, default, deserialize_with = "strings")]
+    pub search: Vec<Cow<'a, str>>,
+}
+
+/// A route of a [DeviceRef].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct RouteRef<'a> {
+    #[serde(borrow, default, deserialize_with = "string")]
+    pub to: Option<Cow<'a, str>>,
+    #[serde(borrow, default, deserialize_with = "string")]
+    pub via: Option<Cow<'a, str>>,
+    #[serde(borrow, default, deserialize_with = "string")]
+    pub from: Option<Cow<'a, str>>,
+    pub metric: Option<u16>,
+    pub table: Option<u32>,
+}
+
+/// The match rules of a [DeviceRef].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct MatchRef<'a> {
+    #[serde(borrow, default, deserialize_with = "string")]
+    pub name: Option<Cow<'a, str>>,
+    #[serde(borrow, default, deserialize_with = "string")]
+    pub macaddress: Option<Cow<'a, str>>,
+    #[serde(borrow, default, deserialize_with = "strings")]
+    pub driver: Vec<Cow<'a, str>>,
+}
+
+impl<'a> NetplanConfigRef<'a> {
+    /// Parse a view of a configuration from netplan YAML.
+    pub fn from_yaml(yaml: &'a str) -> Result<Self, serde_yaml::Error> {
+        #[derive(Deserialize)]
+        struct Document<'a> {
+            #[serde(borrow)]
+            network: NetworkConfigRef<'a>,
+        }
+
+        let document: Document = serde_yaml::from_str(yaml)?;
+        Ok(Self {
+            source: yaml,
+            network: document.network,
+        })
+    }
+
+    /// The YAML the view was parsed from.
+    pub fn source(&self) -> &'a str {
+        self.source
+    }
+
+    /// Parse the YAML of the view into a configuration with all settings, e.g. to edit it.
+    /// This fails if settings the view does not hold are invalid.
+    pub fn to_config(&self) -> Result<NetplanConfig, serde_yaml::Error> {
+        NetplanConfig::from_yaml(self.source)
+    }
+
+    /// The device definitions, in the order of [NetworkConfig::devices](crate::NetworkConfig::devices),
+    /// and by ID within a section.
+    pub fn devices(&self) -> impl Iterator<Item = (DeviceKind, &str, &DeviceRef<'a>)> {
+        let network = &self.network;
+        [
+            (DeviceKind::Ethernet, &network.ethernets),
+            (DeviceKind::Wifi, &network.wifis),
+            (DeviceKind::Modem, &network.modems),
+            (DeviceKind::Bond, &network.bonds),
+            (DeviceKind::Bridge, &network.bridges),
+            (DeviceKind::Vlan, &network.vlans),
+            (DeviceKind::Tunnel, &network.tunnels),
+            (DeviceKind::Vrf, &network.vrfs),
+            (DeviceKind::DummyDevice, &network.dummy_devices),
+            (DeviceKind::NmDevice, &network.nm_devices),
+        ]
+        .into_iter()
+        .flat_map(|(kind, section)| {
+            section
+                .iter()
+                .map(move |(name, device)| (kind, name.as_ref(), device))
+        })
+    }
+
+    /// Find the device definition with the given ID, in whichever section it is defined.
+    pub fn device(&self, name: &str) -> Option<&DeviceRef<'a>> {
+        self.devices()
+            .find(|(_, id, _)| *id == name)
+            .map(|(_, _, device)| device)
+    }
+}
+
+/// A string, borrowed from the input unless it has to be unescaped.
+struct Str<'a>(Cow<'a, str>);
+
+impl<'de> Deserialize<'de> for Str<'de> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct StrVisitor;
+
+        impl<'de> Visitor<'de> for StrVisitor {
+            type Value = Str<'de>;
+
+            fn expecting(&self, f: &mut Formatter) -> std::fmt::Result {
+                f.write_str("a string")
+            }
+
+            fn visit_borrowed_str<E>(self, v: &'de str) -> Result<Self::Value, E> {
+                Ok(Str(Cow::Borrowed(v)))
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E> {
+                Ok(Str(Cow::Owned(v.to_string())))
+            }
+
+            fn visit_string<E>(self, v: String) -> Result<Self::Value, E> {
+                Ok(Str(Cow::Owned(v)))
+            }
+        }
+
+        deserializer.deserialize_str(StrVisitor)
+    }
+}
+
+fn string<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Cow<'de, str>>, D::Error> {
+    Ok(Option::<Str>::deserialize(deserializer)?.map(|s| s.0))
+}
+
+fn strings<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Cow<'de, str>>, D::Error> {
+    let strings = Option::<Vec<Str>>::deserialize(deserializer)?;
+    Ok(strings.into_iter().flatten().map(|s| s.0).collect())
+}
+
+fn devices<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<BTreeMap<Cow<'de, str>, DeviceRef<'de>>, D::Error> {
+    struct DevicesVisitor;
+
+    impl<'de> Visitor<'de> for DevicesVisitor {
+        type Value = BTreeMap<Cow<'de, str>, DeviceRef<'de>>;
+
+        fn expecting(&self, f: &mut Formatter) -> std::fmt::Result {
+            f.write_str("a mapping of device definitions")
+        }
+
+        /// An empty section, like `ethernets:` without definitions.
+        fn visit_unit<E>(self) -> Result<Self::Value, E> {
+            Ok(BTreeMap::new())
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+            let mut devices = BTreeMap::new();
+            while let Some((Str(name), device)) = map.next_entry()? {
+                devices.insert(name, device);
+            }
+
+            Ok(devices)
+        }
+    }
+
+    deserializer.deserialize_map(DevicesVisitor)
+}
+
+/// The addresses of a device: a sequence of strings, or of mappings from addresses to their
+/// properties.
+fn addresses<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Cow<'de, str>>, D::Error> {
+    struct AddressesVisitor;
+
+    impl<'de> Visitor<'de> for AddressesVisitor {
+        type Value = Vec<Cow<'de, str>>;
+
+        fn expecting(&self, f: &mut Formatter) -> std::fmt::Result {
+            f.write_str("a sequence of addresses")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            let mut addresses = Vec::new();
+            while let Some(Address(entry)) = seq.next_element()? {
+                addresses.extend(entry);
+            }
+
+            Ok(addresses)
+        }
+    }
+
+    /// An entry of the addresses: an address, or a mapping of addresses to properties.
+    struct Address<'a>(Vec<Cow<'a, str>>);
+
+    impl<'de> Deserialize<'de> for Address<'de> {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            struct AddressVisitor;
+
+            impl<'de> Visitor<'de> for AddressVisitor {
+                type Value = Address<'de>;
+
+                fn expecting(&self, f: &mut Formatter) -> std::fmt::Result {
+                    f.write_str("an address, or a mapping of an address to its properties")
+                }
+
+                fn visit_borrowed_str<E>(self, v: &'de str) -> Result<Self::Value, E> {
+                    Ok(Address(vec![Cow::Borrowed(v)]))
+                }
+
+                fn visit_str<E>(self, v: &str) -> Result<Self::Value, E> {
+                    Ok(Address(vec![Cow::Owned(v.to_string())]))
+                }
+
+                fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                    let mut addresses = Vec::new();
+                    while let Some((Str(address), IgnoredAny)) = map.next_entry()? {
+                        addresses.push(address);
+                    }
+
+                    Ok(Address(addresses))
+                }
+            }
+
+            deserializer.deserialize_any(AddressVisitor)
+        }
+    }
+
+    deserializer.deserialize_seq(AddressesVisitor)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{DeviceKind, NetplanConfigRef};
+    use std::borrow::Cow;
+
+    #[test]
+    fn borrowed_view() {
+        let yaml = r#"
+network:
+  version: 2
+  renderer: networkd
+  ethernets:
+    eth0:
+      match: {macaddress: "52:54:00:12:34:00"}
+      set-name: lan0
+      dhcp4: yes
+      addresses:
+        - 192.0.2.10/24
+        - "2001:db8::10/64":
+            label: lan0:v6
+      nameservers:
+        addresses: [192.0.2.53]
+        search: ["ex\u0061mple.com"]
+      routes:
+        - to: default
+          via: 192.0.2.1
+          metric: 100
+  bridges:
+  bonds:
+    bond0:
+      interfaces: [eth1, eth2]
+  tunnels:
+    wg0:
+      mode: wireguard
+"#;
+
+        let view = NetplanConfigRef::from_yaml(yaml).unwrap();
+        let devices = view
+            .devices()
+            .map(|(kind, name, _)| (kind, name))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            devices,
+            [
+                (DeviceKind::Ethernet, "eth0"),
+                (DeviceKind::Bond, "bond0"),
+                (DeviceKind::Tunnel, "wg0")
+            ]
+        );
+
+        let eth0 = view.device("eth0").unwrap();
+        assert_eq!(eth0.dhcp4, Some(true));
+        assert_eq!(eth0.addresses, ["192.0.2.10/24", "2001:db8::10/64"]);
+        assert!(matches!(eth0.addresses[0], Cow::Borrowed(_)));
+        let nameservers = eth0.nameservers.as_ref().unwrap();
+        assert_eq!(nameservers.search, ["example.com"]);
+        // Escaped strings cannot be borrowed
+        assert!(matches!(nameservers.search[0], Cow::Owned(_)));
+        assert_eq!(eth0.routes[0].via.as_deref(), Some("192.0.2.1"));
+        assert_eq!(eth0.routes[0].metric, Some(100));
+        let match_config = eth0.match_config.as_ref().unwrap();
+        assert_eq!(
+            match_config.macaddress.as_deref(),
+            Some("52:54:00:12:34:00")
+        );
+        assert_eq!(eth0.set_name.as_deref(), Some("lan0"));
+        assert_eq!(view.device("bond0").unwrap().interfaces, ["eth1", "eth2"]);
+        assert_eq!(
+            view.device("wg0").unwrap().mode.as_deref(),
+            Some("wireguard")
+        );
+
+        let config = view.to_config().unwrap();
+        assert_eq!(config.network.devices().count(), 3);
+    }
+}
@@ -0,0 +1,384 @@
+//! A closure based API for building configurations, without having to deal
+//! with the nested `Option<HashMap<..>>`s of the configuration types.
+
+use crate::{
+    AccessPointConfig, AddressMapping, BondConfig, BondMode, BridgeConfig, CommonProperties,
+    DummyDeviceConfig, EthernetConfig, MacAddress, MatchConfig, NetplanConfig, NetworkConfig,
+    PhysicalProperties, Renderer, RoutingConfig, TunnelConfig, TunnelMode, VlanConfig, VrfsConfig,
+    WifiConfig,
+};
+use std::collections::HashMap;
+
+impl NetplanConfig {
+    /// Build a configuration using a closure.
+    ///
+    /// ```
+    /// use netplan_types::NetplanConfig;
+    ///
+    /// let config = NetplanConfig::build(|net| {
+    ///     net.ethernet("eno1", |e| e.dhcp4(false));
+    ///     net.bridge("br0", |b| b.interfaces(["eno1"]).address("10.0.0.2/24"));
+    /// });
+    ///
+    /// assert_eq!(config.network.bridges.unwrap()["br0"].interfaces, Some(vec!["eno1".to_string()]));
+    /// ```
+    pub fn build(f: impl FnOnce(&mut NetworkBuilder)) -> Self {
+        let mut builder = NetworkBuilder {
+            network: NetworkConfig::default(),
+        };
+
+        f(&mut builder);
+        Self {
+            network: builder.network,
+        }
+    }
+
+    /// A configuration with a single ethernet device, configured with DHCP for IPv4.
+    ///
+    /// ```
+    /// use netplan_types::NetplanConfig;
+    ///
+    /// let config = NetplanConfig::single_nic_dhcp("eth0");
+    /// assert_eq!(config.summary().to_string(), "eth0: ethernet, DHCPv4");
+    /// ```
+    pub fn single_nic_dhcp(name: impl Into<String>) -> Self {
+        let mut config = Self::build(|_| {});
+        config.network.add_ethernet(name, EthernetConfig::dhcp());
+        config
+    }
+}
+
+/// Builds the network section of a configuration, see [NetplanConfig::build].
+#[derive(Debug)]
+pub struct NetworkBuilder {
+    network: NetworkConfig,
+}
+
+impl NetworkBuilder {
+    /// Set the global renderer.
+    pub fn renderer(&mut self, renderer: Renderer) -> &mut Self {
+        self.network.renderer = Some(renderer);
+        self
+    }
+
+    /// Define an ethernet device. If it is defined already, the existing definition is modified.
+    pub fn ethernet(
+        &mut self,
+        name: impl Into<String>,
+        f: impl FnOnce(DeviceBuilder<EthernetConfig>) -> DeviceBuilder<EthernetConfig>,
+    ) -> &mut Self {
+        define(&mut self.network.ethernets, name.into(), f);
+        self
+    }
+
+    /// Define a wifi device. If it is defined already, the existing definition is modified.
+    pub fn wifi(
+        &mut self,
+        name: impl Into<String>,
+        f: impl FnOnce(DeviceBuilder<WifiConfig>) -> DeviceBuilder<WifiConfig>,
+    ) -> &mut Self {
+        define(&mut self.network.wifis, name.into(), f);
+        self
+    }
+
+    /// Define a bond. If it is defined already, the existing definition is modified.
+    pub fn bond(
+        &mut self,
+        name: impl Into<String>,
+        f: impl FnOnce(DeviceBuilder<BondConfig>) -> DeviceBuilder<BondConfig>,
+    ) -> &mut Self {
+        define(&mut self.network.bonds, name.into(), f);
+        self
+    }
+
+    /// Define a bridge. If it is defined already, the existing definition is modified.
+    pub fn bridge(
+        &mut self,
+        name: impl Into<String>,
+        f: impl FnOnce(DeviceBuilder<BridgeConfig>) -> DeviceBuilder<BridgeConfig>,
+    ) -> &mut Self {
+        define(&mut self.network.bridges, name.into(), f);
+        self
+    }
+
+    /// Define a VLAN. If it is defined already, the existing definition is modified.
+    pub fn vlan(
+        &mut self,
+        name: impl Into<String>,
+        f: impl FnOnce(DeviceBuilder<VlanConfig>) -> DeviceBuilder<VlanConfig>,
+    ) -> &mut Self {
+        define(&mut self.network.vlans, name.into(), f);
+        self
+    }
+
+    /// Define a tunnel. If it is defined already, the existing definition is modified.
+    pub fn tunnel(
+        &mut self,
+        name: impl Into<String>,
+        f: impl FnOnce(DeviceBuilder<TunnelConfig>) -> DeviceBuilder<TunnelConfig>,
+    ) -> &mut Self {
+        define(&mut self.network.tunnels, name.into(), f);
+        self
+    }
+
+    /// Define a VRF. If it is defined already, the existing definition is modified.
+    pub fn vrf(
+        &mut self,
+        name: impl Into<String>,
+        f: impl FnOnce(DeviceBuilder<VrfsConfig>) -> DeviceBuilder<VrfsConfig>,
+    ) -> &mut Self {
+        define(&mut self.network.vrfs, name.into(), f);
+        self
+    }
+
+    /// Define a dummy device. If it is defined already, the existing definition is modified.
+    pub fn dummy_device(
+        &mut self,
+        name: impl Into<String>,
+        f: impl FnOnce(DeviceBuilder<DummyDeviceConfig>) -> DeviceBuilder<DummyDeviceConfig>,
+    ) -> &mut Self {
+        define(&mut self.network.dummy_devices, name.into(), f);
+        self
+    }
+}
+
+fn define<T: Default>(
+    map: &mut Option<HashMap<String, T>>,
+    name: String,
+    f: impl FnOnce(DeviceBuilder<T>) -> DeviceBuilder<T>,
+) {
+    let map = map.get_or_insert_with(HashMap::new);
+    let config = map.remove(&name).unwrap_or_default();
+    map.insert(name, f(DeviceBuilder { config }).config);
+}
+
+/// Builds a single device definition, see [NetplanConfig::build].
+#[derive(Debug)]
+pub struct DeviceBuilder<T> {
+    config: T,
+}
+
+impl<T: CommonProperties> DeviceBuilder<T> {
+    /// Set the renderer of this device.
+    pub fn renderer(mut self, renderer: Renderer) -> Self {
+        self.config.set_renderer(Some(renderer));
+        self
+    }
+
+    pub fn dhcp4(mut self, enabled: bool) -> Self {
+        self.config.set_dhcp4(Some(enabled));
+        self
+    }
+
+    pub fn dhcp6(mut self, enabled: bool) -> Self {
+        self.config.set_dhcp6(Some(enabled));
+        self
+    }
+
+    /// Add a static address in CIDR notation, e.g. `10.0.0.2/24`.
+    pub fn address(mut self, address: impl Into<String>) -> Self {
+        self.config
+            .common_all_mut()
+            .addresses
+            .get_or_insert_with(Vec::new)
+            .push(AddressMapping::Simple(address.into()));
+        self
+    }
+
+    /// Add static addresses in CIDR notation.
+    pub fn addresses<S: Into<String>>(self, addresses: impl IntoIterator<Item = S>) -> Self {
+        addresses
+            .into_iter()
+            .fold(self, |builder, address| builder.address(address))
+    }
+
+    /// Add a route to `to` via the gateway `via`. Use `default` as destination for a default route.
+    pub fn route(mut self, to: impl Into<String>, via: impl Into<String>) -> Self {
+        self.config
+            .common_all_mut()
+            .routes
+            .get_or_insert_with(Vec::new)
+            .push(RoutingConfig {
+                to: Some(to.into()),
+                via: Some(via.into()),
+                ..Default::default()
+            });
+        self
+    }
+
+    /// Add DNS servers.
+    pub fn nameservers<S: Into<String>>(mut self, addresses: impl IntoIterator<Item = S>) -> Self {
+        self.config
+            .common_all_mut()
+            .nameservers
+            .get_or_insert_with(Default::default)
+            .addresses
+            .get_or_insert_with(Vec::new)
+            .extend(addresses.into_iter().map(Into::into));
+        self
+    }
+
+    /// Add DNS search domains.
+    pub fn search<S: Into<String>>(mut self, domains: impl IntoIterator<Item = S>) -> Self {
+        self.config
+            .common_all_mut()
+            .nameservers
+            .get_or_insert_with(Default::default)
+            .search
+            .get_or_insert_with(Vec::new)
+            .extend(domains.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn mtu(mut self, mtu: u16) -> Self {
+        self.config.set_mtu(Some(mtu));
+        self
+    }
+
+    /// Set the MAC address of the device.
+    pub fn macaddress(mut self, macaddress: impl Into<MacAddress>) -> Self {
+        self.config.set_macaddress(Some(macaddress.into()));
+        self
+    }
+
+    /// Mark the device as optional, so booting does not wait for it.
+    pub fn optional(mut self, optional: bool) -> Self {
+        self.config.set_optional(Some(optional));
+        self
+    }
+
+    /// Modify the configuration directly, for properties without a dedicated method.
+    pub fn with(mut self, f: impl FnOnce(&mut T)) -> Self {
+        f(&mut self.config);
+        self
+    }
+
+    /// The configuration built so far.
+    pub fn into_inner(self) -> T {
+        self.config
+    }
+}
+
+impl DeviceBuilder<EthernetConfig> {
+    /// Match the device on its MAC address, rather than on its ID.
+    pub fn match_macaddress(mut self, macaddress: impl Into<MacAddress>) -> Self {
+        self.match_rules().macaddress = Some(macaddress.into());
+        self
+    }
+
+    /// Match the device on its name, which may be a glob.
+    pub fn match_name(mut self, name: impl Into<String>) -> Self {
+        self.match_rules().name = Some(name.into());
+        self
+    }
+
+    /// Rename the matched device.
+    pub fn set_name(mut self, name: impl Into<String>) -> Self {
+        self.config.common_physical_mut().set_name = Some(name.into());
+        self
+    }
+
+    fn match_rules(&mut self) -> &mut MatchConfig {
+        self.config
+            .common_physical_mut()
+            .r#match
+            .get_or_insert_with(Default::default)
+    }
+}
+
+impl DeviceBuilder<WifiConfig> {
+    /// Add an access point to connect to.
+    pub fn access_point(mut self, ssid: impl Into<String>, config: AccessPointConfig) -> Self {
+        self.config
+            .access_points
+            .get_or_insert_with(HashMap::new)
+            .insert(ssid.into(), config);
+        self
+    }
+}
+
+impl DeviceBuilder<BondConfig> {
+    /// Add member interfaces to the bond.
+    pub fn interfaces<S: Into<String>>(mut self, interfaces: impl IntoIterator<Item = S>) -> Self {
+        self.config
+            .interfaces
+            .get_or_insert_with(Vec::new)
+            .extend(interfaces.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn mode(mut self, mode: BondMode) -> Self {
+        self.config
+            .parameters
+            .get_or_insert_with(Default::default)
+            .mode = Some(mode);
+        self
+    }
+}
+
+impl DeviceBuilder<BridgeConfig> {
+    /// Add member interfaces to the bridge.
+    pub fn interfaces<S: Into<String>>(mut self, interfaces: impl IntoIterator<Item = S>) -> Self {
+        self.config
+            .interfaces
+            .get_or_insert_with(Vec::new)
+            .extend(interfaces.into_iter().map(Into::into));
+        self
+    }
+
+    /// Enable or disable the spanning tree protocol.
+    pub fn stp(mut self, stp: bool) -> Self {
+        self.config
+            .parameters
+            .get_or_insert_with(Default::default)
+            .stp = Some(stp);
+        self
+    }
+}
+
+impl DeviceBuilder<VlanConfig> {
+    pub fn id(mut self, id: u16) -> Self {
+        self.config.id = Some(id);
+        self
+    }
+
+    /// Set the underlying device of the VLAN.
+    pub fn link(mut self, link: impl Into<String>) -> Self {
+        self.config.link = Some(link.into());
+        self
+    }
+}
+
+impl DeviceBuilder<TunnelConfig> {
+    pub fn mode(mut self, mode: TunnelMode) -> Self {
+        self.config.mode = Some(mode);
+        self
+    }
+
+    /// Set the address of the local endpoint.
+    pub fn local(mut self, local: impl Into<String>) -> Self {
+        self.config.local = Some(local.into());
+        self
+    }
+
+    /// Set the address of the remote endpoint.
+    pub fn remote(mut self, remote: impl Into<String>) -> Self {
+        self.config.remote = Some(remote.into());
+        self
+    }
+}
+
+impl DeviceBuilder<VrfsConfig> {
+    pub fn table(mut self, table: i32) -> Self {
+        self.config.table = table;
+        self
+    }
+
+    /// Add member interfaces to the VRF.
+    pub fn interfaces<S: Into<String>>(mut self, interfaces: impl IntoIterator<Item = S>) -> Self {
+        self.config
+            .interfaces
+            .extend(interfaces.into_iter().map(Into::into));
+        self
+    }
+}
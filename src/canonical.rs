@@ -0,0 +1,399 @@
+//! Canonical forms of configurations, for comparing them semantically.
+
+use crate::netplan::normalize_mac;
+use crate::{
+    AccessPointMode, AddressMapping, AddressProperties, AnyDeviceConfigMut,
+    CommonPropertiesAllDevices, CommonPropertiesPhysicalDeviceType, NetplanConfig, NetworkConfig,
+    Renderer, TunnelConfig, TunnelKey, TunnelMode, WifiConfig,
+};
+use std::collections::HashMap;
+use std::net::IpAddr;
+
+impl NetplanConfig {
+    /// Rewrite the configuration into its canonical form, see [NetworkConfig::canonicalize].
+    pub fn canonicalize(&mut self) {
+        self.network.canonicalize();
+    }
+
+    /// A canonical copy of the configuration, see [NetworkConfig::canonicalize].
+    pub fn canonicalized(&self) -> Self {
+        let mut config = self.clone();
+        config.canonicalize();
+        config
+    }
+
+    /// Whether two configurations have the same effect, even if they are written differently.
+    ///
+    /// ```
+    /// use netplan_types::NetplanConfig;
+    ///
+    /// let a: NetplanConfig = serde_yaml::from_str(r#"
+    ///     network:
+    ///       version: 2
+    ///       renderer: networkd
+    ///       bonds:
+    ///         bond0:
+    ///           interfaces: [eth1, eth0]
+    ///           dhcp4: no
+    ///     "#).unwrap();
+    /// let b: NetplanConfig = serde_yaml::from_str(r#"
+    ///     network:
+    ///       version: 2
+    ///       bonds:
+    ///         bond0:
+    ///           interfaces: [eth0, eth1]
+    ///     "#).unwrap();
+    ///
+    /// assert_ne!(a, b);
+    /// assert!(a.semantically_eq(&b));
+    /// ```
+    pub fn semantically_eq(&self, other: &NetplanConfig) -> bool {
+        self.canonicalized() == other.canonicalized()
+    }
+}
+
+impl NetworkConfig {
+    /// Rewrite the configuration into its canonical form, so that configurations which
+    /// only differ in the way they are written compare equal:
+    ///
+    /// - Fields set to their default value, like `dhcp4: false` or a `networkd` renderer,
+    ///   are removed, as are empty sequences, mappings and blocks.
+    /// - Member interfaces of bonds, bridges and VRFs are sorted, as their order has no effect.
+    /// - Match rules are normalized with [MatchConfig::normalized](crate::MatchConfig::normalized)
+    ///   and MAC addresses are lowercased.
+    /// - Addresses without properties, and tunnel keys which can be written as a single
+    ///   string, use their short form.
+    /// - Default routes written as `to: default` use the prefix of the family of their
+    ///   gateway, `0.0.0.0/0` or `::/0`.
+    ///
+    /// Sequences whose order is significant, like addresses and nameservers, are left as is.
+    pub fn canonicalize(&mut self) {
+        if self.renderer == Some(Renderer::Networkd) {
+            self.renderer = None;
+        }
+
+        let renderer = self.renderer.clone().unwrap_or(Renderer::Networkd);
+        let common = |common: &mut Option<Box<CommonPropertiesAllDevices>>| {
+            if let Some(common) = common.as_deref_mut() {
+                canonicalize_common(common, &renderer);
+            }
+            prune(common);
+        };
+
+        for (_, _, mut device) in self.devices_mut() {
+            common(device.common_all_entry());
+
+            match device {
+                AnyDeviceConfigMut::Ethernet(ethernet) => {
+                    canonicalize_physical(&mut ethernet.common_physical);
+                }
+                AnyDeviceConfigMut::Wifi(wifi) => {
+                    canonicalize_physical(&mut wifi.common_physical);
+                    canonicalize_wifi(wifi);
+                }
+                AnyDeviceConfigMut::Modem(modem) => {
+                    canonicalize_physical(&mut modem.common_physical);
+                }
+                AnyDeviceConfigMut::Bond(bond) => {
+                    canonicalize_members(&mut bond.interfaces);
+                    prune(&mut bond.parameters);
+                    prune(&mut bond.openvswitch);
+                }
+                AnyDeviceConfigMut::Bridge(bridge) => {
+                    canonicalize_members(&mut bridge.interfaces);
+                    if let Some(parameters) = bridge.parameters.as_deref_mut() {
+                        // STP is enabled by default
+                        if parameters.stp == Some(true) {
+                            parameters.stp = None;
+                        }
+                    }
+                    prune(&mut bridge.parameters);
+                    prune(&mut bridge.openvswitch);
+                }
+                AnyDeviceConfigMut::Vlan(vlan) => prune(&mut vlan.openvswitch),
+                AnyDeviceConfigMut::Tunnel(tunnel) => canonicalize_tunnel(tunnel),
+                AnyDeviceConfigMut::Vrf(vrf) => {
+                    vrf.interfaces.sort();
+                    vrf.interfaces.dedup();
+                }
+                AnyDeviceConfigMut::DummyDevice(_) | AnyDeviceConfigMut::NmDevice(_) => {}
+            }
+        }
+
+        prune(&mut self.openvswitch);
+        prune_map(&mut self.ethernets);
+        prune_map(&mut self.wifis);
+        prune_map(&mut self.modems);
+        prune_map(&mut self.bonds);
+        prune_map(&mut self.bridges);
+        prune_map(&mut self.vlans);
+        prune_map(&mut self.tunnels);
+        prune_map(&mut self.vrfs);
+        prune_map(&mut self.dummy_devices);
+        prune_map(&mut self.nm_devices);
+    }
+}
+
+fn canonicalize_common(common: &mut CommonPropertiesAllDevices, global_renderer: &Renderer) {
+    if common.renderer.as_ref() == Some(global_renderer) {
+        common.renderer = None;
+    }
+
+    for flag in [
+        &mut common.dhcp4,
+        &mut common.dhcp6,
+        &mut common.ignore_carrier,
+        &mut common.critical,
+        &mut common.optional,
+    ] {
+        prune_false(flag);
+    }
+
+    if let Some(addresses) = common.addresses.take() {
+        common.addresses = Some(
+            addresses
+                .into_iter()
+                .flat_map(canonical_addresses)
+                .collect(),
+        );
+    }
+
+    if let Some(macaddress) = common.macaddress.as_mut() {
+        *macaddress = normalize_mac(macaddress);
+    }
+
+    if let Some(nameservers) = common.nameservers.as_mut() {
+        prune_vec(&mut nameservers.addresses);
+        prune_vec(&mut nameservers.search);
+    }
+
+    for route in common.routes.iter_mut().flatten() {
+        prune_false(&mut route.on_link);
+
+        if route.to.as_deref() == Some("default") {
+            let via = route
+                .via
+                .as_deref()
+                .and_then(|via| via.parse::<IpAddr>().ok());
+            route.to = match via {
+                Some(IpAddr::V4(_)) => Some("0.0.0.0/0".to_string()),
+                Some(IpAddr::V6(_)) => Some("::/0".to_string()),
+                None => route.to.take(),
+            };
+        }
+    }
+
+    if let Some(networkmanager) = common.networkmanager.as_mut() {
+        prune(&mut networkmanager.passthrough);
+    }
+
+    prune_vec(&mut common.addresses);
+    prune_vec(&mut common.optional_addresses);
+    prune_vec(&mut common.routes);
+    prune_vec(&mut common.routing_policy);
+    prune(&mut common.nameservers);
+    prune(&mut common.dhcp4_overrides);
+    prune(&mut common.dhcp6_overrides);
+    prune(&mut common.networkmanager);
+}
+
+/// Split an address entry into one entry per address, using the short form for
+/// addresses without properties.
+fn canonical_addresses(mapping: AddressMapping) -> Vec<AddressMapping> {
+    let AddressMapping::Complex(addresses) = mapping else {
+        return vec![mapping];
+    };
+
+    let mut addresses = addresses.into_iter().collect::<Vec<_>>();
+    addresses.sort_by(|(a, _), (b, _)| a.cmp(b));
+    addresses
+        .into_iter()
+        .map(|(address, properties)| {
+            if properties == AddressProperties::default() {
+                AddressMapping::Simple(address)
+            } else {
+                AddressMapping::Complex(HashMap::from([(address, properties)]))
+            }
+        })
+        .collect()
+}
+
+fn canonicalize_physical(physical: &mut Option<Box<CommonPropertiesPhysicalDeviceType>>) {
+    if let Some(physical) = physical.as_deref_mut() {
+        if let Some(rules) = physical.r#match.as_mut() {
+            *rules = rules.normalized();
+        }
+
+        prune_false(&mut physical.wakeonlan);
+        prune(&mut physical.r#match);
+        prune(&mut physical.openvswitch);
+    }
+
+    prune(physical);
+}
+
+fn canonicalize_wifi(wifi: &mut WifiConfig) {
+    for access_point in wifi.access_points.iter_mut().flat_map(HashMap::values_mut) {
+        if access_point.mode == Some(AccessPointMode::Infrastructure) {
+            access_point.mode = None;
+        }
+
+        if let Some(bssid) = access_point.bssid.as_mut() {
+            *bssid = normalize_mac(bssid);
+        }
+
+        prune_false(&mut access_point.hidden);
+        prune(&mut access_point.auth);
+    }
+
+    prune_vec(&mut wifi.wakeonwlan);
+}
+
+fn canonicalize_tunnel(tunnel: &mut TunnelConfig) {
+    let wireguard = tunnel.mode == Some(TunnelMode::Wireguard);
+    let short = match &tunnel.key {
+        Some(TunnelKey::Complex {
+            input: None,
+            output: None,
+            private: Some(private),
+        }) if wireguard => Some(private.clone()),
+        Some(TunnelKey::Complex {
+            input: Some(input),
+            output: Some(output),
+            private: None,
+        }) if !wireguard && input == output => Some(input.as_str().into()),
+        _ => None,
+    };
+
+    if let Some(key) = short {
+        tunnel.key = Some(TunnelKey::Simple(key));
+    }
+}
+
+fn canonicalize_members(interfaces: &mut Option<Vec<String>>) {
+    if let Some(interfaces) = interfaces.as_mut() {
+        interfaces.sort();
+        interfaces.dedup();
+    }
+
+    prune_vec(interfaces);
+}
+
+/// Remove a flag which is disabled by default.
+fn prune_false(flag: &mut Option<bool>) {
+    if *flag == Some(false) {
+        *flag = None;
+    }
+}
+
+/// Remove a block with only default values.
+fn prune<T: Default + PartialEq>(value: &mut Option<T>) {
+    if value.as_ref().is_some_and(|value| *value == T::default()) {
+        *value = None;
+    }
+}
+
+fn prune_vec<T>(value: &mut Option<Vec<T>>) {
+    if value.as_ref().is_some_and(Vec::is_empty) {
+        *value = None;
+    }
+}
+
+fn prune_map<T>(value: &mut Option<HashMap<String, T>>) {
+    if value.as_ref().is_some_and(HashMap::is_empty) {
+        *value = None;
+    }
+}
+
+#[cfg(all(test, feature = "yaml"))]
+mod test {
+    use crate::NetplanConfig;
+
+    #[test]
+    fn semantically_equal() {
+        let a = r#"
+            network:
+              version: 2
+              renderer: NetworkManager
+              ethernets:
+                eth0:
+                  renderer: NetworkManager
+                  dhcp4: false
+                  macaddress: 00:11:22:AA:BB:CC
+                  addresses:
+                    - 10.0.0.2/24: {}
+                  match:
+                    driver: [ixgbe, e1000]
+              bridges:
+                br0:
+                  interfaces: [eth1, eth0]
+                  parameters:
+                    stp: true
+              tunnels:
+                gre0:
+                  mode: gre
+                  key:
+                    input: "1234"
+                    output: "1234"
+              vlans: {}
+            "#;
+        let b = r#"
+            network:
+              version: 2
+              renderer: NetworkManager
+              ethernets:
+                eth0:
+                  macaddress: 00:11:22:aa:bb:cc
+                  addresses: [10.0.0.2/24]
+                  match:
+                    driver: [e1000, ixgbe]
+              bridges:
+                br0:
+                  interfaces: [eth0, eth1]
+              tunnels:
+                gre0:
+                  mode: gre
+                  key: "1234"
+            "#;
+
+        let a: NetplanConfig = serde_yaml::from_str(a).unwrap();
+        let b: NetplanConfig = serde_yaml::from_str(b).unwrap();
+        assert!(a.semantically_eq(&b));
+
+        let c = NetplanConfig::build(|net| {
+            net.renderer(crate::Renderer::NetworkManager);
+            net.ethernet("eth0", |e| e.address("10.0.0.3/24"));
+        });
+        assert!(!a.semantically_eq(&c));
+    }
+
+    #[test]
+    fn default_routes() {
+        let a = r#"
+            network:
+              version: 2
+              ethernets:
+                eth0:
+                  routes:
+                    - to: default
+                      via: 10.0.0.1
+                    - to: default
+                      via: 2001:db8::1
+            "#;
+        let b = r#"
+            network:
+              version: 2
+              ethernets:
+                eth0:
+                  routes:
+                    - to: 0.0.0.0/0
+                      via: 10.0.0.1
+                    - to: ::/0
+                      via: 2001:db8::1
+            "#;
+
+        let a: NetplanConfig = serde_yaml::from_str(a).unwrap();
+        let b: NetplanConfig = serde_yaml::from_str(b).unwrap();
+        assert!(a.semantically_eq(&b));
+    }
+}
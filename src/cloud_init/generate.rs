@@ -0,0 +1,392 @@
+//! Rendering configurations into cloud-init network configurations.
+
+use crate::cloud_init::{
+    CloudInitEntry, CloudInitInterface, CloudInitNameserver, CloudInitNetworkConfig,
+    CloudInitRoute, CloudInitSubnet,
+};
+use crate::ifupdown::controller_options;
+use crate::{
+    ActivationMode, AnyDeviceConfig, CommonPropertiesAllDevices, DeviceKind, NetplanConfig,
+};
+use std::collections::BTreeMap;
+
+impl NetplanConfig {
+    /// Render the configuration into a cloud-init network configuration in version 1 of
+    /// its format, for images and datasources which only accept that version.
+    ///
+    /// Ethernets become physical interfaces, named after the name they are set to, with the
+    /// MAC address they are matched by, and bonds, bridges and VLANs become the interfaces
+    /// of the same kind, with the parameters of bonds and bridges written like the options
+    /// of an ifupdown interfaces file. DHCP and router advertisements become a subnet of
+    /// the matching type, and every address a static subnet. The default route and DNS
+    /// servers of an address family are set on its first static subnet, as are the other
+    /// routes, which become global routes on devices without one.
+    ///
+    /// The format has no equivalent for other kinds of devices, like wifis or tunnels, or
+    /// for settings like routing policy or DHCP overrides, which are left out. The
+    /// configuration can be imported again with [NetplanConfig::from_cloud_init_v1].
+    ///
+    /// ```
+    /// use netplan_types::{CloudInitEntry, NetplanConfig};
+    ///
+    /// let config = NetplanConfig::build(|net| {
+    ///     net.ethernet("eth0", |e| {
+    ///         e.address("192.168.1.10/24").route("default", "192.168.1.1")
+    ///     });
+    /// });
+    ///
+    /// let cloud_init = config.to_cloud_init_v1();
+    /// let CloudInitEntry::Physical(eth0) = &cloud_init.config[0] else { panic!() };
+    /// assert_eq!(eth0.subnets[0].address.as_deref(), Some("192.168.1.10/24"));
+    /// assert_eq!(eth0.subnets[0].gateway.as_deref(), Some("192.168.1.1"));
+    /// assert!(cloud_init.to_yaml().contains("type: physical"));
+    /// ```
+    pub fn to_cloud_init_v1(&self) -> CloudInitNetworkConfig {
+        // Members and links are defined before the devices using them
+        let mut devices = self.network.devices().collect::<Vec<_>>();
+        devices.sort_by_key(|(kind, name, _)| {
+            let rank = match kind {
+                DeviceKind::Ethernet => 0,
+                DeviceKind::Bond => 1,
+                DeviceKind::Vlan => 2,
+                DeviceKind::Bridge => 3,
+                _ => 4,
+            };
+            (rank, *name)
+        });
+
+        let mut cloud_init = CloudInitNetworkConfig::default();
+        let mut globals = Vec::new();
+        for (_, name, device) in devices {
+            let mut interface = CloudInitInterface {
+                name: name.to_string(),
+                ..Default::default()
+            };
+            let entry = match device {
+                AnyDeviceConfig::Ethernet(ethernet) => {
+                    let physical = ethernet.common_physical.as_deref();
+                    if let Some(set_name) = physical.and_then(|p| p.set_name.as_ref()) {
+                        interface.name = set_name.clone();
+                    }
+                    interface.mac_address = physical
+                        .and_then(|p| p.r#match.as_ref())
+                        .and_then(|m| m.macaddress.as_ref())
+                        .or(device.common_all().and_then(|c| c.macaddress.as_ref()))
+                        .map(|mac| mac.to_string());
+                    CloudInitEntry::Physical
+                }
+                AnyDeviceConfig::Bond(bond) => {
+                    interface.bond_interfaces = bond.interfaces.clone();
+                    interface.params = params(&device, "bond-slaves", false);
+                    CloudInitEntry::Bond
+                }
+                AnyDeviceConfig::Bridge(bridge) => {
+                    interface.bridge_interfaces = bridge.interfaces.clone();
+                    interface.params = params(&device, "bridge-ports", true);
+                    CloudInitEntry::Bridge
+                }
+                AnyDeviceConfig::Vlan(vlan) => {
+                    interface.vlan_link = vlan.link.clone();
+                    interface.vlan_id = vlan.id;
+                    CloudInitEntry::Vlan
+                }
+                _ => continue,
+            };
+
+            if let Some(common) = device.common_all() {
+                interface.mtu = common.mtu;
+                interface.subnets = subnets(common);
+                // Default routes are set as the gateways of subnets, if there is one
+                let routes = routes(common, None)
+                    .into_iter()
+                    .filter(|route| {
+                        !interface.subnets.iter().any(|subnet| {
+                            subnet.gateway.is_some()
+                                && subnet.gateway == route.gateway
+                                && matches!(
+                                    route.destination.as_deref(),
+                                    Some("0.0.0.0/0" | "::/0")
+                                )
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                match interface.subnets.iter_mut().find(|s| is_static(s)) {
+                    Some(subnet) if !routes.is_empty() => subnet.routes = Some(routes),
+                    Some(_) => {}
+                    None => globals.extend(routes.into_iter().map(CloudInitEntry::Route)),
+                }
+                if let Some(nameservers) = &common.nameservers {
+                    let address = nameservers.addresses.clone().unwrap_or_default();
+                    let search = nameservers.search.clone().unwrap_or_default();
+                    match interface.subnets.iter_mut().find(|s| is_static(s)) {
+                        Some(subnet) => {
+                            subnet.dns_nameservers = (!address.is_empty()).then_some(address);
+                            subnet.dns_search = (!search.is_empty()).then_some(search);
+                        }
+                        None if !address.is_empty() || !search.is_empty() => {
+                            globals.push(CloudInitEntry::Nameserver(CloudInitNameserver {
+                                address,
+                                search,
+                                interface: Some(interface.name.clone()),
+                            }))
+                        }
+                        None => {}
+                    }
+                }
+            }
+            cloud_init.config.push(entry(interface));
+        }
+
+        cloud_init.config.extend(globals);
+        cloud_init
+    }
+}
+
+/// The parameters of a bond or bridge, as the options of an ifupdown interfaces file
+/// without the option listing its members. bridge-utils options are spelled with
+/// underscores, as in cloud-init's documentation.
+fn params(
+    device: &AnyDeviceConfig<'_>,
+    members: &str,
+    underscores: bool,
+) -> Option<BTreeMap<String, serde_yaml::Value>> {
+    let params = controller_options(device)
+        .into_iter()
+        .filter(|(key, _)| key != members)
+        .map(|(key, value)| {
+            let key = if underscores {
+                key.replace('-', "_")
+            } else {
+                key
+            };
+            let value = match value.parse::<u64>() {
+                Ok(number) => serde_yaml::Value::from(number),
+                Err(_) => serde_yaml::Value::from(value),
+            };
+            (key, value)
+        })
+        .collect::<BTreeMap<_, _>>();
+    (!params.is_empty()).then_some(params)
+}
+
+fn is_static(subnet: &CloudInitSubnet) -> bool {
+    matches!(subnet.r#type.as_str(), "static" | "static6")
+}
+
+/// A subnet per address, and per kind of dynamic addressing.
+fn subnets(common: &CommonPropertiesAllDevices) -> Vec<CloudInitSubnet> {
+    let mut types = Vec::new();
+    if common.dhcp4 == Some(true) {
+        types.push("dhcp4");
+    }
+    match (common.dhcp6 == Some(true), common.accept_ra == Some(true)) {
+        (true, true) => types.push("ipv6_dhcpv6-stateless"),
+        (true, false) => types.push("dhcp6"),
+        (false, true) => types.push("ipv6_slaac"),
+        (false, false) => {}
+    }
+    let mut subnets = types
+        .into_iter()
+        .map(|r#type| CloudInitSubnet {
+            r#type: r#type.to_string(),
+            ..Default::default()
+        })
+        .collect::<Vec<_>>();
+
+    for mapping in common.addresses.iter().flatten() {
+        for address in mapping.addresses() {
+            let ipv6 = address.contains(':');
+            let gateway = routes(common, Some(ipv6))
+                .into_iter()
+                .find(|route| route.destination.as_deref() == Some(default(ipv6)))
+                .and_then(|route| route.gateway);
+            let first = !subnets.iter().any(|s| s.r#type == family(ipv6));
+            subnets.push(CloudInitSubnet {
+                r#type: family(ipv6).to_string(),
+                address: Some(address.to_string()),
+                gateway: gateway.filter(|_| first),
+                ..Default::default()
+            });
+        }
+    }
+
+    if common.activation_mode == Some(ActivationMode::Manual) {
+        for subnet in &mut subnets {
+            subnet.control = Some("manual".to_string());
+        }
+    }
+    subnets
+}
+
+/// The type of the static subnets of an address family.
+fn family(ipv6: bool) -> &'static str {
+    if ipv6 {
+        "static6"
+    } else {
+        "static"
+    }
+}
+
+/// The destination of the default route of an address family.
+fn default(ipv6: bool) -> &'static str {
+    if ipv6 {
+        "::/0"
+    } else {
+        "0.0.0.0/0"
+    }
+}
+
+/// The routes of a device, of one address family or all of them. The family of a route is
+/// that of its gateway or destination.
+fn routes(common: &CommonPropertiesAllDevices, ipv6: Option<bool>) -> Vec<CloudInitRoute> {
+    let gateways = [(false, &common.gateway4), (true, &common.gateway6)]
+        .into_iter()
+        .filter_map(|(ipv6, gateway)| {
+            Some(CloudInitRoute {
+                destination: Some(default(ipv6).to_string()),
+                gateway: Some(gateway.clone()?),
+                ..Default::default()
+            })
+        });
+    let routes = common.routes.iter().flatten().filter_map(|route| {
+        let family = route.via.as_deref().or(route.to.as_deref())?.contains(':');
+        let destination = match route.to.as_deref() {
+            Some("default") | None => default(family).to_string(),
+            Some(to) => to.to_string(),
+        };
+        Some(CloudInitRoute {
+            destination: Some(destination),
+            gateway: route.via.clone(),
+            metric: route.metric,
+            ..Default::default()
+        })
+    });
+    gateways
+        .chain(routes)
+        .filter(|route| {
+            let family = route
+                .gateway
+                .as_deref()
+                .or(route.destination.as_deref())
+                .is_some_and(|address| address.contains(':'));
+            ipv6.is_none_or(|ipv6| ipv6 == family)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{CloudInitNetworkConfig, NetplanConfig};
+
+    #[test]
+    fn export_cloud_init() {
+        let config = NetplanConfig::from_yaml(
+            r#"
+network:
+  version: 2
+  ethernets:
+    eno1:
+      match: {macaddress: "52:54:00:12:34:01"}
+      set-name: eno1
+    eno2: {}
+  bonds:
+    bond0:
+      interfaces: [eno1, eno2]
+      parameters:
+        mode: 802.3ad
+        mii-monitor-interval: "100"
+      addresses: [10.0.0.10/24, "2001:db8::10/64"]
+      routes:
+        - to: default
+          via: 10.0.0.1
+        - to: 10.1.0.0/16
+          via: 10.0.0.254
+          metric: 100
+      nameservers:
+        addresses: [10.0.0.53]
+  vlans:
+    bond0.20:
+      id: 20
+      link: bond0
+      dhcp4: true
+      activation-mode: manual
+      routes:
+        - to: 10.2.0.0/16
+          via: 10.20.0.1
+  bridges:
+    br0:
+      interfaces: [eno2]
+      parameters: {stp: false}
+      dhcp6: true
+      accept-ra: true
+      nameservers:
+        search: [example.com]
+  tunnels:
+    wg0:
+      mode: wireguard
+"#,
+        )
+        .unwrap();
+
+        let expected = CloudInitNetworkConfig::from_yaml(
+            r#"
+version: 1
+config:
+  - type: physical
+    name: eno1
+    mac_address: "52:54:00:12:34:01"
+  - type: physical
+    name: eno2
+  - type: bond
+    name: bond0
+    bond_interfaces: [eno1, eno2]
+    params:
+      bond-miimon: 100
+      bond-mode: 802.3ad
+    subnets:
+      - type: static
+        address: 10.0.0.10/24
+        gateway: 10.0.0.1
+        dns_nameservers: [10.0.0.53]
+        routes:
+          - destination: 10.1.0.0/16
+            gateway: 10.0.0.254
+            metric: 100
+      - type: static6
+        address: "2001:db8::10/64"
+  - type: vlan
+    name: bond0.20
+    vlan_link: bond0
+    vlan_id: 20
+    subnets:
+      - type: dhcp4
+        control: manual
+  - type: bridge
+    name: br0
+    bridge_interfaces: [eno2]
+    params:
+      bridge_stp: "off"
+    subnets:
+      - type: ipv6_dhcpv6-stateless
+  - type: route
+    destination: 10.2.0.0/16
+    gateway: 10.20.0.1
+  - type: nameserver
+    search: [example.com]
+    interface: br0
+"#,
+        )
+        .unwrap();
+        let cloud_init = config.to_cloud_init_v1();
+        assert_eq!(cloud_init, expected);
+
+        let yaml = cloud_init.to_yaml();
+        let imported =
+            NetplanConfig::from_cloud_init_v1(&CloudInitNetworkConfig::from_yaml(&yaml).unwrap());
+        let bond0 = &imported.network.bonds.unwrap()["bond0"];
+        assert_eq!(
+            Some(bond0),
+            config.network.bonds.as_ref().map(|b| &b["bond0"])
+        );
+    }
+}
@@ -0,0 +1,457 @@
+//! Importing cloud-init network configurations into configurations.
+
+use crate::cloud_init::{
+    CloudInitEntry, CloudInitInterface, CloudInitNetworkConfig, CloudInitRoute, CloudInitSubnet,
+};
+use crate::ifupdown::{define_from_options, ethernet_by_mac, prefix_length};
+use crate::{
+    ActivationMode, AddressMapping, CommonProperties, CommonPropertiesAllDevices, EthernetConfig,
+    NameserverConfig, NetplanConfig, NetworkConfig, RoutingConfig,
+};
+use ipnet::IpNet;
+use std::collections::HashSet;
+use std::net::IpAddr;
+
+impl NetplanConfig {
+    /// Lift a cloud-init network configuration in version 1 of its format into a
+    /// configuration, the inverse of [NetplanConfig::to_cloud_init_v1].
+    ///
+    /// Physical interfaces become ethernets, matched by their MAC address and renamed to
+    /// their name if they have one, and bonds, bridges and VLANs become the devices of the
+    /// same kind, with their parameters read like the options of an ifupdown interfaces
+    /// file. The subnets of an interface become its DHCP settings, addresses, default
+    /// routes, routes and nameservers, and a subnet with `control: manual` makes its
+    /// device manually activated.
+    ///
+    /// Global nameservers are added to the interface they name, or to every device with
+    /// addressing. Global routes are added to the device with a static subnet containing
+    /// their gateway, and left out if there is none. Other kinds of entries, like
+    /// `infiniband`, are left out.
+    ///
+    /// ```
+    /// use netplan_types::{CloudInitNetworkConfig, NetplanConfig};
+    ///
+    /// let cloud_init = CloudInitNetworkConfig::from_yaml("
+    /// version: 1
+    /// config:
+    ///   - type: physical
+    ///     name: eth0
+    ///     subnets:
+    ///       - type: static
+    ///         address: 192.168.1.10
+    ///         netmask: 255.255.255.0
+    ///         gateway: 192.168.1.1
+    /// ").unwrap();
+    ///
+    /// let expected = NetplanConfig::build(|net| {
+    ///     net.ethernet("eth0", |e| {
+    ///         e.address("192.168.1.10/24").route("default", "192.168.1.1")
+    ///     });
+    /// });
+    /// assert_eq!(NetplanConfig::from_cloud_init_v1(&cloud_init), expected);
+    /// ```
+    pub fn from_cloud_init_v1(cloud_init: &CloudInitNetworkConfig) -> Self {
+        let mut config = NetplanConfig::default();
+        let network = &mut config.network;
+        let mut dependencies = HashSet::new();
+
+        for entry in &cloud_init.config {
+            let (interface, mut options) = match entry {
+                CloudInitEntry::Physical(interface) => {
+                    let ethernet =
+                        ethernet_by_mac(&interface.name, interface.mac_address.as_deref());
+                    network.add_ethernet(interface.name.as_str(), ethernet);
+                    import_interface(network, interface);
+                    continue;
+                }
+                CloudInitEntry::Bond(interface) => {
+                    let slaves = interface.bond_interfaces.as_deref().unwrap_or_default();
+                    (interface, vec![("bond-slaves".to_string(), words(slaves))])
+                }
+                CloudInitEntry::Bridge(interface) => {
+                    let ports = interface.bridge_interfaces.as_deref().unwrap_or_default();
+                    (interface, vec![("bridge-ports".to_string(), words(ports))])
+                }
+                CloudInitEntry::Vlan(interface) => {
+                    let mut options = Vec::new();
+                    if let Some(link) = &interface.vlan_link {
+                        options.push(("vlan-raw-device".to_string(), link.clone()));
+                    }
+                    if let Some(id) = interface.vlan_id {
+                        options.push(("vlan-id".to_string(), id.to_string()));
+                    }
+                    (interface, options)
+                }
+                _ => continue,
+            };
+
+            for (key, value) in interface.params.iter().flatten() {
+                if let Some(value) = param(value) {
+                    options.push((key.clone(), value));
+                }
+            }
+            dependencies.extend(define_from_options(network, &interface.name, options));
+            import_interface(network, interface);
+        }
+
+        for dependency in &dependencies {
+            if network.device(dependency).is_none() {
+                network.add_ethernet(dependency.as_str(), EthernetConfig::default());
+            }
+        }
+
+        for entry in &cloud_init.config {
+            match entry {
+                CloudInitEntry::Nameserver(nameserver) => {
+                    let names = match &nameserver.interface {
+                        Some(name) => vec![name.clone()],
+                        None => addressed(network),
+                    };
+                    for name in names {
+                        if let Some(mut device) = network.device_mut(&name) {
+                            add_nameservers(
+                                device.common_all_mut(),
+                                &nameserver.address,
+                                &nameserver.search,
+                            );
+                        }
+                    }
+                }
+                CloudInitEntry::Route(route) => {
+                    let Some(name) = route_interface(cloud_init, route) else {
+                        continue;
+                    };
+                    if let Some(mut device) = network.device_mut(name) {
+                        let common = device.common_all_mut();
+                        common
+                            .routes
+                            .get_or_insert_with(Vec::new)
+                            .push(import_route(route));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        config
+    }
+}
+
+/// A list of interfaces as the value of an ifupdown option.
+fn words(interfaces: &[String]) -> String {
+    if interfaces.is_empty() {
+        "none".to_string()
+    } else {
+        interfaces.join(" ")
+    }
+}
+
+/// A parameter of a bond or bridge as the value of an ifupdown option.
+fn param(value: &serde_yaml::Value) -> Option<String> {
+    match value {
+        serde_yaml::Value::String(value) => Some(value.clone()),
+        serde_yaml::Value::Number(value) => Some(value.to_string()),
+        serde_yaml::Value::Bool(value) => Some(if *value { "on" } else { "off" }.to_string()),
+        serde_yaml::Value::Sequence(values) => Some(
+            values
+                .iter()
+                .filter_map(param)
+                .collect::<Vec<_>>()
+                .join(" "),
+        ),
+        _ => None,
+    }
+}
+
+/// Import the MTU and subnets of an interface into its device.
+fn import_interface(network: &mut NetworkConfig, interface: &CloudInitInterface) {
+    let mut common = CommonPropertiesAllDevices {
+        mtu: interface.mtu,
+        ..Default::default()
+    };
+    for subnet in &interface.subnets {
+        import_subnet(&mut common, subnet);
+    }
+    if common == CommonPropertiesAllDevices::default() {
+        return;
+    }
+    if let Some(mut device) = network.device_mut(&interface.name) {
+        *device.common_all_mut() = common;
+    }
+}
+
+fn import_subnet(common: &mut CommonPropertiesAllDevices, subnet: &CloudInitSubnet) {
+    match subnet.r#type.as_str() {
+        "dhcp" | "dhcp4" => common.dhcp4 = Some(true),
+        "dhcp6" | "ipv6_dhcpv6-stateful" => common.dhcp6 = Some(true),
+        "ipv6_dhcpv6-stateless" => {
+            common.dhcp6 = Some(true);
+            common.accept_ra = Some(true);
+        }
+        "ipv6_slaac" => common.accept_ra = Some(true),
+        "static" | "static6" => {
+            if let Some(address) = &subnet.address {
+                let ipv6 = address.contains(':');
+                let netmask = subnet.netmask.as_deref().and_then(prefix_length);
+                let address = match (address.contains('/'), netmask) {
+                    (true, _) => address.clone(),
+                    (false, Some(prefix)) => format!("{address}/{prefix}"),
+                    (false, None) if ipv6 => format!("{address}/128"),
+                    (false, None) => format!("{address}/32"),
+                };
+                common
+                    .addresses
+                    .get_or_insert_with(Vec::new)
+                    .push(AddressMapping::Simple(address));
+            }
+            if let Some(gateway) = &subnet.gateway {
+                common
+                    .routes
+                    .get_or_insert_with(Vec::new)
+                    .push(RoutingConfig {
+                        to: Some("default".to_string()),
+                        via: Some(gateway.clone()),
+                        ..Default::default()
+                    });
+            }
+        }
+        _ => {}
+    }
+
+    if subnet.control.as_deref() == Some("manual") {
+        common.activation_mode = Some(ActivationMode::Manual);
+    }
+    for route in subnet.routes.iter().flatten() {
+        common
+            .routes
+            .get_or_insert_with(Vec::new)
+            .push(import_route(route));
+    }
+    add_nameservers(
+        common,
+        subnet.dns_nameservers.as_deref().unwrap_or_default(),
+        subnet.dns_search.as_deref().unwrap_or_default(),
+    );
+}
+
+pub(crate) fn add_nameservers(
+    common: &mut CommonPropertiesAllDevices,
+    addresses: &[String],
+    search: &[String],
+) {
+    if addresses.is_empty() && search.is_empty() {
+        return;
+    }
+    let nameservers = common
+        .nameservers
+        .get_or_insert_with(NameserverConfig::default);
+    for (list, values) in [
+        (&mut nameservers.addresses, addresses),
+        (&mut nameservers.search, search),
+    ] {
+        for value in values {
+            let list = list.get_or_insert_with(Vec::new);
+            if !list.contains(value) {
+                list.push(value.clone());
+            }
+        }
+    }
+}
+
+/// The destination of a route, in CIDR notation, or `default`.
+fn destination(route: &CloudInitRoute) -> Option<String> {
+    let destination = match (&route.destination, &route.network) {
+        (Some(destination), _) => destination.clone(),
+        (None, Some(network)) => match route.netmask.as_deref().and_then(prefix_length) {
+            Some(prefix) => format!("{network}/{prefix}"),
+            None => network.clone(),
+        },
+        (None, None) => return None,
+    };
+    match destination.as_str() {
+        "0.0.0.0/0" | "::/0" => Some("default".to_string()),
+        _ => Some(destination),
+    }
+}
+
+fn import_route(route: &CloudInitRoute) -> RoutingConfig {
+    RoutingConfig {
+        to: destination(route),
+        via: route.gateway.clone(),
+        metric: route.metric,
+        ..Default::default()
+    }
+}
+
+/// The names of the devices with addresses or DHCP enabled.
+pub(crate) fn addressed(network: &NetworkConfig) -> Vec<String> {
+    network
+        .devices()
+        .filter(|(_, _, device)| {
+            device.common_all().is_some_and(|common| {
+                common.dhcp4 == Some(true)
+                    || common.dhcp6 == Some(true)
+                    || common.addresses.as_ref().is_some_and(|a| !a.is_empty())
+            })
+        })
+        .map(|(_, name, _)| name.to_string())
+        .collect()
+}
+
+/// The name of the interface with a static subnet containing the gateway of a route.
+fn route_interface<'a>(
+    cloud_init: &'a CloudInitNetworkConfig,
+    route: &CloudInitRoute,
+) -> Option<&'a str> {
+    let gateway = route.gateway.as_deref()?.parse::<IpAddr>().ok()?;
+    cloud_init.config.iter().find_map(|entry| {
+        let (CloudInitEntry::Physical(interface)
+        | CloudInitEntry::Bond(interface)
+        | CloudInitEntry::Bridge(interface)
+        | CloudInitEntry::Vlan(interface)) = entry
+        else {
+            return None;
+        };
+        let contains = interface.subnets.iter().any(|subnet| {
+            let Some(address) = &subnet.address else {
+                return false;
+            };
+            let network = match (address.contains('/'), &subnet.netmask) {
+                (false, Some(netmask)) => prefix_length(netmask)
+                    .map(|prefix| format!("{address}/{prefix}"))
+                    .unwrap_or_default(),
+                _ => address.clone(),
+            };
+            network
+                .parse::<IpNet>()
+                .is_ok_and(|network| network.contains(&gateway))
+        });
+        contains.then_some(interface.name.as_str())
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{CloudInitNetworkConfig, NetplanConfig};
+
+    #[test]
+    fn import_cloud_init() {
+        let cloud_init = CloudInitNetworkConfig::from_yaml(
+            r#"
+network:
+  version: 1
+  config:
+    - type: physical
+      name: eno1
+      mac_address: "52:54:00:12:34:01"
+    - type: physical
+      name: eno2
+      mac_address: "52:54:00:12:34:02"
+      mtu: 9000
+    - type: bond
+      name: bond0
+      bond_interfaces: [eno1, eno2]
+      params:
+        bond-mode: 802.3ad
+        bond-miimon: 100
+        bond_xmit_hash_policy: layer3+4
+      subnets:
+        - type: static
+          address: 10.0.0.10
+          netmask: 255.255.255.0
+          gateway: 10.0.0.1
+          dns_nameservers: [10.0.0.53]
+        - type: ipv6_slaac
+    - type: vlan
+      name: bond0.20
+      vlan_link: bond0
+      vlan_id: 20
+      subnets:
+        - type: dhcp
+          control: manual
+    - type: bridge
+      name: br0
+      bridge_interfaces: [eno3]
+      params:
+        bridge_stp: off
+        bridge_fd: 0
+      subnets:
+        - type: static6
+          address: "2001:db8::10/64"
+          routes:
+            - destination: "2001:db8:1::/48"
+              gateway: "2001:db8::1"
+    - type: nameserver
+      address: [1.1.1.1]
+      search: [example.com]
+    - type: route
+      network: 10.1.0.0
+      netmask: 255.255.0.0
+      gateway: 10.0.0.254
+      metric: 100
+    - type: infiniband
+      name: ib0
+"#,
+        )
+        .unwrap();
+
+        let expected = NetplanConfig::from_yaml(
+            r#"
+network:
+  version: 2
+  ethernets:
+    eno1:
+      match: {macaddress: "52:54:00:12:34:01"}
+      set-name: eno1
+    eno2:
+      match: {macaddress: "52:54:00:12:34:02"}
+      set-name: eno2
+      mtu: 9000
+    eno3: {}
+  bonds:
+    bond0:
+      interfaces: [eno1, eno2]
+      parameters:
+        mode: 802.3ad
+        mii-monitor-interval: "100"
+        transmit-hash-policy: layer3+4
+      addresses: [10.0.0.10/24]
+      accept-ra: true
+      routes:
+        - to: default
+          via: 10.0.0.1
+        - to: 10.1.0.0/16
+          via: 10.0.0.254
+          metric: 100
+      nameservers:
+        addresses: [10.0.0.53, 1.1.1.1]
+        search: [example.com]
+  vlans:
+    bond0.20:
+      id: 20
+      link: bond0
+      dhcp4: true
+      activation-mode: manual
+      nameservers:
+        addresses: [1.1.1.1]
+        search: [example.com]
+  bridges:
+    br0:
+      interfaces: [eno3]
+      parameters:
+        stp: false
+        forward-delay: "0"
+      addresses: ["2001:db8::10/64"]
+      routes:
+        - to: "2001:db8:1::/48"
+          via: "2001:db8::1"
+      nameservers:
+        addresses: [1.1.1.1]
+        search: [example.com]
+"#,
+        )
+        .unwrap();
+        let imported = NetplanConfig::from_cloud_init_v1(&cloud_init);
+        assert!(imported.semantically_eq(&expected), "{imported:#?}");
+    }
+}
@@ -0,0 +1,167 @@
+//! Converting cloud-init's network configuration version 1, which some clouds still provide.
+
+mod generate;
+mod import;
+pub(crate) use import::{add_nameservers, addressed};
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// A cloud-init network configuration in version 1 of its format: a list of interfaces,
+/// nameservers and routes. Version 2 of the format is netplan's own, and is read as a
+/// [NetplanConfig](crate::NetplanConfig).
+///
+/// ```
+/// use netplan_types::{CloudInitEntry, CloudInitNetworkConfig};
+///
+/// let config = CloudInitNetworkConfig::from_yaml("
+/// network:
+///   version: 1
+///   config:
+///     - type: physical
+///       name: eth0
+///       mac_address: '52:54:00:12:34:00'
+///       subnets:
+///         - type: dhcp
+/// ").unwrap();
+///
+/// let CloudInitEntry::Physical(eth0) = &config.config[0] else { panic!() };
+/// assert_eq!(eth0.mac_address.as_deref(), Some("52:54:00:12:34:00"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CloudInitNetworkConfig {
+    /// The version of the format, `1`.
+    pub version: u8,
+    #[serde(default)]
+    pub config: Vec<CloudInitEntry>,
+}
+
+impl Default for CloudInitNetworkConfig {
+    fn default() -> Self {
+        Self {
+            version: 1,
+            config: Vec::new(),
+        }
+    }
+}
+
+/// An entry of a [CloudInitNetworkConfig], tagged with its `type`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum CloudInitEntry {
+    Physical(CloudInitInterface),
+    Bond(CloudInitInterface),
+    Bridge(CloudInitInterface),
+    Vlan(CloudInitInterface),
+    Nameserver(CloudInitNameserver),
+    Route(CloudInitRoute),
+    /// An entry of a type netplan has no equivalent for, like `infiniband`.
+    #[serde(other)]
+    Other,
+}
+
+/// An interface of a [CloudInitNetworkConfig]. Which fields apply depends on the type of
+/// the entry, e.g. `bond_interfaces` only applies to bonds.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CloudInitInterface {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mac_address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mtu: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bond_interfaces: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bridge_interfaces: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vlan_link: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vlan_id: Option<u16>,
+    /// The parameters of a bond or bridge, named like the ifupdown options, e.g. `bond-mode`
+    /// or `bridge_stp`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub params: Option<BTreeMap<String, serde_yaml::Value>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub subnets: Vec<CloudInitSubnet>,
+}
+
+/// The addressing of a [CloudInitInterface].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CloudInitSubnet {
+    /// How the interface is addressed: `dhcp4` (or `dhcp`), `dhcp6`, `static`, `static6`,
+    /// `ipv6_dhcpv6-stateful`, `ipv6_dhcpv6-stateless`, `ipv6_slaac` or `manual`.
+    pub r#type: String,
+    /// When the interface is brought up: `auto`, `hotplug` or `manual`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub control: Option<String>,
+    /// The address of a static subnet, with or without a prefix length.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+    /// The netmask of an address without a prefix length, dotted or as a prefix length.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub netmask: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gateway: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dns_nameservers: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dns_search: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub routes: Option<Vec<CloudInitRoute>>,
+}
+
+/// The global DNS servers and search domains, or those of a single interface.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CloudInitNameserver {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub address: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub search: Vec<String>,
+    /// The interface the nameservers apply to, all interfaces if it is not set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interface: Option<String>,
+}
+
+/// A route, either of a subnet or global.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CloudInitRoute {
+    /// The destination, in CIDR notation.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub destination: Option<String>,
+    /// The destination address, with `netmask`, as written by older versions of cloud-init.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub netmask: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gateway: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metric: Option<u16>,
+}
+
+impl CloudInitNetworkConfig {
+    /// Parse a configuration from YAML, either on its own or under the `network` key, as in
+    /// cloud-init's `network-config` files.
+    pub fn from_yaml(yaml: &str) -> Result<Self, serde_yaml::Error> {
+        #[derive(Deserialize)]
+        struct Nested {
+            network: CloudInitNetworkConfig,
+        }
+
+        match serde_yaml::from_str::<Nested>(yaml) {
+            Ok(nested) => Ok(nested.network),
+            Err(_) => serde_yaml::from_str(yaml),
+        }
+    }
+
+    /// Serialize the configuration to YAML, under the `network` key.
+    pub fn to_yaml(&self) -> String {
+        #[derive(Serialize)]
+        struct Nested<'a> {
+            network: &'a CloudInitNetworkConfig,
+        }
+
+        // Serializing the configuration types cannot fail, all map keys are strings
+        serde_yaml::to_string(&Nested { network: self }).expect("Serializing configuration")
+    }
+}
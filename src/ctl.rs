@@ -0,0 +1,416 @@
+//! Running the `netplan` command line tool.
+
+use crate::{NetplanConfig, NetplanStatus};
+use serde::{Deserialize, Serialize};
+use std::ffi::OsString;
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+#[cfg(unix)]
+use std::process::{Child, Stdio};
+use std::process::{Command, ExitStatus, Output};
+#[cfg(unix)]
+use std::time::Duration;
+
+/// The error returned when a `netplan` command fails.
+#[derive(Debug)]
+pub enum NetplanError {
+    /// The `netplan` binary could not be run.
+    Spawn(std::io::Error),
+    /// The command exited unsuccessfully.
+    Failed {
+        /// The command line, e.g. `netplan generate`.
+        command: String,
+        status: ExitStatus,
+        /// The error output of the command, with surrounding whitespace removed.
+        stderr: String,
+    },
+    /// The output of the command could not be parsed.
+    Output(serde_yaml::Error),
+}
+
+impl Display for NetplanError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Spawn(e) => write!(f, "Failed to run netplan: {e}"),
+            Self::Failed {
+                command,
+                status,
+                stderr,
+            } => write!(f, "'{command}' failed with {status}: {stderr}"),
+            Self::Output(e) => write!(f, "Invalid output from netplan: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for NetplanError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Spawn(e) => Some(e),
+            Self::Failed { .. } => None,
+            Self::Output(e) => Some(e),
+        }
+    }
+}
+
+impl From<serde_yaml::Error> for NetplanError {
+    fn from(e: serde_yaml::Error) -> Self {
+        Self::Output(e)
+    }
+}
+
+/// Information about the installed netplan, see [Netplan::info].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct NetplanInfo {
+    pub website: Option<String>,
+    /// The optional features supported by this release, e.g. `dhcp-use-domains`.
+    #[serde(default)]
+    pub features: Vec<String>,
+}
+
+impl NetplanInfo {
+    /// Whether the installed netplan supports the feature.
+    pub fn has_feature(&self, feature: &str) -> bool {
+        self.features.iter().any(|f| f == feature)
+    }
+}
+
+/// Runs the `netplan` command line tool.
+///
+/// ```no_run
+/// use netplan_types::{Netplan, NetplanConfig};
+///
+/// let config = NetplanConfig::single_nic_dhcp("eth0");
+/// config.write_to("/etc/netplan/50-provisioning.yaml").unwrap();
+///
+/// let netplan = Netplan::new();
+/// netplan.generate().unwrap();
+/// netplan.apply().unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Netplan {
+    binary: PathBuf,
+    root_dir: Option<PathBuf>,
+}
+
+impl Default for Netplan {
+    fn default() -> Self {
+        Self {
+            binary: PathBuf::from("netplan"),
+            root_dir: None,
+        }
+    }
+}
+
+impl Netplan {
+    /// Run the `netplan` binary found in `PATH`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Run the netplan binary at `binary`.
+    pub fn with_binary(mut self, binary: impl Into<PathBuf>) -> Self {
+        self.binary = binary.into();
+        self
+    }
+
+    /// Operate on the configuration below `root_dir` rather than `/`, passed as `--root-dir`.
+    /// Not supported by `netplan apply`, which always applies the configuration of the system.
+    pub fn with_root_dir(mut self, root_dir: impl Into<PathBuf>) -> Self {
+        self.root_dir = Some(root_dir.into());
+        self
+    }
+
+    /// Generate the configuration of the renderers, `netplan generate`.
+    pub fn generate(&self) -> Result<(), NetplanError> {
+        self.run("generate", [], true)?;
+        Ok(())
+    }
+
+    /// Apply the configuration to the running system, `netplan apply`.
+    pub fn apply(&self) -> Result<(), NetplanError> {
+        self.run("apply", [], false)?;
+        Ok(())
+    }
+
+    /// Get a setting of the merged configuration, `netplan get <key>`, e.g.
+    /// `ethernets.eth0.dhcp4`. Returns [serde_yaml::Value::Null] if the setting is not set.
+    pub fn get(&self, key: &str) -> Result<serde_yaml::Value, NetplanError> {
+        let output = self.run("get", [key.into()], true)?;
+        Ok(serde_yaml::from_str(&output)?)
+    }
+
+    /// Get the merged configuration, `netplan get all`.
+    pub fn get_config(&self) -> Result<NetplanConfig, NetplanError> {
+        let output = self.run("get", ["all".into()], true)?;
+        Ok(NetplanConfig::from_yaml(&output)?)
+    }
+
+    /// Change a setting, `netplan set <key>=<value>`, e.g. `ethernets.eth0.dhcp4` to `true`.
+    /// Setting a key to `None` removes it. The value is serialized as YAML.
+    pub fn set(&self, key: &str, value: impl Serialize) -> Result<(), NetplanError> {
+        // JSON is valid YAML, and keeps the value on a single line
+        let value = serde_json::to_string(&value)
+            .map_err(<serde_yaml::Error as serde::ser::Error>::custom)?;
+        self.run("set", [format!("{key}={value}").into()], true)?;
+        Ok(())
+    }
+
+    /// Information about the installed netplan, `netplan info`.
+    pub fn info(&self) -> Result<NetplanInfo, NetplanError> {
+        #[derive(Deserialize)]
+        struct Output {
+            #[serde(rename = "netplan.io")]
+            netplan: NetplanInfo,
+        }
+
+        let output = self.run("info", [], false)?;
+        Ok(serde_yaml::from_str::<Output>(&output)?.netplan)
+    }
+
+    /// The runtime state of all interfaces, `netplan status --all --format=json`.
+    pub fn status(&self) -> Result<NetplanStatus, NetplanError> {
+        let output = self.run("status", ["--all".into(), "--format=json".into()], true)?;
+        // JSON is valid YAML, so the output is parsed like the output of the other commands
+        Ok(serde_yaml::from_str(&output)?)
+    }
+
+    /// Run a netplan subcommand, returning its standard output.
+    fn run<const N: usize>(
+        &self,
+        subcommand: &str,
+        args: [OsString; N],
+        root_dir: bool,
+    ) -> Result<String, NetplanError> {
+        let mut command = Command::new(&self.binary);
+        command.arg(subcommand);
+        if let Some(dir) = self.root_dir.as_ref().filter(|_| root_dir) {
+            command.arg("--root-dir").arg(dir);
+        }
+        command.args(args);
+
+        let output = command.output().map_err(NetplanError::Spawn)?;
+        check_status(&command_line(&command), &output)?;
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    /// Start `netplan try`, which applies the configuration and reverts it after `timeout`
+    /// unless it is confirmed, see [TrySession].
+    #[cfg(unix)]
+    pub fn start_try(&self, timeout: Duration) -> Result<TrySession, NetplanError> {
+        let mut command = Command::new(&self.binary);
+        command
+            .arg("try")
+            .arg("--timeout")
+            .arg(timeout.as_secs().max(1).to_string())
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped());
+
+        let child = command.spawn().map_err(NetplanError::Spawn)?;
+        Ok(TrySession {
+            command: command_line(&command),
+            child,
+        })
+    }
+}
+
+/// The outcome of a [TrySession].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TryOutcome {
+    /// The configuration was confirmed and is kept.
+    Confirmed,
+    /// The previous configuration was restored, as the session was rejected or timed out.
+    RolledBack,
+}
+
+/// A running `netplan try`, see [Netplan::start_try].
+///
+/// The new configuration is applied as soon as the session starts. Unless it is confirmed
+/// within the timeout, netplan restores the previous configuration, so a configuration
+/// which cuts off the connection to the machine is reverted on its own. The session is
+/// confirmed with `SIGUSR1` and rejected with `SIGINT`, the signals `netplan try` handles.
+///
+/// Give netplan a moment to apply the configuration before confirming it; checking that
+/// the machine is still reachable, as in the example, usually takes care of that.
+///
+/// ```no_run
+/// use netplan_types::{Netplan, TryOutcome};
+/// use std::time::Duration;
+///
+/// let session = Netplan::new().start_try(Duration::from_secs(60)).unwrap();
+/// let outcome = session.confirm_if(|| gateway_reachable()).unwrap();
+/// if outcome == TryOutcome::RolledBack {
+///     eprintln!("The new configuration was reverted");
+/// }
+/// # fn gateway_reachable() -> bool { true }
+/// ```
+#[cfg(unix)]
+#[derive(Debug)]
+pub struct TrySession {
+    command: String,
+    child: Child,
+}
+
+#[cfg(unix)]
+impl TrySession {
+    /// Keep the new configuration. Returns [TryOutcome::RolledBack] if the session already
+    /// timed out.
+    pub fn confirm(self) -> Result<TryOutcome, NetplanError> {
+        self.finish(Some(libc::SIGUSR1))
+    }
+
+    /// Restore the previous configuration right away.
+    pub fn rollback(self) -> Result<TryOutcome, NetplanError> {
+        self.finish(Some(libc::SIGINT))
+    }
+
+    /// Wait for the session to time out, restoring the previous configuration.
+    pub fn wait(self) -> Result<TryOutcome, NetplanError> {
+        self.finish(None)
+    }
+
+    /// Confirm the configuration if `check` succeeds, e.g. a reachability check, and
+    /// roll it back otherwise.
+    pub fn confirm_if(self, check: impl FnOnce() -> bool) -> Result<TryOutcome, NetplanError> {
+        if check() {
+            self.confirm()
+        } else {
+            self.rollback()
+        }
+    }
+
+    fn finish(mut self, signal: Option<libc::c_int>) -> Result<TryOutcome, NetplanError> {
+        let timed_out = self
+            .child
+            .try_wait()
+            .map_err(NetplanError::Spawn)?
+            .is_some();
+
+        match signal {
+            Some(signal) if !timed_out => {
+                // The process has not been waited for, so its ID cannot have been reused
+                let pid = self.child.id() as libc::pid_t;
+                if unsafe { libc::kill(pid, signal) } != 0 {
+                    return Err(NetplanError::Spawn(std::io::Error::last_os_error()));
+                }
+            }
+            _ => {}
+        }
+
+        let output = self.child.wait_with_output().map_err(NetplanError::Spawn)?;
+        match signal {
+            Some(libc::SIGUSR1) if !timed_out => {
+                check_status(&self.command, &output)?;
+                Ok(TryOutcome::Confirmed)
+            }
+            // netplan exits unsuccessfully when it is interrupted
+            Some(libc::SIGINT) => Ok(TryOutcome::RolledBack),
+            _ => {
+                check_status(&self.command, &output)?;
+                Ok(TryOutcome::RolledBack)
+            }
+        }
+    }
+}
+
+/// The command line of `command`, e.g. `netplan generate`.
+fn command_line(command: &Command) -> String {
+    std::iter::once(command.get_program())
+        .chain(command.get_args())
+        .map(|arg| arg.to_string_lossy())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Turn an unsuccessful exit of `command` into an error.
+fn check_status(command: &str, output: &Output) -> Result<(), NetplanError> {
+    if output.status.success() {
+        return Ok(());
+    }
+
+    Err(NetplanError::Failed {
+        command: command.to_string(),
+        status: output.status,
+        stderr: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+    })
+}
+
+#[cfg(all(test, unix))]
+mod test {
+    use crate::{Netplan, NetplanError, TryOutcome};
+    use std::os::unix::fs::PermissionsExt;
+    use std::time::Duration;
+
+    #[test]
+    fn netplan_output_and_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let binary = dir.path().join("netplan");
+        std::fs::write(
+            &binary,
+            r#"#!/bin/sh
+case "$1" in
+    info) printf 'netplan.io:\n  features:\n  - generate-just-in-time\n' ;;
+    get) echo "{dhcp4: true}" ;;
+    *) echo "$* is not supported" >&2; exit 1 ;;
+esac
+"#,
+        )
+        .unwrap();
+        std::fs::set_permissions(&binary, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let netplan = Netplan::new().with_binary(&binary).with_root_dir("/tmp");
+        assert!(netplan.info().unwrap().has_feature("generate-just-in-time"));
+        assert_eq!(
+            netplan.get("ethernets.eth0").unwrap()["dhcp4"],
+            serde_yaml::Value::Bool(true)
+        );
+
+        match netplan.set("ethernets.eth0.mtu", 9000) {
+            Err(NetplanError::Failed {
+                command, stderr, ..
+            }) => {
+                assert!(command.ends_with("netplan set --root-dir /tmp ethernets.eth0.mtu=9000"));
+                assert_eq!(
+                    stderr,
+                    "set --root-dir /tmp ethernets.eth0.mtu=9000 is not supported"
+                );
+            }
+            result => panic!("Unexpected result {result:?}"),
+        }
+    }
+
+    #[test]
+    fn try_session_outcomes() {
+        let dir = tempfile::tempdir().unwrap();
+        let binary = dir.path().join("netplan");
+        std::fs::write(
+            &binary,
+            r#"#!/bin/sh
+trap 'exit 0' USR1
+trap 'echo "Reverting." >&2; exit 130' INT
+sleep "$3" 2>/dev/null &
+wait $!
+"#,
+        )
+        .unwrap();
+        std::fs::set_permissions(&binary, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let netplan = Netplan::new().with_binary(&binary);
+        let start = || {
+            let session = netplan.start_try(Duration::from_secs(5)).unwrap();
+            // Give the script time to install its traps
+            std::thread::sleep(Duration::from_millis(200));
+            session
+        };
+
+        assert_eq!(start().confirm_if(|| true).unwrap(), TryOutcome::Confirmed);
+        assert_eq!(
+            start().confirm_if(|| false).unwrap(),
+            TryOutcome::RolledBack
+        );
+
+        let session = netplan.start_try(Duration::ZERO).unwrap();
+        std::thread::sleep(Duration::from_millis(1500));
+        assert_eq!(session.confirm().unwrap(), TryOutcome::RolledBack);
+    }
+}
@@ -0,0 +1,179 @@
+//! The devices which supply default routes, i.e. the uplinks of a configuration.
+
+use crate::validation::default_route_family;
+use crate::{CommonPropertiesAllDevices, DeviceKind, NetworkConfig};
+use std::fmt::{Display, Formatter};
+
+/// How a device supplies a default route.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DefaultRouteMechanism {
+    /// The deprecated `gateway4` setting.
+    Gateway4,
+    /// The deprecated `gateway6` setting.
+    Gateway6,
+    /// A route to `default`, `0.0.0.0/0` or `::/0` in the main table.
+    Route,
+    /// DHCPv4, unless `use-routes` is disabled in `dhcp4-overrides`.
+    Dhcp4,
+    /// DHCPv6 and router advertisements, unless `use-routes` is disabled in
+    /// `dhcp6-overrides` or router advertisements are not accepted.
+    Dhcp6,
+}
+
+impl DefaultRouteMechanism {
+    /// The setting the default route comes from, e.g. `gateway4`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Gateway4 => "gateway4",
+            Self::Gateway6 => "gateway6",
+            Self::Route => "route",
+            Self::Dhcp4 => "dhcp4",
+            Self::Dhcp6 => "dhcp6",
+        }
+    }
+}
+
+impl Display for DefaultRouteMechanism {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A device supplying a default route to the main routing table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DefaultRouteProvider<'a> {
+    pub kind: DeviceKind,
+    pub device: &'a str,
+    pub mechanism: DefaultRouteMechanism,
+    /// The gateway, if it is configured rather than learned.
+    pub via: Option<&'a str>,
+    /// The metric of the route: the `metric` of a route, or the `route-metric` of the DHCP
+    /// overrides. `None` if the renderer's default is used.
+    pub metric: Option<u16>,
+}
+
+/// The devices supplying default routes, per address family, see
+/// [NetworkConfig::default_route_providers].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DefaultRouteProviders<'a> {
+    pub ipv4: Vec<DefaultRouteProvider<'a>>,
+    pub ipv6: Vec<DefaultRouteProvider<'a>>,
+}
+
+impl NetworkConfig {
+    /// The devices which supply a default route, and how, per address family, sorted by
+    /// the name of the device. Routes in other tables than the main table, like those of a
+    /// VRF, are not default routes of the system, and are left out.
+    ///
+    /// ```
+    /// use netplan_types::{DefaultRouteMechanism, NetplanConfig};
+    ///
+    /// let config = NetplanConfig::from_yaml("
+    /// network:
+    ///   version: 2
+    ///   ethernets:
+    ///     eth0:
+    ///       dhcp4: true
+    ///       dhcp4-overrides: {route-metric: 100}
+    ///     eth1:
+    ///       addresses: [192.0.2.10/24]
+    ///       routes: [{to: default, via: 192.0.2.1, metric: 200}]
+    ///     eth2:
+    ///       dhcp4: true
+    ///       dhcp4-overrides: {use-routes: false}
+    /// ").unwrap();
+    ///
+    /// let providers = config.network.default_route_providers();
+    /// let uplinks = providers.ipv4.iter().map(|p| (p.device, p.mechanism, p.metric));
+    /// assert_eq!(
+    ///     uplinks.collect::<Vec<_>>(),
+    ///     [
+    ///         ("eth0", DefaultRouteMechanism::Dhcp4, Some(100)),
+    ///         ("eth1", DefaultRouteMechanism::Route, Some(200)),
+    ///     ]
+    /// );
+    /// assert!(providers.ipv6.is_empty());
+    /// ```
+    pub fn default_route_providers(&self) -> DefaultRouteProviders<'_> {
+        let mut providers = DefaultRouteProviders::default();
+        for (kind, device, config) in self.devices() {
+            let Some(common) = config.common_all() else {
+                continue;
+            };
+
+            add_providers(&mut providers, kind, device, common);
+        }
+
+        providers.ipv4.sort_by_key(|p| p.device);
+        providers.ipv6.sort_by_key(|p| p.device);
+        providers
+    }
+}
+
+/// Add the default routes a single device supplies.
+fn add_providers<'a>(
+    providers: &mut DefaultRouteProviders<'a>,
+    kind: DeviceKind,
+    device: &'a str,
+    common: &'a CommonPropertiesAllDevices,
+) {
+    let mut add = |ipv6, mechanism, via: Option<&'a String>, metric| {
+        let provider = DefaultRouteProvider {
+            kind,
+            device,
+            mechanism,
+            via: via.map(String::as_str),
+            metric,
+        };
+        match ipv6 {
+            false => providers.ipv4.push(provider),
+            true => providers.ipv6.push(provider),
+        }
+    };
+
+    if let Some(gateway) = &common.gateway4 {
+        add(false, DefaultRouteMechanism::Gateway4, Some(gateway), None);
+    }
+
+    if let Some(gateway) = &common.gateway6 {
+        add(true, DefaultRouteMechanism::Gateway6, Some(gateway), None);
+    }
+
+    for route in common.routes.iter().flatten() {
+        if let Some(ipv6) = default_route_family(route) {
+            add(
+                ipv6,
+                DefaultRouteMechanism::Route,
+                route.via.as_ref(),
+                route.metric,
+            );
+        }
+    }
+
+    for (ipv6, enabled, overrides, mechanism) in [
+        (
+            false,
+            common.dhcp4,
+            &common.dhcp4_overrides,
+            DefaultRouteMechanism::Dhcp4,
+        ),
+        (
+            true,
+            common.dhcp6,
+            &common.dhcp6_overrides,
+            DefaultRouteMechanism::Dhcp6,
+        ),
+    ] {
+        let use_routes = overrides
+            .as_ref()
+            .and_then(|o| o.use_routes)
+            .unwrap_or(true);
+        // IPv6 default routes are learned from router advertisements
+        let accept_ra = !ipv6 || common.accept_ra != Some(false);
+
+        if enabled == Some(true) && use_routes && accept_ra {
+            let metric = overrides.as_ref().and_then(|o| o.route_metric);
+            add(ipv6, mechanism, None, metric);
+        }
+    }
+}
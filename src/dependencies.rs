@@ -0,0 +1,246 @@
+//! The dependencies between devices, e.g. of a VLAN on its link and of a bond on its
+//! members, and the order in which devices can be brought up and down.
+
+use crate::{AnyDeviceConfig, NetworkConfig};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{Display, Formatter};
+
+/// How a device depends on another device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DependencyKind {
+    /// The device is created on top of the other device, like a VLAN on its `link`, or an
+    /// SR-IOV virtual function on its physical function.
+    Link,
+    /// The other device is one of the `interfaces` of the bond, bridge or VRF.
+    Member,
+}
+
+/// The dependencies between the devices of a configuration, see
+/// [NetworkConfig::dependency_graph].
+///
+/// The graph contains every defined device, and every device which is referenced but not
+/// defined, like a `link` to an interface which is configured elsewhere.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DependencyGraph<'a> {
+    /// The dependencies of every device, by its name.
+    dependencies: BTreeMap<&'a str, BTreeMap<&'a str, DependencyKind>>,
+}
+
+/// The error returned when the devices cannot be ordered, as they depend on each other.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DependencyCycle {
+    /// The devices in the cycle, each depending on the next and the last on the first.
+    pub devices: Vec<String>,
+}
+
+impl Display for DependencyCycle {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Dependency cycle: ")?;
+        for device in &self.devices {
+            write!(f, "{device} -> ")?;
+        }
+
+        f.write_str(self.devices.first().map_or("", String::as_str))
+    }
+}
+
+impl std::error::Error for DependencyCycle {}
+
+impl NetworkConfig {
+    /// The graph of the dependencies between the devices: a VLAN depends on its `link`, an
+    /// ethernet on the physical function in its `link`, and a bond, bridge or VRF on its
+    /// `interfaces`.
+    ///
+    /// ```
+    /// use netplan_types::NetplanConfig;
+    ///
+    /// let config = NetplanConfig::from_yaml("
+    /// network:
+    ///   version: 2
+    ///   ethernets: {eth0: {}, eth1: {}}
+    ///   bonds:
+    ///     bond0: {interfaces: [eth0, eth1]}
+    ///   bridges:
+    ///     br0: {interfaces: [vlan10]}
+    ///   vlans:
+    ///     vlan10: {id: 10, link: bond0}
+    /// ").unwrap();
+    ///
+    /// let graph = config.network.dependency_graph();
+    /// let up = graph.topological_order().unwrap();
+    /// assert_eq!(up, ["eth0", "eth1", "bond0", "vlan10", "br0"]);
+    /// assert_eq!(graph.dependents("bond0").collect::<Vec<_>>(), ["vlan10"]);
+    /// ```
+    pub fn dependency_graph(&self) -> DependencyGraph<'_> {
+        let mut graph = DependencyGraph::default();
+        for (_, name, device) in self.devices() {
+            graph.dependencies.entry(name).or_default();
+            let (kind, dependencies) = match device {
+                AnyDeviceConfig::Ethernet(ethernet) => {
+                    (DependencyKind::Link, ethernet.link.as_slice())
+                }
+                AnyDeviceConfig::Vlan(vlan) => (DependencyKind::Link, vlan.link.as_slice()),
+                AnyDeviceConfig::Bond(bond) => (
+                    DependencyKind::Member,
+                    bond.interfaces.as_deref().unwrap_or_default(),
+                ),
+                AnyDeviceConfig::Bridge(bridge) => (
+                    DependencyKind::Member,
+                    bridge.interfaces.as_deref().unwrap_or_default(),
+                ),
+                AnyDeviceConfig::Vrf(vrf) => (DependencyKind::Member, vrf.interfaces.as_slice()),
+                _ => continue,
+            };
+
+            for dependency in dependencies {
+                graph.add(name, dependency, kind);
+            }
+        }
+
+        graph
+    }
+}
+
+impl<'a> DependencyGraph<'a> {
+    fn add(&mut self, device: &'a str, dependency: &'a str, kind: DependencyKind) {
+        self.dependencies.entry(dependency).or_default();
+        self.dependencies
+            .entry(device)
+            .or_default()
+            .insert(dependency, kind);
+    }
+
+    /// The names of all devices, in lexicographic order.
+    pub fn devices(&self) -> impl Iterator<Item = &'a str> + '_ {
+        self.dependencies.keys().copied()
+    }
+
+    /// Every dependency, as the device, the device it depends on, and how.
+    pub fn edges(&self) -> impl Iterator<Item = (&'a str, &'a str, DependencyKind)> + '_ {
+        self.dependencies.iter().flat_map(|(device, dependencies)| {
+            dependencies
+                .iter()
+                .map(|(dependency, kind)| (*device, *dependency, *kind))
+        })
+    }
+
+    /// The devices the device depends on directly.
+    pub fn dependencies(&self, device: &str) -> impl Iterator<Item = &'a str> + '_ {
+        self.dependencies
+            .get(device)
+            .into_iter()
+            .flat_map(|dependencies| dependencies.keys().copied())
+    }
+
+    /// The devices which depend on the device directly.
+    pub fn dependents<'s>(&'s self, device: &'s str) -> impl Iterator<Item = &'a str> + 's {
+        self.dependencies
+            .iter()
+            .filter(move |(_, dependencies)| dependencies.contains_key(device))
+            .map(|(dependent, _)| *dependent)
+    }
+
+    /// The devices ordered so that every device comes after the devices it depends on,
+    /// which is the order to bring them up in. Bring them down in the reverse order.
+    /// Devices which do not depend on each other are ordered by name, so the order is
+    /// deterministic.
+    pub fn topological_order(&self) -> Result<Vec<&'a str>, DependencyCycle> {
+        let mut remaining = self
+            .dependencies
+            .iter()
+            .map(|(device, dependencies)| (*device, dependencies.len()))
+            .collect::<BTreeMap<_, _>>();
+        let mut ready = remaining
+            .iter()
+            .filter(|(_, count)| **count == 0)
+            .map(|(device, _)| *device)
+            .collect::<BTreeSet<_>>();
+
+        let mut order = Vec::with_capacity(remaining.len());
+        while let Some(device) = ready.pop_first() {
+            remaining.remove(device);
+            order.push(device);
+            for dependent in self.dependents(device) {
+                let count = remaining
+                    .get_mut(dependent)
+                    .expect("Dependent is not ordered yet");
+                *count -= 1;
+                if *count == 0 {
+                    ready.insert(dependent);
+                }
+            }
+        }
+
+        match remaining.keys().next() {
+            None => Ok(order),
+            Some(start) => Err(self.cycle(start, &remaining)),
+        }
+    }
+
+    /// A cycle reached from `start`, which cannot be ordered. Every device which is not
+    /// ordered depends on another one which is not, so following those dependencies from
+    /// any of them leads into a cycle.
+    fn cycle(&self, start: &'a str, remaining: &BTreeMap<&'a str, usize>) -> DependencyCycle {
+        let mut path = vec![start];
+        loop {
+            let device = path[path.len() - 1];
+            let next = self
+                .dependencies(device)
+                .find(|dependency| remaining.contains_key(dependency))
+                .expect("Unordered device has an unordered dependency");
+
+            if let Some(index) = path.iter().position(|d| *d == next) {
+                let devices = path[index..].iter().map(|d| d.to_string()).collect();
+                return DependencyCycle { devices };
+            }
+            path.push(next);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{DependencyKind, NetplanConfig};
+
+    #[test]
+    fn order_devices() {
+        let config = NetplanConfig::build(|net| {
+            net.ethernet("enp1s0", |e| e)
+                .ethernet("enp1s0v0", |e| e.with(|e| e.link = Some("enp1s0".into())))
+                .bond("bond0", |b| b.interfaces(["enp1s0v0", "eth9"]))
+                .vlan("vlan10", |v| v.id(10).link("bond0"))
+                .bridge("br0", |b| b.interfaces(["vlan10"]))
+                .vrf("vrf0", |v| v.table(100).interfaces(["br0"]));
+        });
+        let graph = config.network.dependency_graph();
+
+        let order = graph.topological_order().unwrap();
+        assert_eq!(
+            order,
+            ["enp1s0", "enp1s0v0", "eth9", "bond0", "vlan10", "br0", "vrf0"]
+        );
+        assert!(graph
+            .edges()
+            .any(|edge| edge == ("bond0", "eth9", DependencyKind::Member)));
+        assert!(graph
+            .edges()
+            .any(|edge| edge == ("vlan10", "bond0", DependencyKind::Link)));
+        assert_eq!(
+            graph.dependencies("bond0").collect::<Vec<_>>(),
+            ["enp1s0v0", "eth9"]
+        );
+
+        let cyclic = NetplanConfig::build(|net| {
+            net.ethernet("eth0", |e| e)
+                .bridge("br0", |b| b.interfaces(["vlan10"]))
+                .vlan("vlan10", |v| v.id(10).link("br0"));
+        });
+        let cycle = cyclic
+            .network
+            .dependency_graph()
+            .topological_order()
+            .unwrap_err();
+        assert_eq!(cycle.devices, ["br0", "vlan10"]);
+        assert_eq!(cycle.to_string(), "Dependency cycle: br0 -> vlan10 -> br0");
+    }
+}
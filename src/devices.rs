@@ -0,0 +1,856 @@
+//! Uniform access to the device definitions of a [NetworkConfig], regardless
+//! of the section they are defined in.
+
+use crate::{
+    BondConfig, BridgeConfig, CommonProperties, CommonPropertiesAllDevices,
+    CommonPropertiesPhysicalDeviceType, DummyDeviceConfig, EthernetConfig, ModemConfig,
+    NetworkConfig, NmDeviceConfig, TunnelConfig, VlanConfig, VrfsConfig, WifiConfig,
+};
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+/// The kind of a device definition, i.e. the section of the configuration it is defined in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DeviceKind {
+    Ethernet,
+    Wifi,
+    Modem,
+    Bond,
+    Bridge,
+    Vlan,
+    Tunnel,
+    Vrf,
+    DummyDevice,
+    NmDevice,
+}
+
+impl DeviceKind {
+    /// The key of the section devices of this kind are defined in, e.g. `ethernets`.
+    pub fn section(&self) -> &'static str {
+        match self {
+            Self::Ethernet => "ethernets",
+            Self::Wifi => "wifis",
+            Self::Modem => "modems",
+            Self::Bond => "bonds",
+            Self::Bridge => "bridges",
+            Self::Vlan => "vlans",
+            Self::Tunnel => "tunnels",
+            Self::Vrf => "vrfs",
+            Self::DummyDevice => "dummy-devices",
+            Self::NmDevice => "nm-devices",
+        }
+    }
+
+    /// Whether devices of this kind are physical devices, selected with match rules.
+    pub fn is_physical(&self) -> bool {
+        matches!(self, Self::Ethernet | Self::Wifi | Self::Modem)
+    }
+}
+
+impl Display for DeviceKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.section())
+    }
+}
+
+/// A reference to a device definition of any kind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnyDeviceConfig<'a> {
+    Ethernet(&'a EthernetConfig),
+    Wifi(&'a WifiConfig),
+    Modem(&'a ModemConfig),
+    Bond(&'a BondConfig),
+    Bridge(&'a BridgeConfig),
+    Vlan(&'a VlanConfig),
+    Tunnel(&'a TunnelConfig),
+    Vrf(&'a VrfsConfig),
+    DummyDevice(&'a DummyDeviceConfig),
+    NmDevice(&'a NmDeviceConfig),
+}
+
+impl<'a> AnyDeviceConfig<'a> {
+    pub fn kind(&self) -> DeviceKind {
+        match self {
+            Self::Ethernet(_) => DeviceKind::Ethernet,
+            Self::Wifi(_) => DeviceKind::Wifi,
+            Self::Modem(_) => DeviceKind::Modem,
+            Self::Bond(_) => DeviceKind::Bond,
+            Self::Bridge(_) => DeviceKind::Bridge,
+            Self::Vlan(_) => DeviceKind::Vlan,
+            Self::Tunnel(_) => DeviceKind::Tunnel,
+            Self::Vrf(_) => DeviceKind::Vrf,
+            Self::DummyDevice(_) => DeviceKind::DummyDevice,
+            Self::NmDevice(_) => DeviceKind::NmDevice,
+        }
+    }
+
+    /// The properties shared by all device types, if any are set.
+    pub fn common_all(&self) -> Option<&'a CommonPropertiesAllDevices> {
+        match self {
+            Self::Ethernet(d) => d.common_all.as_deref(),
+            Self::Wifi(d) => d.common_all.as_deref(),
+            Self::Modem(d) => d.common_all.as_deref(),
+            Self::Bond(d) => d.common_all.as_deref(),
+            Self::Bridge(d) => d.common_all.as_deref(),
+            Self::Vlan(d) => d.common_all.as_deref(),
+            Self::Tunnel(d) => d.common_all.as_deref(),
+            Self::Vrf(d) => d.common_all.as_deref(),
+            Self::DummyDevice(d) => d.common_all.as_deref(),
+            Self::NmDevice(d) => d.common_all.as_deref(),
+        }
+    }
+
+    /// The properties shared by physical devices, if this is a physical device and any are set.
+    pub fn common_physical(&self) -> Option<&'a CommonPropertiesPhysicalDeviceType> {
+        match self {
+            Self::Ethernet(d) => d.common_physical.as_deref(),
+            Self::Wifi(d) => d.common_physical.as_deref(),
+            Self::Modem(d) => d.common_physical.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+/// A mutable reference to a device definition of any kind.
+#[derive(Debug, PartialEq, Eq)]
+pub enum AnyDeviceConfigMut<'a> {
+    Ethernet(&'a mut EthernetConfig),
+    Wifi(&'a mut WifiConfig),
+    Modem(&'a mut ModemConfig),
+    Bond(&'a mut BondConfig),
+    Bridge(&'a mut BridgeConfig),
+    Vlan(&'a mut VlanConfig),
+    Tunnel(&'a mut TunnelConfig),
+    Vrf(&'a mut VrfsConfig),
+    DummyDevice(&'a mut DummyDeviceConfig),
+    NmDevice(&'a mut NmDeviceConfig),
+}
+
+impl AnyDeviceConfigMut<'_> {
+    pub fn kind(&self) -> DeviceKind {
+        self.as_ref().kind()
+    }
+
+    pub fn as_ref(&self) -> AnyDeviceConfig<'_> {
+        match self {
+            Self::Ethernet(d) => AnyDeviceConfig::Ethernet(d),
+            Self::Wifi(d) => AnyDeviceConfig::Wifi(d),
+            Self::Modem(d) => AnyDeviceConfig::Modem(d),
+            Self::Bond(d) => AnyDeviceConfig::Bond(d),
+            Self::Bridge(d) => AnyDeviceConfig::Bridge(d),
+            Self::Vlan(d) => AnyDeviceConfig::Vlan(d),
+            Self::Tunnel(d) => AnyDeviceConfig::Tunnel(d),
+            Self::Vrf(d) => AnyDeviceConfig::Vrf(d),
+            Self::DummyDevice(d) => AnyDeviceConfig::DummyDevice(d),
+            Self::NmDevice(d) => AnyDeviceConfig::NmDevice(d),
+        }
+    }
+
+    /// The block of properties shared by all device types, whether it is set or not.
+    pub(crate) fn common_all_entry(&mut self) -> &mut Option<Box<CommonPropertiesAllDevices>> {
+        match self {
+            Self::Ethernet(d) => &mut d.common_all,
+            Self::Wifi(d) => &mut d.common_all,
+            Self::Modem(d) => &mut d.common_all,
+            Self::Bond(d) => &mut d.common_all,
+            Self::Bridge(d) => &mut d.common_all,
+            Self::Vlan(d) => &mut d.common_all,
+            Self::Tunnel(d) => &mut d.common_all,
+            Self::Vrf(d) => &mut d.common_all,
+            Self::DummyDevice(d) => &mut d.common_all,
+            Self::NmDevice(d) => &mut d.common_all,
+        }
+    }
+}
+
+impl CommonProperties for AnyDeviceConfigMut<'_> {
+    fn common_all(&self) -> Option<&CommonPropertiesAllDevices> {
+        match self {
+            Self::Ethernet(d) => d.common_all(),
+            Self::Wifi(d) => d.common_all(),
+            Self::Modem(d) => d.common_all(),
+            Self::Bond(d) => d.common_all(),
+            Self::Bridge(d) => d.common_all(),
+            Self::Vlan(d) => d.common_all(),
+            Self::Tunnel(d) => d.common_all(),
+            Self::Vrf(d) => d.common_all(),
+            Self::DummyDevice(d) => d.common_all(),
+            Self::NmDevice(d) => d.common_all(),
+        }
+    }
+
+    fn common_all_mut(&mut self) -> &mut CommonPropertiesAllDevices {
+        match self {
+            Self::Ethernet(d) => d.common_all_mut(),
+            Self::Wifi(d) => d.common_all_mut(),
+            Self::Modem(d) => d.common_all_mut(),
+            Self::Bond(d) => d.common_all_mut(),
+            Self::Bridge(d) => d.common_all_mut(),
+            Self::Vlan(d) => d.common_all_mut(),
+            Self::Tunnel(d) => d.common_all_mut(),
+            Self::Vrf(d) => d.common_all_mut(),
+            Self::DummyDevice(d) => d.common_all_mut(),
+            Self::NmDevice(d) => d.common_all_mut(),
+        }
+    }
+}
+
+impl NetworkConfig {
+    /// Iterate over every device definition, yielding its kind, ID and configuration.
+    /// Devices are yielded per section, in the order the sections appear in [NetworkConfig].
+    /// Within a section the order is unspecified.
+    pub fn devices(&self) -> impl Iterator<Item = (DeviceKind, &str, AnyDeviceConfig<'_>)> {
+        fn section<'a, T>(
+            kind: DeviceKind,
+            map: &'a Option<HashMap<String, T>>,
+            wrap: fn(&'a T) -> AnyDeviceConfig<'a>,
+        ) -> impl Iterator<Item = (DeviceKind, &'a str, AnyDeviceConfig<'a>)> {
+            map.iter()
+                .flatten()
+                .map(move |(name, device)| (kind, name.as_str(), wrap(device)))
+        }
+
+        section(
+            DeviceKind::Ethernet,
+            &self.ethernets,
+            AnyDeviceConfig::Ethernet,
+        )
+        .chain(section(
+            DeviceKind::Wifi,
+            &self.wifis,
+            AnyDeviceConfig::Wifi,
+        ))
+        .chain(section(
+            DeviceKind::Modem,
+            &self.modems,
+            AnyDeviceConfig::Modem,
+        ))
+        .chain(section(
+            DeviceKind::Bond,
+            &self.bonds,
+            AnyDeviceConfig::Bond,
+        ))
+        .chain(section(
+            DeviceKind::Bridge,
+            &self.bridges,
+            AnyDeviceConfig::Bridge,
+        ))
+        .chain(section(
+            DeviceKind::Vlan,
+            &self.vlans,
+            AnyDeviceConfig::Vlan,
+        ))
+        .chain(section(
+            DeviceKind::Tunnel,
+            &self.tunnels,
+            AnyDeviceConfig::Tunnel,
+        ))
+        .chain(section(DeviceKind::Vrf, &self.vrfs, AnyDeviceConfig::Vrf))
+        .chain(section(
+            DeviceKind::DummyDevice,
+            &self.dummy_devices,
+            AnyDeviceConfig::DummyDevice,
+        ))
+        .chain(section(
+            DeviceKind::NmDevice,
+            &self.nm_devices,
+            AnyDeviceConfig::NmDevice,
+        ))
+    }
+
+    /// Iterate mutably over every device definition, yielding its kind, ID and configuration.
+    /// The order is the same as for [NetworkConfig::devices].
+    pub fn devices_mut(
+        &mut self,
+    ) -> impl Iterator<Item = (DeviceKind, &str, AnyDeviceConfigMut<'_>)> {
+        fn section<'a, T>(
+            kind: DeviceKind,
+            map: &'a mut Option<HashMap<String, T>>,
+            wrap: fn(&'a mut T) -> AnyDeviceConfigMut<'a>,
+        ) -> impl Iterator<Item = (DeviceKind, &'a str, AnyDeviceConfigMut<'a>)> {
+            map.iter_mut()
+                .flatten()
+                .map(move |(name, device)| (kind, name.as_str(), wrap(device)))
+        }
+
+        section(
+            DeviceKind::Ethernet,
+            &mut self.ethernets,
+            AnyDeviceConfigMut::Ethernet,
+        )
+        .chain(section(
+            DeviceKind::Wifi,
+            &mut self.wifis,
+            AnyDeviceConfigMut::Wifi,
+        ))
+        .chain(section(
+            DeviceKind::Modem,
+            &mut self.modems,
+            AnyDeviceConfigMut::Modem,
+        ))
+        .chain(section(
+            DeviceKind::Bond,
+            &mut self.bonds,
+            AnyDeviceConfigMut::Bond,
+        ))
+        .chain(section(
+            DeviceKind::Bridge,
+            &mut self.bridges,
+            AnyDeviceConfigMut::Bridge,
+        ))
+        .chain(section(
+            DeviceKind::Vlan,
+            &mut self.vlans,
+            AnyDeviceConfigMut::Vlan,
+        ))
+        .chain(section(
+            DeviceKind::Tunnel,
+            &mut self.tunnels,
+            AnyDeviceConfigMut::Tunnel,
+        ))
+        .chain(section(
+            DeviceKind::Vrf,
+            &mut self.vrfs,
+            AnyDeviceConfigMut::Vrf,
+        ))
+        .chain(section(
+            DeviceKind::DummyDevice,
+            &mut self.dummy_devices,
+            AnyDeviceConfigMut::DummyDevice,
+        ))
+        .chain(section(
+            DeviceKind::NmDevice,
+            &mut self.nm_devices,
+            AnyDeviceConfigMut::NmDevice,
+        ))
+    }
+
+    /// Find the device definition with the given ID, in whichever section it is defined.
+    pub fn device(&self, name: &str) -> Option<AnyDeviceConfig<'_>> {
+        self.devices()
+            .find(|(_, id, _)| *id == name)
+            .map(|(_, _, device)| device)
+    }
+
+    /// Find the device definition with the given ID mutably, in whichever section it is defined.
+    pub fn device_mut(&mut self, name: &str) -> Option<AnyDeviceConfigMut<'_>> {
+        self.devices_mut()
+            .find(|(_, id, _)| *id == name)
+            .map(|(_, _, device)| device)
+    }
+
+    /// The device definitions for which `predicate` returns true, sorted by kind and ID.
+    ///
+    /// ```
+    /// use netplan_types::{DeviceKind, NetplanConfig};
+    ///
+    /// let config = NetplanConfig::build(|net| {
+    ///     net.ethernet("eth0", |e| e.mtu(9000))
+    ///         .ethernet("eth1", |e| e.mtu(1500))
+    ///         .vlan("vlan10", |v| v.id(10).link("eth0").mtu(9000));
+    /// });
+    ///
+    /// let jumbo = config.network.find_devices(|_, _, device| {
+    ///     device.common_all().and_then(|common| common.mtu) == Some(9000)
+    /// });
+    /// let jumbo = jumbo.iter().map(|(kind, name, _)| (*kind, *name)).collect::<Vec<_>>();
+    /// assert_eq!(jumbo, [(DeviceKind::Ethernet, "eth0"), (DeviceKind::Vlan, "vlan10")]);
+    /// ```
+    pub fn find_devices(
+        &self,
+        mut predicate: impl FnMut(DeviceKind, &str, AnyDeviceConfig<'_>) -> bool,
+    ) -> Vec<(DeviceKind, &str, AnyDeviceConfig<'_>)> {
+        let mut devices = self
+            .devices()
+            .filter(|(kind, name, device)| predicate(*kind, name, *device))
+            .collect::<Vec<_>>();
+        devices.sort_by_key(|(kind, name, _)| (*kind, *name));
+        devices
+    }
+
+    /// The device definitions with DHCPv4 or DHCPv6 enabled, see [NetworkConfig::find_devices].
+    pub fn dhcp_enabled(&self) -> Vec<(DeviceKind, &str, AnyDeviceConfig<'_>)> {
+        self.find_devices(|_, _, device| {
+            device
+                .common_all()
+                .is_some_and(|common| common.dhcp4 == Some(true) || common.dhcp6 == Some(true))
+        })
+    }
+
+    /// The device definitions with at least one static address, see
+    /// [NetworkConfig::find_devices].
+    pub fn with_static_addresses(&self) -> Vec<(DeviceKind, &str, AnyDeviceConfig<'_>)> {
+        self.find_devices(|_, _, device| {
+            device
+                .common_all()
+                .and_then(|common| common.addresses.as_ref())
+                .is_some_and(|addresses| !addresses.is_empty())
+        })
+    }
+
+    /// The wifi and modem definitions, see [NetworkConfig::find_devices].
+    pub fn wireless(&self) -> Vec<(DeviceKind, &str, AnyDeviceConfig<'_>)> {
+        self.find_devices(|kind, _, _| matches!(kind, DeviceKind::Wifi | DeviceKind::Modem))
+    }
+
+    /// Add an ethernet definition, creating the section if needed.
+    /// Returns the definition previously stored under this ID, if any.
+    pub fn add_ethernet(
+        &mut self,
+        name: impl Into<String>,
+        config: EthernetConfig,
+    ) -> Option<EthernetConfig> {
+        self.ethernets
+            .get_or_insert_with(HashMap::new)
+            .insert(name.into(), config)
+    }
+
+    /// Add a wifi definition, creating the section if needed.
+    /// Returns the definition previously stored under this ID, if any.
+    pub fn add_wifi(&mut self, name: impl Into<String>, config: WifiConfig) -> Option<WifiConfig> {
+        self.wifis
+            .get_or_insert_with(HashMap::new)
+            .insert(name.into(), config)
+    }
+
+    /// Add a modem definition, creating the section if needed.
+    /// Returns the definition previously stored under this ID, if any.
+    pub fn add_modem(
+        &mut self,
+        name: impl Into<String>,
+        config: ModemConfig,
+    ) -> Option<ModemConfig> {
+        self.modems
+            .get_or_insert_with(HashMap::new)
+            .insert(name.into(), config)
+    }
+
+    /// Add a bond definition, creating the section if needed.
+    /// Returns the definition previously stored under this ID, if any.
+    pub fn add_bond(&mut self, name: impl Into<String>, config: BondConfig) -> Option<BondConfig> {
+        self.bonds
+            .get_or_insert_with(HashMap::new)
+            .insert(name.into(), config)
+    }
+
+    /// Add a bridge definition, creating the section if needed.
+    /// Returns the definition previously stored under this ID, if any.
+    pub fn add_bridge(
+        &mut self,
+        name: impl Into<String>,
+        config: BridgeConfig,
+    ) -> Option<BridgeConfig> {
+        self.bridges
+            .get_or_insert_with(HashMap::new)
+            .insert(name.into(), config)
+    }
+
+    /// Add a VLAN definition, creating the section if needed.
+    /// Returns the definition previously stored under this ID, if any.
+    pub fn add_vlan(&mut self, name: impl Into<String>, config: VlanConfig) -> Option<VlanConfig> {
+        self.vlans
+            .get_or_insert_with(HashMap::new)
+            .insert(name.into(), config)
+    }
+
+    /// Add a VLAN on `link` for every ID in `ids`, e.g. to define all VLANs of a trunk.
+    ///
+    /// The VLANs are named after `name`, in which `{id}` and `{link}` are replaced by the
+    /// VLAN ID and the link. The definitions are created by `f`, after which their `id` and
+    /// `link` are set. Existing definitions with the same names are replaced.
+    /// Returns the names of the VLANs, in the order of `ids`.
+    ///
+    /// ```
+    /// use netplan_types::{CommonProperties, NetworkConfig, VlanConfig};
+    ///
+    /// let mut network = NetworkConfig::default();
+    /// let names = network.add_vlan_range("eth0", 100..=150, "vlan{id}", |_| {
+    ///     let mut vlan = VlanConfig::default();
+    ///     vlan.set_mtu(Some(9000));
+    ///     vlan
+    /// });
+    ///
+    /// assert_eq!(names.len(), 51);
+    /// assert_eq!(network.vlans.unwrap()["vlan120"].id, Some(120));
+    /// ```
+    pub fn add_vlan_range(
+        &mut self,
+        link: &str,
+        ids: impl IntoIterator<Item = u16>,
+        name: &str,
+        mut f: impl FnMut(u16) -> VlanConfig,
+    ) -> Vec<String> {
+        let vlans = self.vlans.get_or_insert_with(HashMap::new);
+        ids.into_iter()
+            .map(|id| {
+                let name = name
+                    .replace("{id}", &id.to_string())
+                    .replace("{link}", link);
+                let config = VlanConfig {
+                    id: Some(id),
+                    link: Some(link.to_string()),
+                    ..f(id)
+                };
+
+                vlans.insert(name.clone(), config);
+                name
+            })
+            .collect()
+    }
+
+    /// Add a tunnel definition, creating the section if needed.
+    /// Returns the definition previously stored under this ID, if any.
+    pub fn add_tunnel(
+        &mut self,
+        name: impl Into<String>,
+        config: TunnelConfig,
+    ) -> Option<TunnelConfig> {
+        self.tunnels
+            .get_or_insert_with(HashMap::new)
+            .insert(name.into(), config)
+    }
+
+    /// Add a VRF definition, creating the section if needed.
+    /// Returns the definition previously stored under this ID, if any.
+    pub fn add_vrf(&mut self, name: impl Into<String>, config: VrfsConfig) -> Option<VrfsConfig> {
+        self.vrfs
+            .get_or_insert_with(HashMap::new)
+            .insert(name.into(), config)
+    }
+
+    /// Add a dummy device definition, creating the section if needed.
+    /// Returns the definition previously stored under this ID, if any.
+    pub fn add_dummy_device(
+        &mut self,
+        name: impl Into<String>,
+        config: DummyDeviceConfig,
+    ) -> Option<DummyDeviceConfig> {
+        self.dummy_devices
+            .get_or_insert_with(HashMap::new)
+            .insert(name.into(), config)
+    }
+
+    /// Add a NetworkManager connection definition, creating the section if needed.
+    /// Returns the definition previously stored under this ID, if any.
+    pub fn add_nm_device(
+        &mut self,
+        name: impl Into<String>,
+        config: NmDeviceConfig,
+    ) -> Option<NmDeviceConfig> {
+        self.nm_devices
+            .get_or_insert_with(HashMap::new)
+            .insert(name.into(), config)
+    }
+
+    /// Add a copy of a device definition to the section of its kind.
+    #[cfg(feature = "yaml")]
+    pub(crate) fn add_device(&mut self, name: &str, device: AnyDeviceConfig<'_>) {
+        match device {
+            AnyDeviceConfig::Ethernet(config) => {
+                self.add_ethernet(name, config.clone());
+            }
+            AnyDeviceConfig::Wifi(config) => {
+                self.add_wifi(name, config.clone());
+            }
+            AnyDeviceConfig::Modem(config) => {
+                self.add_modem(name, config.clone());
+            }
+            AnyDeviceConfig::Bond(config) => {
+                self.add_bond(name, config.clone());
+            }
+            AnyDeviceConfig::Bridge(config) => {
+                self.add_bridge(name, config.clone());
+            }
+            AnyDeviceConfig::Vlan(config) => {
+                self.add_vlan(name, config.clone());
+            }
+            AnyDeviceConfig::Tunnel(config) => {
+                self.add_tunnel(name, config.clone());
+            }
+            AnyDeviceConfig::Vrf(config) => {
+                self.add_vrf(name, config.clone());
+            }
+            AnyDeviceConfig::DummyDevice(config) => {
+                self.add_dummy_device(name, config.clone());
+            }
+            AnyDeviceConfig::NmDevice(config) => {
+                self.add_nm_device(name, config.clone());
+            }
+        }
+    }
+
+    /// Remove the device definition with the given ID, in whichever section it is defined.
+    /// References to the device are removed as well: it is removed from the interfaces of
+    /// bonds, bridges and VRFs, and unset as the primary of bonds and the link of SR-IOV
+    /// virtual functions.
+    ///
+    /// A VLAN cannot exist without its link, so a device which VLANs are linked to is not
+    /// removed. Remove or relink the VLANs first.
+    ///
+    /// Returns the kind of the removed device.
+    pub fn remove_device(&mut self, name: &str) -> Result<DeviceKind, RemoveDeviceError> {
+        let kind = self
+            .device(name)
+            .ok_or_else(|| RemoveDeviceError::NotFound(name.to_string()))?
+            .kind();
+
+        let mut vlans = self
+            .vlans
+            .iter()
+            .flatten()
+            .filter(|(_, vlan)| vlan.link.as_deref() == Some(name))
+            .map(|(id, _)| id.clone())
+            .collect::<Vec<_>>();
+        if !vlans.is_empty() {
+            vlans.sort_unstable();
+            return Err(RemoveDeviceError::LinkedVlans(vlans));
+        }
+
+        match kind {
+            DeviceKind::Ethernet => remove_from(&mut self.ethernets, name),
+            DeviceKind::Wifi => remove_from(&mut self.wifis, name),
+            DeviceKind::Modem => remove_from(&mut self.modems, name),
+            DeviceKind::Bond => remove_from(&mut self.bonds, name),
+            DeviceKind::Bridge => remove_from(&mut self.bridges, name),
+            DeviceKind::Vlan => remove_from(&mut self.vlans, name),
+            DeviceKind::Tunnel => remove_from(&mut self.tunnels, name),
+            DeviceKind::Vrf => remove_from(&mut self.vrfs, name),
+            DeviceKind::DummyDevice => remove_from(&mut self.dummy_devices, name),
+            DeviceKind::NmDevice => remove_from(&mut self.nm_devices, name),
+        }
+
+        self.replace_references(name, None);
+        Ok(kind)
+    }
+
+    /// Rename a device definition, updating every reference to it in the same way
+    /// [NetworkConfig::remove_device] removes them.
+    pub fn rename_device(&mut self, old: &str, new: &str) -> Result<DeviceKind, RenameDeviceError> {
+        if old != new && self.device(new).is_some() {
+            return Err(RenameDeviceError::AlreadyExists(new.to_string()));
+        }
+
+        let kind = self
+            .device(old)
+            .ok_or_else(|| RenameDeviceError::NotFound(old.to_string()))?
+            .kind();
+
+        match kind {
+            DeviceKind::Ethernet => rename_in(&mut self.ethernets, old, new),
+            DeviceKind::Wifi => rename_in(&mut self.wifis, old, new),
+            DeviceKind::Modem => rename_in(&mut self.modems, old, new),
+            DeviceKind::Bond => rename_in(&mut self.bonds, old, new),
+            DeviceKind::Bridge => rename_in(&mut self.bridges, old, new),
+            DeviceKind::Vlan => rename_in(&mut self.vlans, old, new),
+            DeviceKind::Tunnel => rename_in(&mut self.tunnels, old, new),
+            DeviceKind::Vrf => rename_in(&mut self.vrfs, old, new),
+            DeviceKind::DummyDevice => rename_in(&mut self.dummy_devices, old, new),
+            DeviceKind::NmDevice => rename_in(&mut self.nm_devices, old, new),
+        }
+
+        self.replace_references(old, Some(new));
+        Ok(kind)
+    }
+
+    /// Replace every reference to the device `old` with `new`, or remove it if `new` is `None`.
+    fn replace_references(&mut self, old: &str, new: Option<&str>) {
+        let replace = |reference: &mut Option<String>| {
+            if reference.as_deref() == Some(old) {
+                *reference = new.map(str::to_string);
+            }
+        };
+
+        let replace_all = |interfaces: &mut Vec<String>| match new {
+            Some(new) => interfaces
+                .iter_mut()
+                .filter(|interface| *interface == old)
+                .for_each(|interface| *interface = new.to_string()),
+            None => interfaces.retain(|interface| interface != old),
+        };
+
+        for bond in self.bonds.iter_mut().flat_map(|bonds| bonds.values_mut()) {
+            bond.interfaces.iter_mut().for_each(replace_all);
+            if let Some(parameters) = &mut bond.parameters {
+                replace(&mut parameters.primary);
+            }
+        }
+
+        for bridge in self.bridges.iter_mut().flat_map(|b| b.values_mut()) {
+            bridge.interfaces.iter_mut().for_each(replace_all);
+        }
+
+        for vrf in self.vrfs.iter_mut().flat_map(|vrfs| vrfs.values_mut()) {
+            replace_all(&mut vrf.interfaces);
+        }
+
+        for vlan in self.vlans.iter_mut().flat_map(|vlans| vlans.values_mut()) {
+            replace(&mut vlan.link);
+        }
+
+        for ethernet in self.ethernets.iter_mut().flat_map(|e| e.values_mut()) {
+            replace(&mut ethernet.link);
+        }
+    }
+}
+
+fn remove_from<T>(map: &mut Option<HashMap<String, T>>, name: &str) {
+    if let Some(map) = map {
+        map.remove(name);
+    }
+}
+
+fn rename_in<T>(map: &mut Option<HashMap<String, T>>, old: &str, new: &str) {
+    if let Some(map) = map {
+        if let Some(device) = map.remove(old) {
+            map.insert(new.to_string(), device);
+        }
+    }
+}
+
+/// The error returned when removing a device fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RemoveDeviceError {
+    /// No device with the ID to remove exists.
+    NotFound(String),
+    /// VLANs are linked to the device, with these IDs.
+    LinkedVlans(Vec<String>),
+}
+
+impl Display for RemoveDeviceError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotFound(name) => write!(f, "No device with ID '{name}' exists"),
+            Self::LinkedVlans(vlans) => {
+                write!(f, "The VLANs {} are linked to the device", vlans.join(", "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for RemoveDeviceError {}
+
+/// The error returned when renaming a device fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RenameDeviceError {
+    /// No device with the ID to rename exists.
+    NotFound(String),
+    /// A device with the new ID already exists.
+    AlreadyExists(String),
+}
+
+impl Display for RenameDeviceError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotFound(name) => write!(f, "No device with ID '{name}' exists"),
+            Self::AlreadyExists(name) => write!(f, "A device with ID '{name}' already exists"),
+        }
+    }
+}
+
+impl std::error::Error for RenameDeviceError {}
+
+#[cfg(all(test, feature = "yaml"))]
+mod test {
+    use crate::{DeviceKind, NetplanConfig, RemoveDeviceError, RenameDeviceError};
+
+    const INPUT: &str = r#"
+        network:
+          version: 2
+          ethernets:
+            eth0: {}
+            eth1: {}
+          bonds:
+            bond0:
+              interfaces: [eth0, eth1]
+              parameters:
+                primary: eth0
+          vlans:
+            vlan10:
+              id: 10
+              link: bond0
+        "#;
+
+    #[test]
+    fn remove_device() {
+        let mut config: NetplanConfig = serde_yaml::from_str(INPUT).unwrap();
+        let network = &mut config.network;
+
+        assert_eq!(network.remove_device("eth0"), Ok(DeviceKind::Ethernet));
+        assert_eq!(
+            network.remove_device("eth0"),
+            Err(RemoveDeviceError::NotFound("eth0".to_string()))
+        );
+        assert!(network.device("eth0").is_none());
+
+        let bond = &network.bonds.as_ref().unwrap()["bond0"];
+        assert_eq!(bond.interfaces, Some(vec!["eth1".to_string()]));
+        assert_eq!(bond.parameters.as_deref().unwrap().primary, None);
+    }
+
+    #[test]
+    fn remove_device_with_vlans() {
+        let mut config: NetplanConfig = serde_yaml::from_str(INPUT).unwrap();
+        let network = &mut config.network;
+
+        assert_eq!(
+            network.remove_device("bond0"),
+            Err(RemoveDeviceError::LinkedVlans(vec!["vlan10".to_string()]))
+        );
+        assert!(network.device("bond0").is_some());
+        assert_eq!(
+            network.vlans.as_ref().unwrap()["vlan10"].link.as_deref(),
+            Some("bond0")
+        );
+
+        assert_eq!(network.remove_device("vlan10"), Ok(DeviceKind::Vlan));
+        assert_eq!(network.remove_device("bond0"), Ok(DeviceKind::Bond));
+    }
+
+    #[test]
+    fn rename_device() {
+        let mut config: NetplanConfig = serde_yaml::from_str(INPUT).unwrap();
+        let network = &mut config.network;
+
+        assert_eq!(
+            network.rename_device("eth0", "eth1"),
+            Err(RenameDeviceError::AlreadyExists("eth1".to_string()))
+        );
+        assert_eq!(
+            network.rename_device("eth0", "lan0"),
+            Ok(DeviceKind::Ethernet)
+        );
+
+        let bond = &network.bonds.as_ref().unwrap()["bond0"];
+        assert_eq!(
+            bond.interfaces,
+            Some(vec!["lan0".to_string(), "eth1".to_string()])
+        );
+        assert_eq!(
+            bond.parameters.as_deref().unwrap().primary.as_deref(),
+            Some("lan0")
+        );
+        assert!(network.device("lan0").is_some());
+    }
+
+    #[test]
+    fn filter_devices() {
+        let config = NetplanConfig::build(|net| {
+            net.ethernet("eth1", |e| e.dhcp4(true))
+                .ethernet("eth0", |e| e.dhcp6(true).address("192.0.2.10/24"))
+                .bond("bond0", |b| b.dhcp4(false).address("198.51.100.1/24"))
+                .wifi("wlan0", |w| w.dhcp4(true));
+        });
+        let names = |devices: Vec<(DeviceKind, &str, _)>| {
+            devices
+                .into_iter()
+                .map(|(_, name, _)| name.to_string())
+                .collect::<Vec<_>>()
+        };
+
+        let network = &config.network;
+        assert_eq!(names(network.dhcp_enabled()), ["eth0", "eth1", "wlan0"]);
+        assert_eq!(names(network.with_static_addresses()), ["eth0", "bond0"]);
+        assert_eq!(names(network.wireless()), ["wlan0"]);
+        assert!(network.find_devices(|_, name, _| name == "br0").is_empty());
+    }
+}
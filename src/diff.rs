@@ -0,0 +1,183 @@
+//! Structural comparison of two configurations.
+
+use crate::NetplanConfig;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fmt::{Display, Formatter};
+
+/// What happened to a field between two configurations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    /// The field is only set in the new configuration.
+    Added,
+    /// The field is only set in the old configuration.
+    Removed,
+    /// The field is set in both configurations, with a different value.
+    Modified,
+}
+
+/// A single difference between two configurations, see [diff].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Change {
+    pub kind: ChangeKind,
+    /// Path to the field, in netplan YAML notation, e.g. `network.ethernets.eth0.dhcp4`.
+    pub path: String,
+    /// The value in the old configuration. `None` if the field was added.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old: Option<Value>,
+    /// The value in the new configuration. `None` if the field was removed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new: Option<Value>,
+}
+
+impl Display for Change {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match (&self.old, &self.new) {
+            (Some(old), Some(new)) => write!(f, "~ {}: {old} -> {new}", self.path),
+            (None, Some(new)) => write!(f, "+ {}: {new}", self.path),
+            (Some(old), None) => write!(f, "- {}: {old}", self.path),
+            (None, None) => write!(f, "  {}", self.path),
+        }
+    }
+}
+
+/// Compare two configurations, returning every field which differs between them.
+///
+/// Mappings, like the device sections and the properties of a device, are compared key by key.
+/// Sequences are compared as a whole, as netplan treats them as a single value as well.
+/// Changes are ordered by path.
+///
+/// ```
+/// use netplan_types::{diff, ChangeKind, NetplanConfig};
+///
+/// let old = NetplanConfig::build(|net| {
+///     net.ethernet("eth0", |e| e.dhcp4(true));
+/// });
+/// let new = NetplanConfig::build(|net| {
+///     net.ethernet("eth0", |e| e.dhcp4(false).mtu(9000));
+/// });
+///
+/// let changes = diff(&old, &new);
+/// assert_eq!(changes.len(), 2);
+/// assert_eq!(changes[0].kind, ChangeKind::Modified);
+/// assert_eq!(changes[0].path, "network.ethernets.eth0.dhcp4");
+/// ```
+pub fn diff(old: &NetplanConfig, new: &NetplanConfig) -> Vec<Change> {
+    let mut changes = Vec::new();
+    diff_values(&mut changes, "", to_value(old), to_value(new));
+    changes.sort_by(|a, b| a.path.cmp(&b.path));
+    changes
+}
+
+impl NetplanConfig {
+    /// Compare this configuration to a newer one, see [diff].
+    pub fn diff(&self, new: &NetplanConfig) -> Vec<Change> {
+        diff(self, new)
+    }
+}
+
+fn to_value(config: &NetplanConfig) -> Value {
+    // Serializing the configuration types cannot fail, all map keys are strings
+    serde_json::to_value(config).expect("Serializing configuration")
+}
+
+fn diff_values(changes: &mut Vec<Change>, path: &str, old: Value, new: Value) {
+    match (old, new) {
+        (Value::Object(old), Value::Object(new)) => diff_maps(changes, path, old, new),
+        (old, new) if old != new => changes.push(Change {
+            kind: ChangeKind::Modified,
+            path: path.to_string(),
+            old: Some(old),
+            new: Some(new),
+        }),
+        _ => {}
+    }
+}
+
+fn diff_maps(
+    changes: &mut Vec<Change>,
+    path: &str,
+    mut old: Map<String, Value>,
+    new: Map<String, Value>,
+) {
+    for (key, new) in new {
+        let path = join(path, &key);
+        match old.remove(&key) {
+            Some(old) => diff_values(changes, &path, old, new),
+            None => changes.push(Change {
+                kind: ChangeKind::Added,
+                path,
+                old: None,
+                new: Some(new),
+            }),
+        }
+    }
+
+    for (key, old) in old {
+        changes.push(Change {
+            kind: ChangeKind::Removed,
+            path: join(path, &key),
+            old: Some(old),
+            new: None,
+        });
+    }
+}
+
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{path}.{key}")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{diff, ChangeKind, NetplanConfig};
+
+    #[test]
+    fn changes() {
+        let old = r#"
+            network:
+              version: 2
+              ethernets:
+                eth0:
+                  addresses: [10.0.0.2/24]
+                  nameservers:
+                    addresses: [10.0.0.1]
+                eth1:
+                  dhcp4: true
+            "#;
+        let new = r#"
+            network:
+              version: 2
+              renderer: networkd
+              ethernets:
+                eth0:
+                  addresses: [10.0.0.3/24]
+                  nameservers:
+                    addresses: [10.0.0.1]
+            "#;
+
+        let old: NetplanConfig = serde_yaml::from_str(old).unwrap();
+        let new: NetplanConfig = serde_yaml::from_str(new).unwrap();
+        let changes = diff(&old, &new)
+            .into_iter()
+            .map(|change| (change.kind, change.path))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            changes,
+            vec![
+                (
+                    ChangeKind::Modified,
+                    "network.ethernets.eth0.addresses".to_string()
+                ),
+                (ChangeKind::Removed, "network.ethernets.eth1".to_string()),
+                (ChangeKind::Added, "network.renderer".to_string()),
+            ]
+        );
+        assert!(diff(&old, &old).is_empty());
+    }
+}
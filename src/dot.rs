@@ -0,0 +1,143 @@
+//! Rendering the topology of a configuration as a Graphviz DOT graph.
+
+use crate::{DependencyKind, DeviceKind, NetworkConfig};
+use std::collections::HashMap;
+
+impl NetworkConfig {
+    /// Render the devices and their dependencies, see [NetworkConfig::dependency_graph], as a
+    /// Graphviz DOT digraph, e.g. for `dot -Tsvg`. Every device is a node labeled with its
+    /// ID, its section and its static addresses, shaped after its kind. Edges point from a
+    /// device to the devices it depends on: solid to the members of bonds, bridges and
+    /// VRFs, and dashed to links. Devices which are referenced but not defined are dashed
+    /// nodes. The output is sorted, so it only changes when the configuration does.
+    ///
+    /// ```
+    /// use netplan_types::NetplanConfig;
+    ///
+    /// let config = NetplanConfig::build(|net| {
+    ///     net.ethernet("eth0", |e| e)
+    ///         .bond("bond0", |b| b.interfaces(["eth0"]).address("192.0.2.10/24"));
+    /// });
+    ///
+    /// let dot = config.network.to_dot();
+    /// assert!(dot.starts_with("digraph netplan {\n"));
+    /// assert!(dot.contains(r#""bond0" [label="bond0\nbonds\n192.0.2.10/24", shape=hexagon];"#));
+    /// assert!(dot.contains(r#""bond0" -> "eth0" [label="member"];"#));
+    /// ```
+    pub fn to_dot(&self) -> String {
+        let kinds = self
+            .devices()
+            .map(|(kind, name, device)| (name, (kind, device)))
+            .collect::<HashMap<_, _>>();
+        let graph = self.dependency_graph();
+
+        let mut dot = String::from("digraph netplan {\n");
+        dot.push_str("  node [fontname=\"sans-serif\"];\n");
+        dot.push_str("  edge [fontname=\"sans-serif\", fontsize=10];\n");
+
+        for name in graph.devices() {
+            let Some((kind, device)) = kinds.get(name) else {
+                dot.push_str(&format!("  {} [style=dashed];\n", quote(name)));
+                continue;
+            };
+
+            let mut label = format!("{name}\n{kind}");
+            let addresses = device
+                .common_all()
+                .and_then(|common| common.addresses.as_ref())
+                .into_iter()
+                .flatten()
+                .flat_map(|address| {
+                    let mut addresses = address.addresses();
+                    addresses.sort();
+                    addresses
+                });
+            for address in addresses {
+                label.push('\n');
+                label.push_str(address);
+            }
+
+            dot.push_str(&format!(
+                "  {} [label={}, shape={}];\n",
+                quote(name),
+                quote(&label),
+                shape(*kind)
+            ));
+        }
+
+        for (device, dependency, kind) in graph.edges() {
+            let attributes = match kind {
+                DependencyKind::Member => "label=\"member\"",
+                DependencyKind::Link => "label=\"link\", style=dashed",
+            };
+            dot.push_str(&format!(
+                "  {} -> {} [{attributes}];\n",
+                quote(device),
+                quote(dependency)
+            ));
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+fn shape(kind: DeviceKind) -> &'static str {
+    match kind {
+        DeviceKind::Ethernet | DeviceKind::Wifi | DeviceKind::Modem => "box",
+        DeviceKind::Bond => "hexagon",
+        DeviceKind::Bridge => "octagon",
+        DeviceKind::Vlan => "ellipse",
+        DeviceKind::Tunnel => "cds",
+        DeviceKind::Vrf => "folder",
+        DeviceKind::DummyDevice | DeviceKind::NmDevice => "note",
+    }
+}
+
+/// Quote an ID for DOT, in which newlines become line breaks of the label.
+fn quote(id: &str) -> String {
+    let escaped = id
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n");
+    format!("\"{escaped}\"")
+}
+
+#[cfg(test)]
+mod test {
+    use crate::NetplanConfig;
+
+    #[test]
+    fn render_topology() {
+        let config = NetplanConfig::build(|net| {
+            net.ethernet("eth0", |e| e)
+                .ethernet("eth1", |e| e)
+                .bond("bond0", |b| b.interfaces(["eth0", "eth1"]))
+                .vlan("vlan10", |v| v.id(10).link("bond0"))
+                .bridge("br0", |b| {
+                    b.interfaces(["vlan10", "tap0"])
+                        .addresses(["192.0.2.1/24", "2001:db8::1/64"])
+                })
+                .tunnel("wg0", |t| t.address("10.0.0.1/24"));
+        });
+
+        let expected = r#"digraph netplan {
+  node [fontname="sans-serif"];
+  edge [fontname="sans-serif", fontsize=10];
+  "bond0" [label="bond0\nbonds", shape=hexagon];
+  "br0" [label="br0\nbridges\n192.0.2.1/24\n2001:db8::1/64", shape=octagon];
+  "eth0" [label="eth0\nethernets", shape=box];
+  "eth1" [label="eth1\nethernets", shape=box];
+  "tap0" [style=dashed];
+  "vlan10" [label="vlan10\nvlans", shape=ellipse];
+  "wg0" [label="wg0\ntunnels\n10.0.0.1/24", shape=cds];
+  "bond0" -> "eth0" [label="member"];
+  "bond0" -> "eth1" [label="member"];
+  "br0" -> "tap0" [label="member"];
+  "br0" -> "vlan10" [label="member"];
+  "vlan10" -> "bond0" [label="link", style=dashed];
+}
+"#;
+        assert_eq!(config.network.to_dot(), expected);
+    }
+}
@@ -0,0 +1,374 @@
+//! Detecting drift between a configuration and the live network state.
+
+use crate::{AnyDeviceConfig, LinkState, NetplanConfig, RouteState, RoutingConfig, SystemState};
+use crate::{RouteType, MAIN_TABLE};
+use ipnet::IpNet;
+use std::fmt::{Display, Formatter};
+use std::net::IpAddr;
+
+/// How the state of a link differs from its definition.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum DriftKind {
+    /// No link exists for the device.
+    MissingLink,
+    /// The MTU of the link differs from the configured MTU.
+    Mtu { expected: u16, actual: Option<u32> },
+    /// A configured static address is not assigned to the link.
+    MissingAddress(String),
+    /// A static address is assigned to the link, but not configured.
+    UnexpectedAddress(String),
+    /// A configured member is not a member of the bond, bridge or VRF.
+    MissingMember(String),
+    /// A link is a member of the bond, bridge or VRF, but not configured as one.
+    UnexpectedMember(String),
+    /// A configured route is not installed.
+    MissingRoute(String),
+    /// A static route is installed over the link, but not configured.
+    UnexpectedRoute(String),
+}
+
+/// A difference between a device definition and the state of its link, see [drift].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Drift {
+    /// The ID of the device definition.
+    pub device: String,
+    pub kind: DriftKind,
+}
+
+impl Display for Drift {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let device = &self.device;
+        match &self.kind {
+            DriftKind::MissingLink => write!(f, "{device}: no such link"),
+            DriftKind::Mtu {
+                expected,
+                actual: Some(actual),
+            } => write!(f, "{device}: MTU is {actual}, configured {expected}"),
+            DriftKind::Mtu { expected, .. } => {
+                write!(f, "{device}: MTU is unknown, configured {expected}")
+            }
+            DriftKind::MissingAddress(address) => write!(f, "{device}: {address} is not assigned"),
+            DriftKind::UnexpectedAddress(address) => {
+                write!(f, "{device}: {address} is assigned, but not configured")
+            }
+            DriftKind::MissingMember(member) => write!(f, "{device}: {member} is not a member"),
+            DriftKind::UnexpectedMember(member) => {
+                write!(f, "{device}: {member} is a member, but not configured")
+            }
+            DriftKind::MissingRoute(route) => write!(f, "{device}: route {route} is missing"),
+            DriftKind::UnexpectedRoute(route) => {
+                write!(
+                    f,
+                    "{device}: route {route} is installed, but not configured"
+                )
+            }
+        }
+    }
+}
+
+/// Compare a configuration to the live network state, returning every link whose addresses,
+/// MTU, members or routes differ from its definition. Drift is ordered by device ID.
+///
+/// Devices are matched to links by their `set-name`, their `match.macaddress` or a
+/// `match.name` without wildcards, and by their ID otherwise. Only static addresses and
+/// routes are compared, addresses and routes learned by DHCP or router advertisements are
+/// ignored, as are IPv6 link-local addresses. The MTU is only compared if it is configured.
+///
+/// ```
+/// use netplan_types::{drift, DriftKind, LinkState, NetplanConfig, SystemState};
+///
+/// let config = NetplanConfig::build(|net| {
+///     net.ethernet("eth0", |e| e.mtu(9000));
+/// });
+/// let state = SystemState {
+///     links: vec![LinkState { index: 2, name: "eth0".into(), mtu: Some(1500), ..Default::default() }],
+///     routes: vec![],
+/// };
+///
+/// let drift = drift(&config, &state);
+/// assert_eq!(drift[0].kind, DriftKind::Mtu { expected: 9000, actual: Some(1500) });
+/// assert_eq!(drift[0].to_string(), "eth0: MTU is 1500, configured 9000");
+/// ```
+pub fn drift(config: &NetplanConfig, state: &SystemState) -> Vec<Drift> {
+    let mut devices = config.network.devices().collect::<Vec<_>>();
+    devices.sort_by_key(|(_, name, _)| *name);
+
+    let mut drift = Vec::new();
+    for (_, name, device) in devices {
+        let mut report = |kind| {
+            drift.push(Drift {
+                device: name.to_string(),
+                kind,
+            })
+        };
+
+        let Some(link) = find_link(name, device, state) else {
+            report(DriftKind::MissingLink);
+            continue;
+        };
+
+        let common = device.common_all();
+        if let Some(expected) = common.and_then(|c| c.mtu) {
+            if link.mtu != Some(expected.into()) {
+                report(DriftKind::Mtu {
+                    expected,
+                    actual: link.mtu,
+                });
+            }
+        }
+
+        let expected = common
+            .and_then(|c| c.addresses.as_ref())
+            .into_iter()
+            .flatten()
+            .flat_map(|a| a.addresses())
+            .filter_map(|address| address.parse::<IpNet>().ok())
+            .collect::<Vec<_>>();
+        let actual = link
+            .addresses
+            .iter()
+            .filter(|address| !address.dynamic && !address.is_link_local())
+            .filter_map(|address| IpNet::new(address.address, address.prefix).ok())
+            .collect::<Vec<_>>();
+        for address in expected.iter().filter(|a| !actual.contains(a)) {
+            report(DriftKind::MissingAddress(address.to_string()));
+        }
+        for address in actual.iter().filter(|a| !expected.contains(a)) {
+            report(DriftKind::UnexpectedAddress(address.to_string()));
+        }
+
+        if let Some(members) = members(device) {
+            let expected = members
+                .iter()
+                .map(|member| match config.network.device(member) {
+                    Some(device) => find_link(member, device, state)
+                        .map_or(member.as_str(), |link| link.name.as_str()),
+                    None => member.as_str(),
+                })
+                .collect::<Vec<_>>();
+            let actual = state.members(link.index);
+            for member in expected.iter().filter(|m| !actual.contains(m)) {
+                report(DriftKind::MissingMember(member.to_string()));
+            }
+            for member in actual.iter().filter(|m| !expected.contains(m)) {
+                report(DriftKind::UnexpectedMember(member.to_string()));
+            }
+        }
+
+        let expected = common
+            .and_then(|c| c.routes.as_ref())
+            .into_iter()
+            .flatten()
+            .filter(|route| matches!(route.r#type, None | Some(RouteType::Unicast)))
+            .filter_map(configured_route)
+            .collect::<Vec<_>>();
+        let actual = state
+            .static_routes(link.index)
+            .map(installed_route)
+            .collect::<Vec<_>>();
+        for route in expected.iter().filter(|r| !actual.contains(r)) {
+            report(DriftKind::MissingRoute(route.to_string()));
+        }
+        for route in actual.iter().filter(|r| !expected.contains(r)) {
+            report(DriftKind::UnexpectedRoute(route.to_string()));
+        }
+    }
+
+    drift
+}
+
+impl NetplanConfig {
+    /// Compare the configuration to the live network state, see [drift].
+    pub fn drift(&self, state: &SystemState) -> Vec<Drift> {
+        drift(self, state)
+    }
+}
+
+/// The link created for a device definition.
+pub(crate) fn find_link<'a>(
+    name: &str,
+    device: AnyDeviceConfig<'_>,
+    state: &'a SystemState,
+) -> Option<&'a LinkState> {
+    let physical = device.common_physical();
+    if let Some(set_name) = physical.and_then(|p| p.set_name.as_deref()) {
+        return state.link_by_name(set_name);
+    }
+
+    let matching = physical.and_then(|p| p.r#match.as_ref());
+    if let Some(macaddress) = matching.and_then(|m| m.macaddress.as_ref()) {
+        let macaddress = macaddress.to_string().to_lowercase();
+        return state
+            .links
+            .iter()
+            .find(|link| link.macaddress.as_deref() == Some(macaddress.as_str()));
+    }
+
+    match matching.and_then(|m| m.name.as_deref()) {
+        Some(pattern) if !crate::glob::is_glob(pattern) => state.link_by_name(pattern),
+        _ => state.link_by_name(name),
+    }
+}
+
+/// The configured members of a bond, bridge or VRF.
+fn members<'a>(device: AnyDeviceConfig<'a>) -> Option<&'a [String]> {
+    match device {
+        AnyDeviceConfig::Bond(bond) => Some(bond.interfaces.as_deref().unwrap_or_default()),
+        AnyDeviceConfig::Bridge(bridge) => Some(bridge.interfaces.as_deref().unwrap_or_default()),
+        AnyDeviceConfig::Vrf(vrf) => Some(&vrf.interfaces),
+        _ => None,
+    }
+}
+
+/// A route, normalized so configured and installed routes can be compared.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct Route {
+    /// The destination, `None` for a default route.
+    to: Option<IpNet>,
+    via: Option<IpAddr>,
+    table: u32,
+}
+
+impl Display for Route {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.to {
+            Some(to) => write!(f, "to {to}")?,
+            None => write!(f, "to default")?,
+        }
+        if let Some(via) = self.via {
+            write!(f, " via {via}")?;
+        }
+        if self.table != MAIN_TABLE {
+            write!(f, " table {}", self.table)?;
+        }
+
+        Ok(())
+    }
+}
+
+pub(crate) fn configured_route(route: &RoutingConfig) -> Option<Route> {
+    let to = match route.to.as_deref()? {
+        "default" => None,
+        to => {
+            let net = to
+                .parse::<IpNet>()
+                .or_else(|_| to.parse::<IpAddr>().map(IpNet::from))
+                .ok()?;
+            Some(net).filter(|net| net.prefix_len() != 0)
+        }
+    };
+
+    Some(Route {
+        to,
+        via: route.via.as_deref().and_then(|via| via.parse().ok()),
+        table: route.table.map_or(MAIN_TABLE, u32::from),
+    })
+}
+
+pub(crate) fn installed_route(route: &RouteState) -> Route {
+    Route {
+        to: route
+            .to
+            .and_then(|to| IpNet::new(to, route.prefix).ok())
+            .filter(|net| net.prefix_len() != 0),
+        via: route.via,
+        table: route.table,
+    }
+}
+
+#[cfg(all(test, feature = "yaml"))]
+mod test {
+    use crate::{
+        AddressState, DriftKind, LinkState, NetplanConfig, RouteOrigin, RouteState, SystemState,
+    };
+
+    #[test]
+    fn drift_between_config_and_state() {
+        let config = NetplanConfig::from_yaml(
+            r#"
+network:
+  version: 2
+  ethernets:
+    nic0:
+      match: {macaddress: "52:54:00:AA:BB:01"}
+      set-name: eth0
+    eth1: {}
+    eth2: {}
+  bonds:
+    bond0:
+      interfaces: [nic0, eth1]
+      addresses: [10.0.0.2/24]
+      routes:
+        - to: default
+          via: 10.0.0.1
+        - to: 10.1.0.0/16
+          via: 10.0.0.254
+  bridges:
+    br0:
+      interfaces: [eth2]
+"#,
+        )
+        .unwrap();
+
+        let link = |index: u32, name: &str| LinkState {
+            index,
+            name: name.to_string(),
+            ..Default::default()
+        };
+        let state = SystemState {
+            links: vec![
+                LinkState {
+                    controller: Some(4),
+                    ..link(2, "eth0")
+                },
+                link(3, "eth1"),
+                LinkState {
+                    controller: Some(4),
+                    ..link(5, "eth2")
+                },
+                LinkState {
+                    addresses: vec![
+                        AddressState {
+                            address: "10.0.0.2".parse().unwrap(),
+                            prefix: 24,
+                            dynamic: false,
+                        },
+                        AddressState {
+                            address: "10.0.0.3".parse().unwrap(),
+                            prefix: 24,
+                            dynamic: true,
+                        },
+                    ],
+                    ..link(4, "bond0")
+                },
+            ],
+            routes: vec![RouteState {
+                to: Some("0.0.0.0".parse().unwrap()),
+                prefix: 0,
+                via: Some("10.0.0.1".parse().unwrap()),
+                link: Some(4),
+                metric: None,
+                table: 254,
+                origin: RouteOrigin::Static,
+            }],
+        };
+
+        let drift = config.drift(&state);
+        let kinds = drift
+            .iter()
+            .map(|d| (d.device.as_str(), d.kind.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            [
+                ("bond0", DriftKind::MissingMember("eth1".into())),
+                ("bond0", DriftKind::UnexpectedMember("eth2".into())),
+                (
+                    "bond0",
+                    DriftKind::MissingRoute("to 10.1.0.0/16 via 10.0.0.254".into())
+                ),
+                ("br0", DriftKind::MissingLink),
+            ]
+        );
+    }
+}
@@ -0,0 +1,274 @@
+//! Resolving the defaults netplan applies to fields which are not set.
+
+use crate::{
+    AccessPointMode, AdSelect, BondMode, BondParameters, BridgeParameters, CommonProperties,
+    CommonPropertiesAllDevices, DeviceKind, DhcpOverrides, FailOverMacPolicy, LacpRate,
+    NetplanConfig, NetworkConfig, PhysicalProperties, PrimaryReselectPolicy, Renderer, RouteScope,
+    RouteType, TransmitHashPolicy,
+};
+use std::collections::HashMap;
+
+impl NetplanConfig {
+    /// A copy of the configuration with the defaults filled in, see [NetworkConfig::effective].
+    pub fn effective(&self) -> Self {
+        Self {
+            network: self.network.effective(),
+        }
+    }
+}
+
+impl NetworkConfig {
+    /// A copy of the configuration in which every field which is not set, but for which
+    /// netplan or the kernel has a well known default, is set to that default. For example
+    /// the renderer is set to networkd, DHCP is disabled, link-local addressing is IPv6 only,
+    /// STP is enabled on bridges and bonds use balance-rr mode.
+    ///
+    /// This answers what a configuration will actually do, without having to know the
+    /// defaults. The result is meant to be queried rather than written back, as it is far
+    /// more verbose than the original.
+    ///
+    /// Fields whose default depends on the kernel, the renderer's version or the system, like
+    /// `accept-ra` or the offloading settings, are left unset.
+    ///
+    /// ```
+    /// use netplan_types::{NetplanConfig, Renderer};
+    ///
+    /// let config = NetplanConfig::build(|net| {
+    ///     net.ethernet("eth0", |e| e.dhcp4(true));
+    /// });
+    ///
+    /// let effective = config.effective();
+    /// let eth0 = &effective.network.ethernets.as_ref().unwrap()["eth0"];
+    /// let common = eth0.common_all.as_deref().unwrap();
+    /// assert_eq!(common.renderer, Some(Renderer::Networkd));
+    /// assert_eq!(common.dhcp6, Some(false));
+    /// assert_eq!(common.dhcp4_overrides.as_ref().unwrap().use_dns, Some(true));
+    /// ```
+    pub fn effective(&self) -> Self {
+        let mut network = self.clone();
+        let renderer = network.renderer.get_or_insert(Renderer::Networkd).clone();
+
+        for (kind, _, mut device) in network.devices_mut() {
+            // Connections netplan cannot express are always rendered by NetworkManager
+            let renderer = match kind {
+                DeviceKind::NmDevice => &Renderer::NetworkManager,
+                _ => &renderer,
+            };
+            effective_common(device.common_all_mut(), renderer);
+        }
+
+        let physical = network
+            .ethernets
+            .iter_mut()
+            .flat_map(HashMap::values_mut)
+            .map(|ethernet| ethernet.common_physical_mut())
+            .chain(
+                network
+                    .wifis
+                    .iter_mut()
+                    .flat_map(HashMap::values_mut)
+                    .map(|wifi| wifi.common_physical_mut()),
+            )
+            .chain(
+                network
+                    .modems
+                    .iter_mut()
+                    .flat_map(HashMap::values_mut)
+                    .map(|modem| modem.common_physical_mut()),
+            );
+        for physical in physical {
+            physical.wakeonlan.get_or_insert(false);
+            physical.emit_lldp.get_or_insert(false);
+        }
+
+        for wifi in network.wifis.iter_mut().flat_map(HashMap::values_mut) {
+            for access_point in wifi.access_points.iter_mut().flat_map(HashMap::values_mut) {
+                access_point
+                    .mode
+                    .get_or_insert(AccessPointMode::Infrastructure);
+                access_point.hidden.get_or_insert(false);
+            }
+        }
+
+        for bond in network.bonds.iter_mut().flat_map(HashMap::values_mut) {
+            effective_bond(bond.parameters.get_or_insert_with(Default::default));
+        }
+
+        for bridge in network.bridges.iter_mut().flat_map(HashMap::values_mut) {
+            effective_bridge(bridge.parameters.get_or_insert_with(Default::default));
+        }
+
+        network
+    }
+}
+
+fn effective_common(common: &mut CommonPropertiesAllDevices, renderer: &Renderer) {
+    common.renderer.get_or_insert_with(|| renderer.clone());
+    common
+        .dhcp_identifier
+        .get_or_insert_with(|| "duid".to_string());
+    common
+        .link_local
+        .get_or_insert_with(|| vec!["ipv6".to_string()]);
+    for flag in [
+        &mut common.ipv6_privacy,
+        &mut common.ignore_carrier,
+        &mut common.critical,
+        &mut common.optional,
+    ] {
+        flag.get_or_insert(false);
+    }
+
+    for (enabled, overrides) in [
+        (
+            *common.dhcp4.get_or_insert(false),
+            &mut common.dhcp4_overrides,
+        ),
+        (
+            *common.dhcp6.get_or_insert(false),
+            &mut common.dhcp6_overrides,
+        ),
+    ] {
+        if enabled {
+            effective_overrides(overrides.get_or_insert_with(Default::default));
+        }
+    }
+
+    for route in common.routes.iter_mut().flatten() {
+        route.r#type.get_or_insert(RouteType::Unicast);
+        route.scope.get_or_insert(RouteScope::Global);
+        route.table.get_or_insert(254);
+        route.on_link.get_or_insert(false);
+    }
+}
+
+fn effective_overrides(overrides: &mut DhcpOverrides) {
+    for flag in [
+        &mut overrides.use_dns,
+        &mut overrides.use_ntp,
+        &mut overrides.send_hostname,
+        &mut overrides.use_hostname,
+        &mut overrides.use_mtu,
+        &mut overrides.use_routes,
+    ] {
+        flag.get_or_insert(true);
+    }
+}
+
+/// Fill in the bonding driver's defaults, for the parameters which apply to the mode.
+fn effective_bond(parameters: &mut BondParameters) {
+    let mode = parameters.mode.get_or_insert(BondMode::BalanceRr).clone();
+    parameters
+        .mii_monitor_interval
+        .get_or_insert_with(|| "0".to_string());
+    parameters
+        .arp_interval
+        .get_or_insert_with(|| "0".to_string());
+    parameters.up_delay.get_or_insert_with(|| "0".to_string());
+    parameters.down_delay.get_or_insert_with(|| "0".to_string());
+    parameters.min_links.get_or_insert(1);
+    parameters.all_slaves_active.get_or_insert(false);
+    parameters.resend_igmp.get_or_insert(1);
+
+    match mode {
+        BondMode::BalanceRr => {
+            parameters.packets_per_slave.get_or_insert(1);
+        }
+        BondMode::ActiveBackup => {
+            parameters
+                .fail_over_mac_policy
+                .get_or_insert(FailOverMacPolicy::None);
+            parameters.gratuitous_arp.get_or_insert(1);
+            parameters
+                .primary_reselect_policy
+                .get_or_insert(PrimaryReselectPolicy::Always);
+        }
+        BondMode::BalanceXor => {
+            parameters
+                .transmit_hash_policy
+                .get_or_insert(TransmitHashPolicy::Layer2);
+        }
+        BondMode::Broadcast => {}
+        BondMode::EightZeroTwoDotThreeAD => {
+            parameters.lacp_rate.get_or_insert(LacpRate::Slow);
+            parameters.ad_select.get_or_insert(AdSelect::Stable);
+            parameters
+                .transmit_hash_policy
+                .get_or_insert(TransmitHashPolicy::Layer2);
+        }
+        BondMode::BalanceTlb | BondMode::BalanceAlb => {
+            parameters
+                .primary_reselect_policy
+                .get_or_insert(PrimaryReselectPolicy::Always);
+            parameters
+                .learn_packet_interval
+                .get_or_insert_with(|| "1".to_string());
+            if mode == BondMode::BalanceTlb {
+                parameters
+                    .transmit_hash_policy
+                    .get_or_insert(TransmitHashPolicy::Layer2);
+            }
+        }
+    }
+}
+
+/// Fill in the kernel's bridge defaults.
+fn effective_bridge(parameters: &mut BridgeParameters) {
+    parameters.stp.get_or_insert(true);
+    parameters.priority.get_or_insert(32768);
+    parameters
+        .ageing_time
+        .get_or_insert_with(|| "300".to_string());
+    parameters
+        .forward_delay
+        .get_or_insert_with(|| "15".to_string());
+    parameters.hello_time.get_or_insert_with(|| "2".to_string());
+    parameters.max_age.get_or_insert_with(|| "20".to_string());
+}
+
+#[cfg(all(test, feature = "yaml"))]
+mod test {
+    use crate::{BondMode, LacpRate, NetplanConfig, Renderer};
+
+    #[test]
+    fn defaults() {
+        let input = r#"
+            network:
+              version: 2
+              renderer: NetworkManager
+              bonds:
+                bond0:
+                  interfaces: [eth0, eth1]
+                  parameters:
+                    mode: 802.3ad
+              bridges:
+                br0:
+                  renderer: networkd
+                  interfaces: [bond0]
+                  parameters:
+                    stp: false
+            "#;
+
+        let config: NetplanConfig = serde_yaml::from_str(input).unwrap();
+        let network = config.effective().network;
+
+        let bond = &network.bonds.as_ref().unwrap()["bond0"];
+        let parameters = bond.parameters.as_deref().unwrap();
+        assert_eq!(parameters.mode, Some(BondMode::EightZeroTwoDotThreeAD));
+        assert_eq!(parameters.lacp_rate, Some(LacpRate::Slow));
+        assert_eq!(parameters.packets_per_slave, None);
+        assert_eq!(
+            bond.common_all.as_deref().unwrap().renderer,
+            Some(Renderer::NetworkManager)
+        );
+
+        let bridge = &network.bridges.as_ref().unwrap()["br0"];
+        let parameters = bridge.parameters.as_deref().unwrap();
+        assert_eq!(parameters.stp, Some(false));
+        assert_eq!(parameters.priority, Some(32768));
+        assert_eq!(
+            bridge.common_all.as_deref().unwrap().renderer,
+            Some(Renderer::Networkd)
+        );
+    }
+}
@@ -0,0 +1,142 @@
+//! Configuration files, named by netplan's `NN-name.yaml` convention.
+
+use crate::{LoadError, NetplanConfig, WriteOptions};
+use std::cmp::Ordering;
+use std::path::{Path, PathBuf};
+
+/// A configuration file in one of netplan's directories, e.g. `50-cloud-init.yaml`.
+///
+/// Netplan merges its files in lexicographic order of their file names, so files named
+/// with a higher priority override the settings of files with a lower priority. The
+/// ordering of [NetplanFile] follows the order netplan merges the files in.
+///
+/// ```
+/// use netplan_types::{NetplanConfig, NetplanFile};
+///
+/// let cloud_init = NetplanFile::new(50, "cloud-init", NetplanConfig::new());
+/// assert_eq!(cloud_init.file_name(), "50-cloud-init.yaml");
+///
+/// let file = cloud_init.overriding("provisioning", NetplanConfig::new()).unwrap();
+/// assert_eq!(file.file_name(), "51-provisioning.yaml");
+/// assert!(file > cloud_init);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetplanFile {
+    /// The priority of the file, written as the two digit prefix of the file name.
+    /// Priorities above 99 do not sort correctly, as they have three digits.
+    pub priority: u8,
+    /// The name of the file, without the priority and extension.
+    pub name: String,
+    pub config: NetplanConfig,
+}
+
+impl NetplanFile {
+    pub fn new(priority: u8, name: impl Into<String>, config: NetplanConfig) -> Self {
+        Self {
+            priority,
+            name: name.into(),
+            config,
+        }
+    }
+
+    /// The file name, e.g. `50-cloud-init.yaml`.
+    pub fn file_name(&self) -> String {
+        format!("{:02}-{}.yaml", self.priority, self.name)
+    }
+
+    /// Split a file name like `50-cloud-init.yaml` into its priority and name.
+    pub fn parse_file_name(file_name: &str) -> Option<(u8, &str)> {
+        let stem = file_name.strip_suffix(".yaml")?;
+        let (priority, name) = stem.split_once('-')?;
+        let digits = !priority.is_empty() && priority.bytes().all(|b| b.is_ascii_digit());
+        if !digits || name.is_empty() {
+            return None;
+        }
+
+        Some((priority.parse().ok()?, name))
+    }
+
+    /// Load a file, whose name has to follow the `NN-name.yaml` convention.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, LoadError> {
+        let path = path.as_ref();
+        let (priority, name) = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(Self::parse_file_name)
+            .ok_or_else(|| LoadError::FileName(path.to_path_buf()))?;
+
+        Ok(Self::new(priority, name, NetplanConfig::load(path)?))
+    }
+
+    /// Write the file into `dir`, see [NetplanConfig::write_to_with]. Returns the path of the file.
+    pub fn write_to_dir(
+        &self,
+        dir: impl AsRef<Path>,
+        options: &WriteOptions,
+    ) -> std::io::Result<PathBuf> {
+        let path = dir.as_ref().join(self.file_name());
+        self.config.write_to_with(&path, options)?;
+        Ok(path)
+    }
+
+    /// Compare the files by the order netplan merges them in, i.e. by their file names.
+    /// Useful to sort files, e.g. `files.sort_by(NetplanFile::cmp_precedence)`.
+    pub fn cmp_precedence(&self, other: &NetplanFile) -> Ordering {
+        self.file_name().cmp(&other.file_name())
+    }
+
+    /// Whether netplan merges this file after `other`, so its settings take precedence.
+    pub fn overrides(&self, other: &NetplanFile) -> bool {
+        self.cmp_precedence(other) == Ordering::Greater
+    }
+
+    /// A file named `name`, which overrides this file. It gets the next priority, or the
+    /// same priority if this file has priority 99 and `name` sorts after its name.
+    /// Returns `None` if no such file name exists.
+    pub fn overriding(&self, name: impl Into<String>, config: NetplanConfig) -> Option<Self> {
+        let name = name.into();
+        let file = if self.priority < 99 {
+            Self::new(self.priority + 1, name, config)
+        } else {
+            Self::new(self.priority, name, config)
+        };
+
+        file.overrides(self).then_some(file)
+    }
+}
+
+/// Files are ordered by their file names, like netplan orders them. Files with the same
+/// name but a different configuration are not comparable.
+impl PartialOrd for NetplanFile {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match self.cmp_precedence(other) {
+            Ordering::Equal => (self.config == other.config).then_some(Ordering::Equal),
+            ordering => Some(ordering),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{NetplanConfig, NetplanFile};
+
+    #[test]
+    fn file_names() {
+        assert_eq!(
+            NetplanFile::parse_file_name("50-cloud-init.yaml"),
+            Some((50, "cloud-init"))
+        );
+        assert_eq!(NetplanFile::parse_file_name("config.yaml"), None);
+        assert_eq!(NetplanFile::parse_file_name("50-.yaml"), None);
+        assert_eq!(NetplanFile::parse_file_name("50-cloud-init.yml"), None);
+
+        let last = NetplanFile::new(99, "zz", NetplanConfig::new());
+        assert!(last.overriding("aa", NetplanConfig::new()).is_none());
+        assert_eq!(
+            last.overriding("zzz", NetplanConfig::new())
+                .unwrap()
+                .file_name(),
+            "99-zzz.yaml"
+        );
+    }
+}
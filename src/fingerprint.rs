@@ -0,0 +1,195 @@
+//! Fingerprints of configurations, which only change when their effect does.
+
+use crate::NetplanConfig;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// A SHA-256 hash of the canonical form of a configuration, see
+/// [NetplanConfig::fingerprint]. Displayed, parsed and serialized as 64 lowercase hex digits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Fingerprint([u8; 32]);
+
+impl Fingerprint {
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+impl Display for Fingerprint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for byte in self.0 {
+            write!(f, "{byte:02x}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The error returned when parsing a [Fingerprint] which is not 64 hex digits.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseFingerprintError(String);
+
+impl Display for ParseFingerprintError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid fingerprint '{}'", self.0)
+    }
+}
+
+impl std::error::Error for ParseFingerprintError {}
+
+impl FromStr for Fingerprint {
+    type Err = ParseFingerprintError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseFingerprintError(s.to_string());
+        if s.len() != 64 || !s.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(error());
+        }
+
+        let mut bytes = [0; 32];
+        for (byte, hex) in bytes.iter_mut().zip(s.as_bytes().chunks(2)) {
+            // The string is ASCII, so every chunk is a valid str
+            let hex = std::str::from_utf8(hex).map_err(|_| error())?;
+            *byte = u8::from_str_radix(hex, 16).map_err(|_| error())?;
+        }
+
+        Ok(Self(bytes))
+    }
+}
+
+impl Serialize for Fingerprint {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Fingerprint {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+impl NetplanConfig {
+    /// A fingerprint of the configuration, which is the same for configurations with the
+    /// same effect, i.e. those which are [semantically equal](NetplanConfig::semantically_eq),
+    /// regardless of the order of their devices, their formatting or the files they were
+    /// loaded from. It can be compared to the fingerprint of the applied configuration to
+    /// decide whether applying is needed, or recorded as the version of a configuration.
+    ///
+    /// The fingerprint is the SHA-256 hash of the canonical form as JSON, with the keys of
+    /// every object sorted. It does not depend on the platform or on the process, but it
+    /// changes if the canonical form changes between releases of this crate, which is
+    /// noted in the changelog.
+    ///
+    /// ```
+    /// use netplan_types::NetplanConfig;
+    ///
+    /// let a = NetplanConfig::from_yaml("
+    /// network:
+    ///   version: 2
+    ///   renderer: networkd
+    ///   ethernets:
+    ///     eth1: {dhcp4: true}
+    ///     eth0: {dhcp4: yes, dhcp6: false}
+    /// ").unwrap();
+    /// let b = NetplanConfig::from_yaml("
+    /// network: {version: 2, ethernets: {eth0: {dhcp4: true}, eth1: {dhcp4: true}}}
+    /// ").unwrap();
+    ///
+    /// assert_eq!(a.fingerprint(), b.fingerprint());
+    /// assert_ne!(a.fingerprint(), NetplanConfig::single_nic_dhcp("eth0").fingerprint());
+    /// ```
+    pub fn fingerprint(&self) -> Fingerprint {
+        // Serializing the configuration types cannot fail, all map keys are strings
+        let value = serde_json::to_value(self.canonicalized()).expect("Serializing configuration");
+        let mut hasher = Sha256::new();
+        hash_value(&mut hasher, &value);
+        Fingerprint(hasher.finalize().into())
+    }
+}
+
+/// Feed a value to the hasher as compact JSON, with the keys of objects sorted, so the
+/// order of a `HashMap` does not matter.
+fn hash_value(hasher: &mut Sha256, value: &Value) {
+    match value {
+        Value::Object(object) => {
+            let mut entries = object.iter().collect::<Vec<_>>();
+            entries.sort_by_key(|(key, _)| *key);
+            hasher.update(b"{");
+            for (index, (key, value)) in entries.into_iter().enumerate() {
+                if index > 0 {
+                    hasher.update(b",");
+                }
+                hasher.update(Value::from(key.as_str()).to_string());
+                hasher.update(b":");
+                hash_value(hasher, value);
+            }
+            hasher.update(b"}");
+        }
+        Value::Array(array) => {
+            hasher.update(b"[");
+            for (index, value) in array.iter().enumerate() {
+                if index > 0 {
+                    hasher.update(b",");
+                }
+                hash_value(hasher, value);
+            }
+            hasher.update(b"]");
+        }
+        scalar => hasher.update(scalar.to_string()),
+    }
+}
+
+#[cfg(all(test, feature = "yaml"))]
+mod test {
+    use crate::{CommonProperties, Fingerprint, NetplanConfig};
+
+    #[test]
+    fn fingerprint_is_stable() {
+        let config = NetplanConfig::from_yaml(
+            r#"
+network:
+  version: 2
+  ethernets:
+    eth0:
+      addresses: [192.0.2.10/24]
+      routes:
+        - to: default
+          via: 192.0.2.1
+  bonds:
+    bond0:
+      interfaces: [eth2, eth1]
+"#,
+        )
+        .unwrap();
+        let fingerprint = config.fingerprint();
+
+        // The fingerprint must not change between runs, platforms or releases without a
+        // change of the canonical form
+        let expected = "85c8c8e6eec5bf877dd2cad4e2da8accbd979da88f55feab4273586c55cd331b";
+        assert_eq!(fingerprint.to_string(), expected);
+        assert_eq!(expected.parse::<Fingerprint>(), Ok(fingerprint));
+        let json = serde_json::to_string(&fingerprint).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Fingerprint>(&json).unwrap(),
+            fingerprint
+        );
+
+        let mut reordered = config.clone();
+        let bond = reordered.network.bonds.as_mut().unwrap().get_mut("bond0");
+        bond.unwrap().interfaces.as_mut().unwrap().reverse();
+        assert_eq!(reordered.fingerprint(), fingerprint);
+
+        let mut changed = config.clone();
+        let eth0 = changed.network.ethernets.as_mut().unwrap().get_mut("eth0");
+        eth0.unwrap().common_all_mut().mtu = Some(9000);
+        assert_ne!(changed.fingerprint(), fingerprint);
+
+        assert!("0123".parse::<Fingerprint>().is_err());
+        assert!("+f".repeat(32).parse::<Fingerprint>().is_err());
+    }
+}
@@ -0,0 +1,168 @@
+//! Handling of the shell-style globs netplan accepts in match rules.
+//! Supported are `*`, `?`, character classes like `[a-z]` or `[!0-9]`
+//! and escaping of special characters with `\`.
+
+/// Check whether the pattern is a syntactically valid glob.
+/// On error, a description of the problem is returned.
+pub(crate) fn check(pattern: &str) -> Result<(), String> {
+    if pattern.is_empty() {
+        return Err("The pattern is empty".to_string());
+    }
+
+    let chars = pattern.chars().collect::<Vec<_>>();
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' if i + 1 == chars.len() => {
+                return Err("The pattern ends with an unescaped '\\'".to_string())
+            }
+            '\\' => i += 1,
+            '[' => i = class_end(&chars, i).ok_or("Unterminated character class")?,
+            _ => {}
+        }
+        i += 1;
+    }
+
+    Ok(())
+}
+
+/// Normalize a glob, so that equivalent patterns compare equal.
+/// Consecutive `*` wildcards are collapsed into one.
+pub(crate) fn normalize(pattern: &str) -> String {
+    let mut normalized = String::with_capacity(pattern.len());
+    let mut escaped = false;
+    for c in pattern.chars() {
+        if c == '*' && !escaped && normalized.ends_with('*') && !normalized.ends_with("\\*") {
+            continue;
+        }
+
+        escaped = c == '\\' && !escaped;
+        normalized.push(c);
+    }
+
+    normalized
+}
+
+/// Whether the string contains any glob special characters.
+pub(crate) fn is_glob(s: &str) -> bool {
+    s.contains(['*', '?', '[', ']', '\\'])
+}
+
+/// Whether the string matches the glob, like `fnmatch(3)` without flags. A `[` which does
+/// not start a character class matches itself.
+pub(crate) fn matches(pattern: &str, s: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let s = s.chars().collect::<Vec<_>>();
+    matches_chars(&pattern, &s)
+}
+
+fn matches_chars(pattern: &[char], s: &[char]) -> bool {
+    let Some((&first, rest)) = pattern.split_first() else {
+        return s.is_empty();
+    };
+
+    match (first, s.split_first()) {
+        ('*', _) => (0..=s.len()).any(|skip| matches_chars(rest, &s[skip..])),
+        (_, None) => false,
+        ('?', Some((_, s))) => matches_chars(rest, s),
+        ('[', Some((&c, s))) => match class_end(pattern, 0) {
+            Some(end) => {
+                class_matches(&pattern[1..end], c) && matches_chars(&pattern[end + 1..], s)
+            }
+            None => c == '[' && matches_chars(rest, s),
+        },
+        ('\\', Some((&c, s))) if !rest.is_empty() => rest[0] == c && matches_chars(&rest[1..], s),
+        (first, Some((&c, s))) => first == c && matches_chars(rest, s),
+    }
+}
+
+/// Whether the character is in the class, given without its brackets.
+fn class_matches(class: &[char], c: char) -> bool {
+    let (negated, class) = match class.split_first() {
+        Some(('!' | '^', class)) => (true, class),
+        _ => (false, class),
+    };
+
+    let mut found = false;
+    let mut i = 0;
+    while i < class.len() {
+        if class[i] == '\\' && i + 1 < class.len() {
+            i += 1;
+        }
+        let start = class[i];
+
+        if class.get(i + 1) == Some(&'-') && i + 2 < class.len() {
+            i += 2;
+            if class[i] == '\\' && i + 1 < class.len() {
+                i += 1;
+            }
+            found |= (start..=class[i]).contains(&c);
+        } else {
+            found |= start == c;
+        }
+        i += 1;
+    }
+
+    found != negated
+}
+
+/// Find the index of the `]` closing the character class opened at `start`.
+/// A `]` directly after the opening `[` (or `[!`) is part of the class.
+fn class_end(chars: &[char], start: usize) -> Option<usize> {
+    let mut i = start + 1;
+    if matches!(chars.get(i), Some('!') | Some('^')) {
+        i += 1;
+    }
+
+    if chars.get(i) == Some(&']') {
+        i += 1;
+    }
+
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => i += 1,
+            ']' => return Some(i),
+            _ => {}
+        }
+        i += 1;
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::{check, matches, normalize};
+
+    #[test]
+    fn syntax() {
+        assert!(check("en*").is_ok());
+        assert!(check("eth[0-9]").is_ok());
+        assert!(check("[]]x").is_ok());
+        assert!(check("eth[0-9").is_err());
+        assert!(check("eth\\").is_err());
+        assert!(check("").is_err());
+    }
+
+    #[test]
+    fn normalization() {
+        assert_eq!(normalize("en**p*"), "en*p*");
+        assert_eq!(normalize("a\\**"), "a\\**");
+    }
+
+    #[test]
+    fn matching() {
+        assert!(matches("en*", "enp1s0"));
+        assert!(matches("en*", "en"));
+        assert!(!matches("en*", "eth0"));
+        assert!(matches("eth?", "eth0"));
+        assert!(!matches("eth?", "eth10"));
+        assert!(matches("eth[0-9]", "eth7"));
+        assert!(!matches("eth[!0-9]", "eth7"));
+        assert!(matches("[]x]", "]"));
+        assert!(matches("a\\*", "a*"));
+        assert!(!matches("a\\*", "ab"));
+        assert!(matches("e*p*s0", "enp1s0"));
+        assert!(matches("eth[0", "eth[0"));
+    }
+}
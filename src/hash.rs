@@ -0,0 +1,132 @@
+//! `Hash` and `Ord` for the types which contain a [HashMap], which implements neither.
+//! The entries are hashed and compared in the order of their keys, so the implementations
+//! agree with `PartialEq`.
+
+use crate::{AddressMapping, NetworkConfig, WifiConfig};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+fn sorted<V>(map: &HashMap<String, V>) -> Vec<(&String, &V)> {
+    let mut entries = map.iter().collect::<Vec<_>>();
+    entries.sort_by_key(|(key, _)| *key);
+    entries
+}
+
+fn hash_map<V: Hash, H: Hasher>(map: &Option<HashMap<String, V>>, state: &mut H) {
+    map.as_ref().map(sorted).hash(state);
+}
+
+impl Hash for AddressMapping {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match self {
+            Self::Simple(address) => (0u8, address).hash(state),
+            Self::Complex(addresses) => (1u8, sorted(addresses)).hash(state),
+        }
+    }
+}
+
+impl PartialOrd for AddressMapping {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Simple addresses are ordered before addresses with properties.
+impl Ord for AddressMapping {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Self::Simple(a), Self::Simple(b)) => a.cmp(b),
+            (Self::Simple(_), Self::Complex(_)) => Ordering::Less,
+            (Self::Complex(_), Self::Simple(_)) => Ordering::Greater,
+            (Self::Complex(a), Self::Complex(b)) => sorted(a).cmp(&sorted(b)),
+        }
+    }
+}
+
+impl Hash for WifiConfig {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let Self {
+            access_points,
+            wakeonwlan,
+            regulatory_domain,
+            common_physical,
+            common_all,
+        } = self;
+
+        hash_map(access_points, state);
+        wakeonwlan.hash(state);
+        regulatory_domain.hash(state);
+        common_physical.hash(state);
+        common_all.hash(state);
+    }
+}
+
+impl Hash for NetworkConfig {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let Self {
+            version,
+            renderer,
+            openvswitch,
+            ethernets,
+            wifis,
+            modems,
+            bonds,
+            bridges,
+            vlans,
+            tunnels,
+            vrfs,
+            dummy_devices,
+            nm_devices,
+        } = self;
+
+        version.hash(state);
+        renderer.hash(state);
+        openvswitch.hash(state);
+        hash_map(ethernets, state);
+        hash_map(wifis, state);
+        hash_map(modems, state);
+        hash_map(bonds, state);
+        hash_map(bridges, state);
+        hash_map(vlans, state);
+        hash_map(tunnels, state);
+        hash_map(vrfs, state);
+        hash_map(dummy_devices, state);
+        hash_map(nm_devices, state);
+    }
+}
+
+#[cfg(all(test, feature = "yaml"))]
+mod test {
+    use crate::NetplanConfig;
+    use std::collections::HashSet;
+
+    #[test]
+    fn hash_ignores_order() {
+        let a = r#"
+            network:
+              version: 2
+              ethernets:
+                eth0:
+                  addresses:
+                    - 10.0.0.2/24: { label: a }
+                      10.0.0.3/24: { label: b }
+                eth1: {}
+            "#;
+        let b = r#"
+            network:
+              version: 2
+              ethernets:
+                eth1: {}
+                eth0:
+                  addresses:
+                    - 10.0.0.3/24: { label: b }
+                      10.0.0.2/24: { label: a }
+            "#;
+
+        let a: NetplanConfig = serde_yaml::from_str(a).unwrap();
+        let b: NetplanConfig = serde_yaml::from_str(b).unwrap();
+        let set = HashSet::from([a, b]);
+        assert_eq!(set.len(), 1);
+    }
+}
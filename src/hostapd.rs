@@ -0,0 +1,255 @@
+//! Rendering access points into hostapd configurations.
+
+use crate::{AccessPointConfig, AccessPointMode, KeyManagmentMode, WifiConfig, WirelessBand};
+use std::fmt::{Display, Formatter};
+
+/// A hostapd configuration, like `/etc/hostapd/hostapd.conf`: `key=value` settings, in
+/// order. Settings following a `bss` setting apply to that additional BSS.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HostapdConf {
+    pub entries: Vec<(String, String)>,
+}
+
+impl HostapdConf {
+    /// The first value of `key`.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn add(&mut self, key: &str, value: impl ToString) {
+        self.entries.push((key.to_string(), value.to_string()));
+    }
+}
+
+impl Display for HostapdConf {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (key, value) in &self.entries {
+            writeln!(f, "{key}={value}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl AccessPointConfig {
+    /// Render an access point in `ap` mode into the hostapd configuration which creates it
+    /// on `interface`, with the nl80211 driver: the SSID, the band and channel, whether
+    /// the SSID is hidden, and WPA2 (WPA-PSK) or WPA3 (SAE) authentication, with
+    /// management frame protection for WPA3. Without a channel, hostapd selects one.
+    ///
+    /// `None` if the access point is not in `ap` mode, or uses WPA-EAP or 802.1X, which
+    /// need a RADIUS server netplan does not configure. The configuration contains the
+    /// passphrase, so it should only be readable by root.
+    ///
+    /// ```
+    /// use netplan_types::{AccessPointConfig, AccessPointMode, WirelessBand};
+    ///
+    /// let access_point = AccessPointConfig {
+    ///     mode: Some(AccessPointMode::Ap),
+    ///     band: Some(WirelessBand::Ghz5),
+    ///     channel: Some(36),
+    ///     ..AccessPointConfig::wpa2("hunter22")
+    /// };
+    ///
+    /// let conf = access_point.to_hostapd("wlan0", "hotspot").unwrap();
+    /// assert_eq!(conf.get("ssid"), Some("hotspot"));
+    /// assert_eq!(conf.get("hw_mode"), Some("a"));
+    /// assert_eq!(conf.get("wpa_key_mgmt"), Some("WPA-PSK"));
+    /// assert!(AccessPointConfig::wpa2("hunter22").to_hostapd("wlan0", "home").is_none());
+    /// ```
+    pub fn to_hostapd(&self, interface: &str, ssid: &str) -> Option<HostapdConf> {
+        let mut conf = HostapdConf::default();
+        conf.add("interface", interface);
+        conf.add("driver", "nl80211");
+        radio(&mut conf, self);
+        bss(&mut conf, self, ssid)?;
+        Some(conf)
+    }
+}
+
+impl WifiConfig {
+    /// Render the access points in `ap` mode of a wifi device into a hostapd
+    /// configuration, see [AccessPointConfig::to_hostapd]. The regulatory domain becomes
+    /// the country code.
+    ///
+    /// The access points are ordered by SSID. The first one sets the band and channel of
+    /// the radio, and the others become additional BSSes on it, on the interfaces
+    /// `<interface>_1`, `<interface>_2` and so on. Access points hostapd cannot create are
+    /// left out, and `None` is returned if there are none it can.
+    pub fn to_hostapd(&self, interface: &str) -> Option<HostapdConf> {
+        let mut access_points = self
+            .access_points
+            .iter()
+            .flatten()
+            .filter(|(_, access_point)| access_point.mode == Some(AccessPointMode::Ap))
+            .collect::<Vec<_>>();
+        access_points.sort_by_key(|(ssid, _)| *ssid);
+
+        let mut conf = HostapdConf::default();
+        let mut count = 0;
+        for (ssid, access_point) in access_points {
+            let mut section = HostapdConf::default();
+            if count == 0 {
+                section.add("interface", interface);
+                section.add("driver", "nl80211");
+                if let Some(country) = &self.regulatory_domain {
+                    section.add("country_code", country);
+                    section.add("ieee80211d", 1);
+                }
+                radio(&mut section, access_point);
+            } else {
+                section.add("bss", format!("{interface}_{count}"));
+            }
+            if bss(&mut section, access_point, ssid).is_some() {
+                conf.entries.extend(section.entries);
+                count += 1;
+            }
+        }
+
+        (count > 0).then_some(conf)
+    }
+}
+
+/// The settings of the radio: the band and channel.
+fn radio(conf: &mut HostapdConf, access_point: &AccessPointConfig) {
+    let (hw_mode, op_class) = match access_point.band {
+        Some(WirelessBand::Ghz5) => ("a", None),
+        Some(WirelessBand::Ghz6) => ("a", Some(131)),
+        Some(WirelessBand::Ghz2) | None => ("g", None),
+    };
+    conf.add("hw_mode", hw_mode);
+    if let Some(op_class) = op_class {
+        conf.add("op_class", op_class);
+        conf.add("ieee80211ax", 1);
+    }
+    // The channel only takes effect together with the band, channel 0 selects one
+    let channel = access_point.channel.filter(|_| access_point.band.is_some());
+    conf.add("channel", channel.unwrap_or(0));
+}
+
+/// The settings of a BSS: the SSID and authentication. `None` if the access point is not
+/// in `ap` mode, or hostapd cannot authenticate its clients by itself.
+fn bss(conf: &mut HostapdConf, access_point: &AccessPointConfig, ssid: &str) -> Option<()> {
+    if access_point.mode != Some(AccessPointMode::Ap) {
+        return None;
+    }
+    conf.add("ssid", ssid);
+    conf.add("utf8_ssid", 1);
+    if let Some(bssid) = &access_point.bssid {
+        conf.add("bssid", bssid.to_string());
+    }
+    if access_point.hidden == Some(true) {
+        conf.add("ignore_broadcast_ssid", 1);
+    }
+
+    let auth = access_point.auth.as_ref();
+    let password = auth
+        .and_then(|auth| auth.password.as_ref())
+        .or(access_point.password.as_ref());
+    let key_management = match auth.and_then(|auth| auth.key_management.as_ref()) {
+        Some(key_management) => key_management.clone(),
+        None if password.is_some() => KeyManagmentMode::Psk,
+        None => KeyManagmentMode::None,
+    };
+    match key_management {
+        KeyManagmentMode::None => {}
+        KeyManagmentMode::Psk => {
+            let password = password?.as_str();
+            conf.add("wpa", 2);
+            conf.add("wpa_key_mgmt", "WPA-PSK");
+            conf.add("rsn_pairwise", "CCMP");
+            // A PSK of 64 hex digits is the key itself, not a passphrase
+            if password.len() == 64 && password.chars().all(|c| c.is_ascii_hexdigit()) {
+                conf.add("wpa_psk", password);
+            } else {
+                conf.add("wpa_passphrase", password);
+            }
+        }
+        KeyManagmentMode::Sae => {
+            conf.add("wpa", 2);
+            conf.add("wpa_key_mgmt", "SAE");
+            conf.add("rsn_pairwise", "CCMP");
+            conf.add("ieee80211w", 2);
+            conf.add("sae_password", password?.as_str());
+        }
+        KeyManagmentMode::Eap | KeyManagmentMode::EightZeroTwoDotOneX => return None,
+    }
+    Some(())
+}
+
+#[cfg(all(test, feature = "yaml"))]
+mod test {
+    use crate::NetplanConfig;
+
+    #[test]
+    fn export_hostapd() {
+        let config = NetplanConfig::from_yaml(
+            r#"
+network:
+  version: 2
+  wifis:
+    wlan0:
+      regulatory-domain: NL
+      access-points:
+        home:
+          mode: ap
+          password: hunter22
+          band: 2.4GHz
+          channel: 6
+        guest:
+          mode: ap
+          hidden: true
+          auth:
+            key-management: sae
+            password: correct horse
+        corp:
+          mode: ap
+          auth:
+            key-management: eap
+            method: peap
+        upstream:
+          password: hunter22
+"#,
+        )
+        .unwrap();
+        let wifis = config.network.wifis.unwrap();
+
+        let expected = "\
+interface=wlan0
+driver=nl80211
+country_code=NL
+ieee80211d=1
+hw_mode=g
+channel=0
+ssid=guest
+utf8_ssid=1
+ignore_broadcast_ssid=1
+wpa=2
+wpa_key_mgmt=SAE
+rsn_pairwise=CCMP
+ieee80211w=2
+sae_password=correct horse
+bss=wlan0_1
+ssid=home
+utf8_ssid=1
+wpa=2
+wpa_key_mgmt=WPA-PSK
+rsn_pairwise=CCMP
+wpa_passphrase=hunter22
+";
+        let conf = wifis["wlan0"].to_hostapd("wlan0").unwrap();
+        assert_eq!(conf.to_string(), expected);
+
+        let access_points = wifis["wlan0"].access_points.as_ref().unwrap();
+        let conf = access_points["home"].to_hostapd("wlan1", "home").unwrap();
+        assert_eq!(conf.get("hw_mode"), Some("g"));
+        assert_eq!(conf.get("channel"), Some("6"));
+        assert!(access_points["corp"].to_hostapd("wlan1", "corp").is_none());
+        assert!(access_points["upstream"]
+            .to_hostapd("wlan1", "upstream")
+            .is_none());
+    }
+}
@@ -0,0 +1,659 @@
+//! Importing ifcfg network scripts into configurations.
+
+use crate::ifcfg::{IfcfgFile, IfcfgParseError};
+use crate::ifupdown::{define_from_options, prefix_length};
+use crate::{
+    ActivationMode, AddressMapping, AnyDeviceConfigMut, CommonProperties,
+    CommonPropertiesAllDevices, CommonPropertiesPhysicalDeviceType, DhcpOverrides, EthernetConfig,
+    Ipv6AddressGeneration, MatchConfig, NameserverConfig, NetplanConfig, PhysicalProperties,
+    RoutingConfig,
+};
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+
+/// The options of `BONDING_OPTS` which have an equivalent bond parameter.
+const BOND_OPTIONS: [&str; 11] = [
+    "mode",
+    "miimon",
+    "lacp_rate",
+    "xmit_hash_policy",
+    "ad_select",
+    "updelay",
+    "downdelay",
+    "arp_interval",
+    "arp_ip_target",
+    "primary",
+    "min_links",
+];
+
+/// The options of `BRIDGING_OPTS` which have an equivalent bridge parameter, with the
+/// ifupdown option they are read as.
+const BRIDGE_OPTIONS: [(&str, &str); 5] = [
+    ("priority", "bridge-bridgeprio"),
+    ("hello_time", "bridge-hello"),
+    ("max_age", "bridge-maxage"),
+    ("ageing_time", "bridge-ageing"),
+    ("forward_delay", "bridge-fd"),
+];
+
+/// The suffixes of backup files, which the network scripts skip.
+const BACKUP_SUFFIXES: [&str; 7] = [
+    "~", ".bak", ".old", ".orig", ".rpmnew", ".rpmorig", ".rpmsave",
+];
+
+/// A variable of a network script which [NetplanConfig::from_ifcfg] has no equivalent
+/// for, and left out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnsupportedIfcfgKey {
+    /// The name of the file, e.g. `ifcfg-eth0`.
+    pub file_name: String,
+    /// The variable, e.g. `ETHTOOL_OPTS`.
+    pub key: String,
+    /// Its value, or for `BONDING_OPTS` and `BRIDGING_OPTS` the option left out, e.g.
+    /// `num_grat_arp=3`.
+    pub value: String,
+}
+
+/// The configuration imported by [NetplanConfig::from_ifcfg].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IfcfgImport {
+    pub config: NetplanConfig,
+    /// The variables which were left out, in the order of the files.
+    pub unsupported: Vec<UnsupportedIfcfgKey>,
+}
+
+/// The error returned when a directory of network scripts cannot be imported.
+#[derive(Debug)]
+pub enum IfcfgImportError {
+    /// The directory or a script in it could not be read.
+    Io(std::io::Error),
+    /// A script is not valid.
+    Parse {
+        path: PathBuf,
+        error: IfcfgParseError,
+    },
+}
+
+impl Display for IfcfgImportError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "Failed to read network scripts: {e}"),
+            Self::Parse { path, error } => {
+                write!(f, "Invalid network script {}: {error}", path.display())
+            }
+        }
+    }
+}
+
+impl std::error::Error for IfcfgImportError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Parse { error, .. } => Some(error),
+        }
+    }
+}
+
+impl From<std::io::Error> for IfcfgImportError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl NetplanConfig {
+    /// Lift the `ifcfg-*` network scripts of RHEL and its derivatives into a configuration.
+    ///
+    /// Every script, other than the loopback's, defines a device named after its `DEVICE`:
+    /// a bond if its `TYPE` is `Bond` or it has `BONDING_OPTS`, a bridge if its `TYPE` is
+    /// `Bridge`, a VLAN if it has `VLAN=yes`, a wifi if its `TYPE` is `Wireless`, and an
+    /// ethernet otherwise. Interfaces join the bonds and bridges named by their `MASTER`
+    /// and `BRIDGE`. Scripts of other types, like teams, are left out.
+    ///
+    /// `BOOTPROTO`, `IPADDR`/`PREFIX`/`NETMASK` and their numbered variants, `GATEWAY`,
+    /// `DNS*`, `DOMAIN`, the `IPV6*` settings, `MTU` and `MACADDR` are carried over, as are
+    /// the options of `BONDING_OPTS` and `BRIDGING_OPTS` netplan has parameters for. `HWADDR`
+    /// matches the interface by its MAC address and renames it to `DEVICE`. Interfaces with
+    /// `ONBOOT=no`, which are not part of another device, get the `manual` activation mode.
+    ///
+    /// Anything else is left out and reported in [IfcfgImport::unsupported], except `NAME`
+    /// and `UUID`, which only identify the script. `route-*` files are not read.
+    ///
+    /// ```
+    /// use netplan_types::{IfcfgFile, NetplanConfig};
+    ///
+    /// let files = [
+    ///     IfcfgFile::parse("ifcfg-eth0", "DEVICE=eth0\nONBOOT=yes\nMASTER=bond0\nSLAVE=yes\n")
+    ///         .unwrap(),
+    ///     IfcfgFile::parse(
+    ///         "ifcfg-bond0",
+    ///         "DEVICE=bond0\nTYPE=Bond\nBONDING_OPTS=\"mode=active-backup num_grat_arp=3\"\n\
+    ///          BOOTPROTO=none\nIPADDR=192.0.2.10\nPREFIX=24\nGATEWAY=192.0.2.1\n",
+    ///     )
+    ///     .unwrap(),
+    /// ];
+    ///
+    /// let import = NetplanConfig::from_ifcfg(&files);
+    /// let bond0 = &import.config.network.bonds.as_ref().unwrap()["bond0"];
+    /// assert_eq!(bond0.interfaces.as_deref(), Some(&["eth0".to_string()][..]));
+    /// assert_eq!(import.unsupported[0].value, "num_grat_arp=3");
+    /// ```
+    pub fn from_ifcfg(files: &[IfcfgFile]) -> IfcfgImport {
+        let mut import = IfcfgImport::default();
+        let network = &mut import.config.network;
+        let unsupported = &mut import.unsupported;
+
+        let mut defined = Vec::new();
+        let mut dependencies = HashSet::new();
+        for file in files {
+            let name = file.device();
+            if name == "lo" {
+                continue;
+            }
+            let Some(options) = device_options(file, unsupported) else {
+                continue;
+            };
+            dependencies.extend(define_from_options(network, name, options));
+            defined.push(file);
+
+            let common = import_file(file, unsupported);
+            let Some(mut device) = network.device_mut(name) else {
+                continue;
+            };
+            if common != CommonPropertiesAllDevices::default() {
+                *device.common_all_mut() = common;
+            }
+
+            let Some(macaddress) = file.get("HWADDR").and_then(|mac| mac.parse().ok()) else {
+                continue;
+            };
+            let physical = match device {
+                AnyDeviceConfigMut::Ethernet(ethernet) => ethernet.common_physical_mut(),
+                AnyDeviceConfigMut::Wifi(wifi) => wifi.common_physical_mut(),
+                _ => continue,
+            };
+            *physical = CommonPropertiesPhysicalDeviceType {
+                r#match: Some(MatchConfig {
+                    macaddress: Some(macaddress),
+                    ..Default::default()
+                }),
+                set_name: Some(name.to_string()),
+                ..Default::default()
+            };
+        }
+
+        for file in &defined {
+            let name = file.device();
+            for key in ["MASTER", "BRIDGE"] {
+                let Some(controller) = file.get(key) else {
+                    continue;
+                };
+                let interfaces = match (key, network.device_mut(controller)) {
+                    ("MASTER", Some(AnyDeviceConfigMut::Bond(bond))) => &mut bond.interfaces,
+                    ("BRIDGE", Some(AnyDeviceConfigMut::Bridge(bridge))) => &mut bridge.interfaces,
+                    _ => continue,
+                };
+                let interfaces = interfaces.get_or_insert_with(Vec::new);
+                if !interfaces.iter().any(|interface| interface == name) {
+                    interfaces.push(name.to_string());
+                }
+                dependencies.insert(name.to_string());
+            }
+        }
+
+        for dependency in &dependencies {
+            if network.device(dependency).is_none() {
+                network.add_ethernet(dependency.as_str(), EthernetConfig::default());
+            }
+        }
+
+        for file in &defined {
+            let name = file.device();
+            if flag(file.get("ONBOOT")) != Some(false) || dependencies.contains(name) {
+                continue;
+            }
+            if let Some(mut device) = network.device_mut(name) {
+                device.common_all_mut().activation_mode = Some(ActivationMode::Manual);
+            }
+        }
+
+        import
+    }
+
+    /// Read the network scripts in a directory like `/etc/sysconfig/network-scripts`, in
+    /// the order of their names, and import them, see [NetplanConfig::from_ifcfg]. Only
+    /// files named `ifcfg-*` are read, skipping backups like `ifcfg-eth0.bak`.
+    pub fn from_ifcfg_dir(dir: impl AsRef<Path>) -> Result<IfcfgImport, IfcfgImportError> {
+        let mut paths = Vec::new();
+        for entry in std::fs::read_dir(dir)? {
+            let entry = entry?;
+            let name = entry.file_name();
+            let Some(name) = name.to_str() else {
+                continue;
+            };
+            let script = name.starts_with("ifcfg-")
+                && !BACKUP_SUFFIXES.iter().any(|suffix| name.ends_with(suffix));
+            if script && entry.file_type()?.is_file() {
+                paths.push(entry.path());
+            }
+        }
+        paths.sort();
+
+        let mut files = Vec::new();
+        for path in paths {
+            let contents = std::fs::read_to_string(&path)?;
+            let file_name = path
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string();
+            let file = IfcfgFile::parse(file_name, &contents)
+                .map_err(|error| IfcfgImportError::Parse { path, error })?;
+            files.push(file);
+        }
+
+        Ok(Self::from_ifcfg(&files))
+    }
+}
+
+/// The value of a boolean variable, `yes` or `no`.
+fn flag(value: Option<&str>) -> Option<bool> {
+    match value?.to_ascii_lowercase().as_str() {
+        "yes" | "true" | "on" | "1" => Some(true),
+        "no" | "false" | "off" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+/// The options of the ifupdown `iface` stanza defining the device of a script, `None` if
+/// it is of a type netplan has no equivalent for.
+fn device_options(
+    file: &IfcfgFile,
+    unsupported: &mut Vec<UnsupportedIfcfgKey>,
+) -> Option<Vec<(String, String)>> {
+    let mut report = |key: &str, value: &str| {
+        unsupported.push(UnsupportedIfcfgKey {
+            file_name: file.file_name.clone(),
+            key: key.to_string(),
+            value: value.to_string(),
+        })
+    };
+    let mut options = Vec::new();
+    let mut option = |key: &str, value: &str| options.push((key.to_string(), value.to_string()));
+
+    let kind = file.get("TYPE").unwrap_or("Ethernet");
+    match kind.to_ascii_lowercase().as_str() {
+        _ if kind.eq_ignore_ascii_case("bond")
+            || flag(file.get("BONDING_MASTER")) == Some(true)
+            || file.get("BONDING_OPTS").is_some() =>
+        {
+            option("bond-slaves", "none");
+            let bonding_opts = file.get("BONDING_OPTS").unwrap_or_default();
+            for bonding_opt in bonding_opts.split_whitespace() {
+                let (key, value) = bonding_opt.split_once('=').unwrap_or((bonding_opt, ""));
+                if !BOND_OPTIONS.contains(&key) {
+                    report("BONDING_OPTS", bonding_opt);
+                    continue;
+                }
+                let values = value.split(',').map(|v| v.trim_start_matches('+'));
+                let key = format!("bond-{}", key.replace('_', "-"));
+                option(&key, &values.collect::<Vec<_>>().join(" "));
+            }
+        }
+        "bridge" => {
+            option("bridge-ports", "none");
+            if let Some(stp) = file.get("STP") {
+                option("bridge-stp", stp);
+            }
+            if let Some(delay) = file.get("DELAY") {
+                option("bridge-fd", delay);
+            }
+            let bridging_opts = file.get("BRIDGING_OPTS").unwrap_or_default();
+            for bridging_opt in bridging_opts.split_whitespace() {
+                let (key, value) = bridging_opt.split_once('=').unwrap_or((bridging_opt, ""));
+                match BRIDGE_OPTIONS.iter().find(|(k, _)| *k == key) {
+                    Some((_, key)) => option(key, value),
+                    None => report("BRIDGING_OPTS", bridging_opt),
+                }
+            }
+        }
+        "wireless" => {
+            if let Some(essid) = file.get("ESSID") {
+                option("wpa-ssid", essid);
+            }
+        }
+        _ if flag(file.get("VLAN")) == Some(true) || kind.eq_ignore_ascii_case("vlan") => {
+            if let Some(physdev) = file.get("PHYSDEV") {
+                option("vlan-raw-device", physdev);
+            }
+            if let Some(id) = file.get("VLAN_ID") {
+                option("vlan-id", id);
+            }
+        }
+        "ethernet" => {}
+        _ => {
+            report("TYPE", kind);
+            return None;
+        }
+    }
+
+    Some(options)
+}
+
+/// Whether `key` is `name` followed by an optional number, like `IPADDR1` for `IPADDR`.
+fn numbered(key: &str, name: &str) -> bool {
+    key.strip_prefix(name)
+        .is_some_and(|n| n.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// The values of the numbered variants of a variable, ordered by their number, e.g.
+/// `DNS1` before `DNS2`, with their numbers.
+fn numbered_values<'a>(file: &'a IfcfgFile, name: &str) -> Vec<(&'a str, &'a str)> {
+    let mut values = file
+        .variables
+        .iter()
+        .filter(|(key, _)| numbered(key, name))
+        .map(|(key, _)| (&key[name.len()..], file.get(key).unwrap_or_default()))
+        .collect::<Vec<_>>();
+    values.sort_by_key(|(number, _)| number.parse::<i64>().unwrap_or(-1));
+    values.dedup();
+    values
+}
+
+/// Import the addressing of a script, reporting the variables netplan has no equivalent
+/// for.
+fn import_file(
+    file: &IfcfgFile,
+    unsupported: &mut Vec<UnsupportedIfcfgKey>,
+) -> CommonPropertiesAllDevices {
+    let get = |key: &str| file.get(key);
+    let mut common = CommonPropertiesAllDevices::default();
+
+    let dhcp4 = matches!(get("BOOTPROTO"), Some("dhcp" | "bootp"));
+    let ipv6 = flag(get("IPV6INIT")) == Some(true);
+    let dhcp6 = ipv6 && flag(get("DHCPV6C")) == Some(true);
+    let route_metric = get("IPV4_ROUTE_METRIC").and_then(|m| m.parse().ok());
+    let peer_dns = flag(get("PEERDNS")) != Some(false);
+
+    let mut seen = HashSet::new();
+    for (key, value) in &file.variables {
+        if !seen.insert(key) {
+            continue;
+        }
+        let value = get(key).unwrap_or(value);
+        let supported = match key.as_str() {
+            // Read when defining the device
+            "DEVICE" | "NAME" | "UUID" | "TYPE" | "ONBOOT" | "HWADDR" | "MASTER" | "SLAVE"
+            | "BRIDGE" | "BONDING_MASTER" | "BONDING_OPTS" | "BRIDGING_OPTS" | "STP" | "DELAY"
+            | "VLAN" | "PHYSDEV" | "VLAN_ID" | "ESSID" => true,
+            "BOOTPROTO" => matches!(value, "dhcp" | "bootp" | "none" | "static" | ""),
+            // Without a default route from DHCP, netplan would ignore all of its routes
+            "DEFROUTE" => !dhcp4 || flag(Some(value)) != Some(false),
+            "IPV6_PRIVACY" => matches!(value, "rfc3041" | "no"),
+            "IPV6_ADDR_GEN_MODE" => Ipv6AddressGeneration::VARIANTS
+                .iter()
+                .any(|mode| mode.as_str() == value),
+            "GATEWAY"
+            | "IPV4_ROUTE_METRIC"
+            | "DOMAIN"
+            | "PEERDNS"
+            | "MTU"
+            | "MACADDR"
+            | "IPV6INIT"
+            | "IPV6_AUTOCONF"
+            | "DHCPV6C"
+            | "IPV6ADDR"
+            | "IPV6ADDR_SECONDARIES"
+            | "IPV6_DEFAULTGW"
+            | "IPV6_DEFROUTE" => true,
+            key => ["IPADDR", "PREFIX", "NETMASK", "DNS"]
+                .iter()
+                .any(|name| numbered(key, name)),
+        };
+        if !supported {
+            unsupported.push(UnsupportedIfcfgKey {
+                file_name: file.file_name.clone(),
+                key: key.clone(),
+                value: value.to_string(),
+            });
+        }
+    }
+
+    let mut addresses = Vec::new();
+    for (number, address) in numbered_values(file, "IPADDR") {
+        let prefix = get(&format!("PREFIX{number}"))
+            .and_then(|prefix| prefix.parse().ok())
+            .or_else(|| get(&format!("NETMASK{number}")).and_then(prefix_length))
+            .unwrap_or(32);
+        addresses.push(format!("{address}/{prefix}"));
+    }
+
+    let mut routes = Vec::new();
+    let default_route = |via: &str, metric| RoutingConfig {
+        to: Some("default".to_string()),
+        via: Some(via.to_string()),
+        metric,
+        ..Default::default()
+    };
+    if let Some(gateway) = get("GATEWAY").filter(|_| flag(get("DEFROUTE")) != Some(false)) {
+        routes.push(default_route(gateway, route_metric));
+    }
+
+    if dhcp4 {
+        common.dhcp4 = Some(true);
+        let overrides = DhcpOverrides {
+            route_metric,
+            use_dns: (!peer_dns).then_some(false),
+            ..Default::default()
+        };
+        common.dhcp4_overrides = (overrides != DhcpOverrides::default()).then_some(overrides);
+    }
+
+    if ipv6 {
+        common.accept_ra = Some(flag(get("IPV6_AUTOCONF")) != Some(false));
+        if dhcp6 {
+            common.dhcp6 = Some(true);
+            if !peer_dns {
+                common.dhcp6_overrides = Some(DhcpOverrides {
+                    use_dns: Some(false),
+                    ..Default::default()
+                });
+            }
+        }
+
+        let ipv6_addresses = get("IPV6ADDR")
+            .into_iter()
+            .chain(get("IPV6ADDR_SECONDARIES"))
+            .flat_map(str::split_whitespace);
+        for address in ipv6_addresses {
+            match address.contains('/') {
+                true => addresses.push(address.to_string()),
+                false => addresses.push(format!("{address}/64")),
+            }
+        }
+
+        let gateway = get("IPV6_DEFAULTGW")
+            .filter(|_| flag(get("IPV6_DEFROUTE")) != Some(false))
+            .map(|gateway| gateway.split('%').next().unwrap_or(gateway));
+        if let Some(gateway) = gateway {
+            routes.push(default_route(gateway, None));
+        }
+
+        common.ipv6_privacy = match get("IPV6_PRIVACY") {
+            Some("rfc3041") => Some(true),
+            Some("no") => Some(false),
+            _ => None,
+        };
+        common.ipv6_address_generation = get("IPV6_ADDR_GEN_MODE").and_then(|mode| {
+            Ipv6AddressGeneration::VARIANTS
+                .iter()
+                .find(|m| m.as_str() == mode)
+                .cloned()
+        });
+    }
+
+    if !addresses.is_empty() {
+        common.addresses = Some(addresses.into_iter().map(AddressMapping::Simple).collect());
+    }
+    if !routes.is_empty() {
+        common.routes = Some(routes);
+    }
+
+    let nameservers = numbered_values(file, "DNS")
+        .into_iter()
+        .map(|(_, address)| address.to_string())
+        .collect::<Vec<_>>();
+    let search = get("DOMAIN")
+        .into_iter()
+        .flat_map(str::split_whitespace)
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+    if !nameservers.is_empty() || !search.is_empty() {
+        common.nameservers = Some(NameserverConfig {
+            addresses: (!nameservers.is_empty()).then_some(nameservers),
+            search: (!search.is_empty()).then_some(search),
+        });
+    }
+
+    common.mtu = get("MTU").and_then(|mtu| mtu.parse().ok());
+    common.macaddress = get("MACADDR").and_then(|mac| mac.parse().ok());
+    common
+}
+
+#[cfg(all(test, feature = "yaml"))]
+mod test {
+    use crate::{IfcfgFile, NetplanConfig};
+
+    #[test]
+    fn import_ifcfg() {
+        let files = [
+            (
+                "ifcfg-eno1",
+                "TYPE=Ethernet\nNAME=eno1\nDEVICE=eno1\nONBOOT=yes\nMASTER=bond0\nSLAVE=yes\n\
+                 HWADDR=52:54:00:AA:BB:01\nETHTOOL_OPTS=\"-K eno1 gro off\"\n",
+            ),
+            (
+                "ifcfg-eno2",
+                "DEVICE=eno2\nONBOOT=yes\nMASTER=bond0\nSLAVE=yes\n",
+            ),
+            (
+                "ifcfg-bond0",
+                "DEVICE=bond0\nTYPE=Bond\nBONDING_MASTER=yes\nONBOOT=yes\n\
+                 BONDING_OPTS=\"mode=4 miimon=100 lacp_rate=fast xmit_hash_policy=layer3+4 \
+                 num_grat_arp=3\"\nBOOTPROTO=none\nIPV6INIT=no\n",
+            ),
+            (
+                "ifcfg-bond0.20",
+                "# Servers\nDEVICE=bond0.20\nVLAN=yes\nBOOTPROTO=none\nONBOOT=yes\n\
+                 IPADDR=10.0.20.2\nNETMASK=255.255.255.0\nIPADDR1=10.0.21.2\nPREFIX1=24\n\
+                 GATEWAY=10.0.20.1\nDNS1=10.0.20.1\nDNS2=10.0.20.2\nDOMAIN=\"example.com lab\"\n\
+                 MTU=9000\nIPV6INIT=yes\nIPV6_AUTOCONF=no\nIPV6ADDR=2001:db8:20::2/64\n\
+                 IPV6_DEFAULTGW=2001:db8:20::1%bond0.20\nUSERCTL=no\n",
+            ),
+            (
+                "ifcfg-br0",
+                "DEVICE=br0\nTYPE=Bridge\nBOOTPROTO=dhcp\nPEERDNS=no\nONBOOT=yes\nSTP=off\n\
+                 DELAY=0\nBRIDGING_OPTS='priority=4096 multicast_snooping=0'\n",
+            ),
+            (
+                "ifcfg-vlan30",
+                "DEVICE=vlan30\nVLAN=yes\nPHYSDEV=eno3\nVLAN_ID=30\nBRIDGE=br0\n",
+            ),
+            ("ifcfg-spare", "DEVICE=spare\nBOOTPROTO=dhcp\nONBOOT=no\n"),
+            ("ifcfg-team0", "DEVICE=team0\nDEVICETYPE=Team\nTYPE=Team\n"),
+            (
+                "ifcfg-lo",
+                "DEVICE=lo\nIPADDR=127.0.0.1\nNETMASK=255.0.0.0\n",
+            ),
+        ]
+        .map(|(name, contents)| IfcfgFile::parse(name, contents).unwrap());
+
+        let expected = NetplanConfig::from_yaml(
+            r#"
+network:
+  version: 2
+  ethernets:
+    eno1:
+      match: {macaddress: "52:54:00:aa:bb:01"}
+      set-name: eno1
+    eno2: {}
+    eno3: {}
+    spare:
+      dhcp4: true
+      activation-mode: manual
+  bonds:
+    bond0:
+      interfaces: [eno1, eno2]
+      parameters:
+        mode: 802.3ad
+        mii-monitor-interval: "100"
+        lacp-rate: fast
+        transmit-hash-policy: layer3+4
+  vlans:
+    bond0.20:
+      id: 20
+      link: bond0
+      addresses: [10.0.20.2/24, 10.0.21.2/24, "2001:db8:20::2/64"]
+      routes:
+        - {to: default, via: 10.0.20.1}
+        - {to: default, via: "2001:db8:20::1"}
+      nameservers: {addresses: [10.0.20.1, 10.0.20.2], search: [example.com, lab]}
+      mtu: 9000
+      accept-ra: false
+    vlan30:
+      id: 30
+      link: eno3
+  bridges:
+    br0:
+      interfaces: [vlan30]
+      dhcp4: true
+      dhcp4-overrides: {use-dns: false}
+      parameters: {stp: false, forward-delay: "0", priority: 4096}
+"#,
+        )
+        .unwrap();
+        let import = NetplanConfig::from_ifcfg(&files);
+        assert!(
+            import.config.semantically_eq(&expected),
+            "{:#?}",
+            import.config
+        );
+
+        let unsupported = import
+            .unsupported
+            .iter()
+            .map(|u| (u.file_name.as_str(), u.key.as_str(), u.value.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            unsupported,
+            [
+                ("ifcfg-eno1", "ETHTOOL_OPTS", "-K eno1 gro off"),
+                ("ifcfg-bond0", "BONDING_OPTS", "num_grat_arp=3"),
+                ("ifcfg-bond0.20", "USERCTL", "no"),
+                ("ifcfg-br0", "BRIDGING_OPTS", "multicast_snooping=0"),
+                ("ifcfg-team0", "TYPE", "Team"),
+            ]
+        );
+
+        // Only scripts are read, in order, skipping backups
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("ifcfg-eth1"),
+            "DEVICE=eth1\nBOOTPROTO=dhcp\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("ifcfg-eth0"), "BOOTPROTO=dhcp\n").unwrap();
+        std::fs::write(dir.path().join("ifcfg-eth0.bak"), "BOOTPROTO=none\n").unwrap();
+        std::fs::write(dir.path().join("route-eth0"), "10.1.0.0/16 via 10.0.0.1\n").unwrap();
+        let loaded = NetplanConfig::from_ifcfg_dir(dir.path()).unwrap();
+        let expected = NetplanConfig::build(|net| {
+            net.ethernet("eth0", |e| e.dhcp4(true));
+            net.ethernet("eth1", |e| e.dhcp4(true));
+        });
+        assert_eq!(loaded.config, expected);
+        assert!(loaded.unsupported.is_empty());
+
+        let error = IfcfgFile::parse("ifcfg-eth0", "BOOTPROTO=dhcp\nONBOOT=\"yes\n").unwrap_err();
+        assert_eq!(error.line, 2);
+    }
+}
@@ -0,0 +1,129 @@
+//! Reading the `ifcfg-*` network scripts of RHEL and its derivatives, to migrate hosts which
+//! use them.
+
+mod import;
+pub use import::*;
+
+use std::fmt::{Display, Formatter};
+
+/// A network script like `/etc/sysconfig/network-scripts/ifcfg-eth0`: shell variable
+/// assignments, such as `BOOTPROTO=dhcp`, describing one interface.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IfcfgFile {
+    /// The name of the file, e.g. `ifcfg-eth0`.
+    pub file_name: String,
+    /// The variables, in order, with their quotes removed.
+    pub variables: Vec<(String, String)>,
+}
+
+impl IfcfgFile {
+    /// Parse the contents of a network script. Empty lines and comments, starting with `#`,
+    /// are skipped. Values may be quoted with double quotes, in which a backslash escapes
+    /// the next character, or with single quotes.
+    ///
+    /// ```
+    /// use netplan_types::IfcfgFile;
+    ///
+    /// let file = IfcfgFile::parse("ifcfg-eth0", "# Uplink\nBOOTPROTO=none\nIPADDR=\"192.0.2.10\"\n")
+    ///     .unwrap();
+    /// assert_eq!(file.device(), "eth0");
+    /// assert_eq!(file.get("IPADDR"), Some("192.0.2.10"));
+    /// ```
+    pub fn parse(file_name: impl Into<String>, contents: &str) -> Result<Self, IfcfgParseError> {
+        let mut file = IfcfgFile {
+            file_name: file_name.into(),
+            variables: Vec::new(),
+        };
+        for (index, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let error = |message: &str| IfcfgParseError {
+                line: index + 1,
+                message: message.to_string(),
+            };
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| error("expected an assignment like KEY=value"))?;
+            let valid_key = key.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+                && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+            if !valid_key {
+                return Err(error(&format!("invalid variable name '{key}'")));
+            }
+
+            let value = unquote(value).ok_or_else(|| error("invalid value"))?;
+            file.variables.push((key.to_string(), value));
+        }
+
+        Ok(file)
+    }
+
+    /// The value of the variable `key`. If it is assigned more than once, the last
+    /// assignment wins, as when the script is sourced.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.variables
+            .iter()
+            .rev()
+            .find(|(k, _)| k == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// The name of the interface: `DEVICE`, or the file name without its `ifcfg-` prefix.
+    pub fn device(&self) -> &str {
+        self.get("DEVICE").unwrap_or_else(|| {
+            self.file_name
+                .strip_prefix("ifcfg-")
+                .unwrap_or(&self.file_name)
+        })
+    }
+}
+
+/// The value of an assignment without its quotes, `None` if a quote is not closed or the
+/// value is followed by anything other than a comment.
+fn unquote(value: &str) -> Option<String> {
+    let mut unquoted = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => loop {
+                match chars.next()? {
+                    '"' => break,
+                    '\\' => unquoted.push(chars.next()?),
+                    c => unquoted.push(c),
+                }
+            },
+            '\'' => loop {
+                match chars.next()? {
+                    '\'' => break,
+                    c => unquoted.push(c),
+                }
+            },
+            '\\' => unquoted.push(chars.next()?),
+            c if c.is_whitespace() => {
+                let rest = chars.as_str().trim_start();
+                return (rest.is_empty() || rest.starts_with('#')).then_some(unquoted);
+            }
+            c => unquoted.push(c),
+        }
+    }
+
+    Some(unquoted)
+}
+
+/// The error returned when a network script cannot be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IfcfgParseError {
+    /// The line the error was found on, starting at 1.
+    pub line: usize,
+    pub message: String,
+}
+
+impl Display for IfcfgParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for IfcfgParseError {}
@@ -0,0 +1,973 @@
+//! Rendering configurations into ifupdown interfaces files.
+
+use crate::ifupdown::{InterfacesFile, InterfacesStanza};
+use crate::{
+    AccessPointConfig, AccessPointMode, ActivationMode, AddressMapping, AnyDeviceConfig,
+    AuthMethod, BondConfig, BridgeConfig, CommonPropertiesAllDevices,
+    CommonPropertiesPhysicalDeviceType, DeviceKind, KeyManagmentMode, NetplanConfig, Renderer,
+    RouteType, RoutingConfig, RoutingPolicy, VlanConfig, WifiConfig,
+};
+
+/// A construct [NetplanConfig::to_ifupdown] cannot express in an interfaces file, and
+/// left out of it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnsupportedConstruct {
+    /// Path to the field, in netplan YAML notation, e.g. `network.tunnels.wg0`.
+    pub path: String,
+    /// Human readable description of what was left out.
+    pub message: String,
+}
+
+/// An interfaces file rendered by [NetplanConfig::to_ifupdown].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IfupdownExport {
+    pub interfaces: InterfacesFile,
+    /// The constructs which were left out of the interfaces file, in the order of the
+    /// devices they belong to.
+    pub unsupported: Vec<UnsupportedConstruct>,
+}
+
+impl NetplanConfig {
+    /// Render the configuration into an ifupdown interfaces file, like
+    /// `/etc/network/interfaces`, for systems which still use ifupdown or ifupdown2,
+    /// e.g. Proxmox.
+    ///
+    /// Ethernets, wifis, bonds, bridges and VLANs get an `iface` stanza per method: `dhcp`
+    /// or `static` for each address family, or `manual` if they have no addresses, with the
+    /// options of the ifenslave, bridge-utils, vlan and wpasupplicant packages. Routes
+    /// other than the default route, and routing policy, become `ip route` and `ip rule`
+    /// commands run by `up` options. Devices are brought up by `auto`, unless they are
+    /// optional, which are brought up by `allow-hotplug`, or their activation mode is set.
+    ///
+    /// Anything the file cannot express, like other kinds of devices, matching or DHCP
+    /// overrides, is left out, and reported in [IfupdownExport::unsupported]. The file can
+    /// be imported again with [NetplanConfig::from_ifupdown].
+    ///
+    /// ```
+    /// use netplan_types::NetplanConfig;
+    ///
+    /// let config = NetplanConfig::build(|net| {
+    ///     net.ethernet("eno1", |e| e);
+    ///     net.bridge("vmbr0", |b| {
+    ///         b.interfaces(["eno1"])
+    ///             .address("192.168.1.10/24")
+    ///             .route("default", "192.168.1.1")
+    ///     });
+    ///     net.tunnel("wg0", |t| t);
+    /// });
+    ///
+    /// let export = config.to_ifupdown();
+    /// let interfaces = export.interfaces.to_string();
+    /// assert!(interfaces.contains("iface vmbr0 inet static\n    address 192.168.1.10/24\n"));
+    /// assert!(interfaces.contains("    bridge-ports eno1\n"));
+    /// assert_eq!(export.unsupported[0].path, "network.tunnels.wg0");
+    /// ```
+    pub fn to_ifupdown(&self) -> IfupdownExport {
+        let network = &self.network;
+        let mut export = IfupdownExport::default();
+        let stanzas = &mut export.interfaces.stanzas;
+        stanzas.push(InterfacesStanza::new("auto", ["lo"]));
+        stanzas.push(InterfacesStanza::new("iface", ["lo", "inet", "loopback"]));
+
+        let mut report = Report {
+            path: "network".to_string(),
+            unsupported: &mut export.unsupported,
+        };
+        if network.renderer == Some(Renderer::NetworkManager) {
+            report.field(
+                "renderer",
+                "ifupdown manages the interfaces, not NetworkManager",
+            );
+        }
+        if network.openvswitch.is_some() {
+            report.field("openvswitch", "ifupdown cannot configure Open vSwitch");
+        }
+
+        // Members and links are defined before the devices using them
+        let mut devices = network.devices().collect::<Vec<_>>();
+        devices.sort_by_key(|(kind, name, _)| {
+            let rank = match kind {
+                DeviceKind::Ethernet | DeviceKind::Wifi => 0,
+                DeviceKind::Bond => 1,
+                DeviceKind::Vlan => 2,
+                DeviceKind::Bridge => 3,
+                _ => 4,
+            };
+            (rank, *name)
+        });
+
+        for (kind, name, device) in devices {
+            let mut report = Report {
+                path: format!("network.{}.{name}", kind.section()),
+                unsupported: &mut export.unsupported,
+            };
+            let mut options = Vec::new();
+            match device {
+                AnyDeviceConfig::Ethernet(ethernet) => {
+                    for (key, set) in [
+                        ("link", ethernet.link.is_some()),
+                        (
+                            "virtual-function-count",
+                            ethernet.virtual_function_count.is_some(),
+                        ),
+                        (
+                            "embedded-switch-mode",
+                            ethernet.embedded_switch_mode.is_some(),
+                        ),
+                        (
+                            "delay-virtual-functions-rebind",
+                            ethernet.delay_virtual_functions_rebind.is_some(),
+                        ),
+                    ] {
+                        if set {
+                            report.field(key, "ifupdown cannot configure SR-IOV");
+                        }
+                    }
+                }
+                AnyDeviceConfig::Wifi(wifi) => wifi_options(&mut options, &mut report, wifi),
+                AnyDeviceConfig::Bond(bond) => bond_options(&mut options, &mut report, bond),
+                AnyDeviceConfig::Bridge(bridge) => {
+                    bridge_options(&mut options, &mut report, bridge)
+                }
+                AnyDeviceConfig::Vlan(vlan) => vlan_options(&mut options, &mut report, name, vlan),
+                _ => {
+                    report.unsupported.push(UnsupportedConstruct {
+                        path: report.path.clone(),
+                        message: format!("ifupdown cannot create {kind}"),
+                    });
+                    continue;
+                }
+            }
+
+            let interface = physical(&mut report, device.common_physical()).unwrap_or(name);
+            let common = device.common_all().cloned().unwrap_or_default();
+            let (activation, device_stanzas) =
+                iface_stanzas(&mut report, interface, &common, options);
+            if let Some(keyword) = activation {
+                stanzas.push(InterfacesStanza::new(keyword, [interface]));
+            }
+            stanzas.extend(device_stanzas);
+        }
+
+        export
+    }
+}
+
+/// Collects the constructs left out of the stanzas of a device.
+struct Report<'a> {
+    /// Path to the device, in netplan YAML notation.
+    path: String,
+    unsupported: &'a mut Vec<UnsupportedConstruct>,
+}
+
+impl Report<'_> {
+    /// Report that the field `key` of the device was left out.
+    fn field(&mut self, key: &str, message: impl Into<String>) {
+        self.unsupported.push(UnsupportedConstruct {
+            path: format!("{}.{key}", self.path),
+            message: message.into(),
+        });
+    }
+}
+
+/// A time span in whole seconds or milliseconds, as the ifupdown hooks expect them, `None`
+/// if it has a unit they do not support. Time spans without a unit are in the unit the
+/// hooks expect already.
+pub(crate) fn time_span(value: &str, milliseconds: bool) -> Option<String> {
+    let total = if let Some(number) = value.strip_suffix("ms") {
+        number.parse::<u64>().ok()?
+    } else if let Some(number) = value.strip_suffix('s') {
+        number.parse::<u64>().ok()? * 1000
+    } else {
+        let scale = if milliseconds { 1 } else { 1000 };
+        value.parse::<u64>().ok()? * scale
+    };
+
+    if milliseconds {
+        Some(total.to_string())
+    } else {
+        Some((total / 1000).to_string())
+    }
+}
+
+/// Report the physical properties ifupdown cannot configure, returning the name the
+/// device is renamed to, if any.
+fn physical<'a>(
+    report: &mut Report<'_>,
+    physical: Option<&'a CommonPropertiesPhysicalDeviceType>,
+) -> Option<&'a str> {
+    let physical = physical?;
+    if physical.r#match.is_some() {
+        report.field(
+            "match",
+            "ifupdown configures interfaces by their name, it cannot match them",
+        );
+    }
+    if physical.set_name.is_some() {
+        report.field(
+            "set-name",
+            "ifupdown cannot rename interfaces, the stanzas use the new name",
+        );
+    }
+    for (key, set) in [
+        ("wakeonlan", physical.wakeonlan.is_some()),
+        ("emit-lldp", physical.emit_lldp.is_some()),
+        (
+            "receive-checksum-offload",
+            physical.receive_checksum_offload.is_some(),
+        ),
+        (
+            "transmit-checksum-offload",
+            physical.transmit_checksum_offload.is_some(),
+        ),
+        (
+            "tcp-segmentation-offload",
+            physical.tcp_segmentation_offload.is_some(),
+        ),
+        (
+            "tcp6-segmentation-offload",
+            physical.tcp6_segmentation_offload.is_some(),
+        ),
+        (
+            "generic-segmentation-offload",
+            physical.generic_segmentation_offload.is_some(),
+        ),
+        (
+            "generic-receive-offload",
+            physical.generic_receive_offload.is_some(),
+        ),
+        (
+            "large-receive-offload",
+            physical.large_receive_offload.is_some(),
+        ),
+        ("openvswitch", physical.openvswitch.is_some()),
+    ] {
+        if set {
+            report.field(key, "ifupdown has no equivalent");
+        }
+    }
+
+    physical.set_name.as_deref()
+}
+
+/// The keyword bringing up an interface, and its `iface` stanzas with the options of its
+/// kind added to the first one.
+fn iface_stanzas(
+    report: &mut Report<'_>,
+    name: &str,
+    common: &CommonPropertiesAllDevices,
+    options: Vec<(String, String)>,
+) -> (Option<&'static str>, Vec<InterfacesStanza>) {
+    for (key, set) in [
+        ("ipv6-mtu", common.ipv6_mtu.is_some()),
+        ("link-local", common.link_local.is_some()),
+        ("ignore-carrier", common.ignore_carrier.is_some()),
+        ("critical", common.critical.is_some()),
+        ("dhcp-identifier", common.dhcp_identifier.is_some()),
+        ("dhcp4-overrides", common.dhcp4_overrides.is_some()),
+        ("dhcp6-overrides", common.dhcp6_overrides.is_some()),
+        (
+            "ipv6-address-generation",
+            common.ipv6_address_generation.is_some(),
+        ),
+        ("ipv6-address-token", common.ipv6_address_token.is_some()),
+        ("optional-addresses", common.optional_addresses.is_some()),
+        ("networkmanager", common.networkmanager.is_some()),
+    ] {
+        if set {
+            report.field(key, "ifupdown has no equivalent");
+        }
+    }
+    if common.renderer == Some(Renderer::NetworkManager) {
+        report.field(
+            "renderer",
+            "ifupdown manages the interface, not NetworkManager",
+        );
+    }
+
+    let mut stanzas = Vec::new();
+    let mut commands = Vec::new();
+    let mut routes = common.routes.iter().flatten().collect::<Vec<_>>();
+    for ipv6 in [false, true] {
+        let family = if ipv6 { "inet6" } else { "inet" };
+        let is_family = |address: &str| address.contains(':') == ipv6;
+        let dhcp = if ipv6 { common.dhcp6 } else { common.dhcp4 };
+        if dhcp == Some(true) {
+            stanzas.push(InterfacesStanza::new("iface", [name, family, "dhcp"]));
+        } else if ipv6 && common.accept_ra == Some(true) {
+            stanzas.push(InterfacesStanza::new("iface", [name, family, "auto"]));
+        }
+
+        let addresses = common
+            .addresses
+            .iter()
+            .flatten()
+            .flat_map(AddressMapping::addresses)
+            .filter(|address| is_family(address))
+            .collect::<Vec<_>>();
+        if !addresses.is_empty() {
+            let mut stanza = InterfacesStanza::new("iface", [name, family, "static"]);
+            for address in addresses {
+                stanza.add("address", address);
+            }
+
+            let gateway = if ipv6 {
+                common.gateway6.as_deref()
+            } else {
+                common.gateway4.as_deref()
+            };
+            let default_route = routes.iter().position(|route| {
+                let to = route.to.as_deref().unwrap_or("default");
+                let default = matches!(to, "default" | "0.0.0.0/0" | "::/0");
+                default
+                    && route.via.as_deref().is_some_and(is_family)
+                    && *route
+                        == &RoutingConfig {
+                            to: route.to.clone(),
+                            via: route.via.clone(),
+                            metric: route.metric,
+                            ..Default::default()
+                        }
+            });
+            if let Some(gateway) = gateway {
+                stanza.add("gateway", gateway);
+            } else if let Some(index) = default_route {
+                let route = routes.remove(index);
+                stanza.add("gateway", route.via.as_deref().unwrap_or_default());
+                if let Some(metric) = route.metric {
+                    stanza.add("metric", metric);
+                }
+            }
+            stanzas.push(stanza);
+        } else if let Some(gateway) = if ipv6 {
+            &common.gateway6
+        } else {
+            &common.gateway4
+        } {
+            let ip = if ipv6 { "ip -6" } else { "ip" };
+            commands.push(format!("{ip} route add default via {gateway}"));
+        }
+
+        if let Some(stanza) = stanzas
+            .iter_mut()
+            .find(|stanza| ipv6 && stanza.arguments[1] == "inet6")
+        {
+            if let Some(accept_ra) = common.accept_ra.filter(|_| stanza.arguments[2] != "auto") {
+                stanza.add("accept_ra", if accept_ra { 2 } else { 0 });
+            }
+            if common.ipv6_privacy == Some(true) && stanza.arguments[2] != "static" {
+                stanza.add("privext", 2);
+            }
+        } else if ipv6 {
+            if common.accept_ra == Some(false) {
+                report.field(
+                    "accept-ra",
+                    "ifupdown only configures router advertisements with IPv6 addressing",
+                );
+            }
+            if common.ipv6_privacy.is_some() {
+                report.field(
+                    "ipv6-privacy",
+                    "ifupdown only configures privacy extensions with IPv6 DHCP or SLAAC",
+                );
+            }
+        }
+    }
+    if stanzas.is_empty() {
+        stanzas.push(InterfacesStanza::new("iface", [name, "inet", "manual"]));
+    }
+
+    for (i, address) in common.addresses.iter().flatten().enumerate() {
+        if let AddressMapping::Complex(_) = address {
+            report.field(
+                &format!("addresses[{i}]"),
+                "ifupdown cannot set the lifetime or label of an address, only the address is configured",
+            );
+        }
+    }
+
+    for route in routes {
+        commands.push(route_command(route));
+    }
+    for policy in common.routing_policy.iter().flatten() {
+        commands.push(rule_command(policy));
+    }
+
+    let first = &mut stanzas[0];
+    for (key, value) in options {
+        first.add(key, value);
+    }
+    if let Some(mac) = &common.macaddress {
+        first.add("hwaddress", format!("ether {mac}"));
+    }
+    first
+        .options
+        .extend(common.mtu.map(|mtu| ("mtu".to_string(), mtu.to_string())));
+    if let Some(nameservers) = &common.nameservers {
+        for (key, values) in [
+            ("dns-nameservers", &nameservers.addresses),
+            ("dns-search", &nameservers.search),
+        ] {
+            let values = values.iter().flatten().cloned().collect::<Vec<_>>();
+            if !values.is_empty() {
+                first.add(key, values.join(" "));
+            }
+        }
+    }
+    for command in commands {
+        first.add("up", command);
+    }
+
+    let activation = match (&common.activation_mode, common.optional) {
+        (Some(ActivationMode::Manual | ActivationMode::Off), _) => None,
+        (None, Some(true)) => Some("allow-hotplug"),
+        (None, _) => Some("auto"),
+    };
+    (activation, stanzas)
+}
+
+/// The `ip route add` command adding a route.
+pub(crate) fn route_command(route: &RoutingConfig) -> String {
+    let to = route.to.as_deref().unwrap_or("default");
+    let ipv6 = to.contains(':') || route.via.as_deref().is_some_and(|via| via.contains(':'));
+    let mut command = if ipv6 {
+        "ip -6 route add"
+    } else {
+        "ip route add"
+    }
+    .to_string();
+    if let Some(route_type) = route.r#type.as_ref().filter(|t| **t != RouteType::Unicast) {
+        command.push_str(&format!(" {}", route_type.as_str()));
+    }
+    command.push_str(&format!(" {to}"));
+    for (key, value) in [
+        ("via", route.via.clone()),
+        ("src", route.from.clone()),
+        ("metric", route.metric.map(|m| m.to_string())),
+        ("table", route.table.map(|t| t.to_string())),
+        (
+            "scope",
+            route.scope.as_ref().map(|s| s.as_str().to_string()),
+        ),
+        ("mtu", route.mtu.map(|m| m.to_string())),
+        ("initcwnd", route.congestion_window.map(|w| w.to_string())),
+        (
+            "initrwnd",
+            route.advertised_receive_window.map(|w| w.to_string()),
+        ),
+    ] {
+        if let Some(value) = value {
+            command.push_str(&format!(" {key} {value}"));
+        }
+    }
+    if route.on_link == Some(true) {
+        command.push_str(" onlink");
+    }
+    command
+}
+
+/// The `ip rule add` command adding a routing policy rule.
+pub(crate) fn rule_command(policy: &RoutingPolicy) -> String {
+    let ipv6 = [&policy.from, &policy.to]
+        .into_iter()
+        .flatten()
+        .any(|address| address.contains(':'));
+    let mut command = if ipv6 {
+        "ip -6 rule add"
+    } else {
+        "ip rule add"
+    }
+    .to_string();
+    for (key, value) in [
+        ("from", policy.from.clone()),
+        ("to", policy.to.clone()),
+        ("tos", policy.type_of_service.clone()),
+        ("fwmark", policy.mark.map(|m| m.to_string())),
+        ("priority", policy.priority.map(|p| p.to_string())),
+    ] {
+        if let Some(value) = value {
+            command.push_str(&format!(" {key} {value}"));
+        }
+    }
+    command.push_str(&format!(" table {}", policy.table));
+    command
+}
+
+/// The ifenslave or bridge-utils options of a bond or bridge, including its members, for
+/// formats which take them as they are. Parameters the options cannot express are left out.
+#[cfg(feature = "yaml")]
+pub(crate) fn controller_options(device: &AnyDeviceConfig<'_>) -> Vec<(String, String)> {
+    let mut options = Vec::new();
+    let mut report = Report {
+        path: String::new(),
+        unsupported: &mut Vec::new(),
+    };
+    match device {
+        AnyDeviceConfig::Bond(bond) => bond_options(&mut options, &mut report, bond),
+        AnyDeviceConfig::Bridge(bridge) => bridge_options(&mut options, &mut report, bridge),
+        _ => {}
+    }
+    options
+}
+
+/// The ifenslave options of a bond.
+fn bond_options(options: &mut Vec<(String, String)>, report: &mut Report<'_>, bond: &BondConfig) {
+    let mut add = |key: &str, value: Option<String>| {
+        if let Some(value) = value {
+            options.push((key.to_string(), value));
+        }
+    };
+
+    let slaves = bond
+        .interfaces
+        .iter()
+        .flatten()
+        .cloned()
+        .collect::<Vec<_>>();
+    add(
+        "bond-slaves",
+        Some(if slaves.is_empty() {
+            "none".to_string()
+        } else {
+            slaves.join(" ")
+        }),
+    );
+    if bond.openvswitch.is_some() {
+        report.field("openvswitch", "ifupdown cannot configure Open vSwitch");
+    }
+
+    let Some(parameters) = &bond.parameters else {
+        return;
+    };
+    let mut times = Vec::new();
+    let mut time = |key: &'static str, value: &Option<String>, milliseconds: bool| {
+        let value = value.as_deref()?;
+        let span = time_span(value, milliseconds);
+        if span.is_none() {
+            times.push(key);
+        }
+        span
+    };
+    let mii_monitor_interval = time(
+        "mii-monitor-interval",
+        &parameters.mii_monitor_interval,
+        true,
+    );
+    let up_delay = time("up-delay", &parameters.up_delay, true);
+    let down_delay = time("down-delay", &parameters.down_delay, true);
+    let arp_interval = time("arp-interval", &parameters.arp_interval, true);
+    let learn_packet_interval = time(
+        "learn-packet-interval",
+        &parameters.learn_packet_interval,
+        false,
+    );
+
+    add(
+        "bond-mode",
+        parameters.mode.as_ref().map(|m| m.as_str().into()),
+    );
+    add("bond-miimon", mii_monitor_interval);
+    add(
+        "bond-lacp-rate",
+        parameters.lacp_rate.as_ref().map(|r| r.as_str().into()),
+    );
+    add(
+        "bond-xmit-hash-policy",
+        parameters
+            .transmit_hash_policy
+            .as_ref()
+            .map(|p| p.as_str().into()),
+    );
+    add(
+        "bond-ad-select",
+        parameters.ad_select.as_ref().map(|s| s.as_str().into()),
+    );
+    add(
+        "bond-min-links",
+        parameters.min_links.map(|m| m.to_string()),
+    );
+    add(
+        "bond-all-slaves-active",
+        parameters
+            .all_slaves_active
+            .map(|active| (active as u8).to_string()),
+    );
+    add("bond-updelay", up_delay);
+    add("bond-downdelay", down_delay);
+    add("bond-arp-interval", arp_interval);
+    add(
+        "bond-arp-ip-target",
+        parameters
+            .arp_ip_targets
+            .as_ref()
+            .map(|targets| targets.join(" ")),
+    );
+    add(
+        "bond-arp-validate",
+        parameters.arp_validate.as_ref().map(|v| v.as_str().into()),
+    );
+    add(
+        "bond-arp-all-targets",
+        parameters
+            .arp_all_targets
+            .as_ref()
+            .map(|t| t.as_str().into()),
+    );
+    add(
+        "bond-fail-over-mac",
+        parameters
+            .fail_over_mac_policy
+            .as_ref()
+            .map(|p| p.as_str().into()),
+    );
+    add(
+        "bond-num-grat-arp",
+        parameters.gratuitous_arp.map(|g| g.to_string()),
+    );
+    add(
+        "bond-packets-per-slave",
+        parameters.packets_per_slave.map(|p| p.to_string()),
+    );
+    add(
+        "bond-primary-reselect",
+        parameters
+            .primary_reselect_policy
+            .as_ref()
+            .map(|p| p.as_str().into()),
+    );
+    add(
+        "bond-resend-igmp",
+        parameters.resend_igmp.map(|r| r.to_string()),
+    );
+    add("bond-lp-interval", learn_packet_interval);
+    add("bond-primary", parameters.primary.clone());
+
+    for key in times {
+        report.field(
+            &format!("parameters.{key}"),
+            "the time span has a unit ifenslave does not support",
+        );
+    }
+}
+
+/// The bridge-utils options of a bridge.
+fn bridge_options(
+    options: &mut Vec<(String, String)>,
+    report: &mut Report<'_>,
+    bridge: &BridgeConfig,
+) {
+    let ports = bridge
+        .interfaces
+        .iter()
+        .flatten()
+        .cloned()
+        .collect::<Vec<_>>();
+    options.push((
+        "bridge-ports".to_string(),
+        if ports.is_empty() {
+            "none".to_string()
+        } else {
+            ports.join(" ")
+        },
+    ));
+    if bridge.openvswitch.is_some() {
+        report.field("openvswitch", "ifupdown cannot configure Open vSwitch");
+    }
+
+    let Some(parameters) = &bridge.parameters else {
+        return;
+    };
+    if let Some(stp) = parameters.stp {
+        options.push((
+            "bridge-stp".to_string(),
+            if stp { "on" } else { "off" }.into(),
+        ));
+    }
+    for (key, option, value) in [
+        ("forward-delay", "bridge-fd", &parameters.forward_delay),
+        ("hello-time", "bridge-hello", &parameters.hello_time),
+        ("max-age", "bridge-maxage", &parameters.max_age),
+        ("ageing-time", "bridge-ageing", &parameters.ageing_time),
+    ] {
+        let Some(value) = value else {
+            continue;
+        };
+        match time_span(value, false) {
+            Some(seconds) => options.push((option.to_string(), seconds)),
+            None => report.field(
+                &format!("parameters.{key}"),
+                "the time span has a unit bridge-utils does not support",
+            ),
+        }
+    }
+    if let Some(priority) = parameters.priority {
+        options.push(("bridge-bridgeprio".to_string(), priority.to_string()));
+    }
+    for (key, set) in [
+        ("port-priority", parameters.port_priority.is_some()),
+        ("path-cost", parameters.path_cost.is_some()),
+    ] {
+        if set {
+            report.field(
+                &format!("parameters.{key}"),
+                "bridge-utils sets it per port, and the port is not known",
+            );
+        }
+    }
+}
+
+/// The vlan package options of a VLAN. Interfaces named like `eth0.10` need none.
+fn vlan_options(
+    options: &mut Vec<(String, String)>,
+    report: &mut Report<'_>,
+    name: &str,
+    vlan: &VlanConfig,
+) {
+    if vlan.openvswitch.is_some() {
+        report.field("openvswitch", "ifupdown cannot configure Open vSwitch");
+    }
+
+    let (Some(link), Some(id)) = (&vlan.link, vlan.id) else {
+        return;
+    };
+    if name == format!("{link}.{id}") {
+        return;
+    }
+    options.push(("vlan-raw-device".to_string(), link.clone()));
+    let digits = name.trim_start_matches(|c: char| !c.is_ascii_digit());
+    if digits.parse::<u16>().ok() != Some(id) {
+        options.push(("vlan-id".to_string(), id.to_string()));
+    }
+}
+
+/// The wpasupplicant options of a wifi, connecting to its first access point.
+fn wifi_options(options: &mut Vec<(String, String)>, report: &mut Report<'_>, wifi: &WifiConfig) {
+    for (key, set) in [
+        ("wakeonwlan", wifi.wakeonwlan.is_some()),
+        ("regulatory-domain", wifi.regulatory_domain.is_some()),
+    ] {
+        if set {
+            report.field(key, "ifupdown has no equivalent");
+        }
+    }
+
+    let mut access_points = wifi.access_points.iter().flatten().collect::<Vec<_>>();
+    access_points.sort_by_key(|(ssid, _)| *ssid);
+    let mut access_points = access_points.into_iter();
+    let access_point = access_points.find(|(ssid, access_point)| {
+        let client = matches!(
+            access_point.mode,
+            None | Some(AccessPointMode::Infrastructure)
+        );
+        if !client {
+            report.field(
+                &format!("access-points.{ssid}"),
+                "wpasupplicant only connects to access points in infrastructure mode",
+            );
+        }
+        client
+    });
+    for (ssid, _) in access_points {
+        report.field(
+            &format!("access-points.{ssid}"),
+            "wpasupplicant only connects to one access point per interface",
+        );
+    }
+    if let Some((ssid, access_point)) = access_point {
+        access_point_options(options, report, ssid, access_point);
+    }
+}
+
+fn access_point_options(
+    options: &mut Vec<(String, String)>,
+    report: &mut Report<'_>,
+    ssid: &str,
+    access_point: &AccessPointConfig,
+) {
+    let mut add = |key: &str, value: Option<&str>| {
+        if let Some(value) = value {
+            options.push((key.to_string(), value.to_string()));
+        }
+    };
+
+    add("wpa-ssid", Some(ssid));
+    let bssid = access_point.bssid.as_ref().map(|bssid| bssid.to_string());
+    add("wpa-bssid", bssid.as_deref());
+    if access_point.hidden == Some(true) {
+        add("wpa-scan-ssid", Some("1"));
+    }
+
+    let auth = access_point.auth.as_ref();
+    let password = auth
+        .and_then(|a| a.password.as_ref())
+        .or(access_point.password.as_ref())
+        .map(|password| password.as_str());
+    let key_mgmt = match (auth.and_then(|a| a.key_management.as_ref()), password) {
+        (Some(KeyManagmentMode::None), _) | (None, None) => Some("NONE"),
+        (Some(KeyManagmentMode::Psk), _) | (None, Some(_)) => None,
+        (Some(KeyManagmentMode::Sae), _) => Some("SAE"),
+        (Some(KeyManagmentMode::Eap), _) => Some("WPA-EAP"),
+        (Some(KeyManagmentMode::EightZeroTwoDotOneX), _) => Some("IEEE8021X"),
+    };
+    add("wpa-key-mgmt", key_mgmt);
+    match (key_mgmt, auth) {
+        (Some("WPA-EAP" | "IEEE8021X"), Some(auth)) => {
+            let method = auth.method.as_ref().map(AuthMethod::as_str);
+            add("wpa-eap", method.map(str::to_uppercase).as_deref());
+            add("wpa-identity", auth.identity.as_deref());
+            add("wpa-anonymous-identity", auth.anonymous_identity.as_deref());
+            add("wpa-password", password);
+            add("wpa-ca-cert", auth.ca_certificate.as_deref());
+            add("wpa-client-cert", auth.client_certificate.as_deref());
+            add("wpa-private-key", auth.client_key.as_deref());
+            let key_password = auth.client_key_password.as_ref();
+            add(
+                "wpa-private-key-passwd",
+                key_password.map(|password| password.as_str()),
+            );
+            let phase2 = auth.phase2_auth.as_ref().map(|p| format!("auth={p}"));
+            add("wpa-phase2", phase2.as_deref());
+        }
+        (Some("NONE"), _) => {}
+        _ => add("wpa-psk", password),
+    }
+
+    let path = format!("access-points.{ssid}");
+    for (key, set) in [
+        ("band", access_point.band.is_some()),
+        ("channel", access_point.channel.is_some()),
+        ("networkmanager", access_point.networkmanager.is_some()),
+    ] {
+        if set {
+            report.field(&format!("{path}.{key}"), "ifupdown has no equivalent");
+        }
+    }
+}
+
+#[cfg(all(test, feature = "yaml"))]
+mod test {
+    use crate::{InterfacesFile, NetplanConfig};
+
+    #[test]
+    fn export_interfaces() {
+        let config = NetplanConfig::from_yaml(
+            r#"
+network:
+  version: 2
+  ethernets:
+    eno1: {}
+    eno2: {}
+    eno3:
+      match: {macaddress: "52:54:00:aa:bb:03"}
+      set-name: lan0
+      dhcp4: true
+      optional: true
+  bonds:
+    bond0:
+      interfaces: [eno1, eno2]
+      parameters:
+        mode: 802.3ad
+        mii-monitor-interval: 100ms
+        lacp-rate: fast
+  vlans:
+    bond0.20:
+      id: 20
+      link: bond0
+      activation-mode: manual
+  bridges:
+    vmbr0:
+      interfaces: [bond0]
+      addresses: [10.0.0.2/24, "2001:db8::2/64"]
+      dhcp6: true
+      accept-ra: true
+      nameservers: {addresses: [10.0.0.1], search: [example.com]}
+      mtu: 9000
+      routes:
+        - to: default
+          via: 10.0.0.1
+          metric: 100
+        - to: 10.1.0.0/16
+          via: 10.0.0.254
+          table: 10
+      routing-policy:
+        - from: 10.0.0.0/24
+          table: 10
+      parameters: {stp: false, forward-delay: "0", port-priority: 8}
+  tunnels:
+    wg0:
+      mode: wireguard
+"#,
+        )
+        .unwrap();
+
+        let export = config.to_ifupdown();
+        let expected = r#"auto lo
+iface lo inet loopback
+
+auto eno1
+iface eno1 inet manual
+
+auto eno2
+iface eno2 inet manual
+
+allow-hotplug lan0
+iface lan0 inet dhcp
+
+auto bond0
+iface bond0 inet manual
+    bond-slaves eno1 eno2
+    bond-mode 802.3ad
+    bond-miimon 100
+    bond-lacp-rate fast
+
+iface bond0.20 inet manual
+
+auto vmbr0
+iface vmbr0 inet static
+    address 10.0.0.2/24
+    gateway 10.0.0.1
+    metric 100
+    bridge-ports bond0
+    bridge-stp off
+    bridge-fd 0
+    mtu 9000
+    dns-nameservers 10.0.0.1
+    dns-search example.com
+    up ip route add 10.1.0.0/16 via 10.0.0.254 table 10
+    up ip rule add from 10.0.0.0/24 table 10
+
+iface vmbr0 inet6 dhcp
+    accept_ra 2
+
+iface vmbr0 inet6 static
+    address 2001:db8::2/64
+"#;
+        assert_eq!(export.interfaces.to_string(), expected);
+
+        let paths = export
+            .unsupported
+            .iter()
+            .map(|unsupported| unsupported.path.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            paths,
+            [
+                "network.ethernets.eno3.match",
+                "network.ethernets.eno3.set-name",
+                "network.bridges.vmbr0.parameters.port-priority",
+                "network.tunnels.wg0",
+            ]
+        );
+
+        // Everything but the unsupported constructs survives a round trip
+        let interfaces: InterfacesFile = expected.parse().unwrap();
+        let imported = NetplanConfig::from_ifupdown(&interfaces);
+        let vmbr0 = imported.network.device("vmbr0").unwrap();
+        let common = vmbr0.common_all().unwrap();
+        assert_eq!(common.routes.as_ref().unwrap().len(), 2);
+        assert_eq!(common.routing_policy.as_ref().unwrap()[0].table, 10);
+        assert_eq!(common.dhcp6, Some(true));
+        assert_eq!(common.mtu, Some(9000));
+        assert!(imported.network.device("bond0.20").is_some());
+    }
+}
@@ -0,0 +1,817 @@
+//! Importing ifupdown interfaces files into configurations.
+
+use crate::ifupdown::{InterfacesFile, InterfacesParseError, InterfacesStanza};
+use crate::{
+    AccessPointConfig, ActivationMode, AdSelect, AddressMapping, BondConfig, BondMode,
+    BondParameters, BridgeConfig, BridgeParameters, CommonProperties, CommonPropertiesAllDevices,
+    EthernetConfig, LacpRate, NameserverConfig, NetplanConfig, NetworkConfig, RouteScope,
+    RouteType, RoutingConfig, RoutingPolicy, TransmitHashPolicy, VlanConfig, WifiConfig,
+};
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+use std::net::Ipv4Addr;
+use std::path::{Path, PathBuf};
+
+/// The error returned when an interfaces file cannot be imported.
+#[derive(Debug)]
+pub enum IfupdownImportError {
+    /// The file, or a file it sources, could not be read.
+    Io(std::io::Error),
+    /// The file, or a file it sources, is not a valid interfaces file.
+    Parse {
+        path: PathBuf,
+        error: InterfacesParseError,
+    },
+}
+
+impl Display for IfupdownImportError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "Failed to read interfaces file: {e}"),
+            Self::Parse { path, error } => {
+                write!(f, "Invalid interfaces file {}: {error}", path.display())
+            }
+        }
+    }
+}
+
+impl std::error::Error for IfupdownImportError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Parse { error, .. } => Some(error),
+        }
+    }
+}
+
+impl From<std::io::Error> for IfupdownImportError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl NetplanConfig {
+    /// Lift an ifupdown interfaces file into a configuration, like the retired
+    /// `netplan migrate` did for Debian-style `/etc/network/interfaces`.
+    ///
+    /// Every `iface` stanza, other than the loopback, defines a device: a bridge if it has
+    /// `bridge-ports`, a bond if it has `bond-slaves` or a `bond-mode`, a VLAN if it has a
+    /// `vlan-raw-device` or is named like `eth0.10`, a wifi if it has a `wpa-ssid`, and an
+    /// ethernet otherwise. Stanzas for the IPv4 and IPv6 families of an interface configure
+    /// the same device. Members of bonds and bridges are defined as ethernets, or VLANs,
+    /// if they have no stanza of their own.
+    ///
+    /// The `dhcp` and `static` methods are carried over with their addresses, gateways,
+    /// DNS servers, MTU and MAC address, as are `ip route add` and `ip rule add` commands
+    /// run by `up` options, bond and bridge parameters. `inet6 auto` accepts router advertisements.
+    /// Interfaces which are neither brought up by `auto` or `allow-hotplug`, nor by the
+    /// device they are part of, get the `manual` activation mode, and interfaces which are
+    /// only brought up by `allow-hotplug` are optional. Options netplan has no equivalent
+    /// for are left out.
+    ///
+    /// Options may be spelled with dashes or underscores, e.g. `bridge_ports` or
+    /// `bridge-ports`.
+    ///
+    /// ```
+    /// use netplan_types::{InterfacesFile, NetplanConfig};
+    ///
+    /// let interfaces: InterfacesFile = "
+    /// auto eth0
+    /// iface eth0 inet manual
+    ///
+    /// auto br0
+    /// iface br0 inet static
+    ///     address 192.168.1.10/24
+    ///     gateway 192.168.1.1
+    ///     bridge_ports eth0
+    /// "
+    /// .parse()
+    /// .unwrap();
+    ///
+    /// let config = NetplanConfig::from_ifupdown(&interfaces);
+    /// let expected = NetplanConfig::build(|net| {
+    ///     net.ethernet("eth0", |e| e);
+    ///     net.bridge("br0", |b| {
+    ///         b.interfaces(["eth0"])
+    ///             .address("192.168.1.10/24")
+    ///             .route("default", "192.168.1.1")
+    ///     });
+    /// });
+    /// assert_eq!(config, expected);
+    /// ```
+    pub fn from_ifupdown(interfaces: &InterfacesFile) -> Self {
+        let mut names = Vec::new();
+        let mut stanzas = HashMap::<&str, Vec<&InterfacesStanza>>::new();
+        for iface in interfaces.ifaces() {
+            let [name, _, method, ..] = iface.arguments.as_slice() else {
+                continue;
+            };
+            if method == "loopback" || name == "lo" {
+                continue;
+            }
+            if !stanzas.contains_key(name.as_str()) {
+                names.push(name.as_str());
+            }
+            stanzas.entry(name).or_default().push(iface);
+        }
+
+        let mut config = NetplanConfig::default();
+        let network = &mut config.network;
+        let mut dependencies = HashSet::new();
+        for name in &names {
+            let stanzas = &stanzas[name];
+            let options = Options(stanzas);
+            dependencies.extend(define_device(network, name, &options));
+
+            let mut common = CommonPropertiesAllDevices::default();
+            for stanza in stanzas {
+                import_stanza(&mut common, stanza);
+            }
+            if common != CommonPropertiesAllDevices::default() {
+                if let Some(mut device) = network.device_mut(name) {
+                    *device.common_all_mut() = common;
+                }
+            }
+        }
+
+        // Members of bonds declaring their controller instead of being listed by it
+        for name in &names {
+            let Some(bond) = Options(&stanzas[name]).get("bond-master") else {
+                continue;
+            };
+            if let Some(crate::AnyDeviceConfigMut::Bond(bond)) = network.device_mut(bond) {
+                let interfaces = bond.interfaces.get_or_insert_with(Vec::new);
+                if !interfaces.iter().any(|interface| interface == name) {
+                    interfaces.push(name.to_string());
+                }
+                dependencies.insert(name.to_string());
+            }
+        }
+
+        for dependency in &dependencies {
+            if network.device(dependency).is_none() {
+                match vlan_from_name(dependency) {
+                    Some(vlan) => {
+                        dependencies_of_vlan(network, &vlan);
+                        network.add_vlan(dependency.as_str(), vlan);
+                    }
+                    None => {
+                        network.add_ethernet(dependency.as_str(), EthernetConfig::default());
+                    }
+                }
+            }
+        }
+
+        let auto = interfaces
+            .listed("auto")
+            .chain(interfaces.listed("allow-auto"))
+            .collect::<HashSet<_>>();
+        let hotplug = interfaces.listed("allow-hotplug").collect::<HashSet<_>>();
+        for name in &names {
+            if auto.contains(name) || dependencies.contains(*name) {
+                continue;
+            }
+            let Some(mut device) = network.device_mut(name) else {
+                continue;
+            };
+            let common = device.common_all_mut();
+            if hotplug.contains(name) {
+                common.optional = Some(true);
+            } else {
+                common.activation_mode = Some(ActivationMode::Manual);
+            }
+        }
+
+        config
+    }
+
+    /// Read an interfaces file like `/etc/network/interfaces`, together with the files it
+    /// includes with `source` and `source-directory`, and import it, see
+    /// [NetplanConfig::from_ifupdown].
+    ///
+    /// Relative paths are resolved against the directory of the including file. Only the
+    /// last component of a `source` path may contain a `*` wildcard, e.g.
+    /// `source /etc/network/interfaces.d/*`.
+    pub fn from_ifupdown_file(path: impl AsRef<Path>) -> Result<Self, IfupdownImportError> {
+        let mut interfaces = InterfacesFile::default();
+        read_interfaces(path.as_ref(), &mut interfaces, &mut HashSet::new())?;
+        Ok(Self::from_ifupdown(&interfaces))
+    }
+}
+
+/// Read an interfaces file into `interfaces`, replacing its `source` stanzas with the
+/// stanzas of the sourced files. Files which have been read already are skipped.
+fn read_interfaces(
+    path: &Path,
+    interfaces: &mut InterfacesFile,
+    read: &mut HashSet<PathBuf>,
+) -> Result<(), IfupdownImportError> {
+    if !read.insert(path.to_path_buf()) {
+        return Ok(());
+    }
+
+    let contents = std::fs::read_to_string(path)?;
+    let file = contents
+        .parse::<InterfacesFile>()
+        .map_err(|error| IfupdownImportError::Parse {
+            path: path.to_path_buf(),
+            error,
+        })?;
+    let dir = path.parent().unwrap_or(Path::new("."));
+    for stanza in file.stanzas {
+        let sources = match stanza.keyword.as_str() {
+            "source" => stanza
+                .arguments
+                .iter()
+                .map(|source| expand(&dir.join(source)))
+                .collect::<Result<Vec<_>, _>>()?
+                .concat(),
+            "source-directory" => stanza
+                .arguments
+                .iter()
+                .map(|source| run_parts(&dir.join(source)))
+                .collect::<Result<Vec<_>, _>>()?
+                .concat(),
+            _ => {
+                interfaces.stanzas.push(stanza);
+                continue;
+            }
+        };
+
+        for source in sources {
+            read_interfaces(&source, interfaces, read)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// The files matching a `source` path, sorted, whose last component may contain a `*`.
+fn expand(path: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let pattern = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or_default();
+    let Some((prefix, suffix)) = pattern.split_once('*') else {
+        return Ok(vec![path.to_path_buf()]);
+    };
+
+    let dir = path.parent().unwrap_or(Path::new("."));
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        let Some(name) = name.to_str() else {
+            continue;
+        };
+        let matches = name.len() >= prefix.len() + suffix.len()
+            && name.starts_with(prefix)
+            && name.ends_with(suffix)
+            && !name.starts_with('.');
+        if matches && entry.file_type()?.is_file() {
+            files.push(entry.path());
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// The files of a `source-directory`, sorted, whose names are valid for run-parts.
+fn run_parts(dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let valid = entry.file_name().to_str().is_some_and(|name| {
+            name.chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        });
+        if valid && entry.file_type()?.is_file() {
+            files.push(entry.path());
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// The options of the stanzas of an interface, looked up regardless of whether they are
+/// spelled with dashes or underscores.
+struct Options<'a>(&'a [&'a InterfacesStanza]);
+
+impl<'a> Options<'a> {
+    fn get(&self, key: &str) -> Option<&'a str> {
+        self.0.iter().find_map(|stanza| option(stanza, key))
+    }
+
+    /// The words of the option `key`, `None` if it is unset or `none`.
+    fn words(&self, key: &str) -> Option<Vec<&'a str>> {
+        let value = self.get(key).filter(|value| *value != "none")?;
+        Some(value.split_whitespace().collect())
+    }
+}
+
+fn option<'a>(stanza: &'a InterfacesStanza, key: &str) -> Option<&'a str> {
+    stanza
+        .options
+        .iter()
+        .find(|(k, _)| k.replace('_', "-") == key)
+        .map(|(_, value)| value.as_str())
+}
+
+fn options<'a>(stanza: &'a InterfacesStanza, key: &'a str) -> impl Iterator<Item = &'a str> {
+    stanza
+        .options
+        .iter()
+        .filter(move |(k, _)| k.replace('_', "-") == key)
+        .map(|(_, value)| value.as_str())
+}
+
+/// A VLAN for an interface named after its link and ID, like `eth0.10`.
+fn vlan_from_name(name: &str) -> Option<VlanConfig> {
+    let (link, id) = name.rsplit_once('.')?;
+    Some(VlanConfig {
+        id: Some(id.parse().ok()?),
+        link: Some(link.to_string()),
+        ..Default::default()
+    })
+}
+
+/// Define the link of a VLAN created for a member, if it is not defined.
+fn dependencies_of_vlan(network: &mut NetworkConfig, vlan: &VlanConfig) {
+    if let Some(link) = vlan.link.as_deref() {
+        if network.device(link).is_none() {
+            network.add_ethernet(link, EthernetConfig::default());
+        }
+    }
+}
+
+/// Define the device of an interface, returning the interfaces it depends on: its members
+/// and the link of a VLAN.
+fn define_device(network: &mut NetworkConfig, name: &str, options: &Options<'_>) -> Vec<String> {
+    let time = |key: &str| options.get(key).map(str::to_string);
+
+    if let Some(ports) = options.get("bridge-ports") {
+        let interfaces = match ports {
+            "none" => Vec::new(),
+            ports => ports.split_whitespace().map(str::to_string).collect(),
+        };
+        let parameters = BridgeParameters {
+            ageing_time: time("bridge-ageing"),
+            priority: options
+                .get("bridge-bridgeprio")
+                .and_then(|p| p.parse().ok()),
+            forward_delay: time("bridge-fd"),
+            hello_time: time("bridge-hello"),
+            max_age: time("bridge-maxage"),
+            stp: options.get("bridge-stp").and_then(|stp| match stp {
+                "on" | "yes" | "1" => Some(true),
+                "off" | "no" | "0" => Some(false),
+                _ => None,
+            }),
+            ..Default::default()
+        };
+        network.add_bridge(
+            name,
+            BridgeConfig {
+                interfaces: Some(interfaces.clone()),
+                parameters: (parameters != BridgeParameters::default())
+                    .then(|| Box::new(parameters)),
+                ..Default::default()
+            },
+        );
+        return interfaces;
+    }
+
+    if options.get("bond-slaves").is_some() || options.get("bond-mode").is_some() {
+        let interfaces = options
+            .words("bond-slaves")
+            .unwrap_or_default()
+            .into_iter()
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        let parameters = BondParameters {
+            mode: options
+                .get("bond-mode")
+                .and_then(|mode| match mode.parse::<usize>() {
+                    Ok(index) => BondMode::VARIANTS.get(index).cloned(),
+                    Err(_) => variant(BondMode::VARIANTS, BondMode::as_str, mode),
+                }),
+            lacp_rate: options.get("bond-lacp-rate").and_then(|rate| match rate {
+                "0" => Some(LacpRate::Slow),
+                "1" => Some(LacpRate::Fast),
+                rate => variant(LacpRate::VARIANTS, LacpRate::as_str, rate),
+            }),
+            mii_monitor_interval: time("bond-miimon"),
+            min_links: options.get("bond-min-links").and_then(|m| m.parse().ok()),
+            transmit_hash_policy: options.get("bond-xmit-hash-policy").and_then(|policy| {
+                variant(
+                    TransmitHashPolicy::VARIANTS,
+                    TransmitHashPolicy::as_str,
+                    policy,
+                )
+            }),
+            ad_select: options
+                .get("bond-ad-select")
+                .and_then(|select| variant(AdSelect::VARIANTS, AdSelect::as_str, select)),
+            up_delay: time("bond-updelay"),
+            down_delay: time("bond-downdelay"),
+            arp_interval: time("bond-arp-interval"),
+            arp_ip_targets: options
+                .words("bond-arp-ip-target")
+                .map(|targets| targets.into_iter().map(str::to_string).collect()),
+            primary: options.get("bond-primary").map(str::to_string),
+            ..Default::default()
+        };
+        network.add_bond(
+            name,
+            BondConfig {
+                interfaces: (!interfaces.is_empty()).then(|| interfaces.clone()),
+                parameters: (parameters != BondParameters::default()).then(|| Box::new(parameters)),
+                ..Default::default()
+            },
+        );
+        return interfaces;
+    }
+
+    let raw_device = options.get("vlan-raw-device");
+    let from_name = vlan_from_name(name);
+    if raw_device.is_some() || from_name.is_some() {
+        let digits = name.trim_start_matches(|c: char| !c.is_ascii_digit());
+        let vlan = VlanConfig {
+            id: options
+                .get("vlan-id")
+                .and_then(|id| id.parse().ok())
+                .or(from_name.as_ref().and_then(|vlan| vlan.id))
+                .or(digits.parse().ok()),
+            link: raw_device
+                .map(str::to_string)
+                .or(from_name.and_then(|vlan| vlan.link)),
+            ..Default::default()
+        };
+        let link = vlan.link.clone();
+        network.add_vlan(name, vlan);
+        return link.into_iter().collect();
+    }
+
+    if let Some(ssid) = options.get("wpa-ssid") {
+        let ssid = ssid.trim_matches('"');
+        let access_point = match options.get("wpa-psk") {
+            Some(psk) => AccessPointConfig::wpa2(psk.trim_matches('"')),
+            None => AccessPointConfig::default(),
+        };
+        network.add_wifi(
+            name,
+            WifiConfig {
+                access_points: Some(HashMap::from([(ssid.to_string(), access_point)])),
+                ..Default::default()
+            },
+        );
+        return Vec::new();
+    }
+
+    network.add_ethernet(name, EthernetConfig::default());
+    Vec::new()
+}
+
+/// Define the device of an interface from its ifupdown options, for formats which take the
+/// options of bonds, bridges and VLANs as they are, returning the interfaces it depends on.
+pub(crate) fn define_from_options(
+    network: &mut NetworkConfig,
+    name: &str,
+    options: Vec<(String, String)>,
+) -> Vec<String> {
+    let stanza = InterfacesStanza {
+        keyword: "iface".to_string(),
+        arguments: vec![name.to_string()],
+        options,
+    };
+    define_device(network, name, &Options(&[&stanza]))
+}
+
+/// The ethernet of a physical interface, matched by its MAC address and renamed to `name` if
+/// it has one, for formats which identify physical interfaces by their MAC address.
+#[cfg(feature = "yaml")]
+pub(crate) fn ethernet_by_mac(name: &str, mac_address: Option<&str>) -> EthernetConfig {
+    let Some(macaddress) = mac_address.and_then(|mac| mac.parse().ok()) else {
+        return EthernetConfig::default();
+    };
+    EthernetConfig {
+        common_physical: Some(Box::new(crate::CommonPropertiesPhysicalDeviceType {
+            r#match: Some(crate::MatchConfig {
+                macaddress: Some(macaddress),
+                ..Default::default()
+            }),
+            set_name: Some(name.to_string()),
+            ..Default::default()
+        })),
+        ..Default::default()
+    }
+}
+
+/// The variant whose string representation is `value`.
+fn variant<T: Clone>(variants: &[T], as_str: fn(&T) -> &'static str, value: &str) -> Option<T> {
+    variants.iter().find(|v| as_str(v) == value).cloned()
+}
+
+/// The prefix length for a netmask, either dotted like `255.255.255.0` or a length.
+pub(crate) fn prefix_length(netmask: &str) -> Option<u32> {
+    match netmask.parse::<Ipv4Addr>() {
+        Ok(mask) => Some(u32::from(mask).count_ones()),
+        Err(_) => netmask.parse().ok(),
+    }
+}
+
+/// Import the addressing of an `iface` stanza for one address family.
+fn import_stanza(common: &mut CommonPropertiesAllDevices, stanza: &InterfacesStanza) {
+    let (Some(family), Some(method)) = (stanza.arguments.get(1), stanza.arguments.get(2)) else {
+        return;
+    };
+    let ipv6 = family == "inet6";
+    let get = |key: &str| option(stanza, key);
+
+    match method.as_str() {
+        "dhcp" if ipv6 => common.dhcp6 = Some(true),
+        "dhcp" => common.dhcp4 = Some(true),
+        "auto" if ipv6 => {
+            common.accept_ra = Some(true);
+            if get("dhcp") == Some("1") {
+                common.dhcp6 = Some(true);
+            }
+        }
+        "static" => {
+            let netmask = get("netmask").and_then(prefix_length);
+            for address in options(stanza, "address") {
+                let address = match (address.contains('/'), netmask) {
+                    (true, _) => address.to_string(),
+                    (false, Some(prefix)) => format!("{address}/{prefix}"),
+                    (false, None) if ipv6 => format!("{address}/128"),
+                    (false, None) => format!("{address}/32"),
+                };
+                common
+                    .addresses
+                    .get_or_insert_with(Vec::new)
+                    .push(AddressMapping::Simple(address));
+            }
+            if let Some(gateway) = get("gateway") {
+                common
+                    .routes
+                    .get_or_insert_with(Vec::new)
+                    .push(RoutingConfig {
+                        to: Some("default".to_string()),
+                        via: Some(gateway.to_string()),
+                        metric: get("metric").and_then(|metric| metric.parse().ok()),
+                        ..Default::default()
+                    });
+            }
+        }
+        _ => {}
+    }
+
+    let commands = ["up", "post-up"]
+        .into_iter()
+        .flat_map(|key| options(stanza, key));
+    for command in commands {
+        if let Some(route) = route_from_command(command) {
+            common.routes.get_or_insert_with(Vec::new).push(route);
+        } else if let Some(policy) = rule_from_command(command) {
+            common
+                .routing_policy
+                .get_or_insert_with(Vec::new)
+                .push(policy);
+        }
+    }
+
+    if let Some(mtu) = get("mtu").and_then(|mtu| mtu.parse().ok()) {
+        common.mtu = Some(mtu);
+    }
+    let hwaddress = get("hwaddress").map(|hwaddress| hwaddress.trim_start_matches("ether").trim());
+    if let Some(mac) = hwaddress.and_then(|mac| mac.parse().ok()) {
+        common.macaddress = Some(mac);
+    }
+
+    let words = |key: &str| {
+        get(key)
+            .into_iter()
+            .flat_map(str::split_whitespace)
+            .map(str::to_string)
+    };
+    let addresses = words("dns-nameservers").collect::<Vec<_>>();
+    let search = words("dns-search")
+        .chain(words("dns-domain"))
+        .collect::<Vec<_>>();
+    if !addresses.is_empty() || !search.is_empty() {
+        let nameservers = common
+            .nameservers
+            .get_or_insert_with(NameserverConfig::default);
+        for (list, values) in [
+            (&mut nameservers.addresses, addresses),
+            (&mut nameservers.search, search),
+        ] {
+            for value in values {
+                let list = list.get_or_insert_with(Vec::new);
+                if !list.contains(&value) {
+                    list.push(value);
+                }
+            }
+        }
+    }
+}
+
+/// The words of an `ip` command for the given object and `add` or `replace`, following the
+/// object, e.g. `10.1.0.0/16 via 10.0.0.254` for `ip route add 10.1.0.0/16 via 10.0.0.254`.
+fn ip_command<'a>(command: &'a str, object: &str) -> Option<impl Iterator<Item = &'a str>> {
+    let mut words = command.split_whitespace().peekable();
+    if words.next()? != "ip" {
+        return None;
+    }
+    if matches!(words.peek(), Some(&"-4" | &"-6")) {
+        words.next();
+    }
+    if words.next()? != object || !matches!(words.next()?, "add" | "replace") {
+        return None;
+    }
+    Some(words)
+}
+
+/// The route added by an `ip route add` command, e.g.
+/// `ip route add 10.1.0.0/16 via 10.0.0.254 metric 100`.
+pub(crate) fn route_from_command(command: &str) -> Option<RoutingConfig> {
+    let mut words = ip_command(command, "route")?.peekable();
+    let route_type = words
+        .peek()
+        .and_then(|word| variant(RouteType::VARIANTS, RouteType::as_str, word));
+    if route_type.is_some() {
+        words.next();
+    }
+
+    let mut route = RoutingConfig {
+        to: Some(words.next()?.to_string()),
+        r#type: route_type,
+        ..Default::default()
+    };
+    while let Some(word) = words.next() {
+        let mut value = || words.next();
+        match word {
+            "via" => route.via = value().map(str::to_string),
+            "src" => route.from = value().map(str::to_string),
+            "metric" => route.metric = value().and_then(|m| m.parse().ok()),
+            "table" => route.table = value().and_then(|t| t.parse().ok()),
+            "scope" => {
+                route.scope =
+                    value().and_then(|s| variant(RouteScope::VARIANTS, RouteScope::as_str, s))
+            }
+            "mtu" => route.mtu = value().and_then(|m| m.parse().ok()),
+            "initcwnd" => route.congestion_window = value().and_then(|w| w.parse().ok()),
+            "initrwnd" => route.advertised_receive_window = value().and_then(|w| w.parse().ok()),
+            "onlink" => route.on_link = Some(true),
+            _ => {}
+        }
+    }
+    Some(route)
+}
+
+/// The routing policy rule added by an `ip rule add` command, e.g.
+/// `ip rule add from 10.0.0.0/24 table 10`.
+pub(crate) fn rule_from_command(command: &str) -> Option<RoutingPolicy> {
+    let mut words = ip_command(command, "rule")?;
+    let mut policy = RoutingPolicy {
+        table: 254,
+        ..Default::default()
+    };
+    while let Some(word) = words.next() {
+        let mut value = || words.next();
+        match word {
+            "from" => policy.from = value().map(str::to_string),
+            "to" => policy.to = value().map(str::to_string),
+            "tos" | "dsfield" => policy.type_of_service = value().map(str::to_string),
+            "fwmark" => policy.mark = value().and_then(|m| m.parse().ok()),
+            "priority" | "preference" | "pref" => {
+                policy.priority = value().and_then(|p| p.parse().ok())
+            }
+            "table" | "lookup" => policy.table = value().and_then(|t| t.parse().ok())?,
+            _ => {}
+        }
+    }
+    Some(policy)
+}
+
+#[cfg(all(test, feature = "yaml"))]
+mod test {
+    use crate::{InterfacesFile, NetplanConfig};
+
+    #[test]
+    fn import_interfaces() {
+        let interfaces: InterfacesFile = r#"
+# The loopback network interface
+auto lo
+iface lo inet loopback
+
+auto bond0
+iface bond0 inet manual
+    bond-slaves eno1 eno2
+    bond-mode 4
+    bond-miimon 100
+    bond-lacp-rate 1
+    bond-xmit-hash-policy layer3+4
+
+auto bond0.20
+iface bond0.20 inet static
+    address 10.0.20.2
+    netmask 255.255.255.0
+    gateway 10.0.20.1
+    dns-nameservers 10.0.20.1 10.0.20.2
+    dns-search example.com
+    mtu 9000
+    up ip route add 10.30.0.0/16 via 10.0.20.254 metric 50
+
+iface bond0.20 inet6 static
+    address 2001:db8:20::2/64
+    gateway 2001:db8:20::1
+
+auto vmbr0
+iface vmbr0 inet dhcp
+    bridge_ports eno3 \
+        eno4.30
+    bridge_stp off
+    bridge_fd 0
+
+allow-hotplug eth9
+iface eth9 inet6 auto
+    hwaddress ether 52:54:00:aa:bb:09
+
+iface spare inet dhcp
+"#
+        .parse()
+        .unwrap();
+
+        let expected = NetplanConfig::from_yaml(
+            r#"
+network:
+  version: 2
+  ethernets:
+    eno1: {}
+    eno2: {}
+    eno3: {}
+    eno4: {}
+    eth9:
+      accept-ra: true
+      macaddress: "52:54:00:aa:bb:09"
+      optional: true
+    spare:
+      dhcp4: true
+      activation-mode: manual
+  bonds:
+    bond0:
+      interfaces: [eno1, eno2]
+      parameters:
+        mode: 802.3ad
+        mii-monitor-interval: "100"
+        lacp-rate: fast
+        transmit-hash-policy: layer3+4
+  vlans:
+    bond0.20:
+      id: 20
+      link: bond0
+      addresses: [10.0.20.2/24, "2001:db8:20::2/64"]
+      nameservers: {addresses: [10.0.20.1, 10.0.20.2], search: [example.com]}
+      mtu: 9000
+      routes:
+        - to: default
+          via: 10.0.20.1
+        - to: 10.30.0.0/16
+          via: 10.0.20.254
+          metric: 50
+        - to: default
+          via: "2001:db8:20::1"
+    eno4.30:
+      id: 30
+      link: eno4
+  bridges:
+    vmbr0:
+      interfaces: [eno3, eno4.30]
+      dhcp4: true
+      parameters: {stp: false, forward-delay: "0"}
+"#,
+        )
+        .unwrap();
+        let imported = NetplanConfig::from_ifupdown(&interfaces);
+        assert!(imported.semantically_eq(&expected), "{imported:#?}");
+
+        // Sourced files are read in order, relative to the including file
+        let dir = tempfile::tempdir().unwrap();
+        let parts = dir.path().join("interfaces.d");
+        std::fs::create_dir(&parts).unwrap();
+        std::fs::write(
+            dir.path().join("interfaces"),
+            "auto eth0\niface eth0 inet dhcp\n\nsource interfaces.d/*.cfg\n",
+        )
+        .unwrap();
+        std::fs::write(parts.join("eth1.cfg"), "auto eth1\niface eth1 inet dhcp\n").unwrap();
+        std::fs::write(parts.join("ignored"), "iface eth2 inet dhcp\n").unwrap();
+        let loaded = NetplanConfig::from_ifupdown_file(dir.path().join("interfaces")).unwrap();
+        let expected = NetplanConfig::build(|net| {
+            net.ethernet("eth0", |e| e.dhcp4(true));
+            net.ethernet("eth1", |e| e.dhcp4(true));
+        });
+        assert_eq!(loaded, expected);
+    }
+}
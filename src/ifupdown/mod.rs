@@ -0,0 +1,232 @@
+//! Reading and writing ifupdown's `/etc/network/interfaces`, to migrate hosts which use it.
+
+mod generate;
+pub use generate::*;
+mod import;
+pub use import::*;
+
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// The keywords which start a stanza. Any other line in a stanza is one of its options.
+const KEYWORDS: [&str; 7] = [
+    "iface",
+    "mapping",
+    "auto",
+    "source",
+    "source-directory",
+    "rename",
+    "no-auto-down",
+];
+
+/// An ifupdown interfaces file, like `/etc/network/interfaces`: a list of stanzas, such as
+/// `auto eth0` or `iface eth0 inet static` followed by its options.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InterfacesFile {
+    pub stanzas: Vec<InterfacesStanza>,
+}
+
+/// A stanza of an [InterfacesFile], e.g. `iface eth0 inet static` and its options.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InterfacesStanza {
+    /// The keyword starting the stanza, e.g. `iface`, `auto` or `allow-hotplug`.
+    pub keyword: String,
+    /// The words following the keyword, e.g. `eth0 inet static`.
+    pub arguments: Vec<String>,
+    /// The options of an `iface` or `mapping` stanza, in order. Options may repeat, e.g.
+    /// one `up` per command.
+    pub options: Vec<(String, String)>,
+}
+
+impl InterfacesFile {
+    /// The `iface` stanzas, in order.
+    pub fn ifaces(&self) -> impl Iterator<Item = &InterfacesStanza> {
+        self.stanzas
+            .iter()
+            .filter(|stanza| stanza.keyword == "iface")
+    }
+
+    /// The interfaces listed by the stanzas with the given keyword, e.g. the interfaces
+    /// brought up at boot for `auto`.
+    pub fn listed<'a>(&'a self, keyword: &'a str) -> impl Iterator<Item = &'a str> {
+        self.stanzas
+            .iter()
+            .filter(move |stanza| stanza.keyword == keyword)
+            .flat_map(|stanza| stanza.arguments.iter().map(String::as_str))
+    }
+}
+
+impl InterfacesStanza {
+    pub fn new<S: Into<String>>(
+        keyword: impl Into<String>,
+        arguments: impl IntoIterator<Item = S>,
+    ) -> Self {
+        Self {
+            keyword: keyword.into(),
+            arguments: arguments.into_iter().map(Into::into).collect(),
+            options: Vec::new(),
+        }
+    }
+
+    /// The first value of the option `key`.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.options
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Every value of the option `key`, in order.
+    pub fn get_all<'a>(&'a self, key: &'a str) -> impl Iterator<Item = &'a str> {
+        self.options
+            .iter()
+            .filter(move |(k, _)| k == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Append an option.
+    pub fn add(&mut self, key: impl Into<String>, value: impl ToString) {
+        self.options.push((key.into(), value.to_string()));
+    }
+}
+
+impl Display for InterfacesFile {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut previous: Option<&InterfacesStanza> = None;
+        for stanza in &self.stanzas {
+            // Stanzas are set apart after the options of an iface, so `auto eth0` sticks to
+            // the `iface eth0` following it
+            if previous.is_some_and(|p| matches!(p.keyword.as_str(), "iface" | "mapping")) {
+                writeln!(f)?;
+            }
+            write!(f, "{stanza}")?;
+            previous = Some(stanza);
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for InterfacesStanza {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.keyword)?;
+        for argument in &self.arguments {
+            write!(f, " {argument}")?;
+        }
+        writeln!(f)?;
+        for (key, value) in &self.options {
+            writeln!(f, "    {key} {value}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The error returned when an interfaces file cannot be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InterfacesParseError {
+    /// The line the error was found on, starting at 1.
+    pub line: usize,
+    pub message: String,
+}
+
+impl Display for InterfacesParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for InterfacesParseError {}
+
+/// Parse an interfaces file. Empty lines and comments, starting with `#`, are skipped, and
+/// lines ending with a backslash are continued on the next line. `allow-*` stanzas, like
+/// `allow-hotplug`, are recognized as well as the other stanzas ifupdown knows.
+///
+/// ```
+/// use netplan_types::InterfacesFile;
+///
+/// let interfaces: InterfacesFile = "auto eth0\niface eth0 inet static\n    address 10.0.0.2/24\n"
+///     .parse()
+///     .unwrap();
+/// let eth0 = interfaces.ifaces().next().unwrap();
+/// assert_eq!(eth0.arguments, ["eth0", "inet", "static"]);
+/// assert_eq!(eth0.get("address"), Some("10.0.0.2/24"));
+/// assert_eq!(interfaces.listed("auto").collect::<Vec<_>>(), ["eth0"]);
+/// ```
+impl FromStr for InterfacesFile {
+    type Err = InterfacesParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut interfaces = InterfacesFile::default();
+        let mut lines = s.lines().enumerate();
+        while let Some((index, line)) = lines.next() {
+            let mut line = line.trim().to_string();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            while line.ends_with('\\') {
+                line.pop();
+                match lines.next() {
+                    Some((_, next)) => {
+                        line = format!("{} {}", line.trim_end(), next.trim());
+                    }
+                    None => break,
+                }
+            }
+
+            // A continuation line on its own leaves leading whitespace behind
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let mut words = line.split_whitespace();
+            let keyword = words.next().unwrap_or_default();
+            if KEYWORDS.contains(&keyword) || keyword.starts_with("allow-") {
+                interfaces
+                    .stanzas
+                    .push(InterfacesStanza::new(keyword, words));
+                continue;
+            }
+
+            let stanza = interfaces
+                .stanzas
+                .last_mut()
+                .filter(|stanza| matches!(stanza.keyword.as_str(), "iface" | "mapping"))
+                .ok_or_else(|| InterfacesParseError {
+                    line: index + 1,
+                    message: format!("option {keyword} outside of an iface stanza"),
+                })?;
+            let value = line
+                .split_once(char::is_whitespace)
+                .map_or("", |(_, value)| value.trim());
+            stanza.add(keyword, value);
+        }
+
+        Ok(interfaces)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::InterfacesFile;
+
+    #[test]
+    fn continuation_lines() {
+        let interfaces: InterfacesFile = "iface eth0 inet static\n\\\n  address 10.0.0.2/24\n"
+            .parse()
+            .unwrap();
+        let eth0 = interfaces.ifaces().next().unwrap();
+        assert_eq!(eth0.get("address"), Some("10.0.0.2/24"));
+
+        let interfaces: InterfacesFile = "iface eth0 inet static\n\\\né x\n".parse().unwrap();
+        let eth0 = interfaces.ifaces().next().unwrap();
+        assert_eq!(eth0.get("é"), Some("x"));
+
+        let interfaces: InterfacesFile = "iface eth0 inet static\n  mtu \\\n  9000\n"
+            .parse()
+            .unwrap();
+        let eth0 = interfaces.ifaces().next().unwrap();
+        assert_eq!(eth0.get("mtu"), Some("9000"));
+    }
+}
@@ -0,0 +1,292 @@
+//! Reading the live network state from the JSON output of iproute2's `ip` command, e.g.
+//! captured over SSH or in a container without netlink access.
+
+use crate::{AddressState, LinkState, RouteOrigin, RouteState, SystemState, MAIN_TABLE};
+use serde::de::Error;
+use serde::Deserialize;
+use std::net::IpAddr;
+
+/// A link as printed by `ip -json -details addr show`.
+#[derive(Deserialize)]
+struct IpLink {
+    ifindex: u32,
+    ifname: String,
+    #[serde(default)]
+    flags: Vec<String>,
+    mtu: Option<u32>,
+    link_type: Option<String>,
+    address: Option<String>,
+    master: Option<String>,
+    link: Option<String>,
+    linkinfo: Option<IpLinkInfo>,
+    #[serde(default)]
+    addr_info: Vec<IpAddress>,
+}
+
+#[derive(Deserialize)]
+struct IpLinkInfo {
+    info_kind: Option<String>,
+    info_data: Option<IpLinkData>,
+}
+
+#[derive(Deserialize)]
+struct IpLinkData {
+    /// The VLAN ID of a VLAN.
+    id: Option<u16>,
+    /// The table of a VRF.
+    table: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct IpAddress {
+    local: Option<IpAddr>,
+    prefixlen: Option<u8>,
+    #[serde(default)]
+    dynamic: bool,
+}
+
+/// A route as printed by `ip -json route show table all`.
+#[derive(Deserialize)]
+struct IpRoute {
+    r#type: Option<String>,
+    dst: String,
+    gateway: Option<IpAddr>,
+    dev: Option<String>,
+    protocol: Option<String>,
+    metric: Option<u32>,
+    table: Option<IpTable>,
+}
+
+/// A routing table, printed as its name if it has one, e.g. `main`, and otherwise as its
+/// number.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum IpTable {
+    Number(u32),
+    Name(String),
+}
+
+impl SystemState {
+    /// Build the state from the output of iproute2's `ip` command in JSON, as an alternative
+    /// to `SystemState::read` (`system` feature) which works on captured output and
+    /// without netlink access. `addr` is the output of `ip -json -details addr show`;
+    /// without `-details` the kinds of links, VLAN IDs and VRF tables are unknown. `routes`
+    /// are the outputs of `ip -json route show table all` and `ip -json -6 route show
+    /// table all`, one or both.
+    ///
+    /// Only unicast routes are kept, and routes through links which are not in `addr` have
+    /// no link.
+    ///
+    /// ```
+    /// use netplan_types::SystemState;
+    ///
+    /// let addr = r#"[{"ifindex": 2, "ifname": "eth0", "flags": ["BROADCAST", "UP"], "mtu": 1500,
+    ///     "link_type": "ether", "address": "52:54:00:6b:3c:58",
+    ///     "addr_info": [{"family": "inet", "local": "192.0.2.10", "prefixlen": 24}]}]"#;
+    /// let routes = r#"[{"dst": "default", "gateway": "192.0.2.1", "dev": "eth0", "protocol": "static"}]"#;
+    ///
+    /// let state = SystemState::from_ip_json(addr, &[routes]).unwrap();
+    /// let eth0 = state.link_by_name("eth0").unwrap();
+    /// assert_eq!(eth0.addresses[0].to_string(), "192.0.2.10/24");
+    /// assert_eq!(state.routes[0].link, Some(2));
+    /// ```
+    pub fn from_ip_json(addr: &str, routes: &[&str]) -> Result<Self, serde_json::Error> {
+        let ip_links = serde_json::from_str::<Vec<IpLink>>(addr)?;
+        let index = |name: &str| {
+            ip_links
+                .iter()
+                .find(|link| link.ifname == name)
+                .map(|link| link.ifindex)
+        };
+
+        let links = ip_links
+            .iter()
+            .map(|link| {
+                let info = link.linkinfo.as_ref();
+                let data = info.and_then(|info| info.info_data.as_ref());
+                LinkState {
+                    index: link.ifindex,
+                    name: link.ifname.clone(),
+                    kind: info.and_then(|info| info.info_kind.clone()),
+                    up: link.flags.iter().any(|flag| flag == "UP"),
+                    loopback: link.flags.iter().any(|flag| flag == "LOOPBACK"),
+                    mtu: link.mtu,
+                    macaddress: link
+                        .address
+                        .clone()
+                        .filter(|_| link.link_type.as_deref() == Some("ether")),
+                    controller: link.master.as_deref().and_then(index),
+                    // The name of a link in another namespace is not known, e.g. `if5`
+                    parent: link.link.as_deref().and_then(index),
+                    vlan_id: data.and_then(|data| data.id),
+                    vrf_table: data.and_then(|data| data.table),
+                    addresses: link
+                        .addr_info
+                        .iter()
+                        .filter_map(|address| {
+                            Some(AddressState {
+                                address: address.local?,
+                                prefix: address.prefixlen?,
+                                dynamic: address.dynamic,
+                            })
+                        })
+                        .collect(),
+                }
+            })
+            .collect();
+
+        let mut state = SystemState {
+            links,
+            routes: Vec::new(),
+        };
+        for routes in routes {
+            for route in serde_json::from_str::<Vec<IpRoute>>(routes)? {
+                if route.r#type.as_deref().is_some_and(|t| t != "unicast") {
+                    continue;
+                }
+
+                let (to, prefix) = destination(&route.dst)?;
+                state.routes.push(RouteState {
+                    to,
+                    prefix,
+                    via: route.gateway,
+                    link: route.dev.as_deref().and_then(index),
+                    metric: route.metric,
+                    table: table(route.table)?,
+                    origin: origin(route.protocol.as_deref()),
+                });
+            }
+        }
+
+        Ok(state)
+    }
+}
+
+/// The destination and prefix of a route, e.g. `default`, `10.0.0.0/8` or a single address.
+fn destination(dst: &str) -> Result<(Option<IpAddr>, u8), serde_json::Error> {
+    let invalid = || serde_json::Error::custom(format!("Invalid route destination '{dst}'"));
+    if dst == "default" {
+        return Ok((None, 0));
+    }
+
+    let (address, prefix) = match dst.split_once('/') {
+        Some((address, prefix)) => (address, Some(prefix)),
+        None => (dst, None),
+    };
+    let address = address.parse::<IpAddr>().map_err(|_| invalid())?;
+    let prefix = match prefix {
+        Some(prefix) => prefix.parse().map_err(|_| invalid())?,
+        None if address.is_ipv4() => 32,
+        None => 128,
+    };
+
+    Ok((Some(address), prefix))
+}
+
+fn table(table: Option<IpTable>) -> Result<u32, serde_json::Error> {
+    match table {
+        None => Ok(MAIN_TABLE),
+        Some(IpTable::Number(table)) => Ok(table),
+        Some(IpTable::Name(name)) => match name.as_str() {
+            "main" => Ok(MAIN_TABLE),
+            "default" => Ok(253),
+            "local" => Ok(255),
+            number => number
+                .parse()
+                .map_err(|_| serde_json::Error::custom(format!("Unknown routing table '{name}'"))),
+        },
+    }
+}
+
+fn origin(protocol: Option<&str>) -> RouteOrigin {
+    match protocol {
+        Some("kernel") => RouteOrigin::Kernel,
+        None | Some("boot" | "static") => RouteOrigin::Static,
+        Some("dhcp" | "ra" | "redirect") => RouteOrigin::Dynamic,
+        Some(_) => RouteOrigin::Other,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{RouteOrigin, SystemState, MAIN_TABLE};
+
+    #[test]
+    fn parse_ip_json() {
+        let addr = r#"[
+          {"ifindex": 1, "ifname": "lo", "flags": ["LOOPBACK", "UP", "LOWER_UP"], "mtu": 65536,
+           "link_type": "loopback", "address": "00:00:00:00:00:00",
+           "addr_info": [{"family": "inet", "local": "127.0.0.1", "prefixlen": 8, "scope": "host"}]},
+          {"ifindex": 2, "ifname": "eth0", "flags": ["BROADCAST", "MULTICAST", "SLAVE", "UP"],
+           "mtu": 9000, "master": "bond0", "link_type": "ether", "address": "52:54:00:6b:3c:58",
+           "linkinfo": {"info_slave_kind": "bond"}, "addr_info": []},
+          {"ifindex": 3, "ifname": "bond0", "flags": ["BROADCAST", "MASTER", "UP"], "mtu": 9000,
+           "link_type": "ether", "address": "52:54:00:6b:3c:58",
+           "linkinfo": {"info_kind": "bond", "info_data": {"mode": "802.3ad"}},
+           "addr_info": [
+             {"family": "inet", "local": "192.0.2.10", "prefixlen": 24, "dynamic": true,
+              "valid_life_time": 3600},
+             {"family": "inet6", "local": "fe80::5054:ff:fe6b:3c58", "prefixlen": 64}
+           ]},
+          {"ifindex": 4, "link": "bond0", "ifname": "bond0.10", "flags": ["BROADCAST"],
+           "mtu": 1500, "master": "vrf-blue", "link_type": "ether",
+           "linkinfo": {"info_kind": "vlan", "info_data": {"protocol": "802.1Q", "id": 10}}},
+          {"ifindex": 5, "ifname": "vrf-blue", "flags": ["NOARP", "MASTER", "UP"],
+           "link_type": "ether", "linkinfo": {"info_kind": "vrf", "info_data": {"table": 100}}}
+        ]"#;
+        let routes4 = r#"[
+          {"dst": "default", "gateway": "192.0.2.1", "dev": "bond0", "protocol": "dhcp",
+           "prefsrc": "192.0.2.10", "metric": 100, "flags": []},
+          {"dst": "192.0.2.0/24", "dev": "bond0", "protocol": "kernel", "scope": "link"},
+          {"dst": "198.51.100.0/24", "dev": "bond0.10", "table": "100", "flags": []},
+          {"type": "local", "dst": "127.0.0.1", "dev": "lo", "table": "local"},
+          {"type": "broadcast", "dst": "192.0.2.255", "dev": "bond0", "table": "local"}
+        ]"#;
+        let routes6 = r#"[
+          {"dst": "2001:db8::1", "dev": "bond0", "protocol": "static", "metric": 1024},
+          {"dst": "fe80::/64", "dev": "bond0", "protocol": "kernel", "metric": 256}
+        ]"#;
+
+        let state = SystemState::from_ip_json(addr, &[routes4, routes6]).unwrap();
+        assert_eq!(state.links.len(), 5);
+
+        let lo = state.link_by_name("lo").unwrap();
+        assert!(lo.loopback && lo.up);
+        assert_eq!(lo.macaddress, None);
+
+        let eth0 = state.link_by_name("eth0").unwrap();
+        assert_eq!(eth0.controller, Some(3));
+        assert_eq!(eth0.kind, None);
+        assert_eq!(eth0.macaddress.as_deref(), Some("52:54:00:6b:3c:58"));
+
+        let bond0 = state.link_by_name("bond0").unwrap();
+        assert_eq!(bond0.kind.as_deref(), Some("bond"));
+        assert_eq!(bond0.addresses[0].to_string(), "192.0.2.10/24");
+        assert!(bond0.addresses[0].dynamic);
+        assert!(bond0.addresses[1].is_link_local());
+
+        let vlan = state.link_by_name("bond0.10").unwrap();
+        assert_eq!((vlan.parent, vlan.vlan_id), (Some(3), Some(10)));
+        assert_eq!(vlan.controller, Some(5));
+        assert!(!vlan.up);
+        assert_eq!(state.link_by_name("vrf-blue").unwrap().vrf_table, Some(100));
+
+        let routes = &state.routes;
+        assert_eq!(routes.len(), 5);
+        assert_eq!(routes[0].destination(), "default");
+        assert_eq!(routes[0].via, Some("192.0.2.1".parse().unwrap()));
+        assert_eq!(
+            (routes[0].metric, routes[0].origin),
+            (Some(100), RouteOrigin::Dynamic)
+        );
+        assert_eq!(routes[1].origin, RouteOrigin::Kernel);
+        assert_eq!(
+            (routes[2].table, routes[2].link, routes[2].origin),
+            (100, Some(4), RouteOrigin::Static)
+        );
+        assert_eq!(routes[3].destination(), "2001:db8::1/128");
+        assert_eq!(routes[3].table, MAIN_TABLE);
+
+        assert!(SystemState::from_ip_json("[]", &[r#"[{"dst": "nowhere"}]"#]).is_err());
+    }
+}
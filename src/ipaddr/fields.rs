@@ -0,0 +1,241 @@
+//! Typed access to the fields which hold IP addresses and networks.
+//!
+//! The fields themselves are strings, written exactly as they appear in the netplan
+//! configuration, so a configuration is always read and written as is. The accessors in this
+//! module parse them into [IpAddr] and [IpNet], and the setters write them back in the form
+//! netplan uses.
+
+use crate::ipaddr::{parse_address, IpParseError};
+use crate::{
+    CommonPropertiesAllDevices, NameserverConfig, RoutingConfig, RoutingPolicy, TunnelConfig,
+};
+use ipnet::{IpNet, Ipv4Net, Ipv6Net};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::str::FromStr;
+
+fn parse<T: FromStr>(value: &Option<String>) -> Result<Option<T>, IpParseError> {
+    value
+        .as_deref()
+        .map(|value| value.parse().map_err(|_| IpParseError::new(value)))
+        .transpose()
+}
+
+/// Parse a network, which netplan also accepts as a single address.
+fn parse_net(value: &Option<String>) -> Result<Option<IpNet>, IpParseError> {
+    value
+        .as_deref()
+        .map(|value| {
+            value
+                .parse::<IpAddr>()
+                .map(IpNet::from)
+                .or_else(|_| parse_address(value))
+        })
+        .transpose()
+}
+
+impl CommonPropertiesAllDevices {
+    /// The parsed `gateway4`.
+    pub fn gateway4_addr(&self) -> Result<Option<Ipv4Addr>, IpParseError> {
+        parse(&self.gateway4)
+    }
+
+    /// The parsed `gateway6`.
+    pub fn gateway6_addr(&self) -> Result<Option<Ipv6Addr>, IpParseError> {
+        parse(&self.gateway6)
+    }
+
+    /// Set `gateway4`. Prefer [CommonPropertiesAllDevices::set_default_gateway], as
+    /// `gateway4` is deprecated.
+    pub fn set_gateway4_addr(&mut self, gateway: Option<Ipv4Addr>) {
+        self.gateway4 = gateway.map(|gateway| gateway.to_string());
+    }
+
+    /// Set `gateway6`. Prefer [CommonPropertiesAllDevices::set_default_gateway], as
+    /// `gateway6` is deprecated.
+    pub fn set_gateway6_addr(&mut self, gateway: Option<Ipv6Addr>) {
+        self.gateway6 = gateway.map(|gateway| gateway.to_string());
+    }
+}
+
+impl NameserverConfig {
+    /// The parsed nameserver addresses.
+    pub fn addresses_parsed(&self) -> Result<Vec<IpAddr>, IpParseError> {
+        self.addresses
+            .iter()
+            .flatten()
+            .map(|address| address.parse().map_err(|_| IpParseError::new(address)))
+            .collect()
+    }
+
+    /// Replace the nameserver addresses.
+    pub fn set_addresses(&mut self, addresses: impl IntoIterator<Item = IpAddr>) {
+        let addresses = addresses
+            .into_iter()
+            .map(|address| address.to_string())
+            .collect::<Vec<_>>();
+        self.addresses = (!addresses.is_empty()).then_some(addresses);
+    }
+}
+
+impl RoutingConfig {
+    /// The parsed source address of the route.
+    pub fn from_net(&self) -> Result<Option<IpNet>, IpParseError> {
+        parse_net(&self.from)
+    }
+
+    /// The parsed destination of the route. `default` is resolved to `0.0.0.0/0` or `::/0`,
+    /// depending on the address family of the gateway, and a single address to a network of
+    /// just that address.
+    ///
+    /// ```
+    /// use netplan_types::RoutingConfig;
+    ///
+    /// let route = RoutingConfig {
+    ///     to: Some("default".to_string()),
+    ///     via: Some("fd00::1".to_string()),
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(route.to_net().unwrap(), Some("::/0".parse().unwrap()));
+    /// ```
+    pub fn to_net(&self) -> Result<Option<IpNet>, IpParseError> {
+        if self.to.as_deref() != Some("default") {
+            return parse_net(&self.to);
+        }
+
+        Ok(Some(match self.via_addr()? {
+            Some(IpAddr::V6(_)) => IpNet::V6(Ipv6Net::default()),
+            _ => IpNet::V4(Ipv4Net::default()),
+        }))
+    }
+
+    /// The parsed gateway of the route.
+    pub fn via_addr(&self) -> Result<Option<IpAddr>, IpParseError> {
+        parse(&self.via)
+    }
+
+    /// Set the source address of the route.
+    pub fn set_from_net(&mut self, from: Option<IpNet>) {
+        self.from = from.map(|from| from.to_string());
+    }
+
+    /// Set the destination of the route.
+    pub fn set_to_net(&mut self, to: Option<IpNet>) {
+        self.to = to.map(|to| to.to_string());
+    }
+
+    /// Set the gateway of the route.
+    pub fn set_via_addr(&mut self, via: Option<IpAddr>) {
+        self.via = via.map(|via| via.to_string());
+    }
+}
+
+impl RoutingPolicy {
+    /// The parsed source network of the rule.
+    pub fn from_net(&self) -> Result<Option<IpNet>, IpParseError> {
+        parse_net(&self.from)
+    }
+
+    /// The parsed destination network of the rule.
+    pub fn to_net(&self) -> Result<Option<IpNet>, IpParseError> {
+        parse_net(&self.to)
+    }
+
+    /// Set the source network of the rule.
+    pub fn set_from_net(&mut self, from: Option<IpNet>) {
+        self.from = from.map(|from| from.to_string());
+    }
+
+    /// Set the destination network of the rule.
+    pub fn set_to_net(&mut self, to: Option<IpNet>) {
+        self.to = to.map(|to| to.to_string());
+    }
+}
+
+impl TunnelConfig {
+    /// The parsed local address of the tunnel.
+    pub fn local_addr(&self) -> Result<Option<IpAddr>, IpParseError> {
+        parse(&self.local)
+    }
+
+    /// The parsed remote address of the tunnel.
+    pub fn remote_addr(&self) -> Result<Option<IpAddr>, IpParseError> {
+        parse(&self.remote)
+    }
+
+    /// Set the local address of the tunnel.
+    pub fn set_local_addr(&mut self, local: Option<IpAddr>) {
+        self.local = local.map(|local| local.to_string());
+    }
+
+    /// Set the remote address of the tunnel.
+    pub fn set_remote_addr(&mut self, remote: Option<IpAddr>) {
+        self.remote = remote.map(|remote| remote.to_string());
+    }
+}
+
+#[cfg(all(test, feature = "yaml"))]
+mod test {
+    use crate::{IpParseError, NetplanConfig};
+
+    #[test]
+    fn typed_fields() {
+        let input = r#"
+            network:
+              version: 2
+              ethernets:
+                eth0:
+                  gateway4: 10.0.0.1
+                  nameservers:
+                    addresses: [1.1.1.1, "2606:4700::1111"]
+                  routes:
+                    - to: default
+                      via: 10.0.0.1
+                    - to: 192.168.1.5
+                      from: 10.0.0.2
+              tunnels:
+                gre0:
+                  mode: gre
+                  remote: not-an-address
+            "#;
+
+        let config: NetplanConfig = serde_yaml::from_str(input).unwrap();
+        let common = config.network.ethernets.as_ref().unwrap()["eth0"]
+            .common_all
+            .clone()
+            .unwrap();
+        assert_eq!(
+            common.gateway4_addr().unwrap(),
+            Some("10.0.0.1".parse().unwrap())
+        );
+        assert_eq!(common.gateway6_addr().unwrap(), None);
+        assert_eq!(
+            common.nameservers.unwrap().addresses_parsed().unwrap(),
+            vec![
+                "1.1.1.1".parse::<std::net::IpAddr>().unwrap(),
+                "2606:4700::1111".parse().unwrap()
+            ]
+        );
+
+        let routes = common.routes.unwrap();
+        assert_eq!(
+            routes[0].to_net().unwrap(),
+            Some("0.0.0.0/0".parse().unwrap())
+        );
+        assert_eq!(
+            routes[1].to_net().unwrap(),
+            Some("192.168.1.5/32".parse().unwrap())
+        );
+        assert_eq!(
+            routes[1].from_net().unwrap(),
+            Some("10.0.0.2/32".parse().unwrap())
+        );
+
+        let gre0 = &config.network.tunnels.as_ref().unwrap()["gre0"];
+        assert_eq!(
+            gre0.remote_addr(),
+            Err(IpParseError {
+                value: "not-an-address".to_string()
+            })
+        );
+    }
+}
@@ -0,0 +1,99 @@
+//! Parsing the fields which hold IP addresses and networks.
+
+#[cfg(feature = "ipaddr")]
+mod fields;
+
+use crate::{AddressMapping, AddressProperties, NetworkConfig};
+use ipnet::IpNet;
+use std::fmt::{Display, Formatter};
+
+/// The error returned when a field does not hold a valid IP address or network.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IpParseError {
+    /// The value which could not be parsed.
+    pub value: String,
+}
+
+impl IpParseError {
+    pub(crate) fn new(value: &str) -> Self {
+        Self {
+            value: value.to_string(),
+        }
+    }
+}
+
+impl Display for IpParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "'{}' is not a valid IP address or network", self.value)
+    }
+}
+
+impl std::error::Error for IpParseError {}
+
+/// Parse a static address of a device, which netplan requires in CIDR notation.
+pub(crate) fn parse_address(value: &str) -> Result<IpNet, IpParseError> {
+    value.parse().map_err(|_| IpParseError::new(value))
+}
+
+impl NetworkConfig {
+    /// The static addresses of every device, of any kind, with their properties, sorted by
+    /// the name of the device. The addresses of a device are in the order they are
+    /// configured, and those with properties sorted, as they are a mapping. Addresses which
+    /// are not in CIDR notation are returned with the error, so the valid ones can be used.
+    ///
+    /// ```
+    /// use netplan_types::NetplanConfig;
+    ///
+    /// let config = NetplanConfig::from_yaml(r#"
+    /// network:
+    ///   version: 2
+    ///   ethernets:
+    ///     eth0: {addresses: [192.0.2.10/24, 192.0.2.11]}
+    ///   bridges:
+    ///     br0:
+    ///       addresses:
+    ///         - "2001:db8::1/64"
+    ///         - 198.51.100.1/24: {label: br0:mgmt}
+    /// "#).unwrap();
+    ///
+    /// let addresses = config.network.all_addresses();
+    /// let (device, net, properties) = &addresses[1];
+    /// assert_eq!((*device, net.as_ref().unwrap().to_string()), ("br0", "198.51.100.1/24".to_string()));
+    /// assert_eq!(properties.label.as_deref(), Some("br0:mgmt"));
+    ///
+    /// let (device, net, _) = &addresses[3];
+    /// assert_eq!((*device, net.as_ref().unwrap_err().value.as_str()), ("eth0", "192.0.2.11"));
+    /// assert_eq!(addresses.len(), 4);
+    /// ```
+    #[allow(clippy::type_complexity)]
+    pub fn all_addresses(&self) -> Vec<(&str, Result<IpNet, IpParseError>, AddressProperties)> {
+        let mut devices = self
+            .devices()
+            .filter_map(|(_, name, device)| Some((name, device.common_all()?.addresses.as_ref()?)))
+            .collect::<Vec<_>>();
+        devices.sort_by_key(|(name, _)| *name);
+
+        let mut all = Vec::new();
+        for (name, addresses) in devices {
+            for address in addresses {
+                match address {
+                    AddressMapping::Simple(address) => {
+                        all.push((name, parse_address(address), AddressProperties::default()));
+                    }
+                    AddressMapping::Complex(addresses) => {
+                        let mut addresses = addresses
+                            .iter()
+                            .map(|(address, properties)| {
+                                (name, parse_address(address), properties.clone())
+                            })
+                            .collect::<Vec<_>>();
+                        addresses.sort_by_key(|(_, net, _)| net.clone().map_err(|e| e.value));
+                        all.extend(addresses);
+                    }
+                }
+            }
+        }
+
+        all
+    }
+}
@@ -0,0 +1,682 @@
+//! Planning the iproute2 commands which realize a configuration.
+
+use crate::drift::{configured_route, find_link, installed_route};
+use crate::ifupdown::{route_command, rule_command, time_span};
+use crate::{
+    ActivationMode, AnyDeviceConfig, BondParameters, BridgeParameters, DeviceKind, LinkState,
+    NetplanConfig, RouteType, SystemState, TunnelConfig, TunnelKey, TunnelMode,
+};
+use ipnet::IpNet;
+use std::collections::HashSet;
+
+/// Plan the `ip link`, `ip addr`, `ip route`, `ip rule` and `wg` commands which realize a
+/// configuration, in the order they have to run, e.g. to preview what applying it does,
+/// or to apply it in a minimal container without netplan or a network daemon.
+///
+/// Virtual devices are created first, bonds, bridges and VRFs before their members are
+/// added to them, and VLANs after their link. Then every link gets its MAC address and
+/// MTU, is brought up, unless its activation mode is `manual`, or down if it is `off`,
+/// and gets its addresses and routes. Routing policy and the peers of WireGuard tunnels
+/// come last. Keys of WireGuard tunnels which are not paths are passed with process
+/// substitution, so the commands contain them.
+///
+/// Without `state`, every command is planned. With it, the plan only contains what
+/// differs: links which exist are not created, and their MAC address, MTU, members and
+/// state are only set if they differ. Static addresses and routes which are not
+/// configured are removed, and renamed devices are renamed from the link they match.
+/// The state has no routing policy, so rules are always added.
+///
+/// Settings iproute2 cannot realize, like DHCP, wifi networks or modems, are left out.
+///
+/// ```
+/// use netplan_types::{plan_iproute2, NetplanConfig};
+///
+/// let config = NetplanConfig::build(|net| {
+///     net.ethernet("eno1", |e| e);
+///     net.bond("bond0", |b| {
+///         b.interfaces(["eno1"])
+///             .address("10.0.0.2/24")
+///             .route("default", "10.0.0.1")
+///     });
+/// });
+///
+/// assert_eq!(
+///     plan_iproute2(&config, None),
+///     [
+///         "ip link add bond0 type bond",
+///         "ip link set dev eno1 down",
+///         "ip link set dev eno1 master bond0",
+///         "ip link set dev eno1 up",
+///         "ip link set dev bond0 up",
+///         "ip addr add 10.0.0.2/24 dev bond0",
+///         "ip route add default via 10.0.0.1 dev bond0",
+///     ]
+/// );
+/// ```
+pub fn plan_iproute2(config: &NetplanConfig, state: Option<&SystemState>) -> Vec<String> {
+    // Links are created after the links they use
+    let mut devices = config.network.devices().collect::<Vec<_>>();
+    devices.sort_by_key(|(kind, name, _)| {
+        let rank = match kind {
+            DeviceKind::Bond => 1,
+            DeviceKind::Vlan => 2,
+            DeviceKind::Bridge => 3,
+            DeviceKind::Vrf => 4,
+            _ => 0,
+        };
+        (rank, *name)
+    });
+    let link = |name: &str, device: AnyDeviceConfig<'_>| {
+        state.and_then(|state| current_link(name, device, state))
+    };
+    // Links are renamed first, so devices with a name set are known by that name
+    let name_of = |name: &str, device: AnyDeviceConfig<'_>| {
+        let renamed = device
+            .common_physical()
+            .is_some_and(|p| p.set_name.is_some());
+        match link(name, device) {
+            Some(link) if !renamed => link.name.clone(),
+            _ => interface_name(name, device),
+        }
+    };
+    let member_name = |member: &str| match config.network.device(member) {
+        Some(device) => name_of(member, device),
+        None => member.to_string(),
+    };
+
+    let mut plan = Vec::new();
+    // The links taken down by the plan, which are brought up again
+    let mut down = HashSet::new();
+    for (_, name, device) in &devices {
+        let interface = interface_name(name, *device);
+        if let Some(link) = link(name, *device) {
+            if link.name != interface {
+                plan.push(format!("ip link set dev {} down", link.name));
+                plan.push(format!("ip link set dev {} name {interface}", link.name));
+                down.insert(interface);
+            }
+        } else if let Some(command) = create(&interface, *device, &member_name) {
+            plan.push(command);
+        }
+    }
+
+    for (_, name, device) in &devices {
+        let interface = name_of(name, *device);
+        let link = link(name, *device);
+        let Some(common) = device.common_all() else {
+            continue;
+        };
+        if let Some(macaddress) = &common.macaddress {
+            let macaddress = macaddress.to_string().to_lowercase();
+            if link.is_none_or(|link| link.macaddress.as_deref() != Some(macaddress.as_str())) {
+                plan.push(format!("ip link set dev {interface} address {macaddress}"));
+            }
+        }
+        if let Some(mtu) = common.mtu {
+            if link.is_none_or(|link| link.mtu != Some(mtu.into())) {
+                plan.push(format!("ip link set dev {interface} mtu {mtu}"));
+            }
+        }
+    }
+
+    for (_, name, device) in &devices {
+        let interface = name_of(name, *device);
+        let members = match device {
+            AnyDeviceConfig::Bond(bond) => bond.interfaces.as_deref().unwrap_or_default(),
+            AnyDeviceConfig::Bridge(bridge) => bridge.interfaces.as_deref().unwrap_or_default(),
+            AnyDeviceConfig::Vrf(vrf) => &vrf.interfaces,
+            _ => continue,
+        };
+        let members = members
+            .iter()
+            .map(|member| member_name(member))
+            .collect::<Vec<_>>();
+        let actual = match (state, link(name, *device)) {
+            (Some(state), Some(link)) => state.members(link.index),
+            _ => Vec::new(),
+        };
+        for member in members.iter().filter(|m| !actual.contains(&m.as_str())) {
+            // Links have to be down to be added to a bond
+            if matches!(device, AnyDeviceConfig::Bond(_)) && down.insert(member.clone()) {
+                plan.push(format!("ip link set dev {member} down"));
+            }
+            plan.push(format!("ip link set dev {member} master {interface}"));
+        }
+        for member in actual.iter().filter(|m| !members.iter().any(|n| n == *m)) {
+            plan.push(format!("ip link set dev {member} nomaster"));
+        }
+
+        if let AnyDeviceConfig::Bond(bond) = device {
+            let primary = bond.parameters.as_ref().and_then(|p| p.primary.as_deref());
+            if let Some(primary) = primary {
+                let primary = member_name(primary);
+                plan.push(format!(
+                    "ip link set dev {interface} type bond primary {primary}"
+                ));
+            }
+        }
+        if let AnyDeviceConfig::Bridge(bridge) = device {
+            let parameters = bridge.parameters.as_deref();
+            let mut options = String::new();
+            if let Some(cost) = parameters.and_then(|p| p.path_cost) {
+                options.push_str(&format!(" cost {cost}"));
+            }
+            if let Some(priority) = parameters.and_then(|p| p.port_priority) {
+                options.push_str(&format!(" priority {priority}"));
+            }
+            if !options.is_empty() {
+                for member in &members {
+                    plan.push(format!(
+                        "ip link set dev {member} type bridge_slave{options}"
+                    ));
+                }
+            }
+        }
+    }
+
+    for (_, name, device) in &devices {
+        let interface = name_of(name, *device);
+        let link = link(name, *device);
+        let common = device.common_all();
+        match common.and_then(|c| c.activation_mode.as_ref()) {
+            Some(ActivationMode::Manual) => {}
+            Some(ActivationMode::Off) => {
+                if link.is_none_or(|link| link.up) {
+                    plan.push(format!("ip link set dev {interface} down"));
+                }
+            }
+            None => {
+                if link.is_none_or(|link| !link.up) || down.contains(&interface) {
+                    plan.push(format!("ip link set dev {interface} up"));
+                }
+            }
+        }
+        let Some(common) = common else {
+            continue;
+        };
+
+        let addresses = common
+            .addresses
+            .iter()
+            .flatten()
+            .flat_map(|mapping| mapping.addresses())
+            .collect::<Vec<_>>();
+        let assigned = link.map(assigned).unwrap_or_default();
+        for address in &addresses {
+            let parsed = address.parse::<IpNet>().ok();
+            if parsed.is_none() || !assigned.contains(&parsed.unwrap()) {
+                plan.push(format!("ip addr add {address} dev {interface}"));
+            }
+        }
+        for address in &assigned {
+            let configured = addresses.iter().any(|a| a.parse().ok() == Some(*address));
+            if !configured {
+                plan.push(format!("ip addr del {address} dev {interface}"));
+            }
+        }
+
+        let installed = match (state, link) {
+            (Some(state), Some(link)) => state
+                .static_routes(link.index)
+                .map(installed_route)
+                .collect(),
+            _ => Vec::new(),
+        };
+        let mut configured = Vec::new();
+        for route in common.routes.iter().flatten() {
+            let normalized = matches!(route.r#type, None | Some(RouteType::Unicast))
+                .then(|| configured_route(route))
+                .flatten();
+            if normalized.as_ref().is_none_or(|r| !installed.contains(r)) {
+                plan.push(format!("{} dev {interface}", route_command(route)));
+            }
+            configured.extend(normalized);
+        }
+        for route in installed.iter().filter(|r| !configured.contains(r)) {
+            plan.push(format!("ip route del {route} dev {interface}"));
+        }
+    }
+
+    for (_, _, device) in &devices {
+        let policy = device.common_all().and_then(|c| c.routing_policy.as_ref());
+        for policy in policy.into_iter().flatten() {
+            plan.push(rule_command(policy));
+        }
+    }
+    for (_, name, device) in &devices {
+        if let AnyDeviceConfig::Tunnel(tunnel) = device {
+            plan.extend(wireguard(&name_of(name, *device), tunnel));
+        }
+    }
+
+    plan
+}
+
+impl NetplanConfig {
+    /// Plan the iproute2 commands which realize the configuration, see [plan_iproute2].
+    pub fn plan_iproute2(&self, state: Option<&SystemState>) -> Vec<String> {
+        plan_iproute2(self, state)
+    }
+}
+
+/// The name of the interface of a device, as far as it is known without the state: the
+/// name it is set to, the name it is matched by if that has no wildcards, or its ID.
+fn interface_name(name: &str, device: AnyDeviceConfig<'_>) -> String {
+    let physical = device.common_physical();
+    if let Some(set_name) = physical.and_then(|p| p.set_name.as_deref()) {
+        return set_name.to_string();
+    }
+    match physical
+        .and_then(|p| p.r#match.as_ref())
+        .and_then(|m| m.name.as_deref())
+    {
+        Some(pattern) if !crate::glob::is_glob(pattern) => pattern.to_string(),
+        _ => name.to_string(),
+    }
+}
+
+/// The link of a device, also if it is still to be renamed to the name it is set to.
+fn current_link<'a>(
+    name: &str,
+    device: AnyDeviceConfig<'_>,
+    state: &'a SystemState,
+) -> Option<&'a LinkState> {
+    find_link(name, device, state).or_else(|| {
+        let matching = device.common_physical()?.r#match.as_ref()?;
+        let macaddress = matching.macaddress.as_ref()?.to_string().to_lowercase();
+        state
+            .links
+            .iter()
+            .find(|link| link.macaddress.as_deref() == Some(macaddress.as_str()))
+    })
+}
+
+/// The static addresses assigned to a link, without link-local addresses.
+fn assigned(link: &LinkState) -> Vec<IpNet> {
+    link.addresses
+        .iter()
+        .filter(|address| !address.dynamic && !address.is_link_local())
+        .filter_map(|address| IpNet::new(address.address, address.prefix).ok())
+        .collect()
+}
+
+/// The `ip link add` command creating a virtual device, `None` for physical devices.
+fn create(
+    interface: &str,
+    device: AnyDeviceConfig<'_>,
+    member_name: &dyn Fn(&str) -> String,
+) -> Option<String> {
+    let kind = match device {
+        AnyDeviceConfig::Bond(bond) => {
+            format!("bond{}", bond_options(bond.parameters.as_deref()))
+        }
+        AnyDeviceConfig::Bridge(bridge) => {
+            format!("bridge{}", bridge_options(bridge.parameters.as_deref()))
+        }
+        AnyDeviceConfig::Vlan(vlan) => {
+            let link = member_name(vlan.link.as_deref()?);
+            return Some(format!(
+                "ip link add link {link} name {interface} type vlan id {}",
+                vlan.id?
+            ));
+        }
+        AnyDeviceConfig::Vrf(vrf) => format!("vrf table {}", vrf.table),
+        AnyDeviceConfig::DummyDevice(_) => "dummy".to_string(),
+        AnyDeviceConfig::Tunnel(tunnel) => tunnel_options(tunnel)?,
+        _ => return None,
+    };
+    Some(format!("ip link add {interface} type {kind}"))
+}
+
+/// The options of `ip link add type bond`. The primary member is set once it is a member.
+fn bond_options(parameters: Option<&BondParameters>) -> String {
+    let Some(parameters) = parameters else {
+        return String::new();
+    };
+    let milliseconds = |value: &Option<String>| time_span(value.as_deref()?, true);
+    let mut options = String::new();
+    for (key, value) in [
+        (
+            "mode",
+            parameters.mode.as_ref().map(|m| m.as_str().to_string()),
+        ),
+        ("miimon", milliseconds(&parameters.mii_monitor_interval)),
+        (
+            "lacp_rate",
+            parameters
+                .lacp_rate
+                .as_ref()
+                .map(|r| r.as_str().to_string()),
+        ),
+        (
+            "xmit_hash_policy",
+            parameters
+                .transmit_hash_policy
+                .as_ref()
+                .map(|p| p.as_str().to_string()),
+        ),
+        (
+            "ad_select",
+            parameters
+                .ad_select
+                .as_ref()
+                .map(|s| s.as_str().to_string()),
+        ),
+        ("min_links", parameters.min_links.map(|m| m.to_string())),
+        (
+            "all_slaves_active",
+            parameters.all_slaves_active.map(|a| (a as u8).to_string()),
+        ),
+        ("updelay", milliseconds(&parameters.up_delay)),
+        ("downdelay", milliseconds(&parameters.down_delay)),
+        ("arp_interval", milliseconds(&parameters.arp_interval)),
+        (
+            "arp_ip_target",
+            parameters.arp_ip_targets.as_ref().map(|t| t.join(",")),
+        ),
+        (
+            "arp_validate",
+            parameters
+                .arp_validate
+                .as_ref()
+                .map(|v| v.as_str().to_string()),
+        ),
+        (
+            "arp_all_targets",
+            parameters
+                .arp_all_targets
+                .as_ref()
+                .map(|t| t.as_str().to_string()),
+        ),
+        (
+            "fail_over_mac",
+            parameters
+                .fail_over_mac_policy
+                .as_ref()
+                .map(|p| p.as_str().to_string()),
+        ),
+        (
+            "num_grat_arp",
+            parameters.gratuitous_arp.map(|g| g.to_string()),
+        ),
+        (
+            "packets_per_slave",
+            parameters.packets_per_slave.map(|p| p.to_string()),
+        ),
+        (
+            "primary_reselect",
+            parameters
+                .primary_reselect_policy
+                .as_ref()
+                .map(|p| p.as_str().to_string()),
+        ),
+        ("resend_igmp", parameters.resend_igmp.map(|r| r.to_string())),
+        (
+            "lp_interval",
+            parameters
+                .learn_packet_interval
+                .as_deref()
+                .and_then(|value| time_span(value, false)),
+        ),
+    ] {
+        if let Some(value) = value {
+            options.push_str(&format!(" {key} {value}"));
+        }
+    }
+    options
+}
+
+/// The options of `ip link add type bridge`, which takes time spans in centiseconds.
+fn bridge_options(parameters: Option<&BridgeParameters>) -> String {
+    let Some(parameters) = parameters else {
+        return String::new();
+    };
+    let centiseconds = |value: &Option<String>| {
+        let milliseconds = time_span(value.as_deref()?, true)?.parse::<u64>().ok()?;
+        Some((milliseconds / 10).to_string())
+    };
+    let mut options = String::new();
+    for (key, value) in [
+        (
+            "stp_state",
+            parameters.stp.map(|stp| (stp as u8).to_string()),
+        ),
+        ("forward_delay", centiseconds(&parameters.forward_delay)),
+        ("hello_time", centiseconds(&parameters.hello_time)),
+        ("max_age", centiseconds(&parameters.max_age)),
+        ("ageing_time", centiseconds(&parameters.ageing_time)),
+        ("priority", parameters.priority.map(|p| p.to_string())),
+    ] {
+        if let Some(value) = value {
+            options.push_str(&format!(" {key} {value}"));
+        }
+    }
+    options
+}
+
+/// The type and options of `ip link add` for a tunnel.
+fn tunnel_options(tunnel: &TunnelConfig) -> Option<String> {
+    let kind = match tunnel.mode.as_ref()? {
+        TunnelMode::Wireguard => return Some("wireguard".to_string()),
+        TunnelMode::Vxlan => format!("vxlan id {}", tunnel.id?),
+        TunnelMode::Ipip6 => "ip6tnl mode ipip6".to_string(),
+        TunnelMode::Ip6ip6 => "ip6tnl mode ip6ip6".to_string(),
+        TunnelMode::Isatap => "sit isatap".to_string(),
+        mode => mode.as_str().to_string(),
+    };
+    let mut options = kind;
+    for (key, value) in [
+        ("local", tunnel.local.clone()),
+        ("remote", tunnel.remote.clone()),
+        ("ttl", tunnel.ttl.map(|ttl| ttl.to_string())),
+        ("dstport", tunnel.port.clone()),
+    ] {
+        if let Some(value) = value {
+            options.push_str(&format!(" {key} {value}"));
+        }
+    }
+    match &tunnel.key {
+        Some(TunnelKey::Simple(key)) => options.push_str(&format!(" key {}", key.as_str())),
+        Some(TunnelKey::Complex { input, output, .. }) => {
+            if let Some(input) = input {
+                options.push_str(&format!(" ikey {input}"));
+            }
+            if let Some(output) = output {
+                options.push_str(&format!(" okey {output}"));
+            }
+        }
+        None => {}
+    }
+    Some(options)
+}
+
+/// A key for `wg set`, which reads keys from files.
+fn key_file(key: &str) -> String {
+    if key.starts_with('/') {
+        key.to_string()
+    } else {
+        format!("<(echo {key})")
+    }
+}
+
+/// The `wg set` command configuring the keys, port, firewall mark and peers of a WireGuard
+/// tunnel.
+fn wireguard(interface: &str, tunnel: &TunnelConfig) -> Option<String> {
+    if tunnel.mode != Some(TunnelMode::Wireguard) {
+        return None;
+    }
+    let mut command = format!("wg set {interface}");
+    let private = match &tunnel.key {
+        Some(TunnelKey::Simple(key)) => Some(key),
+        Some(TunnelKey::Complex { private, .. }) => private.as_ref(),
+        None => None,
+    };
+    if let Some(private) = private {
+        command.push_str(&format!(" private-key {}", key_file(private.as_str())));
+    }
+    if let Some(port) = tunnel.port.as_deref().filter(|port| *port != "auto") {
+        command.push_str(&format!(" listen-port {port}"));
+    }
+    if let Some(mark) = &tunnel.mark {
+        command.push_str(&format!(" fwmark {mark}"));
+    }
+    for peer in &tunnel.peers {
+        let keys = peer.keys.as_ref();
+        let Some(public) = keys.and_then(|k| k.public.as_deref()) else {
+            continue;
+        };
+        command.push_str(&format!(" peer {public}"));
+        if let Some(shared) = keys.and_then(|k| k.shared.as_ref()) {
+            command.push_str(&format!(" preshared-key {}", key_file(shared.as_str())));
+        }
+        if let Some(endpoint) = &peer.endpoint {
+            command.push_str(&format!(" endpoint {endpoint}"));
+        }
+        if let Some(allowed_ips) = &peer.allowed_ips {
+            command.push_str(&format!(" allowed-ips {}", allowed_ips.join(",")));
+        }
+        if let Some(keepalive) = peer.keepalive {
+            command.push_str(&format!(" persistent-keepalive {keepalive}"));
+        }
+    }
+    Some(command)
+}
+
+#[cfg(all(test, feature = "yaml"))]
+mod test {
+    use crate::{AddressState, LinkState, NetplanConfig, RouteOrigin, RouteState, SystemState};
+
+    #[test]
+    fn plan_commands() {
+        let config = NetplanConfig::from_yaml(
+            r#"
+network:
+  version: 2
+  ethernets:
+    nic0:
+      match: {macaddress: "52:54:00:aa:bb:01"}
+      set-name: eth0
+    eth1: {}
+  bonds:
+    bond0:
+      interfaces: [nic0, eth1]
+      parameters:
+        mode: active-backup
+        mii-monitor-interval: 1s
+        primary: nic0
+      mtu: 9000
+      addresses: [10.0.0.2/24]
+      routes:
+        - to: default
+          via: 10.0.0.1
+        - to: 10.1.0.0/16
+          via: 10.0.0.254
+      routing-policy:
+        - from: 10.0.0.0/24
+          table: 100
+  vlans:
+    bond0.20:
+      id: 20
+      link: bond0
+      activation-mode: manual
+  bridges:
+    br0:
+      interfaces: [bond0.20]
+      parameters: {stp: false, forward-delay: 2s}
+  tunnels:
+    wg0:
+      mode: wireguard
+      key: /etc/wireguard/wg0.key
+      port: "51820"
+      addresses: ["fd00:10::1/64"]
+      peers:
+        - keys: {public: M9nt4YujIOmNrRmpIRTmYSfMdrpvE7u6WkG8FY8WjG4=}
+          allowed-ips: ["fd00:10::/64"]
+          endpoint: 192.0.2.10:51820
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.plan_iproute2(None),
+            [
+                "ip link add wg0 type wireguard",
+                "ip link add bond0 type bond mode active-backup miimon 1000",
+                "ip link add link bond0 name bond0.20 type vlan id 20",
+                "ip link add br0 type bridge stp_state 0 forward_delay 200",
+                "ip link set dev bond0 mtu 9000",
+                "ip link set dev eth0 down",
+                "ip link set dev eth0 master bond0",
+                "ip link set dev eth1 down",
+                "ip link set dev eth1 master bond0",
+                "ip link set dev bond0 type bond primary eth0",
+                "ip link set dev bond0.20 master br0",
+                "ip link set dev eth1 up",
+                "ip link set dev eth0 up",
+                "ip link set dev wg0 up",
+                "ip addr add fd00:10::1/64 dev wg0",
+                "ip link set dev bond0 up",
+                "ip addr add 10.0.0.2/24 dev bond0",
+                "ip route add default via 10.0.0.1 dev bond0",
+                "ip route add 10.1.0.0/16 via 10.0.0.254 dev bond0",
+                "ip link set dev br0 up",
+                "ip rule add from 10.0.0.0/24 table 100",
+                "wg set wg0 private-key /etc/wireguard/wg0.key listen-port 51820 peer M9nt4YujIOmNrRmpIRTmYSfMdrpvE7u6WkG8FY8WjG4= endpoint 192.0.2.10:51820 allowed-ips fd00:10::/64",
+            ]
+        );
+
+        let link = |index: u32, name: &str| LinkState {
+            index,
+            name: name.to_string(),
+            up: true,
+            ..Default::default()
+        };
+        let state = SystemState {
+            links: vec![
+                LinkState {
+                    macaddress: Some("52:54:00:aa:bb:01".to_string()),
+                    ..link(2, "ens3")
+                },
+                LinkState {
+                    controller: Some(4),
+                    ..link(3, "eth1")
+                },
+                LinkState {
+                    mtu: Some(9000),
+                    addresses: vec![AddressState {
+                        address: "10.0.0.3".parse().unwrap(),
+                        prefix: 24,
+                        dynamic: false,
+                    }],
+                    ..link(4, "bond0")
+                },
+            ],
+            routes: vec![RouteState {
+                to: Some("0.0.0.0".parse().unwrap()),
+                prefix: 0,
+                via: Some("10.0.0.1".parse().unwrap()),
+                link: Some(4),
+                metric: None,
+                table: 254,
+                origin: RouteOrigin::Static,
+            }],
+        };
+        let plan = config.plan_iproute2(Some(&state));
+        assert_eq!(
+            &plan[..9],
+            [
+                "ip link set dev ens3 down",
+                "ip link set dev ens3 name eth0",
+                "ip link add wg0 type wireguard",
+                "ip link add link bond0 name bond0.20 type vlan id 20",
+                "ip link add br0 type bridge stp_state 0 forward_delay 200",
+                "ip link set dev eth0 master bond0",
+                "ip link set dev bond0 type bond primary eth0",
+                "ip link set dev bond0.20 master br0",
+                "ip link set dev eth0 up",
+            ]
+        );
+        assert!(plan.contains(&"ip addr del 10.0.0.3/24 dev bond0".to_string()));
+        assert!(!plan.contains(&"ip route add default via 10.0.0.1 dev bond0".to_string()));
+    }
+}
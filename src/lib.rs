@@ -11,17 +11,283 @@
 //! Please note that I do not check the docs often for updates, if anything is missing or incorrect in the future,
 //! please open an issue or a pull-request so the issue can be addressed.
 //!
+//! ## Building
+//! Configurations can be built without spelling out every `Option` and `HashMap` using
+//! [NetplanConfig::build].
+//!
+//! ## Validation
+//! Not every configuration which can be expressed with these types is accepted by netplan.
+//! [NetplanConfig::validate] checks a configuration against a set of rules and reports the
+//! problems it found as a [ValidationReport]. [NetplanConfig::redact] removes passwords and
+//! keys, so a configuration can be shared when reporting a problem.
+//!
+//! ## Loading
+//! [NetplanConfig::load_dir] loads and merges the configuration files of a directory like
+//! `/etc/netplan`, the way netplan itself does. [NetplanConfig::write_to] writes a configuration
+//! file atomically, with the permissions netplan expects.
+//! [NetplanConfig::split_by_kind] splits a configuration into one file per concern, and
+//! [NetplanConfig::from_files] reassembles them. [ConfigTransaction] snapshots a directory
+//! before changing it, and restores the snapshot unless the changes are committed.
+//! [NetplanConfig::from_template] resolves `${NAME}` placeholders against variables before
+//! parsing, for configurations which are shared between machines.
+//! [ConfigProfiles] keeps a base configuration with overlays for environments like `lab`
+//! and `prod`, and resolves a profile by merging its overlay into the base.
+//! [NetplanConfig::visit_devices] decodes the device definitions of very large
+//! configurations one at a time, and [NetplanConfigRef] is a read-only view of one which
+//! borrows its strings from the YAML.
+//!
+//! ## Generating
+//! [NetplanConfig::to_networkd] renders a configuration into the systemd-networkd units
+//! `netplan generate` would write, for systems without the netplan binary.
+//! [NetplanConfig::from_networkd] does the reverse, lifting existing networkd units into a
+//! configuration. [NetplanConfig::to_network_manager] and
+//! [NetplanConfig::from_network_manager] do the same for NetworkManager keyfiles.
+//! [NetplanConfig::from_ifupdown] migrates Debian-style `/etc/network/interfaces` files, and
+//! [NetplanConfig::to_ifupdown] renders them, reporting what they cannot express.
+//! [NetplanConfig::from_ifcfg] migrates the `ifcfg-*` network scripts of RHEL, reporting the
+//! variables netplan has no equivalent for.
+//! [WifiConfig::from_wpa_supplicant] lifts the networks of a `wpa_supplicant.conf` into
+//! access points. [TunnelConfig::to_wg_quick] renders a WireGuard tunnel for wg-quick, and
+//! [TunnelConfig::from_wg_quick] imports one. [NetplanConfig::from_cloud_init_v1] and
+//! [NetplanConfig::to_cloud_init_v1] convert cloud-init's network configuration version 1.
+//! [NetplanConfig::to_nmstate] and [NetplanConfig::from_nmstate] convert nmstate's desired state,
+//! as used by kubernetes-nmstate. [NetplanConfig::from_maas] lifts the network layout of a MAAS
+//! machine, as its API describes it.
+//! [WifiConfig::to_hostapd] renders the access points in `ap` mode for hostapd.
+//! [plan_iproute2] lists the `ip` and `wg` commands which realize a configuration, for
+//! dry runs and for containers without a network daemon. [NetworkConfig::dependency_graph]
+//! tells which devices are built on which, and the order to bring them up in, and
+//! [NetworkConfig::to_dot] draws them as a Graphviz diagram.
+//! [NetplanConfig::report] documents every device, and optionally the validation issues,
+//! in Markdown or HTML for change reviews.
+//! [NetworkConfig::default_route_providers] tells which devices are the uplinks.
+//! [NetworkConfig::claiming_device] predicts which definition claims an interface, from
+//! its name, MAC address and driver.
+//!
+//! ## Comparing
+//! [diff] lists the fields which differ between two configurations, e.g. to preview
+//! what writing a new configuration will change. [drift] compares a configuration to a
+//! [SystemState], the live network state, reporting links which differ from their definition.
+//! [SystemState::from_ip_json] builds the live state from the JSON output of `ip`, e.g. captured
+//! over SSH.
+//! [NetplanConfig::fingerprint] hashes the canonical form of a configuration, to tell
+//! cheaply whether it changed.
+//!
 //! ## Features
 //! - `serde`: \[Default\] Add serde support
+//! - `yaml`: \[Default\] Read and write netplan YAML, with [NetplanConfig::from_yaml], [NetplanConfig::load_dir] and [NetplanConfig::write_to], and the converters of formats written in YAML
+//! - `json`: Add [diff], [NetplanConfig::apply_merge_patch], [NetplanStatus] and the converters of formats written in JSON, like [SystemState::from_ip_json]
+//! - `fingerprint`: Add [NetplanConfig::fingerprint], a SHA-256 hash of the canonical form of a configuration
+//! - `cli`: Build the `netplan-types` command line tool, which validates, formats, compares, converts and redacts configurations
 //! - `derive_builder` Enable the derive_builder crate for an automatically generated builder pattern API
 //! - `schemars`: Enable the schemars crate for generating a JSON schema from the structs
+//! - `ipaddr`: Add accessors which parse the address fields into `std::net` and `ipnet` types
+//! - `macaddr`: Use [MacAddr] rather than [String] for the MAC address fields
+//! - `netplan-ctl`: Add [Netplan], which runs the `netplan` command line tool to generate, apply and try configurations
+//! - `rayon`: Add [NetplanConfig::load_dir_parallel] and [NetplanConfig::parse_many], which parse many files in parallel
+//! - `rkyv`: Derive rkyv's `Archive`, `Serialize` and `Deserialize` for the configuration types, so parsed configurations can be cached and accessed in place, e.g. memory-mapped, without parsing YAML again
+//! - `secret`: Use `Secret<String>` for passwords and keys, which is redacted by `Debug` and zeroized on drop
+//! - `system`: Add [NetplanConfig::from_system], which reads the live network state from the kernel (Linux only)
+//! - `tokio`: Add asynchronous variants of the loading and writing functions, like [NetplanConfig::load_dir_async]
+//! - `watch`: Add [NetplanWatcher], which reports changes to the configuration files as they happen
+//! - `wasm`: Add JavaScript bindings with wasm-bindgen, which parse, validate and serialize configurations as JSON, see [parse_yaml]
+
+mod aggregate;
+pub use aggregate::*;
+
+#[cfg(feature = "tokio")]
+mod async_io;
 
 #[cfg(feature = "serde")]
 mod bool;
 
+#[cfg(feature = "yaml")]
+mod borrowed;
+#[cfg(feature = "yaml")]
+pub use borrowed::*;
+
+mod build;
+pub use build::*;
+
+mod canonical;
+
+#[cfg(feature = "yaml")]
+mod cloud_init;
+#[cfg(feature = "yaml")]
+pub use cloud_init::*;
+
+mod default_routes;
+pub use default_routes::*;
+
+mod dependencies;
+pub use dependencies::*;
+
+mod devices;
+pub use devices::*;
+
+#[cfg(feature = "netplan-ctl")]
+mod ctl;
+#[cfg(feature = "netplan-ctl")]
+pub use ctl::*;
+
+mod dot;
+
+mod drift;
+pub use drift::*;
+
+#[cfg(feature = "json")]
+mod diff;
+#[cfg(feature = "json")]
+pub use diff::*;
+
+mod effective;
+
+#[cfg(feature = "yaml")]
+mod file;
+#[cfg(feature = "yaml")]
+pub use file::*;
+
+#[cfg(feature = "fingerprint")]
+mod fingerprint;
+#[cfg(feature = "fingerprint")]
+pub use fingerprint::*;
+
+mod glob;
+
+mod hash;
+
+mod hostapd;
+pub use hostapd::*;
+
+mod ifcfg;
+pub use ifcfg::*;
+
+mod ifupdown;
+pub use ifupdown::*;
+
+mod iproute2;
+pub use iproute2::*;
+
+#[cfg(feature = "json")]
+mod ip_json;
+
+mod ipaddr;
+pub use ipaddr::*;
+
+#[cfg(feature = "yaml")]
+mod load;
+#[cfg(feature = "yaml")]
+pub use load::*;
+
+#[cfg(all(feature = "json", feature = "yaml"))]
+mod maas;
+#[cfg(all(feature = "json", feature = "yaml"))]
+pub use maas::*;
+
+mod matching;
+pub use matching::*;
+
+mod merge;
+
+mod modernize;
+pub use modernize::*;
+
+mod macros;
+#[doc(hidden)]
+pub use macros::__private;
+
 mod netplan;
 pub use netplan::*;
 
+mod network_manager;
+pub use network_manager::*;
+
+mod networkd;
+pub use networkd::*;
+
+#[cfg(feature = "yaml")]
+mod nmstate;
+#[cfg(feature = "yaml")]
+pub use nmstate::*;
+
+#[cfg(feature = "rayon")]
+mod parallel;
+
+#[cfg(feature = "json")]
+mod patch;
+#[cfg(feature = "json")]
+pub use patch::*;
+
+#[cfg(feature = "yaml")]
+mod profile;
+#[cfg(feature = "yaml")]
+pub use profile::*;
+
+mod redact;
+pub use redact::*;
+
+mod report;
+pub use report::*;
+
+#[cfg(feature = "serde")]
+mod sorted;
+
+#[cfg(feature = "yaml")]
+mod split;
+
+mod state;
+pub use state::*;
+
+#[cfg(feature = "json")]
+mod status;
+#[cfg(feature = "json")]
+pub use status::*;
+
+#[cfg(feature = "yaml")]
+mod stream;
+
+mod summary;
+pub use summary::*;
+
+#[cfg(all(feature = "system", target_os = "linux"))]
+mod system;
+#[cfg(all(feature = "system", target_os = "linux"))]
+pub use system::*;
+
+#[cfg(feature = "yaml")]
+mod template;
+#[cfg(feature = "yaml")]
+pub use template::*;
+
+#[cfg(feature = "yaml")]
+mod transaction;
+#[cfg(feature = "yaml")]
+pub use transaction::*;
+
+mod validation;
+pub use validation::*;
+
+#[cfg(feature = "watch")]
+mod watch;
+#[cfg(feature = "watch")]
+pub use watch::*;
+
+#[cfg(feature = "wasm")]
+mod wasm;
+#[cfg(feature = "wasm")]
+pub use wasm::*;
+
+mod wg_quick;
+pub use wg_quick::*;
+
+mod wpa_supplicant;
+pub use wpa_supplicant::*;
+
+#[cfg(feature = "yaml")]
+mod write;
+#[cfg(feature = "yaml")]
+pub use write::*;
+
 use std::collections::HashMap;
 
 #[cfg(feature = "serde")]
@@ -30,40 +296,165 @@ use serde::{Deserialize, Serialize};
 #[cfg(feature = "derive_builder")]
 use derive_builder::Builder;
 
-#[derive(Default, Debug, Clone, PartialEq, Eq)]
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "derive_builder", derive(Builder))]
+#[cfg_attr(
+    feature = "derive_builder",
+    builder(setter(strip_option, into), default)
+)]
 #[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 pub struct NetplanConfig {
     pub network: NetworkConfig,
 }
 
-#[derive(Default, Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "derive_builder", derive(Builder))]
+#[cfg_attr(
+    feature = "derive_builder",
+    builder(setter(strip_option, into), default)
+)]
 #[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 pub struct NetworkConfig {
     pub version: u8,
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
     pub renderer: Option<Renderer>,
+    /// Global openvswitch settings, such as SSL and patch ports.
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub openvswitch: Option<OpenVSwitchConfig>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            skip_serializing_if = "Option::is_none",
+            serialize_with = "crate::sorted::sorted_option"
+        )
+    )]
     pub ethernets: Option<HashMap<String, EthernetConfig>>,
-    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            skip_serializing_if = "Option::is_none",
+            serialize_with = "crate::sorted::sorted_option"
+        )
+    )]
     pub wifis: Option<HashMap<String, WifiConfig>>,
-    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            skip_serializing_if = "Option::is_none",
+            serialize_with = "crate::sorted::sorted_option"
+        )
+    )]
+    pub modems: Option<HashMap<String, ModemConfig>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            skip_serializing_if = "Option::is_none",
+            serialize_with = "crate::sorted::sorted_option"
+        )
+    )]
     pub bonds: Option<HashMap<String, BondConfig>>,
-    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            skip_serializing_if = "Option::is_none",
+            serialize_with = "crate::sorted::sorted_option"
+        )
+    )]
     pub bridges: Option<HashMap<String, BridgeConfig>>,
-    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            skip_serializing_if = "Option::is_none",
+            serialize_with = "crate::sorted::sorted_option"
+        )
+    )]
     pub vlans: Option<HashMap<String, VlanConfig>>,
-    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            skip_serializing_if = "Option::is_none",
+            serialize_with = "crate::sorted::sorted_option"
+        )
+    )]
     pub tunnels: Option<HashMap<String, TunnelConfig>>,
-    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            skip_serializing_if = "Option::is_none",
+            serialize_with = "crate::sorted::sorted_option"
+        )
+    )]
     pub vrfs: Option<HashMap<String, VrfsConfig>>,
-    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            skip_serializing_if = "Option::is_none",
+            serialize_with = "crate::sorted::sorted_option"
+        )
+    )]
     pub dummy_devices: Option<HashMap<String, DummyDeviceConfig>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            skip_serializing_if = "Option::is_none",
+            serialize_with = "crate::sorted::sorted_option"
+        )
+    )]
+    pub nm_devices: Option<HashMap<String, NmDeviceConfig>>,
+}
+
+impl NetplanConfig {
+    /// An empty configuration in version 2 of the netplan format, the same as [NetplanConfig::default].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the global renderer.
+    ///
+    /// ```
+    /// use netplan_types::{NetplanConfig, Renderer};
+    ///
+    /// let config = NetplanConfig::new().with_renderer(Renderer::NetworkManager);
+    /// assert_eq!(config.network.version, 2);
+    /// assert_eq!(config.network.renderer, Some(Renderer::NetworkManager));
+    /// ```
+    pub fn with_renderer(mut self, renderer: Renderer) -> Self {
+        self.network.renderer = Some(renderer);
+        self
+    }
+}
+
+/// Defaults to version 2 of the netplan format, which is the only version netplan accepts.
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        Self {
+            version: 2,
+            renderer: None,
+            openvswitch: None,
+            ethernets: None,
+            wifis: None,
+            modems: None,
+            bonds: None,
+            bridges: None,
+            vlans: None,
+            tunnels: None,
+            vrfs: None,
+            dummy_devices: None,
+            nm_devices: None,
+        }
+    }
 }
 
 /// Use the given networking backend for this definition. Currently supported are
@@ -75,9 +466,13 @@ pub struct NetworkConfig {
 /// objects (i. e. defined in vlans:): sriov. If a vlan is defined with the
 /// sriov renderer for an SR-IOV Virtual Function interface, this causes netplan to
 /// set up a hardware VLAN filter for it. There can be only one defined per VF.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 pub enum Renderer {
     #[cfg_attr(feature = "serde", serde(rename = "networkd"))]
     Networkd,
@@ -87,6 +482,18 @@ pub enum Renderer {
     Sriov,
 }
 
+impl Renderer {
+    pub const VARIANTS: &'static [Self] = &[Self::Networkd, Self::NetworkManager, Self::Sriov];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Networkd => "networkd",
+            Self::NetworkManager => "NetworkManager",
+            Self::Sriov => "sriov",
+        }
+    }
+}
+
 /// Takes a boolean, or the special value “route”. When true, the domain
 /// name received from the DHCP server will be used as DNS search domain
 /// over this link, similar to the effect of the Domains= setting. If set
@@ -94,11 +501,15 @@ pub enum Renderer {
 /// used for routing DNS queries only, but not for searching, similar to
 /// the effect of the Domains= setting when the argument is prefixed with
 /// “~”.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "serde", serde(untagged))]
 #[cfg_attr(feature = "serde", serde(rename = "lowercase"))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 pub enum UseDomains {
     Boolean(
         #[cfg_attr(
@@ -110,7 +521,7 @@ pub enum UseDomains {
     Route,
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "yaml", feature = "json"))]
 mod test {
     use crate::NetplanConfig;
 
@@ -127,14 +538,109 @@ mod test {
                   dhcp6: N
             "#;
 
-        let netplan_config: NetplanConfig = serde_yaml::from_str(&input).unwrap();
+        let netplan_config: NetplanConfig = serde_yaml::from_str(input).unwrap();
         let ethernets = netplan_config.network.ethernets.unwrap();
         let ethernet = ethernets.values().next().unwrap();
 
         assert!(ethernet.common_all.is_some());
 
-        let common = ethernet.common_all.as_ref().unwrap();
+        let common = ethernet.common_all.as_deref().unwrap();
+
+        assert_eq!(common.dhcp4, Some(true));
+    }
+
+    #[test]
+    fn variant_names_match_serde() {
+        fn check<T: serde::Serialize>(variants: &[T], as_str: fn(&T) -> &'static str) {
+            for variant in variants {
+                let serialized = serde_json::to_value(variant).unwrap();
+                assert_eq!(serialized.as_str(), Some(as_str(variant)));
+            }
+        }
+
+        use crate::*;
+        check(Renderer::VARIANTS, Renderer::as_str);
+        check(
+            Ipv6AddressGeneration::VARIANTS,
+            Ipv6AddressGeneration::as_str,
+        );
+        check(PreferredLifetime::VARIANTS, PreferredLifetime::as_str);
+        check(BondMode::VARIANTS, BondMode::as_str);
+        check(LacpRate::VARIANTS, LacpRate::as_str);
+        check(TransmitHashPolicy::VARIANTS, TransmitHashPolicy::as_str);
+        check(AdSelect::VARIANTS, AdSelect::as_str);
+        check(ArpValidate::VARIANTS, ArpValidate::as_str);
+        check(ArpAllTargets::VARIANTS, ArpAllTargets::as_str);
+        check(FailOverMacPolicy::VARIANTS, FailOverMacPolicy::as_str);
+        check(
+            PrimaryReselectPolicy::VARIANTS,
+            PrimaryReselectPolicy::as_str,
+        );
+        check(TunnelMode::VARIANTS, TunnelMode::as_str);
+        check(WirelessBand::VARIANTS, WirelessBand::as_str);
+        check(AccessPointMode::VARIANTS, AccessPointMode::as_str);
+        check(WakeOnWLan::VARIANTS, WakeOnWLan::as_str);
+        check(ActivationMode::VARIANTS, ActivationMode::as_str);
+        check(ConnectionMode::VARIANTS, ConnectionMode::as_str);
+        check(OpenFlowProtocol::VARIANTS, OpenFlowProtocol::as_str);
+        check(Lacp::VARIANTS, Lacp::as_str);
+        check(FailMode::VARIANTS, FailMode::as_str);
+        check(EmbeddedSwitchMode::VARIANTS, EmbeddedSwitchMode::as_str);
+        check(AuthMethod::VARIANTS, AuthMethod::as_str);
+        check(KeyManagmentMode::VARIANTS, KeyManagmentMode::as_str);
+        check(RouteType::VARIANTS, RouteType::as_str);
+        check(RouteScope::VARIANTS, RouteScope::as_str);
+    }
+
+    #[cfg(feature = "derive_builder")]
+    #[test]
+    fn builder_defaults() {
+        use crate::{CommonPropertiesAllDevicesBuilder, EthernetConfigBuilder, MacAddress};
+
+        let common = CommonPropertiesAllDevicesBuilder::default()
+            .dhcp4(true)
+            .macaddress("52:54:00:6b:3c:58".parse::<MacAddress>().unwrap())
+            .build()
+            .unwrap();
+        let ethernet = EthernetConfigBuilder::default()
+            .common_all(common)
+            .build()
+            .unwrap();
 
+        let common = ethernet.common_all.unwrap();
         assert_eq!(common.dhcp4, Some(true));
+        assert_eq!(common.dhcp6, None);
+    }
+
+    #[cfg(all(feature = "rkyv", feature = "serde"))]
+    #[test]
+    fn rkyv_archive() {
+        use crate::{ArchivedNetplanConfig, NetplanConfig};
+
+        let config = NetplanConfig::from_yaml(
+            r#"
+network:
+  version: 2
+  ethernets:
+    eth0:
+      match: {macaddress: "52:54:00:6b:3c:58"}
+      addresses: [192.0.2.10/24]
+      mtu: 9000
+  wifis:
+    wlan0:
+      access-points:
+        home: {password: hunter22}
+"#,
+        )
+        .unwrap();
+
+        let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&config).unwrap();
+        let archived = rkyv::access::<ArchivedNetplanConfig, rkyv::rancor::Error>(&bytes).unwrap();
+        let ethernets = archived.network.ethernets.as_ref().unwrap();
+        let common = ethernets.get("eth0").unwrap().common_all.as_ref().unwrap();
+        assert_eq!(common.mtu.as_ref().map(|mtu| mtu.to_native()), Some(9000));
+
+        let deserialized = rkyv::deserialize::<NetplanConfig, rkyv::rancor::Error>(archived);
+        assert_eq!(deserialized.unwrap(), config);
     }
 }
@@ -0,0 +1,308 @@
+//! Loading configurations from netplan YAML files.
+
+use crate::NetplanConfig;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+
+/// The directories netplan reads its configuration from, from the lowest to the highest precedence.
+pub const NETPLAN_DIRS: [&str; 3] = ["lib/netplan", "etc/netplan", "run/netplan"];
+
+/// The error returned when a configuration file cannot be loaded.
+#[derive(Debug)]
+pub enum LoadError {
+    /// The file could not be read.
+    Io(std::io::Error),
+    /// The file is not a valid netplan configuration.
+    Yaml(serde_yaml::Error),
+    /// The file name does not follow the `NN-name.yaml` convention, see [NetplanFile](crate::NetplanFile).
+    FileName(PathBuf),
+}
+
+impl Display for LoadError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "Failed to read configuration: {e}"),
+            Self::Yaml(e) => write!(f, "Invalid configuration: {e}"),
+            Self::FileName(path) => {
+                write!(f, "'{}' is not named like NN-name.yaml", path.display())
+            }
+        }
+    }
+}
+
+impl std::error::Error for LoadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Yaml(e) => Some(e),
+            Self::FileName(_) => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for LoadError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<serde_yaml::Error> for LoadError {
+    fn from(e: serde_yaml::Error) -> Self {
+        Self::Yaml(e)
+    }
+}
+
+/// The configuration files of a directory, and the configuration they result in.
+#[derive(Debug)]
+pub struct LoadedConfig {
+    /// The merged configuration of all files which could be loaded.
+    pub config: NetplanConfig,
+    /// Every file, in the order they were merged, with its configuration or the error
+    /// encountered loading it.
+    pub files: Vec<(PathBuf, Result<NetplanConfig, LoadError>)>,
+}
+
+impl LoadedConfig {
+    /// Whether every file could be loaded.
+    pub fn is_ok(&self) -> bool {
+        self.files.iter().all(|(_, result)| result.is_ok())
+    }
+
+    /// The files which could not be loaded.
+    pub fn errors(&self) -> impl Iterator<Item = (&Path, &LoadError)> {
+        self.files
+            .iter()
+            .filter_map(|(path, result)| result.as_ref().err().map(|e| (path.as_path(), e)))
+    }
+
+    /// Merge the configurations of `files`, in order.
+    pub(crate) fn merge(files: Vec<(PathBuf, Result<NetplanConfig, LoadError>)>) -> Self {
+        let mut config = NetplanConfig::default();
+        for file in files.iter().filter_map(|(_, result)| result.as_ref().ok()) {
+            config.merge(file.clone());
+        }
+
+        Self { config, files }
+    }
+}
+
+impl NetplanConfig {
+    /// Parse a configuration from netplan YAML.
+    pub fn from_yaml(yaml: &str) -> Result<Self, serde_yaml::Error> {
+        serde_yaml::from_str(yaml)
+    }
+
+    /// Serialize the configuration to netplan YAML.
+    pub fn to_yaml(&self) -> String {
+        // Serializing the configuration types cannot fail, all map keys are strings
+        serde_yaml::to_string(self).expect("Serializing configuration")
+    }
+
+    /// Load the configuration from a single file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, LoadError> {
+        let yaml = std::fs::read_to_string(path)?;
+        Ok(Self::from_yaml(&yaml)?)
+    }
+
+    /// Load all `*.yaml` files in a directory, like `/etc/netplan`, and merge them in
+    /// lexicographic order of their file names, the way netplan does. See [NetplanConfig::merge].
+    ///
+    /// Files which cannot be loaded are reported in [LoadedConfig::files] and left out of the
+    /// merged configuration. An error is only returned if the directory cannot be read.
+    pub fn load_dir(path: impl AsRef<Path>) -> std::io::Result<LoadedConfig> {
+        let files = yaml_files(path.as_ref())?
+            .into_iter()
+            .map(|path| {
+                let result = Self::load(&path);
+                (path, result)
+            })
+            .collect();
+
+        Ok(LoadedConfig::merge(files))
+    }
+}
+
+/// The configuration files found in all of netplan's directories, see [NetplanConfig::load_system].
+#[derive(Debug)]
+pub struct DiscoveredConfig {
+    /// The merged configuration, and the files it was merged from.
+    pub loaded: LoadedConfig,
+    /// The files which are ignored, as a file with the same name exists in a directory with
+    /// a higher precedence.
+    pub shadowed: Vec<PathBuf>,
+}
+
+impl DiscoveredConfig {
+    /// The file the device was defined in. If multiple files define the device, this is
+    /// the file which was merged last, i.e. the one whose settings take precedence.
+    pub fn origin(&self, name: &str) -> Option<&Path> {
+        self.loaded
+            .files
+            .iter()
+            .rev()
+            .find(|(_, result)| {
+                result
+                    .as_ref()
+                    .is_ok_and(|config| config.network.device(name).is_some())
+            })
+            .map(|(path, _)| path.as_path())
+    }
+
+    /// The file each device was defined in, see [DiscoveredConfig::origin].
+    pub fn origins(&self) -> HashMap<&str, &Path> {
+        let mut origins = HashMap::new();
+        for (path, result) in &self.loaded.files {
+            let Ok(config) = result else {
+                continue;
+            };
+
+            for (_, name, _) in config.network.devices() {
+                origins.insert(name, path.as_path());
+            }
+        }
+
+        origins
+    }
+}
+
+impl NetplanConfig {
+    /// Load the configuration of the system, from `/lib/netplan`, `/etc/netplan` and `/run/netplan`.
+    /// See [NetplanConfig::load_system_from].
+    pub fn load_system() -> std::io::Result<DiscoveredConfig> {
+        Self::load_system_from("/")
+    }
+
+    /// Load the configuration from netplan's directories below `root`, following netplan's
+    /// precedence rules: a file in `/run/netplan` hides files with the same name in
+    /// `/etc/netplan` and `/lib/netplan`, and a file in `/etc/netplan` hides a file with the
+    /// same name in `/lib/netplan`. The remaining files are merged in lexicographic order of
+    /// their file names, regardless of the directory they are in.
+    ///
+    /// Directories which do not exist are skipped.
+    pub fn load_system_from(root: impl AsRef<Path>) -> std::io::Result<DiscoveredConfig> {
+        let mut files = HashMap::new();
+        let mut shadowed = Vec::new();
+        for dir in NETPLAN_DIRS {
+            let paths = match yaml_files(&root.as_ref().join(dir)) {
+                Ok(paths) => paths,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e),
+            };
+
+            for path in paths {
+                let name = path.file_name().unwrap_or_default().to_os_string();
+                shadowed.extend(files.insert(name, path));
+            }
+        }
+
+        let mut files = files.into_iter().collect::<Vec<_>>();
+        files.sort_by(|(a, _), (b, _)| a.cmp(b));
+        let files = files
+            .into_iter()
+            .map(|(_, path)| {
+                let result = Self::load(&path);
+                (path, result)
+            })
+            .collect();
+
+        Ok(DiscoveredConfig {
+            loaded: LoadedConfig::merge(files),
+            shadowed,
+        })
+    }
+}
+
+/// The paths of the `*.yaml` files in a directory, sorted by file name.
+pub(crate) fn yaml_files(dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "yaml") && path.is_file() {
+            files.push(path);
+        }
+    }
+
+    files.sort_by(|a, b| a.file_name().cmp(&b.file_name()));
+    Ok(files)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::NetplanConfig;
+
+    #[test]
+    fn load_dir_merges_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let write = |name: &str, yaml: &str| std::fs::write(dir.path().join(name), yaml).unwrap();
+        write(
+            "90-override.yaml",
+            "network: {version: 2, ethernets: {eth0: {mtu: 9000}}}",
+        );
+        write(
+            "01-base.yaml",
+            "network: {version: 2, ethernets: {eth0: {dhcp4: true, mtu: 1500}}}",
+        );
+        write("50-broken.yaml", "network: [");
+        write("README", "not a configuration");
+
+        let loaded = NetplanConfig::load_dir(dir.path()).unwrap();
+        let names = loaded
+            .files
+            .iter()
+            .map(|(path, _)| path.file_name().unwrap().to_str().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            ["01-base.yaml", "50-broken.yaml", "90-override.yaml"]
+        );
+        assert!(!loaded.is_ok());
+        assert_eq!(loaded.errors().count(), 1);
+
+        let eth0 = &loaded.config.network.ethernets.unwrap()["eth0"];
+        let common = eth0.common_all.as_deref().unwrap();
+        assert_eq!(common.dhcp4, Some(true));
+        assert_eq!(common.mtu, Some(9000));
+    }
+
+    #[test]
+    fn load_system_shadows_by_name() {
+        let root = tempfile::tempdir().unwrap();
+        let write = |dir: &str, name: &str, yaml: &str| {
+            let dir = root.path().join(dir);
+            std::fs::create_dir_all(&dir).unwrap();
+            std::fs::write(dir.join(name), yaml).unwrap();
+        };
+        write(
+            "lib/netplan",
+            "10-base.yaml",
+            "network: {version: 2, ethernets: {eth0: {dhcp4: true}}}",
+        );
+        write(
+            "etc/netplan",
+            "10-base.yaml",
+            "network: {version: 2, ethernets: {eth0: {dhcp6: true}}}",
+        );
+        write(
+            "run/netplan",
+            "90-vlan.yaml",
+            "network: {version: 2, vlans: {vlan10: {id: 10, link: eth0}}}",
+        );
+
+        let discovered = NetplanConfig::load_system_from(root.path()).unwrap();
+        assert_eq!(
+            discovered.shadowed,
+            [root.path().join("lib/netplan/10-base.yaml")]
+        );
+
+        let eth0 = &discovered.loaded.config.network.ethernets.as_ref().unwrap()["eth0"];
+        assert_eq!(eth0.common_all.as_deref().unwrap().dhcp4, None);
+        assert_eq!(eth0.common_all.as_deref().unwrap().dhcp6, Some(true));
+
+        assert_eq!(
+            discovered.origin("vlan10"),
+            Some(root.path().join("run/netplan/90-vlan.yaml").as_path())
+        );
+        assert_eq!(discovered.origins().len(), 2);
+    }
+}
@@ -0,0 +1,453 @@
+//! Importing the network layout of a MAAS machine, as its API describes it.
+
+use crate::cloud_init::add_nameservers;
+use crate::ifupdown::{define_from_options, ethernet_by_mac};
+use crate::{
+    AddressMapping, CommonProperties, CommonPropertiesAllDevices, EthernetConfig, NetplanConfig,
+    RoutingConfig,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// A machine as returned by the MAAS API, e.g. `GET /MAAS/api/2.0/machines/{system_id}/`,
+/// of which only the network layout is read.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MaasMachine {
+    #[serde(default)]
+    pub interface_set: Vec<MaasInterface>,
+    /// The gateways MAAS chose for the machine, and the links they are reached through.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_gateways: Option<MaasDefaultGateways>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub domain: Option<MaasDomain>,
+}
+
+/// An interface of a [MaasMachine]: `physical`, `bond`, `bridge` or `vlan`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MaasInterface {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<u64>,
+    pub name: String,
+    pub r#type: String,
+    #[serde(default = "enabled")]
+    pub enabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mac_address: Option<String>,
+    /// The parameters of the interface, like `mtu` or `bond_mode`. MAAS returns an empty
+    /// string for interfaces without parameters.
+    #[serde(default)]
+    pub params: serde_json::Value,
+    /// The interfaces a bond or bridge consists of, or the link of a VLAN.
+    #[serde(default)]
+    pub parents: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vlan: Option<MaasVlan>,
+    #[serde(default)]
+    pub links: Vec<MaasLink>,
+}
+
+fn enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MaasVlan {
+    /// The VLAN ID, 0 for untagged traffic.
+    pub vid: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mtu: Option<u16>,
+}
+
+/// How an interface is connected to a subnet.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MaasLink {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<u64>,
+    /// `static`, `auto` for an address MAAS assigns on deployment, `dhcp` or `link_up`.
+    pub mode: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ip_address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subnet: Option<MaasSubnet>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MaasSubnet {
+    pub cidr: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gateway_ip: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dns_servers: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MaasDefaultGateways {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipv4: Option<MaasGateway>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipv6: Option<MaasGateway>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MaasGateway {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gateway_ip: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub link_id: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MaasDomain {
+    pub name: String,
+}
+
+impl MaasMachine {
+    /// Parse a machine from the JSON of the MAAS API, either a machine or the list of its
+    /// interfaces, as returned by `GET /MAAS/api/2.0/nodes/{system_id}/interfaces/`.
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        match serde_json::from_str::<Vec<MaasInterface>>(json) {
+            Ok(interface_set) => Ok(Self {
+                interface_set,
+                ..Default::default()
+            }),
+            Err(_) => serde_json::from_str(json),
+        }
+    }
+}
+
+impl MaasInterface {
+    /// The parameter `key`, if the interface has parameters.
+    pub fn param(&self, key: &str) -> Option<&serde_json::Value> {
+        self.params.get(key).filter(|value| !value.is_null())
+    }
+}
+
+impl NetplanConfig {
+    /// Lift the network layout of a MAAS machine into a configuration, like the one MAAS
+    /// renders for deployed machines.
+    ///
+    /// Physical interfaces become ethernets, matched by their MAC address and renamed to
+    /// their name, and bonds, bridges and VLANs become the devices of the same kind, with
+    /// the `bond_*` and `bridge_*` parameters read like the options of an ifupdown
+    /// interfaces file. Disabled interfaces, and other types, are left out.
+    ///
+    /// `static` links, and `auto` links once MAAS assigned their address, become addresses
+    /// with the prefix length of their subnet, `dhcp` links enable DHCP for the family of
+    /// their subnet. The DNS servers of the subnets become the nameservers of their
+    /// interface, and the domain of the machine its search domain. The MTU is the `mtu`
+    /// parameter, or that of the VLAN if it differs from 1500.
+    ///
+    /// The default route of each address family is the one in `default_gateways`, through
+    /// the interface of its link, or else the gateway of the first subnet with a static
+    /// address and a gateway.
+    ///
+    /// ```
+    /// use netplan_types::{MaasMachine, NetplanConfig};
+    ///
+    /// let machine = MaasMachine::from_json(r#"[{
+    ///     "id": 1, "name": "eth0", "type": "physical", "enabled": true,
+    ///     "mac_address": "52:54:00:12:34:01", "params": "", "parents": [],
+    ///     "vlan": {"vid": 0, "mtu": 1500},
+    ///     "links": [{"id": 10, "mode": "auto", "ip_address": "10.0.0.10",
+    ///                "subnet": {"cidr": "10.0.0.0/24", "gateway_ip": "10.0.0.1"}}]
+    /// }]"#).unwrap();
+    ///
+    /// let config = NetplanConfig::from_maas(&machine);
+    /// let yaml = config.to_yaml();
+    /// assert!(yaml.contains("set-name: eth0"));
+    /// assert!(yaml.contains("- 10.0.0.10/24"));
+    /// assert!(yaml.contains("via: 10.0.0.1"));
+    /// ```
+    pub fn from_maas(machine: &MaasMachine) -> Self {
+        let mut config = NetplanConfig::default();
+        let network = &mut config.network;
+        let interfaces = machine
+            .interface_set
+            .iter()
+            .filter(|interface| interface.enabled)
+            .collect::<Vec<_>>();
+
+        let mut dependencies = HashSet::new();
+        for interface in &interfaces {
+            let name = interface.name.as_str();
+            let mut options = Vec::new();
+            match interface.r#type.as_str() {
+                "physical" => {
+                    network.add_ethernet(
+                        name,
+                        ethernet_by_mac(name, interface.mac_address.as_deref()),
+                    );
+                }
+                "bond" | "bridge" => {
+                    let members = match interface.r#type.as_str() {
+                        "bond" => "bond-slaves",
+                        _ => "bridge-ports",
+                    };
+                    let parents = match interface.parents.is_empty() {
+                        true => "none".to_string(),
+                        false => interface.parents.join(" "),
+                    };
+                    options.push((members.to_string(), parents));
+                    let params = interface.params.as_object().into_iter().flatten();
+                    for (key, value) in params {
+                        let Some(value) = param(value) else {
+                            continue;
+                        };
+                        if key.starts_with("bond_") || key.starts_with("bridge_") {
+                            options.push((key.replacen('_', "-", 1), value));
+                        }
+                    }
+                }
+                "vlan" => {
+                    if let Some(parent) = interface.parents.first() {
+                        options.push(("vlan-raw-device".to_string(), parent.clone()));
+                    }
+                    if let Some(vlan) = &interface.vlan {
+                        options.push(("vlan-id".to_string(), vlan.vid.to_string()));
+                    }
+                }
+                _ => continue,
+            }
+            if !options.is_empty() {
+                dependencies.extend(define_from_options(network, name, options));
+            }
+
+            let mut common = import_interface(interface);
+            if let Some(domain) = &machine.domain {
+                if common.nameservers.is_some() {
+                    add_nameservers(&mut common, &[], std::slice::from_ref(&domain.name));
+                }
+            }
+            if common != CommonPropertiesAllDevices::default() {
+                if let Some(mut device) = network.device_mut(name) {
+                    *device.common_all_mut() = common;
+                }
+            }
+        }
+
+        for dependency in &dependencies {
+            if network.device(dependency).is_none() {
+                network.add_ethernet(dependency.as_str(), EthernetConfig::default());
+            }
+        }
+
+        let gateways = machine.default_gateways.clone().unwrap_or_default();
+        for (ipv6, gateway) in [(false, gateways.ipv4), (true, gateways.ipv6)] {
+            let Some((name, via)) = default_gateway(&interfaces, ipv6, gateway) else {
+                continue;
+            };
+            if let Some(mut device) = network.device_mut(name) {
+                device
+                    .common_all_mut()
+                    .routes
+                    .get_or_insert_with(Vec::new)
+                    .push(RoutingConfig {
+                        to: Some("default".to_string()),
+                        via: Some(via),
+                        ..Default::default()
+                    });
+            }
+        }
+
+        config
+    }
+}
+
+/// A parameter of a bond or bridge as the value of an ifupdown option.
+fn param(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::String(value) => Some(value.clone()),
+        serde_json::Value::Number(value) => Some(value.to_string()),
+        serde_json::Value::Bool(value) => Some(if *value { "on" } else { "off" }.to_string()),
+        _ => None,
+    }
+}
+
+/// Whether the address, or subnet, is IPv6.
+fn is_ipv6(cidr: &str) -> bool {
+    cidr.contains(':')
+}
+
+/// The addressing, nameservers, MTU and MAC address of an interface.
+fn import_interface(interface: &MaasInterface) -> CommonPropertiesAllDevices {
+    let mut common = CommonPropertiesAllDevices {
+        mtu: interface
+            .param("mtu")
+            .and_then(|mtu| mtu.as_u64())
+            .and_then(|mtu| mtu.try_into().ok())
+            .or(interface
+                .vlan
+                .as_ref()
+                .and_then(|vlan| vlan.mtu)
+                .filter(|mtu| *mtu != 1500)),
+        ..Default::default()
+    };
+    if interface.r#type != "physical" {
+        common.macaddress = interface
+            .mac_address
+            .as_deref()
+            .and_then(|mac| mac.parse().ok());
+    }
+
+    let mut addresses = Vec::new();
+    for link in &interface.links {
+        let Some(subnet) = &link.subnet else {
+            continue;
+        };
+        match (link.mode.as_str(), &link.ip_address) {
+            ("dhcp", _) if is_ipv6(&subnet.cidr) => common.dhcp6 = Some(true),
+            ("dhcp", _) => common.dhcp4 = Some(true),
+            ("static" | "auto", Some(address)) => {
+                let Some((_, prefix)) = subnet.cidr.split_once('/') else {
+                    continue;
+                };
+                addresses.push(format!("{address}/{prefix}"));
+            }
+            _ => continue,
+        }
+        add_nameservers(&mut common, &subnet.dns_servers, &[]);
+    }
+    if !addresses.is_empty() {
+        common.addresses = Some(addresses.into_iter().map(AddressMapping::Simple).collect());
+    }
+
+    common
+}
+
+/// The interface and gateway of the default route of an address family.
+fn default_gateway<'a>(
+    interfaces: &[&'a MaasInterface],
+    ipv6: bool,
+    gateway: Option<MaasGateway>,
+) -> Option<(&'a str, String)> {
+    let static_links = interfaces.iter().flat_map(|interface| {
+        interface
+            .links
+            .iter()
+            .filter(|link| link.ip_address.is_some() && link.mode != "dhcp")
+            .filter_map(move |link| Some((interface.name.as_str(), link.subnet.as_ref()?)))
+            .filter(|(_, subnet)| is_ipv6(&subnet.cidr) == ipv6)
+    });
+
+    let gateway = gateway.unwrap_or_default();
+    if let (Some(via), Some(link_id)) = (gateway.gateway_ip, gateway.link_id) {
+        let name = interfaces.iter().find_map(|interface| {
+            let link = interface.links.iter().find(|link| link.id == Some(link_id));
+            link.map(|_| interface.name.as_str())
+        })?;
+        return Some((name, via));
+    }
+
+    static_links
+        .into_iter()
+        .find_map(|(name, subnet)| Some((name, subnet.gateway_ip.clone()?)))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{MaasMachine, NetplanConfig};
+
+    #[test]
+    fn import_maas() {
+        let machine = MaasMachine::from_json(
+            r#"{
+  "system_id": "4y3h7n",
+  "hostname": "node01",
+  "domain": {"id": 0, "name": "maas"},
+  "default_gateways": {
+    "ipv4": {"gateway_ip": "10.0.20.1", "link_id": 21},
+    "ipv6": {"gateway_ip": null, "link_id": null}
+  },
+  "interface_set": [
+    {"id": 1, "name": "eno1", "type": "physical", "enabled": true,
+     "mac_address": "52:54:00:12:34:01", "params": "", "parents": [], "children": ["bond0"],
+     "vlan": {"id": 5001, "vid": 0, "mtu": 1500}, "links": []},
+    {"id": 2, "name": "eno2", "type": "physical", "enabled": true,
+     "mac_address": "52:54:00:12:34:02", "params": "", "parents": [], "children": ["bond0"],
+     "vlan": {"id": 5001, "vid": 0, "mtu": 1500}, "links": []},
+    {"id": 3, "name": "eno3", "type": "physical", "enabled": false,
+     "mac_address": "52:54:00:12:34:03", "params": "", "parents": [], "links": []},
+    {"id": 4, "name": "bond0", "type": "bond", "enabled": true,
+     "mac_address": "52:54:00:12:34:01",
+     "params": {"bond_mode": "802.3ad", "bond_miimon": 100, "bond_lacp_rate": "fast",
+                "bond_xmit_hash_policy": "layer3+4", "mtu": 9000},
+     "parents": ["eno1", "eno2"], "children": ["bond0.20"],
+     "vlan": {"id": 5001, "vid": 0, "mtu": 9000},
+     "links": [
+       {"id": 11, "mode": "static", "ip_address": "10.0.0.10",
+        "subnet": {"cidr": "10.0.0.0/24", "gateway_ip": "10.0.0.1", "dns_servers": ["10.0.0.53"]}},
+       {"id": 12, "mode": "dhcp",
+        "subnet": {"cidr": "2001:db8::/64", "gateway_ip": null, "dns_servers": []}}
+     ]},
+    {"id": 5, "name": "bond0.20", "type": "vlan", "enabled": true,
+     "mac_address": "52:54:00:12:34:01", "params": "", "parents": ["bond0"],
+     "vlan": {"id": 5002, "vid": 20, "mtu": 1500},
+     "links": [
+       {"id": 21, "mode": "auto", "ip_address": "10.0.20.10",
+        "subnet": {"cidr": "10.0.20.0/24", "gateway_ip": "10.0.20.1", "dns_servers": []}},
+       {"id": 22, "mode": "auto",
+        "subnet": {"cidr": "10.0.21.0/24", "gateway_ip": "10.0.21.1", "dns_servers": []}}
+     ]},
+    {"id": 6, "name": "br0", "type": "bridge", "enabled": true,
+     "mac_address": "52:54:00:12:34:04",
+     "params": {"bridge_type": "standard", "bridge_stp": false, "bridge_fd": 15},
+     "parents": ["eno4"], "vlan": {"id": 5003, "vid": 0, "mtu": 1500},
+     "links": [{"id": 31, "mode": "link_up"}]}
+  ]
+}"#,
+        )
+        .unwrap();
+
+        let expected = NetplanConfig::from_yaml(
+            r#"
+network:
+  version: 2
+  ethernets:
+    eno1:
+      match: {macaddress: "52:54:00:12:34:01"}
+      set-name: eno1
+    eno2:
+      match: {macaddress: "52:54:00:12:34:02"}
+      set-name: eno2
+    eno4: {}
+  bonds:
+    bond0:
+      interfaces: [eno1, eno2]
+      macaddress: "52:54:00:12:34:01"
+      mtu: 9000
+      parameters:
+        mode: 802.3ad
+        mii-monitor-interval: "100"
+        lacp-rate: fast
+        transmit-hash-policy: layer3+4
+      addresses: [10.0.0.10/24]
+      dhcp6: true
+      nameservers: {addresses: [10.0.0.53], search: [maas]}
+  vlans:
+    bond0.20:
+      id: 20
+      link: bond0
+      macaddress: "52:54:00:12:34:01"
+      addresses: [10.0.20.10/24]
+      routes:
+        - {to: default, via: 10.0.20.1}
+  bridges:
+    br0:
+      interfaces: [eno4]
+      macaddress: "52:54:00:12:34:04"
+      parameters: {stp: false, forward-delay: "15"}
+"#,
+        )
+        .unwrap();
+        let imported = NetplanConfig::from_maas(&machine);
+        assert!(imported.semantically_eq(&expected), "{imported:#?}");
+
+        // Without the gateways MAAS chose, the first static subnet with a gateway is used
+        let mut machine = machine;
+        machine.default_gateways = None;
+        let imported = NetplanConfig::from_maas(&machine);
+        let bond0 = &imported.network.bonds.unwrap()["bond0"];
+        let routes = bond0.common_all.as_ref().unwrap().routes.as_ref().unwrap();
+        assert_eq!(routes[0].via.as_deref(), Some("10.0.0.1"));
+    }
+}
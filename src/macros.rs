@@ -0,0 +1,519 @@
+//! The [netplan!](crate::netplan) macro and its supporting items.
+
+use crate::{
+    BondConfig, BridgeConfig, CommonProperties, CommonPropertiesAllDevices, EthernetConfig,
+    OpenVSwitchConfig, PhysicalProperties, VlanConfig,
+};
+
+/// Build a [NetplanConfig](crate::NetplanConfig) using a syntax resembling netplan YAML.
+///
+/// Keys are written in snake_case, as the fields of the configuration types. Nested
+/// mappings are written as blocks, sequences of mappings as a list of blocks, and any
+/// other value as a Rust expression. Strings and arrays of strings are converted into
+/// the type of the field. Keys which do not exist on a device type are a compile error.
+///
+/// Keys of the properties shared by all (physical) devices, such as `dhcp4` or `match`,
+/// are set in the corresponding block of the device. Device IDs and the keys of other
+/// mappings, such as `access_points`, may be written as string literals.
+///
+/// ```
+/// use netplan_types::{netplan, Renderer};
+///
+/// let config = netplan! {
+///     renderer: Renderer::Networkd,
+///     ethernets {
+///         eth0 {
+///             match { macaddress: "52:54:00:6b:3c:58" },
+///             set_name: "lan0",
+///             addresses: ["10.0.0.2/24"],
+///             nameservers { addresses: ["10.0.0.1"] },
+///             routes: [{ to: "default", via: "10.0.0.1" }],
+///         },
+///     },
+///     wifis {
+///         wlan0 {
+///             dhcp4: true,
+///             access_points {
+///                 "home" { password: "hunter22" },
+///             },
+///         },
+///     },
+/// };
+///
+/// let ethernet = &config.network.ethernets.as_ref().unwrap()["eth0"];
+/// assert_eq!(ethernet.common_all.as_deref().unwrap().mtu, None);
+/// ```
+///
+/// Keys which do not exist are rejected at compile time:
+///
+/// ```compile_fail
+/// use netplan_types::netplan;
+///
+/// let config = netplan! {
+///     bonds {
+///         bond0 { set_name: "bond1" },
+///     },
+/// };
+/// ```
+#[macro_export]
+macro_rules! netplan {
+    // Device sections of the network block
+    (@body network $n:ident; ethernets { $($devices:tt)* } $($rest:tt)*) => {
+        $crate::netplan!(@devices $n.ethernets; $($devices)*);
+        $crate::netplan!(@body network $n; $($rest)*);
+    };
+    (@body network $n:ident; wifis { $($devices:tt)* } $($rest:tt)*) => {
+        $crate::netplan!(@devices $n.wifis; $($devices)*);
+        $crate::netplan!(@body network $n; $($rest)*);
+    };
+    (@body network $n:ident; modems { $($devices:tt)* } $($rest:tt)*) => {
+        $crate::netplan!(@devices $n.modems; $($devices)*);
+        $crate::netplan!(@body network $n; $($rest)*);
+    };
+    (@body network $n:ident; bonds { $($devices:tt)* } $($rest:tt)*) => {
+        $crate::netplan!(@devices $n.bonds; $($devices)*);
+        $crate::netplan!(@body network $n; $($rest)*);
+    };
+    (@body network $n:ident; bridges { $($devices:tt)* } $($rest:tt)*) => {
+        $crate::netplan!(@devices $n.bridges; $($devices)*);
+        $crate::netplan!(@body network $n; $($rest)*);
+    };
+    (@body network $n:ident; vlans { $($devices:tt)* } $($rest:tt)*) => {
+        $crate::netplan!(@devices $n.vlans; $($devices)*);
+        $crate::netplan!(@body network $n; $($rest)*);
+    };
+    (@body network $n:ident; tunnels { $($devices:tt)* } $($rest:tt)*) => {
+        $crate::netplan!(@devices $n.tunnels; $($devices)*);
+        $crate::netplan!(@body network $n; $($rest)*);
+    };
+    (@body network $n:ident; vrfs { $($devices:tt)* } $($rest:tt)*) => {
+        $crate::netplan!(@devices $n.vrfs; $($devices)*);
+        $crate::netplan!(@body network $n; $($rest)*);
+    };
+    (@body network $n:ident; dummy_devices { $($devices:tt)* } $($rest:tt)*) => {
+        $crate::netplan!(@devices $n.dummy_devices; $($devices)*);
+        $crate::netplan!(@body network $n; $($rest)*);
+    };
+    (@body network $n:ident; nm_devices { $($devices:tt)* } $($rest:tt)*) => {
+        $crate::netplan!(@devices $n.nm_devices; $($devices)*);
+        $crate::netplan!(@body network $n; $($rest)*);
+    };
+    // The entries of a device section
+    (@devices $map:expr; $(,)?) => {};
+    (@devices $map:expr; $name:tt { $($fields:tt)* } $($rest:tt)*) => {
+        {
+            let device = $crate::__private::Slot::slot(&mut $map)
+                .entry(::std::string::String::from($crate::netplan!(@name $name)))
+                .or_default();
+            $crate::netplan!(@body device device; $($fields)*);
+        }
+        $crate::netplan!(@devices $map; $($rest)*);
+    };
+    (@devices $map:expr; , $($rest:tt)*) => {
+        $crate::netplan!(@devices $map; $($rest)*);
+    };
+
+    // The fields of a block. In `device` mode, shared keys are routed into the
+    // common blocks, in `network` and `direct` mode fields are set as-is.
+    (@body $mode:ident $d:ident; $(,)?) => {};
+    (@body $mode:ident $d:ident; , $($rest:tt)*) => {
+        $crate::netplan!(@body $mode $d; $($rest)*);
+    };
+    (@body $mode:ident $d:ident; $key:ident { $($name:literal { $($inner:tt)* } $(,)?)* } $($rest:tt)*) => {
+        {
+            let map = $crate::__private::Slot::slot(&mut $crate::netplan!(@place $mode $d $key));
+            $({
+                let entry = map.entry(::std::string::String::from($name)).or_default();
+                $crate::netplan!(@body direct entry; $($inner)*);
+            })*
+        }
+        $crate::netplan!(@body $mode $d; $($rest)*);
+    };
+    (@body $mode:ident $d:ident; $key:ident { $($inner:tt)* } $($rest:tt)*) => {
+        {
+            let block = $crate::__private::Slot::slot(&mut $crate::netplan!(@place $mode $d $key));
+            $crate::netplan!(@body direct block; $($inner)*);
+        }
+        $crate::netplan!(@body $mode $d; $($rest)*);
+    };
+    (@body $mode:ident $d:ident; $key:ident : [ $({ $($inner:tt)* }),* $(,)? ] $($rest:tt)*) => {
+        {
+            let list = $crate::__private::Slot::slot(&mut $crate::netplan!(@place $mode $d $key));
+            $({
+                list.push(::core::default::Default::default());
+                let item = list.last_mut().unwrap();
+                $crate::netplan!(@body direct item; $($inner)*);
+            })*
+        }
+        $crate::netplan!(@body $mode $d; $($rest)*);
+    };
+    (@body $mode:ident $d:ident; $key:ident : $value:expr $(, $($rest:tt)*)?) => {
+        $crate::netplan!(@place $mode $d $key) = $crate::__private::IntoValue::into_value($value);
+        $crate::netplan!(@body $mode $d; $($($rest)*)?);
+    };
+
+    // The place a key is stored
+    (@place device $d:ident renderer) => {
+        $crate::__private::common($d).renderer
+    };
+    (@place device $d:ident dhcp4) => {
+        $crate::__private::common($d).dhcp4
+    };
+    (@place device $d:ident dhcp6) => {
+        $crate::__private::common($d).dhcp6
+    };
+    (@place device $d:ident ipv6_mtu) => {
+        $crate::__private::common($d).ipv6_mtu
+    };
+    (@place device $d:ident ipv6_privacy) => {
+        $crate::__private::common($d).ipv6_privacy
+    };
+    (@place device $d:ident link_local) => {
+        $crate::__private::common($d).link_local
+    };
+    (@place device $d:ident ignore_carrier) => {
+        $crate::__private::common($d).ignore_carrier
+    };
+    (@place device $d:ident critical) => {
+        $crate::__private::common($d).critical
+    };
+    (@place device $d:ident dhcp_identifier) => {
+        $crate::__private::common($d).dhcp_identifier
+    };
+    (@place device $d:ident dhcp4_overrides) => {
+        $crate::__private::common($d).dhcp4_overrides
+    };
+    (@place device $d:ident dhcp6_overrides) => {
+        $crate::__private::common($d).dhcp6_overrides
+    };
+    (@place device $d:ident accept_ra) => {
+        $crate::__private::common($d).accept_ra
+    };
+    (@place device $d:ident addresses) => {
+        $crate::__private::common($d).addresses
+    };
+    (@place device $d:ident ipv6_address_generation) => {
+        $crate::__private::common($d).ipv6_address_generation
+    };
+    (@place device $d:ident ipv6_address_token) => {
+        $crate::__private::common($d).ipv6_address_token
+    };
+    (@place device $d:ident gateway4) => {
+        $crate::__private::common($d).gateway4
+    };
+    (@place device $d:ident gateway6) => {
+        $crate::__private::common($d).gateway6
+    };
+    (@place device $d:ident nameservers) => {
+        $crate::__private::common($d).nameservers
+    };
+    (@place device $d:ident macaddress) => {
+        $crate::__private::common($d).macaddress
+    };
+    (@place device $d:ident mtu) => {
+        $crate::__private::common($d).mtu
+    };
+    (@place device $d:ident optional) => {
+        $crate::__private::common($d).optional
+    };
+    (@place device $d:ident optional_addresses) => {
+        $crate::__private::common($d).optional_addresses
+    };
+    (@place device $d:ident activation_mode) => {
+        $crate::__private::common($d).activation_mode
+    };
+    (@place device $d:ident routes) => {
+        $crate::__private::common($d).routes
+    };
+    (@place device $d:ident routing_policy) => {
+        $crate::__private::common($d).routing_policy
+    };
+    (@place device $d:ident networkmanager) => {
+        $crate::__private::common($d).networkmanager
+    };
+    (@place device $d:ident match) => {
+        $crate::PhysicalProperties::common_physical_mut($d).r#match
+    };
+    (@place device $d:ident set_name) => {
+        $crate::PhysicalProperties::common_physical_mut($d).set_name
+    };
+    (@place device $d:ident wakeonlan) => {
+        $crate::PhysicalProperties::common_physical_mut($d).wakeonlan
+    };
+    (@place device $d:ident emit_lldp) => {
+        $crate::PhysicalProperties::common_physical_mut($d).emit_lldp
+    };
+    (@place device $d:ident receive_checksum_offload) => {
+        $crate::PhysicalProperties::common_physical_mut($d).receive_checksum_offload
+    };
+    (@place device $d:ident transmit_checksum_offload) => {
+        $crate::PhysicalProperties::common_physical_mut($d).transmit_checksum_offload
+    };
+    (@place device $d:ident tcp_segmentation_offload) => {
+        $crate::PhysicalProperties::common_physical_mut($d).tcp_segmentation_offload
+    };
+    (@place device $d:ident tcp6_segmentation_offload) => {
+        $crate::PhysicalProperties::common_physical_mut($d).tcp6_segmentation_offload
+    };
+    (@place device $d:ident generic_segmentation_offload) => {
+        $crate::PhysicalProperties::common_physical_mut($d).generic_segmentation_offload
+    };
+    (@place device $d:ident generic_receive_offload) => {
+        $crate::PhysicalProperties::common_physical_mut($d).generic_receive_offload
+    };
+    (@place device $d:ident large_receive_offload) => {
+        $crate::PhysicalProperties::common_physical_mut($d).large_receive_offload
+    };
+    (@place device $d:ident openvswitch) => {
+        *$crate::__private::OpenVSwitchDevice::openvswitch($d)
+    };
+    (@place $mode:ident $d:ident match) => {
+        $d.r#match
+    };
+    (@place $mode:ident $d:ident type) => {
+        $d.r#type
+    };
+    (@place $mode:ident $d:ident $key:ident) => {
+        $d.$key
+    };
+
+    (@name $name:ident) => {
+        stringify!($name)
+    };
+    (@name $name:literal) => {
+        $name
+    };
+
+    ($($body:tt)*) => {{
+        let mut network = <$crate::NetworkConfig as ::core::default::Default>::default();
+        {
+            let network = &mut network;
+            $crate::netplan!(@body network network; $($body)*);
+        }
+        $crate::NetplanConfig { network }
+    }};
+}
+
+#[doc(hidden)]
+pub mod __private {
+    use super::*;
+    use crate::AddressMapping;
+
+    /// Conversion of the values written in [netplan!](crate::netplan) into the type of the field.
+    pub trait IntoValue<T> {
+        fn into_value(self) -> T;
+    }
+
+    impl<T> IntoValue<T> for T {
+        fn into_value(self) -> T {
+            self
+        }
+    }
+
+    impl<T> IntoValue<Option<T>> for T {
+        fn into_value(self) -> Option<T> {
+            Some(self)
+        }
+    }
+
+    impl IntoValue<String> for &str {
+        fn into_value(self) -> String {
+            self.to_string()
+        }
+    }
+
+    impl IntoValue<Option<String>> for &str {
+        fn into_value(self) -> Option<String> {
+            Some(self.to_string())
+        }
+    }
+
+    /// With the `macaddr` feature, MAC addresses are parsed, panicking if they are not valid.
+    #[cfg(feature = "macaddr")]
+    impl IntoValue<Option<crate::MacAddr>> for &str {
+        fn into_value(self) -> Option<crate::MacAddr> {
+            Some(self.parse().unwrap_or_else(|e| panic!("{e}")))
+        }
+    }
+
+    #[cfg(feature = "secret")]
+    impl IntoValue<Option<crate::Secret<String>>> for &str {
+        fn into_value(self) -> Option<crate::Secret<String>> {
+            Some(self.into())
+        }
+    }
+
+    impl<T, const N: usize> IntoValue<Vec<T>> for [T; N] {
+        fn into_value(self) -> Vec<T> {
+            self.into()
+        }
+    }
+
+    impl<T, const N: usize> IntoValue<Option<Vec<T>>> for [T; N] {
+        fn into_value(self) -> Option<Vec<T>> {
+            Some(self.into())
+        }
+    }
+
+    impl<const N: usize> IntoValue<Vec<String>> for [&str; N] {
+        fn into_value(self) -> Vec<String> {
+            self.iter().map(|s| s.to_string()).collect()
+        }
+    }
+
+    impl<const N: usize> IntoValue<Option<Vec<String>>> for [&str; N] {
+        fn into_value(self) -> Option<Vec<String>> {
+            Some(self.into_value())
+        }
+    }
+
+    impl<const N: usize> IntoValue<Option<Vec<AddressMapping>>> for [&str; N] {
+        fn into_value(self) -> Option<Vec<AddressMapping>> {
+            Some(
+                self.iter()
+                    .map(|s| AddressMapping::Simple(s.to_string()))
+                    .collect(),
+            )
+        }
+    }
+
+    /// The value of a field a block or list is written into, created if it is not set.
+    pub trait Slot {
+        type Target;
+
+        fn slot(&mut self) -> &mut Self::Target;
+    }
+
+    impl<T: Default> Slot for Option<T> {
+        type Target = T;
+
+        fn slot(&mut self) -> &mut T {
+            self.get_or_insert_with(Default::default)
+        }
+    }
+
+    impl<T> Slot for Vec<T> {
+        type Target = Vec<T>;
+
+        fn slot(&mut self) -> &mut Vec<T> {
+            self
+        }
+    }
+
+    pub fn common<T: CommonProperties>(device: &mut T) -> &mut CommonPropertiesAllDevices {
+        device.common_all_mut()
+    }
+
+    /// Devices with openvswitch settings, which are stored with the physical
+    /// properties for ethernets, but directly on the device for virtual devices.
+    pub trait OpenVSwitchDevice {
+        fn openvswitch(&mut self) -> &mut Option<Box<OpenVSwitchConfig>>;
+    }
+
+    impl OpenVSwitchDevice for EthernetConfig {
+        fn openvswitch(&mut self) -> &mut Option<Box<OpenVSwitchConfig>> {
+            &mut self.common_physical_mut().openvswitch
+        }
+    }
+
+    impl OpenVSwitchDevice for BondConfig {
+        fn openvswitch(&mut self) -> &mut Option<Box<OpenVSwitchConfig>> {
+            &mut self.openvswitch
+        }
+    }
+
+    impl OpenVSwitchDevice for BridgeConfig {
+        fn openvswitch(&mut self) -> &mut Option<Box<OpenVSwitchConfig>> {
+            &mut self.openvswitch
+        }
+    }
+
+    impl OpenVSwitchDevice for VlanConfig {
+        fn openvswitch(&mut self) -> &mut Option<Box<OpenVSwitchConfig>> {
+            &mut self.openvswitch
+        }
+    }
+}
+
+#[cfg(all(test, feature = "yaml"))]
+mod test {
+    use crate::{BondMode, NetplanConfig};
+
+    #[test]
+    fn same_as_yaml() {
+        let input = r#"
+            network:
+              version: 2
+              ethernets:
+                eth0:
+                  match:
+                    macaddress: "52:54:00:6b:3c:58"
+                  set-name: lan0
+                  mtu: 9000
+                eth1:
+                  dhcp4: true
+              bonds:
+                bond0:
+                  interfaces: [eth0, eth1]
+                  parameters:
+                    mode: active-backup
+                    primary: eth0
+                  addresses: [10.0.0.2/24]
+                  routes:
+                    - to: default
+                      via: 10.0.0.1
+              vlans:
+                vlan10:
+                  id: 10
+                  link: bond0
+              tunnels:
+                wg0:
+                  mode: wireguard
+                  key: /etc/wireguard/private.key
+                  peers:
+                    - allowed-ips: [0.0.0.0/0]
+                      keys:
+                        public: rlbInAj0qV69CysWPQY7KEBnKxpYCpaWqOs/dLevdWc=
+            "#;
+
+        let expected: NetplanConfig = serde_yaml::from_str(input).unwrap();
+        let config = netplan! {
+            ethernets {
+                eth0 {
+                    match { macaddress: "52:54:00:6b:3c:58" },
+                    set_name: "lan0",
+                    mtu: 9000,
+                },
+                "eth1" { dhcp4: true },
+            },
+            bonds {
+                bond0 {
+                    interfaces: ["eth0", "eth1"],
+                    parameters { mode: BondMode::ActiveBackup, primary: "eth0" },
+                    addresses: ["10.0.0.2/24"],
+                    routes: [{ to: "default", via: "10.0.0.1" }],
+                },
+            },
+            vlans {
+                vlan10 { id: 10, link: "bond0" },
+            },
+            tunnels {
+                wg0 {
+                    mode: crate::TunnelMode::Wireguard,
+                    key: crate::TunnelKey::Simple("/etc/wireguard/private.key".into()),
+                    peers: [{
+                        allowed_ips: ["0.0.0.0/0"],
+                        keys { public: "rlbInAj0qV69CysWPQY7KEBnKxpYCpaWqOs/dLevdWc=" },
+                    }],
+                },
+            },
+        };
+
+        // Deserializing yields empty common blocks where the macro leaves them unset,
+        // so compare the serialized form
+        assert_eq!(
+            serde_yaml::to_value(&config).unwrap(),
+            serde_yaml::to_value(&expected).unwrap()
+        );
+    }
+}
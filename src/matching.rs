@@ -0,0 +1,118 @@
+//! Predicting which definition claims an interface, by evaluating match rules the way
+//! netplan does.
+
+use crate::{DeviceKind, MacAddr, MatchConfig, NetworkConfig};
+
+/// The properties of a real interface that match rules are evaluated against, e.g. as
+/// reported by udev.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InterfaceAttrs {
+    /// The current name of the interface.
+    pub name: String,
+    /// The MAC address of the interface.
+    pub macaddress: Option<String>,
+    /// The kernel driver, the `DRIVER` udev property.
+    pub driver: Option<String>,
+}
+
+impl InterfaceAttrs {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ..Self::default()
+        }
+    }
+
+    pub fn macaddress(mut self, macaddress: impl Into<String>) -> Self {
+        self.macaddress = Some(macaddress.into());
+        self
+    }
+
+    pub fn driver(mut self, driver: impl Into<String>) -> Self {
+        self.driver = Some(driver.into());
+        self
+    }
+}
+
+impl MatchConfig {
+    /// Whether the interface satisfies the match rules. Every rule which is set must match:
+    /// `name` as a glob, `macaddress` exactly, ignoring case, and `driver` when any of its
+    /// globs matches. Match rules without any rule match every interface. An interface
+    /// whose MAC address or driver is not known does not match rules on it.
+    ///
+    /// ```
+    /// use netplan_types::{InterfaceAttrs, MatchConfig};
+    ///
+    /// let rules = MatchConfig {
+    ///     name: Some("en*".to_string()),
+    ///     driver: Some(vec!["ixgbe".to_string(), "i40e".to_string()]),
+    ///     ..Default::default()
+    /// };
+    ///
+    /// assert!(rules.matches(&InterfaceAttrs::new("enp1s0").driver("i40e")));
+    /// assert!(!rules.matches(&InterfaceAttrs::new("enp1s0").driver("e1000e")));
+    /// assert!(!rules.matches(&InterfaceAttrs::new("wlp2s0").driver("ixgbe")));
+    /// ```
+    pub fn matches(&self, interface: &InterfaceAttrs) -> bool {
+        let name = self
+            .name
+            .as_deref()
+            .is_none_or(|glob| crate::glob::matches(glob, &interface.name));
+
+        let macaddress = self.macaddress.as_ref().is_none_or(|expected| {
+            let expected = expected.to_string();
+            interface.macaddress.as_deref().is_some_and(|actual| {
+                match (expected.parse::<MacAddr>(), actual.parse::<MacAddr>()) {
+                    (Ok(expected), Ok(actual)) => expected == actual,
+                    _ => expected.eq_ignore_ascii_case(actual),
+                }
+            })
+        });
+
+        let driver = self.driver.as_ref().is_none_or(|globs| {
+            interface
+                .driver
+                .as_deref()
+                .is_some_and(|driver| globs.iter().any(|glob| crate::glob::matches(glob, driver)))
+        });
+
+        name && macaddress && driver
+    }
+}
+
+impl NetworkConfig {
+    /// The physical device definition which claims the interface: one whose match rules
+    /// the interface satisfies, or one without match rules whose ID is the name of the
+    /// interface. If several definitions apply, the one with the lexicographically first
+    /// ID claims the interface, as its configuration is applied first.
+    ///
+    /// ```
+    /// use netplan_types::{DeviceKind, InterfaceAttrs, NetplanConfig};
+    ///
+    /// let config = NetplanConfig::from_yaml(r#"
+    /// network:
+    ///   version: 2
+    ///   ethernets:
+    ///     uplink:
+    ///       match: {macaddress: "52:54:00:6b:3c:58"}
+    ///       set-name: uplink0
+    ///     eth1: {dhcp4: true}
+    /// "#).unwrap();
+    ///
+    /// let nic = InterfaceAttrs::new("enp1s0").macaddress("52:54:00:6B:3C:58");
+    /// assert_eq!(config.network.claiming_device(&nic), Some((DeviceKind::Ethernet, "uplink")));
+    /// assert_eq!(config.network.claiming_device(&InterfaceAttrs::new("eth2")), None);
+    /// ```
+    pub fn claiming_device(&self, interface: &InterfaceAttrs) -> Option<(DeviceKind, &str)> {
+        self.devices()
+            .filter(|(kind, _, _)| kind.is_physical())
+            .filter(|(_, name, device)| {
+                match device.common_physical().and_then(|p| p.r#match.as_ref()) {
+                    Some(rules) => rules.matches(interface),
+                    None => *name == interface.name,
+                }
+            })
+            .map(|(kind, name, _)| (kind, name))
+            .min_by_key(|(_, name)| *name)
+    }
+}
@@ -0,0 +1,293 @@
+//! Merging of configurations, following the way netplan combines multiple files.
+
+use crate::{
+    AccessPointConfig, AuthConfig, BondConfig, BondParameters, BridgeConfig, BridgeParameters,
+    CommonPropertiesAllDevices, CommonPropertiesPhysicalDeviceType, ControllerConfig,
+    DhcpOverrides, DummyDeviceConfig, EthernetConfig, MatchConfig, ModemConfig, NameserverConfig,
+    NetplanConfig, NetworkConfig, NetworkManagerSettings, NmDeviceConfig, OpenVSwitchConfig,
+    SslConfig, TunnelConfig, VlanConfig, VrfsConfig, WifiConfig,
+};
+use std::collections::{BTreeMap, HashMap};
+
+impl NetplanConfig {
+    /// Merge a configuration which shadows this one into it, the way netplan combines
+    /// its configuration files: `overlay` takes the place of a lexicographically later file.
+    ///
+    /// Mappings are merged key by key, so devices and properties which are only defined
+    /// in one of the configurations are retained. Scalars and sequences set in `overlay`
+    /// replace those in `self`; sequences are not concatenated.
+    pub fn merge(&mut self, overlay: NetplanConfig) {
+        self.network.merge(overlay.network);
+    }
+}
+
+impl NetworkConfig {
+    /// Merge a configuration which shadows this one into it, see [NetplanConfig::merge].
+    pub fn merge(&mut self, overlay: NetworkConfig) {
+        Merge::merge(self, overlay);
+    }
+}
+
+/// Merging of a value with one shadowing it.
+pub(crate) trait Merge {
+    fn merge(&mut self, overlay: Self);
+}
+
+impl<T: Merge> Merge for Option<T> {
+    fn merge(&mut self, overlay: Self) {
+        match (self.as_mut(), overlay) {
+            (Some(value), Some(overlay)) => value.merge(overlay),
+            (None, Some(overlay)) => *self = Some(overlay),
+            (_, None) => {}
+        }
+    }
+}
+
+impl<T: Merge> Merge for Box<T> {
+    fn merge(&mut self, overlay: Self) {
+        T::merge(self, *overlay);
+    }
+}
+
+impl<T: Merge> Merge for HashMap<String, T> {
+    fn merge(&mut self, overlay: Self) {
+        for (key, overlay) in overlay {
+            match self.get_mut(&key) {
+                Some(value) => value.merge(overlay),
+                None => {
+                    self.insert(key, overlay);
+                }
+            }
+        }
+    }
+}
+
+/// Passthrough settings are merged key by key, like any other mapping.
+impl Merge for BTreeMap<String, String> {
+    fn merge(&mut self, overlay: Self) {
+        self.extend(overlay);
+    }
+}
+
+/// Replace the value if the overlay sets it.
+fn replace<T>(value: &mut Option<T>, overlay: Option<T>) {
+    if overlay.is_some() {
+        *value = overlay;
+    }
+}
+
+/// Implement [Merge] for a struct. Every field must be listed, as either `set` (always
+/// taken from the overlay), `replace` (taken from the overlay if set) or `merge`
+/// (merged recursively).
+macro_rules! impl_merge {
+    ($ty:ident { $(set: $($set:ident)*;)? $(replace: $($replace:ident)*;)? $(merge: $($merge:ident)*;)? }) => {
+        impl Merge for $ty {
+            fn merge(&mut self, overlay: Self) {
+                let $ty {
+                    $($($set,)*)?
+                    $($($replace,)*)?
+                    $($($merge,)*)?
+                } = overlay;
+
+                $($(self.$set = $set;)*)?
+                $($(replace(&mut self.$replace, $replace);)*)?
+                $($(self.$merge.merge($merge);)*)?
+            }
+        }
+    };
+}
+
+impl_merge!(NetworkConfig {
+    set: version;
+    replace: renderer;
+    merge: openvswitch ethernets wifis modems bonds bridges vlans tunnels vrfs dummy_devices nm_devices;
+});
+
+impl_merge!(EthernetConfig {
+    replace: link virtual_function_count embedded_switch_mode delay_virtual_functions_rebind;
+    merge: common_physical common_all;
+});
+
+impl_merge!(WifiConfig {
+    replace: wakeonwlan regulatory_domain;
+    merge: access_points common_physical common_all;
+});
+
+impl_merge!(ModemConfig {
+    replace: apn auto_config device_id network_id number password pin sim_id sim_operator_id username;
+    merge: common_physical common_all;
+});
+
+impl_merge!(BondConfig {
+    replace: interfaces;
+    merge: parameters openvswitch common_all;
+});
+
+impl_merge!(BridgeConfig {
+    replace: interfaces;
+    merge: parameters openvswitch common_all;
+});
+
+impl_merge!(VlanConfig {
+    replace: id link;
+    merge: openvswitch common_all;
+});
+
+impl_merge!(VrfsConfig {
+    set: table interfaces;
+    merge: common_all;
+});
+
+impl_merge!(DummyDeviceConfig {
+    merge: common_all;
+});
+
+impl_merge!(NmDeviceConfig {
+    merge: common_all;
+});
+
+impl_merge!(CommonPropertiesAllDevices {
+    replace: renderer dhcp4 dhcp6 ipv6_mtu ipv6_privacy link_local ignore_carrier critical dhcp_identifier accept_ra addresses ipv6_address_generation ipv6_address_token gateway4 gateway6 macaddress mtu optional optional_addresses activation_mode routes routing_policy;
+    merge: dhcp4_overrides dhcp6_overrides nameservers networkmanager;
+});
+
+impl_merge!(NetworkManagerSettings {
+    replace: name uuid stable_id device;
+    merge: passthrough;
+});
+
+impl_merge!(CommonPropertiesPhysicalDeviceType {
+    replace: set_name wakeonlan emit_lldp receive_checksum_offload transmit_checksum_offload tcp_segmentation_offload tcp6_segmentation_offload generic_segmentation_offload generic_receive_offload large_receive_offload;
+    merge: r#match openvswitch;
+});
+
+impl_merge!(MatchConfig {
+    replace: name macaddress driver;
+});
+
+impl_merge!(DhcpOverrides {
+    replace: use_dns use_ntp send_hostname use_hostname use_mtu hostname use_routes route_metric use_domains;
+});
+
+impl_merge!(NameserverConfig {
+    replace: addresses search;
+});
+
+impl_merge!(BondParameters {
+    replace: mode lacp_rate mii_monitor_interval min_links transmit_hash_policy ad_select all_slaves_active arp_interval arp_ip_targets arp_validate arp_all_targets up_delay down_delay fail_over_mac_policy gratuitous_arp packets_per_slave primary_reselect_policy resend_igmp learn_packet_interval primary;
+});
+
+impl_merge!(BridgeParameters {
+    replace: ageing_time priority port_priority forward_delay hello_time max_age path_cost stp;
+});
+
+impl_merge!(AccessPointConfig {
+    replace: password mode bssid band channel hidden;
+    merge: auth networkmanager;
+});
+
+impl_merge!(AuthConfig {
+    replace: key_management password method identity anonymous_identity ca_certificate client_certificate client_key client_key_password phase2_auth;
+});
+
+impl_merge!(OpenVSwitchConfig {
+    replace: external_ids other_config lacp fail_mode mcast_snooping protocols rtsp ports;
+    merge: controller ssl;
+});
+
+impl_merge!(SslConfig {
+    replace: ca_cert certificate private_key;
+});
+
+impl_merge!(ControllerConfig {
+    replace: addresses connection_mode;
+});
+
+impl Merge for TunnelConfig {
+    fn merge(&mut self, overlay: Self) {
+        let TunnelConfig {
+            mode,
+            id,
+            local,
+            remote,
+            ttl,
+            key,
+            mark,
+            port,
+            peers,
+            common_all,
+        } = overlay;
+
+        replace(&mut self.mode, mode);
+        replace(&mut self.id, id);
+        replace(&mut self.local, local);
+        replace(&mut self.remote, remote);
+        replace(&mut self.ttl, ttl);
+        replace(&mut self.key, key);
+        replace(&mut self.mark, mark);
+        replace(&mut self.port, port);
+        // An absent peers sequence deserializes as empty, rather than as None
+        if !peers.is_empty() {
+            self.peers = peers;
+        }
+        self.common_all.merge(common_all);
+    }
+}
+
+#[cfg(all(test, feature = "yaml"))]
+mod test {
+    use crate::NetplanConfig;
+
+    #[test]
+    fn shadowing() {
+        let base = r#"
+            network:
+              version: 2
+              renderer: networkd
+              ethernets:
+                eth0:
+                  dhcp4: true
+                  addresses: [10.0.0.2/24, 10.0.0.3/24]
+                  nameservers:
+                    addresses: [10.0.0.1]
+                    search: [example.com]
+                eth1:
+                  dhcp4: true
+            "#;
+        let overlay = r#"
+            network:
+              version: 2
+              ethernets:
+                eth0:
+                  addresses: [10.0.0.4/24]
+                  nameservers:
+                    search: [example.org]
+              bonds:
+                bond0:
+                  interfaces: [eth1]
+            "#;
+        let expected = r#"
+            network:
+              version: 2
+              renderer: networkd
+              ethernets:
+                eth0:
+                  dhcp4: true
+                  addresses: [10.0.0.4/24]
+                  nameservers:
+                    addresses: [10.0.0.1]
+                    search: [example.org]
+                eth1:
+                  dhcp4: true
+              bonds:
+                bond0:
+                  interfaces: [eth1]
+            "#;
+
+        let mut config: NetplanConfig = serde_yaml::from_str(base).unwrap();
+        config.merge(serde_yaml::from_str(overlay).unwrap());
+
+        let expected: NetplanConfig = serde_yaml::from_str(expected).unwrap();
+        assert_eq!(config, expected);
+    }
+}
@@ -0,0 +1,195 @@
+//! Rewriting deprecated constructs into their current equivalents.
+
+use crate::validation::default_route_family;
+use crate::{
+    AuthConfig, CommonProperties, KeyManagmentMode, NetplanConfig, NetworkConfig, RoutingConfig,
+};
+
+/// A deprecated construct which was rewritten by [NetplanConfig::modernize].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Migration {
+    /// Path to the deprecated field, in netplan YAML notation, e.g. `network.ethernets.eth0.gateway4`.
+    pub path: String,
+    /// Human readable description of the rewrite.
+    pub message: String,
+}
+
+impl NetplanConfig {
+    /// Rewrite deprecated constructs into their current equivalents, returning what was changed.
+    /// See [NetworkConfig::modernize].
+    pub fn modernize(&mut self) -> Vec<Migration> {
+        self.network.modernize()
+    }
+}
+
+impl NetworkConfig {
+    /// Rewrite deprecated constructs into their current equivalents, returning what was changed:
+    ///
+    /// - `gateway4` and `gateway6` become a default route. If the device already has a default
+    ///   route for the address family, the gateway is left in place, as the two conflict.
+    /// - The scalar `password` of an access point becomes an `auth` block with WPA-PSK key
+    ///   management. If the access point has an `auth` block with a password already, the
+    ///   scalar password is left in place.
+    ///
+    /// ```
+    /// use netplan_types::NetplanConfig;
+    ///
+    /// let mut config: NetplanConfig = serde_yaml::from_str(r#"
+    ///     network:
+    ///       version: 2
+    ///       ethernets:
+    ///         eth0:
+    ///           addresses: [10.0.0.2/24]
+    ///           gateway4: 10.0.0.1
+    ///     "#).unwrap();
+    ///
+    /// let migrations = config.modernize();
+    /// assert_eq!(migrations[0].path, "network.ethernets.eth0.gateway4");
+    /// assert!(config.validate().is_empty());
+    /// ```
+    pub fn modernize(&mut self) -> Vec<Migration> {
+        let mut migrations = Vec::new();
+
+        for (kind, name, mut device) in self.devices_mut() {
+            if device.common_all().is_none() {
+                continue;
+            }
+
+            let common = device.common_all_mut();
+            let path = format!("network.{}.{name}", kind.section());
+            for (key, gateway, default) in [
+                ("gateway4", &mut common.gateway4, "0.0.0.0/0"),
+                ("gateway6", &mut common.gateway6, "::/0"),
+            ] {
+                if let Some(via) = migrate_gateway(gateway, &mut common.routes, default) {
+                    migrations.push(Migration {
+                        path: format!("{path}.{key}"),
+                        message: format!("Replaced {key} with a route to {default} via {via}"),
+                    });
+                }
+            }
+        }
+
+        for (name, wifi) in self.wifis.iter_mut().flatten() {
+            for (ssid, access_point) in wifi.access_points.iter_mut().flatten() {
+                let has_auth_password = access_point
+                    .auth
+                    .as_ref()
+                    .is_some_and(|auth| auth.password.is_some());
+                if has_auth_password {
+                    continue;
+                }
+
+                let Some(password) = access_point.password.take() else {
+                    continue;
+                };
+
+                let auth = access_point.auth.get_or_insert_with(AuthConfig::default);
+                auth.key_management.get_or_insert(KeyManagmentMode::Psk);
+                auth.password = Some(password);
+
+                migrations.push(Migration {
+                    path: format!("network.wifis.{name}.access-points.{ssid}.password"),
+                    message: "Moved password into the auth block".to_string(),
+                });
+            }
+        }
+
+        migrations.sort_by(|a, b| a.path.cmp(&b.path));
+        migrations
+    }
+}
+
+/// Replace a gateway with a default route, unless the routes contain a default route for
+/// the address family already. Returns the gateway if it was replaced.
+fn migrate_gateway(
+    gateway: &mut Option<String>,
+    routes: &mut Option<Vec<RoutingConfig>>,
+    default: &str,
+) -> Option<String> {
+    let ipv6 = default == "::/0";
+    let has_default = routes
+        .iter()
+        .flatten()
+        .any(|route| default_route_family(route) == Some(ipv6));
+    if has_default {
+        return None;
+    }
+
+    let via = gateway.take()?;
+    routes.get_or_insert_with(Vec::new).push(RoutingConfig {
+        to: Some(default.to_string()),
+        via: Some(via.clone()),
+        ..Default::default()
+    });
+
+    Some(via)
+}
+
+#[cfg(all(test, feature = "yaml"))]
+mod test {
+    use crate::NetplanConfig;
+
+    #[test]
+    fn modernize() {
+        let input = r#"
+            network:
+              version: 2
+              ethernets:
+                eth0:
+                  addresses: [10.0.0.2/24, "fd00::2/64"]
+                  gateway4: 10.0.0.1
+                  gateway6: fd00::1
+                  routes:
+                    - to: ::/0
+                      via: fd00::3
+              wifis:
+                wlan0:
+                  access-points:
+                    home:
+                      password: hunter22
+                    cafe: {}
+            "#;
+        let expected = r#"
+            network:
+              version: 2
+              ethernets:
+                eth0:
+                  addresses: [10.0.0.2/24, "fd00::2/64"]
+                  gateway6: fd00::1
+                  routes:
+                    - to: ::/0
+                      via: fd00::3
+                    - to: 0.0.0.0/0
+                      via: 10.0.0.1
+              wifis:
+                wlan0:
+                  access-points:
+                    home:
+                      auth:
+                        key-management: psk
+                        password: hunter22
+                    cafe: {}
+            "#;
+
+        let mut config: NetplanConfig = serde_yaml::from_str(input).unwrap();
+        let paths = config
+            .modernize()
+            .into_iter()
+            .map(|migration| migration.path)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            paths,
+            vec![
+                "network.ethernets.eth0.gateway4",
+                "network.wifis.wlan0.access-points.home.password"
+            ]
+        );
+
+        let expected: NetplanConfig = serde_yaml::from_str(expected).unwrap();
+        assert_eq!(
+            serde_yaml::to_value(&config).unwrap(),
+            serde_yaml::to_value(&expected).unwrap()
+        );
+    }
+}
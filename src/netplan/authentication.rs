@@ -4,13 +4,23 @@ use serde::{Deserialize, Serialize};
 #[cfg(feature = "derive_builder")]
 use derive_builder::Builder;
 
+use crate::SecretString;
+
 /// Netplan supports advanced authentication settings for ethernet and wifi
 /// interfaces, as well as individual wifi networks, by means of the auth block.
-#[derive(Default, Debug, Clone, PartialEq, Eq)]
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "derive_builder", derive(Builder))]
+#[cfg_attr(
+    feature = "derive_builder",
+    builder(setter(strip_option, into), default)
+)]
 #[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 pub struct AuthConfig {
     /// The supported key management modes are none (no key management);
     /// psk (WPA with pre-shared key, common for home wifi); eap (WPA
@@ -20,7 +30,7 @@ pub struct AuthConfig {
     pub key_management: Option<KeyManagmentMode>,
     /// The password string for EAP, or the pre-shared key for WPA-PSK.
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
-    pub password: Option<String>,
+    pub password: Option<SecretString>,
     /// The EAP method to use. The supported EAP methods are tls (TLS),
     /// peap (Protected EAP), and ttls (Tunneled TLS).
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
@@ -47,15 +57,19 @@ pub struct AuthConfig {
     /// Password to use to decrypt the private key specified in
     /// client-key if it is encrypted.
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
-    pub client_key_password: Option<String>,
+    pub client_key_password: Option<SecretString>,
     /// Phase 2 authentication mechanism.
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
     pub phase2_auth: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 pub enum AuthMethod {
     #[cfg_attr(feature = "serde", serde(rename = "tls"))]
     Tls,
@@ -65,9 +79,25 @@ pub enum AuthMethod {
     Ttls,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+impl AuthMethod {
+    pub const VARIANTS: &'static [Self] = &[Self::Tls, Self::Peap, Self::Ttls];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Tls => "tls",
+            Self::Peap => "peap",
+            Self::Ttls => "ttls",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 pub enum KeyManagmentMode {
     #[cfg_attr(feature = "serde", serde(rename = "none"))]
     None,
@@ -81,3 +111,23 @@ pub enum KeyManagmentMode {
     #[cfg_attr(feature = "serde", serde(rename = "802.1x"))]
     EightZeroTwoDotOneX,
 }
+
+impl KeyManagmentMode {
+    pub const VARIANTS: &'static [Self] = &[
+        Self::None,
+        Self::Psk,
+        Self::Eap,
+        Self::Sae,
+        Self::EightZeroTwoDotOneX,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Psk => "psk",
+            Self::Eap => "eap",
+            Self::Sae => "sae",
+            Self::EightZeroTwoDotOneX => "802.1x",
+        }
+    }
+}
@@ -4,13 +4,21 @@ use serde::{Deserialize, Serialize};
 #[cfg(feature = "derive_builder")]
 use derive_builder::Builder;
 
-use crate::CommonPropertiesAllDevices;
+use crate::{CommonPropertiesAllDevices, OpenVSwitchConfig};
 
-#[derive(Default, Debug, Clone, PartialEq, Eq)]
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "derive_builder", derive(Builder))]
+#[cfg_attr(
+    feature = "derive_builder",
+    builder(setter(strip_option, into), default)
+)]
 #[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 pub struct BondConfig {
     /// All devices matching this ID list will be added to the bond.
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
@@ -21,18 +29,31 @@ pub struct BondConfig {
     /// be qualified using a time suffix (such as “s” for seconds, “ms” for
     /// milliseconds) to allow for more control over its behavior.
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
-    pub parameters: Option<BondParameters>,
+    pub parameters: Option<Box<BondParameters>>,
+    /// This provides additional configuration for the network device for openvswitch.
+    /// If openvswitch is not available on the system, netplan treats the presence of
+    /// openvswitch configuration as an error.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub openvswitch: Option<Box<OpenVSwitchConfig>>,
     /// Common properties for all devices
     #[cfg_attr(feature = "serde", serde(flatten))]
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
-    pub common_all: Option<CommonPropertiesAllDevices>,
+    pub common_all: Option<Box<CommonPropertiesAllDevices>>,
 }
 
-#[derive(Default, Debug, Clone, PartialEq, Eq)]
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "derive_builder", derive(Builder))]
+#[cfg_attr(
+    feature = "derive_builder",
+    builder(setter(strip_option, into), default)
+)]
 #[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 pub struct BondParameters {
     /// Set the bonding mode used for the interfaces. The default is
     /// balance-rr (round robin). Possible values are balance-rr,
@@ -179,9 +200,13 @@ pub struct BondParameters {
 /// balance-tlb, and balance-alb.
 /// For OpenVSwitch active-backup and the additional modes
 /// balance-tcp and balance-slb are supported.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 pub enum BondMode {
     #[cfg_attr(feature = "serde", serde(rename = "balance-rr"))]
     BalanceRr,
@@ -200,12 +225,40 @@ pub enum BondMode {
     BalanceAlb,
 }
 
+impl BondMode {
+    pub const VARIANTS: &'static [Self] = &[
+        Self::BalanceRr,
+        Self::ActiveBackup,
+        Self::BalanceXor,
+        Self::Broadcast,
+        Self::EightZeroTwoDotThreeAD,
+        Self::BalanceTlb,
+        Self::BalanceAlb,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::BalanceRr => "balance-rr",
+            Self::ActiveBackup => "active-backup",
+            Self::BalanceXor => "balance-xor",
+            Self::Broadcast => "broadcast",
+            Self::EightZeroTwoDotThreeAD => "802.3ad",
+            Self::BalanceTlb => "balance-tlb",
+            Self::BalanceAlb => "balance-alb",
+        }
+    }
+}
+
 /// Set the rate at which LACPDUs are transmitted. This is only useful
 /// in 802.3ad mode. Possible values are slow (30 seconds, default),
 /// and fast (every second).
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 pub enum LacpRate {
     #[cfg_attr(feature = "serde", serde(rename = "slow"))]
     Slow,
@@ -213,13 +266,28 @@ pub enum LacpRate {
     Fast,
 }
 
+impl LacpRate {
+    pub const VARIANTS: &'static [Self] = &[Self::Slow, Self::Fast];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Slow => "slow",
+            Self::Fast => "fast",
+        }
+    }
+}
+
 /// Specifies the transmit hash policy for the selection of slaves. This
 /// is only useful in balance-xor, 802.3ad and balance-tlb modes.
 /// Possible values are layer2, layer3+4, layer2+3,
 /// encap2+3, and encap3+4.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 pub enum TransmitHashPolicy {
     #[cfg_attr(feature = "serde", serde(rename = "layer2"))]
     Layer2,
@@ -233,12 +301,36 @@ pub enum TransmitHashPolicy {
     Encap3Plus4,
 }
 
+impl TransmitHashPolicy {
+    pub const VARIANTS: &'static [Self] = &[
+        Self::Layer2,
+        Self::Layer3Plus4,
+        Self::Layer2Plus3,
+        Self::Encap2Plus3,
+        Self::Encap3Plus4,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Layer2 => "layer2",
+            Self::Layer3Plus4 => "layer3+4",
+            Self::Layer2Plus3 => "layer2+3",
+            Self::Encap2Plus3 => "encap2+3",
+            Self::Encap3Plus4 => "encap3+4",
+        }
+    }
+}
+
 /// Set the aggregation selection mode. Possible values are stable,
 /// bandwidth, and count. This option is only used in 802.3ad
 /// mode.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 pub enum AdSelect {
     #[cfg_attr(feature = "serde", serde(rename = "stable"))]
     Stable,
@@ -248,12 +340,28 @@ pub enum AdSelect {
     Count,
 }
 
+impl AdSelect {
+    pub const VARIANTS: &'static [Self] = &[Self::Stable, Self::Bandwidth, Self::Count];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Stable => "stable",
+            Self::Bandwidth => "bandwidth",
+            Self::Count => "count",
+        }
+    }
+}
+
 /// Configure how ARP replies are to be validated when using ARP link
 /// monitoring. Possible values are none, active, backup,
 /// and all.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 pub enum ArpValidate {
     #[cfg_attr(feature = "serde", serde(rename = "none"))]
     None,
@@ -265,13 +373,30 @@ pub enum ArpValidate {
     All,
 }
 
+impl ArpValidate {
+    pub const VARIANTS: &'static [Self] = &[Self::None, Self::Active, Self::Backup, Self::All];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Active => "active",
+            Self::Backup => "backup",
+            Self::All => "all",
+        }
+    }
+}
+
 /// Specify whether to use any ARP IP target being up as sufficient for
 /// a slave to be considered up; or if all the targets must be up. This
 /// is only used for active-backup mode when arp-validate is
 /// enabled. Possible values are any and all.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 pub enum ArpAllTargets {
     #[cfg_attr(feature = "serde", serde(rename = "any"))]
     Any,
@@ -279,28 +404,59 @@ pub enum ArpAllTargets {
     All,
 }
 
+impl ArpAllTargets {
+    pub const VARIANTS: &'static [Self] = &[Self::Any, Self::All];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Any => "any",
+            Self::All => "all",
+        }
+    }
+}
+
 /// Set whether to set all slaves to the same MAC address when adding
 /// them to the bond, or how else the system should handle MAC addresses.
 /// The possible values are none, active, and follow.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 pub enum FailOverMacPolicy {
     #[cfg_attr(feature = "serde", serde(rename = "none"))]
     None,
-    #[cfg_attr(feature = "serde", serde(rename = "activv"))]
+    #[cfg_attr(feature = "serde", serde(rename = "active"))]
     Active,
     #[cfg_attr(feature = "serde", serde(rename = "follow"))]
     Follow,
 }
 
+impl FailOverMacPolicy {
+    pub const VARIANTS: &'static [Self] = &[Self::None, Self::Active, Self::Follow];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Active => "active",
+            Self::Follow => "follow",
+        }
+    }
+}
+
 /// Set the reselection policy for the primary slave. On failure of the
 /// active slave, the system will use this policy to decide how the new
 /// active slave will be chosen and how recovery will be handled. The
 /// possible values are always, better, and failure.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 pub enum PrimaryReselectPolicy {
     #[cfg_attr(feature = "serde", serde(rename = "always"))]
     Always,
@@ -309,3 +465,15 @@ pub enum PrimaryReselectPolicy {
     #[cfg_attr(feature = "serde", serde(rename = "failure"))]
     Failure,
 }
+
+impl PrimaryReselectPolicy {
+    pub const VARIANTS: &'static [Self] = &[Self::Always, Self::Better, Self::Failure];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Always => "always",
+            Self::Better => "better",
+            Self::Failure => "failure",
+        }
+    }
+}
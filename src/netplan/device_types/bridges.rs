@@ -4,13 +4,21 @@ use serde::{Deserialize, Serialize};
 #[cfg(feature = "derive_builder")]
 use derive_builder::Builder;
 
-use crate::CommonPropertiesAllDevices;
+use crate::{CommonPropertiesAllDevices, OpenVSwitchConfig};
 
-#[derive(Default, Debug, Clone, PartialEq, Eq)]
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "derive_builder", derive(Builder))]
+#[cfg_attr(
+    feature = "derive_builder",
+    builder(setter(strip_option, into), default)
+)]
 #[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 pub struct BridgeConfig {
     /// All devices matching this ID list will be added to the bridge. This may
     /// be an empty list, in which case the bridge will be brought online with
@@ -23,11 +31,16 @@ pub struct BridgeConfig {
     /// be qualified using a time suffix (such as “s” for seconds, “ms” for
     /// milliseconds) to allow for more control over its behavior.
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
-    pub parameters: Option<BridgeParameters>,
+    pub parameters: Option<Box<BridgeParameters>>,
+    /// This provides additional configuration for the network device for openvswitch.
+    /// If openvswitch is not available on the system, netplan treats the presence of
+    /// openvswitch configuration as an error.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub openvswitch: Option<Box<OpenVSwitchConfig>>,
     /// Common properties for all devices
     #[cfg_attr(feature = "serde", serde(flatten))]
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
-    pub common_all: Option<CommonPropertiesAllDevices>,
+    pub common_all: Option<Box<CommonPropertiesAllDevices>>,
 }
 
 /// Customization parameters for special bridging options. Time intervals
@@ -35,11 +48,19 @@ pub struct BridgeConfig {
 /// default value type is specified below. If necessary, time intervals can
 /// be qualified using a time suffix (such as “s” for seconds, “ms” for
 /// milliseconds) to allow for more control over its behavior.
-#[derive(Default, Debug, Clone, PartialEq, Eq)]
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "derive_builder", derive(Builder))]
+#[cfg_attr(
+    feature = "derive_builder",
+    builder(setter(strip_option, into), default)
+)]
 #[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 pub struct BridgeParameters {
     /// Set the period of time to keep a MAC address in the forwarding
     /// database after a packet is received. This maps to the AgeingTimeSec=
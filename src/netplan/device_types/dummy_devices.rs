@@ -10,14 +10,22 @@ use crate::CommonPropertiesAllDevices;
 ///
 /// Structure: The key consists of a mapping of interface names.
 /// Dummy devices are virtual devices that can be used to route packets to without actually transmitting them.
-#[derive(Default, Debug, Clone, PartialEq, Eq)]
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "derive_builder", derive(Builder))]
+#[cfg_attr(
+    feature = "derive_builder",
+    builder(setter(strip_option, into), default)
+)]
 #[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 pub struct DummyDeviceConfig {
     /// Common properties for all devices
     #[cfg_attr(feature = "serde", serde(flatten))]
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
-    pub common_all: Option<CommonPropertiesAllDevices>,
+    pub common_all: Option<Box<CommonPropertiesAllDevices>>,
 }
@@ -4,13 +4,23 @@ use serde::{Deserialize, Serialize};
 #[cfg(feature = "derive_builder")]
 use derive_builder::Builder;
 
-use crate::{CommonPropertiesAllDevices, CommonPropertiesPhysicalDeviceType};
+use crate::{CommonPropertiesAllDevices, CommonPropertiesPhysicalDeviceType, NameserverConfig};
+use ipnet::IpNet;
+use std::net::IpAddr;
 
-#[derive(Default, Debug, Clone, PartialEq, Eq)]
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "derive_builder", derive(Builder))]
+#[cfg_attr(
+    feature = "derive_builder",
+    builder(setter(strip_option, into), default)
+)]
 #[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 pub struct EthernetConfig {
     /// (SR-IOV devices only) The link property declares the device as a
     /// Virtual Function of the selected Physical Function device, as identified
@@ -39,18 +49,86 @@ pub struct EthernetConfig {
     /// Common properties for physical device types
     #[cfg_attr(feature = "serde", serde(flatten))]
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
-    pub common_physical: Option<CommonPropertiesPhysicalDeviceType>,
+    pub common_physical: Option<Box<CommonPropertiesPhysicalDeviceType>>,
     /// Common properties for all devices
     #[cfg_attr(feature = "serde", serde(flatten))]
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
-    pub common_all: Option<CommonPropertiesAllDevices>,
+    pub common_all: Option<Box<CommonPropertiesAllDevices>>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+impl EthernetConfig {
+    /// An ethernet device configured with DHCP for IPv4.
+    pub fn dhcp() -> Self {
+        Self {
+            common_all: Some(Box::new(CommonPropertiesAllDevices {
+                dhcp4: Some(true),
+                ..Default::default()
+            })),
+            ..Default::default()
+        }
+    }
+
+    /// An ethernet device with a static address, a default gateway and DNS servers.
+    ///
+    /// ```
+    /// use netplan_types::{CommonProperties, EthernetConfig};
+    ///
+    /// let ethernet = EthernetConfig::static_ip(
+    ///     "10.0.0.2/24".parse().unwrap(),
+    ///     "10.0.0.1".parse().unwrap(),
+    ///     ["1.1.1.1".parse().unwrap()],
+    /// );
+    ///
+    /// assert!(ethernet.has_address("10.0.0.2/24".parse().unwrap()));
+    /// assert_eq!(ethernet.routes().unwrap()[0].via.as_deref(), Some("10.0.0.1"));
+    /// ```
+    pub fn static_ip(
+        address: IpNet,
+        gateway: IpAddr,
+        nameservers: impl IntoIterator<Item = IpAddr>,
+    ) -> Self {
+        let mut common = CommonPropertiesAllDevices::default();
+        common.add_address(address);
+        common.set_default_gateway(gateway, None);
+
+        let nameservers = nameservers
+            .into_iter()
+            .map(|address| address.to_string())
+            .collect::<Vec<_>>();
+        if !nameservers.is_empty() {
+            common.nameservers = Some(NameserverConfig {
+                addresses: Some(nameservers),
+                search: None,
+            });
+        }
+
+        Self {
+            common_all: Some(Box::new(common)),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 pub enum EmbeddedSwitchMode {
     Switchdev,
     Legacy,
 }
+
+impl EmbeddedSwitchMode {
+    pub const VARIANTS: &'static [Self] = &[Self::Switchdev, Self::Legacy];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Switchdev => "switchdev",
+            Self::Legacy => "legacy",
+        }
+    }
+}
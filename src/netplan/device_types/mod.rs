@@ -31,6 +31,9 @@ pub use nm_devices::*;
 mod physical;
 pub use physical::*;
 
+mod properties;
+pub use properties::*;
+
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
@@ -38,15 +41,23 @@ use serde::{Deserialize, Serialize};
 use derive_builder::Builder;
 
 use crate::{
-    AddressMapping, DhcpOverrides, Ipv6AddressGeneration, NameserverConfig, Renderer,
+    AddressMapping, DhcpOverrides, Ipv6AddressGeneration, MacAddress, NameserverConfig, Renderer,
     RoutingConfig, RoutingPolicy,
 };
 
-#[derive(Default, Debug, Clone, PartialEq, Eq)]
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "derive_builder", derive(Builder))]
+#[cfg_attr(
+    feature = "derive_builder",
+    builder(setter(strip_option, into), default)
+)]
 #[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 pub struct CommonPropertiesAllDevices {
     /// Use the given networking backend for this definition. Currently supported are
     /// networkd and NetworkManager. This property can be specified globally
@@ -198,7 +209,7 @@ pub struct CommonPropertiesAllDevices {
     /// only and rendered by networkd, due to interactions with device
     /// renaming in udev. Match devices by MAC when setting MAC addresses.
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
-    pub macaddress: Option<String>,
+    pub macaddress: Option<MacAddress>,
     /// Set the Maximum Transmission Unit for the interface. The default is 1500.
     /// Valid values depend on your network interface.
     ///
@@ -236,6 +247,10 @@ pub struct CommonPropertiesAllDevices {
     /// Configure policy routing for the device
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
     pub routing_policy: Option<Vec<RoutingPolicy>>,
+    /// Settings of the NetworkManager connection profile, like its UUID and keyfile
+    /// settings to pass through. Only used by the NetworkManager renderer.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub networkmanager: Option<NetworkManagerSettings>,
 }
 
 /// Allows specifying the management policy of the selected interface. By
@@ -246,11 +261,26 @@ pub struct CommonPropertiesAllDevices {
 /// in a down state at all times. Any interface with activation-mode
 /// defined is implicitly considered optional.
 /// Supported officially as of networkd v248+.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-#[cfg_attr(feature = "serde", serde(rename = "lowercase"))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 pub enum ActivationMode {
     Manual,
     Off,
 }
+
+impl ActivationMode {
+    pub const VARIANTS: &'static [Self] = &[Self::Manual, Self::Off];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Manual => "manual",
+            Self::Off => "off",
+        }
+    }
+}
@@ -4,15 +4,23 @@ use serde::{Deserialize, Serialize};
 #[cfg(feature = "derive_builder")]
 use derive_builder::Builder;
 
-use crate::{CommonPropertiesAllDevices, CommonPropertiesPhysicalDeviceType};
+use crate::{CommonPropertiesAllDevices, CommonPropertiesPhysicalDeviceType, SecretString};
 
 /// GSM/CDMA modem configuration is only supported for the NetworkManager
 /// backend. systemd-networkd does not support modems.
-#[derive(Default, Debug, Clone, PartialEq, Eq)]
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "derive_builder", derive(Builder))]
+#[cfg_attr(
+    feature = "derive_builder",
+    builder(setter(strip_option, into), default)
+)]
 #[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 pub struct ModemConfig {
     /// Set the carrier APN (Access Point Name). This can be omitted if
     /// auto-config is enabled.
@@ -43,10 +51,10 @@ pub struct ModemConfig {
     /// Specify the password used to authenticate with the carrier network. This
     /// can be omitted if auto-config is enabled.
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
-    pub password: Option<String>,
+    pub password: Option<SecretString>,
     /// Specify the SIM PIN to allow it to operate if a PIN is set.
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
-    pub pin: Option<String>,
+    pub pin: Option<SecretString>,
     /// Specify the SIM unique identifier (as given by the WWAN management service)
     /// which this connection applies to. If given, the connection will apply to
     /// any device also allowed by device-id which contains a SIM card matching
@@ -66,9 +74,9 @@ pub struct ModemConfig {
     /// Common properties for physical device types
     #[cfg_attr(feature = "serde", serde(flatten))]
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
-    pub common_physical: Option<CommonPropertiesPhysicalDeviceType>,
+    pub common_physical: Option<Box<CommonPropertiesPhysicalDeviceType>>,
     /// Common properties for all devices
     #[cfg_attr(feature = "serde", serde(flatten))]
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
-    pub common_all: Option<CommonPropertiesAllDevices>,
+    pub common_all: Option<Box<CommonPropertiesAllDevices>>,
 }
@@ -1 +1,70 @@
+use std::collections::BTreeMap;
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "derive_builder")]
+use derive_builder::Builder;
+
+use crate::CommonPropertiesAllDevices;
+
+/// Purpose: Use the nm-devices key to configure connection profiles NetworkManager
+/// supports, but netplan cannot express natively, e.g. VPNs.
+///
+/// Structure: The key consists of a mapping of NetworkManager connection IDs. The
+/// connections are defined entirely by the passthrough of their networkmanager settings,
+/// and always use the NetworkManager renderer. `netplan migrate` names them
+/// `NM-<uuid>`.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "derive_builder", derive(Builder))]
+#[cfg_attr(
+    feature = "derive_builder",
+    builder(setter(strip_option, into), default)
+)]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
+pub struct NmDeviceConfig {
+    /// Common properties for all devices
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub common_all: Option<Box<CommonPropertiesAllDevices>>,
+}
+
+/// Settings of the NetworkManager connection profile of a device, only used by the
+/// NetworkManager renderer.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "derive_builder", derive(Builder))]
+#[cfg_attr(
+    feature = "derive_builder",
+    builder(setter(strip_option, into), default)
+)]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
+pub struct NetworkManagerSettings {
+    /// The connection ID NetworkManager shows for the profile, `connection.id`.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub name: Option<String>,
+    /// The UUID of the profile, `connection.uuid`.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub uuid: Option<String>,
+    /// The token to generate stable IDs from, `connection.stable-id`.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub stable_id: Option<String>,
+    /// The interface the profile is bound to, `connection.interface-name`.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub device: Option<String>,
+    /// Keyfile settings netplan has no equivalent for, passed through to NetworkManager
+    /// as they are. The keys are of the form `section.key`, e.g. `vpn.service-type`.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub passthrough: Option<BTreeMap<String, String>>,
+}
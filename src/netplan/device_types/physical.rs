@@ -4,12 +4,23 @@ use serde::{Deserialize, Serialize};
 #[cfg(feature = "derive_builder")]
 use derive_builder::Builder;
 
+use crate::netplan::normalize_mac;
+use crate::MacAddress;
+
 /// Common properties for physical device types
-#[derive(Default, Debug, Clone, PartialEq, Eq)]
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "derive_builder", derive(Builder))]
+#[cfg_attr(
+    feature = "derive_builder",
+    builder(setter(strip_option, into), default)
+)]
 #[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 pub struct CommonPropertiesPhysicalDeviceType {
     /// This selects a subset of available physical devices by various hardware
     /// properties. The following configuration will then apply to all matching
@@ -125,7 +136,7 @@ pub struct CommonPropertiesPhysicalDeviceType {
     /// In the case of a vlan definition declared the same way, netplan will create
     /// a fake VLAN bridge in openvswitch with the requested vlan properties.
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
-    pub openvswitch: Option<OpenVSwitchConfig>,
+    pub openvswitch: Option<Box<OpenVSwitchConfig>>,
 }
 
 /// This provides additional configuration for the network device for openvswitch.
@@ -137,11 +148,19 @@ pub struct CommonPropertiesPhysicalDeviceType {
 /// will be created in openvswitch instead of the defined renderer.
 /// In the case of a vlan definition declared the same way, netplan will create
 /// a fake VLAN bridge in openvswitch with the requested vlan properties.
-#[derive(Default, Debug, Clone, PartialEq, Eq)]
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "derive_builder", derive(Builder))]
+#[cfg_attr(
+    feature = "derive_builder",
+    builder(setter(strip_option, into), default)
+)]
 #[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 pub struct OpenVSwitchConfig {
     /// Passed-through directly to OpenVSwitch
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
@@ -175,6 +194,7 @@ pub struct OpenVSwitchConfig {
         feature = "serde",
         serde(deserialize_with = "crate::bool::string_or_bool_option")
     )]
+    #[cfg_attr(feature = "serde", serde(rename = "rstp"))]
     pub rtsp: Option<bool>,
     /// Valid for bridge interfaces. Specify an external OpenFlow controller.
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
@@ -192,11 +212,19 @@ pub struct OpenVSwitchConfig {
 
 /// Valid for global openvswitch settings. Options for configuring SSL
 /// server endpoint for the switch.
-#[derive(Default, Debug, Clone, PartialEq, Eq)]
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "derive_builder", derive(Builder))]
+#[cfg_attr(
+    feature = "derive_builder",
+    builder(setter(strip_option, into), default)
+)]
 #[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 pub struct SslConfig {
     /// Path to a file containing the CA certificate to be used.
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
@@ -210,11 +238,19 @@ pub struct SslConfig {
 }
 
 /// Valid for bridge interfaces. Specify an external OpenFlow controller.
-#[derive(Default, Debug, Clone, PartialEq, Eq)]
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "derive_builder", derive(Builder))]
+#[cfg_attr(
+    feature = "derive_builder",
+    builder(setter(strip_option, into), default)
+)]
 #[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 pub struct ControllerConfig {
     /// Set the list of addresses to use for the controller targets. The
     /// syntax of these addresses is as defined in ovs-vsctl(8). Example:
@@ -227,18 +263,37 @@ pub struct ControllerConfig {
     pub connection_mode: Option<ConnectionMode>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 pub enum ConnectionMode {
     InBand,
     OutOfBand,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+impl ConnectionMode {
+    pub const VARIANTS: &'static [Self] = &[Self::InBand, Self::OutOfBand];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::InBand => "in-band",
+            Self::OutOfBand => "out-of-band",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 pub enum OpenFlowProtocol {
     OpenFlow10,
     OpenFlow11,
@@ -249,31 +304,96 @@ pub enum OpenFlowProtocol {
     OpenFlow16,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+impl OpenFlowProtocol {
+    pub const VARIANTS: &'static [Self] = &[
+        Self::OpenFlow10,
+        Self::OpenFlow11,
+        Self::OpenFlow12,
+        Self::OpenFlow13,
+        Self::OpenFlow14,
+        Self::OpenFlow15,
+        Self::OpenFlow16,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::OpenFlow10 => "OpenFlow10",
+            Self::OpenFlow11 => "OpenFlow11",
+            Self::OpenFlow12 => "OpenFlow12",
+            Self::OpenFlow13 => "OpenFlow13",
+            Self::OpenFlow14 => "OpenFlow14",
+            Self::OpenFlow15 => "OpenFlow15",
+            Self::OpenFlow16 => "OpenFlow16",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 pub enum Lacp {
     Active,
     Passive,
     Off,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+impl Lacp {
+    pub const VARIANTS: &'static [Self] = &[Self::Active, Self::Passive, Self::Off];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Active => "active",
+            Self::Passive => "passive",
+            Self::Off => "off",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 pub enum FailMode {
     Secure,
     Standalone,
 }
 
+impl FailMode {
+    pub const VARIANTS: &'static [Self] = &[Self::Secure, Self::Standalone];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Secure => "secure",
+            Self::Standalone => "standalone",
+        }
+    }
+}
+
 /// This selects a subset of available physical devices by various hardware
 /// properties. The following configuration will then apply to all matching
 /// devices, as soon as they appear. All specified properties must match.
-#[derive(Default, Debug, Clone, PartialEq, Eq)]
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "derive_builder", derive(Builder))]
+#[cfg_attr(
+    feature = "derive_builder",
+    builder(setter(strip_option, into), default)
+)]
 #[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 pub struct MatchConfig {
     /// Current interface name. Globs are supported, and the primary use case
     /// for matching on names, as selecting one fixed name can be more easily
@@ -285,10 +405,33 @@ pub struct MatchConfig {
     /// Device’s MAC address in the form “XX:XX:XX:XX:XX:XX”. Globs are not
     /// allowed.
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
-    pub macaddress: Option<String>,
+    pub macaddress: Option<MacAddress>,
     /// Kernel driver name, corresponding to the DRIVER udev property.
     /// A sequence of globs is supported, any of which must match.
     /// Matching on driver is only supported with networkd.
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
     pub driver: Option<Vec<String>>,
 }
+
+impl MatchConfig {
+    /// A normalized copy of the match rules, so that equivalent rules compare equal.
+    /// Redundant wildcards are removed from the globs, the drivers are sorted and
+    /// deduplicated, and the MAC address is lowercased.
+    pub fn normalized(&self) -> Self {
+        let driver = self.driver.as_ref().map(|drivers| {
+            let mut drivers = drivers
+                .iter()
+                .map(|driver| crate::glob::normalize(driver))
+                .collect::<Vec<_>>();
+            drivers.sort();
+            drivers.dedup();
+            drivers
+        });
+
+        Self {
+            name: self.name.as_deref().map(crate::glob::normalize),
+            macaddress: self.macaddress.as_ref().map(normalize_mac),
+            driver,
+        }
+    }
+}
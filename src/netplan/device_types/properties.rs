@@ -0,0 +1,397 @@
+use crate::ipaddr::parse_address;
+use crate::validation::default_route_family;
+use crate::{
+    AddressMapping, BondConfig, BridgeConfig, CommonPropertiesAllDevices,
+    CommonPropertiesPhysicalDeviceType, DummyDeviceConfig, EthernetConfig, IpParseError,
+    MacAddress, ModemConfig, NameserverConfig, NmDeviceConfig, Renderer, RoutingConfig,
+    RoutingPolicy, TunnelConfig, VlanConfig, VrfsConfig, WifiConfig,
+};
+use ipnet::IpNet;
+use std::net::IpAddr;
+
+/// Uniform access to the properties shared by all device types, regardless of the
+/// type of the device. The getters return `None` if the property is not set, the
+/// setters create the common properties block if it does not exist yet.
+///
+/// ```
+/// use netplan_types::{CommonProperties, NetworkConfig};
+///
+/// fn disable_dhcp(network: &mut NetworkConfig) {
+///     for (_, _, mut device) in network.devices_mut() {
+///         device.set_dhcp4(Some(false));
+///         device.set_dhcp6(Some(false));
+///     }
+/// }
+/// ```
+pub trait CommonProperties {
+    /// The properties shared by all device types, if set.
+    fn common_all(&self) -> Option<&CommonPropertiesAllDevices>;
+
+    /// The properties shared by all device types, inserting an empty block if none are set.
+    fn common_all_mut(&mut self) -> &mut CommonPropertiesAllDevices;
+
+    /// The networking backend used for this device.
+    fn renderer(&self) -> Option<&Renderer> {
+        self.common_all().and_then(|c| c.renderer.as_ref())
+    }
+
+    fn set_renderer(&mut self, renderer: Option<Renderer>) {
+        self.common_all_mut().renderer = renderer;
+    }
+
+    /// Whether DHCP for IPv4 is enabled.
+    fn dhcp4(&self) -> Option<bool> {
+        self.common_all().and_then(|c| c.dhcp4)
+    }
+
+    fn set_dhcp4(&mut self, dhcp4: Option<bool>) {
+        self.common_all_mut().dhcp4 = dhcp4;
+    }
+
+    /// Whether DHCP for IPv6 is enabled.
+    fn dhcp6(&self) -> Option<bool> {
+        self.common_all().and_then(|c| c.dhcp6)
+    }
+
+    fn set_dhcp6(&mut self, dhcp6: Option<bool>) {
+        self.common_all_mut().dhcp6 = dhcp6;
+    }
+
+    /// The static addresses of the device.
+    fn addresses(&self) -> Option<&[AddressMapping]> {
+        self.common_all().and_then(|c| c.addresses.as_deref())
+    }
+
+    fn set_addresses(&mut self, addresses: Option<Vec<AddressMapping>>) {
+        self.common_all_mut().addresses = addresses;
+    }
+
+    /// The DNS servers and search domains.
+    fn nameservers(&self) -> Option<&NameserverConfig> {
+        self.common_all().and_then(|c| c.nameservers.as_ref())
+    }
+
+    fn set_nameservers(&mut self, nameservers: Option<NameserverConfig>) {
+        self.common_all_mut().nameservers = nameservers;
+    }
+
+    /// The MAC address to set on the device.
+    fn macaddress(&self) -> Option<&MacAddress> {
+        self.common_all().and_then(|c| c.macaddress.as_ref())
+    }
+
+    fn set_macaddress(&mut self, macaddress: Option<MacAddress>) {
+        self.common_all_mut().macaddress = macaddress;
+    }
+
+    /// The MTU of the device.
+    fn mtu(&self) -> Option<u16> {
+        self.common_all().and_then(|c| c.mtu)
+    }
+
+    fn set_mtu(&mut self, mtu: Option<u16>) {
+        self.common_all_mut().mtu = mtu;
+    }
+
+    /// Whether the device is optional for booting.
+    fn optional(&self) -> Option<bool> {
+        self.common_all().and_then(|c| c.optional)
+    }
+
+    fn set_optional(&mut self, optional: Option<bool>) {
+        self.common_all_mut().optional = optional;
+    }
+
+    /// The static routes of the device.
+    fn routes(&self) -> Option<&[RoutingConfig]> {
+        self.common_all().and_then(|c| c.routes.as_deref())
+    }
+
+    fn set_routes(&mut self, routes: Option<Vec<RoutingConfig>>) {
+        self.common_all_mut().routes = routes;
+    }
+
+    /// The routing policy rules of the device.
+    fn routing_policy(&self) -> Option<&[RoutingPolicy]> {
+        self.common_all().and_then(|c| c.routing_policy.as_deref())
+    }
+
+    fn set_routing_policy(&mut self, routing_policy: Option<Vec<RoutingPolicy>>) {
+        self.common_all_mut().routing_policy = routing_policy;
+    }
+
+    /// The static addresses of the device, parsed. See [CommonPropertiesAllDevices::addresses_parsed].
+    fn addresses_parsed(&self) -> Result<Vec<IpNet>, IpParseError> {
+        self.common_all()
+            .map_or(Ok(Vec::new()), CommonPropertiesAllDevices::addresses_parsed)
+    }
+
+    /// Whether the device has the static address.
+    fn has_address(&self, address: IpNet) -> bool {
+        self.common_all().is_some_and(|c| c.has_address(address))
+    }
+
+    /// Add a static address, unless the device has it already. Returns whether it was added.
+    fn add_address(&mut self, address: IpNet) -> bool {
+        self.common_all_mut().add_address(address)
+    }
+
+    /// Remove a static address. Returns whether the device had it. A device without
+    /// common properties is left as is.
+    ///
+    /// ```
+    /// use netplan_types::{CommonProperties, EthernetConfig};
+    ///
+    /// let mut ethernet = EthernetConfig::default();
+    /// assert!(!ethernet.remove_address("10.0.0.2/24".parse().unwrap()));
+    /// assert_eq!(ethernet, EthernetConfig::default());
+    /// ```
+    fn remove_address(&mut self, address: IpNet) -> bool {
+        if !self.has_address(address) {
+            return false;
+        }
+
+        self.common_all_mut().remove_address(address)
+    }
+
+    /// Route the address family of the gateway through it. See [CommonPropertiesAllDevices::set_default_gateway].
+    fn set_default_gateway(&mut self, gateway: IpAddr, metric: Option<u16>) {
+        self.common_all_mut().set_default_gateway(gateway, metric);
+    }
+}
+
+/// Access to the properties shared by physical devices: ethernets, wifis and modems.
+pub trait PhysicalProperties {
+    /// The properties shared by physical devices, inserting an empty block if none are set.
+    fn common_physical_mut(&mut self) -> &mut CommonPropertiesPhysicalDeviceType;
+}
+
+impl CommonPropertiesAllDevices {
+    /// The static addresses, parsed. Fails if any address is not in CIDR notation.
+    ///
+    /// ```
+    /// use netplan_types::CommonPropertiesAllDevices;
+    ///
+    /// let mut common = CommonPropertiesAllDevices::default();
+    /// common.add_address("10.0.0.2/24".parse().unwrap());
+    /// common.add_address("fd00::2/64".parse().unwrap());
+    /// assert!(!common.add_address("10.0.0.2/24".parse().unwrap()));
+    ///
+    /// common.remove_address("10.0.0.2/24".parse().unwrap());
+    /// assert_eq!(common.addresses_parsed(), Ok(vec!["fd00::2/64".parse().unwrap()]));
+    /// ```
+    pub fn addresses_parsed(&self) -> Result<Vec<IpNet>, IpParseError> {
+        self.addresses
+            .iter()
+            .flatten()
+            .flat_map(AddressMapping::addresses)
+            .map(parse_address)
+            .collect()
+    }
+
+    /// Whether the address is one of the static addresses.
+    pub fn has_address(&self, address: IpNet) -> bool {
+        self.addresses
+            .iter()
+            .flatten()
+            .flat_map(AddressMapping::addresses)
+            .any(|a| same_address(a, &address))
+    }
+
+    /// Add a static address, unless it is present already. Returns whether it was added.
+    pub fn add_address(&mut self, address: IpNet) -> bool {
+        if self.has_address(address) {
+            return false;
+        }
+
+        self.addresses
+            .get_or_insert_with(Vec::new)
+            .push(AddressMapping::Simple(address.to_string()));
+        true
+    }
+
+    /// Remove a static address, including its properties if it has any.
+    /// Returns whether the address was present.
+    pub fn remove_address(&mut self, address: IpNet) -> bool {
+        let Some(addresses) = self.addresses.as_mut() else {
+            return false;
+        };
+
+        let before = addresses.len();
+        let mut removed = false;
+        addresses.retain_mut(|mapping| match mapping {
+            AddressMapping::Simple(a) => !same_address(a, &address),
+            AddressMapping::Complex(map) => {
+                let len = map.len();
+                map.retain(|a, _| !same_address(a, &address));
+                removed |= map.len() != len;
+                !map.is_empty()
+            }
+        });
+        removed |= addresses.len() != before;
+
+        if addresses.is_empty() {
+            self.addresses = None;
+        }
+
+        removed
+    }
+
+    /// Set the default gateway for the address family of `gateway`, as a route to `default`.
+    /// Any default route of the same address family in the main table is replaced, and the
+    /// deprecated `gateway4` or `gateway6` is removed.
+    ///
+    /// ```
+    /// use netplan_types::CommonPropertiesAllDevices;
+    ///
+    /// let mut common = CommonPropertiesAllDevices {
+    ///     gateway4: Some("10.0.0.1".to_string()),
+    ///     ..Default::default()
+    /// };
+    /// common.set_default_gateway("10.0.0.254".parse().unwrap(), Some(100));
+    ///
+    /// let routes = common.routes.unwrap();
+    /// assert_eq!(common.gateway4, None);
+    /// assert_eq!(routes.len(), 1);
+    /// assert_eq!(routes[0].via.as_deref(), Some("10.0.0.254"));
+    /// ```
+    pub fn set_default_gateway(&mut self, gateway: IpAddr, metric: Option<u16>) {
+        let ipv6 = gateway.is_ipv6();
+        if ipv6 {
+            self.gateway6 = None;
+        } else {
+            self.gateway4 = None;
+        }
+
+        let routes = self.routes.get_or_insert_with(Vec::new);
+        routes.retain(|route| default_route_family(route) != Some(ipv6));
+        routes.push(RoutingConfig {
+            to: Some("default".to_string()),
+            via: Some(gateway.to_string()),
+            metric,
+            ..Default::default()
+        });
+    }
+}
+
+fn same_address(written: &str, address: &IpNet) -> bool {
+    parse_address(written).is_ok_and(|a| a == *address)
+}
+
+impl CommonProperties for EthernetConfig {
+    fn common_all(&self) -> Option<&CommonPropertiesAllDevices> {
+        self.common_all.as_deref()
+    }
+
+    fn common_all_mut(&mut self) -> &mut CommonPropertiesAllDevices {
+        self.common_all.get_or_insert_with(Default::default)
+    }
+}
+
+impl CommonProperties for WifiConfig {
+    fn common_all(&self) -> Option<&CommonPropertiesAllDevices> {
+        self.common_all.as_deref()
+    }
+
+    fn common_all_mut(&mut self) -> &mut CommonPropertiesAllDevices {
+        self.common_all.get_or_insert_with(Default::default)
+    }
+}
+
+impl CommonProperties for ModemConfig {
+    fn common_all(&self) -> Option<&CommonPropertiesAllDevices> {
+        self.common_all.as_deref()
+    }
+
+    fn common_all_mut(&mut self) -> &mut CommonPropertiesAllDevices {
+        self.common_all.get_or_insert_with(Default::default)
+    }
+}
+
+impl CommonProperties for BondConfig {
+    fn common_all(&self) -> Option<&CommonPropertiesAllDevices> {
+        self.common_all.as_deref()
+    }
+
+    fn common_all_mut(&mut self) -> &mut CommonPropertiesAllDevices {
+        self.common_all.get_or_insert_with(Default::default)
+    }
+}
+
+impl CommonProperties for BridgeConfig {
+    fn common_all(&self) -> Option<&CommonPropertiesAllDevices> {
+        self.common_all.as_deref()
+    }
+
+    fn common_all_mut(&mut self) -> &mut CommonPropertiesAllDevices {
+        self.common_all.get_or_insert_with(Default::default)
+    }
+}
+
+impl CommonProperties for VlanConfig {
+    fn common_all(&self) -> Option<&CommonPropertiesAllDevices> {
+        self.common_all.as_deref()
+    }
+
+    fn common_all_mut(&mut self) -> &mut CommonPropertiesAllDevices {
+        self.common_all.get_or_insert_with(Default::default)
+    }
+}
+
+impl CommonProperties for TunnelConfig {
+    fn common_all(&self) -> Option<&CommonPropertiesAllDevices> {
+        self.common_all.as_deref()
+    }
+
+    fn common_all_mut(&mut self) -> &mut CommonPropertiesAllDevices {
+        self.common_all.get_or_insert_with(Default::default)
+    }
+}
+
+impl CommonProperties for VrfsConfig {
+    fn common_all(&self) -> Option<&CommonPropertiesAllDevices> {
+        self.common_all.as_deref()
+    }
+
+    fn common_all_mut(&mut self) -> &mut CommonPropertiesAllDevices {
+        self.common_all.get_or_insert_with(Default::default)
+    }
+}
+
+impl CommonProperties for DummyDeviceConfig {
+    fn common_all(&self) -> Option<&CommonPropertiesAllDevices> {
+        self.common_all.as_deref()
+    }
+
+    fn common_all_mut(&mut self) -> &mut CommonPropertiesAllDevices {
+        self.common_all.get_or_insert_with(Default::default)
+    }
+}
+
+impl CommonProperties for NmDeviceConfig {
+    fn common_all(&self) -> Option<&CommonPropertiesAllDevices> {
+        self.common_all.as_deref()
+    }
+
+    fn common_all_mut(&mut self) -> &mut CommonPropertiesAllDevices {
+        self.common_all.get_or_insert_with(Default::default)
+    }
+}
+
+impl PhysicalProperties for EthernetConfig {
+    fn common_physical_mut(&mut self) -> &mut CommonPropertiesPhysicalDeviceType {
+        self.common_physical.get_or_insert_with(Default::default)
+    }
+}
+
+impl PhysicalProperties for WifiConfig {
+    fn common_physical_mut(&mut self) -> &mut CommonPropertiesPhysicalDeviceType {
+        self.common_physical.get_or_insert_with(Default::default)
+    }
+}
+
+impl PhysicalProperties for ModemConfig {
+    fn common_physical_mut(&mut self) -> &mut CommonPropertiesPhysicalDeviceType {
+        self.common_physical.get_or_insert_with(Default::default)
+    }
+}
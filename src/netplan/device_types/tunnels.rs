@@ -4,7 +4,8 @@ use serde::{Deserialize, Serialize};
 #[cfg(feature = "derive_builder")]
 use derive_builder::Builder;
 
-use crate::CommonPropertiesAllDevices;
+use crate::{CommonPropertiesAllDevices, SecretString};
+use std::fmt::{Display, Formatter};
 
 /// Tunnels allow traffic to pass as if it was between systems on the same local
 /// network, although systems may be far from each other but reachable via the
@@ -12,11 +13,19 @@ use crate::CommonPropertiesAllDevices;
 /// does not provide the service, or to extend and “connect” separate local
 /// networks. Please see <https://en.wikipedia.org/wiki/Tunneling_protocol> for
 /// more general information about tunnels.
-#[derive(Default, Debug, Clone, PartialEq, Eq)]
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "derive_builder", derive(Builder))]
+#[cfg_attr(
+    feature = "derive_builder",
+    builder(setter(strip_option, into), default)
+)]
 #[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 pub struct TunnelConfig {
     /// Defines the tunnel mode. Valid options are sit, gre, ip6gre,
     /// ipip, ipip6, ip6ip6, vti, vti6 and wireguard.
@@ -25,6 +34,9 @@ pub struct TunnelConfig {
     /// In addition, the NetworkManager backend supports isatap tunnels.
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
     pub mode: Option<TunnelMode>,
+    /// The VXLAN Network Identifier (VNI), required for VXLAN tunnels (since 0.105).
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub id: Option<u32>,
     /// Defines the address of the local endpoint of the tunnel.
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
     pub local: Option<String>,
@@ -56,19 +68,74 @@ pub struct TunnelConfig {
     pub port: Option<String>,
     /// A list of peers
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Vec::is_empty"))]
+    #[cfg_attr(feature = "serde", serde(default))]
     pub peers: Vec<WireGuardPeer>,
     /// Common properties for all devices
     #[cfg_attr(feature = "serde", serde(flatten))]
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
-    pub common_all: Option<CommonPropertiesAllDevices>,
+    pub common_all: Option<Box<CommonPropertiesAllDevices>>,
+}
+
+impl TunnelConfig {
+    /// A WireGuard tunnel using the private key, which is either base64-encoded or,
+    /// with networkd v242 or later, the absolute path to a file containing the key.
+    ///
+    /// ```
+    /// use netplan_types::TunnelConfig;
+    ///
+    /// let tunnel = TunnelConfig::wireguard("/etc/wireguard/private.key")
+    ///     .listen_port(51820)
+    ///     .peer("M9nt4YujIOmNrRmpIRTmYSfMdrpvE7u6WkG8FY8WjG4=", "vpn.example.com:51820", ["10.10.0.0/24"]);
+    ///
+    /// assert_eq!(tunnel.peers.len(), 1);
+    /// ```
+    pub fn wireguard(private_key: impl Into<SecretString>) -> Self {
+        Self {
+            mode: Some(TunnelMode::Wireguard),
+            key: Some(TunnelKey::Simple(private_key.into())),
+            ..Default::default()
+        }
+    }
+
+    /// Set the UDP port the WireGuard tunnel listens on.
+    pub fn listen_port(mut self, port: u16) -> Self {
+        self.port = Some(port.to_string());
+        self
+    }
+
+    /// Add a WireGuard peer, reachable at `endpoint`, which may send from and receive
+    /// traffic for `allowed_ips`. Use [TunnelConfig::with_peer] for peers without a
+    /// known endpoint, or with further settings.
+    pub fn peer<S: Into<String>>(
+        self,
+        public_key: impl Into<String>,
+        endpoint: impl Into<String>,
+        allowed_ips: impl IntoIterator<Item = S>,
+    ) -> Self {
+        self.with_peer(WireGuardPeer::new(public_key, allowed_ips).endpoint(endpoint))
+    }
+
+    /// Add a WireGuard peer.
+    pub fn with_peer(mut self, peer: WireGuardPeer) -> Self {
+        self.peers.push(peer);
+        self
+    }
 }
 
 /// A list of peers
-#[derive(Default, Debug, Clone, PartialEq, Eq)]
+#[derive(Default, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "derive_builder", derive(Builder))]
+#[cfg_attr(
+    feature = "derive_builder",
+    builder(setter(strip_option, into), default)
+)]
 #[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 pub struct WireGuardPeer {
     /// Remote endpoint IPv4/IPv6 address or a hostname, followed by a colon
     /// and a port number.
@@ -91,14 +158,58 @@ pub struct WireGuardPeer {
     pub keys: Option<WireGuardPeerKey>,
 }
 
+impl WireGuardPeer {
+    /// A peer with the public key, which may send from and receive traffic for `allowed_ips`.
+    pub fn new<S: Into<String>>(
+        public_key: impl Into<String>,
+        allowed_ips: impl IntoIterator<Item = S>,
+    ) -> Self {
+        Self {
+            allowed_ips: Some(allowed_ips.into_iter().map(Into::into).collect()),
+            keys: Some(WireGuardPeerKey {
+                public: Some(public_key.into()),
+                shared: None,
+            }),
+            ..Default::default()
+        }
+    }
+
+    /// Set the address and port the peer is reachable at, e.g. `vpn.example.com:51820`.
+    pub fn endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = Some(endpoint.into());
+        self
+    }
+
+    /// Set the interval in seconds at which keepalive packets are sent to the peer.
+    pub fn keepalive(mut self, seconds: u32) -> Self {
+        self.keepalive = Some(seconds);
+        self
+    }
+
+    /// Set the preshared key, which is either base64-encoded or, with networkd v242
+    /// or later, the absolute path to a file containing the key.
+    pub fn shared_key(mut self, shared_key: impl Into<SecretString>) -> Self {
+        self.keys.get_or_insert_with(Default::default).shared = Some(shared_key.into());
+        self
+    }
+}
+
 /// Define keys to use for the WireGuard peers.
 ///
 /// This field can be used as a mapping, where you can further specify the
 /// public and shared keys.
-#[derive(Default, Debug, Clone, PartialEq, Eq)]
+#[derive(Default, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "derive_builder", derive(Builder))]
+#[cfg_attr(
+    feature = "derive_builder",
+    builder(setter(strip_option, into), default)
+)]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 pub struct WireGuardPeerKey {
     /// A base64-encoded public key, required for WireGuard peers.
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
@@ -107,7 +218,7 @@ pub struct WireGuardPeerKey {
     /// When the systemd-networkd backend (v242+) is used, this can
     /// also be an absolute path to a file containing the preshared key.
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
-    pub shared: Option<String>,
+    pub shared: Option<SecretString>,
 }
 
 /// Define keys to use for the tunnel. The key can be a number or a dotted
@@ -121,20 +232,28 @@ pub struct WireGuardPeerKey {
 /// This field may be used as a scalar (meaning that a single key is
 /// specified and to be used for input, output and private key), or as a
 /// mapping, where you can further specify input/output/private.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(untagged))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 pub enum TunnelKey {
-    Simple(String),
+    Simple(SecretString),
     Complex {
         /// The input key for the tunnel
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
         input: Option<String>,
         /// The output key for the tunnel
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
         output: Option<String>,
         /// A base64-encoded private key required for WireGuard tunnels. When the
         /// systemd-networkd backend (v242+) is used, this can also be an
         /// absolute path to a file containing the private key.
-        private: Option<String>,
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        private: Option<SecretString>,
     },
 }
 
@@ -143,9 +262,14 @@ pub enum TunnelKey {
 /// Additionally, the networkd backend also supports gretap and
 /// ip6gretap modes.
 /// In addition, the NetworkManager backend supports isatap tunnels.
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// VXLAN tunnels are supported since 0.105.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 pub enum TunnelMode {
     #[cfg_attr(feature = "serde", serde(rename = "sit"))]
     Sit,
@@ -171,4 +295,316 @@ pub enum TunnelMode {
     Ip6gretap,
     #[cfg_attr(feature = "serde", serde(rename = "isatap"))]
     Isatap,
+    #[cfg_attr(feature = "serde", serde(rename = "vxlan"))]
+    Vxlan,
+}
+
+impl TunnelMode {
+    pub const VARIANTS: &'static [Self] = &[
+        Self::Sit,
+        Self::Gre,
+        Self::Ip6gre,
+        Self::Ipip,
+        Self::Ipip6,
+        Self::Ip6ip6,
+        Self::Vti,
+        Self::Vti6,
+        Self::Wireguard,
+        Self::Gretap,
+        Self::Ip6gretap,
+        Self::Isatap,
+        Self::Vxlan,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Sit => "sit",
+            Self::Gre => "gre",
+            Self::Ip6gre => "ip6gre",
+            Self::Ipip => "ipip",
+            Self::Ipip6 => "ipip6",
+            Self::Ip6ip6 => "ip6ip6",
+            Self::Vti => "vti",
+            Self::Vti6 => "vti6",
+            Self::Wireguard => "wireguard",
+            Self::Gretap => "gretap",
+            Self::Ip6gretap => "ip6gretap",
+            Self::Isatap => "isatap",
+            Self::Vxlan => "vxlan",
+        }
+    }
+}
+
+/// A tunnel definition whose fields depend on its mode, so that a WireGuard tunnel cannot
+/// have a TTL and a GRE tunnel cannot have peers. It is (de)serialized in the same flat
+/// shape as [TunnelConfig], and converts from and into it.
+///
+/// ```
+/// use netplan_types::Tunnel;
+///
+/// let tunnel: Tunnel = serde_yaml::from_str(r#"
+///     mode: gre
+///     local: 192.0.2.1
+///     remote: 198.51.100.1
+/// "#).unwrap();
+///
+/// let Tunnel::Gre(gre) = &tunnel else {
+///     unreachable!()
+/// };
+/// assert_eq!(gre.remote.as_deref(), Some("198.51.100.1"));
+///
+/// let invalid = serde_yaml::from_str::<Tunnel>(r#"
+///     mode: gre
+///     peers:
+///       - endpoint: 198.51.100.1:51820
+/// "#);
+/// assert!(invalid.is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(try_from = "TunnelConfig", into = "TunnelConfig")
+)]
+pub enum Tunnel {
+    Sit(PlainTunnel),
+    Gre(PlainTunnel),
+    Ip6gre(PlainTunnel),
+    Ipip(PlainTunnel),
+    Ipip6(PlainTunnel),
+    Ip6ip6(PlainTunnel),
+    Vti(PlainTunnel),
+    Vti6(PlainTunnel),
+    Gretap(PlainTunnel),
+    Ip6gretap(PlainTunnel),
+    Isatap(PlainTunnel),
+    Wireguard(WireGuardTunnel),
+    Vxlan(VxlanTunnel),
+}
+
+/// The fields of a tunnel in one of the point to point modes, e.g. `gre` or `sit`.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "derive_builder", derive(Builder))]
+#[cfg_attr(
+    feature = "derive_builder",
+    builder(setter(strip_option, into), default)
+)]
+pub struct PlainTunnel {
+    pub local: Option<String>,
+    pub remote: Option<String>,
+    pub ttl: Option<u64>,
+    pub key: Option<TunnelKey>,
+    pub common_all: Option<Box<CommonPropertiesAllDevices>>,
+}
+
+/// The fields of a tunnel in `wireguard` mode.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "derive_builder", derive(Builder))]
+#[cfg_attr(
+    feature = "derive_builder",
+    builder(setter(strip_option, into), default)
+)]
+pub struct WireGuardTunnel {
+    /// The private key, see [TunnelConfig::key].
+    pub key: Option<TunnelKey>,
+    pub mark: Option<String>,
+    pub port: Option<String>,
+    pub peers: Vec<WireGuardPeer>,
+    pub common_all: Option<Box<CommonPropertiesAllDevices>>,
+}
+
+/// The fields of a tunnel in `vxlan` mode.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "derive_builder", derive(Builder))]
+#[cfg_attr(
+    feature = "derive_builder",
+    builder(setter(strip_option, into), default)
+)]
+pub struct VxlanTunnel {
+    pub id: Option<u32>,
+    pub local: Option<String>,
+    pub remote: Option<String>,
+    pub ttl: Option<u64>,
+    /// The destination UDP port.
+    pub port: Option<String>,
+    pub common_all: Option<Box<CommonPropertiesAllDevices>>,
+}
+
+/// The error returned when a [TunnelConfig] cannot be represented as a [Tunnel].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TunnelConversionError {
+    /// The tunnel has no mode.
+    MissingMode,
+    /// The field is set, but not used by tunnels of the mode.
+    UnsupportedField(TunnelMode, &'static str),
+}
+
+impl Display for TunnelConversionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingMode => f.write_str("The tunnel has no mode"),
+            Self::UnsupportedField(mode, field) => write!(
+                f,
+                "The field '{field}' is not supported by {} tunnels",
+                mode.as_str()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TunnelConversionError {}
+
+impl Tunnel {
+    pub fn mode(&self) -> TunnelMode {
+        match self {
+            Self::Sit(_) => TunnelMode::Sit,
+            Self::Gre(_) => TunnelMode::Gre,
+            Self::Ip6gre(_) => TunnelMode::Ip6gre,
+            Self::Ipip(_) => TunnelMode::Ipip,
+            Self::Ipip6(_) => TunnelMode::Ipip6,
+            Self::Ip6ip6(_) => TunnelMode::Ip6ip6,
+            Self::Vti(_) => TunnelMode::Vti,
+            Self::Vti6(_) => TunnelMode::Vti6,
+            Self::Gretap(_) => TunnelMode::Gretap,
+            Self::Ip6gretap(_) => TunnelMode::Ip6gretap,
+            Self::Isatap(_) => TunnelMode::Isatap,
+            Self::Wireguard(_) => TunnelMode::Wireguard,
+            Self::Vxlan(_) => TunnelMode::Vxlan,
+        }
+    }
+
+    fn plain(mode: &TunnelMode, tunnel: PlainTunnel) -> Self {
+        match mode {
+            TunnelMode::Sit => Self::Sit(tunnel),
+            TunnelMode::Gre => Self::Gre(tunnel),
+            TunnelMode::Ip6gre => Self::Ip6gre(tunnel),
+            TunnelMode::Ipip => Self::Ipip(tunnel),
+            TunnelMode::Ipip6 => Self::Ipip6(tunnel),
+            TunnelMode::Ip6ip6 => Self::Ip6ip6(tunnel),
+            TunnelMode::Vti => Self::Vti(tunnel),
+            TunnelMode::Vti6 => Self::Vti6(tunnel),
+            TunnelMode::Gretap => Self::Gretap(tunnel),
+            TunnelMode::Ip6gretap => Self::Ip6gretap(tunnel),
+            TunnelMode::Isatap => Self::Isatap(tunnel),
+            TunnelMode::Wireguard | TunnelMode::Vxlan => {
+                unreachable!("{} tunnels are not plain tunnels", mode.as_str())
+            }
+        }
+    }
+}
+
+impl TryFrom<TunnelConfig> for Tunnel {
+    type Error = TunnelConversionError;
+
+    fn try_from(config: TunnelConfig) -> Result<Self, Self::Error> {
+        let mode = config.mode.ok_or(TunnelConversionError::MissingMode)?;
+        let unsupported: &[(bool, &'static str)] = match mode {
+            TunnelMode::Wireguard => &[
+                (config.id.is_some(), "id"),
+                (config.local.is_some(), "local"),
+                (config.remote.is_some(), "remote"),
+                (config.ttl.is_some(), "ttl"),
+            ],
+            TunnelMode::Vxlan => &[
+                (config.key.is_some(), "key"),
+                (config.mark.is_some(), "mark"),
+                (!config.peers.is_empty(), "peers"),
+            ],
+            _ => &[
+                (config.id.is_some(), "id"),
+                (config.mark.is_some(), "mark"),
+                (config.port.is_some(), "port"),
+                (!config.peers.is_empty(), "peers"),
+            ],
+        };
+
+        if let Some((_, field)) = unsupported.iter().find(|(set, _)| *set) {
+            return Err(TunnelConversionError::UnsupportedField(mode, field));
+        }
+
+        Ok(match mode {
+            TunnelMode::Wireguard => Self::Wireguard(WireGuardTunnel {
+                key: config.key,
+                mark: config.mark,
+                port: config.port,
+                peers: config.peers,
+                common_all: config.common_all,
+            }),
+            TunnelMode::Vxlan => Self::Vxlan(VxlanTunnel {
+                id: config.id,
+                local: config.local,
+                remote: config.remote,
+                ttl: config.ttl,
+                port: config.port,
+                common_all: config.common_all,
+            }),
+            _ => Self::plain(
+                &mode,
+                PlainTunnel {
+                    local: config.local,
+                    remote: config.remote,
+                    ttl: config.ttl,
+                    key: config.key,
+                    common_all: config.common_all,
+                },
+            ),
+        })
+    }
+}
+
+impl From<Tunnel> for TunnelConfig {
+    fn from(tunnel: Tunnel) -> Self {
+        let mode = Some(tunnel.mode());
+        match tunnel {
+            Tunnel::Wireguard(wireguard) => Self {
+                mode,
+                key: wireguard.key,
+                mark: wireguard.mark,
+                port: wireguard.port,
+                peers: wireguard.peers,
+                common_all: wireguard.common_all,
+                ..Default::default()
+            },
+            Tunnel::Vxlan(vxlan) => Self {
+                mode,
+                id: vxlan.id,
+                local: vxlan.local,
+                remote: vxlan.remote,
+                ttl: vxlan.ttl,
+                port: vxlan.port,
+                common_all: vxlan.common_all,
+                ..Default::default()
+            },
+            Tunnel::Sit(plain)
+            | Tunnel::Gre(plain)
+            | Tunnel::Ip6gre(plain)
+            | Tunnel::Ipip(plain)
+            | Tunnel::Ipip6(plain)
+            | Tunnel::Ip6ip6(plain)
+            | Tunnel::Vti(plain)
+            | Tunnel::Vti6(plain)
+            | Tunnel::Gretap(plain)
+            | Tunnel::Ip6gretap(plain)
+            | Tunnel::Isatap(plain) => Self {
+                mode,
+                local: plain.local,
+                remote: plain.remote,
+                ttl: plain.ttl,
+                key: plain.key,
+                common_all: plain.common_all,
+                ..Default::default()
+            },
+        }
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for Tunnel {
+    fn schema_name() -> String {
+        TunnelConfig::schema_name()
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        TunnelConfig::json_schema(gen)
+    }
 }
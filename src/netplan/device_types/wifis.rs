@@ -4,20 +4,37 @@ use serde::{Deserialize, Serialize};
 #[cfg(feature = "derive_builder")]
 use derive_builder::Builder;
 
-use crate::{AuthConfig, CommonPropertiesAllDevices, CommonPropertiesPhysicalDeviceType};
+use crate::{
+    AuthConfig, CommonPropertiesAllDevices, CommonPropertiesPhysicalDeviceType, KeyManagmentMode,
+    MacAddress, NetworkManagerSettings, SecretString,
+};
 use std::collections::HashMap;
 
 #[derive(Default, Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "derive_builder", derive(Builder))]
+#[cfg_attr(
+    feature = "derive_builder",
+    builder(setter(strip_option, into), default)
+)]
 #[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 pub struct WifiConfig {
     /// This provides pre-configured connections to NetworkManager. Note that
     /// users can of course select other access points/SSIDs. The keys of the
     /// mapping are the SSIDs, and the values are mappings with the following
     /// supported properties:
-    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            skip_serializing_if = "Option::is_none",
+            serialize_with = "crate::sorted::sorted_option"
+        )
+    )]
     pub access_points: Option<HashMap<String, AccessPointConfig>>,
     /// This enables WakeOnWLan on supported devices. Not all drivers support all
     /// options. May be any combination of any, disconnect, magic_pkt,
@@ -26,27 +43,69 @@ pub struct WifiConfig {
     /// default flag (the default).
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
     pub wakeonwlan: Option<Vec<WakeOnWLan>>,
+    /// This can be used to define the radio’s regulatory domain, to make use of
+    /// additional WiFi channels outside the “world domain”. Takes an ISO /
+    /// IEC 3166 country code (e.g. GB) or 00 to reset to the “world domain”.
+    /// (Since 0.105)
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub regulatory_domain: Option<String>,
     /// Common properties for physical device types
     #[cfg_attr(feature = "serde", serde(flatten))]
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
-    pub common_physical: Option<CommonPropertiesPhysicalDeviceType>,
+    pub common_physical: Option<Box<CommonPropertiesPhysicalDeviceType>>,
     /// Common properties for all devices
     #[cfg_attr(feature = "serde", serde(flatten))]
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
-    pub common_all: Option<CommonPropertiesAllDevices>,
+    pub common_all: Option<Box<CommonPropertiesAllDevices>>,
 }
 
-#[derive(Default, Debug, Clone, PartialEq, Eq)]
+impl WifiConfig {
+    /// A wifi device connecting to the WPA2 personal network `ssid`.
+    ///
+    /// ```
+    /// use netplan_types::{KeyManagmentMode, WifiConfig};
+    ///
+    /// let wifi = WifiConfig::psk("home", "correct horse");
+    /// let auth = wifi.access_points.as_ref().unwrap()["home"].auth.clone().unwrap();
+    /// assert_eq!(auth.key_management, Some(KeyManagmentMode::Psk));
+    /// assert_eq!(auth.password.unwrap().as_str(), "correct horse");
+    /// ```
+    pub fn psk(ssid: impl Into<String>, passphrase: impl Into<SecretString>) -> Self {
+        Self::default().access_point(ssid, AccessPointConfig::wpa2(passphrase))
+    }
+
+    /// Add the access point `ssid`, replacing an access point with the same SSID.
+    pub fn access_point(
+        mut self,
+        ssid: impl Into<String>,
+        access_point: AccessPointConfig,
+    ) -> Self {
+        self.access_points
+            .get_or_insert_with(HashMap::new)
+            .insert(ssid.into(), access_point);
+        self
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "derive_builder", derive(Builder))]
+#[cfg_attr(
+    feature = "derive_builder",
+    builder(setter(strip_option, into), default)
+)]
 #[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 pub struct AccessPointConfig {
     /// Enable WPA2 authentication and set the passphrase for it. If neither
     /// this nor an auth block are given, the network is assumed to be
     /// open. The setting
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
-    pub password: Option<String>,
+    pub password: Option<SecretString>,
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
     pub auth: Option<AuthConfig>,
     /// Possible access point modes are infrastructure (the default),
@@ -58,10 +117,10 @@ pub struct AccessPointConfig {
     /// If specified, directs the device to only associate with the given
     /// access point.
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
-    pub bssid: Option<String>,
-    /// Possible bands are 5GHz (for 5GHz 802.11a) and 2.4GHz
-    /// (for 2.4GHz 802.11), do not restrict the 802.11 frequency band of the
-    /// network if unset (the default).
+    pub bssid: Option<MacAddress>,
+    /// Possible bands are 5GHz (for 5GHz 802.11a), 2.4GHz
+    /// (for 2.4GHz 802.11) and 6GHz (for 6GHz 802.11ax), do not restrict the
+    /// 802.11 frequency band of the network if unset (the default).
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
     pub band: Option<WirelessBand>,
     /// Wireless channel to use for the Wi-Fi connection. Because channel
@@ -80,14 +139,47 @@ pub struct AccessPointConfig {
         serde(deserialize_with = "crate::bool::string_or_bool_option")
     )]
     pub hidden: Option<bool>,
+    /// Settings of the NetworkManager connection profile for this access point.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub networkmanager: Option<NetworkManagerSettings>,
 }
 
-/// Possible bands are 5GHz (for 5GHz 802.11a) and 2.4GHz
-/// (for 2.4GHz 802.11), do not restrict the 802.11 frequency band of the
-/// network if unset (the default).
-#[derive(Debug, Clone, PartialEq, Eq)]
+impl AccessPointConfig {
+    /// An access point using WPA2 personal, i.e. WPA-PSK, with the given passphrase.
+    pub fn wpa2(passphrase: impl Into<SecretString>) -> Self {
+        Self::with_key_management(KeyManagmentMode::Psk, passphrase)
+    }
+
+    /// An access point using WPA3 personal, i.e. SAE, with the given passphrase.
+    pub fn wpa3(passphrase: impl Into<SecretString>) -> Self {
+        Self::with_key_management(KeyManagmentMode::Sae, passphrase)
+    }
+
+    fn with_key_management(
+        key_management: KeyManagmentMode,
+        passphrase: impl Into<SecretString>,
+    ) -> Self {
+        Self {
+            auth: Some(AuthConfig {
+                key_management: Some(key_management),
+                password: Some(passphrase.into()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+}
+
+/// Possible bands are 5GHz (for 5GHz 802.11a), 2.4GHz
+/// (for 2.4GHz 802.11) and 6GHz (for 6GHz 802.11ax), do not restrict the
+/// 802.11 frequency band of the network if unset (the default).
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 pub enum WirelessBand {
     /// 2.4Ghz
     #[cfg_attr(feature = "serde", serde(rename = "2.4GHz"))]
@@ -95,15 +187,48 @@ pub enum WirelessBand {
     /// 5Ghz
     #[cfg_attr(feature = "serde", serde(rename = "5GHz"))]
     Ghz5,
+    /// 6Ghz
+    #[cfg_attr(feature = "serde", serde(rename = "6GHz"))]
+    Ghz6,
+}
+
+impl WirelessBand {
+    pub const VARIANTS: &'static [Self] = &[Self::Ghz2, Self::Ghz5, Self::Ghz6];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Ghz2 => "2.4GHz",
+            Self::Ghz5 => "5GHz",
+            Self::Ghz6 => "6GHz",
+        }
+    }
+
+    /// Whether the given channel number belongs to this band.
+    pub fn has_channel(&self, channel: u32) -> bool {
+        match self {
+            Self::Ghz2 => (1..=14).contains(&channel),
+            Self::Ghz5 => {
+                (36..=144).contains(&channel) && channel.is_multiple_of(4)
+                    || (149..=177).contains(&channel) && (channel - 1).is_multiple_of(4)
+            }
+            Self::Ghz6 => {
+                channel == 2 || (1..=233).contains(&channel) && (channel - 1).is_multiple_of(4)
+            }
+        }
+    }
 }
 
 /// Possible access point modes are infrastructure (the default),
 /// ap (create an access point to which other devices can connect),
 /// and adhoc (peer to peer networks without a central access point).
 /// ap is only supported with NetworkManager.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 pub enum AccessPointMode {
     #[cfg_attr(feature = "serde", serde(rename = "infrastructure"))]
     Infrastructure,
@@ -113,14 +238,30 @@ pub enum AccessPointMode {
     Adhoc,
 }
 
+impl AccessPointMode {
+    pub const VARIANTS: &'static [Self] = &[Self::Infrastructure, Self::Ap, Self::Adhoc];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Infrastructure => "infrastructure",
+            Self::Ap => "ap",
+            Self::Adhoc => "adhoc",
+        }
+    }
+}
+
 /// This enables WakeOnWLan on supported devices. Not all drivers support all
 /// options. May be any combination of any, disconnect, magic_pkt,
 /// gtk_rekey_failure, eap_identity_req, four_way_handshake,
 /// rfkill_release or tcp (NetworkManager only). Or the exclusive
 /// default flag (the default).
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 pub enum WakeOnWLan {
     #[cfg_attr(feature = "serde", serde(rename = "any"))]
     Any,
@@ -141,3 +282,65 @@ pub enum WakeOnWLan {
     #[cfg_attr(feature = "serde", serde(rename = "default"))]
     Default,
 }
+
+impl WakeOnWLan {
+    pub const VARIANTS: &'static [Self] = &[
+        Self::Any,
+        Self::Disconnect,
+        Self::MagicPkt,
+        Self::GtkRekeyFailure,
+        Self::EapIdentityReq,
+        Self::FourWayHandshake,
+        Self::RfkillRelease,
+        Self::Tcp,
+        Self::Default,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Any => "any",
+            Self::Disconnect => "disconnect",
+            Self::MagicPkt => "magic_pkt",
+            Self::GtkRekeyFailure => "gtk_rekey_failure",
+            Self::EapIdentityReq => "eap_identity_req",
+            Self::FourWayHandshake => "four_way_handshake",
+            Self::RfkillRelease => "rfkill_release",
+            Self::Tcp => "tcp",
+            Self::Default => "default",
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::WirelessBand;
+
+    fn channels(band: WirelessBand) -> Vec<u32> {
+        (0..=250).filter(|c| band.has_channel(*c)).collect()
+    }
+
+    #[test]
+    fn ghz2_channels() {
+        assert_eq!(channels(WirelessBand::Ghz2), (1..=14).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn ghz5_channels() {
+        let expected = (36..=144)
+            .step_by(4)
+            .chain((149..=177).step_by(4))
+            .collect::<Vec<_>>();
+        assert_eq!(channels(WirelessBand::Ghz5), expected);
+        assert!(!WirelessBand::Ghz5.has_channel(6));
+        assert!(!WirelessBand::Ghz5.has_channel(148));
+    }
+
+    #[test]
+    fn ghz6_channels() {
+        let mut expected = (1..=233).step_by(4).collect::<Vec<_>>();
+        expected.insert(1, 2);
+        assert_eq!(channels(WirelessBand::Ghz6), expected);
+        assert!(!WirelessBand::Ghz6.has_channel(3));
+        assert!(!WirelessBand::Ghz6.has_channel(237));
+    }
+}
@@ -4,6 +4,8 @@ use serde::{Deserialize, Serialize};
 #[cfg(feature = "derive_builder")]
 use derive_builder::Builder;
 
+use std::collections::HashMap;
+
 /// Several DHCP behavior overrides are available. Most currently only have any
 /// effect when using the networkd backend, with the exception of use-routes
 /// and route-metric.
@@ -19,11 +21,19 @@ use derive_builder::Builder;
 /// When using the NetworkManager backend, different values may be specified for
 /// dhcp4-overrides and dhcp6-overrides, and will be applied to the DHCP
 /// client processes as specified in the netplan YAML.
-#[derive(Default, Debug, Clone, PartialEq, Eq)]
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "derive_builder", derive(Builder))]
+#[cfg_attr(
+    feature = "derive_builder",
+    builder(setter(strip_option, into), default)
+)]
 #[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 pub struct DhcpOverrides {
     /// Default: true. When true, the DNS servers received from the
     /// DHCP server will be used and take precedence over any statically
@@ -114,9 +124,13 @@ pub struct DhcpOverrides {
     pub use_domains: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 pub enum Ipv6AddressGeneration {
     #[cfg_attr(feature = "serde", serde(rename = "eui64"))]
     Eui64,
@@ -124,29 +138,97 @@ pub enum Ipv6AddressGeneration {
     StablePrivacy,
 }
 
+impl Ipv6AddressGeneration {
+    pub const VARIANTS: &'static [Self] = &[Self::Eui64, Self::StablePrivacy];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Eui64 => "eui64",
+            Self::StablePrivacy => "stable-privacy",
+        }
+    }
+}
+
+/// A static address in CIDR notation. The address can either be given as a plain string,
+/// or as a mapping from the address to additional properties:
+///
+/// ```yaml
+/// addresses:
+///   - 10.0.0.15/24:
+///       lifetime: 0
+///       label: "maas"
+///   - "2001:1::1/64"
+/// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "serde", serde(untagged))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 pub enum AddressMapping {
     Simple(String),
-    Complex {
-        /// Default: forever. This can be forever or 0 and corresponds
-        /// to the PreferredLifetime option in systemd-networkd’s Address
-        /// section. Currently supported on the networkd backend only.
-        lifetime: PreferredLifetime,
-        /// An IP address label, equivalent to the ip address label
-        /// command. Currently supported on the networkd backend only.
-        label: String,
-    },
+    Complex(HashMap<String, AddressProperties>),
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+impl AddressMapping {
+    /// The address(es) in CIDR notation, without their properties.
+    pub fn addresses(&self) -> Vec<&str> {
+        match self {
+            Self::Simple(address) => vec![address.as_str()],
+            Self::Complex(addresses) => addresses.keys().map(String::as_str).collect(),
+        }
+    }
+}
+
+/// Additional properties of a static address.
+#[derive(Default, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "derive_builder", derive(Builder))]
+#[cfg_attr(
+    feature = "derive_builder",
+    builder(setter(strip_option, into), default)
+)]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
+pub struct AddressProperties {
+    /// Default: forever. This can be forever or 0 and corresponds
+    /// to the PreferredLifetime option in systemd-networkd’s Address
+    /// section. Currently supported on the networkd backend only.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub lifetime: Option<PreferredLifetime>,
+    /// An IP address label, equivalent to the ip address label
+    /// command. Currently supported on the networkd backend only.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub label: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 pub enum PreferredLifetime {
     #[cfg_attr(feature = "serde", serde(rename = "forever"))]
     Forever,
     #[cfg_attr(feature = "serde", serde(rename = "0"))]
     Zero,
 }
+
+impl PreferredLifetime {
+    pub const VARIANTS: &'static [Self] = &[Self::Forever, Self::Zero];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Forever => "forever",
+            Self::Zero => "0",
+        }
+    }
+}
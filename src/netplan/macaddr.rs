@@ -0,0 +1,144 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// The type of the MAC address fields. With the `macaddr` feature this is [MacAddr], so
+/// only valid MAC addresses can be set, without it a [String] as written in the
+/// configuration.
+pub use alias::MacAddress;
+
+#[cfg(feature = "macaddr")]
+mod alias {
+    pub type MacAddress = super::MacAddr;
+}
+
+#[cfg(not(feature = "macaddr"))]
+mod alias {
+    pub type MacAddress = String;
+}
+
+/// A MAC address, either a 6 octet ethernet address or a 20 octet InfiniBand address.
+/// Parsed from and displayed in the form `XX:XX:XX:XX:XX:XX`, the hexadecimal digits are
+/// case-insensitive when parsing and lowercase when displayed.
+///
+/// ```
+/// use netplan_types::MacAddr;
+///
+/// let mac: MacAddr = "52:54:00:6B:3C:58".parse().unwrap();
+/// assert_eq!(mac, "52:54:00:6b:3c:58".parse().unwrap());
+/// assert_eq!(mac.to_string(), "52:54:00:6b:3c:58");
+/// assert!("52:54:00:6b:3c".parse::<MacAddr>().is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
+pub struct MacAddr {
+    octets: [u8; 20],
+    len: usize,
+}
+
+impl MacAddr {
+    /// The octets of the address.
+    pub fn octets(&self) -> &[u8] {
+        &self.octets[..self.len]
+    }
+}
+
+impl From<[u8; 6]> for MacAddr {
+    fn from(value: [u8; 6]) -> Self {
+        let mut octets = [0; 20];
+        octets[..6].copy_from_slice(&value);
+        Self { octets, len: 6 }
+    }
+}
+
+/// The error returned when parsing a [MacAddr] fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MacAddrParseError(String);
+
+impl Display for MacAddrParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "'{}' is not a MAC address in the form XX:XX:XX:XX:XX:XX",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for MacAddrParseError {}
+
+impl FromStr for MacAddr {
+    type Err = MacAddrParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || MacAddrParseError(s.to_string());
+
+        let mut octets = [0; 20];
+        let mut len = 0;
+        for octet in s.split(':') {
+            if len == octets.len()
+                || octet.len() != 2
+                || !octet.bytes().all(|b| b.is_ascii_hexdigit())
+            {
+                return Err(error());
+            }
+
+            octets[len] = u8::from_str_radix(octet, 16).map_err(|_| error())?;
+            len += 1;
+        }
+
+        match len {
+            6 | 20 => Ok(Self { octets, len }),
+            _ => Err(error()),
+        }
+    }
+}
+
+impl Display for MacAddr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (i, octet) in self.octets().iter().enumerate() {
+            if i > 0 {
+                f.write_str(":")?;
+            }
+            write!(f, "{octet:02x}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for MacAddr {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for MacAddr {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        value.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for MacAddr {
+    fn schema_name() -> String {
+        "MacAddr".to_string()
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        String::json_schema(gen)
+    }
+}
+
+/// The MAC address in its normalized form, with lowercase hexadecimal digits.
+pub(crate) fn normalize_mac(mac: &MacAddress) -> MacAddress {
+    #[cfg(feature = "macaddr")]
+    return *mac;
+    #[cfg(not(feature = "macaddr"))]
+    return mac.to_lowercase();
+}
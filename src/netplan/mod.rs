@@ -9,3 +9,9 @@ pub use authentication::*;
 
 mod dhcp;
 pub use dhcp::*;
+
+mod macaddr;
+pub use macaddr::*;
+
+mod secret;
+pub use secret::*;
@@ -13,11 +13,19 @@ use derive_builder::Builder;
 ///
 /// For from, to, and via, both IPv4 and IPv6 addresses are
 /// recognized, and must be in the form addr/prefixlen or addr.
-#[derive(Default, Debug, Clone, PartialEq, Eq)]
+#[derive(Default, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "derive_builder", derive(Builder))]
+#[cfg_attr(
+    feature = "derive_builder",
+    builder(setter(strip_option, into), default)
+)]
 #[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 pub struct RoutingConfig {
     /// Set a source IP address for traffic going through the route.
     /// (NetworkManager: as of v1.8.0)
@@ -77,10 +85,14 @@ pub struct RoutingConfig {
 /// The type of route. Valid options are “unicast” (default), “anycast”,
 /// “blackhole”, “broadcast”, “local”, “multicast”, “nat”, “prohibit”,
 /// “throw”, “unreachable” or “xresolve”.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 pub enum RouteType {
     Unicast,
     Anycast,
@@ -95,29 +107,85 @@ pub enum RouteType {
     Xresolve,
 }
 
+impl RouteType {
+    pub const VARIANTS: &'static [Self] = &[
+        Self::Unicast,
+        Self::Anycast,
+        Self::Blackhole,
+        Self::Broadcast,
+        Self::Local,
+        Self::Multicast,
+        Self::Nat,
+        Self::Prohibit,
+        Self::Throw,
+        Self::Unreachable,
+        Self::Xresolve,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Unicast => "unicast",
+            Self::Anycast => "anycast",
+            Self::Blackhole => "blackhole",
+            Self::Broadcast => "broadcast",
+            Self::Local => "local",
+            Self::Multicast => "multicast",
+            Self::Nat => "nat",
+            Self::Prohibit => "prohibit",
+            Self::Throw => "throw",
+            Self::Unreachable => "unreachable",
+            Self::Xresolve => "xresolve",
+        }
+    }
+}
+
 /// The route scope, how wide-ranging it is to the network. Possible
 /// values are “global”, “link”, or “host”.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 pub enum RouteScope {
     Global,
     Link,
     Host,
 }
 
+impl RouteScope {
+    pub const VARIANTS: &'static [Self] = &[Self::Global, Self::Link, Self::Host];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Global => "global",
+            Self::Link => "link",
+            Self::Host => "host",
+        }
+    }
+}
+
 /// The routing-policy block defines extra routing policy for a network,
 /// where traffic may be handled specially based on the source IP, firewall
 /// marking, etc.
 ///
 /// For from, to, both IPv4 and IPv6 addresses are recognized, and
 /// must be in the form addr/prefixlen or addr.
-#[derive(Default, Debug, Clone, PartialEq, Eq)]
+#[derive(Default, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "derive_builder", derive(Builder))]
+#[cfg_attr(
+    feature = "derive_builder",
+    builder(setter(strip_option, into), default)
+)]
 #[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 pub struct RoutingPolicy {
     /// Set a source IP address to match traffic for this policy rule.
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
@@ -149,10 +217,18 @@ pub struct RoutingPolicy {
 }
 
 /// Set DNS servers and search domains, for manual address configuration.
-#[derive(Default, Debug, Clone, PartialEq, Eq)]
+#[derive(Default, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "derive_builder", derive(Builder))]
+#[cfg_attr(
+    feature = "derive_builder",
+    builder(setter(strip_option, into), default)
+)]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 pub struct NameserverConfig {
     /// A list of IPv4 or IPv6 addresses
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
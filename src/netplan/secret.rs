@@ -0,0 +1,114 @@
+/// The type of the fields holding passwords and keys. With the `secret` feature this is
+/// `Secret<String>`, so credentials are not printed by `Debug` and are cleared from
+/// memory when dropped, without it a [String].
+pub use alias::SecretString;
+
+#[cfg(feature = "secret")]
+mod alias {
+    pub type SecretString = super::Secret<String>;
+}
+
+#[cfg(not(feature = "secret"))]
+mod alias {
+    pub type SecretString = String;
+}
+
+#[cfg(feature = "secret")]
+use std::fmt::{Debug, Formatter};
+#[cfg(feature = "secret")]
+use std::ops::Deref;
+#[cfg(feature = "secret")]
+use zeroize::Zeroize;
+
+/// A value which is printed as `[REDACTED]` by `Debug`, and zeroized when dropped.
+/// Serialized and deserialized as the value itself.
+///
+/// ```
+/// use netplan_types::Secret;
+///
+/// let password = Secret::from("hunter22");
+/// assert_eq!(format!("{password:?}"), "[REDACTED]");
+/// assert_eq!(password.expose(), "hunter22");
+/// ```
+#[cfg(feature = "secret")]
+#[derive(Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
+pub struct Secret<T: Zeroize>(T);
+
+#[cfg(feature = "secret")]
+impl<T: Zeroize> Secret<T> {
+    /// Wrap the value.
+    pub fn new(value: T) -> Self {
+        Self(value)
+    }
+
+    /// The value itself.
+    pub fn expose(&self) -> &T {
+        &self.0
+    }
+}
+
+#[cfg(feature = "secret")]
+impl<T: Zeroize> Deref for Secret<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+#[cfg(feature = "secret")]
+impl<T: Zeroize> From<T> for Secret<T> {
+    fn from(value: T) -> Self {
+        Self(value)
+    }
+}
+
+#[cfg(feature = "secret")]
+impl From<&str> for Secret<String> {
+    fn from(value: &str) -> Self {
+        Self(value.to_string())
+    }
+}
+
+#[cfg(feature = "secret")]
+impl<T: Zeroize> Debug for Secret<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("[REDACTED]")
+    }
+}
+
+#[cfg(feature = "secret")]
+impl<T: Zeroize> Drop for Secret<T> {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+#[cfg(all(feature = "secret", feature = "serde"))]
+impl<T: Zeroize + serde::Serialize> serde::Serialize for Secret<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+#[cfg(all(feature = "secret", feature = "serde"))]
+impl<'de, T: Zeroize + serde::Deserialize<'de>> serde::Deserialize<'de> for Secret<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize(deserializer).map(Self)
+    }
+}
+
+#[cfg(all(feature = "secret", feature = "schemars"))]
+impl<T: Zeroize + schemars::JsonSchema> schemars::JsonSchema for Secret<T> {
+    fn schema_name() -> String {
+        T::schema_name()
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        T::json_schema(gen)
+    }
+}
@@ -0,0 +1,858 @@
+//! Rendering configurations into NetworkManager keyfiles.
+
+use crate::network_manager::{NetworkManagerConnection, EXTENSION, FILE_PREFIX};
+use crate::{
+    AccessPointConfig, AccessPointMode, AnyDeviceConfig, BondParameters, BridgeParameters,
+    CommonPropertiesAllDevices, DeviceKind, KeyManagmentMode, NetplanConfig,
+    NetworkManagerSettings, Renderer, RouteType, UnitFile, UnitSection, WirelessBand,
+};
+use std::io::Write;
+use std::path::Path;
+
+impl NetplanConfig {
+    /// Render the devices using the NetworkManager renderer into the keyfile connection
+    /// profiles `netplan generate` writes to `/run/NetworkManager/system-connections`,
+    /// sorted by file name.
+    ///
+    /// Ethernets, VLANs, bonds, bridges and dummy devices get one profile each, named
+    /// `netplan-<id>.nmconnection`. Wifi devices get one profile per access point, named
+    /// after the device and the SSID, with WPA-PSK, SAE or EAP security. Other kinds of
+    /// devices are left out, as are devices which use networkd. The `networkmanager`
+    /// settings of a device are applied last, so their passthrough overrides the generated
+    /// values, and `nm-devices` are rendered from these settings alone.
+    ///
+    /// ```
+    /// use netplan_types::{NetplanConfig, Renderer};
+    ///
+    /// let config = NetplanConfig::build(|net| {
+    ///     net.ethernet("eth0", |e| e.dhcp4(true));
+    /// })
+    /// .with_renderer(Renderer::NetworkManager);
+    ///
+    /// let connections = config.to_network_manager();
+    /// assert_eq!(connections[0].file_name, "netplan-eth0.nmconnection");
+    /// assert_eq!(connections[0].keyfile.get("connection", "type"), Some("ethernet"));
+    /// assert_eq!(connections[0].keyfile.get("ipv4", "method"), Some("auto"));
+    /// ```
+    pub fn to_network_manager(&self) -> Vec<NetworkManagerConnection> {
+        let network = &self.network;
+        let mut devices = network
+            .devices()
+            .filter(|(kind, _, device)| {
+                let renderer = device
+                    .common_all()
+                    .and_then(|c| c.renderer.clone())
+                    .or_else(|| network.renderer.clone())
+                    .unwrap_or(Renderer::Networkd);
+                renderer == Renderer::NetworkManager || *kind == DeviceKind::NmDevice
+            })
+            .collect::<Vec<_>>();
+        devices.sort_by_key(|(_, name, _)| *name);
+
+        let mut connections = Vec::new();
+        for (_, name, device) in devices {
+            let connection = |id: String, keyfile: UnitFile| NetworkManagerConnection {
+                file_name: format!("{}.{EXTENSION}", escape(&id)),
+                keyfile,
+            };
+
+            let settings = device.common_all().and_then(|c| c.networkmanager.as_ref());
+            if let AnyDeviceConfig::Wifi(wifi) = device {
+                let mut access_points = wifi.access_points.iter().flatten().collect::<Vec<_>>();
+                access_points.sort_by_key(|(ssid, _)| *ssid);
+                for (ssid, access_point) in access_points {
+                    let id = format!("{FILE_PREFIX}{name}-{ssid}");
+                    let mut keyfile = connection_keyfile(self, &id, name, device);
+                    wifi_sections(&mut keyfile, ssid, access_point);
+                    apply_settings(&mut keyfile, settings);
+                    apply_settings(&mut keyfile, access_point.networkmanager.as_ref());
+                    connections.push(connection(id, keyfile));
+                }
+            } else if let AnyDeviceConfig::NmDevice(_) = device {
+                let id = format!("{FILE_PREFIX}{name}");
+                let mut keyfile = UnitFile::default();
+                keyfile.set("connection", "id", &id);
+                apply_settings(&mut keyfile, settings);
+                connections.push(connection(id, keyfile));
+            } else if let Some(mut keyfile) = device_keyfile(self, name, device) {
+                apply_settings(&mut keyfile, settings);
+                connections.push(connection(format!("{FILE_PREFIX}{name}"), keyfile));
+            }
+        }
+
+        connections.sort_by(|a, b| a.file_name.cmp(&b.file_name));
+        connections
+    }
+
+    /// Render the configuration with [NetplanConfig::to_network_manager], and write the
+    /// profiles to `dir`, like `netplan generate` does for
+    /// `/run/NetworkManager/system-connections`. Profiles a previous run generated for
+    /// devices which no longer exist are removed.
+    ///
+    /// The profiles are only readable by their owner, as NetworkManager ignores keyfiles
+    /// others can read, and they may contain wifi passwords.
+    pub fn write_network_manager(&self, dir: impl AsRef<Path>) -> std::io::Result<()> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)?;
+
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            let generated = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(FILE_PREFIX));
+            if generated
+                && path
+                    .extension()
+                    .is_some_and(|extension| extension == EXTENSION)
+            {
+                std::fs::remove_file(&path)?;
+            }
+        }
+
+        for connection in self.to_network_manager() {
+            let mut options = std::fs::OpenOptions::new();
+            options.write(true).create(true).truncate(true);
+            #[cfg(unix)]
+            std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+            let mut file = options.open(dir.join(&connection.file_name))?;
+            file.write_all(connection.keyfile.to_string().as_bytes())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Apply the NetworkManager settings of a device or access point to its keyfile, overriding
+/// the generated values with the passthrough settings.
+fn apply_settings(keyfile: &mut UnitFile, settings: Option<&NetworkManagerSettings>) {
+    let Some(settings) = settings else {
+        return;
+    };
+
+    let connection = [
+        ("id", &settings.name),
+        ("uuid", &settings.uuid),
+        ("stable-id", &settings.stable_id),
+        ("interface-name", &settings.device),
+    ];
+    for (key, value) in connection {
+        if let Some(value) = value {
+            keyfile.set("connection", key, value);
+        }
+    }
+
+    for (key, value) in settings.passthrough.iter().flatten() {
+        if let Some((section, key)) = key.split_once('.') {
+            keyfile.set(section, key, value);
+        }
+    }
+}
+
+/// Escape the characters which cannot be part of a file name, e.g. in an SSID.
+pub(super) fn escape(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '_' | '.' => c.to_string(),
+            c => {
+                let mut buffer = [0; 4];
+                c.encode_utf8(&mut buffer)
+                    .bytes()
+                    .map(|b| format!("%{b:02X}"))
+                    .collect()
+            }
+        })
+        .collect()
+}
+
+/// A keyfile boolean.
+fn bool(value: bool) -> &'static str {
+    if value {
+        "true"
+    } else {
+        "false"
+    }
+}
+
+/// A time span in whole seconds or milliseconds, as NetworkManager expects them. Time spans
+/// without a unit are in the unit NetworkManager expects already.
+fn time_span(value: &str, milliseconds: bool) -> String {
+    let total = if let Some(number) = value.strip_suffix("ms") {
+        number.parse::<u64>().ok()
+    } else if let Some(number) = value.strip_suffix('s') {
+        number.parse::<u64>().ok().map(|seconds| seconds * 1000)
+    } else {
+        let scale = if milliseconds { 1 } else { 1000 };
+        value.parse::<u64>().ok().map(|number| number * scale)
+    };
+
+    match total {
+        Some(total) if milliseconds => total.to_string(),
+        Some(total) => (total / 1000).to_string(),
+        None => value.to_string(),
+    }
+}
+
+/// The keyfile of a device other than a wifi, `None` if NetworkManager cannot manage it.
+fn device_keyfile(
+    config: &NetplanConfig,
+    name: &str,
+    device: AnyDeviceConfig<'_>,
+) -> Option<UnitFile> {
+    let kind_section = match device {
+        AnyDeviceConfig::Ethernet(_) => None,
+        AnyDeviceConfig::Vlan(vlan) => {
+            let mut section = UnitSection::new("vlan");
+            section.add_opt("id", vlan.id);
+            section.add_opt("parent", vlan.link.as_deref());
+            Some(section)
+        }
+        AnyDeviceConfig::Bond(bond) => Some(bond_section(bond.parameters.as_deref())),
+        AnyDeviceConfig::Bridge(bridge) => Some(bridge_section(bridge.parameters.as_deref())),
+        AnyDeviceConfig::DummyDevice(_) => None,
+        _ => return None,
+    };
+
+    let mut keyfile = connection_keyfile(config, &format!("{FILE_PREFIX}{name}"), name, device);
+    if let Some(section) = kind_section {
+        keyfile.push(section);
+    }
+    Some(keyfile)
+}
+
+/// The sections shared by every kind of connection: the connection itself, matching the
+/// interface, its controller and IP configuration.
+fn connection_keyfile(
+    config: &NetplanConfig,
+    id: &str,
+    name: &str,
+    device: AnyDeviceConfig<'_>,
+) -> UnitFile {
+    let common = device.common_all();
+    let physical = device.common_physical();
+    let matching = physical.and_then(|p| p.r#match.as_ref());
+    let mut keyfile = UnitFile::default();
+
+    let mut connection = UnitSection::new("connection");
+    connection.add("id", id);
+    connection.add(
+        "type",
+        match device {
+            AnyDeviceConfig::Ethernet(_) => "ethernet",
+            AnyDeviceConfig::Wifi(_) => "wifi",
+            AnyDeviceConfig::Vlan(_) => "vlan",
+            AnyDeviceConfig::Bond(_) => "bond",
+            AnyDeviceConfig::Bridge(_) => "bridge",
+            _ => "dummy",
+        },
+    );
+    let interface_name = physical
+        .and_then(|p| p.set_name.as_deref())
+        .or_else(|| matching.and_then(|m| m.name.as_deref()))
+        .filter(|name| !crate::glob::is_glob(name));
+    match (interface_name, matching) {
+        (Some(name), _) => connection.add("interface-name", name),
+        (None, None) => connection.add("interface-name", name),
+        (None, Some(_)) => {}
+    }
+    if common.and_then(|c| c.activation_mode.as_ref()).is_some() {
+        connection.add("autoconnect", "false");
+    }
+
+    // Members only have a controller, their IP configuration is ignored
+    let controller = config.network.devices().find_map(|(_, id, controller)| {
+        let (kind, members) = match controller {
+            AnyDeviceConfig::Bond(bond) => ("bond", bond.interfaces.as_deref()?),
+            AnyDeviceConfig::Bridge(bridge) => ("bridge", bridge.interfaces.as_deref()?),
+            _ => return None,
+        };
+        members
+            .iter()
+            .any(|member| member == name)
+            .then_some((kind, id, controller))
+    });
+    if let Some((kind, id, _)) = controller {
+        connection.add("master", id);
+        connection.add("slave-type", kind);
+    }
+    keyfile.push(connection);
+
+    let mut matches = UnitSection::new("match");
+    if interface_name.is_none() {
+        matches.add_opt("interface-name", matching.and_then(|m| m.name.as_deref()));
+    }
+    matches.add_opt(
+        "driver",
+        matching
+            .and_then(|m| m.driver.as_ref())
+            .map(|d| d.join(";")),
+    );
+    keyfile.push(matches);
+
+    let link_section = match device {
+        AnyDeviceConfig::Ethernet(_) => Some("ethernet"),
+        AnyDeviceConfig::Wifi(_) => Some("wifi"),
+        _ => None,
+    };
+    if let Some(link_section) = link_section {
+        let mut link = UnitSection::new(link_section);
+        link.add_opt(
+            "mac-address",
+            matching
+                .and_then(|m| m.macaddress.as_ref())
+                .map(|mac| mac.to_string()),
+        );
+        link.add_opt(
+            "cloned-mac-address",
+            common
+                .and_then(|c| c.macaddress.as_ref())
+                .map(|mac| mac.to_string()),
+        );
+        link.add_opt("mtu", common.and_then(|c| c.mtu));
+        if link_section == "ethernet" {
+            let wakeonlan = physical.and_then(|p| p.wakeonlan).unwrap_or_default();
+            link.add("wake-on-lan", if wakeonlan { 64 } else { 0 });
+        }
+        keyfile.push(link);
+    } else if let Some(mtu) = common.and_then(|c| c.mtu) {
+        let mut ethernet = UnitSection::new("ethernet");
+        ethernet.add("mtu", mtu);
+        keyfile.push(ethernet);
+    }
+
+    if let Some((_, _, AnyDeviceConfig::Bridge(bridge))) = controller {
+        let parameters = bridge.parameters.as_deref();
+        let mut port = UnitSection::new("bridge-port");
+        port.add_opt("priority", parameters.and_then(|p| p.port_priority));
+        port.add_opt("path-cost", parameters.and_then(|p| p.path_cost));
+        keyfile.push(port);
+    }
+
+    if controller.is_none() {
+        let common = common.cloned().unwrap_or_default();
+        keyfile.push(ip_section(&common, false));
+        keyfile.push(ip_section(&common, true));
+    }
+
+    keyfile
+}
+
+/// The `[ipv4]` or `[ipv6]` section of a connection.
+fn ip_section(common: &CommonPropertiesAllDevices, ipv6: bool) -> UnitSection {
+    let is_family = |address: &str| address.contains(':') == ipv6;
+    let mut section = UnitSection::new(if ipv6 { "ipv6" } else { "ipv4" });
+
+    let addresses = common
+        .addresses
+        .iter()
+        .flatten()
+        .flat_map(|a| a.addresses())
+        .filter(|address| is_family(address))
+        .collect::<Vec<_>>();
+    let dhcp = if ipv6 { common.dhcp6 } else { common.dhcp4 };
+    let link_local = common.link_local.as_ref().map_or(ipv6, |families| {
+        families
+            .iter()
+            .any(|f| f == if ipv6 { "ipv6" } else { "ipv4" })
+    });
+    let method = match (dhcp == Some(true), !addresses.is_empty()) {
+        (true, _) => "auto",
+        (false, true) => "manual",
+        (false, false) if ipv6 => "ignore",
+        (false, false) if link_local => "link-local",
+        (false, false) => "disabled",
+    };
+    section.add("method", method);
+    if matches!(method, "ignore" | "disabled") {
+        return section;
+    }
+
+    for (i, address) in addresses.iter().enumerate() {
+        section.add(format!("address{}", i + 1), address);
+    }
+    let gateway = if ipv6 {
+        common.gateway6.as_deref()
+    } else {
+        common.gateway4.as_deref()
+    };
+    section.add_opt("gateway", gateway);
+
+    if let Some(nameservers) = &common.nameservers {
+        let dns = nameservers
+            .addresses
+            .iter()
+            .flatten()
+            .filter(|address| is_family(address))
+            .map(|address| format!("{address};"))
+            .collect::<String>();
+        if !dns.is_empty() {
+            section.add("dns", dns);
+        }
+        let search = nameservers
+            .search
+            .iter()
+            .flatten()
+            .map(|domain| format!("{domain};"))
+            .collect::<String>();
+        if !search.is_empty() {
+            section.add("dns-search", search);
+        }
+    }
+
+    let routes = common.routes.iter().flatten().filter(|route| {
+        let to = route.to.as_deref().unwrap_or("default");
+        match route.via.as_deref() {
+            Some(via) => is_family(via),
+            None => to != "default" && is_family(to),
+        }
+    });
+    for (i, route) in routes.enumerate() {
+        let to = match route.to.as_deref() {
+            None | Some("default") if ipv6 => "::/0",
+            None | Some("default") => "0.0.0.0/0",
+            Some(to) => to,
+        };
+        let mut value = to.to_string();
+        if let Some(via) = &route.via {
+            value.push_str(&format!(",{via}"));
+            if let Some(metric) = route.metric {
+                value.push_str(&format!(",{metric}"));
+            }
+        } else if let Some(metric) = route.metric {
+            value.push_str(&format!(",,{metric}"));
+        }
+        section.add(format!("route{}", i + 1), value);
+
+        let mut options = Vec::new();
+        if let Some(table) = route.table {
+            options.push(format!("table={table}"));
+        }
+        if let Some(kind) = route.r#type.as_ref().filter(|t| **t != RouteType::Unicast) {
+            options.push(format!("type={}", kind.as_str()));
+        }
+        if route.on_link == Some(true) {
+            options.push("onlink=true".to_string());
+        }
+        if let Some(from) = &route.from {
+            options.push(format!("src={from}"));
+        }
+        if let Some(mtu) = route.mtu {
+            options.push(format!("mtu={mtu}"));
+        }
+        if !options.is_empty() {
+            section.add(format!("route{}_options", i + 1), options.join(","));
+        }
+    }
+
+    let policies = common.routing_policy.iter().flatten().filter(|policy| {
+        let address = policy.from.as_deref().or(policy.to.as_deref());
+        address.map_or(!ipv6, is_family)
+    });
+    for (i, policy) in policies.enumerate() {
+        let mut rule = Vec::new();
+        if let Some(priority) = policy.priority {
+            rule.push(format!("priority {priority}"));
+        }
+        if let Some(from) = &policy.from {
+            rule.push(format!("from {from}"));
+        }
+        if let Some(to) = &policy.to {
+            rule.push(format!("to {to}"));
+        }
+        if let Some(mark) = policy.mark {
+            rule.push(format!("fwmark {mark}"));
+        }
+        if let Some(tos) = &policy.type_of_service {
+            rule.push(format!("tos {tos}"));
+        }
+        rule.push(format!("table {}", policy.table));
+        section.add(format!("routing-rule{}", i + 1), rule.join(" "));
+    }
+
+    let overrides = if ipv6 {
+        common.dhcp6_overrides.as_ref()
+    } else {
+        common.dhcp4_overrides.as_ref()
+    };
+    if let Some(overrides) = overrides.filter(|_| dhcp == Some(true)) {
+        if overrides.use_dns == Some(false) {
+            section.add("ignore-auto-dns", "true");
+        }
+        if overrides.use_routes == Some(false) {
+            section.add("ignore-auto-routes", "true");
+        }
+        section.add_opt("route-metric", overrides.route_metric);
+        section.add_opt("dhcp-hostname", overrides.hostname.as_deref());
+        section.add_opt("dhcp-send-hostname", overrides.send_hostname.map(bool));
+    }
+
+    if ipv6 {
+        if common.ipv6_privacy == Some(true) {
+            section.add("ip6-privacy", 2);
+        }
+        section.add_opt(
+            "addr-gen-mode",
+            common.ipv6_address_generation.as_ref().map(|g| g.as_str()),
+        );
+        section.add_opt("token", common.ipv6_address_token.as_deref());
+        section.add_opt("mtu", common.ipv6_mtu);
+    }
+
+    section
+}
+
+fn bond_section(parameters: Option<&BondParameters>) -> UnitSection {
+    let mut section = UnitSection::new("bond");
+    let Some(parameters) = parameters else {
+        return section;
+    };
+
+    let ms = |value: &Option<String>| value.as_deref().map(|t| time_span(t, true));
+    section.add_opt("mode", parameters.mode.as_ref().map(|m| m.as_str()));
+    section.add_opt(
+        "lacp_rate",
+        parameters.lacp_rate.as_ref().map(|r| r.as_str()),
+    );
+    section.add_opt("miimon", ms(&parameters.mii_monitor_interval));
+    section.add_opt("min_links", parameters.min_links);
+    section.add_opt(
+        "xmit_hash_policy",
+        parameters.transmit_hash_policy.as_ref().map(|p| p.as_str()),
+    );
+    section.add_opt(
+        "ad_select",
+        parameters.ad_select.as_ref().map(|s| s.as_str()),
+    );
+    section.add_opt(
+        "all_slaves_active",
+        parameters.all_slaves_active.map(|active| active as u8),
+    );
+    section.add_opt("arp_interval", ms(&parameters.arp_interval));
+    section.add_opt(
+        "arp_ip_target",
+        parameters.arp_ip_targets.as_ref().map(|t| t.join(",")),
+    );
+    section.add_opt(
+        "arp_validate",
+        parameters.arp_validate.as_ref().map(|v| v.as_str()),
+    );
+    section.add_opt(
+        "arp_all_targets",
+        parameters.arp_all_targets.as_ref().map(|t| t.as_str()),
+    );
+    section.add_opt("updelay", ms(&parameters.up_delay));
+    section.add_opt("downdelay", ms(&parameters.down_delay));
+    section.add_opt(
+        "fail_over_mac",
+        parameters.fail_over_mac_policy.as_ref().map(|p| p.as_str()),
+    );
+    section.add_opt("num_grat_arp", parameters.gratuitous_arp);
+    section.add_opt("packets_per_slave", parameters.packets_per_slave);
+    section.add_opt(
+        "primary_reselect",
+        parameters
+            .primary_reselect_policy
+            .as_ref()
+            .map(|p| p.as_str()),
+    );
+    section.add_opt("resend_igmp", parameters.resend_igmp);
+    section.add_opt(
+        "lp_interval",
+        parameters
+            .learn_packet_interval
+            .as_deref()
+            .map(|t| time_span(t, false)),
+    );
+    section.add_opt("primary", parameters.primary.as_deref());
+    section
+}
+
+fn bridge_section(parameters: Option<&BridgeParameters>) -> UnitSection {
+    let mut section = UnitSection::new("bridge");
+    let Some(parameters) = parameters else {
+        return section;
+    };
+
+    let s = |value: &Option<String>| value.as_deref().map(|t| time_span(t, false));
+    section.add_opt("ageing-time", s(&parameters.ageing_time));
+    section.add_opt("priority", parameters.priority);
+    section.add_opt("forward-delay", s(&parameters.forward_delay));
+    section.add_opt("hello-time", s(&parameters.hello_time));
+    section.add_opt("max-age", s(&parameters.max_age));
+    section.add_opt("stp", parameters.stp.map(bool));
+    section
+}
+
+/// The wifi and security sections of the connection to an access point.
+fn wifi_sections(keyfile: &mut UnitFile, ssid: &str, access_point: &AccessPointConfig) {
+    let wifi = match keyfile.sections.iter_mut().find(|s| s.name == "wifi") {
+        Some(wifi) => wifi,
+        None => {
+            keyfile.sections.insert(1, UnitSection::new("wifi"));
+            &mut keyfile.sections[1]
+        }
+    };
+    wifi.add("ssid", ssid);
+    wifi.add(
+        "mode",
+        match access_point.mode {
+            Some(AccessPointMode::Ap) => "ap",
+            Some(AccessPointMode::Adhoc) => "adhoc",
+            _ => "infrastructure",
+        },
+    );
+    wifi.add_opt(
+        "band",
+        access_point.band.as_ref().and_then(|band| match band {
+            WirelessBand::Ghz2 => Some("bg"),
+            WirelessBand::Ghz5 => Some("a"),
+            WirelessBand::Ghz6 => None,
+        }),
+    );
+    wifi.add_opt("channel", access_point.channel);
+    wifi.add_opt(
+        "bssid",
+        access_point.bssid.as_ref().map(|bssid| bssid.to_string()),
+    );
+    if access_point.hidden == Some(true) {
+        wifi.add("hidden", "true");
+    }
+
+    let auth = access_point.auth.as_ref();
+    let key_management = auth.and_then(|a| a.key_management.as_ref());
+    let password = auth
+        .and_then(|a| a.password.as_ref())
+        .or(access_point.password.as_ref())
+        .map(|password| password.as_str());
+    let key_mgmt = match (key_management, password) {
+        (Some(KeyManagmentMode::None), _) | (None, None) => return,
+        (Some(KeyManagmentMode::Psk), _) | (None, Some(_)) => "wpa-psk",
+        (Some(KeyManagmentMode::Sae), _) => "sae",
+        (Some(KeyManagmentMode::Eap), _) => "wpa-eap",
+        (Some(KeyManagmentMode::EightZeroTwoDotOneX), _) => "ieee8021x",
+    };
+
+    let mut security = UnitSection::new("wifi-security");
+    security.add("key-mgmt", key_mgmt);
+    let eap = matches!(key_mgmt, "wpa-eap" | "ieee8021x");
+    if !eap {
+        security.add_opt("psk", password);
+    }
+    let position = keyfile
+        .sections
+        .iter()
+        .position(|s| s.name == "wifi")
+        .map_or(keyfile.sections.len(), |i| i + 1);
+    keyfile.sections.insert(position, security);
+
+    if let Some(auth) = auth.filter(|_| eap) {
+        let mut dot1x = UnitSection::new("802-1x");
+        if let Some(method) = &auth.method {
+            dot1x.add("eap", format!("{};", method.as_str()));
+        }
+        dot1x.add_opt("identity", auth.identity.as_deref());
+        dot1x.add_opt("anonymous-identity", auth.anonymous_identity.as_deref());
+        dot1x.add_opt("password", password);
+        dot1x.add_opt("ca-cert", auth.ca_certificate.as_deref());
+        dot1x.add_opt("client-cert", auth.client_certificate.as_deref());
+        dot1x.add_opt("private-key", auth.client_key.as_deref());
+        dot1x.add_opt("private-key-password", auth.client_key_password.as_deref());
+        dot1x.add_opt("phase2-auth", auth.phase2_auth.as_deref());
+        keyfile.sections.insert(position + 1, dot1x);
+    }
+}
+
+#[cfg(all(test, feature = "yaml"))]
+mod test {
+    use crate::NetplanConfig;
+
+    #[test]
+    fn render_keyfiles() {
+        let config = NetplanConfig::from_yaml(
+            r#"
+network:
+  version: 2
+  renderer: NetworkManager
+  ethernets:
+    eth0: {}
+    eth1:
+      renderer: networkd
+  wifis:
+    wlan0:
+      dhcp4: true
+      access-points:
+        "home net":
+          password: hunter22
+        corp:
+          auth:
+            key-management: eap
+            method: peap
+            identity: alice
+            password: secret
+  bonds:
+    bond0:
+      interfaces: [eth0]
+      parameters: {mode: 802.3ad, mii-monitor-interval: 100}
+  vlans:
+    vlan10:
+      id: 10
+      link: bond0
+      addresses: [10.0.10.2/24, "2001:db8::2/64"]
+      nameservers: {addresses: [10.0.10.1], search: [lan]}
+      routes:
+        - to: default
+          via: 10.0.10.1
+        - to: 10.1.0.0/16
+          via: 10.0.10.254
+          metric: 50
+          table: 100
+"#,
+        )
+        .unwrap();
+
+        let connections = config.to_network_manager();
+        let rendered = connections
+            .iter()
+            .map(|c| format!("# {}\n{}", c.file_name, c.keyfile))
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert_eq!(
+            rendered,
+            r#"# netplan-bond0.nmconnection
+[connection]
+id=netplan-bond0
+type=bond
+interface-name=bond0
+
+[ipv4]
+method=disabled
+
+[ipv6]
+method=ignore
+
+[bond]
+mode=802.3ad
+miimon=100
+
+# netplan-eth0.nmconnection
+[connection]
+id=netplan-eth0
+type=ethernet
+interface-name=eth0
+master=bond0
+slave-type=bond
+
+[ethernet]
+wake-on-lan=0
+
+# netplan-vlan10.nmconnection
+[connection]
+id=netplan-vlan10
+type=vlan
+interface-name=vlan10
+
+[ipv4]
+method=manual
+address1=10.0.10.2/24
+dns=10.0.10.1;
+dns-search=lan;
+route1=0.0.0.0/0,10.0.10.1
+route2=10.1.0.0/16,10.0.10.254,50
+route2_options=table=100
+
+[ipv6]
+method=manual
+address1=2001:db8::2/64
+dns-search=lan;
+
+[vlan]
+id=10
+parent=bond0
+
+# netplan-wlan0-corp.nmconnection
+[connection]
+id=netplan-wlan0-corp
+type=wifi
+interface-name=wlan0
+
+[wifi]
+ssid=corp
+mode=infrastructure
+
+[wifi-security]
+key-mgmt=wpa-eap
+
+[802-1x]
+eap=peap;
+identity=alice
+password=secret
+
+[ipv4]
+method=auto
+
+[ipv6]
+method=ignore
+
+# netplan-wlan0-home%20net.nmconnection
+[connection]
+id=netplan-wlan0-home net
+type=wifi
+interface-name=wlan0
+
+[wifi]
+ssid=home net
+mode=infrastructure
+
+[wifi-security]
+key-mgmt=wpa-psk
+psk=hunter22
+
+[ipv4]
+method=auto
+
+[ipv6]
+method=ignore
+"#
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("netplan-old.nmconnection"), "").unwrap();
+        config.write_network_manager(dir.path()).unwrap();
+        let mut names = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect::<Vec<_>>();
+        names.sort_unstable();
+        let expected = connections
+            .iter()
+            .map(|c| c.file_name.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(names, expected);
+    }
+
+    #[test]
+    fn eap_without_method() {
+        let config = NetplanConfig::from_yaml(
+            r#"
+network:
+  version: 2
+  renderer: NetworkManager
+  wifis:
+    wlan0:
+      access-points:
+        corp:
+          auth:
+            key-management: eap
+            identity: alice
+"#,
+        )
+        .unwrap();
+
+        let connections = config.to_network_manager();
+        let keyfile = &connections[0].keyfile;
+        assert_eq!(keyfile.get("802-1x", "identity"), Some("alice"));
+        assert_eq!(keyfile.get("802-1x", "eap"), None);
+        assert_eq!(keyfile.get("802-1x", "phase2-auth"), None);
+    }
+}
@@ -0,0 +1,926 @@
+//! Importing NetworkManager keyfiles into configurations.
+
+use crate::network_manager::generate::escape;
+use crate::network_manager::{NetworkManagerConnection, EXTENSION, FILE_PREFIX};
+use crate::{
+    AccessPointConfig, AccessPointMode, ActivationMode, AdSelect, AddressMapping, ArpAllTargets,
+    ArpValidate, AuthConfig, AuthMethod, BondConfig, BondMode, BondParameters, BridgeConfig,
+    BridgeParameters, CommonPropertiesAllDevices, CommonPropertiesPhysicalDeviceType, DeviceKind,
+    DhcpOverrides, DummyDeviceConfig, EthernetConfig, FailOverMacPolicy, Ipv6AddressGeneration,
+    KeyManagmentMode, LacpRate, MatchConfig, NameserverConfig, NetplanConfig, NetworkConfig,
+    NetworkManagerSettings, NmDeviceConfig, PhysicalProperties, PrimaryReselectPolicy, Renderer,
+    RouteType, RoutingConfig, RoutingPolicy, SecretString, TransmitHashPolicy, UnitFile,
+    UnitParseError, VlanConfig, WifiConfig, WirelessBand,
+};
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+
+/// The error returned when the keyfiles of a directory cannot be imported.
+#[derive(Debug)]
+pub enum NetworkManagerImportError {
+    /// A keyfile could not be read.
+    Io(std::io::Error),
+    /// A keyfile is not a valid keyfile.
+    Parse {
+        file_name: String,
+        error: UnitParseError,
+    },
+}
+
+impl Display for NetworkManagerImportError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "Failed to read keyfile: {e}"),
+            Self::Parse { file_name, error } => write!(f, "Invalid keyfile {file_name}: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for NetworkManagerImportError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Parse { error, .. } => Some(error),
+        }
+    }
+}
+
+impl From<std::io::Error> for NetworkManagerImportError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl NetplanConfig {
+    /// Lift NetworkManager connection profiles into a configuration, like `netplan migrate`
+    /// does, e.g. to capture a desktop setup in netplan YAML. This is the reverse of
+    /// [NetplanConfig::to_network_manager].
+    ///
+    /// Ethernet, wifi, VLAN, bond, bridge and dummy profiles become devices of their kind,
+    /// with their addresses, routes, routing policy, DHCP, DNS, MTU, MAC addresses, wifi
+    /// security and membership of bonds and bridges. Profiles for the same wifi device
+    /// become access points of a single device. The device ID is taken from the file name
+    /// of profiles netplan generated, from the interface name of other profiles, or is
+    /// `NM-<uuid>` if they are not bound to an interface.
+    ///
+    /// Settings netplan has no equivalent for are kept in the `passthrough` of the
+    /// `networkmanager` settings of the device, or its access point, and the name and UUID
+    /// of the profile are kept as well. Profiles of other types, like VPNs, become
+    /// `nm-devices` defined entirely by their passthrough. The global renderer is set to
+    /// NetworkManager.
+    ///
+    /// ```
+    /// use netplan_types::{NetplanConfig, NetworkManagerConnection};
+    ///
+    /// let connection = NetworkManagerConnection::parse(
+    ///     "Wired connection 1.nmconnection",
+    ///     "[connection]\nid=Wired connection 1\ntype=ethernet\ninterface-name=eth0\n\
+    ///      [ipv4]\nmethod=auto\n[proxy]\nbrowser-only=true\n",
+    /// )
+    /// .unwrap();
+    ///
+    /// let config = NetplanConfig::from_network_manager([&connection]);
+    /// let common = config.network.ethernets.unwrap()["eth0"].common_all.clone().unwrap();
+    /// assert_eq!(common.dhcp4, Some(true));
+    ///
+    /// let settings = common.networkmanager.unwrap();
+    /// assert_eq!(settings.name.as_deref(), Some("Wired connection 1"));
+    /// assert_eq!(settings.passthrough.unwrap()["proxy.browser-only"], "true");
+    /// ```
+    pub fn from_network_manager<'a>(
+        connections: impl IntoIterator<Item = &'a NetworkManagerConnection>,
+    ) -> Self {
+        let mut connections = connections.into_iter().collect::<Vec<_>>();
+        connections.sort_by(|a, b| a.file_name.cmp(&b.file_name));
+        let ids = connections
+            .iter()
+            .map(|connection| device_id(connection))
+            .collect::<Vec<_>>();
+
+        // Controllers and parents are referenced by their interface name or UUID
+        let mut references = HashMap::new();
+        for (connection, id) in connections.iter().zip(&ids) {
+            for key in ["interface-name", "uuid"] {
+                if let Some(reference) = connection.keyfile.get("connection", key) {
+                    references.entry(reference).or_insert(id.as_str());
+                }
+            }
+        }
+
+        let mut config = NetplanConfig::default();
+        let network = &mut config.network;
+        let mut members = Vec::new();
+        for (connection, id) in connections.iter().zip(&ids) {
+            if let Some(member) = import_connection(network, id, connection, &references) {
+                members.push(member);
+            }
+        }
+
+        for member in members {
+            add_member(network, member);
+        }
+
+        if network
+            .devices()
+            .any(|(kind, _, _)| kind != DeviceKind::NmDevice)
+        {
+            network.renderer = Some(Renderer::NetworkManager);
+        }
+        config
+    }
+
+    /// Import the keyfiles of a directory like `/etc/NetworkManager/system-connections`,
+    /// see [NetplanConfig::from_network_manager].
+    pub fn from_network_manager_dir(
+        dir: impl AsRef<Path>,
+    ) -> Result<Self, NetworkManagerImportError> {
+        let mut connections = Vec::new();
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else {
+                continue;
+            };
+            let file_name = file_name.to_string();
+            if path.extension().and_then(|e| e.to_str()) != Some(EXTENSION) {
+                continue;
+            }
+
+            let contents = std::fs::read_to_string(&path)?;
+            let connection = NetworkManagerConnection::parse(&file_name, &contents)
+                .map_err(|error| NetworkManagerImportError::Parse { file_name, error })?;
+            connections.push(connection);
+        }
+
+        Ok(Self::from_network_manager(&connections))
+    }
+}
+
+/// A keyfile, keeping track of the settings which have been imported, so the others can be
+/// passed through.
+struct Keyfile<'a> {
+    unit: &'a UnitFile,
+    imported: RefCell<HashSet<(&'a str, &'a str)>>,
+}
+
+impl<'a> Keyfile<'a> {
+    fn new(unit: &'a UnitFile) -> Self {
+        Self {
+            unit,
+            imported: RefCell::new(HashSet::new()),
+        }
+    }
+
+    /// The value of `key`, which is marked as imported.
+    fn get(&self, section: &str, key: &str) -> Option<&'a str> {
+        self.parse(section, key, Some)
+    }
+
+    /// The value of `key` converted by `f`. It is only marked as imported if `f` accepts
+    /// it, otherwise it is passed through.
+    fn parse<T>(
+        &self,
+        section: &str,
+        key: &str,
+        f: impl FnOnce(&'a str) -> Option<T>,
+    ) -> Option<T> {
+        let (section, key, value) = self
+            .entries()
+            .find(|(s, k, _)| *s == section && *k == key)?;
+        let value = f(value)?;
+        self.imported.borrow_mut().insert((section, key));
+        Some(value)
+    }
+
+    /// The values of the numbered keys `<prefix>N`, like `address1`, in the order of
+    /// their numbers.
+    fn numbered(&self, section: &str, prefix: &str) -> Vec<(&'a str, &'a str)> {
+        let mut values = self
+            .entries()
+            .filter(|(s, _, _)| *s == section)
+            .filter_map(|(section, key, value)| {
+                let number = key.strip_prefix(prefix)?.parse::<u32>().ok()?;
+                Some((number, section, key, value))
+            })
+            .collect::<Vec<_>>();
+        values.sort_by_key(|(number, _, _, _)| *number);
+
+        let mut imported = self.imported.borrow_mut();
+        values
+            .into_iter()
+            .map(|(_, section, key, value)| {
+                imported.insert((section, key));
+                (key, value)
+            })
+            .collect()
+    }
+
+    /// The settings which have not been imported, as `section.key`.
+    fn passthrough(&self) -> Option<BTreeMap<String, String>> {
+        let imported = self.imported.borrow();
+        let passthrough = self
+            .entries()
+            .filter(|(section, key, _)| !imported.contains(&(*section, *key)))
+            .map(|(section, key, value)| (format!("{section}.{key}"), value.to_string()))
+            .collect::<BTreeMap<_, _>>();
+        (!passthrough.is_empty()).then_some(passthrough)
+    }
+
+    fn entries(&self) -> impl Iterator<Item = (&'a str, &'a str, &'a str)> {
+        self.unit.sections.iter().flat_map(|section| {
+            section
+                .entries
+                .iter()
+                .map(|(key, value)| (section.name.as_str(), key.as_str(), value.as_str()))
+        })
+    }
+}
+
+/// The kind of device a connection type configures, `None` for types netplan cannot express.
+fn device_kind(connection_type: Option<&str>) -> Option<DeviceKind> {
+    match connection_type? {
+        "ethernet" | "802-3-ethernet" => Some(DeviceKind::Ethernet),
+        "wifi" | "802-11-wireless" => Some(DeviceKind::Wifi),
+        "vlan" => Some(DeviceKind::Vlan),
+        "bond" => Some(DeviceKind::Bond),
+        "bridge" => Some(DeviceKind::Bridge),
+        "dummy" => Some(DeviceKind::DummyDevice),
+        _ => None,
+    }
+}
+
+/// The device ID for a connection profile, see [NetplanConfig::from_network_manager].
+fn device_id(connection: &NetworkManagerConnection) -> String {
+    let keyfile = &connection.keyfile;
+    let file_name = &connection.file_name;
+    let stem = file_name
+        .strip_suffix(&format!(".{EXTENSION}"))
+        .unwrap_or(file_name);
+
+    if let Some(id) = stem.strip_prefix(FILE_PREFIX) {
+        // Profiles for access points are named after the wifi and the SSID
+        let ssid = keyfile.get("wifi", "ssid").map(escape);
+        return match ssid.and_then(|ssid| id.strip_suffix(&format!("-{ssid}"))) {
+            Some(id) => id.to_string(),
+            None => id.to_string(),
+        };
+    }
+
+    let interface_name = keyfile.get("connection", "interface-name");
+    match (
+        device_kind(keyfile.get("connection", "type")),
+        interface_name,
+    ) {
+        (Some(_), Some(name)) => name.to_string(),
+        _ => match keyfile.get("connection", "uuid") {
+            Some(uuid) => format!("NM-{uuid}"),
+            None => stem.to_string(),
+        },
+    }
+}
+
+/// A keyfile boolean.
+fn boolean(value: &str) -> Option<bool> {
+    match value {
+        "true" | "yes" | "1" => Some(true),
+        "false" | "no" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+/// The variant whose string representation is `value`.
+fn variant<T: Clone>(variants: &[T], as_str: fn(&T) -> &'static str, value: &str) -> Option<T> {
+    variants.iter().find(|v| as_str(v) == value).cloned()
+}
+
+/// The entries of a list, like `dns=10.0.0.1;10.0.0.2;`.
+fn list(value: &str) -> impl Iterator<Item = &str> {
+    value
+        .split([';', ','])
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+}
+
+/// A member of a bond or bridge, added once every controller has been defined.
+struct Member<'a> {
+    id: &'a str,
+    controller: &'a str,
+    port_priority: Option<u8>,
+    path_cost: Option<i32>,
+}
+
+fn add_member(network: &mut NetworkConfig, member: Member<'_>) {
+    match network.device_mut(member.controller) {
+        Some(crate::AnyDeviceConfigMut::Bond(bond)) => bond
+            .interfaces
+            .get_or_insert_with(Vec::new)
+            .push(member.id.to_string()),
+        Some(crate::AnyDeviceConfigMut::Bridge(bridge)) => {
+            bridge
+                .interfaces
+                .get_or_insert_with(Vec::new)
+                .push(member.id.to_string());
+            if member.port_priority.is_some() || member.path_cost.is_some() {
+                let parameters = bridge.parameters.get_or_insert_with(Default::default);
+                parameters.port_priority = member.port_priority.or(parameters.port_priority);
+                parameters.path_cost = member.path_cost.or(parameters.path_cost);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Define the device a connection profile configures, returning its membership of a bond
+/// or bridge.
+fn import_connection<'a>(
+    network: &mut NetworkConfig,
+    id: &'a str,
+    connection: &'a NetworkManagerConnection,
+    references: &HashMap<&str, &'a str>,
+) -> Option<Member<'a>> {
+    let keyfile = Keyfile::new(&connection.keyfile);
+    let get = |section: &str, key: &str| keyfile.get(section, key);
+    let kind = keyfile.parse("connection", "type", |t| device_kind(Some(t)));
+    let ssid = get("wifi", "ssid").filter(|_| kind == Some(DeviceKind::Wifi));
+    let generated_name = match ssid {
+        Some(ssid) => format!("{FILE_PREFIX}{id}-{ssid}"),
+        None => format!("{FILE_PREFIX}{id}"),
+    };
+    let mut settings = NetworkManagerSettings {
+        name: get("connection", "id")
+            .filter(|name| *name != generated_name)
+            .map(str::to_string),
+        uuid: get("connection", "uuid").map(str::to_string),
+        stable_id: get("connection", "stable-id").map(str::to_string),
+        ..Default::default()
+    };
+
+    let Some(kind) = kind else {
+        settings.device = get("connection", "interface-name").map(str::to_string);
+        settings.passthrough = keyfile.passthrough();
+        let common = CommonPropertiesAllDevices {
+            networkmanager: Some(settings),
+            ..Default::default()
+        };
+        network.add_nm_device(
+            id,
+            NmDeviceConfig {
+                common_all: Some(Box::new(common)),
+            },
+        );
+        return None;
+    };
+
+    let mut common = CommonPropertiesAllDevices::default();
+    let mut physical = CommonPropertiesPhysicalDeviceType::default();
+    let interface_name = get("connection", "interface-name");
+    if keyfile.parse("connection", "autoconnect", boolean) == Some(false) {
+        common.activation_mode = Some(ActivationMode::Manual);
+    }
+
+    if kind.is_physical() {
+        let link = if kind == DeviceKind::Wifi {
+            "wifi"
+        } else {
+            "ethernet"
+        };
+        let matching = MatchConfig {
+            name: get("match", "interface-name").map(str::to_string),
+            macaddress: keyfile.parse(link, "mac-address", |mac| mac.parse().ok()),
+            driver: get("match", "driver")
+                .map(|drivers| list(drivers).map(str::to_string).collect()),
+        };
+        match interface_name {
+            Some(name) if matching != MatchConfig::default() => {
+                physical.set_name = Some(name.to_string())
+            }
+            Some(name) if name != id => {
+                physical.r#match = Some(MatchConfig {
+                    name: Some(name.to_string()),
+                    ..Default::default()
+                })
+            }
+            _ => {}
+        }
+        if matching != MatchConfig::default() {
+            physical.r#match = Some(matching);
+        }
+        common.macaddress = keyfile.parse(link, "cloned-mac-address", |mac| mac.parse().ok());
+        common.mtu = keyfile.parse(link, "mtu", |mtu| mtu.parse().ok());
+        if kind == DeviceKind::Ethernet {
+            // Only disabling Wake-on-LAN and waking on magic packets can be expressed
+            physical.wakeonlan = keyfile
+                .parse("ethernet", "wake-on-lan", |wol| {
+                    match wol.parse::<u32>().ok()? {
+                        0 => Some(None),
+                        64 => Some(Some(true)),
+                        _ => None,
+                    }
+                })
+                .flatten();
+        }
+    } else {
+        common.mtu = keyfile.parse("ethernet", "mtu", |mtu| mtu.parse().ok());
+    }
+
+    let controller = keyfile
+        .parse("connection", "master", |master| {
+            references.get(master).copied()
+        })
+        .filter(|_| {
+            let slave_type = get("connection", "slave-type");
+            matches!(slave_type, Some("bond" | "bridge"))
+        });
+    let member = controller.map(|controller| Member {
+        id,
+        controller,
+        port_priority: keyfile.parse("bridge-port", "priority", |p| p.parse().ok()),
+        path_cost: keyfile.parse("bridge-port", "path-cost", |c| c.parse().ok()),
+    });
+    if member.is_none() {
+        import_ip(&keyfile, &mut common, false);
+        import_ip(&keyfile, &mut common, true);
+    }
+
+    let mut access_point = None;
+    match kind {
+        DeviceKind::Ethernet => {
+            network.add_ethernet(id, EthernetConfig::default());
+        }
+        DeviceKind::Wifi => {
+            access_point = ssid.map(|ssid| (ssid, import_access_point(&keyfile)));
+            if network.device(id).is_none() {
+                network.add_wifi(id, WifiConfig::default());
+            }
+        }
+        DeviceKind::Vlan => {
+            let vlan = VlanConfig {
+                id: keyfile.parse("vlan", "id", |id| id.parse().ok()),
+                link: keyfile
+                    .parse("vlan", "parent", |parent| references.get(parent).copied())
+                    .map(str::to_string),
+                ..Default::default()
+            };
+            network.add_vlan(id, vlan);
+        }
+        DeviceKind::Bond => {
+            let parameters = import_bond(&keyfile);
+            let bond = BondConfig {
+                parameters: (parameters != BondParameters::default()).then(|| Box::new(parameters)),
+                ..Default::default()
+            };
+            network.add_bond(id, bond);
+        }
+        DeviceKind::Bridge => {
+            let parameters = import_bridge(&keyfile);
+            let bridge = BridgeConfig {
+                parameters: (parameters != BridgeParameters::default())
+                    .then(|| Box::new(parameters)),
+                ..Default::default()
+            };
+            network.add_bridge(id, bridge);
+        }
+        _ => {
+            network.add_dummy_device(id, DummyDeviceConfig::default());
+        }
+    }
+
+    settings.passthrough = keyfile.passthrough();
+    let settings = (settings != NetworkManagerSettings::default()).then_some(settings);
+    match (network.device_mut(id), access_point) {
+        (Some(crate::AnyDeviceConfigMut::Wifi(wifi)), Some((ssid, mut access_point))) => {
+            // The device is shared by the profiles of its access points
+            access_point.networkmanager = settings;
+            wifi.access_points
+                .get_or_insert_with(HashMap::new)
+                .insert(ssid.to_string(), access_point);
+            if wifi.common_all.is_some() || wifi.common_physical.is_some() {
+                return member;
+            }
+        }
+        _ => common.networkmanager = settings,
+    }
+
+    let Some(mut device) = network.device_mut(id) else {
+        return member;
+    };
+    if common != CommonPropertiesAllDevices::default() {
+        *crate::CommonProperties::common_all_mut(&mut device) = common;
+    }
+    if physical != CommonPropertiesPhysicalDeviceType::default() {
+        match device {
+            crate::AnyDeviceConfigMut::Ethernet(ethernet) => {
+                *ethernet.common_physical_mut() = physical
+            }
+            crate::AnyDeviceConfigMut::Wifi(wifi) => *wifi.common_physical_mut() = physical,
+            _ => {}
+        }
+    }
+    member
+}
+
+/// Import the `[ipv4]` or `[ipv6]` section of a connection.
+fn import_ip(keyfile: &Keyfile<'_>, common: &mut CommonPropertiesAllDevices, ipv6: bool) {
+    let section = if ipv6 { "ipv6" } else { "ipv4" };
+    let get = |key: &str| keyfile.get(section, key);
+    let family = section.to_string();
+
+    let method = get("method");
+    match method {
+        Some("auto" | "dhcp") => {
+            if ipv6 {
+                common.dhcp6 = Some(true);
+            } else {
+                common.dhcp4 = Some(true);
+            }
+        }
+        Some("link-local") => {
+            let link_local = common
+                .link_local
+                .get_or_insert_with(|| vec!["ipv6".to_string()]);
+            if !link_local.contains(&family) {
+                link_local.push(family.clone());
+            }
+        }
+        Some("disabled") if ipv6 => {
+            let link_local = common
+                .link_local
+                .get_or_insert_with(|| vec!["ipv6".to_string()]);
+            link_local.retain(|f| *f != family);
+        }
+        _ => {}
+    }
+    if common.link_local.as_deref() == Some(&["ipv6".to_string()]) {
+        common.link_local = None;
+    }
+
+    let mut routes = Vec::new();
+    for (_, address) in keyfile.numbered(section, "address") {
+        let mut parts = address.split(',');
+        if let Some(address) = parts.next().filter(|a| !a.is_empty()) {
+            common
+                .addresses
+                .get_or_insert_with(Vec::new)
+                .push(AddressMapping::Simple(address.to_string()));
+        }
+        if let Some(gateway) = parts.next().filter(|g| !g.is_empty()) {
+            routes.push(RoutingConfig {
+                to: Some("default".to_string()),
+                via: Some(gateway.to_string()),
+                ..Default::default()
+            });
+        }
+    }
+    if let Some(gateway) = get("gateway") {
+        routes.push(RoutingConfig {
+            to: Some("default".to_string()),
+            via: Some(gateway.to_string()),
+            ..Default::default()
+        });
+    }
+
+    for (key, route) in keyfile.numbered(section, "route") {
+        let mut parts = route.split(',');
+        let to = match parts.next() {
+            None | Some("0.0.0.0/0" | "::/0") => "default".to_string(),
+            Some(to) => to.to_string(),
+        };
+        let via = parts.next().filter(|via| !via.is_empty());
+        let mut route = RoutingConfig {
+            to: Some(to),
+            via: via.map(str::to_string),
+            metric: parts.next().and_then(|metric| metric.parse().ok()),
+            ..Default::default()
+        };
+
+        let options = get(&format!("{key}_options")).unwrap_or_default();
+        for option in list(options) {
+            let Some((name, value)) = option.split_once('=') else {
+                continue;
+            };
+            match name {
+                "table" => route.table = value.parse().ok(),
+                "type" => route.r#type = variant(RouteType::VARIANTS, RouteType::as_str, value),
+                "onlink" => route.on_link = boolean(value).filter(|on_link| *on_link),
+                "src" => route.from = Some(value.to_string()),
+                "mtu" => route.mtu = value.parse().ok(),
+                _ => {}
+            }
+        }
+        routes.push(route);
+    }
+    if !routes.is_empty() {
+        common.routes.get_or_insert_with(Vec::new).extend(routes);
+    }
+
+    for (_, rule) in keyfile.numbered(section, "routing-rule") {
+        let mut policy = RoutingPolicy {
+            table: 254,
+            ..Default::default()
+        };
+        let mut words = rule.split_whitespace();
+        while let (Some(name), Some(value)) = (words.next(), words.next()) {
+            match name {
+                "priority" => policy.priority = value.parse().ok(),
+                "from" => policy.from = Some(value.to_string()),
+                "to" => policy.to = Some(value.to_string()),
+                "fwmark" => policy.mark = value.parse().ok(),
+                "tos" => policy.type_of_service = Some(value.to_string()),
+                "table" => policy.table = value.parse().unwrap_or(policy.table),
+                _ => {}
+            }
+        }
+        common
+            .routing_policy
+            .get_or_insert_with(Vec::new)
+            .push(policy);
+    }
+
+    let mut nameservers = common.nameservers.take().unwrap_or_default();
+    for (values, key) in [
+        (&mut nameservers.addresses, "dns"),
+        (&mut nameservers.search, "dns-search"),
+    ] {
+        // The search domains are the same for both families
+        for value in get(key).into_iter().flat_map(list) {
+            let values = values.get_or_insert_with(Vec::new);
+            if !values.iter().any(|existing| existing == value) {
+                values.push(value.to_string());
+            }
+        }
+    }
+    common.nameservers = (nameservers != NameserverConfig::default()).then_some(nameservers);
+
+    let dhcp = if ipv6 { common.dhcp6 } else { common.dhcp4 };
+    if dhcp == Some(true) {
+        let overrides = DhcpOverrides {
+            use_dns: keyfile
+                .parse(section, "ignore-auto-dns", boolean)
+                .map(|ignore| !ignore),
+            use_routes: keyfile
+                .parse(section, "ignore-auto-routes", boolean)
+                .map(|ignore| !ignore),
+            route_metric: keyfile.parse(section, "route-metric", |m| m.parse().ok()),
+            hostname: get("dhcp-hostname").map(str::to_string),
+            send_hostname: keyfile.parse(section, "dhcp-send-hostname", boolean),
+            ..Default::default()
+        };
+        let overrides = (overrides != DhcpOverrides::default()).then_some(overrides);
+        if ipv6 {
+            common.dhcp6_overrides = overrides;
+        } else {
+            common.dhcp4_overrides = overrides;
+        }
+    }
+
+    if ipv6 {
+        if keyfile.parse(section, "ip6-privacy", |p| (p == "2").then_some(())) == Some(()) {
+            common.ipv6_privacy = Some(true);
+        }
+        common.ipv6_address_generation = keyfile.parse(section, "addr-gen-mode", |mode| {
+            variant(
+                Ipv6AddressGeneration::VARIANTS,
+                Ipv6AddressGeneration::as_str,
+                mode,
+            )
+        });
+        common.ipv6_address_token = get("token").map(str::to_string);
+        common.ipv6_mtu = keyfile.parse(section, "mtu", |mtu| mtu.parse().ok());
+    }
+}
+
+fn import_bond(keyfile: &Keyfile<'_>) -> BondParameters {
+    let get = |key: &str| keyfile.get("bond", key).map(str::to_string);
+
+    BondParameters {
+        mode: keyfile.parse("bond", "mode", |v| {
+            variant(BondMode::VARIANTS, BondMode::as_str, v)
+        }),
+        lacp_rate: keyfile.parse("bond", "lacp_rate", |v| {
+            variant(LacpRate::VARIANTS, LacpRate::as_str, v)
+        }),
+        mii_monitor_interval: get("miimon"),
+        min_links: keyfile.parse("bond", "min_links", |v| v.parse().ok()),
+        transmit_hash_policy: keyfile.parse("bond", "xmit_hash_policy", |v| {
+            variant(TransmitHashPolicy::VARIANTS, TransmitHashPolicy::as_str, v)
+        }),
+        ad_select: keyfile.parse("bond", "ad_select", |v| {
+            variant(AdSelect::VARIANTS, AdSelect::as_str, v)
+        }),
+        all_slaves_active: keyfile.parse("bond", "all_slaves_active", boolean),
+        arp_interval: get("arp_interval"),
+        arp_ip_targets: keyfile
+            .get("bond", "arp_ip_target")
+            .map(|targets| list(targets).map(str::to_string).collect()),
+        arp_validate: keyfile.parse("bond", "arp_validate", |v| {
+            variant(ArpValidate::VARIANTS, ArpValidate::as_str, v)
+        }),
+        arp_all_targets: keyfile.parse("bond", "arp_all_targets", |v| {
+            variant(ArpAllTargets::VARIANTS, ArpAllTargets::as_str, v)
+        }),
+        up_delay: get("updelay"),
+        down_delay: get("downdelay"),
+        fail_over_mac_policy: keyfile.parse("bond", "fail_over_mac", |v| {
+            variant(FailOverMacPolicy::VARIANTS, FailOverMacPolicy::as_str, v)
+        }),
+        gratuitous_arp: keyfile.parse("bond", "num_grat_arp", |v| v.parse().ok()),
+        packets_per_slave: keyfile.parse("bond", "packets_per_slave", |v| v.parse().ok()),
+        primary_reselect_policy: keyfile.parse("bond", "primary_reselect", |v| {
+            variant(
+                PrimaryReselectPolicy::VARIANTS,
+                PrimaryReselectPolicy::as_str,
+                v,
+            )
+        }),
+        resend_igmp: keyfile.parse("bond", "resend_igmp", |v| v.parse().ok()),
+        learn_packet_interval: get("lp_interval"),
+        primary: get("primary"),
+    }
+}
+
+fn import_bridge(keyfile: &Keyfile<'_>) -> BridgeParameters {
+    let get = |key: &str| keyfile.get("bridge", key).map(str::to_string);
+    BridgeParameters {
+        ageing_time: get("ageing-time"),
+        priority: keyfile.parse("bridge", "priority", |p| p.parse().ok()),
+        forward_delay: get("forward-delay"),
+        hello_time: get("hello-time"),
+        max_age: get("max-age"),
+        stp: keyfile.parse("bridge", "stp", boolean),
+        ..Default::default()
+    }
+}
+
+/// The access point of a wifi profile, with its security settings.
+fn import_access_point(keyfile: &Keyfile<'_>) -> AccessPointConfig {
+    let mut access_point = AccessPointConfig {
+        mode: keyfile.parse("wifi", "mode", |mode| {
+            variant(AccessPointMode::VARIANTS, AccessPointMode::as_str, mode)
+        }),
+        band: keyfile.parse("wifi", "band", |band| match band {
+            "bg" => Some(WirelessBand::Ghz2),
+            "a" => Some(WirelessBand::Ghz5),
+            _ => None,
+        }),
+        channel: keyfile.parse("wifi", "channel", |channel| channel.parse().ok()),
+        bssid: keyfile.parse("wifi", "bssid", |bssid| bssid.parse().ok()),
+        hidden: keyfile
+            .parse("wifi", "hidden", boolean)
+            .filter(|hidden| *hidden),
+        ..Default::default()
+    };
+    if access_point.mode == Some(AccessPointMode::Infrastructure) {
+        access_point.mode = None;
+    }
+
+    let key_management = keyfile.parse("wifi-security", "key-mgmt", |mode| match mode {
+        "wpa-psk" => Some(KeyManagmentMode::Psk),
+        "sae" => Some(KeyManagmentMode::Sae),
+        "wpa-eap" => Some(KeyManagmentMode::Eap),
+        "ieee8021x" => Some(KeyManagmentMode::EightZeroTwoDotOneX),
+        _ => None,
+    });
+    let Some(key_management) = key_management else {
+        return access_point;
+    };
+
+    let get = |key: &str| keyfile.get("802-1x", key).map(str::to_string);
+    let secret = |section: &str, key: &str| keyfile.get(section, key).map(SecretString::from);
+    let auth = match key_management {
+        KeyManagmentMode::Psk | KeyManagmentMode::Sae => AuthConfig {
+            password: secret("wifi-security", "psk"),
+            ..Default::default()
+        },
+        _ => AuthConfig {
+            method: keyfile.parse("802-1x", "eap", |methods| {
+                let method = list(methods).next()?;
+                variant(AuthMethod::VARIANTS, AuthMethod::as_str, method)
+            }),
+            identity: get("identity"),
+            anonymous_identity: get("anonymous-identity"),
+            password: secret("802-1x", "password"),
+            ca_certificate: get("ca-cert"),
+            client_certificate: get("client-cert"),
+            client_key: get("private-key"),
+            client_key_password: secret("802-1x", "private-key-password"),
+            phase2_auth: get("phase2-auth"),
+            ..Default::default()
+        },
+    };
+    access_point.auth = Some(AuthConfig {
+        key_management: Some(key_management),
+        ..auth
+    });
+    access_point
+}
+
+#[cfg(all(test, feature = "yaml"))]
+mod test {
+    use crate::{NetplanConfig, NetworkManagerConnection};
+
+    #[test]
+    fn import_keyfiles() {
+        let config = NetplanConfig::from_yaml(
+            r#"
+network:
+  version: 2
+  renderer: NetworkManager
+  ethernets:
+    eth0: {}
+    lan:
+      match: {macaddress: "52:54:00:aa:bb:01"}
+      set-name: lan0
+      mtu: 9000
+      networkmanager:
+        uuid: 0f1e2d3c-4b5a-6978-8796-a5b4c3d2e1f0
+        passthrough:
+          ethernet.auto-negotiate: "true"
+  wifis:
+    wlan0:
+      dhcp4: true
+      access-points:
+        "home net":
+          auth: {key-management: psk, password: hunter22}
+        corp:
+          auth:
+            key-management: eap
+            method: ttls
+            identity: alice
+            password: secret
+  bonds:
+    bond0:
+      interfaces: [eth0]
+      parameters: {mode: 802.3ad, mii-monitor-interval: "100"}
+  vlans:
+    vlan10:
+      id: 10
+      link: bond0
+      addresses: [10.0.10.2/24, "2001:db8::2/64"]
+      nameservers: {addresses: [10.0.10.1], search: [lan]}
+      routes:
+        - to: default
+          via: 10.0.10.1
+        - to: 10.1.0.0/16
+          via: 10.0.10.254
+          metric: 50
+          table: 100
+      routing-policy:
+        - from: 10.0.10.0/24
+          table: 100
+  nm-devices:
+    NM-5b1f1ec2-5e54-4f5e-8f8e-7b4a0d3c2b1a:
+      networkmanager:
+        name: office
+        uuid: 5b1f1ec2-5e54-4f5e-8f8e-7b4a0d3c2b1a
+        passthrough:
+          connection.type: vpn
+          vpn.service-type: org.freedesktop.NetworkManager.openvpn
+          vpn.remote: vpn.example.com
+"#,
+        )
+        .unwrap();
+
+        let connections = config.to_network_manager();
+        let imported = NetplanConfig::from_network_manager(&connections);
+        assert!(imported.semantically_eq(&config), "{imported:#?}");
+
+        // A profile NetworkManager wrote itself, with settings netplan cannot express
+        let connection = NetworkManagerConnection::parse(
+            "Home.nmconnection",
+            "[connection]\nid=Home\nuuid=2c9f1f3e-8f4a-4e8e-9d3b-1f2e3d4c5b6a\ntype=wifi\n\
+             interface-name=wlp2s0\ntimestamp=1700000000\n\n[wifi]\nmode=infrastructure\n\
+             ssid=Home\n\n[wifi-security]\nkey-mgmt=sae\npsk=hunter22\n\n[ipv4]\nmethod=auto\n\n\
+             [ipv6]\naddr-gen-mode=default\nmethod=auto\n\n[proxy]\n",
+        )
+        .unwrap();
+        let expected = NetplanConfig::from_yaml(
+            r#"
+network:
+  version: 2
+  renderer: NetworkManager
+  wifis:
+    wlp2s0:
+      dhcp4: true
+      dhcp6: true
+      access-points:
+        Home:
+          auth: {key-management: sae, password: hunter22}
+          networkmanager:
+            name: Home
+            uuid: 2c9f1f3e-8f4a-4e8e-9d3b-1f2e3d4c5b6a
+            passthrough:
+              connection.timestamp: "1700000000"
+              ipv6.addr-gen-mode: default
+"#,
+        )
+        .unwrap();
+        let imported = NetplanConfig::from_network_manager([&connection]);
+        assert!(imported.semantically_eq(&expected), "{imported:#?}");
+
+        let dir = tempfile::tempdir().unwrap();
+        config.write_network_manager(dir.path()).unwrap();
+        let loaded = NetplanConfig::from_network_manager_dir(dir.path()).unwrap();
+        assert!(loaded.semantically_eq(&config));
+    }
+}
@@ -0,0 +1,34 @@
+//! Reading and writing NetworkManager keyfiles, to use configurations without the netplan binary.
+
+mod generate;
+mod import;
+pub use import::*;
+
+use crate::{UnitFile, UnitParseError};
+
+/// The prefix of the connection profiles generated for the NetworkManager renderer, the same
+/// one netplan uses.
+pub(crate) const FILE_PREFIX: &str = "netplan-";
+
+/// The extension of NetworkManager keyfiles.
+pub(crate) const EXTENSION: &str = "nmconnection";
+
+/// A NetworkManager connection profile in the keyfile format, e.g. generated by
+/// [NetplanConfig::to_network_manager](crate::NetplanConfig::to_network_manager). Keyfiles
+/// share their syntax with systemd units, so they are represented as a [UnitFile].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetworkManagerConnection {
+    /// The name of the file, e.g. `netplan-eth0.nmconnection`.
+    pub file_name: String,
+    pub keyfile: UnitFile,
+}
+
+impl NetworkManagerConnection {
+    /// Parse the contents of a keyfile.
+    pub fn parse(file_name: impl Into<String>, contents: &str) -> Result<Self, UnitParseError> {
+        Ok(Self {
+            file_name: file_name.into(),
+            keyfile: contents.parse()?,
+        })
+    }
+}
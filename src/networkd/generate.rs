@@ -0,0 +1,914 @@
+//! Rendering configurations into systemd-networkd units.
+
+use crate::networkd::{NetworkdFile, UnitFile, UnitSection, EXTENSIONS, FILE_PREFIX};
+use crate::{
+    AddressMapping, AnyDeviceConfig, CommonPropertiesAllDevices,
+    CommonPropertiesPhysicalDeviceType, DeviceKind, DhcpOverrides, NetplanConfig, Renderer,
+    RouteScope, RouteType, RoutingConfig, RoutingPolicy, TunnelConfig, TunnelKey, TunnelMode,
+};
+use std::io::Write;
+use std::path::Path;
+
+impl NetplanConfig {
+    /// Render the devices using the networkd renderer into the `.network`, `.netdev` and
+    /// `.link` units `netplan generate` writes to `/run/systemd/network`, sorted by file name.
+    ///
+    /// Every device gets a `.network` unit. Virtual devices get a `.netdev` unit creating
+    /// them, and physical devices which are renamed, or have their MTU, Wake-on-LAN or
+    /// offloading configured before they are brought up, get a `.link` unit. Devices which
+    /// use NetworkManager are left out, as are modems, which networkd cannot manage. Wifi
+    /// devices get a `.network` unit, their access points need a wpa_supplicant configuration.
+    ///
+    /// ```
+    /// use netplan_types::NetplanConfig;
+    ///
+    /// let config = NetplanConfig::build(|net| {
+    ///     net.ethernet("eth0", |e| e.dhcp4(true));
+    /// });
+    ///
+    /// let files = config.to_networkd();
+    /// assert_eq!(files[0].file_name, "10-netplan-eth0.network");
+    /// assert_eq!(files[0].unit.get("Match", "Name"), Some("eth0"));
+    /// assert_eq!(files[0].unit.get("Network", "DHCP"), Some("ipv4"));
+    /// ```
+    pub fn to_networkd(&self) -> Vec<NetworkdFile> {
+        let network = &self.network;
+        let mut devices = network
+            .devices()
+            .filter(|(kind, _, device)| {
+                let renderer = device
+                    .common_all()
+                    .and_then(|c| c.renderer.clone())
+                    .or_else(|| network.renderer.clone())
+                    .unwrap_or(Renderer::Networkd);
+                renderer == Renderer::Networkd
+                    && !matches!(kind, DeviceKind::Modem | DeviceKind::NmDevice)
+            })
+            .collect::<Vec<_>>();
+        devices.sort_by_key(|(_, name, _)| *name);
+
+        let mut files = Vec::new();
+        for (_, name, device) in &devices {
+            let mut file = |extension: &str, unit: UnitFile| {
+                files.push(NetworkdFile {
+                    file_name: format!("{FILE_PREFIX}{name}.{extension}"),
+                    unit,
+                })
+            };
+
+            if let Some(unit) = link_unit(device.common_physical(), device.common_all()) {
+                file("link", unit);
+            }
+            if let Some(unit) = netdev_unit(name, *device) {
+                file("netdev", unit);
+            }
+            file("network", network_unit(self, name, *device));
+        }
+
+        files.sort_by(|a, b| a.file_name.cmp(&b.file_name));
+        files
+    }
+
+    /// Render the configuration with [NetplanConfig::to_networkd], and write the units to
+    /// `dir`, like `netplan generate` does for `/run/systemd/network`. Units a previous run
+    /// generated for devices which no longer exist are removed.
+    ///
+    /// Units of WireGuard tunnels contain their private keys, so the units are only
+    /// readable by their owner and, as netplan does, the `systemd-network` group networkd
+    /// runs as, if it exists.
+    pub fn write_networkd(&self, dir: impl AsRef<Path>) -> std::io::Result<()> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)?;
+
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            let generated = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(FILE_PREFIX));
+            let extension = path.extension().and_then(|extension| extension.to_str());
+            if generated && extension.is_some_and(|extension| EXTENSIONS.contains(&extension)) {
+                std::fs::remove_file(&path)?;
+            }
+        }
+
+        #[cfg(unix)]
+        let group = systemd_network_gid();
+        for file in self.to_networkd() {
+            let path = dir.join(&file.file_name);
+            let mut options = std::fs::OpenOptions::new();
+            options.write(true).create(true).truncate(true);
+            #[cfg(unix)]
+            std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o640);
+
+            let mut unit = options.open(&path)?;
+            unit.write_all(file.unit.to_string().as_bytes())?;
+
+            // Without the privileges to change the group, the unit stays readable by the
+            // group of its owner only
+            #[cfg(unix)]
+            if let Some(group) = group {
+                let _ = std::os::unix::fs::chown(&path, None, Some(group));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The ID of the group systemd-networkd runs as, if it exists.
+#[cfg(unix)]
+fn systemd_network_gid() -> Option<u32> {
+    let groups = std::fs::read_to_string("/etc/group").ok()?;
+    groups.lines().find_map(|line| {
+        let mut fields = line.split(':');
+        if fields.next()? != "systemd-network" {
+            return None;
+        }
+        fields.nth(1)?.parse().ok()
+    })
+}
+
+/// The name of the interface created for a device definition.
+fn interface_name<'a>(name: &'a str, device: AnyDeviceConfig<'a>) -> &'a str {
+    device
+        .common_physical()
+        .and_then(|p| p.set_name.as_deref())
+        .unwrap_or(name)
+}
+
+/// A networkd boolean.
+fn bool(value: bool) -> &'static str {
+    if value {
+        "true"
+    } else {
+        "false"
+    }
+}
+
+/// A time span, in `unit` if it has none, e.g. `100` becomes `100ms` for milliseconds.
+fn time_span(value: &str, unit: &str) -> String {
+    if value.chars().all(|c| c.is_ascii_digit()) {
+        format!("{value}{unit}")
+    } else {
+        value.to_string()
+    }
+}
+
+/// The `.link` unit renaming a physical device, or configuring it before it is brought up.
+fn link_unit(
+    physical: Option<&CommonPropertiesPhysicalDeviceType>,
+    common: Option<&CommonPropertiesAllDevices>,
+) -> Option<UnitFile> {
+    let physical = physical?;
+    let matching = physical.r#match.as_ref()?;
+
+    let mut link = UnitSection::new("Link");
+    link.add_opt("Name", physical.set_name.as_deref());
+    link.add_opt(
+        "WakeOnLan",
+        physical
+            .wakeonlan
+            .map(|wol| if wol { "magic" } else { "off" }),
+    );
+    link.add_opt("MTUBytes", common.and_then(|c| c.mtu));
+    let offloads = [
+        ("ReceiveChecksumOffload", physical.receive_checksum_offload),
+        (
+            "TransmitChecksumOffload",
+            physical.transmit_checksum_offload,
+        ),
+        ("TCPSegmentationOffload", physical.tcp_segmentation_offload),
+        (
+            "TCP6SegmentationOffload",
+            physical.tcp6_segmentation_offload,
+        ),
+        (
+            "GenericSegmentationOffload",
+            physical.generic_segmentation_offload,
+        ),
+        ("GenericReceiveOffload", physical.generic_receive_offload),
+        ("LargeReceiveOffload", physical.large_receive_offload),
+    ];
+    for (key, value) in offloads {
+        link.add_opt(key, value.map(bool));
+    }
+    if link.entries.is_empty() {
+        return None;
+    }
+
+    let mut r#match = UnitSection::new("Match");
+    r#match.add_opt("OriginalName", matching.name.as_deref());
+    r#match.add_opt(
+        "MACAddress",
+        matching.macaddress.as_ref().map(|mac| mac.to_string()),
+    );
+    r#match.add_opt("Driver", matching.driver.as_ref().map(|d| d.join(" ")));
+
+    let mut unit = UnitFile::default();
+    unit.push(r#match);
+    unit.push(link);
+    Some(unit)
+}
+
+/// The `.netdev` unit creating a virtual device, `None` for physical devices.
+fn netdev_unit(name: &str, device: AnyDeviceConfig<'_>) -> Option<UnitFile> {
+    let common = device.common_all();
+    let mut netdev = UnitSection::new("NetDev");
+    netdev.add("Name", name);
+    let mut sections = Vec::new();
+
+    match device {
+        AnyDeviceConfig::Ethernet(_)
+        | AnyDeviceConfig::Wifi(_)
+        | AnyDeviceConfig::Modem(_)
+        | AnyDeviceConfig::NmDevice(_) => return None,
+        AnyDeviceConfig::Bond(bond) => {
+            netdev.add("Kind", "bond");
+            let mut section = UnitSection::new("Bond");
+            if let Some(parameters) = bond.parameters.as_deref() {
+                section.add_opt("Mode", parameters.mode.as_ref().map(|m| m.as_str()));
+                section.add_opt(
+                    "LACPTransmitRate",
+                    parameters.lacp_rate.as_ref().map(|r| r.as_str()),
+                );
+                section.add_opt(
+                    "MIIMonitorSec",
+                    parameters
+                        .mii_monitor_interval
+                        .as_deref()
+                        .map(|t| time_span(t, "ms")),
+                );
+                section.add_opt("MinLinks", parameters.min_links);
+                section.add_opt(
+                    "TransmitHashPolicy",
+                    parameters.transmit_hash_policy.as_ref().map(|p| p.as_str()),
+                );
+                section.add_opt(
+                    "AdSelect",
+                    parameters.ad_select.as_ref().map(|s| s.as_str()),
+                );
+                section.add_opt("AllSlavesActive", parameters.all_slaves_active.map(bool));
+                section.add_opt(
+                    "ARPIntervalSec",
+                    parameters
+                        .arp_interval
+                        .as_deref()
+                        .map(|t| time_span(t, "ms")),
+                );
+                section.add_opt(
+                    "ARPIPTargets",
+                    parameters.arp_ip_targets.as_ref().map(|t| t.join(" ")),
+                );
+                section.add_opt(
+                    "ARPValidate",
+                    parameters.arp_validate.as_ref().map(|v| v.as_str()),
+                );
+                section.add_opt(
+                    "ARPAllTargets",
+                    parameters.arp_all_targets.as_ref().map(|t| t.as_str()),
+                );
+                section.add_opt(
+                    "UpDelaySec",
+                    parameters.up_delay.as_deref().map(|t| time_span(t, "ms")),
+                );
+                section.add_opt(
+                    "DownDelaySec",
+                    parameters.down_delay.as_deref().map(|t| time_span(t, "ms")),
+                );
+                section.add_opt(
+                    "FailOverMACPolicy",
+                    parameters.fail_over_mac_policy.as_ref().map(|p| p.as_str()),
+                );
+                section.add_opt("GratuitousARP", parameters.gratuitous_arp);
+                section.add_opt("PacketsPerSlave", parameters.packets_per_slave);
+                section.add_opt(
+                    "PrimaryReselectPolicy",
+                    parameters
+                        .primary_reselect_policy
+                        .as_ref()
+                        .map(|p| p.as_str()),
+                );
+                section.add_opt("ResendIGMP", parameters.resend_igmp);
+                section.add_opt(
+                    "LearnPacketIntervalSec",
+                    parameters
+                        .learn_packet_interval
+                        .as_deref()
+                        .map(|t| time_span(t, "s")),
+                );
+            }
+            sections.push(section);
+        }
+        AnyDeviceConfig::Bridge(bridge) => {
+            netdev.add("Kind", "bridge");
+            let mut section = UnitSection::new("Bridge");
+            if let Some(parameters) = bridge.parameters.as_deref() {
+                section.add_opt(
+                    "AgeingTimeSec",
+                    parameters.ageing_time.as_deref().map(|t| time_span(t, "s")),
+                );
+                section.add_opt("Priority", parameters.priority);
+                section.add_opt(
+                    "ForwardDelaySec",
+                    parameters
+                        .forward_delay
+                        .as_deref()
+                        .map(|t| time_span(t, "s")),
+                );
+                section.add_opt(
+                    "HelloTimeSec",
+                    parameters.hello_time.as_deref().map(|t| time_span(t, "s")),
+                );
+                section.add_opt(
+                    "MaxAgeSec",
+                    parameters.max_age.as_deref().map(|t| time_span(t, "s")),
+                );
+                section.add_opt("STP", parameters.stp.map(bool));
+            }
+            sections.push(section);
+        }
+        AnyDeviceConfig::Vlan(vlan) => {
+            netdev.add("Kind", "vlan");
+            let mut section = UnitSection::new("VLAN");
+            section.add_opt("Id", vlan.id);
+            sections.push(section);
+        }
+        AnyDeviceConfig::Vrf(vrf) => {
+            netdev.add("Kind", "vrf");
+            let mut section = UnitSection::new("VRF");
+            section.add("Table", vrf.table);
+            sections.push(section);
+        }
+        AnyDeviceConfig::DummyDevice(_) => netdev.add("Kind", "dummy"),
+        AnyDeviceConfig::Tunnel(tunnel) => {
+            netdev.add("Kind", tunnel_kind(tunnel.mode.as_ref()));
+            sections.extend(tunnel_sections(tunnel));
+        }
+    }
+
+    netdev.add_opt(
+        "MACAddress",
+        common
+            .and_then(|c| c.macaddress.as_ref())
+            .map(|mac| mac.to_string()),
+    );
+    netdev.add_opt("MTUBytes", common.and_then(|c| c.mtu));
+
+    let mut unit = UnitFile::default();
+    unit.push(netdev);
+    for section in sections {
+        unit.push(section);
+    }
+    Some(unit)
+}
+
+/// The networkd kind of a tunnel.
+fn tunnel_kind(mode: Option<&TunnelMode>) -> &'static str {
+    match mode {
+        Some(TunnelMode::Ipip6 | TunnelMode::Ip6ip6) => "ip6tnl",
+        Some(TunnelMode::Isatap) => "sit",
+        Some(mode) => mode.as_str(),
+        None => "sit",
+    }
+}
+
+fn tunnel_sections(tunnel: &TunnelConfig) -> Vec<UnitSection> {
+    let (input, output, private) = match &tunnel.key {
+        Some(TunnelKey::Simple(key)) => {
+            (Some(key.as_str()), Some(key.as_str()), Some(key.as_str()))
+        }
+        Some(TunnelKey::Complex {
+            input,
+            output,
+            private,
+        }) => (
+            input.as_deref(),
+            output.as_deref(),
+            private.as_ref().map(|key| key.as_str()),
+        ),
+        None => (None, None, None),
+    };
+
+    match tunnel.mode {
+        Some(TunnelMode::Wireguard) => {
+            let mut wireguard = UnitSection::new("WireGuard");
+            match private {
+                Some(path) if path.starts_with('/') => wireguard.add("PrivateKeyFile", path),
+                private => wireguard.add_opt("PrivateKey", private),
+            }
+            wireguard.add_opt("ListenPort", tunnel.port.as_deref());
+            wireguard.add_opt("FirewallMark", tunnel.mark.as_deref());
+
+            let peers = tunnel.peers.iter().map(|peer| {
+                let keys = peer.keys.as_ref();
+                let mut section = UnitSection::new("WireGuardPeer");
+                section.add_opt("PublicKey", keys.and_then(|k| k.public.as_deref()));
+                section.add_opt(
+                    "AllowedIPs",
+                    peer.allowed_ips.as_ref().map(|ips| ips.join(",")),
+                );
+                section.add_opt("Endpoint", peer.endpoint.as_deref());
+                section.add_opt("PersistentKeepalive", peer.keepalive);
+                match keys.and_then(|k| k.shared.as_ref()).map(|key| key.as_str()) {
+                    Some(path) if path.starts_with('/') => section.add("PresharedKeyFile", path),
+                    shared => section.add_opt("PresharedKey", shared),
+                }
+                section
+            });
+            std::iter::once(wireguard).chain(peers).collect()
+        }
+        Some(TunnelMode::Vxlan) => {
+            let mut vxlan = UnitSection::new("VXLAN");
+            vxlan.add_opt("VNI", tunnel.id);
+            vxlan.add_opt("Local", tunnel.local.as_deref());
+            vxlan.add_opt("Remote", tunnel.remote.as_deref());
+            vxlan.add_opt("TTL", tunnel.ttl);
+            vxlan.add_opt("DestinationPort", tunnel.port.as_deref());
+            vxlan.add("Independent", "true");
+            vec![vxlan]
+        }
+        ref mode => {
+            let mut section = UnitSection::new("Tunnel");
+            section.add("Independent", "true");
+            match mode {
+                Some(TunnelMode::Ipip6) => section.add("Mode", "ipip6"),
+                Some(TunnelMode::Ip6ip6) => section.add("Mode", "ip6ip6"),
+                Some(TunnelMode::Isatap) => section.add("ISATAP", "true"),
+                _ => {}
+            }
+            section.add_opt("Local", tunnel.local.as_deref());
+            section.add_opt("Remote", tunnel.remote.as_deref());
+            section.add_opt("TTL", tunnel.ttl);
+            section.add_opt("InputKey", input);
+            section.add_opt("OutputKey", output);
+            vec![section]
+        }
+    }
+}
+
+/// The `.network` unit configuring the addresses, routes and members of a device.
+fn network_unit(config: &NetplanConfig, name: &str, device: AnyDeviceConfig<'_>) -> UnitFile {
+    let network_config = &config.network;
+    let common = device.common_all();
+    let physical = device.common_physical();
+    let mut unit = UnitFile::default();
+
+    let mut r#match = UnitSection::new("Match");
+    match physical.and_then(|p| p.r#match.as_ref()) {
+        Some(matching) if physical.and_then(|p| p.set_name.as_ref()).is_none() => {
+            r#match.add_opt("Name", matching.name.as_deref());
+            r#match.add_opt(
+                "MACAddress",
+                matching.macaddress.as_ref().map(|mac| mac.to_string()),
+            );
+            r#match.add_opt("Driver", matching.driver.as_ref().map(|d| d.join(" ")));
+        }
+        _ => r#match.add("Name", interface_name(name, device)),
+    }
+    unit.push(r#match);
+
+    let mut link = UnitSection::new("Link");
+    if device.kind().is_physical() {
+        link.add_opt(
+            "MACAddress",
+            common
+                .and_then(|c| c.macaddress.as_ref())
+                .map(|mac| mac.to_string()),
+        );
+    }
+    link.add_opt("MTUBytes", common.and_then(|c| c.mtu));
+    if common.and_then(|c| c.optional) == Some(true) {
+        link.add("RequiredForOnline", "no");
+    }
+    link.add_opt(
+        "ActivationPolicy",
+        common
+            .and_then(|c| c.activation_mode.as_ref())
+            .map(|mode| match mode {
+                crate::ActivationMode::Manual => "manual",
+                crate::ActivationMode::Off => "always-down",
+            }),
+    );
+    unit.push(link);
+
+    // Members of a bond, bridge or VRF are configured through their controller
+    let controllers = network_config
+        .devices()
+        .filter_map(|(_, id, controller)| {
+            let (key, members) = match controller {
+                AnyDeviceConfig::Bond(bond) => ("Bond", bond.interfaces.as_deref()?),
+                AnyDeviceConfig::Bridge(bridge) => ("Bridge", bridge.interfaces.as_deref()?),
+                AnyDeviceConfig::Vrf(vrf) => ("VRF", vrf.interfaces.as_slice()),
+                _ => return None,
+            };
+            members
+                .iter()
+                .any(|member| member == name)
+                .then_some((key, id, controller))
+        })
+        .collect::<Vec<_>>();
+    let enslaved = controllers.iter().any(|(key, _, _)| *key != "VRF");
+
+    let mut network = UnitSection::new("Network");
+    if enslaved {
+        network.add("LinkLocalAddressing", "no");
+    } else {
+        let link_local = common.and_then(|c| c.link_local.as_deref());
+        network.add(
+            "LinkLocalAddressing",
+            match link_local {
+                None => "ipv6",
+                Some(families) => match (
+                    families.iter().any(|f| f == "ipv4"),
+                    families.iter().any(|f| f == "ipv6"),
+                ) {
+                    (true, true) => "yes",
+                    (true, false) => "ipv4",
+                    (false, true) => "ipv6",
+                    (false, false) => "no",
+                },
+            },
+        );
+    }
+
+    if let Some(common) = common {
+        let dhcp = match (common.dhcp4 == Some(true), common.dhcp6 == Some(true)) {
+            (true, true) => Some("yes"),
+            (true, false) => Some("ipv4"),
+            (false, true) => Some("ipv6"),
+            (false, false) => None,
+        };
+        network.add_opt("DHCP", dhcp);
+        if common.ignore_carrier == Some(true) {
+            network.add("ConfigureWithoutCarrier", "yes");
+            network.add("IgnoreCarrierLoss", "yes");
+        }
+        if common.critical == Some(true) {
+            network.add("KeepConfiguration", "true");
+        }
+        if common.ipv6_privacy == Some(true) {
+            network.add("IPv6PrivacyExtensions", "yes");
+        }
+        network.add_opt("IPv6AcceptRA", common.accept_ra.map(bool));
+        network.add_opt("IPv6MTUBytes", common.ipv6_mtu);
+        network.add_opt(
+            "IPv6LinkLocalAddressGenerationMode",
+            common.ipv6_address_generation.as_ref().map(|g| g.as_str()),
+        );
+        network.add_opt(
+            "IPv6Token",
+            common
+                .ipv6_address_token
+                .as_ref()
+                .map(|token| format!("static:{token}")),
+        );
+        for address in common.addresses.iter().flatten() {
+            if let AddressMapping::Simple(address) = address {
+                network.add("Address", address);
+            }
+        }
+        network.add_opt("Gateway", common.gateway4.as_deref());
+        network.add_opt("Gateway", common.gateway6.as_deref());
+        if let Some(nameservers) = &common.nameservers {
+            for address in nameservers.addresses.iter().flatten() {
+                network.add("DNS", address);
+            }
+            network.add_opt(
+                "Domains",
+                nameservers.search.as_ref().map(|search| search.join(" ")),
+            );
+        }
+    }
+    if physical.and_then(|p| p.emit_lldp) == Some(true) {
+        network.add("EmitLLDP", "true");
+    }
+
+    for (key, id, controller) in &controllers {
+        network.add(*key, id);
+        if let AnyDeviceConfig::Bond(bond) = controller {
+            let primary = bond.parameters.as_ref().and_then(|p| p.primary.as_deref());
+            if primary == Some(name) {
+                network.add("PrimarySlave", "true");
+            }
+        }
+    }
+
+    // VLANs are attached to the network of their underlying device
+    let mut vlans = network_config
+        .vlans
+        .iter()
+        .flatten()
+        .filter(|(_, vlan)| vlan.link.as_deref() == Some(name))
+        .map(|(id, _)| id.as_str())
+        .collect::<Vec<_>>();
+    vlans.sort_unstable();
+    for vlan in vlans {
+        network.add("VLAN", vlan);
+    }
+    unit.push(network);
+
+    let Some(common) = common else {
+        return unit;
+    };
+
+    for (section, enabled, overrides) in [
+        ("DHCPv4", common.dhcp4, &common.dhcp4_overrides),
+        ("DHCPv6", common.dhcp6, &common.dhcp6_overrides),
+    ] {
+        if enabled == Some(true) {
+            unit.push(dhcp_section(
+                section,
+                overrides.as_ref(),
+                common.dhcp_identifier.as_deref(),
+            ));
+        }
+    }
+
+    for address in common.addresses.iter().flatten() {
+        if let AddressMapping::Complex(addresses) = address {
+            let mut addresses = addresses.iter().collect::<Vec<_>>();
+            addresses.sort_by_key(|(address, _)| *address);
+            for (address, properties) in addresses {
+                let mut section = UnitSection::new("Address");
+                section.add("Address", address);
+                section.add_opt(
+                    "PreferredLifetime",
+                    properties.lifetime.as_ref().map(|l| l.as_str()),
+                );
+                section.add_opt("Label", properties.label.as_deref());
+                unit.push(section);
+            }
+        }
+    }
+
+    for route in common.routes.iter().flatten() {
+        unit.push(route_section(route));
+    }
+    for policy in common.routing_policy.iter().flatten() {
+        unit.push(routing_policy_section(policy));
+    }
+
+    // The port settings of bridges apply to each of their members
+    for (_, _, controller) in &controllers {
+        if let AnyDeviceConfig::Bridge(bridge) = controller {
+            let parameters = bridge.parameters.as_deref();
+            let mut section = UnitSection::new("Bridge");
+            section.add_opt("Priority", parameters.and_then(|p| p.port_priority));
+            section.add_opt("Cost", parameters.and_then(|p| p.path_cost));
+            unit.push(section);
+        }
+    }
+
+    unit
+}
+
+fn dhcp_section(
+    name: &str,
+    overrides: Option<&DhcpOverrides>,
+    identifier: Option<&str>,
+) -> UnitSection {
+    let mut section = UnitSection::new(name);
+    if name == "DHCPv4" {
+        section.add_opt("ClientIdentifier", identifier.filter(|id| *id == "mac"));
+        section.add(
+            "RouteMetric",
+            overrides.and_then(|o| o.route_metric).unwrap_or(100),
+        );
+        section.add(
+            "UseMTU",
+            bool(overrides.and_then(|o| o.use_mtu).unwrap_or(true)),
+        );
+    } else {
+        section.add_opt("RouteMetric", overrides.and_then(|o| o.route_metric));
+    }
+
+    if let Some(overrides) = overrides {
+        section.add_opt("UseDNS", overrides.use_dns.map(bool));
+        section.add_opt("UseNTP", overrides.use_ntp.map(bool));
+        section.add_opt("SendHostname", overrides.send_hostname.map(bool));
+        section.add_opt("UseHostname", overrides.use_hostname.map(bool));
+        section.add_opt("Hostname", overrides.hostname.as_deref());
+        section.add_opt("UseRoutes", overrides.use_routes.map(bool));
+        section.add_opt("UseDomains", overrides.use_domains.as_deref());
+    }
+
+    section
+}
+
+fn route_section(route: &RoutingConfig) -> UnitSection {
+    let mut section = UnitSection::new("Route");
+    let destination = match route.to.as_deref() {
+        Some("default") if route.via.as_deref().is_some_and(|via| via.contains(':')) => {
+            Some("::/0")
+        }
+        Some("default") => Some("0.0.0.0/0"),
+        to => to,
+    };
+    section.add_opt("Destination", destination);
+    section.add_opt("Gateway", route.via.as_deref());
+    if route.on_link == Some(true) {
+        section.add("GatewayOnLink", "true");
+    }
+    section.add_opt("PreferredSource", route.from.as_deref());
+    section.add_opt("Metric", route.metric);
+    section.add_opt(
+        "Type",
+        route
+            .r#type
+            .as_ref()
+            .filter(|t| **t != RouteType::Unicast)
+            .map(|t| t.as_str()),
+    );
+    section.add_opt(
+        "Scope",
+        route
+            .scope
+            .as_ref()
+            .filter(|s| **s != RouteScope::Global)
+            .map(|s| s.as_str()),
+    );
+    section.add_opt("Table", route.table);
+    section.add_opt("MTUBytes", route.mtu);
+    section.add_opt("InitialCongestionWindow", route.congestion_window);
+    section.add_opt(
+        "InitialAdvertisedReceiveWindow",
+        route.advertised_receive_window,
+    );
+    section
+}
+
+fn routing_policy_section(policy: &RoutingPolicy) -> UnitSection {
+    let mut section = UnitSection::new("RoutingPolicyRule");
+    section.add_opt("From", policy.from.as_deref());
+    section.add_opt("To", policy.to.as_deref());
+    section.add("Table", policy.table);
+    section.add_opt("Priority", policy.priority);
+    section.add_opt("FirewallMark", policy.mark);
+    section.add_opt("TypeOfService", policy.type_of_service.as_deref());
+    section
+}
+
+#[cfg(all(test, feature = "yaml"))]
+mod test {
+    use crate::NetplanConfig;
+
+    #[test]
+    fn render_networkd_units() {
+        let config = NetplanConfig::from_yaml(
+            r#"
+network:
+  version: 2
+  ethernets:
+    nic0:
+      match: {macaddress: "52:54:00:aa:bb:01"}
+      set-name: eth0
+    eth1: {}
+    wlan0:
+      renderer: NetworkManager
+  bonds:
+    bond0:
+      interfaces: [nic0, eth1]
+      parameters: {mode: active-backup, mii-monitor-interval: 100, primary: nic0}
+      addresses: [10.0.0.2/24]
+      nameservers: {addresses: [10.0.0.1], search: [lan]}
+      routes:
+        - to: default
+          via: 10.0.0.1
+  vlans:
+    vlan10:
+      id: 10
+      link: bond0
+      dhcp4: true
+"#,
+        )
+        .unwrap();
+
+        let files = config.to_networkd();
+        let rendered = files
+            .iter()
+            .map(|file| format!("# {}\n{}", file.file_name, file.unit))
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert_eq!(
+            rendered,
+            r#"# 10-netplan-bond0.netdev
+[NetDev]
+Name=bond0
+Kind=bond
+
+[Bond]
+Mode=active-backup
+MIIMonitorSec=100ms
+
+# 10-netplan-bond0.network
+[Match]
+Name=bond0
+
+[Network]
+LinkLocalAddressing=ipv6
+Address=10.0.0.2/24
+DNS=10.0.0.1
+Domains=lan
+VLAN=vlan10
+
+[Route]
+Destination=0.0.0.0/0
+Gateway=10.0.0.1
+
+# 10-netplan-eth1.network
+[Match]
+Name=eth1
+
+[Network]
+LinkLocalAddressing=no
+Bond=bond0
+
+# 10-netplan-nic0.link
+[Match]
+MACAddress=52:54:00:aa:bb:01
+
+[Link]
+Name=eth0
+
+# 10-netplan-nic0.network
+[Match]
+Name=eth0
+
+[Network]
+LinkLocalAddressing=no
+Bond=bond0
+PrimarySlave=true
+
+# 10-netplan-vlan10.netdev
+[NetDev]
+Name=vlan10
+Kind=vlan
+
+[VLAN]
+Id=10
+
+# 10-netplan-vlan10.network
+[Match]
+Name=vlan10
+
+[Network]
+LinkLocalAddressing=ipv6
+DHCP=ipv4
+
+[DHCPv4]
+RouteMetric=100
+UseMTU=true
+"#
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("10-netplan-old.network"), "").unwrap();
+        config.write_networkd(dir.path()).unwrap();
+        let mut names = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect::<Vec<_>>();
+        names.sort_unstable();
+        let expected = files
+            .iter()
+            .map(|f| f.file_name.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(names, expected);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+
+            for name in names {
+                let metadata = std::fs::metadata(dir.path().join(name)).unwrap();
+                assert_eq!(metadata.permissions().mode() & 0o007, 0);
+            }
+        }
+    }
+
+    #[test]
+    fn segmentation_offloads() {
+        let config = NetplanConfig::from_yaml(
+            r#"
+network:
+  version: 2
+  ethernets:
+    eth0:
+      match: {name: eth0}
+      tcp-segmentation-offload: true
+      tcp6-segmentation-offload: false
+"#,
+        )
+        .unwrap();
+
+        let files = config.to_networkd();
+        let link = files
+            .iter()
+            .find(|file| file.file_name == "10-netplan-eth0.link")
+            .unwrap();
+        let rendered = link.unit.to_string();
+        assert!(rendered.contains("TCPSegmentationOffload=true\n"));
+        assert!(rendered.contains("TCP6SegmentationOffload=false\n"));
+    }
+}
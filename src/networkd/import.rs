@@ -0,0 +1,827 @@
+//! Importing systemd-networkd units into configurations.
+
+use crate::networkd::{NetworkdFile, UnitFile, UnitParseError, FILE_PREFIX};
+use crate::{
+    ActivationMode, AdSelect, AddressMapping, AddressProperties, ArpAllTargets, ArpValidate,
+    BondConfig, BondMode, BondParameters, BridgeConfig, BridgeParameters, CommonProperties,
+    CommonPropertiesAllDevices, DhcpOverrides, DummyDeviceConfig, EthernetConfig,
+    FailOverMacPolicy, Ipv6AddressGeneration, LacpRate, MatchConfig, NameserverConfig,
+    NetplanConfig, NetworkConfig, PhysicalProperties, PreferredLifetime, PrimaryReselectPolicy,
+    RouteScope, RouteType, RoutingConfig, RoutingPolicy, SecretString, TransmitHashPolicy,
+    TunnelConfig, TunnelKey, TunnelMode, VlanConfig, VrfsConfig, WireGuardPeer, WireGuardPeerKey,
+};
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+
+/// The error returned when the units of a directory cannot be imported.
+#[derive(Debug)]
+pub enum NetworkdImportError {
+    /// A unit could not be read.
+    Io(std::io::Error),
+    /// A unit is not a valid unit file.
+    Parse {
+        file_name: String,
+        error: UnitParseError,
+    },
+}
+
+impl Display for NetworkdImportError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "Failed to read unit: {e}"),
+            Self::Parse { file_name, error } => write!(f, "Invalid unit {file_name}: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for NetworkdImportError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Parse { error, .. } => Some(error),
+        }
+    }
+}
+
+impl From<std::io::Error> for NetworkdImportError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl NetplanConfig {
+    /// Lift networkd units into a configuration, e.g. to migrate a hand-written networkd
+    /// setup to netplan. This is the reverse of [NetplanConfig::to_networkd].
+    ///
+    /// Bonds, bridges, VLANs, VRFs, dummy devices and tunnels are created from the `.netdev`
+    /// units, other kinds of netdevs are left out. Each `.network` unit configures the device
+    /// it matches: the netdev or ethernet with the name it matches, or an ethernet matching
+    /// the same MAC address, driver or name pattern, whose ID is taken from the file name.
+    /// Like networkd, only the first `.network` unit in lexical order applies to a device.
+    /// `.link` units renaming interfaces become ethernets with a `set-name`.
+    ///
+    /// Addresses, routes, routing policy, DHCP, DNS, MTU and membership of bonds, bridges,
+    /// VRFs and VLANs are carried over, as are bond, bridge and tunnel parameters. Settings
+    /// netplan has no equivalent for are left out. Gateways become default routes.
+    ///
+    /// ```
+    /// use netplan_types::{NetplanConfig, NetworkdFile};
+    ///
+    /// let files = [
+    ///     NetworkdFile::parse("10-bond0.netdev", "[NetDev]\nName=bond0\nKind=bond\n").unwrap(),
+    ///     NetworkdFile::parse("20-eth0.network", "[Match]\nName=eth0\n[Network]\nBond=bond0\n").unwrap(),
+    ///     NetworkdFile::parse("30-bond0.network", "[Match]\nName=bond0\n[Network]\nDHCP=ipv4\n").unwrap(),
+    /// ];
+    ///
+    /// let config = NetplanConfig::from_networkd(&files);
+    /// let expected = NetplanConfig::build(|net| {
+    ///     net.ethernet("eth0", |e| e);
+    ///     net.bond("bond0", |b| b.interfaces(["eth0"]).dhcp4(true));
+    /// });
+    /// assert_eq!(config, expected);
+    /// ```
+    pub fn from_networkd<'a>(files: impl IntoIterator<Item = &'a NetworkdFile>) -> Self {
+        let mut files = files.into_iter().collect::<Vec<_>>();
+        files.sort_by(|a, b| a.file_name.cmp(&b.file_name));
+        let units = |extension: &'static str| {
+            files
+                .iter()
+                .filter(move |file| file.extension() == Some(extension))
+        };
+
+        let mut config = NetplanConfig::default();
+        let network = &mut config.network;
+        for file in units("netdev") {
+            import_netdev(network, &file.unit);
+        }
+
+        let renames = units("link")
+            .filter_map(|file| {
+                let name = file.unit.get("Link", "Name")?;
+                Some((name, (device_id(&file.file_name), &file.unit)))
+            })
+            .collect::<HashMap<_, _>>();
+        let mut configured = HashSet::new();
+        for file in units("network") {
+            let Some(id) = network_target(network, file, &renames) else {
+                continue;
+            };
+            if configured.insert(id.clone()) {
+                import_network(network, &id, &file.unit);
+            }
+        }
+
+        // Renamed interfaces are defined, even if no .network unit configures them
+        for (name, (id, unit)) in renames {
+            if network.device(id).is_none() {
+                network.add_ethernet(id, renamed_ethernet(name, unit));
+            }
+        }
+
+        config
+    }
+
+    /// Import the `.network`, `.netdev` and `.link` units of a directory like
+    /// `/etc/systemd/network`, see [NetplanConfig::from_networkd].
+    pub fn from_networkd_dir(dir: impl AsRef<Path>) -> Result<Self, NetworkdImportError> {
+        let mut files = Vec::new();
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else {
+                continue;
+            };
+            let file_name = file_name.to_string();
+            let extension = path.extension().and_then(|extension| extension.to_str());
+            if !matches!(extension, Some("network" | "netdev" | "link")) {
+                continue;
+            }
+
+            let contents = std::fs::read_to_string(&path)?;
+            let file = NetworkdFile::parse(&file_name, &contents)
+                .map_err(|error| NetworkdImportError::Parse { file_name, error })?;
+            files.push(file);
+        }
+
+        Ok(Self::from_networkd(&files))
+    }
+}
+
+/// The device ID for a unit file, its name without the extension and priority,
+/// e.g. `eth0` for `10-netplan-eth0.network` or `20-eth0.network`.
+fn device_id(file_name: &str) -> &str {
+    let stem = file_name
+        .rsplit_once('.')
+        .map_or(file_name, |(stem, _)| stem);
+    if let Some(id) = stem.strip_prefix(FILE_PREFIX) {
+        return id;
+    }
+
+    match stem.split_once('-') {
+        Some((priority, id)) if priority.chars().all(|c| c.is_ascii_digit()) => id,
+        _ => stem,
+    }
+}
+
+/// A systemd boolean.
+fn boolean(value: &str) -> Option<bool> {
+    match value {
+        "1" | "yes" | "y" | "true" | "t" | "on" => Some(true),
+        "0" | "no" | "n" | "false" | "f" | "off" => Some(false),
+        _ => None,
+    }
+}
+
+/// A time span in netplan notation, without `unit` if that is the unit netplan assumes,
+/// e.g. `100` for `100ms` in milliseconds.
+fn time_span(value: &str, unit: &str) -> String {
+    match value.strip_suffix(unit) {
+        Some(number) if number.chars().all(|c| c.is_ascii_digit()) => number.to_string(),
+        _ => value.to_string(),
+    }
+}
+
+/// The variant whose string representation is `value`.
+fn variant<T: Clone>(variants: &[T], as_str: fn(&T) -> &'static str, value: &str) -> Option<T> {
+    variants.iter().find(|v| as_str(v) == value).cloned()
+}
+
+/// The properties every netdev may have.
+fn netdev_common(unit: &UnitFile) -> Option<Box<CommonPropertiesAllDevices>> {
+    let common = CommonPropertiesAllDevices {
+        macaddress: unit
+            .get("NetDev", "MACAddress")
+            .and_then(|mac| mac.parse().ok()),
+        mtu: unit
+            .get("NetDev", "MTUBytes")
+            .and_then(|mtu| mtu.parse().ok()),
+        ..Default::default()
+    };
+    (common != CommonPropertiesAllDevices::default()).then(|| Box::new(common))
+}
+
+fn import_netdev(network: &mut NetworkConfig, unit: &UnitFile) {
+    let Some(name) = unit.get("NetDev", "Name") else {
+        return;
+    };
+    let common_all = netdev_common(unit);
+    let get = |section: &str, key: &str| unit.get(section, key);
+
+    match unit.get("NetDev", "Kind").unwrap_or_default() {
+        "bond" => {
+            let ms = |key: &str| get("Bond", key).map(|t| time_span(t, "ms"));
+            let parameters = BondParameters {
+                mode: get("Bond", "Mode")
+                    .and_then(|v| variant(BondMode::VARIANTS, BondMode::as_str, v)),
+                lacp_rate: get("Bond", "LACPTransmitRate")
+                    .and_then(|v| variant(LacpRate::VARIANTS, LacpRate::as_str, v)),
+                mii_monitor_interval: ms("MIIMonitorSec"),
+                min_links: get("Bond", "MinLinks").and_then(|v| v.parse().ok()),
+                transmit_hash_policy: get("Bond", "TransmitHashPolicy").and_then(|v| {
+                    variant(TransmitHashPolicy::VARIANTS, TransmitHashPolicy::as_str, v)
+                }),
+                ad_select: get("Bond", "AdSelect")
+                    .and_then(|v| variant(AdSelect::VARIANTS, AdSelect::as_str, v)),
+                all_slaves_active: get("Bond", "AllSlavesActive").and_then(boolean),
+                arp_interval: ms("ARPIntervalSec"),
+                arp_ip_targets: get("Bond", "ARPIPTargets")
+                    .map(|targets| targets.split_whitespace().map(str::to_string).collect()),
+                arp_validate: get("Bond", "ARPValidate")
+                    .and_then(|v| variant(ArpValidate::VARIANTS, ArpValidate::as_str, v)),
+                arp_all_targets: get("Bond", "ARPAllTargets")
+                    .and_then(|v| variant(ArpAllTargets::VARIANTS, ArpAllTargets::as_str, v)),
+                up_delay: ms("UpDelaySec"),
+                down_delay: ms("DownDelaySec"),
+                fail_over_mac_policy: get("Bond", "FailOverMACPolicy").and_then(|v| {
+                    variant(FailOverMacPolicy::VARIANTS, FailOverMacPolicy::as_str, v)
+                }),
+                gratuitous_arp: get("Bond", "GratuitousARP").and_then(|v| v.parse().ok()),
+                packets_per_slave: get("Bond", "PacketsPerSlave").and_then(|v| v.parse().ok()),
+                primary_reselect_policy: get("Bond", "PrimaryReselectPolicy").and_then(|v| {
+                    variant(
+                        PrimaryReselectPolicy::VARIANTS,
+                        PrimaryReselectPolicy::as_str,
+                        v,
+                    )
+                }),
+                resend_igmp: get("Bond", "ResendIGMP").and_then(|v| v.parse().ok()),
+                learn_packet_interval: get("Bond", "LearnPacketIntervalSec")
+                    .map(|t| time_span(t, "s")),
+                ..Default::default()
+            };
+            network.add_bond(
+                name,
+                BondConfig {
+                    parameters: (parameters != BondParameters::default())
+                        .then(|| Box::new(parameters)),
+                    common_all,
+                    ..Default::default()
+                },
+            );
+        }
+        "bridge" => {
+            let s = |key: &str| get("Bridge", key).map(|t| time_span(t, "s"));
+            let parameters = BridgeParameters {
+                ageing_time: s("AgeingTimeSec"),
+                priority: get("Bridge", "Priority").and_then(|v| v.parse().ok()),
+                forward_delay: s("ForwardDelaySec"),
+                hello_time: s("HelloTimeSec"),
+                max_age: s("MaxAgeSec"),
+                stp: get("Bridge", "STP").and_then(boolean),
+                ..Default::default()
+            };
+            network.add_bridge(
+                name,
+                BridgeConfig {
+                    parameters: (parameters != BridgeParameters::default())
+                        .then(|| Box::new(parameters)),
+                    common_all,
+                    ..Default::default()
+                },
+            );
+        }
+        "vlan" => {
+            let id = get("VLAN", "Id").and_then(|id| id.parse().ok());
+            network.add_vlan(
+                name,
+                VlanConfig {
+                    id,
+                    common_all,
+                    ..Default::default()
+                },
+            );
+        }
+        "vrf" => {
+            let table = get("VRF", "Table").and_then(|table| table.parse().ok());
+            network.add_vrf(
+                name,
+                VrfsConfig {
+                    table: table.unwrap_or_default(),
+                    common_all,
+                    ..Default::default()
+                },
+            );
+        }
+        "dummy" => {
+            network.add_dummy_device(name, DummyDeviceConfig { common_all });
+        }
+        kind => {
+            if let Some(mut tunnel) = import_tunnel(kind, unit) {
+                tunnel.common_all = common_all;
+                network.add_tunnel(name, tunnel);
+            }
+        }
+    }
+}
+
+fn import_tunnel(kind: &str, unit: &UnitFile) -> Option<TunnelConfig> {
+    let get = |section: &str, key: &str| unit.get(section, key);
+    let mode = match (kind, get("Tunnel", "Mode")) {
+        ("ip6tnl", Some("ipip6")) => TunnelMode::Ipip6,
+        ("ip6tnl", _) => TunnelMode::Ip6ip6,
+        ("sit", _) if get("Tunnel", "ISATAP").and_then(boolean) == Some(true) => TunnelMode::Isatap,
+        (kind, _) => variant(TunnelMode::VARIANTS, TunnelMode::as_str, kind)?,
+    };
+
+    let tunnel = match mode {
+        TunnelMode::Wireguard => {
+            let private = get("WireGuard", "PrivateKeyFile").or(get("WireGuard", "PrivateKey"));
+            let peers = unit
+                .sections("WireGuardPeer")
+                .map(|peer| {
+                    let shared = peer
+                        .get("PresharedKeyFile")
+                        .or(peer.get("PresharedKey"))
+                        .map(SecretString::from);
+                    let public = peer.get("PublicKey").map(str::to_string);
+                    WireGuardPeer {
+                        endpoint: peer.get("Endpoint").map(str::to_string),
+                        allowed_ips: peer.get("AllowedIPs").map(|ips| {
+                            ips.split([',', ' '])
+                                .filter(|ip| !ip.is_empty())
+                                .map(str::to_string)
+                                .collect()
+                        }),
+                        keepalive: peer.get("PersistentKeepalive").and_then(|k| k.parse().ok()),
+                        keys: (public.is_some() || shared.is_some())
+                            .then_some(WireGuardPeerKey { public, shared }),
+                    }
+                })
+                .collect();
+            TunnelConfig {
+                key: private.map(|private| TunnelKey::Complex {
+                    input: None,
+                    output: None,
+                    private: Some(SecretString::from(private)),
+                }),
+                port: get("WireGuard", "ListenPort").map(str::to_string),
+                mark: get("WireGuard", "FirewallMark").map(str::to_string),
+                peers,
+                ..Default::default()
+            }
+        }
+        TunnelMode::Vxlan => TunnelConfig {
+            id: get("VXLAN", "VNI").and_then(|id| id.parse().ok()),
+            local: get("VXLAN", "Local").map(str::to_string),
+            remote: get("VXLAN", "Remote").map(str::to_string),
+            ttl: get("VXLAN", "TTL").and_then(|ttl| ttl.parse().ok()),
+            port: get("VXLAN", "DestinationPort").map(str::to_string),
+            ..Default::default()
+        },
+        _ => {
+            let key = get("Tunnel", "Key");
+            let input = get("Tunnel", "InputKey").or(key);
+            let output = get("Tunnel", "OutputKey").or(key);
+            let key = match (input, output) {
+                (Some(input), Some(output)) if input == output => {
+                    Some(TunnelKey::Simple(SecretString::from(input)))
+                }
+                (None, None) => None,
+                (input, output) => Some(TunnelKey::Complex {
+                    input: input.map(str::to_string),
+                    output: output.map(str::to_string),
+                    private: None,
+                }),
+            };
+            TunnelConfig {
+                local: get("Tunnel", "Local").map(str::to_string),
+                remote: get("Tunnel", "Remote").map(str::to_string),
+                ttl: get("Tunnel", "TTL").and_then(|ttl| ttl.parse().ok()),
+                key,
+                ..Default::default()
+            }
+        }
+    };
+
+    Some(TunnelConfig {
+        mode: Some(mode),
+        ..tunnel
+    })
+}
+
+/// The match rules of a `[Match]` section, `None` if it matches nothing netplan can express.
+fn match_config(unit: &UnitFile, name_key: &str) -> Option<MatchConfig> {
+    let names = unit
+        .get_all("Match", name_key)
+        .flat_map(str::split_whitespace)
+        .collect::<Vec<_>>();
+    if names.len() > 1 {
+        return None;
+    }
+
+    let matching = MatchConfig {
+        name: names.first().map(|name| name.to_string()),
+        macaddress: unit
+            .get("Match", "MACAddress")
+            .or(unit.get("Match", "PermanentMACAddress"))
+            .and_then(|mac| mac.parse().ok()),
+        driver: unit
+            .get("Match", "Driver")
+            .map(|drivers| drivers.split_whitespace().map(str::to_string).collect()),
+    };
+    (matching != MatchConfig::default()).then_some(matching)
+}
+
+/// An ethernet for a `.link` unit renaming an interface to `name`.
+fn renamed_ethernet(name: &str, unit: &UnitFile) -> EthernetConfig {
+    let get = |key: &str| unit.get("Link", key);
+    let mut ethernet = EthernetConfig::default();
+    let physical = ethernet.common_physical_mut();
+    physical.r#match = match_config(unit, "OriginalName");
+    physical.set_name = Some(name.to_string());
+    physical.wakeonlan = get("WakeOnLan").map(|wol| wol != "off");
+    physical.receive_checksum_offload = get("ReceiveChecksumOffload").and_then(boolean);
+    physical.transmit_checksum_offload = get("TransmitChecksumOffload").and_then(boolean);
+    physical.tcp_segmentation_offload = get("TCPSegmentationOffload").and_then(boolean);
+    physical.tcp6_segmentation_offload = get("TCP6SegmentationOffload").and_then(boolean);
+    physical.generic_segmentation_offload = get("GenericSegmentationOffload").and_then(boolean);
+    physical.generic_receive_offload = get("GenericReceiveOffload").and_then(boolean);
+    physical.large_receive_offload = get("LargeReceiveOffload").and_then(boolean);
+    if let Some(mtu) = get("MTUBytes").and_then(|mtu| mtu.parse().ok()) {
+        ethernet.set_mtu(Some(mtu));
+    }
+    ethernet
+}
+
+/// The ID of the device a `.network` unit configures, creating an ethernet for it if no
+/// device has been defined yet. `None` if the unit matches nothing netplan can express.
+fn network_target(
+    network: &mut NetworkConfig,
+    file: &NetworkdFile,
+    renames: &HashMap<&str, (&str, &UnitFile)>,
+) -> Option<String> {
+    let matching = match_config(&file.unit, "Name")?;
+    if let (Some(name), None, None) = (&matching.name, &matching.macaddress, &matching.driver) {
+        if !crate::glob::is_glob(name) {
+            if network.device(name).is_some() {
+                return Some(name.clone());
+            }
+            if let Some((id, unit)) = renames.get(name.as_str()) {
+                if network.device(id).is_none() {
+                    network.add_ethernet(*id, renamed_ethernet(name, unit));
+                }
+                return Some(id.to_string());
+            }
+
+            network.add_ethernet(name.as_str(), EthernetConfig::default());
+            return Some(name.clone());
+        }
+    }
+
+    let id = device_id(&file.file_name);
+    if network.device(id).is_none() {
+        let mut ethernet = EthernetConfig::default();
+        ethernet.common_physical_mut().r#match = Some(matching);
+        network.add_ethernet(id, ethernet);
+    }
+    Some(id.to_string())
+}
+
+fn import_network(network: &mut NetworkConfig, id: &str, unit: &UnitFile) {
+    let get = |section: &str, key: &str| unit.get(section, key);
+    let all = |section: &'static str, key: &'static str| unit.get_all(section, key);
+    let controllers = ["Bond", "Bridge", "VRF"]
+        .into_iter()
+        .flat_map(|key| all("Network", key).map(move |controller| (key, controller)))
+        .collect::<Vec<_>>();
+    let enslaved = controllers.iter().any(|(key, _)| *key != "VRF");
+
+    let Some(mut device) = network.device_mut(id) else {
+        return;
+    };
+    if let crate::AnyDeviceConfigMut::Ethernet(ethernet) = &mut device {
+        if let Some(lldp) = get("Network", "EmitLLDP").and_then(boolean) {
+            ethernet.common_physical_mut().emit_lldp = Some(lldp);
+        }
+    }
+
+    let mut common = device.common_all().cloned().unwrap_or_default();
+    if let Some(mtu) = get("Link", "MTUBytes").and_then(|mtu| mtu.parse().ok()) {
+        common.mtu = Some(mtu);
+    }
+    if let Some(mac) = get("Link", "MACAddress").and_then(|mac| mac.parse().ok()) {
+        common.macaddress = Some(mac);
+    }
+    if get("Link", "RequiredForOnline").and_then(boolean) == Some(false) {
+        common.optional = Some(true);
+    }
+    common.activation_mode = match get("Link", "ActivationPolicy") {
+        Some("manual") => Some(ActivationMode::Manual),
+        Some("always-down") => Some(ActivationMode::Off),
+        _ => common.activation_mode.take(),
+    };
+
+    match get("Network", "DHCP") {
+        Some("ipv4") => common.dhcp4 = Some(true),
+        Some("ipv6") => common.dhcp6 = Some(true),
+        Some(value) if boolean(value) == Some(true) || value == "both" => {
+            common.dhcp4 = Some(true);
+            common.dhcp6 = Some(true);
+        }
+        _ => {}
+    }
+
+    // Members of bonds and bridges never have link-local addresses
+    if !enslaved {
+        common.link_local = match get("Network", "LinkLocalAddressing") {
+            Some("ipv6") | None => None,
+            Some("ipv4") => Some(vec!["ipv4".to_string()]),
+            Some("yes") | Some("true") => Some(vec!["ipv4".to_string(), "ipv6".to_string()]),
+            Some(_) => Some(vec![]),
+        };
+    }
+
+    common.accept_ra = get("Network", "IPv6AcceptRA").and_then(boolean);
+    if get("Network", "IPv6PrivacyExtensions").and_then(boolean) == Some(true) {
+        common.ipv6_privacy = Some(true);
+    }
+    if get("Network", "ConfigureWithoutCarrier").and_then(boolean) == Some(true) {
+        common.ignore_carrier = Some(true);
+    }
+    if get("Network", "KeepConfiguration").and_then(boolean) == Some(true) {
+        common.critical = Some(true);
+    }
+    common.ipv6_mtu = get("Network", "IPv6MTUBytes").and_then(|mtu| mtu.parse().ok());
+    common.ipv6_address_generation =
+        get("Network", "IPv6LinkLocalAddressGenerationMode").and_then(|mode| {
+            variant(
+                Ipv6AddressGeneration::VARIANTS,
+                Ipv6AddressGeneration::as_str,
+                mode,
+            )
+        });
+    common.ipv6_address_token = get("Network", "IPv6Token")
+        .map(|token| token.strip_prefix("static:").unwrap_or(token).to_string());
+
+    let mut addresses = all("Network", "Address")
+        .map(|address| AddressMapping::Simple(address.to_string()))
+        .collect::<Vec<_>>();
+    for section in unit.sections("Address") {
+        let Some(address) = section.get("Address") else {
+            continue;
+        };
+        let properties = AddressProperties {
+            lifetime: section.get("PreferredLifetime").and_then(|lifetime| {
+                variant(
+                    PreferredLifetime::VARIANTS,
+                    PreferredLifetime::as_str,
+                    lifetime,
+                )
+            }),
+            label: section.get("Label").map(str::to_string),
+        };
+        addresses.push(if properties == AddressProperties::default() {
+            AddressMapping::Simple(address.to_string())
+        } else {
+            AddressMapping::Complex(HashMap::from([(address.to_string(), properties)]))
+        });
+    }
+    common.addresses = (!addresses.is_empty()).then_some(addresses);
+
+    let nameservers = NameserverConfig {
+        addresses: Some(
+            all("Network", "DNS")
+                .flat_map(str::split_whitespace)
+                .map(str::to_string)
+                .collect::<Vec<_>>(),
+        )
+        .filter(|addresses| !addresses.is_empty()),
+        search: Some(
+            all("Network", "Domains")
+                .flat_map(str::split_whitespace)
+                .map(str::to_string)
+                .collect::<Vec<_>>(),
+        )
+        .filter(|search| !search.is_empty()),
+    };
+    common.nameservers =
+        (nameservers.addresses.is_some() || nameservers.search.is_some()).then_some(nameservers);
+
+    let gateways = all("Network", "Gateway").map(|gateway| RoutingConfig {
+        to: Some("default".to_string()),
+        via: Some(gateway.to_string()),
+        ..Default::default()
+    });
+    let routes = gateways
+        .chain(unit.sections("Route").map(|section| {
+            let get = |key: &str| section.get(key);
+            RoutingConfig {
+                to: Some(match get("Destination") {
+                    None | Some("0.0.0.0/0") | Some("::/0") => "default".to_string(),
+                    Some(destination) => destination.to_string(),
+                }),
+                via: get("Gateway").map(str::to_string),
+                on_link: get("GatewayOnLink")
+                    .and_then(boolean)
+                    .filter(|on_link| *on_link),
+                from: get("PreferredSource").map(str::to_string),
+                metric: get("Metric").and_then(|metric| metric.parse().ok()),
+                r#type: get("Type")
+                    .and_then(|kind| variant(RouteType::VARIANTS, RouteType::as_str, kind)),
+                scope: get("Scope")
+                    .and_then(|scope| variant(RouteScope::VARIANTS, RouteScope::as_str, scope)),
+                table: get("Table").and_then(|table| table.parse().ok()),
+                mtu: get("MTUBytes").and_then(|mtu| mtu.parse().ok()),
+                congestion_window: get("InitialCongestionWindow").and_then(|w| w.parse().ok()),
+                advertised_receive_window: get("InitialAdvertisedReceiveWindow")
+                    .and_then(|w| w.parse().ok()),
+            }
+        }))
+        .collect::<Vec<_>>();
+    common.routes = (!routes.is_empty()).then_some(routes);
+
+    let policies = unit
+        .sections("RoutingPolicyRule")
+        .map(|section| RoutingPolicy {
+            from: section.get("From").map(str::to_string),
+            to: section.get("To").map(str::to_string),
+            table: section
+                .get("Table")
+                .and_then(|table| table.parse().ok())
+                .unwrap_or(254),
+            priority: section.get("Priority").and_then(|p| p.parse().ok()),
+            mark: section
+                .get("FirewallMark")
+                .and_then(|mark| mark.parse().ok()),
+            type_of_service: section.get("TypeOfService").map(str::to_string),
+        })
+        .collect::<Vec<_>>();
+    common.routing_policy = (!policies.is_empty()).then_some(policies);
+
+    let identifier = get("DHCPv4", "ClientIdentifier").or(get("DHCP", "ClientIdentifier"));
+    if identifier == Some("mac") {
+        common.dhcp_identifier = Some("mac".to_string());
+    }
+    if common.dhcp4 == Some(true) {
+        common.dhcp4_overrides = dhcp_overrides(unit, &["DHCP", "DHCPv4"], true);
+    }
+    if common.dhcp6 == Some(true) {
+        common.dhcp6_overrides = dhcp_overrides(unit, &["DHCP", "DHCPv6"], false);
+    }
+
+    if common != CommonPropertiesAllDevices::default() {
+        *device.common_all_mut() = common;
+    }
+
+    // Membership is configured on the controllers and underlying devices
+    let primary = get("Network", "PrimarySlave").and_then(boolean) == Some(true);
+    let port_priority = get("Bridge", "Priority").and_then(|p| p.parse().ok());
+    let path_cost = get("Bridge", "Cost").and_then(|cost| cost.parse().ok());
+    for (key, controller) in controllers {
+        match (key, network.device_mut(controller)) {
+            (_, Some(crate::AnyDeviceConfigMut::Bond(bond))) => {
+                bond.interfaces
+                    .get_or_insert_with(Vec::new)
+                    .push(id.to_string());
+                if primary {
+                    bond.parameters.get_or_insert_with(Default::default).primary =
+                        Some(id.to_string());
+                }
+            }
+            (_, Some(crate::AnyDeviceConfigMut::Bridge(bridge))) => {
+                bridge
+                    .interfaces
+                    .get_or_insert_with(Vec::new)
+                    .push(id.to_string());
+                if port_priority.is_some() || path_cost.is_some() {
+                    let parameters = bridge.parameters.get_or_insert_with(Default::default);
+                    parameters.port_priority = port_priority.or(parameters.port_priority);
+                    parameters.path_cost = path_cost.or(parameters.path_cost);
+                }
+            }
+            (_, Some(crate::AnyDeviceConfigMut::Vrf(vrf))) => vrf.interfaces.push(id.to_string()),
+            _ => {}
+        }
+    }
+    for vlan in all("Network", "VLAN") {
+        if let Some(crate::AnyDeviceConfigMut::Vlan(vlan)) = network.device_mut(vlan) {
+            vlan.link = Some(id.to_string());
+        }
+    }
+}
+
+/// The DHCP overrides of a `.network` unit, leaving out the defaults netplan generates.
+fn dhcp_overrides(unit: &UnitFile, sections: &[&str], ipv4: bool) -> Option<DhcpOverrides> {
+    let get = |key: &str| {
+        sections
+            .iter()
+            .rev()
+            .find_map(|section| unit.get(section, key))
+    };
+    let overrides = DhcpOverrides {
+        use_dns: get("UseDNS").and_then(boolean),
+        use_ntp: get("UseNTP").and_then(boolean),
+        send_hostname: get("SendHostname").and_then(boolean),
+        use_hostname: get("UseHostname").and_then(boolean),
+        use_mtu: get("UseMTU")
+            .and_then(boolean)
+            .filter(|use_mtu| !(ipv4 && *use_mtu)),
+        hostname: get("Hostname").map(str::to_string),
+        use_routes: get("UseRoutes").and_then(boolean),
+        route_metric: get("RouteMetric")
+            .and_then(|metric| metric.parse().ok())
+            .filter(|metric| !(ipv4 && *metric == 100)),
+        use_domains: get("UseDomains").map(str::to_string),
+    };
+    (overrides != DhcpOverrides::default()).then_some(overrides)
+}
+
+#[cfg(all(test, feature = "yaml"))]
+mod test {
+    use crate::{NetplanConfig, NetworkdFile};
+
+    #[test]
+    fn import_networkd_units() {
+        let config = NetplanConfig::from_yaml(
+            r#"
+network:
+  version: 2
+  ethernets:
+    nic0:
+      match: {macaddress: "52:54:00:aa:bb:01"}
+      set-name: eth0
+      mtu: 9000
+      tcp-segmentation-offload: true
+      tcp6-segmentation-offload: false
+    eth1: {}
+    eth2:
+      match: {driver: [ixgbe]}
+      dhcp6: true
+      dhcp6-overrides: {use-dns: false}
+  bonds:
+    bond0:
+      interfaces: [nic0, eth1]
+      parameters: {mode: active-backup, mii-monitor-interval: "100", primary: nic0}
+      addresses:
+        - 10.0.0.2/24
+        - 10.0.0.3/24: {label: "bond0:1"}
+      nameservers: {addresses: [10.0.0.1], search: [lan]}
+      routes:
+        - to: default
+          via: 10.0.0.1
+        - to: 10.1.0.0/16
+          via: 10.0.0.254
+          table: 100
+      routing-policy:
+        - from: 10.0.0.0/24
+          table: 100
+  vlans:
+    vlan10:
+      id: 10
+      link: bond0
+      dhcp4: true
+  tunnels:
+    wg0:
+      mode: wireguard
+      key: {private: /etc/wireguard/wg0.key}
+      port: "51820"
+      addresses: [10.10.0.1/24]
+      peers:
+        - endpoint: 198.51.100.1:51820
+          allowed-ips: [10.10.0.0/24]
+          keys: {public: "M9nt4YujIOmNrRmpIRTmYSfMdrpvE7u6WkG8FY8WjG4="}
+"#,
+        )
+        .unwrap();
+
+        let files = config.to_networkd();
+        let imported = NetplanConfig::from_networkd(&files);
+        assert!(imported.semantically_eq(&config), "{imported:#?}");
+
+        // Units written by hand, with comments, continuation lines and gateways
+        let files = [
+            NetworkdFile::parse(
+                "50-wired.network",
+                "# Uplink\n[Match]\nName=enp*\n\n[Network]\nAddress=192.0.2.10/24\nGateway=192.0.2.1\nDNS=192.0.2.1 \\\n    192.0.2.2\n",
+            )
+            .unwrap(),
+            NetworkdFile::parse("60-fiber.network", "[Match]\nDriver=ixgbe\n[Network]\nDHCP=yes\n")
+                .unwrap(),
+        ];
+        let expected = NetplanConfig::from_yaml(
+            r#"
+network:
+  version: 2
+  ethernets:
+    wired:
+      match: {name: "enp*"}
+      addresses: [192.0.2.10/24]
+      nameservers: {addresses: [192.0.2.1, 192.0.2.2]}
+      routes:
+        - to: default
+          via: 192.0.2.1
+    fiber:
+      match: {driver: [ixgbe]}
+      dhcp4: true
+      dhcp6: true
+"#,
+        )
+        .unwrap();
+        assert!(NetplanConfig::from_networkd(&files).semantically_eq(&expected));
+
+        let dir = tempfile::tempdir().unwrap();
+        config.write_networkd(dir.path()).unwrap();
+        let loaded = NetplanConfig::from_networkd_dir(dir.path()).unwrap();
+        assert!(loaded.semantically_eq(&config));
+    }
+}
@@ -0,0 +1,236 @@
+//! Reading and writing systemd-networkd units, to use configurations without the netplan binary.
+
+mod generate;
+mod import;
+pub use import::*;
+
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// The prefix of the files generated for the networkd renderer, the same one netplan uses.
+pub(crate) const FILE_PREFIX: &str = "10-netplan-";
+
+/// The extensions of the files generated for the networkd renderer.
+pub(crate) const EXTENSIONS: [&str; 3] = ["network", "netdev", "link"];
+
+/// A systemd unit file: a list of sections, each a list of `Key=Value` entries. Sections
+/// and keys may repeat, e.g. one `[Route]` section per route, or one `Address=` per address.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UnitFile {
+    pub sections: Vec<UnitSection>,
+}
+
+/// A section of a [UnitFile], e.g. `[Network]`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UnitSection {
+    pub name: String,
+    pub entries: Vec<(String, String)>,
+}
+
+impl UnitFile {
+    /// The sections with the given name, in order.
+    pub fn sections<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a UnitSection> {
+        self.sections
+            .iter()
+            .filter(move |section| section.name == name)
+    }
+
+    /// The first value of `key` in the first section with the given name which has it.
+    pub fn get(&self, section: &str, key: &str) -> Option<&str> {
+        self.sections
+            .iter()
+            .filter(|s| s.name == section)
+            .find_map(|section| section.get(key))
+    }
+
+    /// Every value of `key` in the sections with the given name, in order.
+    pub fn get_all<'a>(&'a self, section: &'a str, key: &'a str) -> impl Iterator<Item = &'a str> {
+        self.sections(section)
+            .flat_map(move |section| section.get_all(key))
+    }
+
+    /// Append the section, unless it has no entries.
+    pub(crate) fn push(&mut self, section: UnitSection) {
+        if !section.entries.is_empty() {
+            self.sections.push(section);
+        }
+    }
+
+    /// Set `key` in the first section with the given name, replacing its first value, or
+    /// appending the entry, and the section if there is none.
+    pub(crate) fn set(&mut self, section: &str, key: &str, value: impl ToString) {
+        let index = match self.sections.iter().position(|s| s.name == section) {
+            Some(index) => index,
+            None => {
+                self.sections.push(UnitSection::new(section));
+                self.sections.len() - 1
+            }
+        };
+        let section = &mut self.sections[index];
+        match section.entries.iter_mut().find(|(k, _)| k == key) {
+            Some((_, existing)) => *existing = value.to_string(),
+            None => section.add(key, value),
+        }
+    }
+}
+
+impl UnitSection {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            entries: Vec::new(),
+        }
+    }
+
+    /// The first value of `key`.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Every value of `key`, in order.
+    pub fn get_all<'a>(&'a self, key: &'a str) -> impl Iterator<Item = &'a str> {
+        self.entries
+            .iter()
+            .filter(move |(k, _)| k == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Append an entry.
+    pub fn add(&mut self, key: impl Into<String>, value: impl ToString) {
+        self.entries.push((key.into(), value.to_string()));
+    }
+
+    /// Append an entry if `value` is set.
+    pub(crate) fn add_opt(&mut self, key: &str, value: Option<impl ToString>) {
+        if let Some(value) = value {
+            self.add(key, value);
+        }
+    }
+}
+
+impl Display for UnitFile {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (i, section) in self.sections.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{section}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for UnitSection {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "[{}]", self.name)?;
+        for (key, value) in &self.entries {
+            writeln!(f, "{key}={value}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The error returned when a unit file cannot be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnitParseError {
+    /// The line the error was found on, starting at 1.
+    pub line: usize,
+    pub message: String,
+}
+
+impl Display for UnitParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for UnitParseError {}
+
+/// Parse a unit file. Empty lines and comments, starting with `#` or `;`, are skipped, and
+/// lines ending with a backslash are continued on the next line.
+///
+/// ```
+/// use netplan_types::UnitFile;
+///
+/// let unit: UnitFile = "[Match]\nName=eth0\n\n# Comment\n[Network]\nDNS=10.0.0.1 \\\n  10.0.0.2\n"
+///     .parse()
+///     .unwrap();
+/// assert_eq!(unit.get("Match", "Name"), Some("eth0"));
+/// assert_eq!(unit.get("Network", "DNS"), Some("10.0.0.1 10.0.0.2"));
+/// ```
+impl FromStr for UnitFile {
+    type Err = UnitParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut unit = UnitFile::default();
+        let mut lines = s.lines().enumerate();
+        while let Some((index, line)) = lines.next() {
+            let mut line = line.trim().to_string();
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+            while line.ends_with('\\') {
+                line.pop();
+                match lines.next() {
+                    Some((_, next)) => {
+                        line = format!("{} {}", line.trim_end(), next.trim());
+                    }
+                    None => break,
+                }
+            }
+
+            let error = |message: &str| UnitParseError {
+                line: index + 1,
+                message: message.to_string(),
+            };
+            if let Some(name) = line.strip_prefix('[') {
+                let name = name
+                    .strip_suffix(']')
+                    .ok_or_else(|| error("unterminated section header"))?;
+                unit.sections.push(UnitSection::new(name));
+            } else {
+                let (key, value) = line
+                    .split_once('=')
+                    .ok_or_else(|| error("expected a Key=Value assignment"))?;
+                let section = unit
+                    .sections
+                    .last_mut()
+                    .ok_or_else(|| error("assignment outside of a section"))?;
+                section.add(key.trim(), value.trim());
+            }
+        }
+
+        Ok(unit)
+    }
+}
+
+/// A networkd unit and its file name, e.g. generated by [NetplanConfig::to_networkd](crate::NetplanConfig::to_networkd).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetworkdFile {
+    /// The name of the file, e.g. `10-netplan-eth0.network`.
+    pub file_name: String,
+    pub unit: UnitFile,
+}
+
+impl NetworkdFile {
+    /// Parse the contents of a unit file.
+    pub fn parse(file_name: impl Into<String>, contents: &str) -> Result<Self, UnitParseError> {
+        Ok(Self {
+            file_name: file_name.into(),
+            unit: contents.parse()?,
+        })
+    }
+
+    /// The extension of the file, which determines the kind of unit: `network`, `netdev`
+    /// or `link`.
+    pub fn extension(&self) -> Option<&str> {
+        self.file_name
+            .rsplit_once('.')
+            .map(|(_, extension)| extension)
+    }
+}
@@ -0,0 +1,445 @@
+//! Rendering configurations into nmstate states.
+
+use crate::ifupdown::controller_options;
+use crate::nmstate::{
+    NmstateAddress, NmstateBond, NmstateBridge, NmstateBridgeOptions, NmstateBridgePort,
+    NmstateDnsConfig, NmstateDnsResolver, NmstateInterface, NmstateIp, NmstateRoute,
+    NmstateRouteRule, NmstateRouteRules, NmstateRoutes, NmstateState, NmstateStp, NmstateVlan,
+    NmstateVrf,
+};
+use crate::{ActivationMode, AnyDeviceConfig, CommonPropertiesAllDevices, NetplanConfig};
+
+impl NetplanConfig {
+    /// Render the configuration into an nmstate state, e.g. for the `desiredState` of a
+    /// kubernetes-nmstate policy, so RHEL nodes can share a configuration with Ubuntu ones.
+    ///
+    /// Ethernets, bonds, bridges, VLANs, VRFs and dummy devices become interfaces of the
+    /// matching type, named after the name ethernets are set to, with the parameters of
+    /// bonds and bridges nmstate has options for. DHCP, router advertisements and the
+    /// addresses of a device become its `ipv4` and `ipv6` settings, and the DHCP overrides
+    /// for DNS, routes and the route metric their `auto-*` settings. Devices which are not
+    /// activated automatically are `down`, all others `up`.
+    ///
+    /// Routes, including the deprecated gateways, become routes through their device, and
+    /// routing policy becomes route rules. nmstate applies DNS settings to the whole host,
+    /// so the nameservers of all devices are merged.
+    ///
+    /// The schema has no equivalent for other kinds of devices, like wifis or tunnels, or for
+    /// settings like matching, which are left out. So are VRFs with a negative table, which
+    /// is not a valid table ID. The state can be imported again with
+    /// [NetplanConfig::from_nmstate].
+    ///
+    /// ```
+    /// use netplan_types::NetplanConfig;
+    ///
+    /// let config = NetplanConfig::build(|net| {
+    ///     net.ethernet("eth0", |e| {
+    ///         e.address("192.168.1.10/24").route("default", "192.168.1.1")
+    ///     });
+    /// });
+    ///
+    /// let state = config.to_nmstate();
+    /// let eth0 = &state.interfaces[0];
+    /// assert_eq!(eth0.ipv4.as_ref().unwrap().address[0].ip, "192.168.1.10");
+    /// let route = &state.routes.as_ref().unwrap().config[0];
+    /// assert_eq!(route.destination.as_deref(), Some("0.0.0.0/0"));
+    /// assert!(state.to_yaml().contains("type: ethernet"));
+    /// ```
+    pub fn to_nmstate(&self) -> NmstateState {
+        let mut devices = self.network.devices().collect::<Vec<_>>();
+        devices.sort_by_key(|(kind, name, _)| (*kind, *name));
+
+        let mut state = NmstateState::default();
+        let mut routes = Vec::new();
+        let mut rules = Vec::new();
+        let mut dns = NmstateDnsConfig::default();
+        for (_, name, device) in devices {
+            let mut interface = NmstateInterface {
+                name: name.to_string(),
+                ..Default::default()
+            };
+            interface.r#type = match &device {
+                AnyDeviceConfig::Ethernet(ethernet) => {
+                    let physical = ethernet.common_physical.as_deref();
+                    if let Some(set_name) = physical.and_then(|p| p.set_name.as_ref()) {
+                        interface.name = set_name.clone();
+                    }
+                    "ethernet"
+                }
+                AnyDeviceConfig::Bond(bond) => {
+                    interface.link_aggregation =
+                        Some(bond_settings(&device, bond.interfaces.clone()));
+                    "bond"
+                }
+                AnyDeviceConfig::Bridge(bridge) => {
+                    interface.bridge = Some(bridge_settings(&device, bridge.interfaces.as_deref()));
+                    "linux-bridge"
+                }
+                AnyDeviceConfig::Vlan(vlan) => {
+                    interface.vlan = vlan
+                        .link
+                        .clone()
+                        .zip(vlan.id)
+                        .map(|(base_iface, id)| NmstateVlan { base_iface, id });
+                    "vlan"
+                }
+                AnyDeviceConfig::Vrf(vrf) => {
+                    let Ok(route_table_id) = u32::try_from(vrf.table) else {
+                        continue;
+                    };
+                    interface.vrf = Some(NmstateVrf {
+                        port: vrf.interfaces.clone(),
+                        route_table_id,
+                    });
+                    "vrf"
+                }
+                AnyDeviceConfig::DummyDevice(_) => "dummy",
+                _ => continue,
+            }
+            .to_string();
+
+            let common = device.common_all().cloned().unwrap_or_default();
+            let manual = matches!(
+                common.activation_mode,
+                Some(ActivationMode::Manual | ActivationMode::Off)
+            );
+            interface.state = Some(if manual { "down" } else { "up" }.to_string());
+            interface.mtu = common.mtu;
+            interface.mac_address = common.macaddress.as_ref().map(|mac| mac.to_string());
+            interface.ipv4 = Some(ip_settings(&common, false));
+            interface.ipv6 = Some(ip_settings(&common, true));
+
+            routes.extend(device_routes(&common, &interface.name));
+            for policy in common.routing_policy.iter().flatten() {
+                rules.push(NmstateRouteRule {
+                    ip_from: policy.from.clone(),
+                    ip_to: policy.to.clone(),
+                    priority: policy.priority.map(i64::from),
+                    route_table: Some(u32::from(policy.table)),
+                    fwmark: policy.mark.map(u32::from),
+                    ..Default::default()
+                });
+            }
+            if let Some(nameservers) = &common.nameservers {
+                for (list, values) in [
+                    (&mut dns.server, &nameservers.addresses),
+                    (&mut dns.search, &nameservers.search),
+                ] {
+                    for value in values.iter().flatten() {
+                        if !list.contains(value) {
+                            list.push(value.clone());
+                        }
+                    }
+                }
+            }
+
+            state.interfaces.push(interface);
+        }
+
+        if dns != NmstateDnsConfig::default() {
+            state.dns_resolver = Some(NmstateDnsResolver { config: Some(dns) });
+        }
+        if !routes.is_empty() {
+            state.routes = Some(NmstateRoutes { config: routes });
+        }
+        if !rules.is_empty() {
+            state.route_rules = Some(NmstateRouteRules { config: rules });
+        }
+        state
+    }
+}
+
+/// The parameters of a bond or bridge as ifupdown options, without the one listing its
+/// members, with the `bond-` or `bridge-` prefix removed.
+fn parameters<'a>(
+    device: &AnyDeviceConfig<'_>,
+    prefix: &'a str,
+) -> impl Iterator<Item = (String, String)> + 'a {
+    controller_options(device)
+        .into_iter()
+        .filter_map(move |(key, value)| Some((key.strip_prefix(prefix)?.to_string(), value)))
+}
+
+fn bond_settings(device: &AnyDeviceConfig<'_>, ports: Option<Vec<String>>) -> NmstateBond {
+    let mut bond = NmstateBond {
+        port: Some(ports.unwrap_or_default()),
+        ..Default::default()
+    };
+    let mut options = std::collections::BTreeMap::new();
+    for (key, value) in parameters(device, "bond-") {
+        let value = match key.as_str() {
+            "slaves" => continue,
+            "mode" => {
+                bond.mode = Some(value);
+                continue;
+            }
+            "arp-ip-target" => serde_yaml::Value::from(value.replace(' ', ",")),
+            _ => match value.parse::<u64>() {
+                Ok(number) => serde_yaml::Value::from(number),
+                Err(_) => serde_yaml::Value::from(value),
+            },
+        };
+        options.insert(key.replace('-', "_"), value);
+    }
+    bond.options = (!options.is_empty()).then_some(options);
+    bond
+}
+
+fn bridge_settings(device: &AnyDeviceConfig<'_>, ports: Option<&[String]>) -> NmstateBridge {
+    let mut options = NmstateBridgeOptions::default();
+    let mut stp = NmstateStp::default();
+    for (key, value) in parameters(device, "bridge-") {
+        let number = value.parse().ok();
+        match key.as_str() {
+            "stp" => stp.enabled = Some(value == "on"),
+            "fd" => stp.forward_delay = number,
+            "hello" => stp.hello_time = number,
+            "maxage" => stp.max_age = number,
+            "bridgeprio" => stp.priority = number,
+            "ageing" => options.mac_ageing_time = number,
+            _ => {}
+        }
+    }
+    if stp != NmstateStp::default() {
+        options.stp = Some(stp);
+    }
+
+    let ports = ports
+        .unwrap_or_default()
+        .iter()
+        .map(|name| NmstateBridgePort { name: name.clone() });
+    NmstateBridge {
+        options: (options != NmstateBridgeOptions::default()).then_some(options),
+        port: Some(ports.collect()),
+    }
+}
+
+/// The settings of one address family of a device.
+fn ip_settings(common: &CommonPropertiesAllDevices, ipv6: bool) -> NmstateIp {
+    let address = common
+        .addresses
+        .iter()
+        .flatten()
+        .flat_map(|mapping| mapping.addresses())
+        .filter(|address| address.contains(':') == ipv6)
+        .filter_map(|address| {
+            let (ip, prefix) = address.split_once('/')?;
+            Some(NmstateAddress {
+                ip: ip.to_string(),
+                prefix_length: prefix.parse().ok()?,
+            })
+        })
+        .collect::<Vec<_>>();
+    let (dhcp, overrides) = match ipv6 {
+        false => (common.dhcp4 == Some(true), &common.dhcp4_overrides),
+        true => (common.dhcp6 == Some(true), &common.dhcp6_overrides),
+    };
+    let autoconf = ipv6 && common.accept_ra == Some(true);
+    if !dhcp && !autoconf && address.is_empty() {
+        return NmstateIp {
+            enabled: Some(false),
+            ..Default::default()
+        };
+    }
+
+    let overrides = overrides.as_ref().filter(|_| dhcp);
+    NmstateIp {
+        enabled: Some(true),
+        dhcp: Some(dhcp),
+        autoconf: ipv6.then_some(autoconf),
+        auto_dns: overrides.and_then(|o| o.use_dns),
+        auto_routes: overrides.and_then(|o| o.use_routes),
+        auto_route_metric: overrides.and_then(|o| o.route_metric).map(u32::from),
+        address,
+    }
+}
+
+/// The routes through a device, including the deprecated gateways. The family of a route
+/// is that of its gateway or destination.
+fn device_routes(common: &CommonPropertiesAllDevices, interface: &str) -> Vec<NmstateRoute> {
+    let default = |ipv6: bool| if ipv6 { "::/0" } else { "0.0.0.0/0" };
+    let gateways = [(false, &common.gateway4), (true, &common.gateway6)]
+        .into_iter()
+        .filter_map(|(ipv6, gateway)| {
+            Some(NmstateRoute {
+                destination: Some(default(ipv6).to_string()),
+                next_hop_address: Some(gateway.clone()?),
+                next_hop_interface: Some(interface.to_string()),
+                ..Default::default()
+            })
+        });
+    let routes = common.routes.iter().flatten().filter_map(|route| {
+        let ipv6 = route.via.as_deref().or(route.to.as_deref())?.contains(':');
+        let destination = match route.to.as_deref() {
+            Some("default") | None => default(ipv6).to_string(),
+            Some(to) => to.to_string(),
+        };
+        Some(NmstateRoute {
+            destination: Some(destination),
+            next_hop_address: route.via.clone(),
+            next_hop_interface: Some(interface.to_string()),
+            metric: route.metric.map(u32::from),
+            table_id: route.table.map(u32::from),
+            ..Default::default()
+        })
+    });
+    gateways.chain(routes).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{NetplanConfig, NmstateState};
+
+    #[test]
+    fn export_nmstate() {
+        let config = NetplanConfig::from_yaml(
+            r#"
+network:
+  version: 2
+  ethernets:
+    nic0:
+      match: {macaddress: "52:54:00:12:34:01"}
+      set-name: eno1
+    eno2: {}
+  bonds:
+    bond0:
+      interfaces: [eno1, eno2]
+      parameters:
+        mode: active-backup
+        mii-monitor-interval: 100ms
+        arp-ip-targets: [10.0.0.1, 10.0.0.2]
+      addresses: [10.0.0.10/24, "2001:db8::10/64"]
+      gateway6: "2001:db8::1"
+      routes:
+        - to: default
+          via: 10.0.0.1
+          metric: 50
+      routing-policy:
+        - from: 10.0.0.0/24
+          table: 100
+      nameservers:
+        addresses: [10.0.0.53]
+  vlans:
+    vlan20:
+      id: 20
+      link: bond0
+      dhcp4: true
+      dhcp4-overrides: {use-routes: false, route-metric: 200}
+      activation-mode: manual
+      nameservers:
+        addresses: [10.0.0.53, 10.0.0.54]
+        search: [example.com]
+  bridges:
+    br0:
+      parameters: {stp: true, priority: 4096, hello-time: 2s}
+  wifis:
+    wlan0:
+      access-points: {home: {password: secret12}}
+"#,
+        )
+        .unwrap();
+
+        let expected = NmstateState::from_yaml(
+            r#"
+dns-resolver:
+  config:
+    server: [10.0.0.53, 10.0.0.54]
+    search: [example.com]
+route-rules:
+  config:
+    - ip-from: 10.0.0.0/24
+      route-table: 100
+routes:
+  config:
+    - destination: "::/0"
+      next-hop-address: "2001:db8::1"
+      next-hop-interface: bond0
+    - destination: 0.0.0.0/0
+      next-hop-address: 10.0.0.1
+      next-hop-interface: bond0
+      metric: 50
+interfaces:
+  - name: eno2
+    type: ethernet
+    state: up
+    ipv4: {enabled: false}
+    ipv6: {enabled: false}
+  - name: eno1
+    type: ethernet
+    state: up
+    ipv4: {enabled: false}
+    ipv6: {enabled: false}
+  - name: bond0
+    type: bond
+    state: up
+    ipv4:
+      enabled: true
+      dhcp: false
+      address: [{ip: 10.0.0.10, prefix-length: 24}]
+    ipv6:
+      enabled: true
+      dhcp: false
+      autoconf: false
+      address: [{ip: "2001:db8::10", prefix-length: 64}]
+    link-aggregation:
+      mode: active-backup
+      port: [eno1, eno2]
+      options:
+        miimon: 100
+        arp_ip_target: 10.0.0.1,10.0.0.2
+  - name: br0
+    type: linux-bridge
+    state: up
+    ipv4: {enabled: false}
+    ipv6: {enabled: false}
+    bridge:
+      options:
+        stp: {enabled: true, hello-time: 2, priority: 4096}
+      port: []
+  - name: vlan20
+    type: vlan
+    state: down
+    ipv4:
+      enabled: true
+      dhcp: true
+      auto-routes: false
+      auto-route-metric: 200
+    ipv6: {enabled: false}
+    vlan: {base-iface: bond0, id: 20}
+"#,
+        )
+        .unwrap();
+        let state = config.to_nmstate();
+        assert_eq!(state, expected, "{}", state.to_yaml());
+
+        let imported =
+            NetplanConfig::from_nmstate(&NmstateState::from_yaml(&state.to_yaml()).unwrap());
+        let vlan20 = &imported.network.vlans.as_ref().unwrap()["vlan20"];
+        assert_eq!(
+            Some(vlan20),
+            config.network.vlans.as_ref().map(|v| &v["vlan20"])
+        );
+    }
+
+    #[test]
+    fn negative_vrf_table() {
+        let config = NetplanConfig::from_yaml(
+            r#"
+network:
+  version: 2
+  vrfs:
+    vrf0: {table: 100, interfaces: []}
+    vrf1: {table: -1, interfaces: []}
+"#,
+        )
+        .unwrap();
+
+        let state = config.to_nmstate();
+        assert_eq!(state.interfaces.len(), 1);
+        assert_eq!(state.interfaces[0].name, "vrf0");
+        assert_eq!(
+            state.interfaces[0].vrf.as_ref().unwrap().route_table_id,
+            100
+        );
+    }
+}
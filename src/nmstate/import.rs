@@ -0,0 +1,448 @@
+//! Importing nmstate states into configurations.
+
+use crate::cloud_init::{add_nameservers, addressed};
+use crate::ifupdown::define_from_options;
+use crate::nmstate::{NmstateInterface, NmstateIp, NmstateState};
+use crate::{
+    ActivationMode, AddressMapping, CommonProperties, CommonPropertiesAllDevices, DhcpOverrides,
+    DummyDeviceConfig, EthernetConfig, NetplanConfig, RoutingConfig, RoutingPolicy, VlanConfig,
+    VrfsConfig,
+};
+use std::collections::HashSet;
+
+impl NetplanConfig {
+    /// Lift an nmstate state into a configuration, the inverse of
+    /// [NetplanConfig::to_nmstate].
+    ///
+    /// Ethernets, bonds, Linux bridges, VLANs, VRFs and dummy interfaces become the devices
+    /// of the same kind, with the options of bonds and bridges read like the options of an
+    /// ifupdown interfaces file, and interfaces which are `down` get the `manual`
+    /// activation mode. Ports of bonds, bridges and VRFs, and links of VLANs, without an
+    /// interface of their own are defined as ethernets. Interfaces which are `absent`, and
+    /// other types, like Open vSwitch bridges, are left out.
+    ///
+    /// Routes are added to the device of their `next-hop-interface`, and route rules to the
+    /// device of their `iif`, or else to the device routing into their table, and left out
+    /// if there is none. The DNS settings, which nmstate applies to the whole host, are
+    /// added to every device with addressing.
+    ///
+    /// ```
+    /// use netplan_types::{NetplanConfig, NmstateState};
+    ///
+    /// let state = NmstateState::from_yaml("
+    /// interfaces:
+    ///   - name: eth0
+    ///     type: ethernet
+    ///     state: up
+    ///     ipv4:
+    ///       enabled: true
+    ///       address:
+    ///         - ip: 192.168.1.10
+    ///           prefix-length: 24
+    /// routes:
+    ///   config:
+    ///     - destination: 0.0.0.0/0
+    ///       next-hop-address: 192.168.1.1
+    ///       next-hop-interface: eth0
+    /// ").unwrap();
+    ///
+    /// let expected = NetplanConfig::build(|net| {
+    ///     net.ethernet("eth0", |e| {
+    ///         e.address("192.168.1.10/24").route("default", "192.168.1.1")
+    ///     });
+    /// });
+    /// assert_eq!(NetplanConfig::from_nmstate(&state), expected);
+    /// ```
+    pub fn from_nmstate(state: &NmstateState) -> Self {
+        let mut config = NetplanConfig::default();
+        let network = &mut config.network;
+        let mut dependencies = HashSet::new();
+
+        for interface in &state.interfaces {
+            if interface.state.as_deref() == Some("absent") {
+                continue;
+            }
+            let name = interface.name.as_str();
+            match interface.r#type.as_str() {
+                "ethernet" => {
+                    network.add_ethernet(name, EthernetConfig::default());
+                }
+                "bond" | "linux-bridge" => {
+                    let options = controller_options(interface);
+                    dependencies.extend(define_from_options(network, name, options));
+                }
+                "vlan" => {
+                    let vlan = interface.vlan.as_ref();
+                    network.add_vlan(
+                        name,
+                        VlanConfig {
+                            id: vlan.map(|vlan| vlan.id),
+                            link: vlan.map(|vlan| vlan.base_iface.clone()),
+                            ..Default::default()
+                        },
+                    );
+                    dependencies.extend(vlan.map(|vlan| vlan.base_iface.clone()));
+                }
+                "vrf" => {
+                    let Some((vrf, table)) = interface
+                        .vrf
+                        .as_ref()
+                        .and_then(|vrf| Some((vrf, i32::try_from(vrf.route_table_id).ok()?)))
+                    else {
+                        continue;
+                    };
+                    network.add_vrf(
+                        name,
+                        VrfsConfig {
+                            table,
+                            interfaces: vrf.port.clone(),
+                            ..Default::default()
+                        },
+                    );
+                    dependencies.extend(vrf.port.iter().cloned());
+                }
+                "dummy" => {
+                    network.add_dummy_device(name, DummyDeviceConfig::default());
+                }
+                _ => continue,
+            }
+
+            let common = import_interface(interface);
+            if common != CommonPropertiesAllDevices::default() {
+                if let Some(mut device) = network.device_mut(name) {
+                    *device.common_all_mut() = common;
+                }
+            }
+        }
+
+        for dependency in &dependencies {
+            if network.device(dependency).is_none() {
+                network.add_ethernet(dependency.as_str(), EthernetConfig::default());
+            }
+        }
+
+        let routes = state.routes.iter().flat_map(|routes| &routes.config);
+        for route in routes {
+            if route.state.as_deref() == Some("absent") {
+                continue;
+            }
+            let Some(name) = &route.next_hop_interface else {
+                continue;
+            };
+            let Some(mut device) = network.device_mut(name) else {
+                continue;
+            };
+            let to = match route.destination.as_deref() {
+                Some("0.0.0.0/0" | "::/0") | None => "default".to_string(),
+                Some(destination) => destination.to_string(),
+            };
+            device
+                .common_all_mut()
+                .routes
+                .get_or_insert_with(Vec::new)
+                .push(RoutingConfig {
+                    to: Some(to),
+                    via: route.next_hop_address.clone(),
+                    metric: route.metric.and_then(|metric| metric.try_into().ok()),
+                    table: route
+                        .table_id
+                        .filter(|table| *table != 0 && *table != 254)
+                        .and_then(|table| table.try_into().ok()),
+                    ..Default::default()
+                });
+        }
+
+        let rules = state.route_rules.iter().flat_map(|rules| &rules.config);
+        for rule in rules {
+            if rule.state.as_deref() == Some("absent") {
+                continue;
+            }
+            let table = rule.route_table.unwrap_or(254);
+            let name = rule.iif.clone().or_else(|| {
+                network
+                    .devices()
+                    .filter(|(_, _, device)| {
+                        device.common_all().is_some_and(|common| {
+                            common
+                                .routes
+                                .iter()
+                                .flatten()
+                                .any(|route| route.table.map_or(254, u32::from) == table)
+                        })
+                    })
+                    .map(|(_, name, _)| name.to_string())
+                    .min()
+            });
+            let Some(mut device) = name.and_then(|name| network.device_mut(&name)) else {
+                continue;
+            };
+            let Ok(table) = table.try_into() else {
+                continue;
+            };
+            device
+                .common_all_mut()
+                .routing_policy
+                .get_or_insert_with(Vec::new)
+                .push(RoutingPolicy {
+                    from: rule.ip_from.clone(),
+                    to: rule.ip_to.clone(),
+                    table,
+                    priority: rule.priority.and_then(|p| p.try_into().ok()),
+                    mark: rule.fwmark.and_then(|mark| mark.try_into().ok()),
+                    ..Default::default()
+                });
+        }
+
+        let dns = state
+            .dns_resolver
+            .as_ref()
+            .and_then(|dns| dns.config.as_ref());
+        if let Some(dns) = dns {
+            for name in addressed(network) {
+                if let Some(mut device) = network.device_mut(&name) {
+                    add_nameservers(device.common_all_mut(), &dns.server, &dns.search);
+                }
+            }
+        }
+
+        config
+    }
+}
+
+/// The ifupdown options of a bond or bridge, with its ports and parameters.
+fn controller_options(interface: &NmstateInterface) -> Vec<(String, String)> {
+    let mut options = Vec::new();
+    let mut add = |key: &str, value: String| options.push((key.to_string(), value));
+    let ports = |ports: Vec<String>| match ports.is_empty() {
+        true => "none".to_string(),
+        false => ports.join(" "),
+    };
+
+    if interface.r#type == "bond" {
+        let bond = interface.link_aggregation.clone().unwrap_or_default();
+        add("bond-slaves", ports(bond.port.unwrap_or_default()));
+        if let Some(mode) = bond.mode {
+            add("bond-mode", mode);
+        }
+        for (key, value) in bond.options.iter().flatten() {
+            let value = match value {
+                serde_yaml::Value::String(value) => value.replace(',', " "),
+                serde_yaml::Value::Number(value) => value.to_string(),
+                serde_yaml::Value::Bool(value) => (*value as u8).to_string(),
+                _ => continue,
+            };
+            add(&format!("bond-{}", key.replace('_', "-")), value);
+        }
+        return options;
+    }
+
+    let bridge = interface.bridge.clone().unwrap_or_default();
+    let names = bridge.port.unwrap_or_default().into_iter().map(|p| p.name);
+    add("bridge-ports", ports(names.collect()));
+    let bridge_options = bridge.options.unwrap_or_default();
+    let stp = bridge_options.stp.unwrap_or_default();
+    if let Some(enabled) = stp.enabled {
+        add("bridge-stp", if enabled { "on" } else { "off" }.to_string());
+    }
+    for (key, value) in [
+        ("bridge-fd", stp.forward_delay),
+        ("bridge-hello", stp.hello_time),
+        ("bridge-maxage", stp.max_age),
+        ("bridge-bridgeprio", stp.priority),
+        ("bridge-ageing", bridge_options.mac_ageing_time),
+    ] {
+        if let Some(value) = value {
+            add(key, value.to_string());
+        }
+    }
+    options
+}
+
+/// The addressing, MTU, MAC address and activation of an interface.
+fn import_interface(interface: &NmstateInterface) -> CommonPropertiesAllDevices {
+    let mut common = CommonPropertiesAllDevices {
+        mtu: interface.mtu,
+        macaddress: interface
+            .mac_address
+            .as_deref()
+            .and_then(|mac| mac.parse().ok()),
+        activation_mode: (interface.state.as_deref() == Some("down"))
+            .then_some(ActivationMode::Manual),
+        ..Default::default()
+    };
+
+    let mut addresses = Vec::new();
+    for (ipv6, ip) in [(false, &interface.ipv4), (true, &interface.ipv6)] {
+        let Some(ip) = ip.as_ref().filter(|ip| ip.enabled != Some(false)) else {
+            continue;
+        };
+        addresses.extend(
+            ip.address
+                .iter()
+                .map(|address| format!("{}/{}", address.ip, address.prefix_length)),
+        );
+
+        let dhcp = ip.dhcp == Some(true);
+        let overrides = dhcp.then(|| overrides(ip)).flatten();
+        if ipv6 {
+            common.dhcp6 = dhcp.then_some(true);
+            common.dhcp6_overrides = overrides;
+            common.accept_ra = ip.autoconf;
+        } else {
+            common.dhcp4 = dhcp.then_some(true);
+            common.dhcp4_overrides = overrides;
+        }
+    }
+    if !addresses.is_empty() {
+        common.addresses = Some(addresses.into_iter().map(AddressMapping::Simple).collect());
+    }
+
+    common
+}
+
+/// The DHCP overrides for the `auto-*` settings of an address family, if any differ from
+/// the defaults.
+fn overrides(ip: &NmstateIp) -> Option<DhcpOverrides> {
+    let overrides = DhcpOverrides {
+        use_dns: ip.auto_dns.filter(|auto| !auto),
+        use_routes: ip.auto_routes.filter(|auto| !auto),
+        route_metric: ip.auto_route_metric.and_then(|m| m.try_into().ok()),
+        ..Default::default()
+    };
+    (overrides != DhcpOverrides::default()).then_some(overrides)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{NetplanConfig, NmstateState};
+
+    #[test]
+    fn import_nmstate() {
+        let state = NmstateState::from_yaml(
+            r#"
+apiVersion: nmstate.io/v1
+kind: NodeNetworkConfigurationPolicy
+metadata:
+  name: workers
+spec:
+  nodeSelector:
+    node-role.kubernetes.io/worker: ""
+  desiredState:
+    dns-resolver:
+      config:
+        server: [10.0.0.53]
+        search: [example.com]
+    routes:
+      config:
+        - destination: 0.0.0.0/0
+          next-hop-address: 10.0.0.1
+          next-hop-interface: bond0
+          metric: 100
+        - destination: 10.1.0.0/16
+          next-hop-address: 10.0.20.254
+          next-hop-interface: bond0.20
+          table-id: 20
+        - destination: 10.9.0.0/16
+          next-hop-interface: bond0
+          state: absent
+    route-rules:
+      config:
+        - ip-from: 10.0.20.0/24
+          route-table: 20
+          priority: 100
+    interfaces:
+      - name: bond0
+        type: bond
+        state: up
+        mtu: 9000
+        ipv4:
+          enabled: true
+          address:
+            - ip: 10.0.0.10
+              prefix-length: 24
+        ipv6:
+          enabled: false
+        link-aggregation:
+          mode: 802.3ad
+          port: [eno1, eno2]
+          options:
+            miimon: 100
+            lacp_rate: fast
+      - name: bond0.20
+        type: vlan
+        state: up
+        vlan:
+          base-iface: bond0
+          id: 20
+        ipv4:
+          enabled: true
+          dhcp: true
+          auto-dns: false
+        ipv6:
+          enabled: true
+          dhcp: false
+          autoconf: true
+      - name: br-ex
+        type: linux-bridge
+        state: down
+        bridge:
+          options:
+            stp:
+              enabled: false
+              forward-delay: 4
+          port:
+            - name: eno3
+      - name: ovs0
+        type: ovs-bridge
+        state: up
+      - name: old0
+        type: dummy
+        state: absent
+"#,
+        )
+        .unwrap();
+
+        let expected = NetplanConfig::from_yaml(
+            r#"
+network:
+  version: 2
+  ethernets:
+    eno1: {}
+    eno2: {}
+    eno3: {}
+  bonds:
+    bond0:
+      interfaces: [eno1, eno2]
+      parameters:
+        mode: 802.3ad
+        mii-monitor-interval: "100"
+        lacp-rate: fast
+      mtu: 9000
+      addresses: [10.0.0.10/24]
+      routes:
+        - {to: default, via: 10.0.0.1, metric: 100}
+      nameservers: {addresses: [10.0.0.53], search: [example.com]}
+  vlans:
+    bond0.20:
+      id: 20
+      link: bond0
+      dhcp4: true
+      dhcp4-overrides: {use-dns: false}
+      accept-ra: true
+      routes:
+        - {to: 10.1.0.0/16, via: 10.0.20.254, table: 20}
+      routing-policy:
+        - {from: 10.0.20.0/24, table: 20, priority: 100}
+      nameservers: {addresses: [10.0.0.53], search: [example.com]}
+  bridges:
+    br-ex:
+      interfaces: [eno3]
+      parameters: {stp: false, forward-delay: "4"}
+      activation-mode: manual
+"#,
+        )
+        .unwrap();
+        let imported = NetplanConfig::from_nmstate(&state);
+        assert!(imported.semantically_eq(&expected), "{imported:#?}");
+    }
+}
@@ -0,0 +1,265 @@
+//! Converting nmstate's desired state, as used by kubernetes-nmstate on RHEL nodes.
+
+mod generate;
+mod import;
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// A desired network state in nmstate's YAML schema: interfaces, routes, route rules and
+/// DNS settings. Fields of the schema netplan has no equivalent for are skipped when it
+/// is read.
+///
+/// ```
+/// use netplan_types::NmstateState;
+///
+/// let state = NmstateState::from_yaml("
+/// interfaces:
+///   - name: eth0
+///     type: ethernet
+///     state: up
+///     ipv4:
+///       enabled: true
+///       dhcp: true
+/// ").unwrap();
+///
+/// let eth0 = &state.interfaces[0];
+/// assert_eq!(eth0.ipv4.as_ref().unwrap().dhcp, Some(true));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct NmstateState {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dns_resolver: Option<NmstateDnsResolver>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub route_rules: Option<NmstateRouteRules>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub routes: Option<NmstateRoutes>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub interfaces: Vec<NmstateInterface>,
+}
+
+/// The DNS settings of an [NmstateState], which apply to the whole host.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NmstateDnsResolver {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub config: Option<NmstateDnsConfig>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NmstateDnsConfig {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub server: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub search: Vec<String>,
+}
+
+/// The routes of an [NmstateState].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NmstateRoutes {
+    #[serde(default)]
+    pub config: Vec<NmstateRoute>,
+}
+
+/// A route through the interface named by `next-hop-interface`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct NmstateRoute {
+    /// The destination in CIDR notation, e.g. `0.0.0.0/0` for the default route.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub destination: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_hop_address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_hop_interface: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metric: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub table_id: Option<u32>,
+    /// `absent` for a route which is to be removed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
+}
+
+/// The routing policy rules of an [NmstateState].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NmstateRouteRules {
+    #[serde(default)]
+    pub config: Vec<NmstateRouteRule>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct NmstateRouteRule {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ip_from: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ip_to: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub route_table: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fwmark: Option<u32>,
+    /// The incoming interface the rule applies to.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub iif: Option<String>,
+    /// `absent` for a rule which is to be removed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
+}
+
+/// An interface of an [NmstateState]. Which of the sections apply depends on its `type`,
+/// e.g. `link-aggregation` only applies to bonds.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct NmstateInterface {
+    pub name: String,
+    /// The type of the interface: `ethernet`, `bond`, `linux-bridge`, `vlan`, `vrf`,
+    /// `dummy`, or another type netplan has no equivalent for.
+    pub r#type: String,
+    /// `up`, `down` or `absent`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mtu: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mac_address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipv4: Option<NmstateIp>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipv6: Option<NmstateIp>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub link_aggregation: Option<NmstateBond>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bridge: Option<NmstateBridge>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vlan: Option<NmstateVlan>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vrf: Option<NmstateVrf>,
+}
+
+/// The addressing of an [NmstateInterface] for one address family.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct NmstateIp {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dhcp: Option<bool>,
+    /// Whether router advertisements are accepted, IPv6 only.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub autoconf: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auto_dns: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auto_routes: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auto_route_metric: Option<u32>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub address: Vec<NmstateAddress>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct NmstateAddress {
+    pub ip: String,
+    pub prefix_length: u8,
+}
+
+/// The bond settings of an [NmstateInterface].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct NmstateBond {
+    /// The bonding mode, e.g. `802.3ad`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub port: Option<Vec<String>>,
+    /// The options of the bonding driver, named like its sysfs files, e.g. `miimon`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub options: Option<BTreeMap<String, serde_yaml::Value>>,
+}
+
+/// The Linux bridge settings of an [NmstateInterface].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NmstateBridge {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub options: Option<NmstateBridgeOptions>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub port: Option<Vec<NmstateBridgePort>>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct NmstateBridgeOptions {
+    /// The ageing time of the MAC address table, in seconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mac_ageing_time: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stp: Option<NmstateStp>,
+}
+
+/// The spanning tree settings of a bridge. Times are in seconds.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct NmstateStp {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub forward_delay: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hello_time: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_age: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<u32>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NmstateBridgePort {
+    pub name: String,
+}
+
+/// The VLAN settings of an [NmstateInterface].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct NmstateVlan {
+    pub base_iface: String,
+    pub id: u16,
+}
+
+/// The VRF settings of an [NmstateInterface].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct NmstateVrf {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub port: Vec<String>,
+    pub route_table_id: u32,
+}
+
+impl NmstateState {
+    /// Parse a state from YAML, either on its own or as the `desiredState` of a
+    /// kubernetes-nmstate `NodeNetworkConfigurationPolicy`, under `spec`.
+    pub fn from_yaml(yaml: &str) -> Result<Self, serde_yaml::Error> {
+        #[derive(Deserialize)]
+        struct Policy {
+            spec: Spec,
+        }
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Spec {
+            desired_state: NmstateState,
+        }
+
+        match serde_yaml::from_str::<Policy>(yaml) {
+            Ok(policy) => Ok(policy.spec.desired_state),
+            Err(_) => serde_yaml::from_str(yaml),
+        }
+    }
+
+    /// Serialize the state to YAML, as `nmstatectl apply` reads it.
+    pub fn to_yaml(&self) -> String {
+        // Serializing the state cannot fail, all map keys are strings
+        serde_yaml::to_string(self).expect("Serializing state")
+    }
+}
@@ -0,0 +1,82 @@
+//! Loading many configuration files in parallel, using rayon.
+
+use crate::load::yaml_files;
+use crate::{LoadedConfig, NetplanConfig};
+use rayon::prelude::*;
+use std::path::Path;
+
+impl NetplanConfig {
+    /// Load and merge all `*.yaml` files in a directory, see [NetplanConfig::load_dir]. The
+    /// files are read and parsed on rayon's thread pool, and merged in lexicographic order
+    /// of their file names afterwards, so the result is the same as that of
+    /// [NetplanConfig::load_dir].
+    pub fn load_dir_parallel(path: impl AsRef<Path>) -> std::io::Result<LoadedConfig> {
+        let files = yaml_files(path.as_ref())?
+            .into_par_iter()
+            .map(|path| {
+                let result = Self::load(&path);
+                (path, result)
+            })
+            .collect();
+
+        Ok(LoadedConfig::merge(files))
+    }
+
+    /// Parse many netplan YAML documents, like the configurations of a fleet of hosts, on
+    /// rayon's thread pool. The results are in the order of `documents`.
+    ///
+    /// ```
+    /// use netplan_types::NetplanConfig;
+    ///
+    /// let documents = (0..100)
+    ///     .map(|host| format!("network: {{version: 2, ethernets: {{eth0: {{mtu: {}}}}}}}", 1400 + host))
+    ///     .collect::<Vec<_>>();
+    ///
+    /// let configs = NetplanConfig::parse_many(&documents);
+    /// let eth0 = &configs[42].as_ref().unwrap().network.ethernets.as_ref().unwrap()["eth0"];
+    /// assert_eq!(eth0.common_all.as_ref().unwrap().mtu, Some(1442));
+    /// ```
+    pub fn parse_many<S>(documents: &[S]) -> Vec<Result<Self, serde_yaml::Error>>
+    where
+        S: AsRef<str> + Sync,
+    {
+        documents
+            .par_iter()
+            .map(|yaml| Self::from_yaml(yaml.as_ref()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::NetplanConfig;
+
+    #[test]
+    fn load_dir_parallel_matches_load_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        for host in 0..50 {
+            let yaml = format!(
+                "network: {{version: 2, ethernets: {{eth0: {{mtu: {}}}, eth{host}: {{dhcp4: true}}}}}}",
+                1400 + host
+            );
+            std::fs::write(dir.path().join(format!("{host:02}-host.yaml")), yaml).unwrap();
+        }
+        std::fs::write(dir.path().join("99-broken.yaml"), "network: [").unwrap();
+
+        let sequential = NetplanConfig::load_dir(dir.path()).unwrap();
+        let parallel = NetplanConfig::load_dir_parallel(dir.path()).unwrap();
+        assert_eq!(parallel.config, sequential.config);
+        let paths = |files: &[(_, _)]| {
+            files
+                .iter()
+                .map(|(path, _)| path)
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(paths(&parallel.files), paths(&sequential.files));
+        assert_eq!(parallel.errors().count(), 1);
+
+        let eth0 = &parallel.config.network.ethernets.as_ref().unwrap()["eth0"];
+        assert_eq!(eth0.common_all.as_ref().unwrap().mtu, Some(1449));
+    }
+}
@@ -0,0 +1,132 @@
+//! Applying merge patches to configurations.
+
+use crate::NetplanConfig;
+use serde_json::Value;
+use std::fmt::{Display, Formatter};
+
+/// The error returned when a merge patch results in an invalid configuration.
+#[derive(Debug)]
+pub struct MergePatchError(serde_json::Error);
+
+impl Display for MergePatchError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Patched configuration is invalid: {}", self.0)
+    }
+}
+
+impl std::error::Error for MergePatchError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
+impl NetplanConfig {
+    /// Apply a [JSON Merge Patch](https://www.rfc-editor.org/rfc/rfc7396) to the configuration.
+    ///
+    /// The patch mirrors the structure of the netplan YAML. Only the fields present in the
+    /// patch are changed: mappings are patched key by key, other values are replaced, and
+    /// an explicit `null` removes the field. To apply a patch which cannot remove fields,
+    /// a partial configuration can be merged with [NetplanConfig::merge] instead.
+    ///
+    /// The patch may be written as YAML as well, by deserializing it into a [serde_json::Value].
+    ///
+    /// If the patched configuration is invalid, an error is returned and the configuration
+    /// is left unchanged.
+    ///
+    /// ```
+    /// use netplan_types::NetplanConfig;
+    /// use serde_json::json;
+    ///
+    /// let mut config = NetplanConfig::build(|net| {
+    ///     net.ethernet("eth0", |e| e.dhcp4(true).mtu(9000));
+    /// });
+    ///
+    /// config.apply_merge_patch(&json!({
+    ///     "network": { "ethernets": { "eth0": { "dhcp4": false, "mtu": null } } }
+    /// })).unwrap();
+    ///
+    /// let eth0 = &config.network.ethernets.unwrap()["eth0"];
+    /// assert_eq!(eth0.common_all.as_deref().unwrap().dhcp4, Some(false));
+    /// assert_eq!(eth0.common_all.as_deref().unwrap().mtu, None);
+    /// ```
+    pub fn apply_merge_patch(&mut self, patch: &Value) -> Result<(), MergePatchError> {
+        // Serializing the configuration types cannot fail, all map keys are strings
+        let mut value = serde_json::to_value(&*self).expect("Serializing configuration");
+        merge_patch(&mut value, patch);
+        *self = serde_json::from_value(value).map_err(MergePatchError)?;
+        Ok(())
+    }
+}
+
+/// The MergePatch algorithm of RFC 7396.
+fn merge_patch(target: &mut Value, patch: &Value) {
+    let Value::Object(patch) = patch else {
+        *target = patch.clone();
+        return;
+    };
+
+    if !target.is_object() {
+        *target = Value::Object(Default::default());
+    }
+
+    let target = target.as_object_mut().unwrap();
+
+    for (key, value) in patch {
+        if value.is_null() {
+            target.remove(key);
+        } else {
+            merge_patch(target.entry(key.as_str()).or_insert(Value::Null), value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::NetplanConfig;
+
+    #[test]
+    fn yaml_patch() {
+        let input = r#"
+            network:
+              version: 2
+              ethernets:
+                eth0:
+                  dhcp4: true
+                  nameservers:
+                    addresses: [10.0.0.1]
+                    search: [example.com]
+                eth1:
+                  dhcp4: true
+            "#;
+        let patch = r#"
+            network:
+              ethernets:
+                eth0:
+                  nameservers:
+                    search: null
+                eth1: null
+            "#;
+        let expected = r#"
+            network:
+              version: 2
+              ethernets:
+                eth0:
+                  dhcp4: true
+                  nameservers:
+                    addresses: [10.0.0.1]
+            "#;
+
+        let mut config: NetplanConfig = serde_yaml::from_str(input).unwrap();
+        let patch: serde_json::Value = serde_yaml::from_str(patch).unwrap();
+        config.apply_merge_patch(&patch).unwrap();
+
+        let expected: NetplanConfig = serde_yaml::from_str(expected).unwrap();
+        assert_eq!(
+            serde_yaml::to_value(&config).unwrap(),
+            serde_yaml::to_value(&expected).unwrap()
+        );
+
+        let invalid = serde_json::json!({ "network": { "version": null } });
+        assert!(config.apply_merge_patch(&invalid).is_err());
+    }
+}
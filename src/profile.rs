@@ -0,0 +1,221 @@
+//! Profiles: a base configuration with named overlays, e.g. one per environment.
+
+use crate::load::yaml_files;
+use crate::{LoadError, NetplanConfig};
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+
+/// A base configuration, and overlays which adapt it to a profile, like `lab`, `prod` or
+/// `dr`. Resolving a profile merges its overlay into the base the way netplan merges its
+/// configuration files, see [NetplanConfig::merge].
+///
+/// ```
+/// use netplan_types::{ConfigProfiles, NetplanConfig};
+///
+/// let mut profiles = ConfigProfiles::new(NetplanConfig::single_nic_dhcp("eth0"));
+/// let prod = NetplanConfig::build(|net| {
+///     net.ethernet("eth0", |e| e.mtu(9000));
+/// });
+/// profiles.add_overlay("prod", prod);
+///
+/// let prod = profiles.resolve("prod").unwrap();
+/// let eth0 = &prod.network.ethernets.as_ref().unwrap()["eth0"];
+/// assert_eq!(eth0.common_all.as_ref().unwrap().dhcp4, Some(true));
+/// assert_eq!(eth0.common_all.as_ref().unwrap().mtu, Some(9000));
+/// assert!(profiles.resolve("staging").is_err());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConfigProfiles {
+    pub base: NetplanConfig,
+    /// The overlays, by the name of their profile.
+    pub overlays: BTreeMap<String, NetplanConfig>,
+}
+
+/// The error returned when profiles cannot be loaded or resolved.
+#[derive(Debug)]
+pub enum ProfileError {
+    /// A directory could not be read.
+    Io(std::io::Error),
+    /// A configuration file could not be loaded.
+    Load(PathBuf, LoadError),
+    /// There is no overlay for the profile.
+    Unknown(String),
+}
+
+impl Display for ProfileError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "Failed to read profiles: {e}"),
+            Self::Load(path, e) => write!(f, "{}: {e}", path.display()),
+            Self::Unknown(profile) => write!(f, "Unknown profile '{profile}'"),
+        }
+    }
+}
+
+impl std::error::Error for ProfileError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Load(_, e) => Some(e),
+            Self::Unknown(_) => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for ProfileError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl ConfigProfiles {
+    pub fn new(base: NetplanConfig) -> Self {
+        Self {
+            base,
+            overlays: BTreeMap::new(),
+        }
+    }
+
+    /// Add the overlay of a profile. Returns the overlay previously stored for the
+    /// profile, if any.
+    pub fn add_overlay(
+        &mut self,
+        profile: impl Into<String>,
+        overlay: NetplanConfig,
+    ) -> Option<NetplanConfig> {
+        self.overlays.insert(profile.into(), overlay)
+    }
+
+    /// The names of the profiles, in lexicographic order.
+    pub fn profiles(&self) -> impl Iterator<Item = &str> {
+        self.overlays.keys().map(String::as_str)
+    }
+
+    /// The configuration of a profile: the base, with the overlay of the profile merged
+    /// into it.
+    pub fn resolve(&self, profile: &str) -> Result<NetplanConfig, ProfileError> {
+        self.resolve_all(&[profile])
+    }
+
+    /// The base configuration with the overlays of several profiles merged into it, in
+    /// order, e.g. `["prod", "dr"]` for the disaster recovery site of production.
+    pub fn resolve_all(&self, profiles: &[&str]) -> Result<NetplanConfig, ProfileError> {
+        let mut config = self.base.clone();
+        for profile in profiles {
+            let overlay = self
+                .overlays
+                .get(*profile)
+                .ok_or_else(|| ProfileError::Unknown(profile.to_string()))?;
+            config.merge(overlay.clone());
+        }
+
+        Ok(config)
+    }
+
+    /// Load profiles from a directory: the `*.yaml` files in the directory are the base
+    /// configuration, and those in each of its subdirectories the overlay of the profile
+    /// named after the subdirectory. The files of a directory are merged like
+    /// [NetplanConfig::load_dir] does, but every file must be valid.
+    ///
+    /// ```text
+    /// profiles/
+    ///   10-base.yaml
+    ///   20-wifi.yaml
+    ///   lab/50-lab.yaml
+    ///   prod/50-prod.yaml
+    /// ```
+    pub fn load_dir(path: impl AsRef<Path>) -> Result<Self, ProfileError> {
+        let path = path.as_ref();
+        let mut profiles = Self::new(load_files(path)?);
+        for entry in std::fs::read_dir(path)? {
+            let path = entry?.path();
+            if !path.is_dir() {
+                continue;
+            }
+
+            let profile = path.file_name().unwrap_or_default().to_string_lossy();
+            profiles.add_overlay(profile.into_owned(), load_files(&path)?);
+        }
+
+        Ok(profiles)
+    }
+}
+
+/// Merge the configuration files of a directory, failing on the first invalid one.
+fn load_files(dir: &Path) -> Result<NetplanConfig, ProfileError> {
+    let mut config = NetplanConfig::default();
+    for path in yaml_files(dir)? {
+        match NetplanConfig::load(&path) {
+            Ok(file) => config.merge(file),
+            Err(e) => return Err(ProfileError::Load(path, e)),
+        }
+    }
+
+    Ok(config)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{ConfigProfiles, NetplanConfig, ProfileError};
+
+    #[test]
+    fn load_profiles() {
+        let dir = tempfile::tempdir().unwrap();
+        let write = |name: &str, yaml: &str| {
+            let path = dir.path().join(name);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, yaml).unwrap();
+        };
+        write(
+            "10-base.yaml",
+            "network: {version: 2, ethernets: {eth0: {dhcp4: true}}}",
+        );
+        write(
+            "20-mtu.yaml",
+            "network: {version: 2, ethernets: {eth0: {mtu: 1500}}}",
+        );
+        write(
+            "lab/50-lab.yaml",
+            "network: {version: 2, ethernets: {eth1: {dhcp4: true}}}",
+        );
+        write(
+            "prod/50-prod.yaml",
+            "network: {version: 2, ethernets: {eth0: {mtu: 9000}}}",
+        );
+        write(
+            "dr/50-dr.yaml",
+            "network: {version: 2, ethernets: {eth0: {dhcp4: false, addresses: [192.0.2.10/24]}}}",
+        );
+
+        let profiles = ConfigProfiles::load_dir(dir.path()).unwrap();
+        assert_eq!(
+            profiles.profiles().collect::<Vec<_>>(),
+            ["dr", "lab", "prod"]
+        );
+
+        let lab = NetplanConfig::from_yaml(
+            "network: {version: 2, ethernets: {eth0: {dhcp4: true, mtu: 1500}, eth1: {dhcp4: true}}}",
+        )
+        .unwrap();
+        assert!(profiles.resolve("lab").unwrap().semantically_eq(&lab));
+
+        let prod_dr = NetplanConfig::from_yaml(
+            "network: {version: 2, ethernets: {eth0: {mtu: 9000, addresses: [192.0.2.10/24]}}}",
+        )
+        .unwrap();
+        let resolved = profiles.resolve_all(&["prod", "dr"]).unwrap();
+        assert!(resolved.semantically_eq(&prod_dr), "{resolved:#?}");
+
+        assert!(matches!(
+            profiles.resolve("staging"),
+            Err(ProfileError::Unknown(profile)) if profile == "staging"
+        ));
+
+        write("prod/60-broken.yaml", "network: [");
+        assert!(matches!(
+            ConfigProfiles::load_dir(dir.path()),
+            Err(ProfileError::Load(path, _)) if path.ends_with("prod/60-broken.yaml")
+        ));
+    }
+}
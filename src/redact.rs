@@ -0,0 +1,220 @@
+//! Removing passwords and keys from configurations, so they can be shared.
+
+use crate::{
+    AnyDeviceConfigMut, AuthConfig, CommonPropertiesAllDevices, NetplanConfig,
+    NetworkManagerSettings, SecretString, TunnelKey, TunnelMode,
+};
+use std::collections::HashMap;
+
+/// The value passwords and keys are replaced with by [NetplanConfig::redact].
+pub const REDACTED: &str = "[REDACTED]";
+
+impl NetplanConfig {
+    /// Replace every password, PIN and key in the configuration with [REDACTED], e.g. to
+    /// attach the configuration to a bug report:
+    ///
+    /// - the passwords of access points, and of their 802.1X authentication,
+    /// - the password and PIN of modems,
+    /// - the private keys of WireGuard tunnels and the preshared keys of their peers,
+    ///   unless they are the path of a file containing the key,
+    /// - NetworkManager passthrough settings holding secrets, like `wifi-security.psk` or
+    ///   `vpn.secrets.password`.
+    ///
+    /// The keys of other tunnel modes, like GRE, are identifiers rather than secrets and are
+    /// left as they are.
+    ///
+    /// ```
+    /// use netplan_types::{AccessPointConfig, NetplanConfig, REDACTED};
+    ///
+    /// let mut config = NetplanConfig::build(|net| {
+    ///     net.wifi("wlan0", |w| w.dhcp4(true).access_point("home", AccessPointConfig::wpa2("hunter22")));
+    /// });
+    /// config.redact();
+    ///
+    /// let wifis = config.network.wifis.unwrap();
+    /// let auth = wifis["wlan0"].access_points.as_ref().unwrap()["home"].auth.clone().unwrap();
+    /// assert_eq!(auth.password.as_ref().map(|p| p.as_str()), Some(REDACTED));
+    /// ```
+    pub fn redact(&mut self) {
+        let network = &mut self.network;
+        for (_, _, device) in network.devices_mut() {
+            if let Some(common) = common_all(device) {
+                if let Some(networkmanager) = common.networkmanager.as_mut() {
+                    redact_passthrough(networkmanager);
+                }
+            }
+        }
+
+        for wifi in network.wifis.iter_mut().flat_map(HashMap::values_mut) {
+            for access_point in wifi.access_points.iter_mut().flat_map(HashMap::values_mut) {
+                redact_secret(&mut access_point.password);
+                if let Some(auth) = access_point.auth.as_mut() {
+                    redact_auth(auth);
+                }
+                if let Some(networkmanager) = access_point.networkmanager.as_mut() {
+                    redact_passthrough(networkmanager);
+                }
+            }
+        }
+
+        for modem in network.modems.iter_mut().flat_map(HashMap::values_mut) {
+            redact_secret(&mut modem.password);
+            redact_secret(&mut modem.pin);
+        }
+
+        for tunnel in network.tunnels.iter_mut().flat_map(HashMap::values_mut) {
+            if tunnel.mode != Some(TunnelMode::Wireguard) {
+                continue;
+            }
+
+            match &mut tunnel.key {
+                Some(TunnelKey::Simple(key)) => redact_key(key),
+                Some(TunnelKey::Complex {
+                    private: Some(key), ..
+                }) => redact_key(key),
+                _ => {}
+            }
+
+            for peer in &mut tunnel.peers {
+                if let Some(shared) = peer.keys.as_mut().and_then(|keys| keys.shared.as_mut()) {
+                    redact_key(shared);
+                }
+            }
+        }
+    }
+
+    /// A copy of the configuration without passwords and keys, see [NetplanConfig::redact].
+    pub fn redacted(&self) -> Self {
+        let mut config = self.clone();
+        config.redact();
+        config
+    }
+}
+
+fn common_all(device: AnyDeviceConfigMut<'_>) -> Option<&mut CommonPropertiesAllDevices> {
+    match device {
+        AnyDeviceConfigMut::Ethernet(d) => d.common_all.as_deref_mut(),
+        AnyDeviceConfigMut::Wifi(d) => d.common_all.as_deref_mut(),
+        AnyDeviceConfigMut::Modem(d) => d.common_all.as_deref_mut(),
+        AnyDeviceConfigMut::Bond(d) => d.common_all.as_deref_mut(),
+        AnyDeviceConfigMut::Bridge(d) => d.common_all.as_deref_mut(),
+        AnyDeviceConfigMut::Vlan(d) => d.common_all.as_deref_mut(),
+        AnyDeviceConfigMut::Tunnel(d) => d.common_all.as_deref_mut(),
+        AnyDeviceConfigMut::Vrf(d) => d.common_all.as_deref_mut(),
+        AnyDeviceConfigMut::DummyDevice(d) => d.common_all.as_deref_mut(),
+        AnyDeviceConfigMut::NmDevice(d) => d.common_all.as_deref_mut(),
+    }
+}
+
+fn redact_auth(auth: &mut AuthConfig) {
+    redact_secret(&mut auth.password);
+    redact_secret(&mut auth.client_key_password);
+}
+
+fn redact_secret(secret: &mut Option<SecretString>) {
+    if let Some(secret) = secret.as_mut() {
+        *secret = REDACTED.into();
+    }
+}
+
+/// Redact a WireGuard key, unless it is the path of the file containing it.
+fn redact_key(key: &mut SecretString) {
+    if !key.starts_with('/') {
+        *key = REDACTED.into();
+    }
+}
+
+fn redact_passthrough(networkmanager: &mut NetworkManagerSettings) {
+    for (key, value) in networkmanager.passthrough.iter_mut().flatten() {
+        if is_secret_setting(key) {
+            *value = REDACTED.to_string();
+        }
+    }
+}
+
+/// Whether a NetworkManager keyfile setting, like `wifi-security.psk`, holds a secret.
+/// The `-flags` and `-type` settings accompanying secrets only describe them.
+fn is_secret_setting(key: &str) -> bool {
+    let name = key.rsplit('.').next().unwrap_or(key);
+    if name.ends_with("-flags") || name.ends_with("-type") {
+        return false;
+    }
+
+    key.starts_with("vpn.secrets.")
+        || name == "private-key"
+        || name.starts_with("wep-key")
+        || name
+            .split('-')
+            .any(|word| matches!(word, "psk" | "password" | "pin" | "secret" | "secrets"))
+}
+
+#[cfg(all(test, feature = "yaml"))]
+mod test {
+    use crate::{NetplanConfig, REDACTED};
+
+    #[test]
+    fn redact_secrets() {
+        let config = NetplanConfig::from_yaml(
+            r#"
+network:
+  version: 2
+  wifis:
+    wlan0:
+      access-points:
+        home:
+          password: hunter22
+        corp:
+          auth:
+            key-management: eap
+            identity: alice
+            password: hunter22
+        cafe:
+          networkmanager:
+            passthrough:
+              wifi-security.psk: hunter22
+              wifi-security.psk-flags: "0"
+              wifi.powersave: "2"
+  modems:
+    wwan0:
+      apn: internet
+      pin: "1234"
+  tunnels:
+    wg0:
+      mode: wireguard
+      key: /etc/wireguard/wg0.key
+      peers:
+        - endpoint: 192.0.2.1:51820
+          keys:
+            public: rlbInAj0qV69CysWPQY7KEBnKxpYCpaWqOs/dLevdWc=
+            shared: 4a6cJ5aLc7WL3kEMcUDwYqOjKUnVL2ixgQcJPHPO0Kc=
+    gre0:
+      mode: gre
+      local: 192.0.2.1
+      remote: 192.0.2.2
+      key: "5678"
+"#,
+        )
+        .unwrap();
+
+        let redacted = config.redacted().to_yaml();
+        for secret in [
+            "hunter22",
+            "1234",
+            "4a6cJ5aLc7WL3kEMcUDwYqOjKUnVL2ixgQcJPHPO0Kc=",
+        ] {
+            assert!(!redacted.contains(secret), "{redacted}");
+        }
+        for kept in [
+            "identity: alice",
+            "apn: internet",
+            "wifi-security.psk-flags: '0'",
+            "wifi.powersave: '2'",
+            "/etc/wireguard/wg0.key",
+            "rlbInAj0qV69CysWPQY7KEBnKxpYCpaWqOs/dLevdWc=",
+            "key: '5678'",
+        ] {
+            assert!(redacted.contains(kept), "{kept}: {redacted}");
+        }
+        assert_eq!(redacted.matches(REDACTED).count(), 5, "{redacted}");
+    }
+}
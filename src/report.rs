@@ -0,0 +1,468 @@
+//! Human readable reports of a configuration, in Markdown or HTML, e.g. for change reviews
+//! and compliance evidence.
+
+use crate::{
+    AddressMapping, AnyDeviceConfig, DependencyKind, NetplanConfig, Severity, ValidationReport,
+};
+
+/// A report of a configuration, with a section per device listing its settings, addresses,
+/// routes, DNS settings and memberships, and optionally the issues found validating it.
+/// Created by [NetplanConfig::report].
+///
+/// Passwords and keys are never included.
+#[derive(Debug, Clone, Copy)]
+pub struct ConfigReport<'a> {
+    config: &'a NetplanConfig,
+    validation: Option<&'a ValidationReport>,
+    title: &'a str,
+}
+
+impl NetplanConfig {
+    /// A report of the configuration, to render as Markdown or HTML.
+    ///
+    /// ```
+    /// use netplan_types::NetplanConfig;
+    ///
+    /// let config = NetplanConfig::build(|net| {
+    ///     net.ethernet("eth0", |e| e.address("192.0.2.10/24").route("default", "192.0.2.1"));
+    /// });
+    /// let validation = config.validate();
+    ///
+    /// let markdown = config.report().title("CHG-1234").validation(&validation).to_markdown();
+    /// assert!(markdown.starts_with("# CHG-1234\n"));
+    /// assert!(markdown.contains("| 192.0.2.10/24 |"));
+    /// assert!(markdown.contains("| default | 192.0.2.1 |"));
+    ///
+    /// let html = config.report().to_html();
+    /// assert!(html.contains("<td>192.0.2.10/24</td>"));
+    /// ```
+    pub fn report(&self) -> ConfigReport<'_> {
+        ConfigReport {
+            config: self,
+            validation: None,
+            title: "Network configuration",
+        }
+    }
+}
+
+impl<'a> ConfigReport<'a> {
+    /// Include the issues found validating the configuration.
+    pub fn validation(mut self, validation: &'a ValidationReport) -> Self {
+        self.validation = Some(validation);
+        self
+    }
+
+    /// The title of the report, `Network configuration` by default.
+    pub fn title(mut self, title: &'a str) -> Self {
+        self.title = title;
+        self
+    }
+
+    /// Render the report as GitHub flavored Markdown.
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = String::new();
+        for block in self.blocks() {
+            if !markdown.is_empty() {
+                markdown.push('\n');
+            }
+
+            match block {
+                Block::Heading(level, text) => {
+                    let hashes = "#".repeat(level);
+                    markdown.push_str(&format!("{hashes} {}\n", escape_markdown(&text)));
+                }
+                Block::Paragraph(text) => {
+                    markdown.push_str(&format!("{}\n", escape_markdown(&text)));
+                }
+                Block::Table(header, rows) => {
+                    let row = |cells: Vec<String>| {
+                        let cells = cells.iter().map(|c| escape_markdown(c)).collect::<Vec<_>>();
+                        format!("| {} |\n", cells.join(" | "))
+                    };
+                    markdown.push_str(&row(header.iter().map(|h| h.to_string()).collect()));
+                    markdown.push_str(&row(header.iter().map(|_| "---".to_string()).collect()));
+                    for cells in rows {
+                        markdown.push_str(&row(cells));
+                    }
+                }
+            }
+        }
+
+        markdown
+    }
+
+    /// Render the report as a standalone HTML document, without styles.
+    pub fn to_html(&self) -> String {
+        let mut html = String::from("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+        html.push_str(&format!("<title>{}</title>\n", escape_html(self.title)));
+        html.push_str("</head>\n<body>\n");
+
+        for block in self.blocks() {
+            match block {
+                Block::Heading(level, text) => {
+                    html.push_str(&format!("<h{level}>{}</h{level}>\n", escape_html(&text)));
+                }
+                Block::Paragraph(text) => {
+                    html.push_str(&format!("<p>{}</p>\n", escape_html(&text)));
+                }
+                Block::Table(header, rows) => {
+                    html.push_str("<table>\n<thead>\n<tr>");
+                    for cell in header {
+                        html.push_str(&format!("<th>{}</th>", escape_html(cell)));
+                    }
+                    html.push_str("</tr>\n</thead>\n<tbody>\n");
+                    for cells in rows {
+                        html.push_str("<tr>");
+                        for cell in cells {
+                            html.push_str(&format!("<td>{}</td>", escape_html(&cell)));
+                        }
+                        html.push_str("</tr>\n");
+                    }
+                    html.push_str("</tbody>\n</table>\n");
+                }
+            }
+        }
+
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+
+    /// The contents of the report, independent of the format.
+    fn blocks(&self) -> Vec<Block> {
+        let network = &self.config.network;
+        let mut devices = network.devices().collect::<Vec<_>>();
+        devices.sort_by_key(|(kind, name, _)| (*kind, *name));
+
+        let renderer = network
+            .renderer
+            .as_ref()
+            .map_or("networkd (default)", |renderer| renderer.as_str());
+        let mut blocks = vec![
+            Block::Heading(1, self.title.to_string()),
+            Block::Paragraph(format!(
+                "Version {}, renderer {renderer}, {} device(s).",
+                network.version,
+                devices.len()
+            )),
+        ];
+
+        if let Some(validation) = self.validation {
+            blocks.push(Block::Heading(2, "Validation".to_string()));
+            if validation.is_empty() {
+                blocks.push(Block::Paragraph("No issues found.".to_string()));
+            } else {
+                let rows = validation
+                    .issues
+                    .iter()
+                    .map(|issue| {
+                        let severity = match issue.severity {
+                            Severity::Error => "error",
+                            Severity::Warning => "warning",
+                        };
+                        vec![
+                            severity.to_string(),
+                            issue.rule.clone(),
+                            issue.path.clone(),
+                            issue.message.clone(),
+                            issue.suggestion.clone().unwrap_or_default(),
+                        ]
+                    })
+                    .collect();
+                blocks.push(Block::Table(
+                    &["Severity", "Rule", "Path", "Message", "Suggestion"],
+                    rows,
+                ));
+            }
+        }
+
+        let graph = network.dependency_graph();
+        for (kind, name, device) in devices {
+            blocks.push(Block::Heading(2, format!("{name} ({kind})")));
+
+            let mut settings = settings(device);
+            let member_of = graph
+                .dependents(name)
+                .filter(|dependent| {
+                    graph
+                        .edges()
+                        .any(|edge| edge == (dependent, name, DependencyKind::Member))
+                })
+                .collect::<Vec<_>>();
+            if !member_of.is_empty() {
+                settings.push(vec!["Member of".to_string(), member_of.join(", ")]);
+            }
+            if !settings.is_empty() {
+                blocks.push(Block::Table(&["Setting", "Value"], settings));
+            }
+
+            let Some(common) = device.common_all() else {
+                continue;
+            };
+
+            let addresses = common
+                .addresses
+                .iter()
+                .flatten()
+                .flat_map(|address| match address {
+                    AddressMapping::Simple(address) => {
+                        vec![vec![address.clone(), String::new(), String::new()]]
+                    }
+                    AddressMapping::Complex(addresses) => {
+                        let mut rows = addresses
+                            .iter()
+                            .map(|(address, properties)| {
+                                vec![
+                                    address.clone(),
+                                    properties.label.clone().unwrap_or_default(),
+                                    properties
+                                        .lifetime
+                                        .as_ref()
+                                        .map(|lifetime| lifetime.as_str().to_string())
+                                        .unwrap_or_default(),
+                                ]
+                            })
+                            .collect::<Vec<_>>();
+                        rows.sort();
+                        rows
+                    }
+                })
+                .collect::<Vec<_>>();
+            if !addresses.is_empty() {
+                blocks.push(Block::Heading(3, "Addresses".to_string()));
+                blocks.push(Block::Table(&["Address", "Label", "Lifetime"], addresses));
+            }
+
+            let gateways = [&common.gateway4, &common.gateway6]
+                .into_iter()
+                .flatten()
+                .map(|gateway| vec!["default".to_string(), gateway.clone()]);
+            let routes = common.routes.iter().flatten().map(|route| {
+                let field = |value: Option<String>| value.unwrap_or_default();
+                vec![
+                    field(route.to.clone()),
+                    field(route.via.clone()),
+                    field(route.metric.map(|metric| metric.to_string())),
+                    field(route.table.map(|table| table.to_string())),
+                ]
+            });
+            let routes = gateways
+                .map(|mut row| {
+                    row.extend([String::new(), String::new()]);
+                    row
+                })
+                .chain(routes)
+                .collect::<Vec<_>>();
+            if !routes.is_empty() {
+                blocks.push(Block::Heading(3, "Routes".to_string()));
+                blocks.push(Block::Table(&["To", "Via", "Metric", "Table"], routes));
+            }
+
+            if let Some(nameservers) = &common.nameservers {
+                let mut dns = Vec::new();
+                if let Some(addresses) = &nameservers.addresses {
+                    dns.push(vec!["Nameservers".to_string(), addresses.join(", ")]);
+                }
+                if let Some(search) = &nameservers.search {
+                    dns.push(vec!["Search domains".to_string(), search.join(", ")]);
+                }
+                if !dns.is_empty() {
+                    blocks.push(Block::Heading(3, "DNS".to_string()));
+                    blocks.push(Block::Table(&["Setting", "Value"], dns));
+                }
+            }
+        }
+
+        blocks
+    }
+}
+
+/// A part of a report.
+enum Block {
+    Heading(usize, String),
+    Paragraph(String),
+    /// A table, with its header and rows.
+    Table(&'static [&'static str], Vec<Vec<String>>),
+}
+
+/// The general settings of a device, as rows of a table.
+fn settings(device: AnyDeviceConfig) -> Vec<Vec<String>> {
+    let mut settings = Vec::new();
+    let mut add = |setting: &str, value: String| settings.push(vec![setting.to_string(), value]);
+
+    match device {
+        AnyDeviceConfig::Bond(bond) => {
+            if let Some(interfaces) = &bond.interfaces {
+                add("Members", interfaces.join(", "));
+            }
+            if let Some(mode) = bond.parameters.as_ref().and_then(|p| p.mode.as_ref()) {
+                add("Mode", mode.as_str().to_string());
+            }
+        }
+        AnyDeviceConfig::Bridge(bridge) => {
+            if let Some(interfaces) = &bridge.interfaces {
+                add("Members", interfaces.join(", "));
+            }
+        }
+        AnyDeviceConfig::Vrf(vrf) => {
+            add("Table", vrf.table.to_string());
+            add("Members", vrf.interfaces.join(", "));
+        }
+        AnyDeviceConfig::Vlan(vlan) => {
+            if let Some(id) = vlan.id {
+                add("VLAN ID", id.to_string());
+            }
+            if let Some(link) = &vlan.link {
+                add("Link", link.clone());
+            }
+        }
+        AnyDeviceConfig::Ethernet(ethernet) => {
+            if let Some(link) = &ethernet.link {
+                add("Link", link.clone());
+            }
+        }
+        AnyDeviceConfig::Tunnel(tunnel) => {
+            if let Some(mode) = &tunnel.mode {
+                add("Mode", mode.as_str().to_string());
+            }
+            if let Some(local) = &tunnel.local {
+                add("Local", local.clone());
+            }
+            if let Some(remote) = &tunnel.remote {
+                add("Remote", remote.clone());
+            }
+        }
+        _ => {}
+    }
+
+    if let Some(physical) = device.common_physical() {
+        if let Some(rules) = &physical.r#match {
+            let mut parts = Vec::new();
+            if let Some(name) = &rules.name {
+                parts.push(format!("name {name}"));
+            }
+            if let Some(macaddress) = &rules.macaddress {
+                parts.push(format!("MAC address {macaddress}"));
+            }
+            if let Some(drivers) = &rules.driver {
+                parts.push(format!("driver {}", drivers.join(" ")));
+            }
+            add("Match", parts.join(", "));
+        }
+        if let Some(set_name) = &physical.set_name {
+            add("Set name", set_name.clone());
+        }
+    }
+
+    if let Some(common) = device.common_all() {
+        if let Some(renderer) = &common.renderer {
+            add("Renderer", renderer.as_str().to_string());
+        }
+        if common.dhcp4 == Some(true) {
+            add("DHCPv4", "enabled".to_string());
+        }
+        if common.dhcp6 == Some(true) {
+            add("DHCPv6", "enabled".to_string());
+        }
+        if let Some(mtu) = common.mtu {
+            add("MTU", mtu.to_string());
+        }
+        if let Some(macaddress) = &common.macaddress {
+            add("MAC address", macaddress.to_string());
+        }
+        if common.optional == Some(true) {
+            add("Optional", "yes".to_string());
+        }
+    }
+
+    settings
+}
+
+fn escape_markdown(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('|', "\\|")
+        .replace('\n', "<br>")
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod test {
+    use crate::NetplanConfig;
+
+    #[test]
+    fn render_reports() {
+        let config = NetplanConfig::build(|net| {
+            net.ethernet("eth0", |e| e.mtu(9000))
+                .ethernet("eth1", |e| e)
+                .bond("bond0", |b| {
+                    b.interfaces(["eth0", "eth1"])
+                        .address("192.0.2.10/24")
+                        .route("default", "192.0.2.1")
+                        .nameservers(["192.0.2.53"])
+                        .search(["example.com"])
+                });
+        });
+        let validation = config.validate();
+
+        let markdown = config.report().validation(&validation).to_markdown();
+        let expected = "\
+# Network configuration
+
+Version 2, renderer networkd (default), 3 device(s).
+
+## Validation
+
+No issues found.
+
+## eth0 (ethernets)
+
+| Setting | Value |
+| --- | --- |
+| MTU | 9000 |
+| Member of | bond0 |
+
+## eth1 (ethernets)
+
+| Setting | Value |
+| --- | --- |
+| Member of | bond0 |
+
+## bond0 (bonds)
+
+| Setting | Value |
+| --- | --- |
+| Members | eth0, eth1 |
+
+### Addresses
+
+| Address | Label | Lifetime |
+| --- | --- | --- |
+| 192.0.2.10/24 |  |  |
+
+### Routes
+
+| To | Via | Metric | Table |
+| --- | --- | --- | --- |
+| default | 192.0.2.1 |  |  |
+
+### DNS
+
+| Setting | Value |
+| --- | --- |
+| Nameservers | 192.0.2.53 |
+| Search domains | example.com |
+";
+        assert_eq!(markdown, expected);
+
+        let html = config.report().title("<CHG-1> & co").to_html();
+        assert!(html.contains("<title>&lt;CHG-1&gt; &amp; co</title>"));
+        assert!(html.contains("<h2>bond0 (bonds)</h2>"));
+        assert!(html.contains("<tr><td>Members</td><td>eth0, eth1</td></tr>"));
+        assert!(!html.contains("Validation"));
+    }
+}
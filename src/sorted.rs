@@ -0,0 +1,23 @@
+//! Serializing mappings ordered by their keys.
+//!
+//! Device definitions and access points are stored in a `HashMap`, of which the order differs
+//! between runs. Serializing them ordered by their ID makes the YAML written for the same
+//! configuration identical every time.
+
+use serde::{Serialize, Serializer};
+use std::collections::HashMap;
+
+/// Serialize an optional `HashMap` with its entries ordered by key
+pub fn sorted_option<S: Serializer, V: Serialize>(
+    map: &Option<HashMap<String, V>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match map {
+        Some(map) => {
+            let mut entries = map.iter().collect::<Vec<_>>();
+            entries.sort_unstable_by_key(|(key, _)| *key);
+            serializer.collect_map(entries)
+        }
+        None => serializer.serialize_none(),
+    }
+}
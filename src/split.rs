@@ -0,0 +1,149 @@
+//! Splitting configurations into multiple files, and reassembling them.
+
+use crate::{AnyDeviceConfig, DeviceKind, NetplanConfig, NetplanFile, NetworkConfig};
+use std::collections::BTreeMap;
+
+/// The priority of the file holding the global settings, see [NetplanConfig::split_by].
+const GLOBAL_PRIORITY: u8 = 0;
+
+impl DeviceKind {
+    /// The priority of the file holding devices of this kind, see [NetplanConfig::split_by_kind].
+    /// Devices are ordered before the devices referencing them, e.g. ethernets before bonds
+    /// and bonds before VLANs.
+    fn split_priority(&self) -> u8 {
+        match self {
+            Self::Ethernet => 10,
+            Self::Wifi => 20,
+            Self::Modem => 30,
+            Self::Bond => 40,
+            Self::Bridge => 50,
+            Self::Vlan => 60,
+            Self::Tunnel => 70,
+            Self::Vrf => 80,
+            Self::DummyDevice => 90,
+            Self::NmDevice => 100,
+        }
+    }
+}
+
+impl NetplanConfig {
+    /// Split the configuration into one file per device kind, named after the section of
+    /// the kind, e.g. `10-ethernets.yaml`, `40-bonds.yaml` and `60-vlans.yaml`.
+    /// See [NetplanConfig::split_by].
+    ///
+    /// ```
+    /// use netplan_types::{NetplanConfig, NetplanFile};
+    ///
+    /// let config = NetplanConfig::from_yaml(
+    ///     "network: {version: 2, ethernets: {eth0: {}}, vlans: {vlan10: {id: 10, link: eth0}}}",
+    /// ).unwrap();
+    ///
+    /// let files = config.split_by_kind();
+    /// let names = files.iter().map(NetplanFile::file_name).collect::<Vec<_>>();
+    /// assert_eq!(names, ["10-ethernets.yaml", "60-vlans.yaml"]);
+    /// assert_eq!(NetplanConfig::from_files(&files), config);
+    /// ```
+    pub fn split_by_kind(&self) -> Vec<NetplanFile> {
+        self.split_by(|kind, _, _| (kind.split_priority(), kind.section().to_string()))
+    }
+
+    /// Split the configuration into multiple files. `group` returns the priority and name
+    /// of the file each device is written to, devices for which it returns the same file
+    /// end up in the same file.
+    ///
+    /// The global settings, the renderer and openvswitch settings, are written to
+    /// `00-global.yaml`, which is left out if neither is set. Every file has the version of
+    /// the configuration. The files are returned in the order netplan merges them in.
+    pub fn split_by<F>(&self, mut group: F) -> Vec<NetplanFile>
+    where
+        F: FnMut(DeviceKind, &str, AnyDeviceConfig<'_>) -> (u8, String),
+    {
+        let empty = || NetworkConfig {
+            version: self.network.version,
+            ..NetworkConfig::default()
+        };
+
+        let mut files = BTreeMap::<(u8, String), NetworkConfig>::new();
+        if self.network.renderer.is_some() || self.network.openvswitch.is_some() {
+            files.insert(
+                (GLOBAL_PRIORITY, "global".to_string()),
+                NetworkConfig {
+                    renderer: self.network.renderer.clone(),
+                    openvswitch: self.network.openvswitch.clone(),
+                    ..empty()
+                },
+            );
+        }
+
+        for (kind, name, device) in self.network.devices() {
+            files
+                .entry(group(kind, name, device))
+                .or_insert_with(empty)
+                .add_device(name, device);
+        }
+
+        let mut files = files
+            .into_iter()
+            .map(|((priority, name), network)| {
+                NetplanFile::new(priority, name, NetplanConfig { network })
+            })
+            .collect::<Vec<_>>();
+        files.sort_by(NetplanFile::cmp_precedence);
+        files
+    }
+
+    /// Reassemble a configuration from files, merging them in the order netplan merges
+    /// them in regardless of the order they are given in. See [NetplanConfig::merge].
+    pub fn from_files<'a>(files: impl IntoIterator<Item = &'a NetplanFile>) -> Self {
+        let mut files = files.into_iter().collect::<Vec<_>>();
+        files.sort_by(|a, b| a.cmp_precedence(b));
+
+        let mut config = NetplanConfig::default();
+        for file in files {
+            config.merge(file.config.clone());
+        }
+
+        config
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{NetplanConfig, NetplanFile};
+
+    #[test]
+    fn split_and_reassemble() {
+        let config = NetplanConfig::from_yaml(
+            r#"
+network:
+  version: 2
+  renderer: networkd
+  ethernets:
+    eth0: {}
+    eth1: {}
+  bonds:
+    bond0:
+      interfaces: [eth0, eth1]
+  vlans:
+    vlan10: {id: 10, link: bond0}
+    vlan20: {id: 20, link: bond0}
+"#,
+        )
+        .unwrap();
+
+        let files = config.split_by(|_, name, _| {
+            if name.starts_with("vlan") {
+                (20, "vlans".to_string())
+            } else {
+                (10, "bond".to_string())
+            }
+        });
+        let names = files.iter().map(NetplanFile::file_name).collect::<Vec<_>>();
+        assert_eq!(names, ["00-global.yaml", "10-bond.yaml", "20-vlans.yaml"]);
+        assert_eq!(files[2].config.network.vlans.as_ref().unwrap().len(), 2);
+        assert!(files[1].config.network.renderer.is_none());
+
+        let reversed = files.iter().rev();
+        assert_eq!(NetplanConfig::from_files(reversed), config);
+    }
+}
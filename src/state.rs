@@ -0,0 +1,355 @@
+//! Snapshots of the live network state, and their netplan representation.
+
+use crate::{CommonProperties, NetplanConfig, RoutingConfig};
+use std::fmt::{Display, Formatter};
+use std::net::IpAddr;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The main routing table, which netplan uses for routes without a table.
+pub const MAIN_TABLE: u32 = 254;
+
+/// The links and routes of a system at one point in time, e.g. read from the kernel with
+/// `SystemState::read` (`system` feature).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SystemState {
+    pub links: Vec<LinkState>,
+    pub routes: Vec<RouteState>,
+}
+
+/// A network interface.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LinkState {
+    pub index: u32,
+    pub name: String,
+    /// The kind of virtual link, e.g. `bond`, `bridge` or `vlan`. `None` for physical links.
+    pub kind: Option<String>,
+    pub up: bool,
+    pub loopback: bool,
+    pub mtu: Option<u32>,
+    pub macaddress: Option<String>,
+    /// The index of the bond, bridge or VRF the link is a member of.
+    pub controller: Option<u32>,
+    /// The index of the underlying link, e.g. of a VLAN.
+    pub parent: Option<u32>,
+    pub vlan_id: Option<u16>,
+    pub vrf_table: Option<u32>,
+    pub addresses: Vec<AddressState>,
+}
+
+/// An address assigned to a link.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AddressState {
+    pub address: IpAddr,
+    pub prefix: u8,
+    /// Whether the address was assigned dynamically, e.g. by DHCP or SLAAC, rather than
+    /// configured statically.
+    pub dynamic: bool,
+}
+
+impl AddressState {
+    /// Whether this is an IPv6 link-local address, which every IPv6 enabled link has.
+    pub fn is_link_local(&self) -> bool {
+        match self.address {
+            IpAddr::V4(_) => false,
+            IpAddr::V6(address) => address.segments()[0] & 0xffc0 == 0xfe80,
+        }
+    }
+}
+
+impl Display for AddressState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.address, self.prefix)
+    }
+}
+
+/// Where a route came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum RouteOrigin {
+    /// Added by the kernel, e.g. the prefix route of an address.
+    Kernel,
+    /// Configured statically.
+    Static,
+    /// Learned dynamically, e.g. by DHCP, router advertisements or ICMP redirects.
+    Dynamic,
+    /// Added by another daemon, e.g. a routing protocol.
+    Other,
+}
+
+/// A unicast route.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RouteState {
+    /// The destination network, `None` for a default route.
+    pub to: Option<IpAddr>,
+    pub prefix: u8,
+    pub via: Option<IpAddr>,
+    /// The index of the outgoing link.
+    pub link: Option<u32>,
+    pub metric: Option<u32>,
+    pub table: u32,
+    pub origin: RouteOrigin,
+}
+
+impl RouteState {
+    /// The destination in netplan notation, e.g. `10.0.0.0/24`, or `default`.
+    pub fn destination(&self) -> String {
+        match self.to {
+            Some(to) => format!("{to}/{}", self.prefix),
+            None => "default".to_string(),
+        }
+    }
+
+    /// The route in netplan's representation.
+    pub fn to_routing_config(&self) -> RoutingConfig {
+        RoutingConfig {
+            to: Some(self.destination()),
+            via: self.via.map(|via| via.to_string()),
+            metric: self.metric.and_then(|metric| metric.try_into().ok()),
+            table: (self.table != MAIN_TABLE)
+                .then(|| self.table.try_into().ok())
+                .flatten(),
+            ..Default::default()
+        }
+    }
+}
+
+impl SystemState {
+    /// The link with the given index.
+    pub fn link(&self, index: u32) -> Option<&LinkState> {
+        self.links.iter().find(|link| link.index == index)
+    }
+
+    /// The link with the given name.
+    pub fn link_by_name(&self, name: &str) -> Option<&LinkState> {
+        self.links.iter().find(|link| link.name == name)
+    }
+
+    /// The names of the links which are members of the given link, sorted by name.
+    pub fn members(&self, index: u32) -> Vec<&str> {
+        let mut members = self
+            .links
+            .iter()
+            .filter(|link| link.controller == Some(index))
+            .map(|link| link.name.as_str())
+            .collect::<Vec<_>>();
+        members.sort_unstable();
+        members
+    }
+
+    /// The statically configured routes over the given link.
+    pub fn static_routes(&self, index: u32) -> impl Iterator<Item = &RouteState> {
+        self.routes
+            .iter()
+            .filter(move |route| route.link == Some(index) && route.origin == RouteOrigin::Static)
+    }
+}
+
+impl NetplanConfig {
+    /// A best-effort netplan representation of a snapshot of the network state.
+    ///
+    /// Physical links become ethernets, and bonds, bridges, VLANs, VRFs and dummy devices
+    /// become their netplan counterparts, with their members and underlying links. Other
+    /// kinds of virtual links, like tunnels, are left out, as their settings are not part
+    /// of the snapshot. Loopback is left out as well.
+    ///
+    /// Static addresses and routes are carried over, as are MTUs other than 1500. Links with
+    /// dynamic addresses get DHCP enabled for the address family instead. IPv6 link-local
+    /// addresses are left out, as are routes added by the kernel or learned dynamically.
+    ///
+    /// ```
+    /// use netplan_types::{AddressState, LinkState, NetplanConfig, SystemState};
+    ///
+    /// let state = SystemState {
+    ///     links: vec![LinkState {
+    ///         index: 2,
+    ///         name: "eth0".into(),
+    ///         up: true,
+    ///         addresses: vec![AddressState { address: "10.0.0.2".parse().unwrap(), prefix: 24, dynamic: true }],
+    ///         ..Default::default()
+    ///     }],
+    ///     routes: vec![],
+    /// };
+    ///
+    /// assert_eq!(NetplanConfig::from_state(&state), NetplanConfig::single_nic_dhcp("eth0"));
+    /// ```
+    pub fn from_state(state: &SystemState) -> Self {
+        Self::build(|net| {
+            for link in state.links.iter().filter(|link| !link.loopback) {
+                let name = link.name.as_str();
+                match link.kind.as_deref() {
+                    None => {
+                        net.ethernet(name, |e| e.with(|c| apply_link(c, link, state)));
+                    }
+                    Some("bond") => {
+                        net.bond(name, |b| {
+                            b.interfaces(state.members(link.index))
+                                .with(|c| apply_link(c, link, state))
+                        });
+                    }
+                    Some("bridge") => {
+                        net.bridge(name, |b| {
+                            b.interfaces(state.members(link.index))
+                                .with(|c| apply_link(c, link, state))
+                        });
+                    }
+                    Some("vlan") => {
+                        let parent = link.parent.and_then(|parent| state.link(parent));
+                        net.vlan(name, |v| {
+                            let v = match link.vlan_id {
+                                Some(id) => v.id(id),
+                                None => v,
+                            };
+                            let v = match parent {
+                                Some(parent) => v.link(&parent.name),
+                                None => v,
+                            };
+                            v.with(|c| apply_link(c, link, state))
+                        });
+                    }
+                    Some("vrf") => {
+                        net.vrf(name, |v| {
+                            let table = link.vrf_table.and_then(|table| table.try_into().ok());
+                            v.table(table.unwrap_or_default())
+                                .interfaces(state.members(link.index))
+                                .with(|c| apply_link(c, link, state))
+                        });
+                    }
+                    Some("dummy") => {
+                        net.dummy_device(name, |d| d.with(|c| apply_link(c, link, state)));
+                    }
+                    Some(_) => {}
+                }
+            }
+        })
+    }
+}
+
+/// Carry the addresses, routes and MTU of `link` over to its definition.
+fn apply_link(config: &mut impl CommonProperties, link: &LinkState, state: &SystemState) {
+    let addresses = link
+        .addresses
+        .iter()
+        .filter(|address| !address.is_link_local());
+    for address in addresses {
+        if address.dynamic {
+            match address.address {
+                IpAddr::V4(_) => config.set_dhcp4(Some(true)),
+                IpAddr::V6(_) => config.set_dhcp6(Some(true)),
+            }
+        } else {
+            config
+                .common_all_mut()
+                .addresses
+                .get_or_insert_with(Vec::new)
+                .push(crate::AddressMapping::Simple(address.to_string()));
+        }
+    }
+
+    for route in state.static_routes(link.index) {
+        config
+            .common_all_mut()
+            .routes
+            .get_or_insert_with(Vec::new)
+            .push(route.to_routing_config());
+    }
+
+    if let Some(mtu) = link.mtu.filter(|mtu| *mtu != 1500) {
+        config.set_mtu(mtu.try_into().ok());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{AddressState, LinkState, NetplanConfig, RouteOrigin, RouteState, SystemState};
+
+    #[test]
+    fn config_from_state() {
+        let link = |index: u32, name: &str, kind: Option<&str>| LinkState {
+            index,
+            name: name.to_string(),
+            kind: kind.map(str::to_string),
+            up: true,
+            mtu: Some(1500),
+            ..Default::default()
+        };
+
+        let state = SystemState {
+            links: vec![
+                LinkState {
+                    loopback: true,
+                    ..link(1, "lo", None)
+                },
+                LinkState {
+                    controller: Some(4),
+                    ..link(2, "eth0", None)
+                },
+                LinkState {
+                    controller: Some(4),
+                    ..link(3, "eth1", None)
+                },
+                LinkState {
+                    mtu: Some(9000),
+                    ..link(4, "bond0", Some("bond"))
+                },
+                LinkState {
+                    parent: Some(4),
+                    vlan_id: Some(10),
+                    addresses: vec![
+                        AddressState {
+                            address: "10.0.10.2".parse().unwrap(),
+                            prefix: 24,
+                            dynamic: false,
+                        },
+                        AddressState {
+                            address: "fe80::1".parse().unwrap(),
+                            prefix: 64,
+                            dynamic: false,
+                        },
+                    ],
+                    ..link(5, "bond0.10", Some("vlan"))
+                },
+                link(6, "veth0", Some("veth")),
+            ],
+            routes: vec![
+                RouteState {
+                    to: None,
+                    prefix: 0,
+                    via: Some("10.0.10.1".parse().unwrap()),
+                    link: Some(5),
+                    metric: None,
+                    table: 254,
+                    origin: RouteOrigin::Static,
+                },
+                RouteState {
+                    to: Some("10.0.10.0".parse().unwrap()),
+                    prefix: 24,
+                    via: None,
+                    link: Some(5),
+                    metric: None,
+                    table: 254,
+                    origin: RouteOrigin::Kernel,
+                },
+            ],
+        };
+
+        let expected = NetplanConfig::build(|net| {
+            net.ethernet("eth0", |e| e);
+            net.ethernet("eth1", |e| e);
+            net.bond("bond0", |b| b.interfaces(["eth0", "eth1"]).mtu(9000));
+            net.vlan("bond0.10", |v| {
+                v.id(10)
+                    .link("bond0")
+                    .address("10.0.10.2/24")
+                    .route("default", "10.0.10.1")
+            });
+        });
+        assert_eq!(NetplanConfig::from_state(&state), expected);
+    }
+}
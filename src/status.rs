@@ -0,0 +1,282 @@
+//! The runtime state reported by `netplan status --format=json`.
+
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// The output of `netplan status --format=json`: the global state of the system and the
+/// state of each interface, by interface name.
+///
+/// ```
+/// use netplan_types::{NetplanStatus, OperState};
+///
+/// let status = NetplanStatus::from_json(r#"{
+///     "netplan-global-state": {"online": true},
+///     "enp5s0": {
+///         "index": 2, "adminstate": "UP", "operstate": "UP", "type": "ethernet",
+///         "backend": "networkd", "id": "eth0",
+///         "addresses": [{"10.0.0.2": {"prefix": 24, "flags": ["dhcp"]}}]
+///     }
+/// }"#).unwrap();
+///
+/// let (name, interface) = status.interface_by_id("eth0").unwrap();
+/// assert_eq!(name, "enp5s0");
+/// assert_eq!(interface.operstate, OperState::Up);
+/// assert_eq!(interface.addresses[0].to_string(), "10.0.0.2/24");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NetplanStatus {
+    #[serde(rename = "netplan-global-state", default)]
+    pub global: GlobalState,
+    #[serde(flatten)]
+    pub interfaces: BTreeMap<String, InterfaceStatus>,
+}
+
+impl NetplanStatus {
+    /// Parse the output of `netplan status --format=json`.
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+
+    /// The interface created for the device with the given netplan ID, and its name.
+    pub fn interface_by_id(&self, id: &str) -> Option<(&str, &InterfaceStatus)> {
+        self.interfaces
+            .iter()
+            .find(|(_, interface)| interface.id.as_deref() == Some(id))
+            .map(|(name, interface)| (name.as_str(), interface))
+    }
+}
+
+/// The state of the system as a whole.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GlobalState {
+    /// Whether the system is online, according to the renderer.
+    #[serde(default)]
+    pub online: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nameservers: Option<NameserverStatus>,
+}
+
+/// The DNS configuration of the system, or of an interface.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NameserverStatus {
+    #[serde(default)]
+    pub addresses: Vec<String>,
+    #[serde(default)]
+    pub search: Vec<String>,
+    /// How DNS is resolved, e.g. `stub` when systemd-resolved is used.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+}
+
+/// The administrative state of an interface, whether it was set up or down.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum AdminState {
+    Up,
+    Down,
+    #[serde(other)]
+    Unknown,
+}
+
+/// The operational state of an interface, as defined by RFC 2863.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum OperState {
+    Up,
+    Down,
+    Dormant,
+    LowerLayerDown,
+    NotPresent,
+    Testing,
+    #[serde(other)]
+    Unknown,
+}
+
+/// The state of a single interface.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InterfaceStatus {
+    pub index: u32,
+    pub adminstate: AdminState,
+    pub operstate: OperState,
+    /// The kind of interface, e.g. `ethernet`, `bond` or `wifi`.
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// The renderer managing the interface, e.g. `networkd` or `NetworkManager`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backend: Option<String>,
+    /// The ID of the netplan device definition the interface belongs to. `None` if the
+    /// interface is not managed by netplan.
+    #[serde(
+        default,
+        deserialize_with = "non_empty",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub macaddress: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vendor: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub activation_mode: Option<String>,
+    #[serde(
+        default,
+        deserialize_with = "address_list",
+        serialize_with = "serialize_address_list"
+    )]
+    pub addresses: Vec<AddressStatus>,
+    #[serde(default)]
+    pub dns_addresses: Vec<String>,
+    #[serde(default)]
+    pub dns_search: Vec<String>,
+    #[serde(default)]
+    pub routes: Vec<RouteStatus>,
+    /// The members of a bond or bridge.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub interfaces: Vec<String>,
+    /// The bond this interface is a member of.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bond: Option<String>,
+    /// The bridge this interface is a member of.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bridge: Option<String>,
+    /// The parent interface, e.g. of a VLAN.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub link: Option<String>,
+}
+
+/// An address assigned to an interface.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AddressStatus {
+    pub address: String,
+    pub prefix: u8,
+    /// How the address was assigned, e.g. `dhcp`, `link` for link-local or `dynamic`.
+    pub flags: Vec<String>,
+}
+
+impl std::fmt::Display for AddressStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.address, self.prefix)
+    }
+}
+
+/// The prefix and flags of an address, as they appear in the output of netplan.
+#[derive(Serialize, Deserialize)]
+struct AddressProperties {
+    prefix: u8,
+    #[serde(default)]
+    flags: Vec<String>,
+}
+
+/// A route installed on an interface.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct RouteStatus {
+    /// The destination, e.g. `default` or `10.0.0.0/24`.
+    pub to: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub via: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+    /// The address family, 2 for IPv4 and 10 for IPv6.
+    pub family: u8,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metric: Option<u32>,
+    /// The route type, e.g. `unicast` or `local`.
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+    /// The origin of the route, e.g. `kernel`, `dhcp` or `static`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub protocol: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub table: Option<RouteTable>,
+}
+
+/// A routing table, by name if it has one, e.g. `main`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum RouteTable {
+    Name(String),
+    Id(u32),
+}
+
+fn non_empty<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+    let value = Option::<String>::deserialize(deserializer)?;
+    Ok(value.filter(|value| !value.is_empty()))
+}
+
+/// Addresses are a list of single entry maps, from the address to its properties.
+fn address_list<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<AddressStatus>, D::Error> {
+    let list = Vec::<HashMap<String, AddressProperties>>::deserialize(deserializer)?;
+    Ok(list
+        .into_iter()
+        .flatten()
+        .map(|(address, properties)| AddressStatus {
+            address,
+            prefix: properties.prefix,
+            flags: properties.flags,
+        })
+        .collect())
+}
+
+fn serialize_address_list<S: serde::Serializer>(
+    addresses: &[AddressStatus],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(addresses.iter().map(|address| {
+        HashMap::from([(
+            address.address.clone(),
+            AddressProperties {
+                prefix: address.prefix,
+                flags: address.flags.clone(),
+            },
+        )])
+    }))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{AdminState, NetplanStatus, OperState, RouteTable};
+
+    #[test]
+    fn parse_status() {
+        let json = r#"{
+            "netplan-global-state": {
+                "online": true,
+                "nameservers": {"addresses": ["127.0.0.53"], "search": ["lan"], "mode": "stub"}
+            },
+            "lo": {
+                "index": 1, "adminstate": "UP", "operstate": "UNKNOWN", "type": "ethernet",
+                "id": "", "macaddress": "00:00:00:00:00:00",
+                "addresses": [{"127.0.0.1": {"prefix": 8}}, {"::1": {"prefix": 128}}]
+            },
+            "bond0": {
+                "index": 4, "adminstate": "UP", "operstate": "LOWERLAYERDOWN", "type": "bond",
+                "backend": "networkd", "id": "bond0", "interfaces": ["eth0", "eth1"],
+                "routes": [{"to": "default", "via": "10.0.0.1", "family": 2, "metric": 100,
+                            "type": "unicast", "scope": "global", "protocol": "static", "table": "main"},
+                           {"to": "10.1.0.0/24", "family": 2, "table": 100}]
+            }
+        }"#;
+
+        let status = NetplanStatus::from_json(json).unwrap();
+        assert!(status.global.online);
+        assert_eq!(status.interfaces.len(), 2);
+
+        let lo = &status.interfaces["lo"];
+        assert_eq!(lo.id, None);
+        assert_eq!(lo.operstate, OperState::Unknown);
+        assert_eq!(lo.addresses[1].to_string(), "::1/128");
+
+        let (_, bond) = status.interface_by_id("bond0").unwrap();
+        assert_eq!(bond.adminstate, AdminState::Up);
+        assert_eq!(bond.operstate, OperState::LowerLayerDown);
+        assert_eq!(bond.routes[0].table, Some(RouteTable::Name("main".into())));
+        assert_eq!(bond.routes[1].table, Some(RouteTable::Id(100)));
+
+        let json = serde_json::to_string(&status).unwrap();
+        assert_eq!(NetplanStatus::from_json(&json).unwrap(), status);
+    }
+}
@@ -0,0 +1,274 @@
+//! Visiting the device definitions of a configuration as they are decoded, without
+//! building the whole configuration.
+
+use crate::{
+    AnyDeviceConfig, BondConfig, BridgeConfig, DeviceKind, DummyDeviceConfig, EthernetConfig,
+    ModemConfig, NetplanConfig, NmDeviceConfig, TunnelConfig, VlanConfig, VrfsConfig, WifiConfig,
+};
+use serde::de::{DeserializeSeed, IgnoredAny, MapAccess, Visitor};
+use serde::{Deserialize, Deserializer};
+use std::fmt::Formatter;
+use std::io::Read;
+use std::ops::ControlFlow;
+
+const KINDS: [DeviceKind; 10] = [
+    DeviceKind::Ethernet,
+    DeviceKind::Wifi,
+    DeviceKind::Modem,
+    DeviceKind::Bond,
+    DeviceKind::Bridge,
+    DeviceKind::Vlan,
+    DeviceKind::Tunnel,
+    DeviceKind::Vrf,
+    DeviceKind::DummyDevice,
+    DeviceKind::NmDevice,
+];
+
+impl NetplanConfig {
+    /// Decode the device definitions of a netplan YAML document one at a time, in the
+    /// order they are written, and pass each to `visit` with its kind and ID. Only a
+    /// single definition is held at a time, so a configuration with thousands of devices
+    /// can be filtered or counted without building its maps. The YAML itself is still
+    /// parsed up front by serde_yaml, which is much smaller than the decoded definitions.
+    /// Settings outside the device sections, like `renderer`, are skipped.
+    ///
+    /// Once `visit` breaks, the remaining definitions are skipped without being decoded.
+    /// The document is checked to be valid YAML, and the definitions which are decoded
+    /// to be valid netplan, but the document is not checked further, e.g. for unknown
+    /// sections.
+    ///
+    /// ```
+    /// use netplan_types::{AnyDeviceConfig, NetplanConfig};
+    /// use std::ops::ControlFlow;
+    ///
+    /// let yaml = "
+    /// network:
+    ///   version: 2
+    ///   ethernets:
+    ///     eth0: {dhcp4: true}
+    ///   vlans:
+    ///     vlan10: {id: 10, link: eth0}
+    ///     vlan20: {id: 20, link: eth0}
+    /// ";
+    ///
+    /// let mut vlans = Vec::new();
+    /// NetplanConfig::visit_devices(yaml, |_, name, device| {
+    ///     if let AnyDeviceConfig::Vlan(vlan) = device {
+    ///         vlans.push((name.to_string(), vlan.id));
+    ///     }
+    ///     ControlFlow::Continue(())
+    /// })
+    /// .unwrap();
+    ///
+    /// assert_eq!(vlans, [("vlan10".to_string(), Some(10)), ("vlan20".to_string(), Some(20))]);
+    /// ```
+    pub fn visit_devices<F>(yaml: &str, visit: F) -> Result<(), serde_yaml::Error>
+    where
+        F: FnMut(DeviceKind, &str, AnyDeviceConfig<'_>) -> ControlFlow<()>,
+    {
+        visit_document(serde_yaml::Deserializer::from_str(yaml), visit)
+    }
+
+    /// Decode the device definitions of a netplan YAML document read from `reader` one at
+    /// a time, see [NetplanConfig::visit_devices].
+    pub fn visit_devices_from_reader<R, F>(reader: R, visit: F) -> Result<(), serde_yaml::Error>
+    where
+        R: Read,
+        F: FnMut(DeviceKind, &str, AnyDeviceConfig<'_>) -> ControlFlow<()>,
+    {
+        visit_document(serde_yaml::Deserializer::from_reader(reader), visit)
+    }
+}
+
+fn visit_document<F>(
+    deserializer: serde_yaml::Deserializer<'_>,
+    visit: F,
+) -> Result<(), serde_yaml::Error>
+where
+    F: FnMut(DeviceKind, &str, AnyDeviceConfig<'_>) -> ControlFlow<()>,
+{
+    let mut state = State { visit, done: false };
+    Level {
+        state: &mut state,
+        depth: Depth::Document,
+    }
+    .deserialize(deserializer)
+}
+
+struct State<F> {
+    visit: F,
+    /// Whether `visit` broke, and the remaining definitions are skipped.
+    done: bool,
+}
+
+/// The level of the document a [Level] decodes.
+#[derive(Clone, Copy)]
+enum Depth {
+    /// The top level, with the `network` key.
+    Document,
+    /// The `network` mapping, with the device sections.
+    Network,
+    /// A device section, mapping IDs to definitions.
+    Section(DeviceKind),
+}
+
+struct Level<'s, F> {
+    state: &'s mut State<F>,
+    depth: Depth,
+}
+
+impl<'de, F> DeserializeSeed<'de> for Level<'_, F>
+where
+    F: FnMut(DeviceKind, &str, AnyDeviceConfig<'_>) -> ControlFlow<()>,
+{
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de, F> Visitor<'de> for Level<'_, F>
+where
+    F: FnMut(DeviceKind, &str, AnyDeviceConfig<'_>) -> ControlFlow<()>,
+{
+    type Value = ();
+
+    fn expecting(&self, f: &mut Formatter) -> std::fmt::Result {
+        f.write_str("a mapping")
+    }
+
+    /// An empty section, like `ethernets:` without definitions.
+    fn visit_unit<E: serde::de::Error>(self) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        while let Some(key) = map.next_key::<String>()? {
+            let depth = match self.depth {
+                Depth::Document if key == "network" => Depth::Network,
+                Depth::Network => match KINDS.into_iter().find(|kind| kind.section() == key) {
+                    Some(kind) => Depth::Section(kind),
+                    None => {
+                        map.next_value::<IgnoredAny>()?;
+                        continue;
+                    }
+                },
+                Depth::Section(kind) if !self.state.done => {
+                    visit_device(&mut map, self.state, kind, &key)?;
+                    continue;
+                }
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                    continue;
+                }
+            };
+
+            map.next_value_seed(Level {
+                state: &mut *self.state,
+                depth,
+            })?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Decode the next definition of a section, and pass it to the visitor.
+fn visit_device<'de, A, F>(
+    map: &mut A,
+    state: &mut State<F>,
+    kind: DeviceKind,
+    name: &str,
+) -> Result<(), A::Error>
+where
+    A: MapAccess<'de>,
+    F: FnMut(DeviceKind, &str, AnyDeviceConfig<'_>) -> ControlFlow<()>,
+{
+    fn decode<'de, A: MapAccess<'de>, T: Deserialize<'de>>(
+        map: &mut A,
+        visit: impl FnOnce(&T) -> ControlFlow<()>,
+    ) -> Result<ControlFlow<()>, A::Error> {
+        Ok(visit(&map.next_value::<T>()?))
+    }
+
+    let visit = &mut state.visit;
+    let flow = match kind {
+        DeviceKind::Ethernet => {
+            decode::<_, EthernetConfig>(map, |d| visit(kind, name, AnyDeviceConfig::Ethernet(d)))
+        }
+        DeviceKind::Wifi => {
+            decode::<_, WifiConfig>(map, |d| visit(kind, name, AnyDeviceConfig::Wifi(d)))
+        }
+        DeviceKind::Modem => {
+            decode::<_, ModemConfig>(map, |d| visit(kind, name, AnyDeviceConfig::Modem(d)))
+        }
+        DeviceKind::Bond => {
+            decode::<_, BondConfig>(map, |d| visit(kind, name, AnyDeviceConfig::Bond(d)))
+        }
+        DeviceKind::Bridge => {
+            decode::<_, BridgeConfig>(map, |d| visit(kind, name, AnyDeviceConfig::Bridge(d)))
+        }
+        DeviceKind::Vlan => {
+            decode::<_, VlanConfig>(map, |d| visit(kind, name, AnyDeviceConfig::Vlan(d)))
+        }
+        DeviceKind::Tunnel => {
+            decode::<_, TunnelConfig>(map, |d| visit(kind, name, AnyDeviceConfig::Tunnel(d)))
+        }
+        DeviceKind::Vrf => {
+            decode::<_, VrfsConfig>(map, |d| visit(kind, name, AnyDeviceConfig::Vrf(d)))
+        }
+        DeviceKind::DummyDevice => decode::<_, DummyDeviceConfig>(map, |d| {
+            visit(kind, name, AnyDeviceConfig::DummyDevice(d))
+        }),
+        DeviceKind::NmDevice => {
+            decode::<_, NmDeviceConfig>(map, |d| visit(kind, name, AnyDeviceConfig::NmDevice(d)))
+        }
+    }?;
+
+    state.done = flow.is_break();
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{DeviceKind, NetplanConfig};
+    use std::fmt::Write;
+    use std::ops::ControlFlow;
+
+    #[test]
+    fn visit_devices() {
+        let mut yaml = String::from("network:\n  version: 2\n  renderer: networkd\n");
+        yaml.push_str("  ethernets:\n    eth0: {dhcp4: true}\n  bridges:\n  vlans:\n");
+        for id in 1..=1000 {
+            writeln!(yaml, "    vlan{id}: {{id: {id}, link: eth0}}").unwrap();
+        }
+        yaml.push_str("  openvswitch: {protocols: [OpenFlow13]}\n");
+
+        let mut visited = Vec::new();
+        NetplanConfig::visit_devices(&yaml, |kind, name, device| {
+            assert_eq!(device.kind(), kind);
+            visited.push((kind, name.to_string()));
+            ControlFlow::Continue(())
+        })
+        .unwrap();
+        assert_eq!(visited.len(), 1001);
+        assert_eq!(visited[0], (DeviceKind::Ethernet, "eth0".to_string()));
+        assert_eq!(visited[1000], (DeviceKind::Vlan, "vlan1000".to_string()));
+
+        let mut count = 0;
+        NetplanConfig::visit_devices_from_reader(yaml.as_bytes(), |kind, _, _| {
+            count += 1;
+            match kind {
+                DeviceKind::Vlan if count == 10 => ControlFlow::Break(()),
+                _ => ControlFlow::Continue(()),
+            }
+        })
+        .unwrap();
+        assert_eq!(count, 10);
+
+        let invalid = "network:\n  vlans:\n    vlan1: {id: ten}\n";
+        let result = NetplanConfig::visit_devices(invalid, |_, _, _| ControlFlow::Continue(()));
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,243 @@
+//! A concise, human readable overview of a configuration.
+
+use crate::{AnyDeviceConfig, NetplanConfig, NetworkConfig};
+use std::fmt::{Display, Formatter};
+
+/// A human readable overview of a configuration, with one line per device, e.g.
+/// `br0: bridge of eth1 eth2, 10.0.0.2/24, default via 10.0.0.1`.
+/// Created by [NetplanConfig::summary].
+#[derive(Debug, Clone, Copy)]
+pub struct Summary<'a>(&'a NetworkConfig);
+
+impl NetplanConfig {
+    /// A concise overview of the configuration, for logs and command line tools.
+    ///
+    /// ```
+    /// use netplan_types::NetplanConfig;
+    ///
+    /// let config = NetplanConfig::build(|net| {
+    ///     net.ethernet("eth0", |e| e.dhcp4(true).mtu(9000));
+    ///     net.bridge("br0", |b| b.interfaces(["eth1", "eth2"]).address("10.0.0.2/24").route("default", "10.0.0.1"));
+    /// });
+    ///
+    /// assert_eq!(
+    ///     config.summary().to_string(),
+    ///     "eth0: ethernet, DHCPv4, MTU 9000\nbr0: bridge of eth1 eth2, 10.0.0.2/24, default via 10.0.0.1"
+    /// );
+    /// ```
+    pub fn summary(&self) -> Summary<'_> {
+        self.network.summary()
+    }
+}
+
+impl NetworkConfig {
+    /// A concise overview of the configuration, see [NetplanConfig::summary].
+    pub fn summary(&self) -> Summary<'_> {
+        Summary(self)
+    }
+}
+
+/// Formats the configuration as its [Summary].
+impl Display for NetplanConfig {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.summary().fmt(f)
+    }
+}
+
+impl Display for Summary<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut devices = self.0.devices().collect::<Vec<_>>();
+        devices.sort_by_key(|(kind, name, _)| (*kind, *name));
+
+        if devices.is_empty() {
+            return f.write_str("no devices");
+        }
+
+        for (i, (_, name, device)) in devices.into_iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+
+            write!(f, "{name}: {}", describe(device).join(", "))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The parts of the summary of a single device.
+fn describe(device: AnyDeviceConfig) -> Vec<String> {
+    let members = |interfaces: &[String]| match interfaces {
+        [] => String::new(),
+        interfaces => format!(" of {}", interfaces.join(" ")),
+    };
+
+    let mut parts = vec![match device {
+        AnyDeviceConfig::Ethernet(_) => "ethernet".to_string(),
+        AnyDeviceConfig::Wifi(wifi) => {
+            let mut ssids = wifi
+                .access_points
+                .iter()
+                .flat_map(|access_points| access_points.keys())
+                .map(String::as_str)
+                .collect::<Vec<_>>();
+            ssids.sort();
+
+            if ssids.is_empty() {
+                "wifi".to_string()
+            } else {
+                format!("wifi to {}", ssids.join(" "))
+            }
+        }
+        AnyDeviceConfig::Modem(_) => "modem".to_string(),
+        AnyDeviceConfig::Bond(bond) => format!(
+            "bond{}",
+            members(bond.interfaces.as_deref().unwrap_or_default())
+        ),
+        AnyDeviceConfig::Bridge(bridge) => format!(
+            "bridge{}",
+            members(bridge.interfaces.as_deref().unwrap_or_default())
+        ),
+        AnyDeviceConfig::Vlan(vlan) => {
+            let mut part = "vlan".to_string();
+            if let Some(id) = vlan.id {
+                part.push_str(&format!(" {id}"));
+            }
+            if let Some(link) = &vlan.link {
+                part.push_str(&format!(" on {link}"));
+            }
+            part
+        }
+        AnyDeviceConfig::Tunnel(tunnel) => {
+            let mut part = match &tunnel.mode {
+                Some(mode) => format!("{} tunnel", mode.as_str()),
+                None => "tunnel".to_string(),
+            };
+            if let Some(remote) = &tunnel.remote {
+                part.push_str(&format!(" to {remote}"));
+            }
+            part
+        }
+        AnyDeviceConfig::Vrf(vrf) => format!("vrf table {}{}", vrf.table, members(&vrf.interfaces)),
+        AnyDeviceConfig::DummyDevice(_) => "dummy device".to_string(),
+        AnyDeviceConfig::NmDevice(_) => "NetworkManager connection".to_string(),
+    }];
+
+    let Some(common) = device.common_all() else {
+        return parts;
+    };
+
+    if common.dhcp4 == Some(true) {
+        parts.push("DHCPv4".to_string());
+    }
+
+    if common.dhcp6 == Some(true) {
+        parts.push("DHCPv6".to_string());
+    }
+
+    parts.extend(
+        common
+            .addresses
+            .iter()
+            .flatten()
+            .flat_map(|address| address.addresses())
+            .map(str::to_string),
+    );
+
+    let gateways = [&common.gateway4, &common.gateway6]
+        .into_iter()
+        .flatten()
+        .map(String::as_str);
+    let (default_routes, routes): (Vec<_>, Vec<_>) =
+        common.routes.iter().flatten().partition(|route| {
+            matches!(route.to.as_deref(), Some("default" | "0.0.0.0/0" | "::/0"))
+        });
+    let default_vias = default_routes
+        .into_iter()
+        .filter_map(|route| route.via.as_deref());
+
+    // A gateway is often repeated as a default route, while migrating away from gatewayN
+    let mut vias = Vec::new();
+    for via in gateways.chain(default_vias) {
+        if !vias.contains(&via) {
+            vias.push(via);
+        }
+    }
+    parts.extend(vias.into_iter().map(|via| format!("default via {via}")));
+
+    match routes.len() {
+        0 => {}
+        1 => parts.push("1 route".to_string()),
+        n => parts.push(format!("{n} routes")),
+    }
+
+    if let Some(mtu) = common.mtu {
+        parts.push(format!("MTU {mtu}"));
+    }
+
+    if common.optional == Some(true) {
+        parts.push("optional".to_string());
+    }
+
+    parts
+}
+
+#[cfg(all(test, feature = "yaml"))]
+mod test {
+    use crate::NetplanConfig;
+
+    #[test]
+    fn summary() {
+        let input = r#"
+            network:
+              version: 2
+              ethernets:
+                eth0:
+                  dhcp6: true
+                  optional: true
+              vlans:
+                vlan10:
+                  id: 10
+                  link: eth0
+                  addresses: [10.0.10.2/24]
+                  routes:
+                    - to: 10.1.0.0/16
+                      via: 10.0.10.1
+              tunnels:
+                wg0:
+                  mode: wireguard
+            "#;
+
+        let config: NetplanConfig = serde_yaml::from_str(input).unwrap();
+        assert_eq!(
+            config.to_string(),
+            "eth0: ethernet, DHCPv6, optional\n\
+             vlan10: vlan 10 on eth0, 10.0.10.2/24, 1 route\n\
+             wg0: wireguard tunnel"
+        );
+        assert_eq!(NetplanConfig::default().to_string(), "no devices");
+    }
+
+    #[test]
+    fn duplicate_default_routes() {
+        let input = r#"
+            network:
+              version: 2
+              ethernets:
+                eth0:
+                  addresses: [10.0.0.2/24]
+                  gateway4: 10.0.0.1
+                  routes:
+                    - to: default
+                      via: 10.0.0.1
+                    - to: 0.0.0.0/0
+                      via: 10.0.0.1
+            "#;
+
+        let config: NetplanConfig = serde_yaml::from_str(input).unwrap();
+        assert_eq!(
+            config.to_string(),
+            "eth0: ethernet, 10.0.0.2/24, default via 10.0.0.1"
+        );
+    }
+}
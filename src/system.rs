@@ -0,0 +1,241 @@
+//! Reading the live network state from the kernel over rtnetlink.
+
+use crate::{AddressState, LinkState, NetplanConfig, RouteOrigin, RouteState, SystemState};
+use futures_util::TryStreamExt;
+use rtnetlink::packet_route::address::{
+    AddressAttribute, AddressFlags, AddressHeaderFlags, AddressMessage,
+};
+use rtnetlink::packet_route::link::{
+    InfoData, InfoVlan, InfoVrf, LinkAttribute, LinkFlags, LinkInfo, LinkMessage,
+};
+use rtnetlink::packet_route::route::{
+    RouteAddress, RouteAttribute, RouteMessage, RouteProtocol, RouteType,
+};
+use rtnetlink::{Handle, RouteMessageBuilder};
+use std::fmt::{Display, Formatter};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+/// The error returned when the network state cannot be read.
+#[derive(Debug)]
+pub enum SystemError {
+    /// The netlink connection could not be set up.
+    Io(std::io::Error),
+    /// The kernel rejected a request.
+    Netlink(rtnetlink::Error),
+}
+
+impl Display for SystemError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "Failed to connect to netlink: {e}"),
+            Self::Netlink(e) => write!(f, "Failed to read network state: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for SystemError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Netlink(e) => Some(e),
+        }
+    }
+}
+
+impl From<std::io::Error> for SystemError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<rtnetlink::Error> for SystemError {
+    fn from(e: rtnetlink::Error) -> Self {
+        Self::Netlink(e)
+    }
+}
+
+impl SystemState {
+    /// Read the links, addresses and unicast routes of the current network namespace from
+    /// the kernel.
+    pub fn read() -> Result<Self, SystemError> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_io()
+            .build()?;
+        runtime.block_on(read_state())
+    }
+}
+
+impl NetplanConfig {
+    /// A best-effort netplan representation of the live network state, for machines which
+    /// were configured by hand. See [NetplanConfig::from_state].
+    ///
+    /// ```no_run
+    /// use netplan_types::NetplanConfig;
+    ///
+    /// let config = NetplanConfig::from_system().unwrap();
+    /// config.write_to("/etc/netplan/50-onboarding.yaml").unwrap();
+    /// ```
+    pub fn from_system() -> Result<Self, SystemError> {
+        Ok(Self::from_state(&SystemState::read()?))
+    }
+}
+
+async fn read_state() -> Result<SystemState, SystemError> {
+    let (connection, handle, _) = rtnetlink::new_connection()?;
+    tokio::spawn(connection);
+
+    let mut links = Vec::new();
+    let mut messages = handle.link().get().execute();
+    while let Some(message) = messages.try_next().await? {
+        links.push(link_state(message));
+    }
+
+    let mut messages = handle.address().get().execute();
+    while let Some(message) = messages.try_next().await? {
+        let link = links
+            .iter_mut()
+            .find(|link| link.index == message.header.index);
+        if let (Some(link), Some(address)) = (link, address_state(&message)) {
+            link.addresses.push(address);
+        }
+    }
+
+    let mut routes = Vec::new();
+    read_routes(
+        &handle,
+        RouteMessageBuilder::<Ipv4Addr>::new().build(),
+        &mut routes,
+    )
+    .await?;
+    read_routes(
+        &handle,
+        RouteMessageBuilder::<Ipv6Addr>::new().build(),
+        &mut routes,
+    )
+    .await?;
+
+    Ok(SystemState { links, routes })
+}
+
+async fn read_routes(
+    handle: &Handle,
+    request: RouteMessage,
+    routes: &mut Vec<RouteState>,
+) -> Result<(), SystemError> {
+    let mut messages = handle.route().get(request).execute();
+    while let Some(message) = messages.try_next().await? {
+        routes.extend(route_state(&message));
+    }
+
+    Ok(())
+}
+
+fn link_state(message: LinkMessage) -> LinkState {
+    let mut link = LinkState {
+        index: message.header.index,
+        up: message.header.flags.contains(LinkFlags::Up),
+        loopback: message.header.flags.contains(LinkFlags::Loopback),
+        ..Default::default()
+    };
+
+    for attribute in message.attributes {
+        match attribute {
+            LinkAttribute::IfName(name) => link.name = name,
+            LinkAttribute::Mtu(mtu) => link.mtu = Some(mtu),
+            LinkAttribute::Address(address) if address.len() == 6 => {
+                let octets = address.iter().map(|b| format!("{b:02x}"));
+                link.macaddress = Some(octets.collect::<Vec<_>>().join(":"));
+            }
+            LinkAttribute::Controller(index) => link.controller = Some(index),
+            LinkAttribute::Link(index) => link.parent = Some(index),
+            LinkAttribute::LinkInfo(infos) => {
+                for info in infos {
+                    match info {
+                        LinkInfo::Kind(kind) => link.kind = Some(kind.to_string()),
+                        LinkInfo::Data(InfoData::Vlan(data)) => {
+                            link.vlan_id = data.iter().find_map(|info| match info {
+                                InfoVlan::Id(id) => Some(*id),
+                                _ => None,
+                            });
+                        }
+                        LinkInfo::Data(InfoData::Vrf(data)) => {
+                            link.vrf_table = data.iter().find_map(|info| match info {
+                                InfoVrf::TableId(table) => Some(*table),
+                                _ => None,
+                            });
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    link
+}
+
+fn address_state(message: &AddressMessage) -> Option<AddressState> {
+    let mut address = None;
+    let mut local = None;
+    let mut permanent = message.header.flags.contains(AddressHeaderFlags::Permanent);
+    for attribute in &message.attributes {
+        match attribute {
+            AddressAttribute::Address(ip) => address = Some(*ip),
+            // For IPv4, the local address is the address of the link, the address may be its peer
+            AddressAttribute::Local(ip) => local = Some(*ip),
+            AddressAttribute::Flags(flags) => permanent = flags.contains(AddressFlags::Permanent),
+            _ => {}
+        }
+    }
+
+    Some(AddressState {
+        address: local.or(address)?,
+        prefix: message.header.prefix_len,
+        dynamic: !permanent,
+    })
+}
+
+fn route_state(message: &RouteMessage) -> Option<RouteState> {
+    if message.header.kind != RouteType::Unicast {
+        return None;
+    }
+
+    let mut route = RouteState {
+        to: None,
+        prefix: message.header.destination_prefix_length,
+        via: None,
+        link: None,
+        metric: None,
+        table: message.header.table.into(),
+        origin: match message.header.protocol {
+            RouteProtocol::Kernel => RouteOrigin::Kernel,
+            RouteProtocol::Boot | RouteProtocol::Static => RouteOrigin::Static,
+            RouteProtocol::Dhcp | RouteProtocol::Ra | RouteProtocol::IcmpRedirect => {
+                RouteOrigin::Dynamic
+            }
+            _ => RouteOrigin::Other,
+        },
+    };
+
+    for attribute in &message.attributes {
+        match attribute {
+            RouteAttribute::Destination(address) => route.to = ip(address),
+            RouteAttribute::Gateway(address) => route.via = ip(address),
+            RouteAttribute::Oif(index) => route.link = Some(*index),
+            RouteAttribute::Priority(metric) => route.metric = Some(*metric),
+            RouteAttribute::Table(table) => route.table = *table,
+            _ => {}
+        }
+    }
+
+    Some(route)
+}
+
+fn ip(address: &RouteAddress) -> Option<IpAddr> {
+    match address {
+        RouteAddress::Inet(ip) => Some(IpAddr::V4(*ip)),
+        RouteAddress::Inet6(ip) => Some(IpAddr::V6(*ip)),
+        _ => None,
+    }
+}
@@ -0,0 +1,229 @@
+//! Templates of configurations, with `${NAME}` placeholders resolved against variables.
+
+use crate::NetplanConfig;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+/// The error returned when a template cannot be rendered into a configuration.
+#[derive(Debug)]
+pub enum TemplateError {
+    /// Placeholders without a variable, in the order they first appear.
+    Unresolved(Vec<String>),
+    /// A `${` on the given line, counted from 1, which does not start a placeholder like
+    /// `${NAME}`.
+    Syntax(usize),
+    /// The value of the variable spans multiple lines, and cannot be inserted into YAML.
+    MultiLine(String),
+    /// The rendered template is not a valid netplan configuration.
+    Yaml(serde_yaml::Error),
+}
+
+impl Display for TemplateError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unresolved(names) => write!(f, "Unresolved variables: {}", names.join(", ")),
+            Self::Syntax(line) => write!(f, "Invalid placeholder on line {line}"),
+            Self::MultiLine(name) => write!(f, "The value of {name} spans multiple lines"),
+            Self::Yaml(e) => write!(f, "Invalid configuration: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for TemplateError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Yaml(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<serde_yaml::Error> for TemplateError {
+    fn from(e: serde_yaml::Error) -> Self {
+        Self::Yaml(e)
+    }
+}
+
+impl NetplanConfig {
+    /// Parse a configuration from a YAML template, after replacing its placeholders with
+    /// the values of `variables`, see [render_template].
+    ///
+    /// ```
+    /// use netplan_types::NetplanConfig;
+    /// use std::collections::HashMap;
+    ///
+    /// let template = r#"
+    /// network:
+    ///   version: 2
+    ///   ethernets:
+    ///     eth0:
+    ///       addresses: ["${MGMT_IP}/24"]
+    ///       mtu: ${MTU}
+    /// "#;
+    /// let variables = HashMap::from([
+    ///     ("MGMT_IP".to_string(), "192.0.2.10".to_string()),
+    ///     ("MTU".to_string(), "9000".to_string()),
+    /// ]);
+    ///
+    /// let config = NetplanConfig::from_template(template, &variables).unwrap();
+    /// let eth0 = config.network.ethernets.unwrap().remove("eth0").unwrap();
+    /// assert_eq!(eth0.common_all.unwrap().mtu, Some(9000));
+    ///
+    /// let error = NetplanConfig::from_template(template, &HashMap::new()).unwrap_err();
+    /// assert_eq!(error.to_string(), "Unresolved variables: MGMT_IP, MTU");
+    /// ```
+    pub fn from_template(
+        template: &str,
+        variables: &HashMap<String, String>,
+    ) -> Result<Self, TemplateError> {
+        Ok(Self::from_yaml(&render_template(template, variables)?)?)
+    }
+}
+
+/// Replace the `${NAME}` placeholders of a YAML template with the values of `variables`.
+/// Names consist of ASCII letters, digits and underscores, and `$${` is written as `${`.
+/// Every placeholder must have a variable, otherwise all unresolved placeholders are
+/// reported. To resolve them from the environment, pass
+/// `std::env::vars().collect()`.
+///
+/// Values are inserted as they are, so they are typed like the rest of the YAML: `mtu:
+/// ${MTU}` becomes a number. A placeholder which is the whole of a quoted scalar, like
+/// `password: "${PSK}"`, is escaped for the quotes, so the value stays a single string
+/// whatever characters it contains. Values spanning multiple lines are rejected.
+pub fn render_template(
+    template: &str,
+    variables: &HashMap<String, String>,
+) -> Result<String, TemplateError> {
+    let mut rendered = String::with_capacity(template.len());
+    let mut unresolved = Vec::<String>::new();
+
+    for (index, line) in template.split_inclusive('\n').enumerate() {
+        let mut rest = line;
+        while let Some(start) = rest.find('$') {
+            rendered.push_str(&rest[..start]);
+            let after = &rest[start + 1..];
+
+            if let Some(escaped) = after.strip_prefix("${") {
+                rendered.push_str("${");
+                rest = escaped;
+                continue;
+            }
+            let Some(placeholder) = after.strip_prefix('{') else {
+                rendered.push('$');
+                rest = after;
+                continue;
+            };
+
+            let name = placeholder
+                .find('}')
+                .map(|end| &placeholder[..end])
+                .filter(|name| is_name(name))
+                .ok_or(TemplateError::Syntax(index + 1))?;
+            rest = &placeholder[name.len() + 1..];
+
+            let Some(value) = variables.get(name) else {
+                if !unresolved.iter().any(|n| n == name) {
+                    unresolved.push(name.to_string());
+                }
+                continue;
+            };
+            if value.contains(['\n', '\r']) {
+                return Err(TemplateError::MultiLine(name.to_string()));
+            }
+
+            let quote = rendered.chars().last().filter(|c| matches!(c, '"' | '\''));
+            match quote {
+                Some(quote) if rest.starts_with(quote) => {
+                    rendered.push_str(&escape(value, quote));
+                }
+                _ => rendered.push_str(value),
+            }
+        }
+        rendered.push_str(rest);
+    }
+
+    if unresolved.is_empty() {
+        Ok(rendered)
+    } else {
+        Err(TemplateError::Unresolved(unresolved))
+    }
+}
+
+fn is_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Escape a value for a YAML scalar in the given quotes.
+fn escape(value: &str, quote: char) -> String {
+    if quote == '\'' {
+        value.replace('\'', "''")
+    } else {
+        value.replace('\\', "\\\\").replace('"', "\\\"")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{render_template, AddressMapping, NetplanConfig, TemplateError};
+    use std::collections::HashMap;
+
+    #[test]
+    fn render_templates() {
+        let variables = HashMap::from([
+            ("HOSTNAME".to_string(), "web-1".to_string()),
+            ("MGMT_IP".to_string(), "192.0.2.10".to_string()),
+            (
+                "PSK".to_string(),
+                r#"it's a "secret" \o/ # not a comment"#.to_string(),
+            ),
+        ]);
+
+        let template = r#"
+network:
+  version: 2
+  ethernets:
+    eth0:
+      addresses: ["${MGMT_IP}/24"]
+      dhcp4-overrides:
+        hostname: ${HOSTNAME}
+      networkmanager:
+        name: "$${HOSTNAME} costs $5"
+  wifis:
+    wlan0:
+      access-points:
+        double:
+          password: "${PSK}"
+        single:
+          password: '${PSK}'
+"#;
+        let config = NetplanConfig::from_template(template, &variables).unwrap();
+        let eth0 = &config.network.ethernets.as_ref().unwrap()["eth0"];
+        let common = eth0.common_all.as_deref().unwrap();
+        let address = AddressMapping::Simple("192.0.2.10/24".to_string());
+        assert_eq!(common.addresses, Some(vec![address]));
+        let overrides = common.dhcp4_overrides.as_ref().unwrap();
+        assert_eq!(overrides.hostname.as_deref(), Some("web-1"));
+        let networkmanager = common.networkmanager.as_ref().unwrap();
+        assert_eq!(networkmanager.name.as_deref(), Some("${HOSTNAME} costs $5"));
+
+        let wifis = config.network.wifis.as_ref().unwrap();
+        let access_points = wifis["wlan0"].access_points.as_ref().unwrap();
+        for ssid in ["double", "single"] {
+            let password = access_points[ssid].password.as_ref().unwrap();
+            assert_eq!(password.as_str(), variables["PSK"]);
+        }
+
+        let error = render_template("mtu: ${MTU}\nname: ${A}${MTU}", &variables).unwrap_err();
+        assert!(matches!(error, TemplateError::Unresolved(names) if names == ["MTU", "A"]));
+        let error = render_template("a: 1\nb: ${not valid}", &variables).unwrap_err();
+        assert!(matches!(error, TemplateError::Syntax(2)));
+        let error = render_template("c: ${NAME", &variables).unwrap_err();
+        assert!(matches!(error, TemplateError::Syntax(1)));
+
+        let variables = HashMap::from([("NAME".to_string(), "a\nb: c".to_string())]);
+        let error = render_template("c: ${NAME}", &variables).unwrap_err();
+        assert!(matches!(error, TemplateError::MultiLine(name) if name == "NAME"));
+    }
+}
@@ -0,0 +1,180 @@
+//! Changing the configuration files of a directory with a snapshot to roll back to.
+
+use crate::load::yaml_files;
+use crate::write::write_atomic;
+use crate::{NetplanFile, WriteOptions};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::Receiver;
+use std::time::Duration;
+
+/// Changes to the configuration files of a directory like `/etc/netplan`, which are
+/// rolled back unless they are committed.
+///
+/// When the transaction begins, the `*.yaml` files of the directory are snapshotted.
+/// Rolling back restores the snapshotted files and removes the `*.yaml` files created
+/// since, other files in the directory are left alone. A transaction which is dropped
+/// without being committed is rolled back, so an early return or a panic while changing
+/// the files restores them as well.
+///
+/// [ConfigTransaction::confirm_within] gives the safety net of `netplan try` for direct
+/// file edits: the changes are rolled back unless they are confirmed in time.
+///
+/// ```no_run
+/// use netplan_types::{ConfigTransaction, NetplanConfig, NetplanFile, WriteOptions};
+///
+/// let transaction = ConfigTransaction::begin("/etc/netplan").unwrap();
+/// let file = NetplanFile::new(50, "provisioning", NetplanConfig::single_nic_dhcp("eth0"));
+/// transaction.write(&file, &WriteOptions::default()).unwrap();
+///
+/// if apply_succeeded() {
+///     transaction.commit();
+/// } else {
+///     transaction.rollback().unwrap();
+/// }
+/// # fn apply_succeeded() -> bool { true }
+/// ```
+#[derive(Debug)]
+pub struct ConfigTransaction {
+    dir: PathBuf,
+    /// The paths and contents of the files when the transaction began.
+    snapshot: Vec<(PathBuf, Vec<u8>)>,
+    finished: bool,
+}
+
+impl ConfigTransaction {
+    /// Snapshot the `*.yaml` files of `dir`, and begin changing them.
+    pub fn begin(dir: impl AsRef<Path>) -> std::io::Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        let snapshot = yaml_files(&dir)?
+            .into_iter()
+            .map(|path| {
+                let contents = std::fs::read(&path)?;
+                Ok((path, contents))
+            })
+            .collect::<std::io::Result<_>>()?;
+
+        Ok(Self {
+            dir,
+            snapshot,
+            finished: false,
+        })
+    }
+
+    /// The directory the transaction changes.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Write a file into the directory, see [NetplanFile::write_to_dir].
+    pub fn write(&self, file: &NetplanFile, options: &WriteOptions) -> std::io::Result<PathBuf> {
+        file.write_to_dir(&self.dir, options)
+    }
+
+    /// Remove the file with the given name from the directory.
+    pub fn remove(&self, file_name: &str) -> std::io::Result<()> {
+        std::fs::remove_file(self.dir.join(file_name))
+    }
+
+    /// Keep the changes.
+    pub fn commit(mut self) {
+        self.finished = true;
+    }
+
+    /// Restore the files as they were when the transaction began.
+    ///
+    /// Restored files are written atomically like [NetplanConfig::write_to](crate::NetplanConfig::write_to),
+    /// so on Unix they are only accessible by their owner, regardless of their previous permissions.
+    pub fn rollback(mut self) -> std::io::Result<()> {
+        self.finished = true;
+        self.restore()
+    }
+
+    /// Keep the changes if a confirmation is received on `confirmation` within `timeout`,
+    /// and roll them back otherwise, e.g. when the network is no longer reachable after
+    /// applying them. A confirmation channel which is disconnected counts as no confirmation.
+    ///
+    /// Returns whether the changes were confirmed.
+    pub fn confirm_within(
+        self,
+        timeout: Duration,
+        confirmation: &Receiver<()>,
+    ) -> std::io::Result<bool> {
+        if confirmation.recv_timeout(timeout).is_ok() {
+            self.commit();
+            Ok(true)
+        } else {
+            self.rollback()?;
+            Ok(false)
+        }
+    }
+
+    fn restore(&self) -> std::io::Result<()> {
+        for path in yaml_files(&self.dir)? {
+            if !self
+                .snapshot
+                .iter()
+                .any(|(snapshotted, _)| *snapshotted == path)
+            {
+                std::fs::remove_file(path)?;
+            }
+        }
+
+        for (path, contents) in &self.snapshot {
+            write_atomic(path, contents)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Rolls back the changes if the transaction was neither committed nor rolled back.
+/// Errors restoring the files are ignored, use [ConfigTransaction::rollback] to handle them.
+impl Drop for ConfigTransaction {
+    fn drop(&mut self) {
+        if !self.finished {
+            let _ = self.restore();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{ConfigTransaction, NetplanConfig, NetplanFile, WriteOptions};
+    use std::time::Duration;
+
+    #[test]
+    fn transaction_rolls_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = dir.path().join("10-base.yaml");
+        std::fs::write(&base, "network: {version: 2}").unwrap();
+        std::fs::write(dir.path().join("README"), "unrelated").unwrap();
+
+        let file = NetplanFile::new(50, "new", NetplanConfig::single_nic_dhcp("eth0"));
+        {
+            let transaction = ConfigTransaction::begin(dir.path()).unwrap();
+            transaction.write(&file, &WriteOptions::default()).unwrap();
+            transaction.remove("10-base.yaml").unwrap();
+        }
+        assert_eq!(
+            std::fs::read_to_string(&base).unwrap(),
+            "network: {version: 2}"
+        );
+        assert!(!dir.path().join("50-new.yaml").exists());
+        assert!(dir.path().join("README").exists());
+
+        let (confirm, confirmation) = std::sync::mpsc::channel();
+        let transaction = ConfigTransaction::begin(dir.path()).unwrap();
+        transaction.write(&file, &WriteOptions::default()).unwrap();
+        confirm.send(()).unwrap();
+        assert!(transaction
+            .confirm_within(Duration::from_millis(10), &confirmation)
+            .unwrap());
+
+        let transaction = ConfigTransaction::begin(dir.path()).unwrap();
+        transaction.remove("50-new.yaml").unwrap();
+        assert!(!transaction
+            .confirm_within(Duration::from_millis(10), &confirmation)
+            .unwrap());
+        assert!(dir.path().join("50-new.yaml").exists());
+    }
+}
@@ -0,0 +1,105 @@
+use crate::validation::{device_path, Context, Severity};
+use ipnet::IpNet;
+
+/// Report static addresses which are invalid, assigned more than once, or of which
+/// the subnet overlaps with a subnet configured on another device.
+pub(super) fn check_static_addresses(ctx: &mut Context) {
+    let mut assigned = Vec::new();
+    for (section, name, common) in ctx.devices() {
+        let addresses = common.and_then(|c| c.addresses.as_ref());
+        for address in addresses.into_iter().flatten().flat_map(|a| a.addresses()) {
+            assigned.push((device_path(section, name), address));
+        }
+    }
+
+    // Device iteration order is not stable, sort so the reported issues are deterministic
+    assigned.sort();
+
+    let mut parsed: Vec<(&str, IpNet)> = Vec::new();
+    for (device, address) in &assigned {
+        let Ok(net) = address.parse::<IpNet>() else {
+            ctx.report(
+                Severity::Error,
+                "invalid-address",
+                format!("{device}.addresses"),
+                format!("'{address}' is not a valid address in CIDR notation (addr/prefixlen)"),
+            );
+            continue;
+        };
+
+        let mut overlapping = Vec::new();
+        for (other_device, other) in &parsed {
+            if other.addr() == net.addr() {
+                let location = if other_device == device {
+                    "earlier on this device".to_string()
+                } else {
+                    format!("to {other_device}")
+                };
+
+                ctx.report(
+                    Severity::Warning,
+                    "duplicate-address",
+                    format!("{device}.addresses"),
+                    format!("{} is already assigned {location}", net.addr()),
+                );
+            } else if other_device != device
+                && !overlapping.contains(other_device)
+                && (other.contains(&net.network()) || net.contains(&other.network()))
+            {
+                overlapping.push(*other_device);
+                ctx.report(
+                    Severity::Warning,
+                    "overlapping-subnet",
+                    format!("{device}.addresses"),
+                    format!("The subnet of {net} overlaps with {other} on {other_device}"),
+                );
+            }
+        }
+
+        parsed.push((device, net));
+    }
+}
+
+#[cfg(all(test, feature = "yaml"))]
+mod test {
+    use crate::NetplanConfig;
+
+    #[test]
+    fn duplicate_and_overlapping() {
+        let input = r#"
+            network:
+              version: 2
+              ethernets:
+                eth0:
+                  addresses:
+                    - 10.0.0.1/24
+                    - 10.0.0.2/24
+                    - 10.0.0.1/24
+                eth1:
+                  addresses:
+                    - 10.0.0.200/25:
+                        label: eth1-primary
+                    - 192.168.1.1
+            "#;
+
+        let config: NetplanConfig = serde_yaml::from_str(input).unwrap();
+        let mut rules = config
+            .validate()
+            .into_iter()
+            .filter(|issue| issue.path.ends_with(".addresses"))
+            .map(|issue| (issue.path, issue.rule))
+            .collect::<Vec<_>>();
+        rules.sort();
+
+        let expected = [
+            ("network.ethernets.eth0.addresses", "duplicate-address"),
+            ("network.ethernets.eth1.addresses", "invalid-address"),
+            ("network.ethernets.eth1.addresses", "overlapping-subnet"),
+        ];
+        assert_eq!(rules.len(), expected.len());
+        for ((path, rule), (expected_path, expected_rule)) in rules.iter().zip(expected) {
+            assert_eq!(path, expected_path);
+            assert_eq!(rule, expected_rule);
+        }
+    }
+}
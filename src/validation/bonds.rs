@@ -0,0 +1,215 @@
+use crate::validation::time::{parse_interval, DefaultUnit};
+use crate::validation::{device_path, Context, Severity};
+use crate::{BondMode, BondParameters};
+use std::time::Duration;
+
+const RULE_IGNORED: &str = "bond-parameter-ignored";
+const RULE_MONITORING: &str = "bond-monitoring-conflict";
+
+/// Report bond parameters which have no effect in the configured bonding mode,
+/// as well as conflicting link monitoring settings.
+pub(super) fn check_parameters(ctx: &mut Context) {
+    for (name, bond) in ctx.network.bonds.iter().flatten() {
+        let Some(parameters) = &bond.parameters else {
+            continue;
+        };
+
+        let path = format!("{}.parameters", device_path("bonds", name));
+        let mode = parameters.mode.clone().unwrap_or(BondMode::BalanceRr);
+
+        for (field, modes) in mode_specific_parameters(parameters) {
+            if !modes.contains(&mode) {
+                let supported = modes
+                    .iter()
+                    .map(BondMode::as_str)
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                ctx.report(
+                    Severity::Warning,
+                    RULE_IGNORED,
+                    format!("{path}.{field}"),
+                    format!(
+                        "{field} is ignored in {} mode, it is only used in: {supported}",
+                        mode.as_str()
+                    ),
+                );
+            }
+        }
+
+        let miimon = is_enabled(&parameters.mii_monitor_interval);
+        let arp = is_enabled(&parameters.arp_interval);
+
+        if miimon && arp {
+            ctx.report(
+                Severity::Warning,
+                RULE_MONITORING,
+                format!("{path}.arp-interval"),
+                "MII monitoring and ARP monitoring cannot be used at the same time",
+            )
+            .suggest("Set either mii-monitor-interval or arp-interval, not both");
+        }
+
+        if arp
+            && matches!(
+                mode,
+                BondMode::EightZeroTwoDotThreeAD | BondMode::BalanceTlb | BondMode::BalanceAlb
+            )
+        {
+            ctx.report(
+                Severity::Warning,
+                RULE_MONITORING,
+                format!("{path}.arp-interval"),
+                format!("ARP monitoring is not supported in {} mode", mode.as_str()),
+            )
+            .suggest("Use mii-monitor-interval instead");
+        }
+
+        if arp
+            && parameters
+                .arp_ip_targets
+                .as_ref()
+                .is_none_or(|targets| targets.is_empty())
+        {
+            ctx.report(
+                Severity::Warning,
+                RULE_MONITORING,
+                format!("{path}.arp-ip-targets"),
+                "ARP monitoring requires at least one ARP IP target",
+            );
+        }
+
+        if !arp {
+            for (field, set) in [
+                ("arp-ip-targets", parameters.arp_ip_targets.is_some()),
+                ("arp-validate", parameters.arp_validate.is_some()),
+                ("arp-all-targets", parameters.arp_all_targets.is_some()),
+            ] {
+                if set {
+                    ctx.report(
+                        Severity::Warning,
+                        RULE_IGNORED,
+                        format!("{path}.{field}"),
+                        format!("{field} is ignored because ARP monitoring is not enabled with arp-interval"),
+                    );
+                }
+            }
+        }
+
+        if !miimon {
+            for (field, set) in [
+                ("up-delay", parameters.up_delay.is_some()),
+                ("down-delay", parameters.down_delay.is_some()),
+            ] {
+                if set {
+                    ctx.report(
+                        Severity::Warning,
+                        RULE_IGNORED,
+                        format!("{path}.{field}"),
+                        format!("{field} is ignored because MII monitoring is not enabled with mii-monitor-interval"),
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// The parameters which are set and only have an effect in certain modes,
+/// together with the modes in which they do.
+fn mode_specific_parameters(
+    parameters: &BondParameters,
+) -> Vec<(&'static str, &'static [BondMode])> {
+    use BondMode::*;
+
+    [
+        (
+            "lacp-rate",
+            parameters.lacp_rate.is_some(),
+            &[EightZeroTwoDotThreeAD][..],
+        ),
+        (
+            "ad-select",
+            parameters.ad_select.is_some(),
+            &[EightZeroTwoDotThreeAD],
+        ),
+        (
+            "transmit-hash-policy",
+            parameters.transmit_hash_policy.is_some(),
+            &[BalanceXor, EightZeroTwoDotThreeAD, BalanceTlb],
+        ),
+        (
+            "packets-per-slave",
+            parameters.packets_per_slave.is_some(),
+            &[BalanceRr],
+        ),
+        (
+            "primary",
+            parameters.primary.is_some(),
+            &[ActiveBackup, BalanceTlb, BalanceAlb],
+        ),
+        (
+            "gratuitous-arp",
+            parameters.gratuitous_arp.is_some(),
+            &[ActiveBackup],
+        ),
+        (
+            "arp-all-targets",
+            parameters.arp_all_targets.is_some(),
+            &[ActiveBackup],
+        ),
+        (
+            "learn-packet-interval",
+            parameters.learn_packet_interval.is_some(),
+            &[BalanceTlb, BalanceAlb],
+        ),
+        (
+            "resend-igmp",
+            parameters.resend_igmp.is_some(),
+            &[BalanceRr, ActiveBackup, BalanceTlb, BalanceAlb],
+        ),
+    ]
+    .into_iter()
+    .filter(|(_, set, _)| *set)
+    .map(|(field, _, modes)| (field, modes))
+    .collect()
+}
+
+/// Whether a monitoring interval is set to something other than zero.
+/// Invalid intervals are reported by a separate rule, and considered enabled here.
+fn is_enabled(interval: &Option<String>) -> bool {
+    interval.as_ref().is_some_and(|interval| {
+        parse_interval(interval, DefaultUnit::Milliseconds) != Some(Duration::ZERO)
+    })
+}
+
+#[cfg(all(test, feature = "yaml"))]
+mod test {
+    use crate::NetplanConfig;
+
+    #[test]
+    fn parameters_for_mode() {
+        let input = r#"
+            network:
+              version: 2
+              bonds:
+                bond0:
+                  interfaces: [eth0, eth1]
+                  parameters:
+                    mode: active-backup
+                    lacp-rate: fast
+                    primary: eth0
+                    mii-monitor-interval: 100ms
+                    arp-interval: 0
+                    up-delay: 200
+            "#;
+
+        let config: NetplanConfig = serde_yaml::from_str(input).unwrap();
+        let paths = config
+            .validate()
+            .into_iter()
+            .filter(|issue| issue.rule.starts_with("bond-"))
+            .map(|issue| issue.path)
+            .collect::<Vec<_>>();
+
+        assert_eq!(paths, vec!["network.bonds.bond0.parameters.lacp-rate"]);
+    }
+}
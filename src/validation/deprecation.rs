@@ -0,0 +1,94 @@
+use crate::validation::{device_path, Context, Severity};
+
+const RULE: &str = "deprecated";
+
+/// Report constructs which netplan still accepts, but which have been superseded.
+pub(super) fn check_deprecated(ctx: &mut Context) {
+    for (section, name, common) in ctx.devices() {
+        let Some(common) = common else {
+            continue;
+        };
+
+        let path = device_path(section, name);
+        for (key, gateway, default) in [
+            ("gateway4", &common.gateway4, "0.0.0.0/0"),
+            ("gateway6", &common.gateway6, "::/0"),
+        ] {
+            if let Some(gateway) = gateway {
+                ctx.report(
+                    Severity::Warning,
+                    RULE,
+                    format!("{path}.{key}"),
+                    format!("{key} is deprecated in favour of a default route"),
+                )
+                .suggest(format!(
+                    "Remove {key} and add a route with `to: default` (or `to: {default}`) and `via: {gateway}`"
+                ));
+            }
+        }
+    }
+
+    for (name, wifi) in ctx.network.wifis.iter().flatten() {
+        for (ssid, access_point) in wifi.access_points.iter().flatten() {
+            if access_point.password.is_none() || access_point.auth.is_none() {
+                continue;
+            }
+
+            ctx.report(
+                Severity::Warning,
+                RULE,
+                format!("{}.access-points.{ssid}.password", device_path("wifis", name)),
+                "The scalar password is superseded by the auth block and should not be combined with it",
+            )
+            .suggest("Remove password and set auth.password instead");
+        }
+    }
+}
+
+#[cfg(all(test, feature = "yaml"))]
+mod test {
+    use crate::NetplanConfig;
+
+    #[test]
+    fn deprecated() {
+        let input = r#"
+            network:
+              version: 2
+              ethernets:
+                eth0:
+                  addresses: [10.0.0.2/24, "2001:db8::2/64"]
+                  gateway4: 10.0.0.1
+                  gateway6: 2001:db8::1
+                eth1:
+                  dhcp4: true
+              wifis:
+                wlan0:
+                  access-points:
+                    both:
+                      password: secret
+                      auth:
+                        key-management: psk
+                        password: secret
+                    scalar:
+                      password: secret
+            "#;
+
+        let config: NetplanConfig = serde_yaml::from_str(input).unwrap();
+        let mut paths = config
+            .validate()
+            .into_iter()
+            .filter(|issue| issue.rule == "deprecated")
+            .map(|issue| issue.path)
+            .collect::<Vec<_>>();
+        paths.sort();
+
+        assert_eq!(
+            paths,
+            vec![
+                "network.ethernets.eth0.gateway4",
+                "network.ethernets.eth0.gateway6",
+                "network.wifis.wlan0.access-points.both.password",
+            ]
+        );
+    }
+}
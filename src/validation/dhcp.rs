@@ -0,0 +1,134 @@
+use crate::validation::{device_path, Context, Severity};
+use crate::{DhcpOverrides, Renderer};
+
+/// If both dhcp4 and dhcp6 are enabled, the networkd backend requires that
+/// dhcp4-overrides and dhcp6-overrides contain the same keys and values.
+///
+/// Keys which are left unset are compared using their documented default,
+/// as that is what netplan does as well.
+pub(super) fn check_overrides(ctx: &mut Context) {
+    for (section, name, common) in ctx.devices() {
+        let Some(common) = common else {
+            continue;
+        };
+
+        if common.dhcp4 != Some(true) || common.dhcp6 != Some(true) {
+            continue;
+        }
+
+        if ctx.effective_renderer(Some(common)) != Renderer::Networkd {
+            continue;
+        }
+
+        let default = DhcpOverrides::default();
+        let v4 = common.dhcp4_overrides.as_ref().unwrap_or(&default);
+        let v6 = common.dhcp6_overrides.as_ref().unwrap_or(&default);
+
+        let differing = differing_keys(v4, v6);
+        if differing.is_empty() {
+            continue;
+        }
+
+        ctx.report(
+            Severity::Error,
+            "dhcp-overrides-mismatch",
+            format!("{}.dhcp6-overrides", device_path(section, name)),
+            format!(
+                "networkd requires dhcp4-overrides and dhcp6-overrides to be identical when both dhcp4 and dhcp6 are enabled, but they differ in: {}",
+                differing.join(", ")
+            ),
+        );
+    }
+}
+
+/// The keys of which the effective values differ between the two overrides.
+fn differing_keys(a: &DhcpOverrides, b: &DhcpOverrides) -> Vec<&'static str> {
+    let flag = |v: Option<bool>| v.unwrap_or(true);
+
+    let mut keys = Vec::new();
+    if flag(a.use_dns) != flag(b.use_dns) {
+        keys.push("use-dns");
+    }
+    if flag(a.use_ntp) != flag(b.use_ntp) {
+        keys.push("use-ntp");
+    }
+    if flag(a.send_hostname) != flag(b.send_hostname) {
+        keys.push("send-hostname");
+    }
+    if flag(a.use_hostname) != flag(b.use_hostname) {
+        keys.push("use-hostname");
+    }
+    if flag(a.use_mtu) != flag(b.use_mtu) {
+        keys.push("use-mtu");
+    }
+    if a.hostname != b.hostname {
+        keys.push("hostname");
+    }
+    if flag(a.use_routes) != flag(b.use_routes) {
+        keys.push("use-routes");
+    }
+    if a.route_metric != b.route_metric {
+        keys.push("route-metric");
+    }
+    if a.use_domains != b.use_domains {
+        keys.push("use-domains");
+    }
+
+    keys
+}
+
+#[cfg(all(test, feature = "yaml"))]
+mod test {
+    use crate::NetplanConfig;
+
+    #[test]
+    fn mismatching_overrides() {
+        let input = r#"
+            network:
+              version: 2
+              ethernets:
+                eth0:
+                  dhcp4: true
+                  dhcp6: true
+                  dhcp4-overrides:
+                    use-dns: false
+                    route-metric: 100
+                  dhcp6-overrides:
+                    route-metric: 100
+                eth1:
+                  dhcp4: true
+                  dhcp6: true
+                  dhcp4-overrides:
+                    use-dns: true
+            "#;
+
+        let config: NetplanConfig = serde_yaml::from_str(input).unwrap();
+        let issues = config
+            .validate()
+            .into_iter()
+            .filter(|issue| issue.rule == "dhcp-overrides-mismatch")
+            .collect::<Vec<_>>();
+
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].path, "network.ethernets.eth0.dhcp6-overrides");
+        assert!(issues[0].message.ends_with("use-dns"));
+    }
+
+    #[test]
+    fn mismatching_overrides_network_manager() {
+        let input = r#"
+            network:
+              version: 2
+              renderer: NetworkManager
+              ethernets:
+                eth0:
+                  dhcp4: true
+                  dhcp6: true
+                  dhcp4-overrides:
+                    use-routes: false
+            "#;
+
+        let config: NetplanConfig = serde_yaml::from_str(input).unwrap();
+        assert!(config.validate().is_empty());
+    }
+}
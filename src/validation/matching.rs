@@ -0,0 +1,155 @@
+use crate::validation::{device_path, Context, Severity};
+use crate::{CommonPropertiesPhysicalDeviceType, NetworkConfig};
+
+/// Report match rules with invalid globs, or with a MAC address which is not valid.
+pub(super) fn check_match_rules(ctx: &mut Context) {
+    for (section, name, physical) in physical_devices(ctx.network) {
+        let Some(rules) = physical.and_then(|p| p.r#match.as_ref()) else {
+            continue;
+        };
+
+        let path = format!("{}.match", device_path(section, name));
+        let globs = rules.name.iter().map(|name| ("name", name)).chain(
+            rules
+                .driver
+                .iter()
+                .flatten()
+                .map(|driver| ("driver", driver)),
+        );
+
+        for (field, glob) in globs {
+            if let Err(e) = crate::glob::check(glob) {
+                ctx.report(
+                    Severity::Error,
+                    "invalid-match-glob",
+                    format!("{path}.{field}"),
+                    format!("'{glob}' is not a valid glob: {e}"),
+                );
+            }
+        }
+
+        // With the `macaddr` feature, the MAC address is valid by construction
+        #[cfg(not(feature = "macaddr"))]
+        if let Some(mac) = &rules.macaddress {
+            if crate::glob::is_glob(mac) {
+                ctx.report(
+                    Severity::Error,
+                    "invalid-match-macaddress",
+                    format!("{path}.macaddress"),
+                    "Globs are not allowed when matching on a MAC address",
+                );
+            } else if let Err(e) = mac.parse::<crate::MacAddr>() {
+                ctx.report(
+                    Severity::Error,
+                    "invalid-match-macaddress",
+                    format!("{path}.macaddress"),
+                    e.to_string(),
+                );
+            }
+        }
+    }
+}
+
+/// Netplan refuses set-name without match rules. Renaming a device which is only
+/// matched by its name is unreliable, as the name no longer matches once it is renamed.
+pub(super) fn check_set_name(ctx: &mut Context) {
+    for (section, name, physical) in physical_devices(ctx.network) {
+        let Some(physical) = physical.filter(|p| p.set_name.is_some()) else {
+            continue;
+        };
+
+        let path = format!("{}.set-name", device_path(section, name));
+        match &physical.r#match {
+            None => {
+                ctx.report(
+                    Severity::Error,
+                    "set-name-without-match",
+                    path,
+                    "set-name requires match rules selecting the device to rename",
+                )
+                .suggest("Add a match block, preferably on the MAC address of the device");
+            }
+            Some(rules) if rules.macaddress.is_none() && rules.driver.is_none() => {
+                ctx.report(
+                    Severity::Warning,
+                    "set-name-without-match",
+                    path,
+                    "Renaming a device which is matched by name only is unreliable, as the name changes once the device is renamed",
+                )
+                .suggest("Match on the MAC address or driver of the device instead");
+            }
+            Some(_) => {}
+        }
+    }
+}
+
+/// The section key, ID and physical properties of every ethernet and wifi device.
+fn physical_devices(
+    network: &NetworkConfig,
+) -> impl Iterator<
+    Item = (
+        &'static str,
+        &String,
+        Option<&CommonPropertiesPhysicalDeviceType>,
+    ),
+> {
+    network
+        .ethernets
+        .iter()
+        .flatten()
+        .map(|(name, d)| ("ethernets", name, d.common_physical.as_deref()))
+        .chain(
+            network
+                .wifis
+                .iter()
+                .flatten()
+                .map(|(name, d)| ("wifis", name, d.common_physical.as_deref())),
+        )
+}
+
+#[cfg(all(test, feature = "yaml"))]
+mod test {
+    use crate::{NetplanConfig, Severity};
+
+    #[test]
+    fn set_name() {
+        let input = r#"
+            network:
+              version: 2
+              ethernets:
+                lan:
+                  set-name: lan0
+                wan:
+                  match:
+                    name: enp3s0
+                  set-name: wan0
+                mgmt:
+                  match:
+                    macaddress: "52:54:00:6b:3c:58"
+                  set-name: mgmt0
+            "#;
+
+        let config: NetplanConfig = serde_yaml::from_str(input).unwrap();
+        let mut issues = config
+            .validate()
+            .into_iter()
+            .filter(|issue| issue.rule == "set-name-without-match")
+            .map(|issue| (issue.severity, issue.path))
+            .collect::<Vec<_>>();
+        issues.sort();
+
+        assert_eq!(
+            issues,
+            vec![
+                (
+                    Severity::Error,
+                    "network.ethernets.lan.set-name".to_string()
+                ),
+                (
+                    Severity::Warning,
+                    "network.ethernets.wan.set-name".to_string()
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,271 @@
+//! Validation of netplan configurations.
+//!
+//! Netplan rejects (or silently ignores) a number of configurations which are perfectly
+//! representable by the types in this crate. The rules in this module try to catch those
+//! mistakes before the configuration is handed to netplan.
+
+mod addresses;
+mod bonds;
+mod deprecation;
+mod dhcp;
+mod matching;
+mod mtu;
+mod openvswitch;
+mod renderer;
+mod routes;
+pub(crate) use routes::default_route_family;
+mod secrets;
+mod sriov;
+mod time;
+mod tunnels;
+mod version;
+pub use version::*;
+mod wifi;
+
+use crate::{CommonPropertiesAllDevices, NetplanConfig, NetworkConfig, Renderer};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// How severe a [ValidationIssue] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum Severity {
+    /// Netplan will refuse to apply the configuration, or it cannot work as written.
+    Error,
+    /// Netplan will apply the configuration, but probably not in the way it was intended.
+    Warning,
+}
+
+/// A single problem found while validating a configuration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ValidationIssue {
+    pub severity: Severity,
+    /// Identifier of the rule which produced this issue, e.g. `dhcp-overrides-mismatch`.
+    pub rule: String,
+    /// Path to the offending field, in netplan YAML notation, e.g. `network.ethernets.eth0.dhcp6-overrides`.
+    pub path: String,
+    /// Human readable description of the problem.
+    pub message: String,
+    /// How the problem can be resolved, if there is an obvious fix.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub suggestion: Option<String>,
+}
+
+impl ValidationIssue {
+    fn suggest(&mut self, suggestion: impl Into<String>) {
+        self.suggestion = Some(suggestion.into());
+    }
+}
+
+/// The outcome of validating a configuration.
+/// With the `serde` feature enabled, the report can be serialized, e.g. to JSON for consumption by CI systems.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ValidationReport {
+    /// All issues found, in the order the rules reported them.
+    pub issues: Vec<ValidationIssue>,
+}
+
+impl ValidationReport {
+    /// Whether no issues were found.
+    pub fn is_empty(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// The number of issues found.
+    pub fn len(&self) -> usize {
+        self.issues.len()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, ValidationIssue> {
+        self.issues.iter()
+    }
+
+    /// Whether any issue is an error, meaning netplan will not accept the configuration.
+    pub fn has_errors(&self) -> bool {
+        self.errors().next().is_some()
+    }
+
+    /// All issues with [Severity::Error].
+    pub fn errors(&self) -> impl Iterator<Item = &ValidationIssue> {
+        self.iter()
+            .filter(|issue| issue.severity == Severity::Error)
+    }
+
+    /// All issues with [Severity::Warning].
+    pub fn warnings(&self) -> impl Iterator<Item = &ValidationIssue> {
+        self.iter()
+            .filter(|issue| issue.severity == Severity::Warning)
+    }
+}
+
+impl IntoIterator for ValidationReport {
+    type Item = ValidationIssue;
+    type IntoIter = std::vec::IntoIter<ValidationIssue>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.issues.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a ValidationReport {
+    type Item = &'a ValidationIssue;
+    type IntoIter = std::slice::Iter<'a, ValidationIssue>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.issues.iter()
+    }
+}
+
+/// Options influencing which rules are applied during validation.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct ValidationOptions {
+    /// The netplan release the configuration will be deployed on. When set, every field
+    /// which is not supported by that release yet is reported as an error.
+    pub target: Option<NetplanVersion>,
+    /// Report passwords and keys which are written into the configuration in plain text,
+    /// rather than referenced from a file or stored as a hash.
+    pub audit_secrets: bool,
+}
+
+impl NetplanConfig {
+    /// Validate the configuration, returning all issues found.
+    /// An empty report means no problems were detected.
+    pub fn validate(&self) -> ValidationReport {
+        self.network.validate()
+    }
+
+    /// Validate the configuration with the given options, returning all issues found.
+    pub fn validate_with(&self, options: &ValidationOptions) -> ValidationReport {
+        self.network.validate_with(options)
+    }
+}
+
+impl NetworkConfig {
+    /// Validate the configuration, returning all issues found.
+    /// An empty report means no problems were detected.
+    pub fn validate(&self) -> ValidationReport {
+        self.validate_with(&ValidationOptions::default())
+    }
+
+    /// Validate the configuration with the given options, returning all issues found.
+    pub fn validate_with(&self, options: &ValidationOptions) -> ValidationReport {
+        let mut ctx = Context::new(self, options);
+        dhcp::check_overrides(&mut ctx);
+        renderer::check_compatibility(&mut ctx);
+        renderer::check_activation_mode(&mut ctx);
+        deprecation::check_deprecated(&mut ctx);
+        version::check_target(&mut ctx);
+        addresses::check_static_addresses(&mut ctx);
+        routes::check_default_routes(&mut ctx);
+        routes::check_tables(&mut ctx);
+        routes::check_gateways(&mut ctx);
+        wifi::check_band_channel(&mut ctx);
+        wifi::check_wakeonwlan(&mut ctx);
+        bonds::check_parameters(&mut ctx);
+        mtu::check_mtu(&mut ctx);
+        time::check_intervals(&mut ctx);
+        openvswitch::check_placement(&mut ctx);
+        matching::check_match_rules(&mut ctx);
+        matching::check_set_name(&mut ctx);
+        secrets::check_plaintext(&mut ctx);
+        sriov::check_sriov(&mut ctx);
+        tunnels::check_required_fields(&mut ctx);
+        ValidationReport { issues: ctx.issues }
+    }
+}
+
+/// The section key, ID and common properties of a device definition.
+type DeviceEntry<'a> = (
+    &'static str,
+    &'a str,
+    Option<&'a CommonPropertiesAllDevices>,
+);
+
+/// State shared between the validation rules.
+struct Context<'a> {
+    network: &'a NetworkConfig,
+    options: &'a ValidationOptions,
+    issues: Vec<ValidationIssue>,
+}
+
+impl<'a> Context<'a> {
+    fn new(network: &'a NetworkConfig, options: &'a ValidationOptions) -> Self {
+        Self {
+            network,
+            options,
+            issues: Vec::new(),
+        }
+    }
+
+    fn report(
+        &mut self,
+        severity: Severity,
+        rule: &str,
+        path: impl Into<String>,
+        message: impl Into<String>,
+    ) -> &mut ValidationIssue {
+        self.issues.push(ValidationIssue {
+            severity,
+            rule: rule.to_string(),
+            path: path.into(),
+            message: message.into(),
+            suggestion: None,
+        });
+
+        self.issues.last_mut().unwrap()
+    }
+
+    /// Iterate over every device definition in the configuration, yielding the
+    /// YAML key of the section it is defined in, its ID and its common properties.
+    fn devices(&self) -> impl Iterator<Item = DeviceEntry<'a>> {
+        self.network
+            .devices()
+            .map(|(kind, name, device)| (kind.section(), name, device.common_all()))
+    }
+
+    /// The renderer which will be used for a device: the device's own renderer if set,
+    /// otherwise the global renderer, otherwise networkd.
+    fn effective_renderer(&self, common: Option<&CommonPropertiesAllDevices>) -> Renderer {
+        common
+            .and_then(|c| c.renderer.clone())
+            .or_else(|| self.network.renderer.clone())
+            .unwrap_or(Renderer::Networkd)
+    }
+}
+
+/// Build the YAML path of a device definition.
+fn device_path(section: &str, name: &str) -> String {
+    format!("network.{section}.{name}")
+}
+
+#[cfg(all(test, feature = "yaml", feature = "json"))]
+mod test {
+    use crate::NetplanConfig;
+
+    #[test]
+    fn report_to_json() {
+        let input = r#"
+            network:
+              version: 2
+              ethernets:
+                eth0:
+                  gateway4: 10.0.0.1
+            "#;
+
+        let config: NetplanConfig = serde_yaml::from_str(input).unwrap();
+        let report = config.validate();
+        assert!(!report.has_errors());
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["issues"][0]["severity"], "warning");
+        assert_eq!(json["issues"][0]["rule"], "deprecated");
+        assert_eq!(json["issues"][0]["path"], "network.ethernets.eth0.gateway4");
+    }
+}
@@ -0,0 +1,287 @@
+use crate::validation::{device_path, Context, Severity};
+use crate::CommonPropertiesAllDevices;
+
+const RULE: &str = "mtu-range";
+
+/// The smallest MTU allowed for IPv4.
+const MIN_MTU: u16 = 68;
+/// The smallest MTU allowed for IPv6.
+const MIN_IPV6_MTU: u16 = 1280;
+/// The MTU used by the kernel when none is configured.
+const DEFAULT_MTU: u16 = 1500;
+
+/// Report MTUs which are out of range for the device type, and VLANs with an
+/// MTU larger than that of their parent link.
+pub(super) fn check_mtu(ctx: &mut Context) {
+    for (section, name, common) in ctx.devices() {
+        let Some(common) = common else {
+            continue;
+        };
+
+        let path = device_path(section, name);
+        if let Some(mtu) = common.mtu {
+            if mtu < MIN_MTU {
+                ctx.report(
+                    Severity::Error,
+                    RULE,
+                    format!("{path}.mtu"),
+                    format!("An MTU of {mtu} is below the minimum of {MIN_MTU}"),
+                );
+            } else if mtu < MIN_IPV6_MTU && uses_ipv6(common) {
+                ctx.report(
+                    Severity::Warning,
+                    RULE,
+                    format!("{path}.mtu"),
+                    format!(
+                        "An MTU of {mtu} disables IPv6, which requires at least {MIN_IPV6_MTU}"
+                    ),
+                );
+            }
+
+            if let Some(max) = max_mtu(section) {
+                if mtu > max {
+                    ctx.report(
+                        Severity::Warning,
+                        RULE,
+                        format!("{path}.mtu"),
+                        format!("An MTU of {mtu} exceeds {max}, the largest MTU commonly supported by {section}"),
+                    );
+                }
+            }
+        }
+
+        if let Some(ipv6_mtu) = common.ipv6_mtu {
+            if ipv6_mtu < MIN_IPV6_MTU {
+                ctx.report(
+                    Severity::Error,
+                    RULE,
+                    format!("{path}.ipv6-mtu"),
+                    format!("An IPv6 MTU of {ipv6_mtu} is below the minimum of {MIN_IPV6_MTU}"),
+                );
+            }
+
+            let mtu = common.mtu.unwrap_or(DEFAULT_MTU);
+            if ipv6_mtu > mtu {
+                ctx.report(
+                    Severity::Warning,
+                    RULE,
+                    format!("{path}.ipv6-mtu"),
+                    format!("The IPv6 MTU of {ipv6_mtu} exceeds the link MTU of {mtu}"),
+                );
+            }
+        }
+    }
+
+    for (name, vlan) in ctx.network.vlans.iter().flatten() {
+        let Some(mtu) = vlan.common_all.as_deref().and_then(|c| c.mtu) else {
+            continue;
+        };
+        let Some(link) = &vlan.link else {
+            continue;
+        };
+        let Some((_, _, parent)) = ctx.devices().find(|(_, id, _)| id == link) else {
+            continue;
+        };
+
+        let parent_mtu = parent.and_then(|c| c.mtu).unwrap_or(DEFAULT_MTU);
+        if mtu > parent_mtu {
+            ctx.report(
+                Severity::Error,
+                RULE,
+                format!("{}.mtu", device_path("vlans", name)),
+                format!(
+                    "The MTU of {mtu} exceeds the MTU of {parent_mtu} of the parent link {link}"
+                ),
+            )
+            .suggest(format!("Raise the MTU of {link} to at least {mtu}"));
+        }
+    }
+}
+
+/// The largest MTU commonly supported by a device type, if it is limited by hardware.
+fn max_mtu(section: &str) -> Option<u16> {
+    match section {
+        "ethernets" | "bonds" | "bridges" | "vlans" => Some(9216),
+        // The maximum MSDU size of 802.11
+        "wifis" => Some(2304),
+        _ => None,
+    }
+}
+
+/// Whether IPv6 is used on a device, which is the case unless it is explicitly
+/// left without any IPv6 configuration, including link-local addressing.
+fn uses_ipv6(common: &CommonPropertiesAllDevices) -> bool {
+    let link_local = common
+        .link_local
+        .as_ref()
+        .is_none_or(|link_local| link_local.iter().any(|l| l == "ipv6"));
+    let addresses = common
+        .addresses
+        .iter()
+        .flatten()
+        .flat_map(|a| a.addresses())
+        .any(|a| a.contains(':'));
+
+    link_local || addresses || common.dhcp6 == Some(true) || common.gateway6.is_some()
+}
+
+#[cfg(all(test, feature = "yaml"))]
+mod test {
+    use crate::validation::Severity;
+    use crate::NetplanConfig;
+
+    fn issues(input: &str) -> Vec<(String, Severity)> {
+        let config: NetplanConfig = serde_yaml::from_str(input).unwrap();
+        let mut issues = config
+            .validate()
+            .into_iter()
+            .filter(|issue| issue.rule == "mtu-range")
+            .map(|issue| (issue.path, issue.severity))
+            .collect::<Vec<_>>();
+        issues.sort_by(|(a, _), (b, _)| a.cmp(b));
+        issues
+    }
+
+    #[test]
+    fn mtu_below_minimum() {
+        let issues = issues(
+            r#"
+            network:
+              version: 2
+              ethernets:
+                eth0:
+                  mtu: 60
+                eth1:
+                  mtu: 68
+                  link-local: []
+            "#,
+        );
+
+        assert_eq!(
+            issues,
+            vec![("network.ethernets.eth0.mtu".to_string(), Severity::Error)]
+        );
+    }
+
+    #[test]
+    fn mtu_disables_ipv6() {
+        let issues = issues(
+            r#"
+            network:
+              version: 2
+              ethernets:
+                eth0:
+                  mtu: 1000
+                eth1:
+                  mtu: 1000
+                  link-local: [ipv4]
+                eth2:
+                  mtu: 1000
+                  link-local: [ipv4]
+                  addresses: ["2001:db8::2/64"]
+            "#,
+        );
+
+        assert_eq!(
+            issues,
+            vec![
+                ("network.ethernets.eth0.mtu".to_string(), Severity::Warning),
+                ("network.ethernets.eth2.mtu".to_string(), Severity::Warning),
+            ]
+        );
+    }
+
+    #[test]
+    fn mtu_above_maximum() {
+        let issues = issues(
+            r#"
+            network:
+              version: 2
+              ethernets:
+                eth0:
+                  mtu: 9000
+                eth1:
+                  mtu: 9300
+              wifis:
+                wlan0:
+                  mtu: 2400
+              tunnels:
+                wg0:
+                  mode: wireguard
+                  mtu: 9300
+            "#,
+        );
+
+        assert_eq!(
+            issues,
+            vec![
+                ("network.ethernets.eth1.mtu".to_string(), Severity::Warning),
+                ("network.wifis.wlan0.mtu".to_string(), Severity::Warning),
+            ]
+        );
+    }
+
+    #[test]
+    fn ipv6_mtu() {
+        let issues = issues(
+            r#"
+            network:
+              version: 2
+              ethernets:
+                eth0:
+                  ipv6-mtu: 1200
+                eth1:
+                  ipv6-mtu: 1600
+                eth2:
+                  mtu: 9000
+                  ipv6-mtu: 1600
+            "#,
+        );
+
+        assert_eq!(
+            issues,
+            vec![
+                (
+                    "network.ethernets.eth0.ipv6-mtu".to_string(),
+                    Severity::Error
+                ),
+                (
+                    "network.ethernets.eth1.ipv6-mtu".to_string(),
+                    Severity::Warning
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn vlan_mtu_exceeds_parent() {
+        let issues = issues(
+            r#"
+            network:
+              version: 2
+              ethernets:
+                eth0: {}
+                eth1:
+                  mtu: 9000
+              vlans:
+                vlan10:
+                  id: 10
+                  link: eth0
+                  mtu: 9000
+                vlan20:
+                  id: 20
+                  link: eth1
+                  mtu: 9000
+                vlan30:
+                  id: 30
+                  link: eth0
+                  mtu: 1400
+            "#,
+        );
+
+        assert_eq!(
+            issues,
+            vec![("network.vlans.vlan10.mtu".to_string(), Severity::Error)]
+        );
+    }
+}
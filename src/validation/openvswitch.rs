@@ -0,0 +1,168 @@
+use crate::validation::{device_path, Context, Severity};
+use crate::OpenVSwitchConfig;
+
+/// Where an openvswitch mapping is declared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Scope {
+    Global,
+    Bond,
+    Bridge,
+    Device,
+}
+
+impl Scope {
+    fn describe(scopes: &[Scope]) -> String {
+        scopes
+            .iter()
+            .map(|scope| match scope {
+                Scope::Global => "the network section",
+                Scope::Bond => "bonds",
+                Scope::Bridge => "bridges",
+                Scope::Device => "devices",
+            })
+            .collect::<Vec<_>>()
+            .join(" or ")
+    }
+}
+
+/// Report openvswitch settings which are declared in a place where they are not valid.
+pub(super) fn check_placement(ctx: &mut Context) {
+    let network = ctx.network;
+    let mut mappings = Vec::new();
+
+    if let Some(ovs) = &network.openvswitch {
+        mappings.push(("network.openvswitch".to_string(), Scope::Global, ovs));
+    }
+
+    for (name, bond) in network.bonds.iter().flatten() {
+        if let Some(ovs) = &bond.openvswitch {
+            mappings.push((openvswitch_path("bonds", name), Scope::Bond, ovs));
+        }
+    }
+
+    for (name, bridge) in network.bridges.iter().flatten() {
+        if let Some(ovs) = &bridge.openvswitch {
+            mappings.push((openvswitch_path("bridges", name), Scope::Bridge, ovs));
+        }
+    }
+
+    for (name, vlan) in network.vlans.iter().flatten() {
+        if let Some(ovs) = &vlan.openvswitch {
+            mappings.push((openvswitch_path("vlans", name), Scope::Device, ovs));
+        }
+    }
+
+    let physical = network
+        .ethernets
+        .iter()
+        .flatten()
+        .map(|(name, d)| ("ethernets", name, d.common_physical.as_deref()))
+        .chain(
+            network
+                .wifis
+                .iter()
+                .flatten()
+                .map(|(name, d)| ("wifis", name, d.common_physical.as_deref())),
+        );
+
+    for (section, name, physical) in physical {
+        if let Some(ovs) = physical.and_then(|p| p.openvswitch.as_ref()) {
+            mappings.push((openvswitch_path(section, name), Scope::Device, ovs));
+        }
+    }
+
+    for (path, scope, ovs) in mappings {
+        for (field, allowed) in scoped_fields(ovs) {
+            if !allowed.contains(&scope) {
+                ctx.report(
+                    Severity::Error,
+                    "openvswitch-misplaced",
+                    format!("{path}.{field}"),
+                    format!(
+                        "The openvswitch setting {field} is only valid on {}",
+                        Scope::describe(allowed)
+                    ),
+                );
+            }
+        }
+    }
+}
+
+fn openvswitch_path(section: &str, name: &str) -> String {
+    format!("{}.openvswitch", device_path(section, name))
+}
+
+/// The fields of the mapping which are set and restricted to certain scopes,
+/// together with the scopes in which they are valid.
+fn scoped_fields(ovs: &OpenVSwitchConfig) -> Vec<(&'static str, &'static [Scope])> {
+    use Scope::*;
+
+    [
+        ("lacp", ovs.lacp.is_some(), &[Bond][..]),
+        ("fail-mode", ovs.fail_mode.is_some(), &[Bridge]),
+        ("mcast-snooping", ovs.mcast_snooping.is_some(), &[Bridge]),
+        ("rstp", ovs.rtsp.is_some(), &[Bridge]),
+        ("controller", ovs.controller.is_some(), &[Bridge]),
+        ("protocols", ovs.protocols.is_some(), &[Bridge, Global]),
+        ("ports", ovs.ports.is_some(), &[Global]),
+        ("ssl", ovs.ssl.is_some(), &[Global]),
+    ]
+    .into_iter()
+    .filter(|(_, set, _)| *set)
+    .map(|(field, _, scopes)| (field, scopes))
+    .collect()
+}
+
+#[cfg(all(test, feature = "yaml"))]
+mod test {
+    use crate::NetplanConfig;
+
+    #[test]
+    fn misplaced_settings() {
+        let input = r#"
+            network:
+              version: 2
+              openvswitch:
+                protocols: [OpenFlow13]
+                fail-mode: secure
+              ethernets:
+                eth0:
+                  openvswitch:
+                    lacp: active
+                eth1: {}
+              bonds:
+                bond0:
+                  interfaces: [eth0, eth1]
+                  openvswitch:
+                    lacp: active
+                    mcast-snooping: true
+              bridges:
+                br0:
+                  interfaces: [bond0]
+                  openvswitch:
+                    fail-mode: standalone
+                    rstp: true
+                    protocols: [OpenFlow13]
+                    ports: [patch0-1, patch1-0]
+            "#;
+
+        let config: NetplanConfig = serde_yaml::from_str(input).unwrap();
+        let mut paths = config
+            .validate()
+            .into_iter()
+            .filter(|issue| issue.rule == "openvswitch-misplaced")
+            .map(|issue| issue.path)
+            .collect::<Vec<_>>();
+        paths.sort();
+
+        assert_eq!(
+            paths,
+            vec![
+                "network.bonds.bond0.openvswitch.mcast-snooping",
+                "network.bridges.br0.openvswitch.ports",
+                "network.ethernets.eth0.openvswitch.lacp",
+                "network.openvswitch.fail-mode",
+            ]
+        );
+    }
+}
@@ -0,0 +1,449 @@
+use crate::validation::{device_path, Context, Severity};
+use crate::{
+    AccessPointMode, ActivationMode, AddressMapping, CommonPropertiesAllDevices,
+    CommonPropertiesPhysicalDeviceType, DhcpOverrides, Renderer, TunnelMode, WakeOnWLan,
+};
+
+const RULE_FIELD: &str = "renderer-unsupported-field";
+const RULE_TUNNEL_MODE: &str = "renderer-unsupported-tunnel-mode";
+const RULE_DEVICE: &str = "renderer-unsupported-device";
+const RULE_WIFI_MODE: &str = "renderer-unsupported-wifi-mode";
+
+/// Report fields which are not supported by the renderer a device will effectively use.
+/// Fields which the backend silently ignores are reported as warnings, fields which make
+/// netplan reject the configuration are reported as errors.
+pub(super) fn check_compatibility(ctx: &mut Context) {
+    for (section, name, common) in ctx.devices() {
+        let Some(common) = common else {
+            continue;
+        };
+
+        let renderer = ctx.effective_renderer(Some(common));
+        let path = device_path(section, name);
+        let fields = match renderer {
+            Renderer::Networkd => nm_only_fields(common),
+            Renderer::NetworkManager => networkd_only_fields(common),
+            Renderer::Sriov => continue,
+        };
+
+        report_ignored(ctx, &path, &renderer, fields);
+    }
+
+    let network = ctx.network;
+    let physical = network
+        .ethernets
+        .iter()
+        .flatten()
+        .map(|(name, d)| {
+            (
+                "ethernets",
+                name,
+                d.common_all.as_deref(),
+                d.common_physical.as_deref(),
+            )
+        })
+        .chain(network.wifis.iter().flatten().map(|(name, d)| {
+            (
+                "wifis",
+                name,
+                d.common_all.as_deref(),
+                d.common_physical.as_deref(),
+            )
+        }));
+
+    for (section, name, common, physical) in physical {
+        let Some(physical) = physical else {
+            continue;
+        };
+
+        let renderer = ctx.effective_renderer(common);
+        if renderer == Renderer::NetworkManager {
+            let path = device_path(section, name);
+            report_ignored(
+                ctx,
+                &path,
+                &renderer,
+                networkd_only_physical_fields(physical),
+            );
+        }
+    }
+
+    for (name, wifi) in network.wifis.iter().flatten() {
+        let renderer = ctx.effective_renderer(wifi.common_all.as_deref());
+        let uses_tcp = wifi
+            .wakeonwlan
+            .iter()
+            .flatten()
+            .any(|flag| *flag == WakeOnWLan::Tcp);
+
+        if renderer == Renderer::Networkd && uses_tcp {
+            let path = device_path("wifis", name);
+            report_ignored(ctx, &path, &renderer, vec!["wakeonwlan.tcp".to_string()]);
+        }
+
+        if renderer != Renderer::Networkd {
+            continue;
+        }
+
+        // Ad-hoc networks are supported by networkd through wpa_supplicant, access points are not
+        for (ssid, access_point) in wifi.access_points.iter().flatten() {
+            if access_point.mode == Some(AccessPointMode::Ap) {
+                ctx.report(
+                    Severity::Error,
+                    RULE_WIFI_MODE,
+                    format!("{}.access-points.{ssid}.mode", device_path("wifis", name)),
+                    "Access point mode is not supported by networkd",
+                )
+                .suggest("Set renderer to NetworkManager for this device");
+            }
+        }
+    }
+
+    for (name, modem) in network.modems.iter().flatten() {
+        let renderer = ctx.effective_renderer(modem.common_all.as_deref());
+        if renderer != Renderer::NetworkManager {
+            ctx.report(
+                Severity::Error,
+                RULE_DEVICE,
+                device_path("modems", name),
+                format!("Modems are not supported by {}", renderer.as_str()),
+            )
+            .suggest("Set renderer to NetworkManager for this device");
+        }
+    }
+
+    for (name, tunnel) in network.tunnels.iter().flatten() {
+        let Some(mode) = &tunnel.mode else {
+            continue;
+        };
+
+        let renderer = ctx.effective_renderer(tunnel.common_all.as_deref());
+        let supported = match mode {
+            TunnelMode::Gretap | TunnelMode::Ip6gretap => renderer != Renderer::NetworkManager,
+            TunnelMode::Isatap => renderer != Renderer::Networkd,
+            _ => true,
+        };
+
+        if !supported {
+            ctx.report(
+                Severity::Error,
+                RULE_TUNNEL_MODE,
+                format!("{}.mode", device_path("tunnels", name)),
+                format!(
+                    "Tunnel mode {} is not supported by {}",
+                    mode.as_str(),
+                    renderer.as_str()
+                ),
+            );
+        }
+    }
+}
+
+/// activation-mode off is only implemented by networkd, and requires systemd v248 or later.
+pub(super) fn check_activation_mode(ctx: &mut Context) {
+    for (section, name, common) in ctx.devices() {
+        let Some(common) = common.filter(|c| c.activation_mode == Some(ActivationMode::Off)) else {
+            continue;
+        };
+
+        let renderer = ctx.effective_renderer(Some(common));
+        if renderer == Renderer::Networkd {
+            continue;
+        }
+
+        ctx.report(
+            Severity::Error,
+            "activation-mode-unsupported",
+            format!("{}.activation-mode", device_path(section, name)),
+            format!(
+                "activation-mode off is not supported by {}, it requires networkd from systemd v248 or later",
+                renderer.as_str()
+            ),
+        )
+        .suggest("Use activation-mode manual, or render the device with networkd");
+    }
+}
+
+fn report_ignored(ctx: &mut Context, path: &str, renderer: &Renderer, fields: Vec<String>) {
+    for field in fields {
+        ctx.report(
+            Severity::Warning,
+            RULE_FIELD,
+            format!("{path}.{field}"),
+            format!(
+                "{field} is not supported by {} and will be ignored",
+                renderer.as_str()
+            ),
+        );
+    }
+}
+
+/// Fields of the common properties which only have an effect with NetworkManager.
+fn nm_only_fields(common: &CommonPropertiesAllDevices) -> Vec<String> {
+    set_fields([(
+        common.ipv6_address_generation.is_some(),
+        "ipv6-address-generation",
+    )])
+}
+
+/// Fields of the common properties which only have an effect with networkd.
+fn networkd_only_fields(common: &CommonPropertiesAllDevices) -> Vec<String> {
+    let complex_addresses = common
+        .addresses
+        .iter()
+        .flatten()
+        .any(|address| matches!(address, AddressMapping::Complex(_)));
+
+    let mut fields = set_fields([
+        (common.ipv6_mtu.is_some(), "ipv6-mtu"),
+        (common.ignore_carrier.is_some(), "ignore-carrier"),
+        (common.critical.is_some(), "critical"),
+        (common.dhcp_identifier.is_some(), "dhcp-identifier"),
+        (common.optional.is_some(), "optional"),
+        (complex_addresses, "addresses"),
+    ]);
+
+    for (key, overrides) in [
+        ("dhcp4-overrides", &common.dhcp4_overrides),
+        ("dhcp6-overrides", &common.dhcp6_overrides),
+    ] {
+        if let Some(overrides) = overrides {
+            fields.extend(
+                networkd_only_overrides(overrides)
+                    .into_iter()
+                    .map(|field| format!("{key}.{field}")),
+            );
+        }
+    }
+
+    fields
+}
+
+/// DHCP overrides which only have an effect with networkd. Only use-routes
+/// and route-metric are supported by NetworkManager.
+fn networkd_only_overrides(overrides: &DhcpOverrides) -> Vec<String> {
+    set_fields([
+        (overrides.use_dns.is_some(), "use-dns"),
+        (overrides.use_ntp.is_some(), "use-ntp"),
+        (overrides.send_hostname.is_some(), "send-hostname"),
+        (overrides.use_hostname.is_some(), "use-hostname"),
+        (overrides.use_mtu.is_some(), "use-mtu"),
+        (overrides.hostname.is_some(), "hostname"),
+        (overrides.use_domains.is_some(), "use-domains"),
+    ])
+}
+
+/// Fields of the physical device properties which only have an effect with networkd.
+fn networkd_only_physical_fields(physical: &CommonPropertiesPhysicalDeviceType) -> Vec<String> {
+    set_fields([
+        (physical.emit_lldp.is_some(), "emit-lldp"),
+        (
+            physical.receive_checksum_offload.is_some(),
+            "receive-checksum-offload",
+        ),
+        (
+            physical.transmit_checksum_offload.is_some(),
+            "transmit-checksum-offload",
+        ),
+        (
+            physical.tcp_segmentation_offload.is_some(),
+            "tcp-segmentation-offload",
+        ),
+        (
+            physical.tcp6_segmentation_offload.is_some(),
+            "tcp6-segmentation-offload",
+        ),
+        (
+            physical.generic_segmentation_offload.is_some(),
+            "generic-segmentation-offload",
+        ),
+        (
+            physical.generic_receive_offload.is_some(),
+            "generic-receive-offload",
+        ),
+        (
+            physical.large_receive_offload.is_some(),
+            "large-receive-offload",
+        ),
+        (
+            physical
+                .r#match
+                .as_ref()
+                .map(|m| m.driver.is_some())
+                .unwrap_or(false),
+            "match.driver",
+        ),
+    ])
+}
+
+/// The names of the fields which are set.
+fn set_fields<const N: usize>(fields: [(bool, &str); N]) -> Vec<String> {
+    fields
+        .into_iter()
+        .filter(|(set, _)| *set)
+        .map(|(_, field)| field.to_string())
+        .collect()
+}
+
+#[cfg(all(test, feature = "yaml"))]
+mod test {
+    use crate::{NetplanConfig, Severity};
+
+    #[test]
+    fn network_manager_ignores_networkd_fields() {
+        let input = r#"
+            network:
+              version: 2
+              renderer: NetworkManager
+              ethernets:
+                eth0:
+                  ignore-carrier: true
+                  emit-lldp: true
+                  dhcp4-overrides:
+                    use-dns: false
+                    use-routes: false
+              tunnels:
+                tun0:
+                  mode: gretap
+            "#;
+
+        let config: NetplanConfig = serde_yaml::from_str(input).unwrap();
+        let mut paths = config
+            .validate()
+            .into_iter()
+            .filter(|issue| issue.rule.starts_with("renderer-"))
+            .map(|issue| (issue.severity, issue.path))
+            .collect::<Vec<_>>();
+        paths.sort();
+
+        assert_eq!(
+            paths,
+            vec![
+                (Severity::Error, "network.tunnels.tun0.mode".to_string()),
+                (
+                    Severity::Warning,
+                    "network.ethernets.eth0.dhcp4-overrides.use-dns".to_string()
+                ),
+                (
+                    Severity::Warning,
+                    "network.ethernets.eth0.emit-lldp".to_string()
+                ),
+                (
+                    Severity::Warning,
+                    "network.ethernets.eth0.ignore-carrier".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn networkd_unsupported_devices() {
+        let input = r#"
+            network:
+              version: 2
+              wifis:
+                wlan0:
+                  access-points:
+                    hotspot:
+                      mode: ap
+                    mesh:
+                      mode: adhoc
+              modems:
+                cdc-wdm1:
+                  apn: internet
+            "#;
+
+        let config: NetplanConfig = serde_yaml::from_str(input).unwrap();
+        let mut paths = config
+            .validate()
+            .into_iter()
+            .filter(|issue| issue.rule.starts_with("renderer-"))
+            .map(|issue| (issue.rule, issue.path))
+            .collect::<Vec<_>>();
+        paths.sort();
+
+        assert_eq!(
+            paths,
+            vec![
+                (
+                    "renderer-unsupported-device".to_string(),
+                    "network.modems.cdc-wdm1".to_string()
+                ),
+                (
+                    "renderer-unsupported-wifi-mode".to_string(),
+                    "network.wifis.wlan0.access-points.hotspot.mode".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn networkd_access_point_mode() {
+        let input = r#"
+            network:
+              version: 2
+              wifis:
+                wlan0:
+                  access-points:
+                    hotspot:
+                      mode: ap
+                    home:
+                      mode: infrastructure
+                wlan1:
+                  renderer: NetworkManager
+                  access-points:
+                    hotspot:
+                      mode: ap
+            "#;
+
+        let config: NetplanConfig = serde_yaml::from_str(input).unwrap();
+        let paths = config
+            .validate()
+            .into_iter()
+            .filter(|issue| issue.rule == "renderer-unsupported-wifi-mode")
+            .map(|issue| issue.path)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            paths,
+            vec!["network.wifis.wlan0.access-points.hotspot.mode"]
+        );
+    }
+
+    #[test]
+    fn activation_mode_off() {
+        let input = r#"
+            network:
+              version: 2
+              renderer: NetworkManager
+              ethernets:
+                eth0:
+                  activation-mode: "off"
+                eth1:
+                  activation-mode: manual
+                eth2:
+                  renderer: networkd
+                  activation-mode: "off"
+              bridges:
+                br0:
+                  activation-mode: "off"
+            "#;
+
+        let config: NetplanConfig = serde_yaml::from_str(input).unwrap();
+        let mut paths = config
+            .validate()
+            .into_iter()
+            .filter(|issue| issue.rule == "activation-mode-unsupported")
+            .map(|issue| issue.path)
+            .collect::<Vec<_>>();
+        paths.sort();
+
+        assert_eq!(
+            paths,
+            vec![
+                "network.bridges.br0.activation-mode",
+                "network.ethernets.eth0.activation-mode",
+            ]
+        );
+    }
+}
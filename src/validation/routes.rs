@@ -0,0 +1,372 @@
+use crate::validation::{device_path, Context, Severity};
+use crate::{RouteType, RoutingConfig};
+use ipnet::IpNet;
+use std::collections::BTreeSet;
+use std::net::IpAddr;
+
+/// The ID of the main routing table.
+const MAIN_TABLE: u16 = 254;
+
+/// The routing tables the kernel always provides: default, main and local.
+const WELL_KNOWN_TABLES: [u16; 3] = [253, MAIN_TABLE, 255];
+
+/// Report gateways, either set with gateway4/gateway6 or as the via of a route, which are
+/// not within any subnet of the device's static addresses and are not marked on-link.
+/// Address families which are configured dynamically are skipped, as the subnet is not known.
+pub(super) fn check_gateways(ctx: &mut Context) {
+    for (section, name, common) in ctx.devices() {
+        let Some(common) = common else {
+            continue;
+        };
+
+        let path = device_path(section, name);
+        let subnets = common
+            .addresses
+            .iter()
+            .flatten()
+            .flat_map(|a| a.addresses())
+            .filter_map(|a| a.parse::<IpNet>().ok())
+            .collect::<Vec<_>>();
+
+        let dynamic = |gateway: &IpAddr| match gateway {
+            IpAddr::V4(_) => common.dhcp4 == Some(true),
+            IpAddr::V6(_) => common.dhcp6 == Some(true) || common.accept_ra == Some(true),
+        };
+
+        let gateways = [
+            ("gateway4", &common.gateway4),
+            ("gateway6", &common.gateway6),
+        ]
+        .into_iter()
+        .filter_map(|(key, gateway)| Some((format!("{path}.{key}"), gateway.as_ref()?, false)));
+        let vias = common
+            .routes
+            .iter()
+            .flatten()
+            .enumerate()
+            .filter_map(|(i, route)| {
+                let on_link = route.on_link == Some(true);
+                Some((
+                    format!("{path}.routes.{i}.via"),
+                    route.via.as_ref()?,
+                    on_link,
+                ))
+            });
+
+        for (path, gateway, on_link) in gateways.chain(vias) {
+            let Ok(address) = gateway.parse::<IpAddr>() else {
+                continue;
+            };
+
+            let link_local = match address {
+                IpAddr::V4(_) => false,
+                IpAddr::V6(v6) => v6.is_unicast_link_local(),
+            };
+
+            if on_link || link_local || dynamic(&address) {
+                continue;
+            }
+
+            if subnets.iter().any(|subnet| subnet.contains(&address)) {
+                continue;
+            }
+
+            ctx.report(
+                Severity::Warning,
+                "unreachable-gateway",
+                path,
+                format!("Gateway {address} is not within any subnet configured on this device"),
+            )
+            .suggest("Add an address in the gateway's subnet, or set on-link on the route");
+        }
+    }
+}
+
+/// Report routing tables which are referenced inconsistently: routing policy rules pointing
+/// at a table no route populates, routes in a table nothing refers to, and routes or rules
+/// of a VRF using a table other than the VRF's own.
+pub(super) fn check_tables(ctx: &mut Context) {
+    let network = ctx.network;
+    let vrf_tables = network
+        .vrfs
+        .iter()
+        .flatten()
+        .map(|(_, vrf)| vrf.table)
+        .collect::<BTreeSet<_>>();
+
+    let mut route_tables = BTreeSet::new();
+    let mut policy_tables = BTreeSet::new();
+    for (_, _, common) in ctx.devices() {
+        let Some(common) = common else {
+            continue;
+        };
+
+        route_tables.extend(common.routes.iter().flatten().filter_map(|r| r.table));
+        policy_tables.extend(common.routing_policy.iter().flatten().map(|p| p.table));
+    }
+
+    let known =
+        |table: u16| WELL_KNOWN_TABLES.contains(&table) || vrf_tables.contains(&i32::from(table));
+
+    for (section, name, common) in ctx.devices() {
+        let Some(common) = common else {
+            continue;
+        };
+
+        let path = device_path(section, name);
+        let vrf_table = match section {
+            "vrfs" => network.vrfs.as_ref().map(|vrfs| vrfs[name].table),
+            _ => None,
+        };
+
+        let routes = common
+            .routes
+            .iter()
+            .flatten()
+            .enumerate()
+            .filter_map(|(i, r)| Some((format!("{path}.routes.{i}.table"), r.table?, true)));
+        let policies = common
+            .routing_policy
+            .iter()
+            .flatten()
+            .enumerate()
+            .map(|(i, p)| (format!("{path}.routing-policy.{i}.table"), p.table, false));
+
+        for (path, table, is_route) in routes.chain(policies) {
+            if let Some(vrf_table) = vrf_table.filter(|vrf_table| *vrf_table != i32::from(table)) {
+                ctx.report(
+                    Severity::Error,
+                    "routing-table-mismatch",
+                    path,
+                    format!("Table {table} differs from the table of the VRF, {vrf_table}"),
+                )
+                .suggest(format!("Set the table to {vrf_table}, or remove it"));
+            } else if known(table) {
+                continue;
+            } else if is_route && !policy_tables.contains(&table) {
+                ctx.report(
+                    Severity::Warning,
+                    "routing-table-unreferenced",
+                    path,
+                    format!("No routing policy rule or VRF refers to table {table}, so this route is never used"),
+                )
+                .suggest(format!("Add a routing-policy rule with table {table}"));
+            } else if !is_route && !route_tables.contains(&table) {
+                ctx.report(
+                    Severity::Warning,
+                    "routing-table-unpopulated",
+                    path,
+                    format!("No route populates table {table}"),
+                )
+                .suggest(format!("Add routes with table {table}"));
+            }
+        }
+    }
+}
+
+/// Report address families for which more than one default route is configured.
+pub(super) fn check_default_routes(ctx: &mut Context) {
+    let providers = ctx.network.default_route_providers();
+    for (providers, family) in [(providers.ipv4, "IPv4"), (providers.ipv6, "IPv6")] {
+        if providers.len() < 2 {
+            continue;
+        }
+
+        let mut providers = providers
+            .iter()
+            .map(|p| (device_path(p.kind.section(), p.device), p))
+            .collect::<Vec<_>>();
+        providers.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        let ambiguous = providers
+            .iter()
+            .enumerate()
+            .any(|(i, (_, a))| providers[i + 1..].iter().any(|(_, b)| a.metric == b.metric));
+
+        let listing = providers
+            .iter()
+            .map(|(path, p)| {
+                let metric = p
+                    .metric
+                    .map(|m| m.to_string())
+                    .unwrap_or_else(|| "default".to_string());
+                format!("{path} ({}, metric {metric})", p.mechanism)
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let message = if ambiguous {
+            format!("Multiple {family} default routes with the same metric make the uplink ambiguous: {listing}")
+        } else {
+            format!("Multiple {family} default routes are configured: {listing}")
+        };
+
+        ctx.report(
+            Severity::Warning,
+            "multiple-default-routes",
+            providers[0].0.clone(),
+            message,
+        )
+        .suggest("Keep a single default route per address family, or use routing-policy to select between them");
+    }
+}
+
+/// If the route is a unicast default route in the main table, whether it is an IPv6 route.
+pub(crate) fn default_route_family(route: &RoutingConfig) -> Option<bool> {
+    if !matches!(route.r#type, None | Some(RouteType::Unicast)) {
+        return None;
+    }
+
+    if !matches!(route.table, None | Some(MAIN_TABLE)) {
+        return None;
+    }
+
+    match route.to.as_deref()? {
+        "0.0.0.0/0" => Some(false),
+        "::/0" => Some(true),
+        "default" => route
+            .via
+            .as_deref()
+            .and_then(|via| via.parse::<IpAddr>().ok())
+            .map(|via| via.is_ipv6()),
+        _ => None,
+    }
+}
+
+#[cfg(all(test, feature = "yaml"))]
+mod test {
+    use crate::NetplanConfig;
+
+    #[test]
+    fn multiple_default_routes() {
+        let input = r#"
+            network:
+              version: 2
+              ethernets:
+                eth0:
+                  dhcp4: true
+                  dhcp4-overrides:
+                    route-metric: 100
+                eth1:
+                  addresses: [10.0.0.2/24]
+                  routes:
+                    - to: default
+                      via: 10.0.0.1
+                      metric: 100
+                    - to: default
+                      via: 10.0.0.1
+                      table: 100
+                eth2:
+                  dhcp4: true
+                  dhcp4-overrides:
+                    use-routes: false
+            "#;
+
+        let config: NetplanConfig = serde_yaml::from_str(input).unwrap();
+        let issues = config
+            .validate()
+            .into_iter()
+            .filter(|issue| issue.rule == "multiple-default-routes")
+            .collect::<Vec<_>>();
+
+        assert_eq!(issues.len(), 1);
+        assert_eq!(
+            issues[0].message,
+            "Multiple IPv4 default routes with the same metric make the uplink ambiguous: \
+            network.ethernets.eth0 (dhcp4, metric 100), network.ethernets.eth1 (route, metric 100)"
+        );
+    }
+
+    #[test]
+    fn routing_tables() {
+        let input = r#"
+            network:
+              version: 2
+              ethernets:
+                eth0:
+                  routes:
+                    - to: 10.1.0.0/16
+                      via: 10.0.0.1
+                      table: 100
+                    - to: 10.2.0.0/16
+                      via: 10.0.0.1
+                      table: 101
+                  routing-policy:
+                    - from: 10.0.0.0/24
+                      table: 100
+                    - from: 10.0.1.0/24
+                      table: 102
+              vrfs:
+                vrf0:
+                  table: 1000
+                  interfaces: [eth0]
+                  routes:
+                    - to: default
+                      via: 10.0.0.1
+                      table: 1001
+            "#;
+
+        let config: NetplanConfig = serde_yaml::from_str(input).unwrap();
+        let mut issues = config
+            .validate()
+            .into_iter()
+            .filter(|issue| issue.rule.starts_with("routing-table-"))
+            .map(|issue| (issue.rule, issue.path))
+            .collect::<Vec<_>>();
+        issues.sort();
+
+        assert_eq!(
+            issues,
+            vec![
+                (
+                    "routing-table-mismatch".to_string(),
+                    "network.vrfs.vrf0.routes.0.table".to_string()
+                ),
+                (
+                    "routing-table-unpopulated".to_string(),
+                    "network.ethernets.eth0.routing-policy.1.table".to_string()
+                ),
+                (
+                    "routing-table-unreferenced".to_string(),
+                    "network.ethernets.eth0.routes.1.table".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn unreachable_gateways() {
+        let input = r#"
+            network:
+              version: 2
+              ethernets:
+                eth0:
+                  addresses: [10.0.0.2/24, "2001:db8::2/64"]
+                  routes:
+                    - to: default
+                      via: 10.0.1.1
+                    - to: 192.168.0.0/16
+                      via: 10.0.0.1
+                    - to: 172.16.0.0/12
+                      via: 172.16.0.1
+                      on-link: true
+                    - to: default
+                      via: fe80::1
+                eth1:
+                  dhcp4: true
+                  routes:
+                    - to: default
+                      via: 192.168.1.1
+            "#;
+
+        let config: NetplanConfig = serde_yaml::from_str(input).unwrap();
+        let paths = config
+            .validate()
+            .into_iter()
+            .filter(|issue| issue.rule == "unreachable-gateway")
+            .map(|issue| issue.path)
+            .collect::<Vec<_>>();
+
+        assert_eq!(paths, vec!["network.ethernets.eth0.routes.0.via"]);
+    }
+}
@@ -0,0 +1,189 @@
+use crate::validation::{device_path, Context, Severity};
+use crate::{AuthConfig, KeyManagmentMode, Renderer, TunnelKey, TunnelMode};
+
+const RULE: &str = "plaintext-secret";
+
+/// Report secrets which are written into the configuration in plain text.
+/// Only applied when [ValidationOptions::audit_secrets](crate::ValidationOptions::audit_secrets) is set.
+pub(super) fn check_plaintext(ctx: &mut Context) {
+    if !ctx.options.audit_secrets {
+        return;
+    }
+
+    let network = ctx.network;
+    for (name, wifi) in network.wifis.iter().flatten() {
+        for (ssid, access_point) in wifi.access_points.iter().flatten() {
+            let path = format!("{}.access-points.{ssid}", device_path("wifis", name));
+            if let Some(password) = &access_point.password {
+                report_password(ctx, &format!("{path}.password"), password, true);
+            }
+
+            if let Some(auth) = &access_point.auth {
+                check_auth(ctx, &format!("{path}.auth"), auth);
+            }
+        }
+    }
+
+    for (name, modem) in network.modems.iter().flatten() {
+        let path = device_path("modems", name);
+        for (key, secret) in [("password", &modem.password), ("pin", &modem.pin)] {
+            if secret.is_some() {
+                ctx.report(
+                    Severity::Warning,
+                    RULE,
+                    format!("{path}.{key}"),
+                    format!("The modem {key} is stored in plain text"),
+                )
+                .suggest(format!(
+                    "Remove {key} and let NetworkManager request it from a secret agent"
+                ));
+            }
+        }
+    }
+
+    for (name, tunnel) in network.tunnels.iter().flatten() {
+        if tunnel.mode != Some(TunnelMode::Wireguard) {
+            continue;
+        }
+
+        let path = device_path("tunnels", name);
+        let file_supported =
+            ctx.effective_renderer(tunnel.common_all.as_deref()) == Renderer::Networkd;
+
+        let private = match &tunnel.key {
+            Some(TunnelKey::Simple(key)) => Some((format!("{path}.key"), key)),
+            Some(TunnelKey::Complex {
+                private: Some(key), ..
+            }) => Some((format!("{path}.key.private"), key)),
+            _ => None,
+        };
+
+        if let Some((path, key)) = private {
+            report_key(ctx, path, key, "private key", file_supported);
+        }
+
+        for (index, peer) in tunnel.peers.iter().enumerate() {
+            if let Some(shared) = peer.keys.as_ref().and_then(|keys| keys.shared.as_ref()) {
+                let path = format!("{path}.peers.{index}.keys.shared");
+                report_key(ctx, path, shared, "preshared key", file_supported);
+            }
+        }
+    }
+}
+
+fn check_auth(ctx: &mut Context, path: &str, auth: &AuthConfig) {
+    if let Some(password) = &auth.password {
+        let psk =
+            auth.key_management.is_none() || auth.key_management == Some(KeyManagmentMode::Psk);
+        report_password(ctx, &format!("{path}.password"), password, psk);
+    }
+
+    if auth.client_key_password.is_some() {
+        ctx.report(
+            Severity::Warning,
+            RULE,
+            format!("{path}.client-key-password"),
+            "The password of the client key is stored in plain text",
+        )
+        .suggest("Store the client key unencrypted in a file only readable by root, and remove client-key-password");
+    }
+}
+
+fn report_password(ctx: &mut Context, path: &str, password: &str, psk: bool) {
+    if psk && is_hashed_psk(password) {
+        return;
+    }
+
+    let issue = ctx.report(
+        Severity::Warning,
+        RULE,
+        path,
+        "The password is stored in plain text",
+    );
+
+    if psk {
+        issue.suggest("Use the 64 hex digit pre-shared key derived from the passphrase and SSID instead, e.g. using wpa_passphrase");
+    } else {
+        issue.suggest(
+            "Use a password hash in the form `hash:<NtPasswordHash>` if the EAP method supports it",
+        );
+    }
+}
+
+fn report_key(ctx: &mut Context, path: String, key: &str, kind: &str, file_supported: bool) {
+    if key.starts_with('/') {
+        return;
+    }
+
+    let issue = ctx.report(
+        Severity::Warning,
+        RULE,
+        path,
+        format!("The WireGuard {kind} is stored in plain text"),
+    );
+
+    if file_supported {
+        issue.suggest(format!(
+            "Store the {kind} in a file only readable by root, and set the absolute path to that file instead"
+        ));
+    }
+}
+
+/// Whether the password is a PSK derived from the passphrase, rather than the passphrase itself.
+fn is_hashed_psk(password: &str) -> bool {
+    password.len() == 64 && password.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+#[cfg(all(test, feature = "yaml"))]
+mod test {
+    use crate::{NetplanConfig, ValidationOptions};
+
+    #[test]
+    fn plaintext_secrets() {
+        let input = r#"
+            network:
+              version: 2
+              wifis:
+                wlan0:
+                  access-points:
+                    home:
+                      password: hunter22
+                    hashed:
+                      password: 9bd36a1f15c0e2b3b9d3c4e2dd8e0d7a12e0b4e4d9a3f5d4c8e9a0b1c2d3e4f5
+              tunnels:
+                wg0:
+                  mode: wireguard
+                  key: /etc/wireguard/private.key
+                  peers:
+                    - keys:
+                        public: rlbInAj0qV69CysWPQY7KEBnKxpYCpaWqOs/dLevdWc=
+                        shared: 7voRZ/ojfXgfPOlswo3Lpma1RJq7qijIEEUEMShQFV8=
+            "#;
+
+        let config: NetplanConfig = serde_yaml::from_str(input).unwrap();
+        assert!(config
+            .validate()
+            .iter()
+            .all(|issue| issue.rule != "plaintext-secret"));
+
+        let options = ValidationOptions {
+            audit_secrets: true,
+            ..Default::default()
+        };
+        let mut paths = config
+            .validate_with(&options)
+            .into_iter()
+            .filter(|issue| issue.rule == "plaintext-secret")
+            .map(|issue| issue.path)
+            .collect::<Vec<_>>();
+        paths.sort();
+
+        assert_eq!(
+            paths,
+            vec![
+                "network.tunnels.wg0.peers.0.keys.shared",
+                "network.wifis.wlan0.access-points.home.password",
+            ]
+        );
+    }
+}
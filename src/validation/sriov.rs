@@ -0,0 +1,132 @@
+use crate::validation::{device_path, Context, Severity};
+use crate::Renderer;
+use std::collections::BTreeMap;
+
+/// Report virtual functions linking to an undefined physical function, physical functions
+/// with a virtual-function-count lower than the number of virtual functions declared for them,
+/// and virtual functions with more than one hardware offloaded VLAN.
+pub(super) fn check_sriov(ctx: &mut Context) {
+    let network = ctx.network;
+    let Some(ethernets) = &network.ethernets else {
+        return;
+    };
+
+    let mut virtual_functions = BTreeMap::<&str, Vec<&str>>::new();
+    for (name, ethernet) in ethernets {
+        let Some(link) = &ethernet.link else {
+            continue;
+        };
+
+        if ethernets.contains_key(link) {
+            virtual_functions.entry(link).or_default().push(name);
+        } else {
+            ctx.report(
+                Severity::Error,
+                "sriov-unknown-pf",
+                format!("{}.link", device_path("ethernets", name)),
+                format!("The physical function '{link}' is not defined in the ethernets section"),
+            );
+        }
+    }
+
+    for (pf, vfs) in &virtual_functions {
+        let Some(count) = ethernets[*pf].virtual_function_count else {
+            continue;
+        };
+
+        if usize::from(count) < vfs.len() {
+            ctx.report(
+                Severity::Error,
+                "sriov-vf-count",
+                format!("{}.virtual-function-count", device_path("ethernets", pf)),
+                format!(
+                    "virtual-function-count is {count}, but {} virtual functions are declared for this device",
+                    vfs.len()
+                ),
+            )
+            .suggest(format!(
+                "Set virtual-function-count to at least {}",
+                vfs.len()
+            ));
+        }
+    }
+
+    let mut offloaded = BTreeMap::<&str, Vec<&str>>::new();
+    for (name, vlan) in network.vlans.iter().flatten() {
+        let sriov = vlan
+            .common_all
+            .as_ref()
+            .is_some_and(|common| common.renderer == Some(Renderer::Sriov));
+        if let (true, Some(link)) = (sriov, &vlan.link) {
+            offloaded.entry(link).or_default().push(name);
+        }
+    }
+
+    for (vf, mut vlans) in offloaded {
+        if vlans.len() < 2 {
+            continue;
+        }
+
+        vlans.sort();
+        for vlan in &vlans[1..] {
+            ctx.report(
+                Severity::Error,
+                "sriov-multiple-vlans",
+                format!("{}.renderer", device_path("vlans", vlan)),
+                format!(
+                    "Only one VLAN can be offloaded to the virtual function '{vf}', which is already used by '{}'",
+                    vlans[0]
+                ),
+            );
+        }
+    }
+}
+
+#[cfg(all(test, feature = "yaml"))]
+mod test {
+    use crate::NetplanConfig;
+
+    #[test]
+    fn sriov() {
+        let input = r#"
+            network:
+              version: 2
+              ethernets:
+                eno1:
+                  virtual-function-count: 1
+                enp1s16f1:
+                  link: eno1
+                enp1s16f2:
+                  link: eno1
+                enp1s16f3:
+                  link: eno2
+              vlans:
+                vlan1:
+                  id: 1
+                  link: enp1s16f1
+                  renderer: sriov
+                vlan2:
+                  id: 2
+                  link: enp1s16f1
+                  renderer: sriov
+            "#;
+
+        let config: NetplanConfig = serde_yaml::from_str(input).unwrap();
+        let mut paths = config
+            .validate()
+            .into_iter()
+            .filter(|issue| issue.rule.starts_with("sriov-"))
+            .map(|issue| issue.path)
+            .collect::<Vec<_>>();
+        paths.sort();
+
+        assert_eq!(
+            paths,
+            vec![
+                "network.ethernets.eno1.virtual-function-count",
+                "network.ethernets.enp1s16f3.link",
+                "network.vlans.vlan2.renderer",
+            ]
+        );
+    }
+}
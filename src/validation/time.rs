@@ -0,0 +1,166 @@
+use crate::validation::{device_path, Context, Severity};
+use std::time::Duration;
+
+/// The unit of a time interval without suffix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum DefaultUnit {
+    Milliseconds,
+    Seconds,
+}
+
+/// Parse a time interval as accepted by netplan (and systemd), e.g. `100`, `100ms`,
+/// `1.5s` or `1min 30s`. Values without a suffix are interpreted in the default unit.
+pub(super) fn parse_interval(value: &str, default_unit: DefaultUnit) -> Option<Duration> {
+    let value = value.trim();
+    if value.is_empty() {
+        return None;
+    }
+
+    if let Ok(number) = value.parse::<f64>() {
+        let seconds = match default_unit {
+            DefaultUnit::Milliseconds => number / 1000.0,
+            DefaultUnit::Seconds => number,
+        };
+
+        return Duration::try_from_secs_f64(seconds).ok();
+    }
+
+    let mut total = Duration::ZERO;
+    let mut rest = value;
+    while !rest.is_empty() {
+        let number_end = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(rest.len());
+        let number = rest[..number_end].parse::<f64>().ok()?;
+        rest = rest[number_end..].trim_start();
+
+        let unit_end = rest
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(rest.len());
+        let seconds_per_unit = unit_seconds(&rest[..unit_end])?;
+        rest = rest[unit_end..].trim_start();
+
+        total += Duration::try_from_secs_f64(number * seconds_per_unit).ok()?;
+    }
+
+    Some(total)
+}
+
+/// The number of seconds in a time unit as understood by systemd.
+fn unit_seconds(unit: &str) -> Option<f64> {
+    let seconds = match unit {
+        "us" | "usec" => 0.000_001,
+        "ms" | "msec" => 0.001,
+        "s" | "sec" | "second" | "seconds" => 1.0,
+        "m" | "min" | "minute" | "minutes" => 60.0,
+        "h" | "hr" | "hour" | "hours" => 3600.0,
+        "d" | "day" | "days" => 86400.0,
+        "w" | "week" | "weeks" => 604800.0,
+        _ => return None,
+    };
+
+    Some(seconds)
+}
+
+/// Report time intervals of bonds and bridges which cannot be parsed.
+pub(super) fn check_intervals(ctx: &mut Context) {
+    use DefaultUnit::*;
+
+    for (name, bond) in ctx.network.bonds.iter().flatten() {
+        let Some(parameters) = &bond.parameters else {
+            continue;
+        };
+
+        let path = format!("{}.parameters", device_path("bonds", name));
+        check_fields(
+            ctx,
+            &path,
+            [
+                (
+                    "mii-monitor-interval",
+                    &parameters.mii_monitor_interval,
+                    Milliseconds,
+                ),
+                ("arp-interval", &parameters.arp_interval, Milliseconds),
+                ("up-delay", &parameters.up_delay, Milliseconds),
+                ("down-delay", &parameters.down_delay, Milliseconds),
+                (
+                    "learn-packet-interval",
+                    &parameters.learn_packet_interval,
+                    Seconds,
+                ),
+            ],
+        );
+    }
+
+    for (name, bridge) in ctx.network.bridges.iter().flatten() {
+        let Some(parameters) = &bridge.parameters else {
+            continue;
+        };
+
+        let path = format!("{}.parameters", device_path("bridges", name));
+        check_fields(
+            ctx,
+            &path,
+            [
+                ("ageing-time", &parameters.ageing_time, Seconds),
+                ("forward-delay", &parameters.forward_delay, Seconds),
+                ("hello-time", &parameters.hello_time, Seconds),
+                ("max-age", &parameters.max_age, Seconds),
+            ],
+        );
+    }
+}
+
+fn check_fields<const N: usize>(
+    ctx: &mut Context,
+    path: &str,
+    fields: [(&str, &Option<String>, DefaultUnit); N],
+) {
+    for (field, value, unit) in fields {
+        let Some(value) = value else {
+            continue;
+        };
+
+        if parse_interval(value, unit).is_none() {
+            ctx.report(
+                Severity::Error,
+                "invalid-time-interval",
+                format!("{path}.{field}"),
+                format!("'{value}' is not a valid time interval"),
+            )
+            .suggest("Use a number, optionally followed by a time suffix such as ms, s or min");
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse_interval, DefaultUnit};
+    use std::time::Duration;
+
+    #[test]
+    fn intervals() {
+        let ms = DefaultUnit::Milliseconds;
+        assert_eq!(parse_interval("100", ms), Some(Duration::from_millis(100)));
+        assert_eq!(
+            parse_interval("100ms", ms),
+            Some(Duration::from_millis(100))
+        );
+        assert_eq!(
+            parse_interval("1.5s", ms),
+            Some(Duration::from_millis(1500))
+        );
+        assert_eq!(
+            parse_interval("1min 30s", ms),
+            Some(Duration::from_secs(90))
+        );
+        assert_eq!(
+            parse_interval("2", DefaultUnit::Seconds),
+            Some(Duration::from_secs(2))
+        );
+        assert_eq!(parse_interval("100sm", ms), None);
+        assert_eq!(parse_interval("ms", ms), None);
+        assert_eq!(parse_interval("", ms), None);
+    }
+}
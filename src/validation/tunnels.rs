@@ -0,0 +1,144 @@
+use crate::validation::{device_path, Context, Severity};
+use crate::{Renderer, TunnelConfig, TunnelKey, TunnelMode};
+
+const RULE: &str = "tunnel-missing-field";
+
+/// Report tunnels which lack a field required by their mode.
+pub(super) fn check_required_fields(ctx: &mut Context) {
+    for (name, tunnel) in ctx.network.tunnels.iter().flatten() {
+        let path = device_path("tunnels", name);
+        let Some(mode) = &tunnel.mode else {
+            ctx.report(
+                Severity::Error,
+                RULE,
+                format!("{path}.mode"),
+                "Every tunnel requires a mode",
+            );
+            continue;
+        };
+
+        let renderer = ctx.effective_renderer(tunnel.common_all.as_deref());
+        let mut missing = Vec::new();
+        match mode {
+            TunnelMode::Wireguard => {
+                if private_key(tunnel).is_none() {
+                    missing.push(("key", "the private key"));
+                }
+
+                if tunnel.peers.is_empty() {
+                    missing.push(("peers", "at least one peer"));
+                }
+            }
+            TunnelMode::Vxlan => {
+                if tunnel.id.is_none() {
+                    missing.push(("id", "the VNI"));
+                }
+            }
+            _ => {
+                if tunnel.local.is_none() {
+                    missing.push(("local", "the local endpoint"));
+                }
+
+                if tunnel.remote.is_none() {
+                    missing.push(("remote", "the remote endpoint"));
+                }
+
+                let key_required = match mode {
+                    TunnelMode::Vti | TunnelMode::Vti6 => renderer == Renderer::Networkd,
+                    TunnelMode::Gre | TunnelMode::Ip6gre => renderer == Renderer::NetworkManager,
+                    _ => false,
+                };
+
+                if key_required && tunnel.key.is_none() {
+                    missing.push(("key", "a key"));
+                }
+            }
+        }
+
+        for (field, description) in missing {
+            ctx.report(
+                Severity::Error,
+                RULE,
+                format!("{path}.{field}"),
+                format!("This tunnel mode requires {description}"),
+            );
+        }
+
+        if *mode != TunnelMode::Wireguard {
+            continue;
+        }
+
+        for (index, peer) in tunnel.peers.iter().enumerate() {
+            let path = format!("{path}.peers.{index}");
+            if peer.keys.as_ref().and_then(|k| k.public.as_ref()).is_none() {
+                ctx.report(
+                    Severity::Error,
+                    RULE,
+                    format!("{path}.keys.public"),
+                    "Every WireGuard peer requires a public key",
+                );
+            }
+
+            if peer.allowed_ips.as_ref().is_none_or(|ips| ips.is_empty()) {
+                ctx.report(
+                    Severity::Error,
+                    RULE,
+                    format!("{path}.allowed-ips"),
+                    "Every WireGuard peer requires allowed-ips",
+                );
+            }
+        }
+    }
+}
+
+fn private_key(tunnel: &TunnelConfig) -> Option<&str> {
+    match tunnel.key.as_ref()? {
+        TunnelKey::Simple(key) => Some(key.as_str()),
+        TunnelKey::Complex { private, .. } => private.as_ref().map(|key| key.as_str()),
+    }
+}
+
+#[cfg(all(test, feature = "yaml"))]
+mod test {
+    use crate::NetplanConfig;
+
+    #[test]
+    fn missing_fields() {
+        let input = r#"
+            network:
+              version: 2
+              tunnels:
+                gre0:
+                  mode: gre
+                  local: 10.0.0.1
+                vxlan0:
+                  mode: vxlan
+                wg0:
+                  mode: wireguard
+                  key:
+                    private: /etc/wireguard/private.key
+                  peers:
+                    - endpoint: 10.0.0.2:51820
+                      keys:
+                        public: rlbInAj0qV69CysWPQY7KEBnKxpYCpaWqOs/dLevdWc=
+            "#;
+
+        let config: NetplanConfig = serde_yaml::from_str(input).unwrap();
+        let mut paths = config
+            .validate()
+            .into_iter()
+            .filter(|issue| issue.rule == "tunnel-missing-field")
+            .map(|issue| issue.path)
+            .collect::<Vec<_>>();
+        paths.sort();
+
+        assert_eq!(
+            paths,
+            vec![
+                "network.tunnels.gre0.remote",
+                "network.tunnels.vxlan0.id",
+                "network.tunnels.wg0.peers.0.allowed-ips",
+            ]
+        );
+    }
+}
@@ -0,0 +1,230 @@
+use crate::validation::{device_path, Context, Severity};
+use crate::{Renderer, TunnelMode};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+const RULE: &str = "unsupported-by-target";
+
+/// A netplan release, e.g. `0.105`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NetplanVersion {
+    pub major: u16,
+    pub minor: u16,
+}
+
+impl NetplanVersion {
+    pub const fn new(major: u16, minor: u16) -> Self {
+        Self { major, minor }
+    }
+}
+
+impl Display for NetplanVersion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+/// The error returned when parsing a [NetplanVersion] fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseNetplanVersionError(String);
+
+impl Display for ParseNetplanVersionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid netplan version '{}'", self.0)
+    }
+}
+
+impl std::error::Error for ParseNetplanVersionError {}
+
+impl FromStr for NetplanVersion {
+    type Err = ParseNetplanVersionError;
+
+    /// Parse a version like `0.105`. Any further components, like the patch
+    /// version in `0.106.1`, are ignored.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseNetplanVersionError(s.to_string());
+        let mut parts = s.trim().split('.');
+        let major = parts
+            .next()
+            .and_then(|v| v.parse().ok())
+            .ok_or_else(error)?;
+        let minor = parts
+            .next()
+            .and_then(|v| v.parse().ok())
+            .ok_or_else(error)?;
+
+        Ok(Self::new(major, minor))
+    }
+}
+
+/// Report every field which is newer than the targeted netplan release.
+pub(super) fn check_target(ctx: &mut Context) {
+    let Some(target) = ctx.options.target else {
+        return;
+    };
+
+    let mut used = Vec::new();
+    let network = ctx.network;
+
+    if network.vrfs.as_ref().is_some_and(|vrfs| !vrfs.is_empty()) {
+        used.push(("network.vrfs".to_string(), NetplanVersion::new(0, 105)));
+    }
+
+    if network
+        .dummy_devices
+        .as_ref()
+        .is_some_and(|dummies| !dummies.is_empty())
+    {
+        used.push((
+            "network.dummy-devices".to_string(),
+            NetplanVersion::new(0, 107),
+        ));
+    }
+
+    for (section, name, common) in ctx.devices() {
+        let Some(common) = common else {
+            continue;
+        };
+
+        let path = device_path(section, name);
+        if common.activation_mode.is_some() {
+            used.push((
+                format!("{path}.activation-mode"),
+                NetplanVersion::new(0, 103),
+            ));
+        }
+
+        if section == "vlans" && common.renderer == Some(Renderer::Sriov) {
+            used.push((format!("{path}.renderer"), NetplanVersion::new(0, 99)));
+        }
+    }
+
+    for (name, ethernet) in network.ethernets.iter().flatten() {
+        let path = device_path("ethernets", name);
+        if ethernet.embedded_switch_mode.is_some() {
+            used.push((
+                format!("{path}.embedded-switch-mode"),
+                NetplanVersion::new(0, 104),
+            ));
+        }
+
+        if ethernet.delay_virtual_functions_rebind.is_some() {
+            used.push((
+                format!("{path}.delay-virtual-functions-rebind"),
+                NetplanVersion::new(0, 104),
+            ));
+        }
+
+        let openvswitch = ethernet
+            .common_physical
+            .as_ref()
+            .is_some_and(|physical| physical.openvswitch.is_some());
+        if openvswitch {
+            used.push((format!("{path}.openvswitch"), NetplanVersion::new(0, 100)));
+        }
+    }
+
+    if network.openvswitch.is_some() {
+        used.push((
+            "network.openvswitch".to_string(),
+            NetplanVersion::new(0, 100),
+        ));
+    }
+
+    let openvswitch = network
+        .bonds
+        .iter()
+        .flatten()
+        .map(|(name, d)| ("bonds", name, d.openvswitch.is_some()))
+        .chain(
+            network
+                .bridges
+                .iter()
+                .flatten()
+                .map(|(name, d)| ("bridges", name, d.openvswitch.is_some())),
+        )
+        .chain(
+            network
+                .vlans
+                .iter()
+                .flatten()
+                .map(|(name, d)| ("vlans", name, d.openvswitch.is_some())),
+        );
+    for (section, name, _) in openvswitch.filter(|(_, _, set)| *set) {
+        used.push((
+            format!("{}.openvswitch", device_path(section, name)),
+            NetplanVersion::new(0, 100),
+        ));
+    }
+
+    for (name, wifi) in network.wifis.iter().flatten() {
+        if wifi.regulatory_domain.is_some() {
+            used.push((
+                format!("{}.regulatory-domain", device_path("wifis", name)),
+                NetplanVersion::new(0, 105),
+            ));
+        }
+    }
+
+    for (name, tunnel) in network.tunnels.iter().flatten() {
+        let since = match tunnel.mode {
+            Some(TunnelMode::Wireguard) => NetplanVersion::new(0, 100),
+            Some(TunnelMode::Vxlan) => NetplanVersion::new(0, 105),
+            _ => continue,
+        };
+
+        used.push((format!("{}.mode", device_path("tunnels", name)), since));
+    }
+
+    for (path, since) in used {
+        if since > target {
+            ctx.report(
+                Severity::Error,
+                RULE,
+                path,
+                format!("Requires netplan {since}, but the target is netplan {target}"),
+            );
+        }
+    }
+}
+
+#[cfg(all(test, feature = "yaml"))]
+mod test {
+    use crate::{NetplanConfig, NetplanVersion, ValidationOptions};
+
+    #[test]
+    fn parse_version() {
+        assert_eq!("0.106.1".parse(), Ok(NetplanVersion::new(0, 106)));
+        assert!("0".parse::<NetplanVersion>().is_err());
+    }
+
+    #[test]
+    fn newer_fields() {
+        let input = r#"
+            network:
+              version: 2
+              ethernets:
+                eth0:
+                  activation-mode: manual
+              wifis:
+                wlan0:
+                  regulatory-domain: GB
+            "#;
+
+        let config: NetplanConfig = serde_yaml::from_str(input).unwrap();
+        let issues = config.validate_with(&ValidationOptions {
+            target: Some(NetplanVersion::new(0, 102)),
+            ..Default::default()
+        });
+
+        let mut paths = issues.into_iter().map(|i| i.path).collect::<Vec<_>>();
+        paths.sort();
+        assert_eq!(
+            paths,
+            vec![
+                "network.ethernets.eth0.activation-mode",
+                "network.wifis.wlan0.regulatory-domain"
+            ]
+        );
+    }
+}
@@ -0,0 +1,153 @@
+use crate::validation::{device_path, Context, Severity};
+use crate::WakeOnWLan;
+
+/// Report access points of which the channel is ignored because no band is set,
+/// or of which the channel does not belong to the configured band.
+pub(super) fn check_band_channel(ctx: &mut Context) {
+    for (name, wifi) in ctx.network.wifis.iter().flatten() {
+        for (ssid, access_point) in wifi.access_points.iter().flatten() {
+            let Some(channel) = access_point.channel else {
+                continue;
+            };
+
+            let path = format!("{}.access-points.{ssid}", device_path("wifis", name));
+            match &access_point.band {
+                None => {
+                    ctx.report(
+                        Severity::Warning,
+                        "wifi-channel-without-band",
+                        format!("{path}.channel"),
+                        "The channel is ignored unless the band is set as well",
+                    )
+                    .suggest(format!("Set band to the band channel {channel} belongs to"));
+                }
+                Some(band) if !band.has_channel(channel) => {
+                    ctx.report(
+                        Severity::Error,
+                        "wifi-channel-not-in-band",
+                        format!("{path}.channel"),
+                        format!(
+                            "Channel {channel} does not exist in the {} band",
+                            band.as_str()
+                        ),
+                    );
+                }
+                Some(_) => {}
+            }
+        }
+    }
+}
+
+/// The default flag of wakeonwlan is exclusive, netplan refuses to combine it with other flags.
+/// Combining any with specific flags is accepted, but the specific flags have no effect.
+pub(super) fn check_wakeonwlan(ctx: &mut Context) {
+    for (name, wifi) in ctx.network.wifis.iter().flatten() {
+        let Some(flags) = wifi.wakeonwlan.as_ref().filter(|flags| flags.len() > 1) else {
+            continue;
+        };
+
+        let path = format!("{}.wakeonwlan", device_path("wifis", name));
+        if flags.contains(&WakeOnWLan::Default) {
+            ctx.report(
+                Severity::Error,
+                "wakeonwlan-exclusive-flag",
+                path,
+                "The default flag cannot be combined with other flags",
+            )
+            .suggest("Either use only the default flag, or remove it");
+        } else if flags.contains(&WakeOnWLan::Any) {
+            ctx.report(
+                Severity::Warning,
+                "wakeonwlan-exclusive-flag",
+                path,
+                "The any flag already includes all other flags",
+            )
+            .suggest("Either use only the any flag, or list the specific flags without it");
+        }
+    }
+}
+
+#[cfg(all(test, feature = "yaml"))]
+mod test {
+    use crate::validation::Severity;
+    use crate::NetplanConfig;
+
+    fn issues(config: &NetplanConfig, rule: &str) -> Vec<String> {
+        config
+            .validate()
+            .into_iter()
+            .filter(|issue| issue.rule == rule)
+            .map(|issue| issue.path)
+            .collect()
+    }
+
+    #[test]
+    fn band_channel() {
+        let input = r#"
+            network:
+              version: 2
+              wifis:
+                wlan0:
+                  access-points:
+                    nochannel: {}
+                    noband:
+                      channel: 6
+                    matching:
+                      band: 5GHz
+                      channel: 36
+                    mismatch:
+                      band: 2.4GHz
+                      channel: 36
+            "#;
+
+        let config: NetplanConfig = serde_yaml::from_str(input).unwrap();
+        assert_eq!(
+            issues(&config, "wifi-channel-without-band"),
+            vec!["network.wifis.wlan0.access-points.noband.channel"]
+        );
+        assert_eq!(
+            issues(&config, "wifi-channel-not-in-band"),
+            vec!["network.wifis.wlan0.access-points.mismatch.channel"]
+        );
+    }
+
+    #[test]
+    fn wakeonwlan_exclusive_flags() {
+        let input = r#"
+            network:
+              version: 2
+              wifis:
+                wlan0:
+                  wakeonwlan: [default, magic_pkt]
+                wlan1:
+                  wakeonwlan: [any, disconnect]
+                wlan2:
+                  wakeonwlan: [magic_pkt, disconnect]
+                wlan3:
+                  wakeonwlan: [default]
+            "#;
+
+        let config: NetplanConfig = serde_yaml::from_str(input).unwrap();
+        let mut issues = config
+            .validate()
+            .into_iter()
+            .filter(|issue| issue.rule == "wakeonwlan-exclusive-flag")
+            .map(|issue| (issue.path, issue.severity))
+            .collect::<Vec<_>>();
+        issues.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        assert_eq!(
+            issues,
+            vec![
+                (
+                    "network.wifis.wlan0.wakeonwlan".to_string(),
+                    Severity::Error
+                ),
+                (
+                    "network.wifis.wlan1.wakeonwlan".to_string(),
+                    Severity::Warning
+                ),
+            ]
+        );
+    }
+}
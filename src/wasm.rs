@@ -0,0 +1,67 @@
+//! Bindings for JavaScript, so web-based editors share the model and validation of the crate.
+
+use crate::NetplanConfig;
+use wasm_bindgen::prelude::*;
+
+/// Parse a netplan YAML document, and return the configuration as JSON.
+#[wasm_bindgen(js_name = parseYaml)]
+pub fn parse_yaml(yaml: &str) -> Result<String, JsError> {
+    parse(yaml).map_err(|e| JsError::new(&e))
+}
+
+/// Validate a configuration in netplan YAML or JSON, and return the
+/// [ValidationReport](crate::ValidationReport) as JSON. Invalid YAML is an error, rather
+/// than a report.
+#[wasm_bindgen(js_name = validate)]
+pub fn validate_config(config: &str) -> Result<String, JsError> {
+    validate(config).map_err(|e| JsError::new(&e))
+}
+
+/// Serialize a configuration in JSON, like the one returned by `parseYaml`, to netplan
+/// YAML.
+#[wasm_bindgen(js_name = toYaml)]
+pub fn to_yaml(json: &str) -> Result<String, JsError> {
+    json_to_yaml(json).map_err(|e| JsError::new(&e))
+}
+
+// The bindings wrap these, as `JsError` can only be created on wasm targets
+
+fn parse(yaml: &str) -> Result<String, String> {
+    let config = NetplanConfig::from_yaml(yaml).map_err(|e| e.to_string())?;
+    serde_json::to_string(&config).map_err(|e| e.to_string())
+}
+
+fn validate(config: &str) -> Result<String, String> {
+    // JSON is valid YAML, so both are parsed the same way
+    let config = NetplanConfig::from_yaml(config).map_err(|e| e.to_string())?;
+    serde_json::to_string(&config.validate()).map_err(|e| e.to_string())
+}
+
+fn json_to_yaml(json: &str) -> Result<String, String> {
+    let config: NetplanConfig = serde_json::from_str(json).map_err(|e| e.to_string())?;
+    Ok(config.to_yaml())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{json_to_yaml, parse, validate};
+    use crate::{NetplanConfig, ValidationReport};
+
+    #[test]
+    fn round_trip_through_json() {
+        let yaml = "network: {version: 2, ethernets: {eth0: {dhcp4: true, mtu: 9000}}}";
+        let json = parse(yaml).unwrap();
+        let config = NetplanConfig::from_yaml(&json_to_yaml(&json).unwrap()).unwrap();
+        assert!(config.semantically_eq(&NetplanConfig::from_yaml(yaml).unwrap()));
+
+        let report = validate("network: {version: 2, ethernets: {eth0: {mtu: 10}}}").unwrap();
+        let report: ValidationReport = serde_json::from_str(&report).unwrap();
+        assert!(!report.issues.is_empty());
+        let report = validate(&json).unwrap();
+        let report: ValidationReport = serde_json::from_str(&report).unwrap();
+        assert!(report.issues.is_empty());
+
+        assert!(parse("network: [").is_err());
+        assert!(json_to_yaml("{\"network\": 2}").is_err());
+    }
+}
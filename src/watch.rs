@@ -0,0 +1,198 @@
+//! Watching netplan's directories for configuration changes.
+
+use crate::{Change, DiscoveredConfig, NetplanConfig, NETPLAN_DIRS};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
+use std::time::Duration;
+
+/// How long to wait for further events after a change, so a file written in several
+/// steps, or several files changed at once, result in a single update.
+const DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// The error returned when watching the configuration fails.
+#[derive(Debug)]
+pub enum WatchError {
+    /// The configuration could not be read.
+    Io(std::io::Error),
+    /// The directories could not be watched.
+    Notify(notify::Error),
+}
+
+impl Display for WatchError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "Failed to read configuration: {e}"),
+            Self::Notify(e) => write!(f, "Failed to watch configuration: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for WatchError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Notify(e) => Some(e),
+        }
+    }
+}
+
+impl From<std::io::Error> for WatchError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<notify::Error> for WatchError {
+    fn from(e: notify::Error) -> Self {
+        Self::Notify(e)
+    }
+}
+
+/// A change of the system's configuration, see [NetplanWatcher].
+#[derive(Debug)]
+pub struct NetplanUpdate {
+    /// The configuration after the change, and the files it was merged from.
+    pub discovered: DiscoveredConfig,
+    /// The fields which changed, see [diff](crate::diff).
+    pub changes: Vec<Change>,
+}
+
+/// Watches netplan's directories, using inotify on Linux, and reloads the configuration
+/// when a `*.yaml` file in them changes.
+///
+/// The watcher is an iterator of updates, which blocks until the merged configuration
+/// changes. Changes to files which do not affect the merged configuration, like touching
+/// a file or rewriting it with the same settings, are not reported. The iterator ends
+/// when the watcher stops delivering events.
+///
+/// Only the directories which exist when the watcher is created are watched.
+///
+/// ```no_run
+/// use netplan_types::NetplanWatcher;
+///
+/// let watcher = NetplanWatcher::new().unwrap();
+/// for update in watcher {
+///     for change in update.unwrap().changes {
+///         println!("{change}");
+///     }
+/// }
+/// ```
+pub struct NetplanWatcher {
+    root: PathBuf,
+    config: NetplanConfig,
+    events: Receiver<notify::Result<notify::Event>>,
+    _watcher: RecommendedWatcher,
+}
+
+impl NetplanWatcher {
+    /// Watch the system's configuration, see [NetplanConfig::load_system].
+    pub fn new() -> Result<Self, WatchError> {
+        Self::new_at("/")
+    }
+
+    /// Watch the configuration in netplan's directories below `root`, see
+    /// [NetplanConfig::load_system_from].
+    pub fn new_at(root: impl AsRef<Path>) -> Result<Self, WatchError> {
+        let root = root.as_ref().to_path_buf();
+        let (sender, events) = std::sync::mpsc::channel();
+        let mut watcher = notify::recommended_watcher(sender)?;
+        for dir in NETPLAN_DIRS {
+            let dir = root.join(dir);
+            if dir.is_dir() {
+                watcher.watch(&dir, RecursiveMode::NonRecursive)?;
+            }
+        }
+
+        // Load after watching, so no change is missed in between
+        let config = NetplanConfig::load_system_from(&root)?.loaded.config;
+        Ok(Self {
+            root,
+            config,
+            events,
+            _watcher: watcher,
+        })
+    }
+
+    /// The configuration as of the last update.
+    pub fn config(&self) -> &NetplanConfig {
+        &self.config
+    }
+
+    /// Block until a `*.yaml` file changed and further events have settled.
+    /// Returns `None` if the watcher stopped delivering events.
+    fn wait_for_change(&self) -> Option<notify::Result<()>> {
+        loop {
+            match self.events.recv().ok()? {
+                Ok(event) if event.paths.iter().any(|path| is_yaml(path)) => break,
+                Ok(_) => continue,
+                Err(e) => return Some(Err(e)),
+            }
+        }
+
+        loop {
+            match self.events.recv_timeout(DEBOUNCE) {
+                Ok(_) => continue,
+                Err(RecvTimeoutError::Timeout) => return Some(Ok(())),
+                Err(RecvTimeoutError::Disconnected) => return None,
+            }
+        }
+    }
+}
+
+impl Iterator for NetplanWatcher {
+    type Item = Result<NetplanUpdate, WatchError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Err(e) = self.wait_for_change()? {
+                return Some(Err(e.into()));
+            }
+
+            let discovered = match NetplanConfig::load_system_from(&self.root) {
+                Ok(discovered) => discovered,
+                Err(e) => return Some(Err(e.into())),
+            };
+
+            let changes = self.config.diff(&discovered.loaded.config);
+            if !changes.is_empty() {
+                self.config = discovered.loaded.config.clone();
+                return Some(Ok(NetplanUpdate {
+                    discovered,
+                    changes,
+                }));
+            }
+        }
+    }
+}
+
+fn is_yaml(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "yaml")
+}
+
+#[cfg(test)]
+mod test {
+    use crate::NetplanWatcher;
+
+    #[test]
+    fn watcher_reports_changes() {
+        let root = tempfile::tempdir().unwrap();
+        let dir = root.path().join("etc/netplan");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("10-base.yaml"), "network: {version: 2}").unwrap();
+
+        let mut watcher = NetplanWatcher::new_at(root.path()).unwrap();
+        std::fs::write(dir.join("README"), "not a configuration").unwrap();
+        std::fs::write(
+            dir.join("10-base.yaml"),
+            "network: {version: 2, ethernets: {eth0: {dhcp4: true}}}",
+        )
+        .unwrap();
+
+        let update = watcher.next().unwrap().unwrap();
+        assert_eq!(update.changes.len(), 1);
+        assert_eq!(update.changes[0].path, "network.ethernets");
+        assert!(watcher.config().network.ethernets.is_some());
+    }
+}
@@ -0,0 +1,184 @@
+//! Rendering WireGuard tunnels into wg-quick configurations.
+
+use crate::ifupdown::{route_command, rule_command};
+use crate::networkd::{UnitFile, UnitSection};
+use crate::{TunnelConfig, TunnelKey, TunnelMode};
+
+impl TunnelConfig {
+    /// Render a WireGuard tunnel into the configuration `wg-quick` reads from
+    /// `/etc/wireguard/<name>.conf`: an `[Interface]` section with the private key, listen
+    /// port, firewall mark, addresses, DNS servers and MTU of the tunnel, and a `[Peer]`
+    /// section per peer. `None` if the tunnel is not a WireGuard tunnel.
+    ///
+    /// wg-quick cannot read keys from files, so private and preshared keys which are paths
+    /// are set by `wg set` commands run by `PostUp`, as are the routes and routing policy
+    /// of the tunnel. The configuration contains the private key, so it should only be
+    /// readable by root.
+    ///
+    /// ```
+    /// use netplan_types::{AddressMapping, CommonProperties, TunnelConfig};
+    ///
+    /// let mut tunnel = TunnelConfig::wireguard("4GgaQCy68nzNsUE5aJ9fuLzHhB65tAlwbmA72MWnOm8=")
+    ///     .listen_port(51820)
+    ///     .peer("M9nt4YujIOmNrRmpIRTmYSfMdrpvE7u6WkG8FY8WjG4=", "vpn.example.com:51820", ["10.10.0.0/24"]);
+    /// let address = AddressMapping::Simple("10.10.0.2/24".to_string());
+    /// tunnel.common_all_mut().addresses = Some(vec![address]);
+    ///
+    /// let conf = tunnel.to_wg_quick().unwrap();
+    /// assert_eq!(conf.get("Interface", "ListenPort"), Some("51820"));
+    /// assert_eq!(conf.get("Interface", "Address"), Some("10.10.0.2/24"));
+    /// assert_eq!(conf.get("Peer", "Endpoint"), Some("vpn.example.com:51820"));
+    /// ```
+    pub fn to_wg_quick(&self) -> Option<UnitFile> {
+        if self.mode != Some(TunnelMode::Wireguard) {
+            return None;
+        }
+
+        let mut interface = UnitSection::new("Interface");
+        let mut post_up = Vec::new();
+        let private = match &self.key {
+            Some(TunnelKey::Simple(key)) => Some(key.as_str()),
+            Some(TunnelKey::Complex { private, .. }) => private.as_ref().map(|key| key.as_str()),
+            None => None,
+        };
+        match private {
+            Some(path) if path.starts_with('/') => {
+                post_up.push(format!("wg set %i private-key {path}"))
+            }
+            private => interface.add_opt("PrivateKey", private),
+        }
+        interface.add_opt(
+            "ListenPort",
+            self.port.as_deref().filter(|port| *port != "auto"),
+        );
+        interface.add_opt("FwMark", self.mark.as_deref());
+
+        if let Some(common) = self.common_all.as_deref() {
+            for mapping in common.addresses.iter().flatten() {
+                for address in mapping.addresses() {
+                    interface.add("Address", address);
+                }
+            }
+            if let Some(nameservers) = &common.nameservers {
+                let dns = nameservers
+                    .addresses
+                    .iter()
+                    .flatten()
+                    .chain(nameservers.search.iter().flatten())
+                    .cloned()
+                    .collect::<Vec<_>>();
+                if !dns.is_empty() {
+                    interface.add("DNS", dns.join(", "));
+                }
+            }
+            interface.add_opt("MTU", common.mtu);
+            for route in common.routes.iter().flatten() {
+                post_up.push(format!("{} dev %i", route_command(route)));
+            }
+            for policy in common.routing_policy.iter().flatten() {
+                post_up.push(rule_command(policy));
+            }
+        }
+
+        let mut conf = UnitFile::default();
+        let mut peers = Vec::new();
+        for peer in &self.peers {
+            let keys = peer.keys.as_ref();
+            let public = keys.and_then(|k| k.public.as_deref());
+            let mut section = UnitSection::new("Peer");
+            section.add_opt("PublicKey", public);
+            match keys.and_then(|k| k.shared.as_ref()).map(|key| key.as_str()) {
+                Some(path) if path.starts_with('/') => post_up.push(format!(
+                    "wg set %i peer {} preshared-key {path}",
+                    public.unwrap_or_default()
+                )),
+                shared => section.add_opt("PresharedKey", shared),
+            }
+            section.add_opt(
+                "AllowedIPs",
+                peer.allowed_ips.as_ref().map(|ips| ips.join(", ")),
+            );
+            section.add_opt("Endpoint", peer.endpoint.as_deref());
+            section.add_opt("PersistentKeepalive", peer.keepalive);
+            peers.push(section);
+        }
+
+        for command in post_up {
+            interface.add("PostUp", command);
+        }
+        conf.sections.push(interface);
+        conf.sections.extend(peers);
+        Some(conf)
+    }
+}
+
+#[cfg(all(test, feature = "yaml"))]
+mod test {
+    use crate::NetplanConfig;
+
+    #[test]
+    fn export_wg_quick() {
+        let config = NetplanConfig::from_yaml(
+            r#"
+network:
+  version: 2
+  tunnels:
+    wg0:
+      mode: wireguard
+      key: {private: /etc/wireguard/wg0.key}
+      port: "51820"
+      mark: "42"
+      addresses: [10.10.0.1/24, "fd00:10::1/64"]
+      nameservers: {addresses: [10.10.0.53], search: [vpn.example.com]}
+      mtu: 1420
+      routes:
+        - to: 10.20.0.0/16
+          via: 10.10.0.2
+      peers:
+        - keys:
+            public: M9nt4YujIOmNrRmpIRTmYSfMdrpvE7u6WkG8FY8WjG4=
+            shared: /etc/wireguard/peer.psk
+          allowed-ips: [10.10.0.2/32, 10.20.0.0/16]
+          endpoint: 192.0.2.10:51820
+          keepalive: 25
+        - keys:
+            public: 8TkU3OzAQTMwm8cdjmYHPsmPDEFQdYKRU6z3r14TVyw=
+            shared: oyP+QKOhSoxVQs6OSq0RQ9EOdelhM0UDvXOC4MiNMIk=
+          allowed-ips: [10.10.0.3/32]
+    gre0:
+      mode: gre
+      local: 192.0.2.1
+      remote: 192.0.2.2
+"#,
+        )
+        .unwrap();
+        let tunnels = config.network.tunnels.unwrap();
+        assert_eq!(tunnels["gre0"].to_wg_quick(), None);
+
+        let expected = "\
+[Interface]
+ListenPort=51820
+FwMark=42
+Address=10.10.0.1/24
+Address=fd00:10::1/64
+DNS=10.10.0.53, vpn.example.com
+MTU=1420
+PostUp=wg set %i private-key /etc/wireguard/wg0.key
+PostUp=ip route add 10.20.0.0/16 via 10.10.0.2 dev %i
+PostUp=wg set %i peer M9nt4YujIOmNrRmpIRTmYSfMdrpvE7u6WkG8FY8WjG4= preshared-key /etc/wireguard/peer.psk
+
+[Peer]
+PublicKey=M9nt4YujIOmNrRmpIRTmYSfMdrpvE7u6WkG8FY8WjG4=
+AllowedIPs=10.10.0.2/32, 10.20.0.0/16
+Endpoint=192.0.2.10:51820
+PersistentKeepalive=25
+
+[Peer]
+PublicKey=8TkU3OzAQTMwm8cdjmYHPsmPDEFQdYKRU6z3r14TVyw=
+PresharedKey=oyP+QKOhSoxVQs6OSq0RQ9EOdelhM0UDvXOC4MiNMIk=
+AllowedIPs=10.10.0.3/32
+";
+        let conf = tunnels["wg0"].to_wg_quick().unwrap();
+        assert_eq!(conf.to_string(), expected);
+    }
+}
@@ -0,0 +1,299 @@
+//! Importing wg-quick configurations into WireGuard tunnels.
+
+use crate::ifupdown::{route_from_command, rule_from_command};
+use crate::networkd::{UnitFile, UnitParseError, UnitSection};
+use crate::{
+    AddressMapping, CommonPropertiesAllDevices, NameserverConfig, SecretString, TunnelConfig,
+    TunnelKey, TunnelMode, WireGuardPeer, WireGuardPeerKey,
+};
+use std::fmt::{Display, Formatter};
+use std::net::IpAddr;
+use std::path::Path;
+
+/// The error returned when a wg-quick configuration cannot be imported.
+#[derive(Debug)]
+pub enum WgQuickImportError {
+    /// The file could not be read.
+    Io(std::io::Error),
+    /// The file is not a valid wg-quick configuration.
+    Parse(UnitParseError),
+}
+
+impl Display for WgQuickImportError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "Failed to read wg-quick configuration: {e}"),
+            Self::Parse(e) => write!(f, "Invalid wg-quick configuration: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for WgQuickImportError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Parse(e) => Some(e),
+        }
+    }
+}
+
+impl From<std::io::Error> for WgQuickImportError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<UnitParseError> for WgQuickImportError {
+    fn from(e: UnitParseError) -> Self {
+        Self::Parse(e)
+    }
+}
+
+impl TunnelConfig {
+    /// Lift a wg-quick configuration into a WireGuard tunnel, the inverse of
+    /// [TunnelConfig::to_wg_quick].
+    ///
+    /// The private key, listen port and firewall mark of the `[Interface]` section are
+    /// carried over, as are its addresses, DNS servers and search domains, and MTU, and
+    /// the `[Peer]` sections. `ip route add` and `ip rule add` commands run by `PostUp`
+    /// become routes and routing policy, and `wg set` commands reading the private or
+    /// a preshared key from a file become the path of the key. Other commands, and
+    /// settings netplan has no equivalent for, like `Table` or `SaveConfig`, are left out.
+    ///
+    /// ```
+    /// use netplan_types::{NetworkConfig, TunnelConfig, UnitFile};
+    ///
+    /// let conf: UnitFile = "
+    /// [Interface]
+    /// PrivateKey = 4GgaQCy68nzNsUE5aJ9fuLzHhB65tAlwbmA72MWnOm8=
+    /// Address = 10.10.0.2/24
+    ///
+    /// [Peer]
+    /// PublicKey = M9nt4YujIOmNrRmpIRTmYSfMdrpvE7u6WkG8FY8WjG4=
+    /// AllowedIPs = 10.10.0.0/24
+    /// Endpoint = vpn.example.com:51820
+    /// "
+    /// .parse()
+    /// .unwrap();
+    ///
+    /// let tunnel = TunnelConfig::from_wg_quick(&conf);
+    /// assert_eq!(tunnel.peers[0].endpoint.as_deref(), Some("vpn.example.com:51820"));
+    ///
+    /// let mut network = NetworkConfig::default();
+    /// network.add_tunnel("wg0", tunnel);
+    /// ```
+    pub fn from_wg_quick(conf: &UnitFile) -> Self {
+        let mut tunnel = TunnelConfig {
+            mode: Some(TunnelMode::Wireguard),
+            ..Default::default()
+        };
+        let mut common = CommonPropertiesAllDevices::default();
+        let mut private_key = None;
+        let mut commands = Vec::new();
+
+        for section in &conf.sections {
+            if section.name.eq_ignore_ascii_case("Peer") {
+                tunnel.peers.push(import_peer(section));
+                continue;
+            }
+            if !section.name.eq_ignore_ascii_case("Interface") {
+                continue;
+            }
+
+            private_key = private_key.or(get(section, "PrivateKey").map(SecretString::from));
+            tunnel.port = get(section, "ListenPort").map(str::to_string);
+            tunnel.mark = get(section, "FwMark")
+                .filter(|mark| !matches!(*mark, "off" | "0"))
+                .map(str::to_string);
+            common.mtu = get(section, "MTU").and_then(|mtu| mtu.parse().ok());
+
+            for address in list(section, "Address") {
+                common
+                    .addresses
+                    .get_or_insert_with(Vec::new)
+                    .push(AddressMapping::Simple(address.to_string()));
+            }
+            for dns in list(section, "DNS") {
+                let nameservers = common
+                    .nameservers
+                    .get_or_insert_with(NameserverConfig::default);
+                let list = if dns.parse::<IpAddr>().is_ok() {
+                    &mut nameservers.addresses
+                } else {
+                    &mut nameservers.search
+                };
+                list.get_or_insert_with(Vec::new).push(dns.to_string());
+            }
+
+            commands.extend(
+                section
+                    .entries
+                    .iter()
+                    .filter(|(key, _)| key.eq_ignore_ascii_case("PostUp"))
+                    .flat_map(|(_, value)| value.split(';'))
+                    .map(str::trim),
+            );
+        }
+
+        // The commands may set the keys of peers which are defined after them
+        for command in commands {
+            let words = command.split_whitespace().collect::<Vec<_>>();
+            match words.as_slice() {
+                ["wg", "set", _, "private-key", path] => {
+                    private_key = Some(SecretString::from(*path))
+                }
+                ["wg", "set", _, "peer", public, "preshared-key", path] => {
+                    let peer = tunnel.peers.iter_mut().find(|peer| {
+                        peer.keys.as_ref().and_then(|k| k.public.as_deref()) == Some(public)
+                    });
+                    if let Some(keys) = peer.and_then(|peer| peer.keys.as_mut()) {
+                        keys.shared = Some(SecretString::from(*path));
+                    }
+                }
+                _ => {
+                    if let Some(route) = route_from_command(command) {
+                        common.routes.get_or_insert_with(Vec::new).push(route);
+                    } else if let Some(policy) = rule_from_command(command) {
+                        common
+                            .routing_policy
+                            .get_or_insert_with(Vec::new)
+                            .push(policy);
+                    }
+                }
+            }
+        }
+
+        tunnel.key = private_key.map(|private| TunnelKey::Complex {
+            input: None,
+            output: None,
+            private: Some(private),
+        });
+        if common != CommonPropertiesAllDevices::default() {
+            tunnel.common_all = Some(Box::new(common));
+        }
+        tunnel
+    }
+
+    /// Read a wg-quick configuration, like `/etc/wireguard/wg0.conf`, and import it, see
+    /// [TunnelConfig::from_wg_quick]. The name of the file, without its extension, is the
+    /// name of the interface wg-quick creates.
+    pub fn from_wg_quick_file(path: impl AsRef<Path>) -> Result<Self, WgQuickImportError> {
+        let conf = std::fs::read_to_string(path)?.parse()?;
+        Ok(Self::from_wg_quick(&conf))
+    }
+}
+
+/// The value of `key`, which wg-quick matches regardless of case, without a trailing
+/// comment.
+fn get<'a>(section: &'a UnitSection, key: &str) -> Option<&'a str> {
+    section
+        .entries
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case(key))
+        .map(|(_, value)| value.split('#').next().unwrap_or_default().trim())
+}
+
+/// The items of the comma separated lists of every value of `key`.
+fn list<'a>(section: &'a UnitSection, key: &'a str) -> impl Iterator<Item = &'a str> {
+    section
+        .entries
+        .iter()
+        .filter(move |(k, _)| k.eq_ignore_ascii_case(key))
+        .flat_map(|(_, value)| value.split('#').next().unwrap_or_default().split(','))
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+}
+
+fn import_peer(section: &UnitSection) -> WireGuardPeer {
+    let allowed_ips = list(section, "AllowedIPs")
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+    WireGuardPeer {
+        endpoint: get(section, "Endpoint").map(str::to_string),
+        allowed_ips: (!allowed_ips.is_empty()).then_some(allowed_ips),
+        keepalive: get(section, "PersistentKeepalive").and_then(|k| k.parse().ok()),
+        keys: Some(WireGuardPeerKey {
+            public: get(section, "PublicKey").map(str::to_string),
+            shared: get(section, "PresharedKey").map(SecretString::from),
+        }),
+    }
+}
+
+#[cfg(all(test, feature = "yaml"))]
+mod test {
+    use crate::{NetplanConfig, TunnelConfig, UnitFile};
+
+    #[test]
+    fn import_wg_quick() {
+        let config = NetplanConfig::from_yaml(
+            r#"
+network:
+  version: 2
+  tunnels:
+    wg0:
+      mode: wireguard
+      key: {private: /etc/wireguard/wg0.key}
+      port: "51820"
+      addresses: [10.10.0.1/24, "fd00:10::1/64"]
+      nameservers: {addresses: [10.10.0.53], search: [vpn.example.com]}
+      mtu: 1420
+      routes:
+        - to: 10.20.0.0/16
+          via: 10.10.0.2
+      routing-policy:
+        - from: 10.10.0.0/24
+          table: 100
+      peers:
+        - keys:
+            public: M9nt4YujIOmNrRmpIRTmYSfMdrpvE7u6WkG8FY8WjG4=
+            shared: /etc/wireguard/peer.psk
+          allowed-ips: [10.10.0.2/32, 10.20.0.0/16]
+          endpoint: 192.0.2.10:51820
+          keepalive: 25
+"#,
+        )
+        .unwrap();
+        let tunnels = config.network.tunnels.unwrap();
+        let conf = tunnels["wg0"].to_wg_quick().unwrap();
+        assert_eq!(TunnelConfig::from_wg_quick(&conf), tunnels["wg0"]);
+
+        // A configuration as written by hand, with comments and lower case keys
+        let conf: UnitFile = "
+[Interface]
+privatekey = 4GgaQCy68nzNsUE5aJ9fuLzHhB65tAlwbmA72MWnOm8=
+Address = 10.10.0.3/24, fd00:10::3/64
+DNS = 10.10.0.53
+Table = off
+PostUp = iptables -A FORWARD -i %i -j ACCEPT; ip route add 10.30.0.0/16 dev %i
+SaveConfig = true
+
+[Peer]
+PublicKey = M9nt4YujIOmNrRmpIRTmYSfMdrpvE7u6WkG8FY8WjG4= # the hub
+AllowedIPs = 10.10.0.0/24,10.30.0.0/16
+"
+        .parse()
+        .unwrap();
+        let expected = NetplanConfig::from_yaml(
+            r#"
+network:
+  version: 2
+  tunnels:
+    wg0:
+      mode: wireguard
+      key: {private: 4GgaQCy68nzNsUE5aJ9fuLzHhB65tAlwbmA72MWnOm8=}
+      addresses: [10.10.0.3/24, "fd00:10::3/64"]
+      nameservers: {addresses: [10.10.0.53]}
+      routes:
+        - to: 10.30.0.0/16
+      peers:
+        - keys: {public: M9nt4YujIOmNrRmpIRTmYSfMdrpvE7u6WkG8FY8WjG4=}
+          allowed-ips: [10.10.0.0/24, 10.30.0.0/16]
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            TunnelConfig::from_wg_quick(&conf),
+            expected.network.tunnels.unwrap()["wg0"]
+        );
+    }
+}
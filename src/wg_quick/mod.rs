@@ -0,0 +1,5 @@
+//! Reading and writing wg-quick configurations, for WireGuard tunnels managed outside netplan.
+
+mod generate;
+mod import;
+pub use import::*;
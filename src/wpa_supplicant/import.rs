@@ -0,0 +1,321 @@
+//! Importing wpa_supplicant networks into wifi configurations.
+
+use crate::wpa_supplicant::{WpaNetwork, WpaSupplicantConf, WpaSupplicantParseError};
+use crate::{
+    AccessPointConfig, AccessPointMode, AuthConfig, AuthMethod, KeyManagmentMode, SecretString,
+    WifiConfig, WirelessBand,
+};
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+
+/// The error returned when a wpa_supplicant configuration file cannot be imported.
+#[derive(Debug)]
+pub enum WpaSupplicantImportError {
+    /// The file could not be read.
+    Io(std::io::Error),
+    /// The file is not a valid wpa_supplicant configuration file.
+    Parse(WpaSupplicantParseError),
+}
+
+impl Display for WpaSupplicantImportError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "Failed to read wpa_supplicant configuration: {e}"),
+            Self::Parse(e) => write!(f, "Invalid wpa_supplicant configuration: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for WpaSupplicantImportError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Parse(e) => Some(e),
+        }
+    }
+}
+
+impl From<std::io::Error> for WpaSupplicantImportError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<WpaSupplicantParseError> for WpaSupplicantImportError {
+    fn from(e: WpaSupplicantParseError) -> Self {
+        Self::Parse(e)
+    }
+}
+
+impl WifiConfig {
+    /// Lift the networks of a wpa_supplicant configuration into the access points of a wifi
+    /// device, keyed by their SSID, see [AccessPointConfig::from_wpa_network]. The `country`
+    /// setting becomes the regulatory domain. Networks without an SSID are skipped, and if
+    /// several networks have the same SSID, the first one is used.
+    ///
+    /// ```
+    /// use netplan_types::{CommonProperties, NetworkConfig, WifiConfig, WpaSupplicantConf};
+    ///
+    /// let conf: WpaSupplicantConf = r#"
+    /// ctrl_interface=DIR=/var/run/wpa_supplicant GROUP=netdev
+    /// country=NL
+    ///
+    /// network={
+    ///     ssid="home"
+    ///     psk="hunter22"
+    /// }
+    /// "#
+    /// .parse()
+    /// .unwrap();
+    ///
+    /// let mut wifi = WifiConfig::from_wpa_supplicant(&conf);
+    /// wifi.common_all_mut().dhcp4 = Some(true);
+    /// let mut network = NetworkConfig::default();
+    /// network.add_wifi("wlan0", wifi);
+    ///
+    /// let wlan0 = &network.wifis.unwrap()["wlan0"];
+    /// assert_eq!(wlan0.regulatory_domain.as_deref(), Some("NL"));
+    /// assert!(wlan0.access_points.as_ref().unwrap().contains_key("home"));
+    /// ```
+    pub fn from_wpa_supplicant(conf: &WpaSupplicantConf) -> Self {
+        let mut access_points = HashMap::new();
+        for network in &conf.networks {
+            let Some(ssid) = network.ssid() else {
+                continue;
+            };
+            access_points
+                .entry(ssid)
+                .or_insert_with(|| AccessPointConfig::from_wpa_network(network));
+        }
+
+        Self {
+            access_points: (!access_points.is_empty()).then_some(access_points),
+            regulatory_domain: conf.get("country").map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    /// Read a wpa_supplicant configuration file, like
+    /// `/etc/wpa_supplicant/wpa_supplicant.conf`, and import it, see
+    /// [WifiConfig::from_wpa_supplicant].
+    pub fn from_wpa_supplicant_file(
+        path: impl AsRef<Path>,
+    ) -> Result<Self, WpaSupplicantImportError> {
+        let conf = std::fs::read_to_string(path)?.parse()?;
+        Ok(Self::from_wpa_supplicant(&conf))
+    }
+}
+
+impl AccessPointConfig {
+    /// Lift a wpa_supplicant network block into an access point.
+    ///
+    /// The key management, the pre-shared key or SAE password, and the EAP method, identities,
+    /// password, certificates and phase 2 authentication are carried over, as are the
+    /// BSSID, the mode, the frequency as a band and channel, and `scan_ssid` as a hidden
+    /// network. Networks with both WPA-PSK and SAE use WPA-PSK, as netplan has no
+    /// transition mode. Settings netplan has no equivalent for, like the priority, are left
+    /// out.
+    pub fn from_wpa_network(network: &WpaNetwork) -> Self {
+        let (band, channel) = network
+            .get("frequency")
+            .and_then(|frequency| frequency.parse().ok())
+            .and_then(channel)
+            .unzip();
+        let mut access_point = AccessPointConfig {
+            mode: network.get("mode").and_then(|mode| match mode {
+                "1" => Some(AccessPointMode::Adhoc),
+                "2" => Some(AccessPointMode::Ap),
+                _ => None,
+            }),
+            bssid: network.get("bssid").and_then(|bssid| bssid.parse().ok()),
+            band,
+            channel,
+            hidden: (network.get("scan_ssid") == Some("1")).then_some(true),
+            ..Default::default()
+        };
+
+        // Without key_mgmt, wpa_supplicant allows both WPA-PSK and WPA-EAP
+        let eap = network.get("eap").is_some() || network.get("identity").is_some();
+        let key_mgmt = network
+            .get("key_mgmt")
+            .unwrap_or(if eap { "WPA-EAP" } else { "WPA-PSK" })
+            .split_whitespace()
+            .collect::<Vec<_>>();
+        let has = |modes: &[&str]| key_mgmt.iter().any(|mode| modes.contains(mode));
+        let key_management = if has(&["WPA-PSK", "WPA-PSK-SHA256", "FT-PSK"]) {
+            KeyManagmentMode::Psk
+        } else if has(&["SAE", "FT-SAE"]) {
+            KeyManagmentMode::Sae
+        } else if has(&["WPA-EAP", "WPA-EAP-SHA256", "FT-EAP", "WPA-EAP-SUITE-B-192"]) {
+            KeyManagmentMode::Eap
+        } else if has(&["IEEE8021X"]) {
+            KeyManagmentMode::EightZeroTwoDotOneX
+        } else {
+            return access_point;
+        };
+
+        let secret =
+            |key: &str| value(network, key).map(|value| SecretString::from(value.as_str()));
+        let auth = match key_management {
+            KeyManagmentMode::Psk => AuthConfig {
+                password: secret("psk"),
+                ..Default::default()
+            },
+            KeyManagmentMode::Sae => AuthConfig {
+                password: secret("sae_password").or_else(|| secret("psk")),
+                ..Default::default()
+            },
+            _ => AuthConfig {
+                method: network.get("eap").and_then(|methods| {
+                    let method = methods.split_whitespace().next()?.to_lowercase();
+                    AuthMethod::VARIANTS
+                        .iter()
+                        .find(|variant| variant.as_str() == method)
+                        .cloned()
+                }),
+                identity: network.string("identity"),
+                anonymous_identity: network.string("anonymous_identity"),
+                password: secret("password"),
+                ca_certificate: network.string("ca_cert"),
+                client_certificate: network.string("client_cert"),
+                client_key: network.string("private_key"),
+                client_key_password: secret("private_key_passwd"),
+                phase2_auth: network.string("phase2").map(|phase2| {
+                    let phase2 = phase2.trim();
+                    phase2
+                        .strip_prefix("autheap=")
+                        .or(phase2.strip_prefix("auth="))
+                        .unwrap_or(phase2)
+                        .to_string()
+                }),
+                ..Default::default()
+            },
+        };
+        if key_management == KeyManagmentMode::Psk && auth.password.is_none() {
+            return access_point;
+        }
+
+        access_point.auth = Some(AuthConfig {
+            key_management: Some(key_management),
+            ..auth
+        });
+        access_point
+    }
+}
+
+/// A secret, which is a quoted string, or written as it is, like a hex PSK or a hashed
+/// EAP password.
+fn value(network: &WpaNetwork, key: &str) -> Option<String> {
+    let value = network.get(key)?;
+    match value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+    {
+        Some(quoted) => Some(quoted.to_string()),
+        None => Some(value.to_string()),
+    }
+}
+
+/// The band and channel of a frequency in MHz.
+fn channel(frequency: u32) -> Option<(WirelessBand, u32)> {
+    match frequency {
+        2484 => Some((WirelessBand::Ghz2, 14)),
+        2412..=2472 => Some((WirelessBand::Ghz2, (frequency - 2407) / 5)),
+        5160..=5885 => Some((WirelessBand::Ghz5, (frequency - 5000) / 5)),
+        5955..=7115 => Some((WirelessBand::Ghz6, (frequency - 5950) / 5)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        AccessPointConfig, AccessPointMode, AuthConfig, AuthMethod, KeyManagmentMode, WifiConfig,
+        WirelessBand, WpaSupplicantConf,
+    };
+
+    #[test]
+    fn import_networks() {
+        let conf: WpaSupplicantConf = r#"
+ctrl_interface=DIR=/var/run/wpa_supplicant GROUP=netdev
+update_config=1
+country=DE
+
+network={
+	ssid="home"
+	psk="hunter22"
+	priority=10
+}
+
+# The SSID "office" in hex
+network={
+	ssid=6f6666696365
+	key_mgmt=SAE
+	sae_password="correct horse"
+	scan_ssid=1
+	frequency=5180
+}
+
+network={
+	ssid="corp"
+	key_mgmt=WPA-EAP
+	eap=PEAP
+	identity="alice@example.com"
+	password="secret"
+	ca_cert="/etc/ssl/certs/corp.pem"
+	phase2="auth=MSCHAPV2"
+}
+
+network={
+	ssid="cafe"
+	key_mgmt=NONE
+}
+
+network={
+	ssid="home"
+	psk="ignored"
+}
+"#
+        .parse()
+        .unwrap();
+
+        let wifi = WifiConfig::from_wpa_supplicant(&conf);
+        assert_eq!(wifi.regulatory_domain.as_deref(), Some("DE"));
+        let access_points = wifi.access_points.unwrap();
+        assert_eq!(access_points.len(), 4);
+        assert_eq!(access_points["home"], AccessPointConfig::wpa2("hunter22"));
+        assert_eq!(
+            access_points["office"],
+            AccessPointConfig {
+                band: Some(WirelessBand::Ghz5),
+                channel: Some(36),
+                hidden: Some(true),
+                ..AccessPointConfig::wpa3("correct horse")
+            }
+        );
+        assert_eq!(
+            access_points["corp"].auth,
+            Some(AuthConfig {
+                key_management: Some(KeyManagmentMode::Eap),
+                method: Some(AuthMethod::Peap),
+                identity: Some("alice@example.com".to_string()),
+                password: Some("secret".into()),
+                ca_certificate: Some("/etc/ssl/certs/corp.pem".to_string()),
+                phase2_auth: Some("MSCHAPV2".to_string()),
+                ..Default::default()
+            })
+        );
+        assert_eq!(access_points["cafe"], AccessPointConfig::default());
+
+        let adhoc: WpaSupplicantConf = "network={\n\tssid=\"mesh\"\n\tmode=1\n\tkey_mgmt=NONE\n}\n"
+            .parse()
+            .unwrap();
+        let access_point = AccessPointConfig::from_wpa_network(&adhoc.networks[0]);
+        assert_eq!(access_point.mode, Some(AccessPointMode::Adhoc));
+
+        assert!("network={\n\tssid=\"open\"\n"
+            .parse::<WpaSupplicantConf>()
+            .is_err());
+    }
+}
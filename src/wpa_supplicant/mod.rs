@@ -0,0 +1,172 @@
+//! Reading wpa_supplicant configuration files, to migrate the wifi networks they define.
+
+mod import;
+pub use import::*;
+
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// A wpa_supplicant configuration file, like `/etc/wpa_supplicant/wpa_supplicant.conf`: global
+/// `key=value` settings and `network={...}` blocks.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WpaSupplicantConf {
+    /// The global settings, e.g. `country` or `ctrl_interface`, in order.
+    pub globals: Vec<(String, String)>,
+    pub networks: Vec<WpaNetwork>,
+}
+
+/// A `network={...}` block of a [WpaSupplicantConf]. Values are kept as they are written,
+/// with the quotes around strings.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WpaNetwork {
+    pub entries: Vec<(String, String)>,
+}
+
+impl WpaSupplicantConf {
+    /// The value of the global setting `key`.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.globals
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+impl WpaNetwork {
+    /// The value of `key`, as it is written.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// The string value of `key`: the contents of a quoted string, or the text of a string
+    /// written in hex, like the SSID `6e6574`. `None` if it is unset, or not a valid string.
+    pub fn string(&self, key: &str) -> Option<String> {
+        let value = self.get(key)?;
+        if let Some(quoted) = value
+            .strip_prefix('"')
+            .and_then(|value| value.strip_suffix('"'))
+        {
+            return Some(quoted.to_string());
+        }
+
+        if value.len() % 2 != 0 {
+            return None;
+        }
+        let bytes = (0..value.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(value.get(i..i + 2)?, 16).ok())
+            .collect::<Option<Vec<_>>>()?;
+        String::from_utf8(bytes).ok()
+    }
+
+    /// The SSID of the network.
+    pub fn ssid(&self) -> Option<String> {
+        self.string("ssid")
+    }
+}
+
+impl Display for WpaSupplicantConf {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (key, value) in &self.globals {
+            writeln!(f, "{key}={value}")?;
+        }
+        for network in &self.networks {
+            writeln!(f)?;
+            write!(f, "{network}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for WpaNetwork {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "network={{")?;
+        for (key, value) in &self.entries {
+            writeln!(f, "\t{key}={value}")?;
+        }
+        writeln!(f, "}}")
+    }
+}
+
+/// The error returned when a wpa_supplicant configuration file cannot be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WpaSupplicantParseError {
+    /// The line the error was found on, starting at 1.
+    pub line: usize,
+    pub message: String,
+}
+
+impl Display for WpaSupplicantParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for WpaSupplicantParseError {}
+
+/// Parse a wpa_supplicant configuration file. Empty lines and comments, starting with `#`,
+/// are skipped.
+///
+/// ```
+/// use netplan_types::WpaSupplicantConf;
+///
+/// let conf: WpaSupplicantConf = "country=NL\nnetwork={\n\tssid=\"home\"\n\tpsk=\"hunter22\"\n}\n"
+///     .parse()
+///     .unwrap();
+/// assert_eq!(conf.get("country"), Some("NL"));
+/// assert_eq!(conf.networks[0].ssid().as_deref(), Some("home"));
+/// assert_eq!(conf.networks[0].get("psk"), Some("\"hunter22\""));
+/// ```
+impl FromStr for WpaSupplicantConf {
+    type Err = WpaSupplicantParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut conf = WpaSupplicantConf::default();
+        let mut network: Option<WpaNetwork> = None;
+        let mut last = 0;
+        for (index, line) in s.lines().enumerate() {
+            last = index + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let error = |message: &str| WpaSupplicantParseError {
+                line: index + 1,
+                message: message.to_string(),
+            };
+            if line == "}" {
+                let network = network
+                    .take()
+                    .ok_or_else(|| error("closing brace outside of a network block"))?;
+                conf.networks.push(network);
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| error("expected a key=value assignment"))?;
+            let (key, value) = (key.trim(), value.trim());
+            match (&mut network, key, value) {
+                (None, "network", "{") => network = Some(WpaNetwork::default()),
+                (Some(_), "network", _) => return Err(error("nested network block")),
+                (Some(network), key, value) => {
+                    network.entries.push((key.to_string(), value.to_string()))
+                }
+                (None, key, value) => conf.globals.push((key.to_string(), value.to_string())),
+            }
+        }
+
+        if network.is_some() {
+            return Err(WpaSupplicantParseError {
+                line: last,
+                message: "unterminated network block".to_string(),
+            });
+        }
+        Ok(conf)
+    }
+}
@@ -0,0 +1,250 @@
+//! Writing configurations to netplan YAML files.
+
+use crate::NetplanConfig;
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// How a configuration is written by [NetplanConfig::write_to_with] and
+/// [NetplanConfig::to_writer_with].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WriteOptions {
+    /// A comment written at the top of the file, e.g. to warn that the file is generated.
+    /// Every line is prefixed with `# `.
+    pub header: Option<String>,
+}
+
+impl WriteOptions {
+    pub fn header(mut self, header: impl Into<String>) -> Self {
+        self.header = Some(header.into());
+        self
+    }
+}
+
+impl NetplanConfig {
+    /// Write the configuration to a file, see [NetplanConfig::write_to_with].
+    pub fn write_to(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        self.write_to_with(path, &WriteOptions::default())
+    }
+
+    /// Write the configuration to a file, replacing it atomically: the configuration is
+    /// written to a temporary file in the same directory, which is renamed to `path` once it
+    /// is complete. Readers will see either the old or the new file, never a partial one.
+    ///
+    /// On Unix the file is only readable and writable by its owner (mode 0600), as netplan
+    /// warns about configurations which are readable by others, and they may contain secrets.
+    ///
+    /// ```no_run
+    /// use netplan_types::{NetplanConfig, WriteOptions};
+    ///
+    /// let config = NetplanConfig::single_nic_dhcp("eth0");
+    /// let options = WriteOptions::default().header("Generated by provisioning, do not edit");
+    /// config.write_to_with("/etc/netplan/50-provisioning.yaml", &options).unwrap();
+    /// ```
+    pub fn write_to_with(
+        &self,
+        path: impl AsRef<Path>,
+        options: &WriteOptions,
+    ) -> std::io::Result<()> {
+        write_atomic_with(path.as_ref(), |file| self.to_writer_with(file, options))
+    }
+
+    /// Serialize the configuration to netplan YAML, streaming it to `writer` rather than
+    /// building it in memory like [NetplanConfig::to_yaml] does. Wrap unbuffered writers,
+    /// like a [File], in a [BufWriter].
+    ///
+    /// ```
+    /// use netplan_types::NetplanConfig;
+    ///
+    /// let mut yaml = Vec::new();
+    /// NetplanConfig::single_nic_dhcp("eth0").to_writer(&mut yaml).unwrap();
+    /// assert!(yaml.starts_with(b"network:\n"));
+    /// ```
+    pub fn to_writer(&self, writer: impl Write) -> std::io::Result<()> {
+        serde_yaml::to_writer(writer, self).map_err(std::io::Error::other)
+    }
+
+    /// Stream the configuration to `writer` as it is written to files by
+    /// [NetplanConfig::write_to_with], i.e. preceded by the header of `options`.
+    pub fn to_writer_with(
+        &self,
+        mut writer: impl Write,
+        options: &WriteOptions,
+    ) -> std::io::Result<()> {
+        if let Some(header) = &options.header {
+            for line in header.lines() {
+                writeln!(writer, "{}", format!("# {line}").trim_end())?;
+            }
+        }
+
+        self.to_writer(writer)
+    }
+
+    /// The contents of the file written by [NetplanConfig::write_to_with].
+    #[cfg(feature = "tokio")]
+    pub(crate) fn to_file_contents(&self, options: &WriteOptions) -> String {
+        let mut contents = Vec::new();
+        // Writing to a Vec cannot fail, and the YAML is valid UTF-8
+        self.to_writer_with(&mut contents, options)
+            .expect("Serializing configuration");
+        String::from_utf8(contents).expect("Serializing configuration")
+    }
+}
+
+/// Replace the file at `path` with `contents` through a temporary file.
+pub(crate) fn write_atomic(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    write_atomic_with(path, |file| file.write_all(contents))
+}
+
+/// Replace the file at `path` with what `write` writes, through a temporary file. Once it is
+/// renamed, the directory is synced as well, so the new file survives a crash.
+fn write_atomic_with(
+    path: &Path,
+    write: impl FnOnce(&mut BufWriter<File>) -> std::io::Result<()>,
+) -> std::io::Result<()> {
+    let (temp_path, file) = create_temp(path)?;
+    let result = (|| {
+        let mut writer = BufWriter::new(file);
+        write(&mut writer)?;
+        writer
+            .into_inner()
+            .map_err(|e| e.into_error())?
+            .sync_all()?;
+        std::fs::rename(&temp_path, path)
+    })();
+
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temp_path);
+    }
+
+    result?;
+    sync_dir(path)
+}
+
+/// Create the temporary file `path` is written to before it is renamed, a hidden file in the
+/// same directory. Its name is unique to the process and the call, so concurrent writes to the
+/// same path, or a file left behind by a process which crashed, don't collide.
+fn create_temp(path: &Path) -> std::io::Result<(PathBuf, File)> {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    let file_name = path.file_name().ok_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("'{}' is not a file path", path.display()),
+        )
+    })?;
+
+    loop {
+        let mut temp_name = std::ffi::OsString::from(".");
+        temp_name.push(file_name);
+        temp_name.push(format!(
+            ".{}.{}.tmp",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let temp_path = path.with_file_name(temp_name);
+
+        match create_private(&temp_path) {
+            Ok(file) => return Ok((temp_path, file)),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Create a new file which is only accessible by its owner.
+fn create_private(path: &Path) -> std::io::Result<File> {
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    options.open(path)
+}
+
+/// Sync the directory containing `path`, making a rename into it durable. Directories can only
+/// be opened, and thus synced, on Unix.
+fn sync_dir(path: &Path) -> std::io::Result<()> {
+    #[cfg(unix)]
+    {
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        File::open(dir)?.sync_all()?;
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{NetplanConfig, WriteOptions};
+
+    #[test]
+    fn write_to_replaces_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("50-test.yaml");
+        std::fs::write(&path, "old").unwrap();
+
+        let config = NetplanConfig::single_nic_dhcp("eth0");
+        let options = WriteOptions::default().header("Generated\n\nDo not edit");
+        config.write_to_with(&path, &options).unwrap();
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert!(contents.starts_with("# Generated\n#\n# Do not edit\nnetwork:\n"));
+        assert!(NetplanConfig::load(&path).unwrap().semantically_eq(&config));
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+    }
+
+    #[test]
+    fn to_writer_matches_to_yaml() {
+        let config = NetplanConfig::single_nic_dhcp("eth0");
+        let mut yaml = Vec::new();
+        config.to_writer(&mut yaml).unwrap();
+        assert_eq!(String::from_utf8(yaml).unwrap(), config.to_yaml());
+
+        let mut yaml = Vec::new();
+        let options = WriteOptions::default().header("Generated");
+        config.to_writer_with(&mut yaml, &options).unwrap();
+        let yaml = String::from_utf8(yaml).unwrap();
+        assert_eq!(yaml, format!("# Generated\n{}", config.to_yaml()));
+    }
+
+    #[test]
+    fn concurrent_writes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("50-test.yaml");
+        // A temporary file left behind by a crashed process must not block writing
+        std::fs::write(
+            dir.path()
+                .join(format!(".50-test.yaml.{}.0.tmp", std::process::id())),
+            "stale",
+        )
+        .unwrap();
+
+        let config = NetplanConfig::single_nic_dhcp("eth0");
+        std::thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| config.write_to(&path).unwrap());
+            }
+        });
+
+        assert!(NetplanConfig::load(&path).unwrap().semantically_eq(&config));
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 2);
+    }
+}
@@ -0,0 +1,173 @@
+use std::path::Path;
+use std::process::{Command, Output};
+
+const FORMATTED: &str = "network:\n  version: 2\n  ethernets:\n    eth0:\n      dhcp4: true\n";
+
+fn run(dir: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_netplan-types"))
+        .current_dir(dir)
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8(output.stderr.clone()).unwrap()
+}
+
+fn workspace() -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    let files = [
+        ("good.yaml", FORMATTED),
+        (
+            "warning.yaml",
+            "network:\n  version: 2\n  ethernets:\n    eth0:\n      mtu: 1000\n",
+        ),
+        (
+            "error.yaml",
+            "network:\n  version: 2\n  ethernets:\n    eth0:\n      mtu: 10\n",
+        ),
+        (
+            "other.yaml",
+            "network:\n  version: 2\n  ethernets:\n    eth0:\n      dhcp4: false\n",
+        ),
+        (
+            "unformatted.yaml",
+            "network: {version: 2, ethernets: {eth0: {dhcp4: yes}}}\n",
+        ),
+        ("broken.yaml", "network: [\n"),
+    ];
+    for (name, contents) in files {
+        std::fs::write(dir.path().join(name), contents).unwrap();
+    }
+    dir
+}
+
+#[test]
+fn validate() {
+    let dir = workspace();
+
+    let output = run(dir.path(), &["validate", "good.yaml"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "");
+
+    let output = run(dir.path(), &["validate", "warning.yaml"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(stdout(&output).starts_with("warning.yaml: warning: network.ethernets.eth0.mtu: "));
+
+    let output = run(dir.path(), &["validate", "good.yaml", "error.yaml"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        stdout(&output),
+        "error.yaml: error: network.ethernets.eth0.mtu: An MTU of 10 is below the minimum of 68 [mtu-range]\n"
+    );
+
+    let output = run(dir.path(), &["validate", "broken.yaml"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).starts_with("netplan-types: broken.yaml: Invalid configuration"));
+
+    let output = run(dir.path(), &["validate", "--strict", "good.yaml"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(
+        stderr(&output),
+        "netplan-types: Unknown option '--strict'\n"
+    );
+
+    let output = run(dir.path(), &["validate"]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn fmt() {
+    let dir = workspace();
+
+    let output = run(dir.path(), &["fmt", "unformatted.yaml"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), FORMATTED);
+
+    let output = run(dir.path(), &["fmt", "--check", "good.yaml"]);
+    assert_eq!(output.status.code(), Some(0));
+
+    let output = run(dir.path(), &["fmt", "--check", "unformatted.yaml"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stderr(&output), "unformatted.yaml is not formatted\n");
+
+    let output = run(dir.path(), &["fmt", "--write", "unformatted.yaml"]);
+    assert_eq!(output.status.code(), Some(0));
+    let path = dir.path().join("unformatted.yaml");
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), FORMATTED);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    let output = run(dir.path(), &["fmt", "--check", "unformatted.yaml"]);
+    assert_eq!(output.status.code(), Some(0));
+
+    let output = run(dir.path(), &["fmt", "--wirte", "good.yaml"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(stderr(&output), "netplan-types: Unknown option '--wirte'\n");
+}
+
+#[test]
+fn diff() {
+    let dir = workspace();
+
+    let output = run(dir.path(), &["diff", "good.yaml", "unformatted.yaml"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "");
+
+    let output = run(dir.path(), &["diff", "good.yaml", "other.yaml"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        stdout(&output),
+        "~ network.ethernets.eth0.dhcp4: true -> false\n"
+    );
+
+    let output = run(dir.path(), &["diff", "good.yaml"]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn convert() {
+    let dir = workspace();
+
+    let output = run(dir.path(), &["convert", "good.yaml"]);
+    assert_eq!(output.status.code(), Some(0));
+    let json = stdout(&output);
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(value["network"]["ethernets"]["eth0"]["dhcp4"], true);
+
+    std::fs::write(dir.path().join("good.json"), json).unwrap();
+    let output = run(dir.path(), &["convert", "good.json"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), FORMATTED);
+
+    let output = run(dir.path(), &["convert", "--to", "yaml", "good.yaml"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), FORMATTED);
+
+    let output = run(dir.path(), &["convert", "--to", "toml", "good.yaml"]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn redact() {
+    let dir = workspace();
+    std::fs::write(
+        dir.path().join("secret.yaml"),
+        "network:\n  version: 2\n  wifis:\n    wlan0:\n      access-points:\n        home:\n          password: hunter22\n",
+    )
+    .unwrap();
+
+    let output = run(dir.path(), &["redact", "secret.yaml"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(!stdout(&output).contains("hunter22"));
+    assert!(stdout(&output).contains("password: '[REDACTED]'"));
+}